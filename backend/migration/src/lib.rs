@@ -18,6 +18,20 @@ mod m20250829_drop_clay_tools_used_column;
 mod m20250905_add_deleted_at_to_data_sources;
 mod m20250913_add_user_id_to_projects;
 mod m20250929_add_progress_content_to_messages;
+mod m20251016_000001_create_datasource_column_views;
+mod m20251016_000002_add_allowed_datasources_to_conversations;
+mod m20251016_000003_add_extraction_status_to_file_uploads;
+mod m20251016_000004_add_unique_datasource_name_index;
+mod m20251016_000005_create_schema_versions;
+mod m20251016_000006_create_slow_query_log;
+mod m20251016_000007_add_schema_fetched_at_to_data_sources;
+mod m20251016_000008_add_model_to_conversations;
+mod m20251016_000009_add_deleted_at_to_conversations;
+mod m20251016_000010_add_archive_id_to_file_uploads;
+mod m20251016_000011_add_schema_info_previous_to_data_sources;
+mod m20251016_000012_create_query_history;
+mod m20251016_000013_create_audit_log;
+mod m20251016_000014_add_connection_status_to_data_sources;
 
 pub struct Migrator;
 
@@ -43,6 +57,20 @@ impl MigratorTrait for Migrator {
             Box::new(m20250905_add_deleted_at_to_data_sources::Migration),
             Box::new(m20250913_add_user_id_to_projects::Migration),
             Box::new(m20250929_add_progress_content_to_messages::Migration),
+            Box::new(m20251016_000001_create_datasource_column_views::Migration),
+            Box::new(m20251016_000002_add_allowed_datasources_to_conversations::Migration),
+            Box::new(m20251016_000003_add_extraction_status_to_file_uploads::Migration),
+            Box::new(m20251016_000004_add_unique_datasource_name_index::Migration),
+            Box::new(m20251016_000005_create_schema_versions::Migration),
+            Box::new(m20251016_000006_create_slow_query_log::Migration),
+            Box::new(m20251016_000007_add_schema_fetched_at_to_data_sources::Migration),
+            Box::new(m20251016_000008_add_model_to_conversations::Migration),
+            Box::new(m20251016_000009_add_deleted_at_to_conversations::Migration),
+            Box::new(m20251016_000010_add_archive_id_to_file_uploads::Migration),
+            Box::new(m20251016_000011_add_schema_info_previous_to_data_sources::Migration),
+            Box::new(m20251016_000012_create_query_history::Migration),
+            Box::new(m20251016_000013_create_audit_log::Migration),
+            Box::new(m20251016_000014_add_connection_status_to_data_sources::Migration),
         ]
     }
 }
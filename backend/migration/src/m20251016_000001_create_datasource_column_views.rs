@@ -0,0 +1,88 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Per-column view metadata (display names, hidden flags) for the data browser
+        manager
+            .create_table(
+                Table::create()
+                    .table(DatasourceColumnViews::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(DatasourceColumnViews::Id)
+                            .uuid()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(DatasourceColumnViews::DatasourceId).string().not_null())
+                    .col(ColumnDef::new(DatasourceColumnViews::TableName).string().not_null())
+                    .col(ColumnDef::new(DatasourceColumnViews::ColumnName).string().not_null())
+                    .col(ColumnDef::new(DatasourceColumnViews::DisplayName).string().null())
+                    .col(
+                        ColumnDef::new(DatasourceColumnViews::Hidden)
+                            .boolean()
+                            .not_null()
+                            .default(false),
+                    )
+                    .col(
+                        ColumnDef::new(DatasourceColumnViews::UpdatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_datasource_column_views_datasource_id")
+                            .from(DatasourceColumnViews::Table, DatasourceColumnViews::DatasourceId)
+                            .to(DataSources::Table, DataSources::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_datasource_column_views_unique")
+                    .if_not_exists()
+                    .table(DatasourceColumnViews::Table)
+                    .col(DatasourceColumnViews::DatasourceId)
+                    .col(DatasourceColumnViews::TableName)
+                    .col(DatasourceColumnViews::ColumnName)
+                    .unique()
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(DatasourceColumnViews::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(Iden)]
+enum DatasourceColumnViews {
+    Table,
+    Id,
+    DatasourceId,
+    TableName,
+    ColumnName,
+    DisplayName,
+    Hidden,
+    UpdatedAt,
+}
+
+#[derive(Iden)]
+enum DataSources {
+    Table,
+    Id,
+}
@@ -0,0 +1,49 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Existing uploads were extracted synchronously, so they are already ready
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(FileUploads::Table)
+                    .add_column_if_not_exists(
+                        ColumnDef::new(FileUploads::ExtractionStatus)
+                            .string()
+                            .not_null()
+                            .default("ready")
+                    )
+                    .add_column_if_not_exists(
+                        ColumnDef::new(FileUploads::ExtractionProgress)
+                            .integer()
+                            .not_null()
+                            .default(100)
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(FileUploads::Table)
+                    .drop_column(FileUploads::ExtractionStatus)
+                    .drop_column(FileUploads::ExtractionProgress)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(Iden)]
+enum FileUploads {
+    Table,
+    ExtractionStatus,
+    ExtractionProgress,
+}
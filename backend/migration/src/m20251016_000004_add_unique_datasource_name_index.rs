@@ -0,0 +1,122 @@
+use sea_orm::{ConnectionTrait, Statement};
+use sea_orm_migration::prelude::*;
+
+const RENAME_DUPLICATES: &str = r#"
+    UPDATE data_sources ds
+    SET name = ds.name || ' (' || LEFT(ds.id, 8) || ')'
+    FROM (
+        SELECT id, ROW_NUMBER() OVER (
+            PARTITION BY project_id, LOWER(name)
+            ORDER BY created_at, id
+        ) AS rn
+        FROM data_sources
+        WHERE deleted_at IS NULL
+    ) dup
+    WHERE ds.id = dup.id AND dup.rn > 1
+"#;
+
+const CREATE_UNIQUE_INDEX: &str = r#"
+    CREATE UNIQUE INDEX IF NOT EXISTS idx_data_sources_project_name_unique
+    ON data_sources (project_id, LOWER(name))
+    WHERE deleted_at IS NULL
+"#;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let conn = manager.get_connection();
+
+        // Rename existing duplicates (all but the oldest) so the index can be built;
+        // the id prefix keeps the new names unique without another pass
+        conn.execute(Statement::from_string(
+            manager.get_database_backend(),
+            RENAME_DUPLICATES.to_string(),
+        ))
+        .await?;
+
+        // Soft-deleted rows are excluded so a name can be reused after deletion
+        conn.execute(Statement::from_string(
+            manager.get_database_backend(),
+            CREATE_UNIQUE_INDEX.to_string(),
+        ))
+        .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .get_connection()
+            .execute(Statement::from_string(
+                manager.get_database_backend(),
+                "DROP INDEX IF EXISTS idx_data_sources_project_name_unique".to_string(),
+            ))
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sea_orm::{Database, DbBackend, TransactionTrait};
+
+    #[async_std::test]
+    #[ignore = "needs a PostgreSQL server in TEST_DATABASE_URL"]
+    async fn duplicate_names_are_renamed_before_the_index_is_built() {
+        let url = std::env::var("TEST_DATABASE_URL").expect("Set TEST_DATABASE_URL");
+        let db = Database::connect(&url).await.unwrap();
+        let sql = |sql: &str| Statement::from_string(DbBackend::Postgres, sql.to_string());
+
+        // A temporary data_sources table shadows the real one inside this transaction
+        let tx = db.begin().await.unwrap();
+        tx.execute(sql(
+            "CREATE TEMPORARY TABLE data_sources (
+                id TEXT PRIMARY KEY, project_id TEXT NOT NULL, name TEXT NOT NULL,
+                created_at TIMESTAMPTZ NOT NULL, deleted_at TIMESTAMPTZ
+            )",
+        ))
+        .await
+        .unwrap();
+        tx.execute(sql(
+            "INSERT INTO data_sources VALUES
+                ('aaaaaaaa-1', 'p1', 'Orders', '2025-01-01', NULL),
+                ('bbbbbbbb-2', 'p1', 'orders', '2025-01-02', NULL),
+                ('cccccccc-3', 'p1', 'Orders', '2025-01-03', '2025-02-01'),
+                ('dddddddd-4', 'p2', 'Orders', '2025-01-04', NULL)",
+        ))
+        .await
+        .unwrap();
+
+        tx.execute(sql(RENAME_DUPLICATES)).await.unwrap();
+        tx.execute(sql(CREATE_UNIQUE_INDEX)).await.unwrap();
+        let names: Vec<(String, String)> = tx
+            .query_all(sql("SELECT id, name FROM data_sources ORDER BY id"))
+            .await
+            .unwrap()
+            .iter()
+            .map(|row| (row.try_get("", "id").unwrap(), row.try_get("", "name").unwrap()))
+            .collect();
+        let duplicate_after_index = tx
+            .execute(sql("INSERT INTO data_sources VALUES ('eeeeeeee-5', 'p1', 'ORDERS', NOW(), NULL)"))
+            .await;
+        tx.rollback().await.unwrap();
+
+        let expected = [
+            ("aaaaaaaa-1", "Orders"),
+            // Only the newer live duplicate is renamed; deleted rows and other projects keep their names
+            ("bbbbbbbb-2", "orders (bbbbbbbb)"),
+            ("cccccccc-3", "Orders"),
+            ("dddddddd-4", "Orders"),
+        ];
+        assert_eq!(
+            names,
+            expected.map(|(id, name)| (id.to_string(), name.to_string())).to_vec()
+        );
+        assert!(duplicate_after_index.is_err());
+    }
+}
@@ -0,0 +1,123 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Snapshots of a datasource's schema; identical snapshots share a version
+        manager
+            .create_table(
+                Table::create()
+                    .table(DatasourceSchemaVersions::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(DatasourceSchemaVersions::Id)
+                            .uuid()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(DatasourceSchemaVersions::DatasourceId).string().not_null())
+                    .col(ColumnDef::new(DatasourceSchemaVersions::Version).integer().not_null())
+                    .col(ColumnDef::new(DatasourceSchemaVersions::SchemaHash).string().not_null())
+                    .col(ColumnDef::new(DatasourceSchemaVersions::Schema).json_binary().not_null())
+                    .col(
+                        ColumnDef::new(DatasourceSchemaVersions::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_datasource_schema_versions_datasource_id")
+                            .from(DatasourceSchemaVersions::Table, DatasourceSchemaVersions::DatasourceId)
+                            .to(DataSources::Table, DataSources::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_datasource_schema_versions_version")
+                    .table(DatasourceSchemaVersions::Table)
+                    .col(DatasourceSchemaVersions::DatasourceId)
+                    .col(DatasourceSchemaVersions::Version)
+                    .unique()
+                    .if_not_exists()
+                    .to_owned(),
+            )
+            .await?;
+
+        // The schema version each analysis validates against, per datasource.
+        // `analyses` comes from the legacy SQL migrations, so analysis_id carries
+        // no foreign key here.
+        manager
+            .create_table(
+                Table::create()
+                    .table(AnalysisSchemaPins::Table)
+                    .if_not_exists()
+                    .col(ColumnDef::new(AnalysisSchemaPins::AnalysisId).uuid().not_null())
+                    .col(ColumnDef::new(AnalysisSchemaPins::DatasourceId).string().not_null())
+                    .col(ColumnDef::new(AnalysisSchemaPins::SchemaVersionId).uuid().not_null())
+                    .col(
+                        ColumnDef::new(AnalysisSchemaPins::PinnedAt)
+                            .timestamp_with_time_zone()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .primary_key(
+                        Index::create()
+                            .col(AnalysisSchemaPins::AnalysisId)
+                            .col(AnalysisSchemaPins::DatasourceId),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_analysis_schema_pins_schema_version_id")
+                            .from(AnalysisSchemaPins::Table, AnalysisSchemaPins::SchemaVersionId)
+                            .to(DatasourceSchemaVersions::Table, DatasourceSchemaVersions::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(AnalysisSchemaPins::Table).if_exists().to_owned())
+            .await?;
+        manager
+            .drop_table(Table::drop().table(DatasourceSchemaVersions::Table).if_exists().to_owned())
+            .await
+    }
+}
+
+#[derive(Iden)]
+enum DatasourceSchemaVersions {
+    Table,
+    Id,
+    DatasourceId,
+    Version,
+    SchemaHash,
+    Schema,
+    CreatedAt,
+}
+
+#[derive(Iden)]
+enum AnalysisSchemaPins {
+    Table,
+    AnalysisId,
+    DatasourceId,
+    SchemaVersionId,
+    PinnedAt,
+}
+
+#[derive(Iden)]
+enum DataSources {
+    Table,
+    Id,
+}
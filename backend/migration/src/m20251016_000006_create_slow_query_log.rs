@@ -0,0 +1,83 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Queries that ran longer than SLOW_QUERY_THRESHOLD_MS, with an optional plan
+        manager
+            .create_table(
+                Table::create()
+                    .table(SlowQueryLog::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(SlowQueryLog::Id)
+                            .uuid()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(SlowQueryLog::DatasourceId).string().not_null())
+                    .col(ColumnDef::new(SlowQueryLog::ProjectId).string().not_null())
+                    .col(ColumnDef::new(SlowQueryLog::Source).string().not_null())
+                    .col(ColumnDef::new(SlowQueryLog::Query).text().not_null())
+                    .col(ColumnDef::new(SlowQueryLog::DurationMs).big_integer().not_null())
+                    .col(ColumnDef::new(SlowQueryLog::RowCount).big_integer())
+                    .col(ColumnDef::new(SlowQueryLog::QueryPlan).json_binary())
+                    .col(
+                        ColumnDef::new(SlowQueryLog::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_slow_query_log_datasource_id")
+                            .from(SlowQueryLog::Table, SlowQueryLog::DatasourceId)
+                            .to(DataSources::Table, DataSources::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_slow_query_log_datasource_created")
+                    .table(SlowQueryLog::Table)
+                    .col(SlowQueryLog::DatasourceId)
+                    .col(SlowQueryLog::CreatedAt)
+                    .if_not_exists()
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(SlowQueryLog::Table).if_exists().to_owned())
+            .await
+    }
+}
+
+#[derive(Iden)]
+enum SlowQueryLog {
+    Table,
+    Id,
+    DatasourceId,
+    ProjectId,
+    Source,
+    Query,
+    DurationMs,
+    RowCount,
+    QueryPlan,
+    CreatedAt,
+}
+
+#[derive(Iden)]
+enum DataSources {
+    Table,
+    Id,
+}
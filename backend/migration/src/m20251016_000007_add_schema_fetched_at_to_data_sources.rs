@@ -0,0 +1,40 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // When schema_info was last fetched from the live database, for cache expiry
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(DataSources::Table)
+                    .add_column_if_not_exists(
+                        ColumnDef::new(DataSources::SchemaFetchedAt)
+                            .timestamp_with_time_zone()
+                            .null(),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(DataSources::Table)
+                    .drop_column(DataSources::SchemaFetchedAt)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(Iden)]
+enum DataSources {
+    Table,
+    SchemaFetchedAt,
+}
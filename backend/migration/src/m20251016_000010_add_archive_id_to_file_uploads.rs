@@ -0,0 +1,57 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Files expanded from one uploaded ZIP archive share its archive_id
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(FileUploads::Table)
+                    .add_column_if_not_exists(ColumnDef::new(FileUploads::ArchiveId).uuid().null())
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .if_not_exists()
+                    .name("idx_file_uploads_archive_id")
+                    .table(FileUploads::Table)
+                    .col(FileUploads::ArchiveId)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_index(
+                Index::drop()
+                    .if_exists()
+                    .name("idx_file_uploads_archive_id")
+                    .table(FileUploads::Table)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(FileUploads::Table)
+                    .drop_column(FileUploads::ArchiveId)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(Iden)]
+enum FileUploads {
+    Table,
+    ArchiveId,
+}
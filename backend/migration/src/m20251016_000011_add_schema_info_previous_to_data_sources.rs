@@ -0,0 +1,36 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // The schema_info snapshot replaced by the latest inspection, for schema diffs
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(DataSources::Table)
+                    .add_column_if_not_exists(ColumnDef::new(DataSources::SchemaInfoPrevious).json().null())
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(DataSources::Table)
+                    .drop_column(DataSources::SchemaInfoPrevious)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(Iden)]
+enum DataSources {
+    Table,
+    SchemaInfoPrevious,
+}
@@ -0,0 +1,88 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Every query run from the data browser or MCP, newest first per project
+        manager
+            .create_table(
+                Table::create()
+                    .table(QueryHistory::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(QueryHistory::Id)
+                            .uuid()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(QueryHistory::ProjectId).string().not_null())
+                    .col(ColumnDef::new(QueryHistory::DatasourceId).string().not_null())
+                    .col(ColumnDef::new(QueryHistory::Source).string().not_null())
+                    .col(ColumnDef::new(QueryHistory::Query).text().not_null())
+                    .col(ColumnDef::new(QueryHistory::RowCount).big_integer())
+                    .col(ColumnDef::new(QueryHistory::DurationMs).big_integer().not_null())
+                    .col(
+                        ColumnDef::new(QueryHistory::RunCount)
+                            .integer()
+                            .not_null()
+                            .default(1),
+                    )
+                    .col(
+                        ColumnDef::new(QueryHistory::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_query_history_datasource_id")
+                            .from(QueryHistory::Table, QueryHistory::DatasourceId)
+                            .to(DataSources::Table, DataSources::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_query_history_project_created")
+                    .table(QueryHistory::Table)
+                    .col(QueryHistory::ProjectId)
+                    .col(QueryHistory::CreatedAt)
+                    .if_not_exists()
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(QueryHistory::Table).if_exists().to_owned())
+            .await
+    }
+}
+
+#[derive(Iden)]
+enum QueryHistory {
+    Table,
+    Id,
+    ProjectId,
+    DatasourceId,
+    Source,
+    Query,
+    RowCount,
+    DurationMs,
+    RunCount,
+    CreatedAt,
+}
+
+#[derive(Iden)]
+enum DataSources {
+    Table,
+    Id,
+}
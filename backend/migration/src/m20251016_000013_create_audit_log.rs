@@ -0,0 +1,77 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Sensitive admin actions: who did what to which client, user or project
+        manager
+            .create_table(
+                Table::create()
+                    .table(AuditLog::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(AuditLog::Id)
+                            .uuid()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(AuditLog::ActorUserId).uuid())
+                    .col(ColumnDef::new(AuditLog::ClientId).uuid())
+                    .col(ColumnDef::new(AuditLog::Action).string().not_null())
+                    .col(ColumnDef::new(AuditLog::TargetId).string())
+                    .col(ColumnDef::new(AuditLog::Details).json_binary())
+                    .col(
+                        ColumnDef::new(AuditLog::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_audit_log_created_at")
+                    .table(AuditLog::Table)
+                    .col(AuditLog::CreatedAt)
+                    .if_not_exists()
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_audit_log_actor_created_at")
+                    .table(AuditLog::Table)
+                    .col(AuditLog::ActorUserId)
+                    .col(AuditLog::CreatedAt)
+                    .if_not_exists()
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(AuditLog::Table).if_exists().to_owned())
+            .await
+    }
+}
+
+#[derive(Iden)]
+enum AuditLog {
+    Table,
+    Id,
+    ActorUserId,
+    ClientId,
+    Action,
+    TargetId,
+    Details,
+    CreatedAt,
+}
@@ -0,0 +1,39 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Outcome of the latest connection test, and its error when it failed
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(DataSources::Table)
+                    .add_column_if_not_exists(ColumnDef::new(DataSources::ConnectionStatus).text().null())
+                    .add_column_if_not_exists(ColumnDef::new(DataSources::ConnectionError).text().null())
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(DataSources::Table)
+                    .drop_column(DataSources::ConnectionStatus)
+                    .drop_column(DataSources::ConnectionError)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(Iden)]
+enum DataSources {
+    Table,
+    ConnectionStatus,
+    ConnectionError,
+}
@@ -1697,6 +1697,16 @@ pub fn configure_analysis_routes() -> Router {
             Router::with_path("/analysis/{analysis_id}/execute")
                 .post(execute_analysis)
         )
+        // Pinned datasource schemas
+        .push(
+            Router::with_path("/analysis/{analysis_id}/schema-pins")
+                .get(super::analysis_schema::list_schema_pins_handler)
+                .post(super::analysis_schema::pin_schema_handler)
+        )
+        .push(
+            Router::with_path("/analysis/{analysis_id}/schema-pins/{datasource_id}")
+                .delete(super::analysis_schema::unpin_schema_handler)
+        )
         // Validation
         .push(
             Router::with_path("/analysis/validate")
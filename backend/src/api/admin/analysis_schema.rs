@@ -0,0 +1,111 @@
+use salvo::prelude::*;
+use serde::Deserialize;
+use serde_json::json;
+use uuid::Uuid;
+
+use crate::core::analysis::schema_pins::{
+    check_pin_drift, get_schema_version, list_schema_pins, pin_schema_version, snapshot_schema, unpin_schema,
+};
+use crate::core::datasources::shared_service::get_datasource_with_validation;
+use crate::utils::error::AppError;
+use crate::utils::get_app_state;
+
+#[derive(Debug, Deserialize)]
+pub struct PinSchemaRequest {
+    pub datasource_id: String,
+    /// Existing version to pin; snapshots the live schema when omitted
+    pub version: Option<i32>,
+}
+
+async fn analysis_project_id(db: &sqlx::PgPool, analysis_id: Uuid) -> Result<String, AppError> {
+    sqlx::query_scalar::<_, String>("SELECT project_id FROM analyses WHERE id = $1")
+        .bind(analysis_id)
+        .fetch_optional(db)
+        .await
+        .map_err(AppError::SqlxError)?
+        .ok_or_else(|| AppError::NotFound("Analysis not found".to_string()))
+}
+
+/// Schema versions pinned to an analysis; `?check_drift=true` compares each with the live schema
+#[handler]
+pub async fn list_schema_pins_handler(req: &mut Request, depot: &mut Depot, res: &mut Response) -> Result<(), AppError> {
+    let state = get_app_state(depot)?;
+    let analysis_id = req.param::<Uuid>("analysis_id")
+        .ok_or_else(|| AppError::BadRequest("analysis_id is required".to_string()))?;
+    let check_drift = req.query::<bool>("check_drift").unwrap_or(false);
+
+    let project_id = analysis_project_id(&state.db_pool, analysis_id).await?;
+    let pins = list_schema_pins(&state.db_pool, analysis_id).await
+        .map_err(|e| AppError::InternalServerError(format!("Failed to load schema pins: {}", e)))?;
+
+    let mut response = Vec::with_capacity(pins.len());
+    for pin in pins {
+        let mut entry = serde_json::to_value(&pin)
+            .map_err(|e| AppError::InternalServerError(e.to_string()))?;
+        if check_drift {
+            entry["drift"] = match check_pin_drift(&state.db_pool, &pin, &project_id).await {
+                Ok(drift) => json!({
+                    "drifted": !drift.is_empty(),
+                    "summary": drift.summary(),
+                    "details": drift,
+                }),
+                Err(e) => json!({ "error": e.to_string() }),
+            };
+        }
+        response.push(entry);
+    }
+
+    res.render(Json(json!({ "analysis_id": analysis_id, "pins": response })));
+    Ok(())
+}
+
+/// Pin a datasource schema version to an analysis, replacing any earlier pin
+#[handler]
+pub async fn pin_schema_handler(req: &mut Request, depot: &mut Depot, res: &mut Response) -> Result<(), AppError> {
+    let state = get_app_state(depot)?;
+    let analysis_id = req.param::<Uuid>("analysis_id")
+        .ok_or_else(|| AppError::BadRequest("analysis_id is required".to_string()))?;
+    let pin_req: PinSchemaRequest = req.parse_json().await
+        .map_err(|e| AppError::BadRequest(format!("Invalid request: {}", e)))?;
+
+    let project_id = analysis_project_id(&state.db_pool, analysis_id).await?;
+    let datasource = get_datasource_with_validation(&pin_req.datasource_id, &project_id, &state.db_pool).await
+        .map_err(|_| AppError::NotFound("Datasource not found in the analysis project".to_string()))?;
+
+    let version = match pin_req.version {
+        Some(version) => get_schema_version(&state.db_pool, &datasource.id, version).await
+            .map_err(|e| AppError::InternalServerError(format!("Failed to load schema version: {}", e)))?
+            .ok_or_else(|| AppError::NotFound(format!("Schema version {} not found", version)))?,
+        None => snapshot_schema(&state.db_pool, &datasource).await
+            .map_err(|e| AppError::InternalServerError(format!("Failed to snapshot schema: {}", e)))?,
+    };
+
+    pin_schema_version(&state.db_pool, analysis_id, &version).await
+        .map_err(|e| AppError::InternalServerError(format!("Failed to pin schema: {}", e)))?;
+
+    res.render(Json(json!({
+        "analysis_id": analysis_id,
+        "datasource_id": datasource.id,
+        "datasource_name": datasource.name,
+        "schema_version": version,
+    })));
+    Ok(())
+}
+
+#[handler]
+pub async fn unpin_schema_handler(req: &mut Request, depot: &mut Depot, res: &mut Response) -> Result<(), AppError> {
+    let state = get_app_state(depot)?;
+    let analysis_id = req.param::<Uuid>("analysis_id")
+        .ok_or_else(|| AppError::BadRequest("analysis_id is required".to_string()))?;
+    let datasource_id = req.param::<String>("datasource_id")
+        .ok_or_else(|| AppError::BadRequest("datasource_id is required".to_string()))?;
+
+    let removed = unpin_schema(&state.db_pool, analysis_id, &datasource_id).await
+        .map_err(|e| AppError::InternalServerError(format!("Failed to unpin schema: {}", e)))?;
+    if !removed {
+        return Err(AppError::NotFound("No schema pinned for this datasource".to_string()));
+    }
+
+    res.status_code(StatusCode::NO_CONTENT);
+    Ok(())
+}
@@ -0,0 +1,62 @@
+use salvo::prelude::*;
+use serde_json::json;
+use uuid::Uuid;
+
+use crate::core::audit::{list_audit_log, AuditLogFilter};
+use crate::utils::middleware::is_current_user_root;
+use crate::utils::{get_app_state, AppError};
+
+const DEFAULT_PAGE_SIZE: i64 = 50;
+const MAX_PAGE_SIZE: i64 = 500;
+
+/// Audit log entries, newest first, filtered by `actor` (user id) and
+/// `action`. Admins see their own client's entries; root sees everything.
+#[handler]
+pub async fn list_audit_log_entries(
+    req: &mut Request,
+    depot: &mut Depot,
+    res: &mut Response,
+) -> Result<(), AppError> {
+    let state = get_app_state(depot)?;
+
+    let actor_user_id = req
+        .query::<String>("actor")
+        .map(|actor| Uuid::parse_str(&actor).map_err(|_| AppError::BadRequest("Invalid actor user ID".to_string())))
+        .transpose()?;
+    let action = req.query::<String>("action").filter(|action| !action.is_empty());
+    let page = req.query::<i64>("page").unwrap_or(1).max(1);
+    let page_size = req
+        .query::<i64>("page_size")
+        .unwrap_or(DEFAULT_PAGE_SIZE)
+        .clamp(1, MAX_PAGE_SIZE);
+
+    let client_id = if is_current_user_root(depot) {
+        None
+    } else {
+        let client_id = depot
+            .get::<String>("current_user_client_id")
+            .ok()
+            .and_then(|id| Uuid::parse_str(id).ok())
+            .ok_or_else(|| AppError::Forbidden("Admin is not assigned to a client".to_string()))?;
+        Some(client_id)
+    };
+
+    let filter = AuditLogFilter {
+        actor_user_id,
+        action,
+        client_id,
+        limit: page_size,
+        offset: (page - 1) * page_size,
+    };
+    let (entries, total) = list_audit_log(&state.db_pool, &filter)
+        .await
+        .map_err(|e| AppError::InternalServerError(format!("Database error: {}", e)))?;
+
+    res.render(Json(json!({
+        "entries": entries,
+        "total": total,
+        "page": page,
+        "page_size": page_size,
+    })));
+    Ok(())
+}
@@ -0,0 +1,65 @@
+use salvo::prelude::*;
+use serde_json::json;
+
+use crate::core::backup::{backup_job, backup_status, list_backups, start_backup_job};
+use crate::utils::{get_app_state, AppError};
+
+/// List the available database backups along with the last backup outcome
+#[handler]
+pub async fn get_backups(depot: &mut Depot, res: &mut Response) -> Result<(), AppError> {
+    let state = get_app_state(depot)?;
+    let config = &state.config.backup;
+
+    let backups = list_backups(config)
+        .await
+        .map_err(|e| AppError::InternalServerError(format!("Failed to list backups: {}", e)))?;
+
+    res.render(Json(json!({
+        "scheduled": config.enabled,
+        "interval_secs": config.interval.as_secs(),
+        "retention": config.retention,
+        "status": backup_status().await,
+        "backups": backups
+    })));
+    Ok(())
+}
+
+/// Start a backup in the background; poll the returned job id for the outcome
+#[handler]
+pub async fn create_backup(depot: &mut Depot, res: &mut Response) -> Result<(), AppError> {
+    let state = get_app_state(depot)?;
+
+    // The only way to fail before spawning is another backup holding the lock
+    let job_id = start_backup_job(state.config.backup.clone(), state.config.database_url.clone())
+        .await
+        .map_err(|e| AppError::Conflict(e.to_string()))?;
+
+    res.status_code(StatusCode::ACCEPTED);
+    res.render(Json(json!({
+        "job_id": job_id,
+        "status": "running"
+    })));
+    Ok(())
+}
+
+/// Progress and outcome of an on-demand backup
+#[handler]
+pub async fn get_backup_job(req: &mut Request, res: &mut Response) -> Result<(), AppError> {
+    let job_id = req
+        .param::<String>("job_id")
+        .ok_or_else(|| AppError::BadRequest("Missing job_id".to_string()))?;
+
+    let job = backup_job(&job_id)
+        .await
+        .ok_or_else(|| AppError::NotFound(format!("Backup job {} not found", job_id)))?;
+
+    res.render(Json(job));
+    Ok(())
+}
+
+/// Last backup status, for diagnostics
+#[handler]
+pub async fn get_backup_status(res: &mut Response) -> Result<(), AppError> {
+    res.render(Json(backup_status().await));
+    Ok(())
+}
@@ -0,0 +1,174 @@
+use std::collections::{BTreeMap, HashSet};
+
+use salvo::prelude::*;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use uuid::Uuid;
+
+use crate::api::websocket::handlers::subscription::{disconnect_user, list_connections, ConnectionSummary};
+use crate::utils::{get_app_state, AppError};
+
+const DEFAULT_DISCONNECT_REASON: &str = "Disconnected by an administrator";
+
+#[derive(Debug, Default, Deserialize)]
+pub struct DisconnectUserRequest {
+    pub reason: Option<String>,
+}
+
+/// Close all live WebSocket connections of a user. Admins can only act on users
+/// of their own client; root can act on anyone.
+#[handler]
+pub async fn disconnect_user_connections(
+    req: &mut Request,
+    depot: &mut Depot,
+    res: &mut Response,
+) -> Result<(), AppError> {
+    let state = get_app_state(depot)?;
+    let user_id = req
+        .param::<String>("user_id")
+        .ok_or_else(|| AppError::BadRequest("Missing user ID".to_string()))?;
+    let user_uuid = Uuid::parse_str(&user_id)
+        .map_err(|_| AppError::BadRequest("Invalid user ID format".to_string()))?;
+
+    // The body is optional
+    let request: DisconnectUserRequest = req.parse_json().await.unwrap_or_default();
+    let reason = request
+        .reason
+        .filter(|r| !r.trim().is_empty())
+        .unwrap_or_else(|| DEFAULT_DISCONNECT_REASON.to_string());
+
+    let user_client_id: Uuid = sqlx::query_scalar("SELECT client_id FROM users WHERE id = $1")
+        .bind(user_uuid)
+        .fetch_optional(&state.db_pool)
+        .await
+        .map_err(|e| AppError::InternalServerError(format!("Database error: {}", e)))?
+        .ok_or_else(|| AppError::NotFound("User not found".to_string()))?;
+
+    let is_root = depot
+        .get::<String>("current_user_role")
+        .map(|role| role == "root")
+        .unwrap_or(false);
+    if !is_root {
+        let admin_client_id = depot
+            .get::<String>("current_user_client_id")
+            .ok()
+            .and_then(|id| Uuid::parse_str(id).ok());
+        if admin_client_id != Some(user_client_id) {
+            return Err(AppError::NotFound("User not found".to_string()));
+        }
+    }
+
+    let closed = disconnect_user(&user_id, &reason, state).await;
+
+    res.render(Json(json!({
+        "user_id": user_id,
+        "closed_connections": closed,
+        "reason": reason
+    })));
+    Ok(())
+}
+
+/// A user's live connections
+#[derive(Debug, Serialize)]
+pub struct UserConnections {
+    pub user_id: String,
+    pub connection_count: usize,
+    pub connections: Vec<ConnectionSummary>,
+}
+
+fn group_by_user(connections: Vec<ConnectionSummary>) -> Vec<UserConnections> {
+    let mut by_user: BTreeMap<String, Vec<ConnectionSummary>> = BTreeMap::new();
+    for connection in connections {
+        by_user.entry(connection.user_id.clone()).or_default().push(connection);
+    }
+    by_user
+        .into_iter()
+        .map(|(user_id, connections)| UserConnections {
+            user_id,
+            connection_count: connections.len(),
+            connections,
+        })
+        .collect()
+}
+
+/// Live WebSocket connections grouped by user, with what each is subscribed
+/// to and how long it has been open. Admins see the users of their own
+/// client; root sees everyone.
+#[handler]
+pub async fn list_active_connections(depot: &mut Depot, res: &mut Response) -> Result<(), AppError> {
+    let state = get_app_state(depot)?;
+    let mut connections = list_connections().await;
+
+    let is_root = depot
+        .get::<String>("current_user_role")
+        .map(|role| role == "root")
+        .unwrap_or(false);
+    if !is_root {
+        let admin_client_id = depot
+            .get::<String>("current_user_client_id")
+            .ok()
+            .and_then(|id| Uuid::parse_str(id).ok())
+            .ok_or_else(|| AppError::Forbidden("No client associated with this admin".to_string()))?;
+
+        let user_ids: Vec<Uuid> = connections
+            .iter()
+            .filter_map(|conn| Uuid::parse_str(&conn.user_id).ok())
+            .collect::<HashSet<_>>()
+            .into_iter()
+            .collect();
+        let visible: HashSet<String> = sqlx::query_scalar::<_, Uuid>(
+            "SELECT id FROM users WHERE client_id = $1 AND id = ANY($2)",
+        )
+        .bind(admin_client_id)
+        .bind(&user_ids)
+        .fetch_all(&state.db_pool)
+        .await
+        .map_err(|e| AppError::InternalServerError(format!("Database error: {}", e)))?
+        .into_iter()
+        .map(|id| id.to_string())
+        .collect();
+        connections.retain(|conn| visible.contains(&conn.user_id));
+    }
+
+    let total_connections = connections.len();
+    let users = group_by_user(connections);
+    res.render(Json(json!({
+        "total_users": users.len(),
+        "total_connections": total_connections,
+        "users": users
+    })));
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::websocket::handlers::subscription::{add_connection, remove_connection, WS_CONNECTIONS};
+    use tokio::sync::mpsc;
+
+    #[tokio::test]
+    async fn registered_connections_are_listed_per_user() {
+        let user_id = Uuid::new_v4().to_string();
+        let (tx, _rx) = mpsc::unbounded_channel();
+        add_connection("admin-list-1".to_string(), user_id.clone(), tx.clone()).await;
+        add_connection("admin-list-2".to_string(), user_id.clone(), tx).await;
+        if let Some(conn) = WS_CONNECTIONS.write().await.get_mut("admin-list-2") {
+            conn.project_id = Some("proj-1".to_string());
+            conn.conversation_id = Some("conv-1".to_string());
+        }
+
+        let users = group_by_user(list_connections().await);
+        remove_connection("admin-list-1", &user_id).await;
+        remove_connection("admin-list-2", &user_id).await;
+
+        let user = users.iter().find(|u| u.user_id == user_id).expect("user is listed");
+        assert_eq!(user.connection_count, 2);
+        let ids: Vec<&str> = user.connections.iter().map(|c| c.connection_id.as_str()).collect();
+        assert!(ids.contains(&"admin-list-1") && ids.contains(&"admin-list-2"));
+
+        let subscribed = user.connections.iter().find(|c| c.connection_id == "admin-list-2").unwrap();
+        assert_eq!(subscribed.project_id.as_deref(), Some("proj-1"));
+        assert_eq!(subscribed.conversation_id.as_deref(), Some("conv-1"));
+        assert!(subscribed.age_seconds >= 0);
+    }
+}
@@ -0,0 +1,57 @@
+use salvo::prelude::*;
+use serde_json::json;
+use uuid::Uuid;
+
+use crate::utils::{get_app_state, AppError};
+
+/// Permanently delete a conversation and its messages, whether or not it was
+/// soft-deleted first. Admins can only purge conversations of their own
+/// client; root can purge any.
+#[handler]
+pub async fn purge_conversation(
+    req: &mut Request,
+    depot: &mut Depot,
+    res: &mut Response,
+) -> Result<(), AppError> {
+    let state = get_app_state(depot)?;
+    let conversation_id = req
+        .param::<String>("conversation_id")
+        .ok_or_else(|| AppError::BadRequest("Missing conversation_id".to_string()))?;
+
+    let is_root = depot
+        .get::<String>("current_user_role")
+        .map(|role| role == "root")
+        .unwrap_or(false);
+    let admin_client_id = depot
+        .get::<String>("current_user_client_id")
+        .ok()
+        .and_then(|id| Uuid::parse_str(id).ok());
+
+    // Messages cascade delete
+    let result = sqlx::query(
+        "DELETE FROM conversations
+         USING projects p
+         WHERE conversations.id = $1 AND conversations.project_id = p.id
+           AND ($2 OR p.client_id = $3)",
+    )
+    .bind(&conversation_id)
+    .bind(is_root)
+    .bind(admin_client_id)
+    .execute(&state.db_pool)
+    .await
+    .map_err(|e| AppError::InternalServerError(format!("Database error: {}", e)))?;
+
+    if result.rows_affected() == 0 {
+        return Err(AppError::NotFound(format!(
+            "Conversation {} not found",
+            conversation_id
+        )));
+    }
+    let _ = state.invalidate_conversation_cache(&conversation_id).await;
+
+    res.render(Json(json!({
+        "success": true,
+        "purged_id": conversation_id
+    })));
+    Ok(())
+}
@@ -2,10 +2,50 @@
 pub mod crud;
 pub mod debug;
 pub mod analysis;
+pub mod analysis_schema;
+pub mod audit;
+pub mod backup;
+pub mod connections;
+pub mod conversations;
 
 use salvo::prelude::*;
 
 pub fn admin_routes() -> Router {
     Router::new()
         .push(Router::with_path("/debug/connections").get(debug::get_active_connections))
-}
\ No newline at end of file
+}
+
+/// User connection management; mounted only behind `admin_required`
+pub fn connection_routes() -> Router {
+    Router::new()
+        .push(Router::with_path("/connections").get(connections::list_active_connections))
+        .push(
+            Router::with_path("/users/{user_id}/disconnect")
+                .post(connections::disconnect_user_connections),
+        )
+}
+
+/// Audit trail of admin actions; mounted only behind `admin_required`
+pub fn audit_routes() -> Router {
+    Router::new().push(Router::with_path("/audit-log").get(audit::list_audit_log_entries))
+}
+
+/// Permanent conversation removal; mounted only behind `admin_required`
+pub fn conversation_routes() -> Router {
+    Router::new().push(
+        Router::with_path("/conversations/{conversation_id}/purge")
+            .delete(conversations::purge_conversation),
+    )
+}
+
+/// System-wide routes that act on the whole installation (root only)
+pub fn root_routes() -> Router {
+    Router::with_path("/admin")
+        .push(
+            Router::with_path("/backups")
+                .get(backup::get_backups)
+                .post(backup::create_backup)
+                .push(Router::with_path("/jobs/{job_id}").get(backup::get_backup_job)),
+        )
+        .push(Router::with_path("/debug/backup").get(backup::get_backup_status))
+}
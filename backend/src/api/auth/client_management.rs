@@ -4,6 +4,7 @@ use serde::{Deserialize, Serialize};
 use sqlx::Row;
 use uuid::Uuid;
 
+use crate::core::audit::{self, record_audit, AuditEntry};
 use crate::core::claude::ClaudeManager;
 use crate::models::client::{
     ClientAdminResponse, ClientCreateRequest, ClientRootResponse, ClientStatus, ClientUpdateRequest,
@@ -461,6 +462,12 @@ pub async fn delete_client(
     .await
     .map_err(|e| AppError::InternalServerError(format!("Database error: {}", e)))?;
 
+    record_audit(
+        &state.db_pool,
+        AuditEntry::by_current_user(depot, audit::CLIENT_DELETE, client_uuid, serde_json::json!({})),
+    )
+    .await;
+
     res.render(Json(serde_json::json!({
         "message": "Client deleted successfully",
         "id": client_id
@@ -693,6 +700,17 @@ pub async fn create_client_root(
     .await
     .map_err(|e| AppError::InternalServerError(format!("Failed to create client: {}", e)))?;
 
+    record_audit(
+        &state.db_pool,
+        AuditEntry::by_current_user(
+            depot,
+            audit::CLIENT_CREATE,
+            client_id,
+            serde_json::json!({ "name": create_request.name, "domains": create_request.domains }),
+        ),
+    )
+    .await;
+
     res.render(Json(serde_json::json!({
         "message": "Client created successfully",
         "id": client_id.to_string(),
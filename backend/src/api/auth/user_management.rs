@@ -5,6 +5,7 @@ use serde::{Deserialize, Serialize};
 use sqlx::Row;
 use uuid::Uuid;
 
+use crate::core::audit::{record_audit, role_change_entry};
 use crate::models::user::UserRole;
 use crate::utils::{AppError, AppState};
 
@@ -321,6 +322,14 @@ pub async fn update_user(
         updates.join(", ")
     );
 
+    let previous_role: Option<String> =
+        sqlx::query_scalar("SELECT role FROM users WHERE id = $1 AND client_id = $2")
+            .bind(user_uuid)
+            .bind(client_uuid)
+            .fetch_optional(&state.db_pool)
+            .await
+            .map_err(|e| AppError::InternalServerError(format!("Database error: {}", e)))?;
+
     let mut query = sqlx::query(&query_str);
     query = query.bind(user_uuid).bind(client_uuid);
 
@@ -328,12 +337,12 @@ pub async fn update_user(
     if let Some(username) = update_req.username {
         query = query.bind(username);
     }
-    if let Some(role) = update_req.role {
-        let role_str = match role {
-            UserRole::User => "user",
-            UserRole::Admin => "admin",
-            UserRole::Root => "root",
-        };
+    let new_role = update_req.role.map(|role| match role {
+        UserRole::User => "user",
+        UserRole::Admin => "admin",
+        UserRole::Root => "root",
+    });
+    if let Some(role_str) = new_role {
         query = query.bind(role_str);
     }
     query = query.bind(Utc::now());
@@ -347,6 +356,12 @@ pub async fn update_user(
         return Err(AppError::NotFound("User not found".to_string()));
     }
 
+    if let Some(entry) = previous_role
+        .and_then(|previous_role| role_change_entry(depot, user_uuid, &previous_role, new_role))
+    {
+        record_audit(&state.db_pool, entry).await;
+    }
+
     res.render(Json(serde_json::json!({
         "message": "User updated successfully",
         "id": user_id
@@ -684,18 +699,18 @@ pub async fn update_user_admin(
     if let Some(username) = update_req.username {
         query = query.bind(username);
     }
-    if let Some(role) = update_req.role {
-        let role_str = match role {
-            UserRole::User => "user",
-            UserRole::Admin => {
-                if user_role == "admin" {
-                    "user"
-                } else {
-                    "admin"
-                }
+    let new_role = update_req.role.map(|role| match role {
+        UserRole::User => "user",
+        UserRole::Admin => {
+            if user_role == "admin" {
+                "user"
+            } else {
+                "admin"
             }
-            UserRole::Root => "root",
-        };
+        }
+        UserRole::Root => "root",
+    });
+    if let Some(role_str) = new_role {
         query = query.bind(role_str);
     }
     query = query.bind(Utc::now());
@@ -705,6 +720,10 @@ pub async fn update_user_admin(
         .await
         .map_err(|e| AppError::InternalServerError(format!("Database error: {}", e)))?;
 
+    if let Some(entry) = role_change_entry(depot, user_uuid, &target_role, new_role) {
+        record_audit(&state.db_pool, entry).await;
+    }
+
     res.render(Json(serde_json::json!({
         "message": "User updated successfully",
         "id": user_id
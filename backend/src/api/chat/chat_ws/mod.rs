@@ -0,0 +1,597 @@
+use crate::core::claude::{ClaudeManager, QueryOptions};
+use crate::models::{Message, MessageRole};
+use crate::utils::AppError;
+use crate::utils::{AppState, ConversationLock, StreamingState};
+use crate::utils::message_files::{associate_files_with_message, get_message_files, format_files_for_prompt};
+use chrono::Utc;
+use sqlx::{PgPool, Row};
+
+use crate::api::websocket::{
+    broadcast_activity_to_project, broadcast_to_subscribers, WebSocketServerMessage as ServerMessage,
+};
+use uuid;
+
+mod prompt;
+mod stream;
+
+use prompt::build_prompt;
+use stream::{display_tool_usages, relay_claude_stream, StreamOutcome};
+
+async fn save_message(
+    pool: &PgPool,
+    conversation_id: &str,
+    message: &Message,
+) -> Result<(), AppError> {
+    // For user messages, insert a new record. For assistant messages, update the placeholder
+    if message.role == MessageRole::User {
+        // Insert new user message
+        sqlx::query(
+            "INSERT INTO messages (id, conversation_id, role, content, created_at, processing_time_ms) 
+             VALUES ($1, $2, $3, $4, $5, $6)",
+        )
+        .bind(&message.id)
+        .bind(conversation_id)
+        .bind("user") // User role as string literal
+        .bind(&message.content)
+        .bind(
+            message.created_at.as_ref()
+                .and_then(|dt| chrono::DateTime::parse_from_rfc3339(dt).ok())
+                .map(|dt| dt.with_timezone(&Utc))
+                .unwrap_or_else(Utc::now)
+        )
+        .bind(message.processing_time_ms)
+        .execute(pool)
+        .await
+        .map_err(|e| AppError::InternalServerError(format!("Failed to insert user message: {}", e)))?;
+    } else {
+        // Update the existing message (created as placeholder during streaming)
+        // Note: We intentionally don't update progress_content here to preserve the "thinking" content
+        // that was saved during streaming. Only update content and processing_time_ms.
+        sqlx::query(
+            "UPDATE messages SET content = $1, processing_time_ms = $2 WHERE id = $3",
+        )
+        .bind(&message.content)
+        .bind(message.processing_time_ms)
+        .bind(&message.id)
+        .execute(pool)
+        .await
+        .map_err(|e| AppError::InternalServerError(format!("Failed to update assistant message: {}", e)))?;
+    }
+
+    // Save tool usages if present (including failed MCP interactions for tracking)
+    if let Some(tool_usages) = &message.tool_usages {
+        for tool_usage in tool_usages {
+            // Always save tool usage for tracking purposes, including failed MCP interactions
+            
+            // Helper function to determine if a tool should preserve parameters and output
+            let should_preserve_tool_data = |tool_name: &str| -> bool {
+                tool_name.starts_with("mcp__interaction__") || tool_name == "TodoWrite"
+            };
+
+            // Determine final parameters and output
+            let (final_parameters, final_output) = if should_preserve_tool_data(&tool_usage.tool_name) {
+                // For MCP interaction tools and TodoWrite, preserve original output and parameters
+                (tool_usage.parameters.clone(), tool_usage.output.clone())
+            } else {
+                // Other tools, keep original (for backward compatibility)
+                (tool_usage.parameters.clone(), tool_usage.output.clone())
+            };
+
+            sqlx::query(
+                "INSERT INTO tool_usages (id, message_id, tool_name, tool_use_id, parameters, output, execution_time_ms, created_at)
+                 VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+                 ON CONFLICT (id) DO UPDATE SET
+                    tool_use_id = EXCLUDED.tool_use_id,
+                    parameters = EXCLUDED.parameters,
+                    output = EXCLUDED.output,
+                    execution_time_ms = EXCLUDED.execution_time_ms"
+            )
+            .bind(tool_usage.id)
+            .bind(&message.id)
+            .bind(&tool_usage.tool_name)
+            .bind(&tool_usage.tool_use_id)
+            .bind(&final_parameters)
+            .bind(&final_output)
+            .bind(tool_usage.execution_time_ms)
+                        .bind(
+                tool_usage.created_at.as_ref()
+                    .and_then(|dt| chrono::DateTime::parse_from_rfc3339(dt).ok())
+                    .map(|dt| dt.with_timezone(&Utc))
+                    .unwrap_or_else(Utc::now)
+            )
+            .execute(pool)
+            .await
+            .map_err(|e| AppError::InternalServerError(format!("Failed to save tool usage: {}", e)))?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Pick the model for a query and remember an explicitly requested one on the
+/// conversation so follow-up messages keep using it
+async fn resolve_conversation_model(
+    state: &AppState,
+    conversation_id: &str,
+    requested: Option<&str>,
+) -> Option<String> {
+    let models = &state.config.claude_models;
+    let stored: Option<String> = sqlx::query_scalar("SELECT model FROM conversations WHERE id = $1")
+        .bind(conversation_id)
+        .fetch_optional(&state.db_pool)
+        .await
+        .ok()
+        .flatten()
+        .flatten();
+
+    if let Some(requested) = requested {
+        match models.validate(requested) {
+            Some(model) if stored.as_deref() != Some(model.as_str()) => {
+                if let Err(e) = sqlx::query("UPDATE conversations SET model = $1 WHERE id = $2")
+                    .bind(&model)
+                    .bind(conversation_id)
+                    .execute(&state.db_pool)
+                    .await
+                {
+                    tracing::warn!("Failed to save model for conversation {}: {}", conversation_id, e);
+                }
+            }
+            Some(_) => {}
+            None => tracing::warn!(
+                "Model '{}' is not allowed for conversation {}, using the default",
+                requested,
+                conversation_id
+            ),
+        }
+    }
+
+    models.resolve(requested, stored.as_deref())
+}
+
+// WebSocket-only message handler (replaces SSE streaming)
+#[allow(clippy::too_many_arguments)]
+pub async fn handle_chat_message_ws(
+    project_id: String,
+    conversation_id: String,
+    content: String,
+    file_ids: Vec<String>, // Changed from _uploaded_file_paths to file_ids
+    model: Option<String>,
+    client_id_str: String,
+    generation_lock: Option<ConversationLock>,
+    state: AppState,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    tracing::info!(
+        "handle_chat_message_ws started: project={}, conversation={}, client={}",
+        project_id,
+        conversation_id,
+        client_id_str
+    );
+
+    let db_pool = state.db_pool.clone();
+    let active_claude_streams = state.active_claude_streams.clone();
+
+    // Handle "new" conversation ID by creating in database and getting the ID
+    let actual_conversation_id = if conversation_id == "new" {
+        tracing::info!("Creating new conversation in database");
+        let new_id = uuid::Uuid::new_v4().to_string();
+
+        sqlx::query(
+            "INSERT INTO conversations (id, project_id, title, created_at, is_title_manually_set) VALUES ($1, $2, $3, $4, $5)"
+        )
+        .bind(&new_id)
+        .bind(&project_id)
+        .bind("New Conversation")
+        .bind(Utc::now())
+        .bind(false)
+        .execute(&db_pool)
+        .await
+        .map_err(|e| format!("Failed to create conversation: {}", e))?;
+
+        tracing::info!("Created new conversation with ID: {}", new_id);
+
+        // Send redirect message to frontend
+        broadcast_to_subscribers(
+            &project_id,
+            "new", // Send to the original "new" subscription
+            ServerMessage::ConversationRedirect {
+                old_conversation_id: "new".to_string(),
+                new_conversation_id: new_id.clone(),
+            },
+        )
+        .await;
+
+        new_id
+    } else {
+        conversation_id.clone()
+    };
+
+    let conversation_id_clone = actual_conversation_id.clone();
+
+    let model = resolve_conversation_model(&state, &actual_conversation_id, model.as_deref()).await;
+
+    // Only one generation runs per conversation; the lock is released when this returns
+    let _generation_lock = match generation_lock.or_else(|| state.try_lock_conversation(&actual_conversation_id)) {
+        Some(lock) => lock,
+        None => {
+            tracing::info!("Conversation {} is busy, not starting another generation", actual_conversation_id);
+            broadcast_to_subscribers(
+                &project_id,
+                &actual_conversation_id,
+                ServerMessage::conversation_busy(&actual_conversation_id),
+            )
+            .await;
+            return Ok(());
+        }
+    };
+
+    // Insert user message first
+    tracing::info!("Creating user message");
+    let user_message = Message {
+        id: uuid::Uuid::new_v4().to_string(),
+        content: content.clone(),
+        role: MessageRole::User,
+        created_at: Some(Utc::now().to_rfc3339()),
+        processing_time_ms: None,
+        file_attachments: None, // WebSocket doesn't handle file attachments in this way
+        tool_usages: None,
+        progress_content: None,
+    };
+
+    tracing::info!("Saving user message");
+    if let Err(e) = save_message(&db_pool, &actual_conversation_id, &user_message).await {
+        tracing::error!("Failed to save user message: {}", e);
+        return Err(e.into());
+    }
+    tracing::info!("User message saved successfully");
+
+    // Associate files with the user message if any are provided
+    if !file_ids.is_empty() {
+        tracing::info!("Associating {} files with message", file_ids.len());
+        let file_uuids: Result<Vec<uuid::Uuid>, _> = file_ids.iter()
+            .map(|id| uuid::Uuid::parse_str(id))
+            .collect();
+        
+        match file_uuids {
+            Ok(uuids) => {
+                if let Err(e) = associate_files_with_message(&db_pool, &user_message.id, uuids).await {
+                    tracing::error!("Failed to associate files with message: {}", e);
+                    // Don't fail the entire request, just log the error
+                }
+            }
+            Err(e) => {
+                tracing::error!("Invalid file ID format in request: {}", e);
+                // Don't fail the entire request, just log the error
+            }
+        }
+    }
+
+    // Update the conversation cache with the new user message
+    state
+        .update_conversation_cache(&actual_conversation_id, user_message.clone())
+        .await;
+    tracing::info!("Conversation cache updated");
+
+    // Get conversation history from cache (fast) or database (slow)
+    tracing::info!("Getting conversation history for context");
+    let cached_messages = state
+        .get_conversation_messages(&actual_conversation_id)
+        .await
+        .map_err(|e| format!("Failed to get conversation history: {}", e))?;
+    tracing::info!("Got conversation history");
+
+    // Build the full prompt with conversation history
+    let (mut full_prompt, history_count) = build_prompt(&cached_messages, &content);
+    
+    // Get files associated with the current user message and add to context
+    if !file_ids.is_empty() {
+        tracing::info!("Getting file context for Claude prompt");
+        match get_message_files(&db_pool, &user_message.id).await {
+            Ok(files) => {
+                if !files.is_empty() {
+                    let file_context = format_files_for_prompt(files);
+                    full_prompt.push_str(&file_context);
+                    tracing::info!("Added file context to prompt ({} characters)", file_context.len());
+                }
+            }
+            Err(e) => {
+                tracing::error!("Failed to get message files for context: {}", e);
+                // Continue without file context
+            }
+        }
+    }
+
+    tracing::info!(
+        "Built prompt with {} historical messages for conversation {}, total length: {} chars",
+        history_count,
+        actual_conversation_id,
+        full_prompt.len()
+    );
+    tracing::debug!(
+        "Full prompt preview (first 500 chars): {}",
+        full_prompt.chars().take(500).collect::<String>()
+    );
+
+    // Warn if prompt is getting too long (approaching OS limits)
+    if full_prompt.len() > 100_000 {
+        tracing::warn!("Prompt length ({} chars) may approach OS command line limits. Consider truncating older messages.", full_prompt.len());
+    }
+
+    let start_time = std::time::Instant::now();
+    let message_id = uuid::Uuid::new_v4();
+
+    // Create a placeholder assistant message immediately to satisfy foreign key constraints
+    // This will be updated with the final content when streaming completes
+    let assistant_created_at = Utc::now();
+    if let Err(e) = sqlx::query(
+        "INSERT INTO messages (id, conversation_id, content, role, created_at)
+         VALUES ($1, $2, $3, $4, $5)"
+    )
+    .bind(message_id)
+    .bind(&actual_conversation_id)
+    .bind("") // Empty content initially, will be updated
+    .bind("assistant")
+    .bind(assistant_created_at)
+    .execute(&db_pool)
+    .await {
+        tracing::error!("Failed to create placeholder assistant message: {}", e);
+        return Err(format!("Failed to create message: {}", e).into());
+    }
+
+    // Add the placeholder assistant message to cache so it's visible during streaming
+    let placeholder_message = crate::models::Message {
+        id: message_id.to_string(),
+        content: String::new(),
+        role: crate::models::MessageRole::Assistant,
+        created_at: Some(assistant_created_at.to_rfc3339()),
+        processing_time_ms: None,
+        file_attachments: None,
+        tool_usages: None,
+        progress_content: None,
+    };
+    state
+        .update_conversation_cache(&actual_conversation_id, placeholder_message)
+        .await;
+    tracing::info!("Added placeholder assistant message to cache");
+
+    // Track this conversation as actively streaming
+    {
+        let mut streams = active_claude_streams.write().await;
+        streams.insert(
+            conversation_id_clone.clone(),
+            StreamingState::new(message_id.to_string()),
+        );
+    }
+
+    // Send start event to the actual conversation ID
+    // Note: If this was a redirect from "new", the connection subscription has already been updated
+    broadcast_to_subscribers(
+        &project_id,
+        &conversation_id_clone,
+        ServerMessage::Start {
+            id: message_id.to_string(),
+            conversation_id: conversation_id_clone.clone(),
+        },
+    )
+    .await;
+
+    // Execute the Claude query with project context
+    tracing::info!("Parsing client ID and starting Claude query");
+    let client_id = client_id_str
+        .parse::<uuid::Uuid>()
+        .map_err(|e| format!("Invalid client ID: {}", e))?;
+    tracing::info!("Client ID parsed successfully: {}", client_id);
+
+    // Get user information for activity notification
+    let user_info = sqlx::query(
+        "SELECT u.username, c.name as client_name FROM users u 
+         JOIN clients c ON u.client_id = c.id 
+         WHERE u.client_id = $1",
+    )
+    .bind(client_id)
+    .fetch_optional(&db_pool)
+    .await;
+
+    let user_name = match user_info {
+        Ok(Some(row)) => {
+            let username: String = row
+                .try_get("username")
+                .unwrap_or_else(|_| "unknown".to_string());
+            let client_name: String = row
+                .try_get("client_name")
+                .unwrap_or_else(|_| "unknown".to_string());
+            format!("{} ({})", username, client_name)
+        }
+        _ => "unknown user".to_string(),
+    };
+
+    // Create message preview (first 100 chars)
+    let message_preview = if content.len() > 100 {
+        format!("{}...", content.chars().take(97).collect::<String>())
+    } else {
+        content.clone()
+    };
+
+    // Broadcast activity to other users in the same project
+    broadcast_activity_to_project(
+        &project_id,
+        &actual_conversation_id,
+        &client_id.to_string(), // Use client_id as unique identifier
+        &user_name,
+        "new_message",
+        Some(message_preview),
+    )
+    .await;
+
+    match ClaudeManager::query_claude_with_project_and_db(
+        client_id,
+        &project_id,
+        full_prompt,
+        Some(QueryOptions {
+            conversation_id: Some(actual_conversation_id.clone()),
+            model,
+            ..Default::default()
+        }),
+        &db_pool,
+    )
+    .await
+    {
+        Ok(receiver) => {
+            tracing::info!("Claude query successful, starting message loop");
+            let StreamOutcome {
+                assistant_content,
+                progress_content,
+                tool_usages,
+            } = relay_claude_stream(receiver, &state, &project_id, &conversation_id_clone, message_id)
+                .await?;
+
+            // Save any remaining progress content before finalizing
+            if !progress_content.is_empty() {
+                if let Err(e) = sqlx::query(
+                    "UPDATE messages SET progress_content = $1 WHERE id = $2"
+                )
+                .bind(&progress_content)
+                .bind(message_id.to_string())
+                .execute(&db_pool)
+                .await {
+                    tracing::error!("Failed to save final progress content: {}", e);
+                } else {
+                    tracing::info!("Saved final progress content: {} chars", progress_content.len());
+                }
+            }
+
+            // Save final assistant message
+            if !assistant_content.is_empty() {
+                // Create message with full tool usages for database storage
+                let assistant_message = Message {
+                    id: message_id.to_string(),
+                    content: assistant_content.clone(),
+                    role: MessageRole::Assistant,
+                    created_at: Some(Utc::now().to_rfc3339()),
+                    processing_time_ms: Some(start_time.elapsed().as_millis() as i64),
+                    file_attachments: None,
+                    tool_usages: if tool_usages.is_empty() {
+                        None
+                    } else {
+                        Some(tool_usages.clone())
+                    },
+                    progress_content: if progress_content.is_empty() {
+                        None
+                    } else {
+                        Some(progress_content.clone())
+                    },
+                };
+
+                if let Err(e) =
+                    save_message(&db_pool, &actual_conversation_id, &assistant_message).await
+                {
+                    tracing::error!("Failed to save assistant message: {}", e);
+                } else {
+                    // Create filtered message for cache (exclude parameters and output)
+                    let filtered_assistant_message = Message {
+                        id: message_id.to_string(),
+                        content: assistant_content,
+                        role: MessageRole::Assistant,
+                        created_at: Some(Utc::now().to_rfc3339()),
+                        processing_time_ms: Some(start_time.elapsed().as_millis() as i64),
+                        file_attachments: None,
+                        tool_usages: display_tool_usages(&tool_usages),
+                        progress_content: if progress_content.is_empty() {
+                            None
+                        } else {
+                            Some(progress_content)
+                        },
+                    };
+
+                    // Update the conversation cache with the filtered assistant message
+                    state
+                        .update_conversation_cache(&actual_conversation_id, filtered_assistant_message)
+                        .await;
+                }
+            }
+
+            // Generate title if this is the first exchange
+            let message_count = sqlx::query_scalar::<_, i64>(
+                "SELECT COUNT(*) FROM messages WHERE conversation_id = $1",
+            )
+            .bind(&actual_conversation_id)
+            .fetch_one(&db_pool)
+            .await
+            .unwrap_or(0);
+
+            if message_count == 2 {
+                // User + Assistant message
+                let title = if content.len() > 50 {
+                    format!("{}...", content.chars().take(47).collect::<String>().trim())
+                } else if content.is_empty() {
+                    "New Conversation".to_string()
+                } else {
+                    content.clone()
+                };
+
+                let _ = sqlx::query(
+                    "UPDATE conversations SET title = $1 WHERE id = $2 AND is_title_manually_set = false"
+                )
+                .bind(&title)
+                .bind(&actual_conversation_id)
+                .execute(&db_pool)
+                .await;
+            }
+
+            // Remove stream from active streams since it's complete
+            {
+                let mut streams = state.active_claude_streams.write().await;
+                if streams.remove(&conversation_id_clone).is_some() {
+                    tracing::info!("Removed completed stream for conversation {}", conversation_id_clone);
+                }
+            }
+            
+            // Send completion event
+            broadcast_to_subscribers(
+                &project_id,
+                &conversation_id_clone,
+                ServerMessage::Complete {
+                    id: message_id.to_string(),
+                    conversation_id: conversation_id_clone.clone(),
+                    processing_time_ms: start_time.elapsed().as_millis() as u64,
+                    tool_usages: display_tool_usages(&tool_usages),
+                },
+            )
+            .await;
+        }
+
+        Err(e) => {
+            let error_msg = e.to_string();
+            tracing::error!("Failed to query Claude: {}", error_msg);
+
+            // Remove stream from active streams on error
+            {
+                let mut streams = active_claude_streams.write().await;
+                streams.remove(&conversation_id_clone);
+            }
+            
+            // Send appropriate error message to client
+            broadcast_to_subscribers(
+                &project_id,
+                &conversation_id_clone,
+                ServerMessage::Error {
+                    error: error_msg.clone(),
+                    conversation_id: conversation_id_clone.clone(),
+                },
+            )
+            .await;
+            return Err(e);
+        }
+    }
+
+    Ok(())
+}
+
+// Placeholder exports for missing types
+#[allow(dead_code)]
+pub struct ChatWebSocketHandlers;
+#[allow(dead_code)]
+pub struct ChatMessage;
+pub mod types {
+    #[allow(dead_code)]
+    pub struct ChatType;
+}
@@ -0,0 +1,74 @@
+//! Prompt assembly for a chat turn
+
+use crate::models::Message;
+
+/// Builds the prompt sent to Claude: the conversation history (tool usages
+/// included) followed by the current message. Returns the prompt and how many
+/// history messages went into it.
+pub(super) fn build_prompt(history: &[Message], content: &str) -> (String, usize) {
+    // Pre-allocate capacity: estimate ~300 chars per message + current content
+    let estimated_capacity = (history.len() * 300) + content.len() + 1000;
+    let mut full_prompt = String::with_capacity(estimated_capacity);
+    let mut history_count = 0;
+    if !history.is_empty() {
+        full_prompt.push_str("Previous conversation:\n\n");
+        for msg in history.iter() {
+            // Skip the current message we just added
+            if msg.role == crate::models::MessageRole::User && msg.content == content {
+                continue;
+            }
+
+            match msg.role {
+                crate::models::MessageRole::User => {
+                    full_prompt.push_str(&format!("User: {}\n\n", msg.content));
+                    history_count += 1;
+                }
+                crate::models::MessageRole::Assistant => {
+                    full_prompt.push_str(&format!("Assistant: {}\n", msg.content));
+
+                    // Include tool usages if present (already in cached message)
+                    if let Some(ref tool_usages) = msg.tool_usages {
+                        if !tool_usages.is_empty() {
+                            full_prompt.push_str("\n[Tool Usage Details]:\n");
+                            for tool in tool_usages {
+                                full_prompt.push_str(&format!("- Tool: {}\n", tool.tool_name));
+                                if let Some(ref params) = tool.parameters {
+                                    full_prompt.push_str(&format!("  Parameters: {}\n", params));
+                                }
+                                if let Some(ref out) = tool.output {
+                                    // Convert JSON to string and truncate if very long
+                                    let output_str = if out.is_string() {
+                                        out.as_str().unwrap_or("").to_string()
+                                    } else {
+                                        serde_json::to_string(&out)
+                                            .unwrap_or_else(|_| out.to_string())
+                                    };
+
+                                    let truncated_output = if output_str.len() > 500 {
+                                        format!(
+                                            "{}... [truncated]",
+                                            output_str.chars().take(497).collect::<String>()
+                                        )
+                                    } else {
+                                        output_str
+                                    };
+                                    full_prompt
+                                        .push_str(&format!("  Output: {}\n", truncated_output));
+                                }
+                            }
+                        }
+                    }
+                    full_prompt.push('\n');
+                    history_count += 1;
+                }
+                _ => {}
+            }
+        }
+        full_prompt.push_str("Current message:\n");
+    }
+    
+    // Add current user message
+    full_prompt.push_str(content);
+
+    (full_prompt, history_count)
+}
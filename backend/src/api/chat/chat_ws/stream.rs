@@ -0,0 +1,426 @@
+//! Relays a Claude run to the conversation's WebSocket subscribers
+//!
+//! Events are broadcast as they arrive and kept in the conversation's
+//! streaming state for replay on reconnection; tool usages and progress are
+//! written to the assistant message while the run is still going.
+
+use crate::api::websocket::{broadcast_to_subscribers, WebSocketServerMessage as ServerMessage};
+use crate::core::claude::ClaudeMessage;
+use crate::models::tool_usage::ToolUsage;
+use crate::utils::AppState;
+use chrono::Utc;
+use sqlx::Row;
+use tokio::sync::mpsc;
+
+/// What a Claude run produced by the time its stream closed
+pub(super) struct StreamOutcome {
+    /// Final user-facing content
+    pub assistant_content: String,
+    /// Internal thinking/progress
+    pub progress_content: String,
+    pub tool_usages: Vec<ToolUsage>,
+}
+
+/// Drains `receiver`, broadcasting each event to `conversation_id`. A Claude
+/// error ends the stream, is forwarded to subscribers and returned.
+pub(super) async fn relay_claude_stream(
+    mut receiver: mpsc::Receiver<ClaudeMessage>,
+    state: &AppState,
+    project_id: &str,
+    conversation_id: &str,
+    message_id: uuid::Uuid,
+) -> Result<StreamOutcome, Box<dyn std::error::Error + Send + Sync>> {
+    let db_pool = &state.db_pool;
+    let active_claude_streams = &state.active_claude_streams;
+    let stream_buffer_max_bytes = state.config.stream_buffer_max_bytes;
+
+    // Pre-allocate capacity for better performance
+    let mut progress_content = String::with_capacity(2048);  // For internal thinking/progress
+    let mut assistant_content = String::with_capacity(1024);  // For final user-facing content
+    let mut tool_usages = Vec::with_capacity(8);
+    let mut pending_tools = std::collections::HashMap::with_capacity(8);
+    let mut last_content_save = std::time::Instant::now();
+    const CONTENT_SAVE_INTERVAL: std::time::Duration = std::time::Duration::from_secs(2);
+
+    while let Some(message) = receiver.recv().await {
+        match message {
+            ClaudeMessage::Progress { content } => {
+                // Store progress event for replay on reconnection
+                {
+                    let mut streams = active_claude_streams.write().await;
+                    if let Some(stream_state) = streams.get_mut(conversation_id) {
+                        stream_state.push_event(serde_json::json!({
+                            "type": "progress",
+                            "content": content
+                        }), stream_buffer_max_bytes);
+                    }
+                }
+                
+                // Extract and save progress/thinking content separately
+                if let Some(content_obj) = content.as_object() {
+                    if let Some(message_obj) = content_obj.get("message") {
+                        if let Some(content_array) = message_obj.get("content").and_then(|v| v.as_array()) {
+                            for content_item in content_array {
+                                if let Some(text) = content_item.get("text").and_then(|v| v.as_str()) {
+                                    progress_content.push_str(text);
+                                }
+                            }
+                        }
+                    } else if let Some(result_text) = content_obj.get("result").and_then(|v| v.as_str()) {
+                        // Handle result format - this might be actual content
+                        progress_content.push_str(result_text);
+                    }
+                }
+                
+                // Periodically save progress content to database
+                if last_content_save.elapsed() > CONTENT_SAVE_INTERVAL && !progress_content.is_empty() {
+                    if let Err(e) = sqlx::query(
+                        "UPDATE messages SET progress_content = $1 WHERE id = $2"
+                    )
+                    .bind(&progress_content)
+                    .bind(message_id.to_string())
+                    .execute(db_pool)
+                    .await {
+                        tracing::error!("Failed to update message progress content: {}", e);
+                    } else {
+                        tracing::debug!("Saved progress content: {} chars", progress_content.len());
+                        last_content_save = std::time::Instant::now();
+                    }
+                }
+
+                // Send progress via WebSocket without any parsing
+                broadcast_to_subscribers(
+                    project_id,
+                    conversation_id,
+                    ServerMessage::Progress {
+                        content,
+                        conversation_id: conversation_id.to_string(),
+                    },
+                )
+                .await;
+            }
+
+            ClaudeMessage::ToolUse {
+                tool,
+                args,
+                tool_use_id,
+            } => {
+                let tool_usage_id = uuid::Uuid::new_v4();
+                let lookup_key = tool_use_id.clone().unwrap_or_else(|| tool.clone());
+                pending_tools.insert(
+                    lookup_key.clone(),
+                    (
+                        tool.clone(),
+                        args.clone(),
+                        std::time::Instant::now(),
+                        tool_usage_id,
+                    ),
+                );
+
+                // Create tool usage record in database immediately to avoid 404 errors
+
+                // Save initial tool usage to database immediately
+                if let Err(e) = sqlx::query(
+                    "INSERT INTO tool_usages (id, message_id, tool_name, tool_use_id, parameters, output, execution_time_ms, created_at)
+                     VALUES ($1, $2, $3, $4, $5, $6, $7, $8)"
+                )
+                .bind(tool_usage_id)
+                .bind(message_id.to_string())
+                .bind(&tool)
+                .bind(&tool_use_id)
+                .bind(&args)
+                .bind(Option::<serde_json::Value>::None) // output starts as null
+                .bind(Option::<i64>::None) // execution_time_ms starts as null
+                .bind(Utc::now())
+                .execute(db_pool)
+                .await {
+                    tracing::error!("Failed to create initial tool usage record: {}", e);
+                }
+
+                // Store tool use event for replay on reconnection
+                {
+                    let mut streams = active_claude_streams.write().await;
+                    if let Some(stream_state) = streams.get_mut(conversation_id) {
+                        stream_state.push_event(serde_json::json!({
+                            "type": "tool_use",
+                            "tool": tool,
+                            "tool_usage_id": tool_usage_id.to_string()
+                        }), stream_buffer_max_bytes);
+                    }
+                }
+
+                // Send ToolUse via WebSocket
+                broadcast_to_subscribers(
+                    project_id,
+                    conversation_id,
+                    ServerMessage::ToolUse {
+                        tool: tool.clone(),
+                        tool_usage_id: tool_usage_id.to_string(),
+                        conversation_id: conversation_id.to_string(),
+                    },
+                )
+                .await;
+            }
+
+            ClaudeMessage::ToolResult { tool, result } => {
+                // Handle tool completion and send ToolComplete event
+                if let Some((name, params, start_time, tool_usage_id)) =
+                    pending_tools.remove(&tool)
+                {
+                    let execution_time = start_time.elapsed().as_millis() as u64;
+
+                    // Update the existing tool usage record with the result
+                    if let Err(e) = sqlx::query(
+                        "UPDATE tool_usages SET output = $1, execution_time_ms = $2 WHERE id = $3"
+                    )
+                    .bind(&result)
+                    .bind(execution_time as i64)
+                    .bind(tool_usage_id)
+                    .execute(db_pool)
+                    .await {
+                        tracing::error!("Failed to update tool usage record: {}", e);
+                    }
+
+                    // Invalidate cache so next fetch gets updated tool usages from database
+                    if let Err(e) = state.invalidate_conversation_cache(conversation_id).await {
+                        tracing::warn!("Failed to invalidate conversation cache: {}", e);
+                    }
+
+                    // Store tool complete event for replay on reconnection
+                    {
+                        let mut streams = active_claude_streams.write().await;
+                        if let Some(stream_state) = streams.get_mut(conversation_id)
+                        {
+                            stream_state.push_event(serde_json::json!({
+                                "type": "tool_complete",
+                                "tool": name,
+                                "tool_usage_id": tool_usage_id.to_string(),
+                                "execution_time_ms": execution_time as i64,
+                                "output": result
+                            }), stream_buffer_max_bytes);
+                        }
+                    }
+
+                    // Send ToolComplete event
+                    // For TodoWrite, include parameters in output since that's where todos are stored
+                    let output_to_send = if name == "TodoWrite" {
+                        // Send parameters as output for TodoWrite since it contains the todos
+                        Some(params.clone())
+                    } else {
+                        Some(result.clone())
+                    };
+                    
+                    broadcast_to_subscribers(
+                        project_id,
+                        conversation_id,
+                        ServerMessage::ToolComplete {
+                            tool: name.clone(),
+                            tool_usage_id: tool_usage_id.to_string(),
+                            execution_time_ms: execution_time as i64,
+                            output: output_to_send,
+                            conversation_id: conversation_id.to_string(),
+                        },
+                    )
+                    .await;
+
+                    // Add to tool_usages for final message (with full data for database storage)
+                    let tool_usage = crate::models::tool_usage::ToolUsage {
+                        id: tool_usage_id,
+                        message_id: message_id.to_string(),
+                        tool_name: name.clone(),
+                        tool_use_id: Some(tool.clone()),
+                        parameters: Some(params),
+                        output: Some(result.clone()),
+                        execution_time_ms: Some(execution_time as i64),
+                        created_at: None,
+                    };
+                    tool_usages.push(tool_usage.clone());
+                    
+                    // Store completed tool usage in streaming state for immediate access
+                    {
+                        let mut streams = active_claude_streams.write().await;
+                        if let Some(stream_state) = streams.get_mut(conversation_id) {
+                            stream_state.completed_tool_usages.push(tool_usage);
+                        }
+                    }
+                } else {
+                    // Tool not found in pending_tools - this can happen during reconnection
+                    // or if the tool tracking had issues. Still send a completion event.
+                    tracing::warn!("Tool result received for unknown tool: {}", tool);
+                    
+                    // Try to find the tool in the database by tool_use_id
+                    if let Ok(Some(row)) = sqlx::query(
+                        "SELECT id, tool_name, parameters, created_at FROM tool_usages 
+                         WHERE message_id = $1 AND tool_use_id = $2 
+                         ORDER BY created_at DESC LIMIT 1"
+                    )
+                    .bind(message_id.to_string())
+                    .bind(&tool)
+                    .fetch_optional(db_pool)
+                    .await {
+                        let tool_usage_id: uuid::Uuid = row.get("id");
+                        let tool_name: String = row.get("tool_name");
+                        let created_at: chrono::DateTime<Utc> = row.get("created_at");
+                        let execution_time = Utc::now().signed_duration_since(created_at).num_milliseconds();
+                        
+                        // Update the tool usage with the result
+                        if let Err(e) = sqlx::query(
+                            "UPDATE tool_usages SET output = $1, execution_time_ms = $2 WHERE id = $3"
+                        )
+                        .bind(&result)
+                        .bind(execution_time)
+                        .bind(tool_usage_id)
+                        .execute(db_pool)
+                        .await {
+                            tracing::error!("Failed to update tool usage record: {}", e);
+                        }
+
+                        // Invalidate cache so next fetch gets updated tool usages from database
+                        if let Err(e) = state.invalidate_conversation_cache(conversation_id).await {
+                            tracing::warn!("Failed to invalidate conversation cache: {}", e);
+                        }
+
+                        // Send ToolComplete event
+                        broadcast_to_subscribers(
+                            project_id,
+                            conversation_id,
+                            ServerMessage::ToolComplete {
+                                tool: tool_name.clone(),
+                                tool_usage_id: tool_usage_id.to_string(),
+                                execution_time_ms: execution_time,
+                                output: Some(result.clone()),
+                                conversation_id: conversation_id.to_string(),
+                            },
+                        )
+                        .await;
+                        
+                        tracing::info!("Sent completion for recovered tool: {} ({})", tool_name, tool_usage_id);
+                    }
+                }
+            }
+
+            ClaudeMessage::Result { result } => {
+                tracing::info!("Processing Result message: {} chars", result.len());
+                tracing::debug!(
+                    "Result content preview: {}",
+                    result.chars().take(100).collect::<String>()
+                );
+
+                let actual_content = readable_result(result);
+
+                // This is the actual assistant response content
+                assistant_content.push_str(&actual_content);
+                broadcast_to_subscribers(
+                    project_id,
+                    conversation_id,
+                    ServerMessage::Content {
+                        content: actual_content,
+                        conversation_id: conversation_id.to_string(),
+                    },
+                )
+                .await;
+            }
+
+            ClaudeMessage::Error { error } => {
+                // Remove stream from active streams on error
+                {
+                    let mut streams = active_claude_streams.write().await;
+                    streams.remove(conversation_id);
+                }
+                
+                broadcast_to_subscribers(
+                    project_id,
+                    conversation_id,
+                    ServerMessage::Error {
+                        error,
+                        conversation_id: conversation_id.to_string(),
+                    },
+                )
+                .await;
+                return Err("Claude SDK error".into());
+            }
+
+            _ => continue,
+        }
+    }
+
+    Ok(StreamOutcome {
+        assistant_content,
+        progress_content,
+        tool_usages,
+    })
+}
+
+/// Readable text for a Result event; JSON results are unwrapped to their text
+/// content where possible
+fn readable_result(result: String) -> String {
+    if result.starts_with('{') && result.ends_with('}') {
+        // Try to parse as JSON to extract readable content
+        if let Ok(json_value) =
+            serde_json::from_str::<serde_json::Value>(&result)
+        {
+            // For Claude messages, extract the actual text from nested structure
+            if let Some(message_obj) = json_value.get("message") {
+                if let Some(content_array) =
+                    message_obj.get("content").and_then(|v| v.as_array())
+                {
+                    if let Some(first_content) = content_array.first() {
+                        if let Some(text) =
+                            first_content.get("text").and_then(|v| v.as_str())
+                        {
+                            text.to_string()
+                        } else {
+                            serde_json::to_string_pretty(&json_value)
+                                .unwrap_or(result)
+                        }
+                    } else {
+                        serde_json::to_string_pretty(&json_value)
+                            .unwrap_or(result)
+                    }
+                } else {
+                    serde_json::to_string_pretty(&json_value).unwrap_or(result)
+                }
+            } else if let Some(content_text) =
+                json_value.get("content").and_then(|v| v.as_str())
+            {
+                // Fallback for simpler JSON structure
+                content_text.to_string()
+            } else {
+                // If no extractable content, pretty print the JSON
+                serde_json::to_string_pretty(&json_value).unwrap_or(result)
+            }
+        } else {
+            result
+        }
+    } else {
+        result
+    }
+}
+
+/// Tool usages as sent to clients: parameters and output are dropped except
+/// for tools whose output the frontend renders
+pub(super) fn display_tool_usages(tool_usages: &[ToolUsage]) -> Option<Vec<ToolUsage>> {
+    if tool_usages.is_empty() {
+        return None;
+    }
+    let filtered_tool_usages: Vec<ToolUsage> = tool_usages
+        .iter()
+        .map(|tu| {
+            // Don't filter MCP interaction tools, TodoWrite, and datasource_query - they need their output for rendering
+            let should_preserve = tu.tool_name.starts_with("mcp__interaction__") ||
+                tu.tool_name == "TodoWrite" ||
+                tu.tool_name == "datasource_query";
+            ToolUsage {
+                id: tu.id,
+                message_id: tu.message_id.clone(),
+                tool_name: tu.tool_name.clone(),
+                tool_use_id: tu.tool_use_id.clone(),
+                parameters: if should_preserve { tu.parameters.clone() } else { None },
+                output: if should_preserve { tu.output.clone() } else { None },
+                execution_time_ms: tu.execution_time_ms,
+                created_at: tu.created_at.clone(),
+            }
+        })
+        .collect();
+    Some(filtered_tool_usages)
+}
@@ -1,6 +1,7 @@
 use crate::core::tools::ToolApplicabilityChecker;
 use crate::models::tool_usage::ToolUsage;
 use crate::models::*;
+use crate::utils::datasource::common::redaction::redact_connection_config;
 use crate::utils::{get_app_state, AppError};
 use salvo::prelude::*;
 use sqlx::Row;
@@ -148,9 +149,12 @@ pub async fn get_conversation_context(
             source_type: row.try_get("source_type").map_err(|e| {
                 AppError::InternalServerError(format!("Failed to get source_type: {}", e))
             })?,
-            connection_config: row.try_get("connection_config").map_err(|e| {
-                AppError::InternalServerError(format!("Failed to get connection_config: {}", e))
-            })?,
+            connection_config: row
+                .try_get("connection_config")
+                .map(|config: serde_json::Value| redact_connection_config(&config))
+                .map_err(|e| {
+                    AppError::InternalServerError(format!("Failed to get connection_config: {}", e))
+                })?,
             schema_info: row.try_get("schema_info").ok(),
             preview_data: row.try_get("preview_data").ok(),
             table_list: row.try_get("table_list").ok(),
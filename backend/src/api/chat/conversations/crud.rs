@@ -1,6 +1,7 @@
 use super::types::{
     CreateConversationRequest, CreateFromMessageRequest, UpdateConversationRequest,
 };
+use crate::api::websocket::handlers::conversation::handle_restore_conversation;
 use crate::models::*;
 use crate::utils::middleware::{get_current_client_id, get_current_user_id, is_current_user_root};
 use crate::utils::{get_app_state, AppError};
@@ -43,9 +44,10 @@ pub async fn list_conversations(
                     c.updated_at,
                     c.is_title_manually_set,
                     c.created_by_user_id,
-                    c.visibility
+                    c.visibility,
+                    c.model
                  FROM conversations c
-                 WHERE c.project_id = $1
+                 WHERE c.project_id = $1 AND c.deleted_at IS NULL
                  ORDER BY c.created_at DESC
                  LIMIT 100",
             )
@@ -68,10 +70,12 @@ pub async fn list_conversations(
                     c.updated_at,
                     c.is_title_manually_set,
                     c.created_by_user_id,
-                    c.visibility
+                    c.visibility,
+                    c.model
                  FROM conversations c
                  JOIN project_members pm ON c.project_id = pm.project_id
                  WHERE c.project_id = $1
+                   AND c.deleted_at IS NULL
                    AND pm.user_id = $2
                    AND (c.visibility = 'public' OR c.created_by_user_id = $2 OR c.visibility IS NULL)
                  ORDER BY c.created_at DESC
@@ -98,8 +102,10 @@ pub async fn list_conversations(
                 c.updated_at,
                 c.is_title_manually_set,
                 c.created_by_user_id,
-                c.visibility
+                c.visibility,
+                c.model
              FROM conversations c
+             WHERE c.deleted_at IS NULL
              ORDER BY c.created_at DESC
              LIMIT 100",
         )
@@ -121,10 +127,12 @@ pub async fn list_conversations(
                 c.updated_at,
                 c.is_title_manually_set,
                 c.created_by_user_id,
-                c.visibility
+                c.visibility,
+                c.model
              FROM conversations c
              JOIN project_members pm ON c.project_id = pm.project_id
              WHERE pm.user_id = $1
+               AND c.deleted_at IS NULL
                AND (c.visibility = 'public' OR c.created_by_user_id = $1 OR c.visibility IS NULL)
              ORDER BY c.created_at DESC
              LIMIT 100",
@@ -167,6 +175,7 @@ pub async fn list_conversations(
             is_title_manually_set: row.try_get("is_title_manually_set").ok(),
             created_by_user_id,
             visibility,
+            model: row.try_get("model").ok().flatten(),
         });
     }
 
@@ -200,9 +209,10 @@ pub async fn get_conversation(
             c.updated_at,
             c.is_title_manually_set,
             c.created_by_user_id,
-            c.visibility
+            c.visibility,
+            c.model
          FROM conversations c
-         WHERE c.id = $1",
+         WHERE c.id = $1 AND c.deleted_at IS NULL",
     )
     .bind(&conversation_id)
     .fetch_optional(&state.db_pool)
@@ -237,6 +247,7 @@ pub async fn get_conversation(
         is_title_manually_set: conversation_row.try_get("is_title_manually_set").ok(),
         created_by_user_id,
         visibility,
+        model: conversation_row.try_get("model").ok().flatten(),
     };
 
     res.render(Json(conversation));
@@ -271,10 +282,16 @@ pub async fn create_conversation(
     // Get current user for created_by_user_id
     let user_id = get_current_user_id(depot)?;
 
+    // Models outside the allowed list are dropped, leaving the default in effect
+    let model = create_req
+        .model
+        .as_deref()
+        .and_then(|m| state.config.claude_models.validate(m));
+
     // Insert into database with default private visibility
     sqlx::query(
-        "INSERT INTO conversations (id, project_id, title, message_count, created_at, updated_at, is_title_manually_set, created_by_user_id, visibility)
-         VALUES ($1, $2, $3, $4, $5, $6, $7, $8, 'private')"
+        "INSERT INTO conversations (id, project_id, title, message_count, created_at, updated_at, is_title_manually_set, created_by_user_id, visibility, model)
+         VALUES ($1, $2, $3, $4, $5, $6, $7, $8, 'private', $9)"
     )
     .bind(&conversation_id)
     .bind(&create_req.project_id)
@@ -284,6 +301,7 @@ pub async fn create_conversation(
     .bind(now)
     .bind(is_manually_set)
     .bind(user_id)
+    .bind(&model)
     .execute(&state.db_pool)
     .await
     .map_err(|e| AppError::InternalServerError(format!("Database error: {}", e)))?;
@@ -298,6 +316,7 @@ pub async fn create_conversation(
         is_title_manually_set: Some(is_manually_set),
         created_by_user_id: Some(user_id),
         visibility: Some(ConversationVisibility::Private),
+        model,
     };
 
     res.render(Json(conversation));
@@ -494,9 +513,10 @@ pub async fn update_conversation(
             c.updated_at,
             c.is_title_manually_set,
             c.created_by_user_id,
-            c.visibility
+            c.visibility,
+            c.model
          FROM conversations c
-         WHERE c.id = $1",
+         WHERE c.id = $1 AND c.deleted_at IS NULL",
     )
     .bind(&conversation_id)
     .fetch_one(&state.db_pool)
@@ -527,6 +547,7 @@ pub async fn update_conversation(
         is_title_manually_set: updated.try_get("is_title_manually_set").ok(),
         created_by_user_id,
         visibility,
+        model: updated.try_get("model").ok().flatten(),
     };
 
     res.render(Json(conversation));
@@ -544,12 +565,13 @@ pub async fn delete_conversation(
         .param::<String>("conversation_id")
         .ok_or(AppError::BadRequest("Missing conversation_id".to_string()))?;
 
-    // Delete from database (messages will cascade delete)
-    sqlx::query("DELETE FROM conversations WHERE id = $1")
+    // Soft delete; it can be restored within the restore window and purged by an admin
+    sqlx::query("UPDATE conversations SET deleted_at = NOW() WHERE id = $1 AND deleted_at IS NULL")
         .bind(&conversation_id)
         .execute(&state.db_pool)
         .await
         .map_err(|e| AppError::InternalServerError(format!("Database error: {}", e)))?;
+    let _ = state.invalidate_conversation_cache(&conversation_id).await;
 
     res.render(Json(serde_json::json!({
         "success": true,
@@ -558,6 +580,26 @@ pub async fn delete_conversation(
     Ok(())
 }
 
+/// Undo a conversation delete made within the restore window
+#[handler]
+pub async fn restore_conversation(
+    req: &mut Request,
+    depot: &mut Depot,
+    res: &mut Response,
+) -> Result<(), AppError> {
+    let state = get_app_state(depot)?;
+    let client_id = get_current_client_id(depot)?;
+    let conversation_id = req
+        .param::<String>("conversation_id")
+        .ok_or(AppError::BadRequest("Missing conversation_id".to_string()))?;
+
+    let conversation =
+        handle_restore_conversation(&conversation_id, &client_id.to_string(), state).await?;
+
+    res.render(Json(conversation));
+    Ok(())
+}
+
 /// Toggle conversation visibility between private and public
 #[handler]
 pub async fn toggle_conversation_visibility(
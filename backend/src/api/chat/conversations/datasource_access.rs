@@ -0,0 +1,118 @@
+use super::types::UpdateDatasourceAccessRequest;
+use crate::utils::middleware::get_current_client_id;
+use crate::utils::{get_app_state, AppError};
+use salvo::prelude::*;
+use sqlx::Row;
+
+/// Get the datasources a conversation's model may use (null = all project datasources)
+#[handler]
+pub async fn get_datasource_access(
+    req: &mut Request,
+    depot: &mut Depot,
+    res: &mut Response,
+) -> Result<(), AppError> {
+    let state = get_app_state(depot)?;
+    let client_id = get_current_client_id(depot)?;
+    let conversation_id = req
+        .param::<String>("conversation_id")
+        .ok_or(AppError::BadRequest("Missing conversation_id".to_string()))?;
+
+    let row = sqlx::query(
+        "SELECT c.allowed_datasource_ids
+         FROM conversations c
+         JOIN projects p ON c.project_id = p.id
+         WHERE c.id = $1 AND p.client_id = $2",
+    )
+    .bind(&conversation_id)
+    .bind(client_id)
+    .fetch_optional(&state.db_pool)
+    .await
+    .map_err(|e| AppError::InternalServerError(format!("Database error: {}", e)))?
+    .ok_or(AppError::NotFound(format!(
+        "Conversation {} not found",
+        conversation_id
+    )))?;
+
+    let allowed: Option<serde_json::Value> = row.try_get("allowed_datasource_ids").ok().flatten();
+
+    res.render(Json(serde_json::json!({
+        "conversation_id": conversation_id,
+        "allowed_datasource_ids": allowed
+    })));
+    Ok(())
+}
+
+/// Restrict a conversation to a set of datasources, or clear the restriction with null
+#[handler]
+pub async fn update_datasource_access(
+    req: &mut Request,
+    depot: &mut Depot,
+    res: &mut Response,
+) -> Result<(), AppError> {
+    let state = get_app_state(depot)?;
+    let client_id = get_current_client_id(depot)?;
+    let conversation_id = req
+        .param::<String>("conversation_id")
+        .ok_or(AppError::BadRequest("Missing conversation_id".to_string()))?;
+
+    let update_req: UpdateDatasourceAccessRequest = req
+        .parse_json()
+        .await
+        .map_err(|_| AppError::BadRequest("Invalid request body".to_string()))?;
+
+    let project_id: String = sqlx::query_scalar(
+        "SELECT c.project_id
+         FROM conversations c
+         JOIN projects p ON c.project_id = p.id
+         WHERE c.id = $1 AND p.client_id = $2",
+    )
+    .bind(&conversation_id)
+    .bind(client_id)
+    .fetch_optional(&state.db_pool)
+    .await
+    .map_err(|e| AppError::InternalServerError(format!("Database error: {}", e)))?
+    .ok_or(AppError::NotFound(format!(
+        "Conversation {} not found",
+        conversation_id
+    )))?;
+
+    // Only datasources of the conversation's own project can be allowed
+    if let Some(ref ids) = update_req.allowed_datasource_ids {
+        let known: Vec<String> = sqlx::query_scalar(
+            "SELECT id FROM data_sources WHERE project_id = $1 AND id = ANY($2) AND deleted_at IS NULL",
+        )
+        .bind(&project_id)
+        .bind(ids)
+        .fetch_all(&state.db_pool)
+        .await
+        .map_err(|e| AppError::InternalServerError(format!("Database error: {}", e)))?;
+
+        let unknown: Vec<&String> = ids.iter().filter(|id| !known.contains(id)).collect();
+        if !unknown.is_empty() {
+            return Err(AppError::BadRequest(format!(
+                "Datasources not found in project: {:?}",
+                unknown
+            )));
+        }
+    }
+
+    let allowed = update_req
+        .allowed_datasource_ids
+        .as_ref()
+        .map(|ids| serde_json::json!(ids));
+
+    sqlx::query(
+        "UPDATE conversations SET allowed_datasource_ids = $1, updated_at = NOW() WHERE id = $2",
+    )
+    .bind(&allowed)
+    .bind(&conversation_id)
+    .execute(&state.db_pool)
+    .await
+    .map_err(|e| AppError::InternalServerError(format!("Database error: {}", e)))?;
+
+    res.render(Json(serde_json::json!({
+        "conversation_id": conversation_id,
+        "allowed_datasource_ids": allowed
+    })));
+    Ok(())
+}
@@ -1,7 +1,9 @@
 pub mod context;
 pub mod crud;
+pub mod datasource_access;
 pub mod messages;
 pub mod routes;
+pub mod stop;
 pub mod types;
 
 // pub use routes::conversation_routes; // Unused
@@ -1,7 +1,10 @@
 use salvo::prelude::*;
-use super::crud::{list_conversations, get_conversation, create_conversation, update_conversation, delete_conversation, toggle_conversation_visibility};
+use super::crud::{list_conversations, get_conversation, create_conversation, update_conversation, delete_conversation, restore_conversation, toggle_conversation_visibility};
+use super::datasource_access::{get_datasource_access, update_datasource_access};
+use super::stop::stop_conversation;
 use crate::utils::middleware::auth::auth_required;
 use crate::utils::middleware::client_scoped;
+use crate::utils::rate_limit::prompt_rate_limit;
 
 pub fn conversation_routes() -> Router {
     Router::new()
@@ -9,11 +12,18 @@ pub fn conversation_routes() -> Router {
         .hoop(client_scoped)
         .push(Router::with_path("/conversations")
             .get(list_conversations)
-            .post(create_conversation))
+            .push(Router::new().hoop(prompt_rate_limit).post(create_conversation)))
         .push(Router::with_path("/conversations/{conversation_id}")
             .get(get_conversation)
             .put(update_conversation)
             .delete(delete_conversation))
+        .push(Router::with_path("/conversations/{conversation_id}/restore")
+            .post(restore_conversation))
+        .push(Router::with_path("/conversations/{conversation_id}/stop")
+            .post(stop_conversation))
         .push(Router::with_path("/conversations/{conversation_id}/visibility")
             .patch(toggle_conversation_visibility))
+        .push(Router::with_path("/conversations/{conversation_id}/datasource-access")
+            .get(get_datasource_access)
+            .put(update_datasource_access))
 }
\ No newline at end of file
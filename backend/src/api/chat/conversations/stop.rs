@@ -0,0 +1,46 @@
+use crate::api::websocket::handlers::streaming::handle_stop_streaming;
+use crate::utils::middleware::get_current_client_id;
+use crate::utils::{get_app_state, AppError};
+use salvo::prelude::*;
+
+/// Stop the response being generated in a conversation, the HTTP counterpart
+/// of the WebSocket `stop_streaming` message. Safe to repeat: an idle
+/// conversation answers with `was_streaming: false`.
+#[handler]
+pub async fn stop_conversation(
+    req: &mut Request,
+    depot: &mut Depot,
+    res: &mut Response,
+) -> Result<(), AppError> {
+    let state = get_app_state(depot)?;
+    let client_id = get_current_client_id(depot)?;
+    let conversation_id = req
+        .param::<String>("conversation_id")
+        .ok_or(AppError::BadRequest("Missing conversation_id".to_string()))?;
+
+    let exists: Option<i32> = sqlx::query_scalar(
+        "SELECT 1
+         FROM conversations c
+         JOIN projects p ON c.project_id = p.id
+         WHERE c.id = $1 AND p.client_id = $2",
+    )
+    .bind(&conversation_id)
+    .bind(client_id)
+    .fetch_optional(&state.db_pool)
+    .await
+    .map_err(|e| AppError::InternalServerError(format!("Database error: {}", e)))?;
+    if exists.is_none() {
+        return Err(AppError::NotFound(format!(
+            "Conversation {} not found",
+            conversation_id
+        )));
+    }
+
+    let was_streaming = handle_stop_streaming(conversation_id.clone(), state).await;
+
+    res.render(Json(serde_json::json!({
+        "conversation_id": conversation_id,
+        "was_streaming": was_streaming
+    })));
+    Ok(())
+}
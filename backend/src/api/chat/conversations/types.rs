@@ -5,6 +5,8 @@ use serde::{Deserialize, Serialize};
 pub struct CreateConversationRequest {
     pub project_id: String,
     pub title: Option<String>,
+    /// Claude model for the conversation; ignored unless it is in the allowed list
+    pub model: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -29,6 +31,11 @@ pub struct UpdateConversationRequest {
     pub is_title_manually_set: Option<bool>,
 }
 
+#[derive(Debug, Serialize, Deserialize)]
+pub struct UpdateDatasourceAccessRequest {
+    pub allowed_datasource_ids: Option<Vec<String>>, // None clears the restriction
+}
+
 #[derive(Debug, Serialize)]
 #[allow(dead_code)]
 pub struct MessageResponse {
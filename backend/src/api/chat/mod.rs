@@ -6,8 +6,18 @@ pub mod tool_usages;
 
 use salvo::prelude::*;
 
+use crate::utils::middleware::auth::auth_required;
+use crate::utils::middleware::client_scoped;
+use crate::utils::query_limit::get_query_limit;
+
 pub fn chat_routes() -> Router {
     Router::new()
         .push(conversations::routes::conversation_routes())
         .push(tool_usages::tool_usage_routes())
+        .push(
+            Router::with_path("/chat/query-limit")
+                .hoop(auth_required)
+                .hoop(client_scoped)
+                .get(get_query_limit),
+        )
 }
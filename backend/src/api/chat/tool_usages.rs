@@ -2,9 +2,15 @@ use crate::models::tool_usage::ToolUsage;
 use crate::utils::{get_app_state, AppError};
 use crate::utils::middleware::auth::auth_required;
 use crate::utils::middleware::client_scoped;
+use crate::utils::middleware::{get_current_client_id, get_current_user_id, is_current_user_root};
+use salvo::http::HeaderValue;
 use salvo::prelude::*;
+use sqlx::Row;
 use uuid::Uuid;
 
+/// Output longer than this is cut down in the export's `output_summary`
+const OUTPUT_SUMMARY_CHARS: usize = 500;
+
 #[handler]
 pub async fn get_tool_usage(
     req: &mut Request,
@@ -67,10 +73,149 @@ pub async fn get_tool_usage(
     Ok(())
 }
 
+/// Export every tool call made in a conversation, oldest first, as JSON or CSV (`?format=csv`)
+#[handler]
+pub async fn export_conversation_tool_usages(
+    req: &mut Request,
+    depot: &mut Depot,
+    res: &mut Response,
+) -> Result<(), AppError> {
+    let state = get_app_state(depot)?;
+    let client_id = get_current_client_id(depot)?;
+    let user_id = get_current_user_id(depot)?;
+    let is_root = is_current_user_root(depot);
+    let conversation_id = req
+        .param::<String>("conversation_id")
+        .ok_or(AppError::BadRequest("Missing conversation_id".to_string()))?;
+    let format = req.query::<String>("format").unwrap_or_else(|| "json".to_string());
+
+    // Same visibility rule as the conversation list: private conversations belong to their creator
+    let visible = sqlx::query(
+        "SELECT 1
+         FROM conversations c
+         JOIN projects p ON c.project_id = p.id
+         WHERE c.id = $1
+           AND p.client_id = $2
+           AND ($3 OR c.visibility = 'public' OR c.visibility IS NULL OR c.created_by_user_id = $4)",
+    )
+    .bind(&conversation_id)
+    .bind(client_id)
+    .bind(is_root)
+    .bind(user_id)
+    .fetch_optional(&state.db_pool)
+    .await
+    .map_err(|e| AppError::InternalServerError(format!("Database error: {}", e)))?;
+
+    if visible.is_none() {
+        return Err(AppError::NotFound(format!(
+            "Conversation {} not found",
+            conversation_id
+        )));
+    }
+
+    let rows = sqlx::query(
+        "SELECT tu.id, tu.message_id, tu.tool_name, tu.tool_use_id, tu.parameters, tu.output,
+                tu.execution_time_ms, tu.created_at
+         FROM tool_usages tu
+         JOIN messages m ON tu.message_id = m.id
+         WHERE m.conversation_id = $1
+         ORDER BY tu.created_at ASC, m.created_at ASC",
+    )
+    .bind(&conversation_id)
+    .fetch_all(&state.db_pool)
+    .await
+    .map_err(|e| AppError::InternalServerError(format!("Database error: {}", e)))?;
+
+    let entries: Vec<serde_json::Value> = rows
+        .iter()
+        .map(|row| {
+            let output: Option<serde_json::Value> = row.get("output");
+            let created_at: chrono::DateTime<chrono::Utc> = row.get("created_at");
+            serde_json::json!({
+                "id": row.get::<Uuid, _>("id"),
+                "message_id": row.get::<String, _>("message_id"),
+                "tool_name": row.get::<String, _>("tool_name"),
+                "tool_use_id": row.get::<Option<String>, _>("tool_use_id"),
+                "parameters": row.get::<Option<serde_json::Value>, _>("parameters"),
+                "output_summary": output.as_ref().map(summarize_output),
+                "execution_time_ms": row.get::<Option<i64>, _>("execution_time_ms"),
+                "created_at": created_at.to_rfc3339(),
+            })
+        })
+        .collect();
+
+    if format.eq_ignore_ascii_case("csv") {
+        let csv = tool_usages_to_csv(&entries)
+            .map_err(|e| AppError::InternalServerError(format!("Failed to build CSV: {}", e)))?;
+        res.headers_mut().insert(
+            "Content-Type",
+            HeaderValue::from_static("text/csv; charset=utf-8"),
+        );
+        let disposition = format!("attachment; filename=\"tool-usages-{}.csv\"", conversation_id);
+        if let Ok(value) = HeaderValue::from_str(&disposition) {
+            res.headers_mut().insert("Content-Disposition", value);
+        }
+        res.write_body(csv)
+            .map_err(|e| AppError::InternalServerError(format!("Failed to write response: {}", e)))?;
+        return Ok(());
+    }
+
+    res.render(Json(serde_json::json!({
+        "conversation_id": conversation_id,
+        "count": entries.len(),
+        "tool_usages": entries
+    })));
+    Ok(())
+}
+
+/// Compact, length-capped rendering of a tool's output
+fn summarize_output(output: &serde_json::Value) -> String {
+    let text = match output {
+        serde_json::Value::String(s) => s.clone(),
+        other => other.to_string(),
+    };
+    if text.chars().count() <= OUTPUT_SUMMARY_CHARS {
+        return text;
+    }
+    let truncated: String = text.chars().take(OUTPUT_SUMMARY_CHARS).collect();
+    format!("{}… ({} chars total)", truncated, text.chars().count())
+}
+
+fn tool_usages_to_csv(
+    entries: &[serde_json::Value],
+) -> Result<Vec<u8>, Box<dyn std::error::Error + Send + Sync>> {
+    let columns = [
+        "created_at",
+        "tool_name",
+        "execution_time_ms",
+        "parameters",
+        "output_summary",
+        "message_id",
+        "tool_use_id",
+        "id",
+    ];
+    let mut writer = csv::Writer::from_writer(Vec::new());
+    writer.write_record(columns)?;
+    for entry in entries {
+        let record: Vec<String> = columns
+            .iter()
+            .map(|column| match entry.get(*column) {
+                None | Some(serde_json::Value::Null) => String::new(),
+                Some(serde_json::Value::String(s)) => s.clone(),
+                Some(other) => other.to_string(),
+            })
+            .collect();
+        writer.write_record(&record)?;
+    }
+    Ok(writer.into_inner()?)
+}
+
 pub fn tool_usage_routes() -> Router {
     Router::new()
         .hoop(auth_required)
         .hoop(client_scoped)
         .push(Router::with_path("/tool-usages/{id}")
             .get(get_tool_usage))
+        .push(Router::with_path("/conversations/{conversation_id}/tool-usages")
+            .get(export_conversation_tool_usages))
 }
\ No newline at end of file
@@ -81,4 +81,35 @@ pub async fn broadcast_activity_to_project(
             }
         }
     }
-}
\ No newline at end of file
+}
+
+/// Send a message to every connection subscribed to a project, whichever
+/// conversation it has open
+pub async fn broadcast_to_project(project_id: &str, message: ServerMessage) {
+    let connections = WS_CONNECTIONS.read().await;
+
+    for (connection_id, conn) in connections.iter() {
+        if conn.project_id.as_deref() == Some(project_id) && conn.sender.send(message.clone()).is_err() {
+            tracing::warn!(
+                "Failed to send project message to connection {} (user {})",
+                connection_id,
+                conn.user_id
+            );
+        }
+    }
+}
+
+/// Send an upload's extraction progress to every connection following it
+pub async fn broadcast_upload_progress(upload_id: &str, message: ServerMessage) {
+    let connections = WS_CONNECTIONS.read().await;
+
+    for (connection_id, conn) in connections.iter() {
+        if conn.upload_ids.contains(upload_id) && conn.sender.send(message.clone()).is_err() {
+            tracing::warn!(
+                "Failed to send upload progress to connection {} (user {})",
+                connection_id,
+                conn.user_id
+            );
+        }
+    }
+}
@@ -1,12 +1,21 @@
+use crate::core::mcp::handlers::ask_user_choice::interaction_response_message;
 use crate::utils::AppState;
+use chrono::{DateTime, Utc};
 use sqlx::Row;
 
+/// How long a deleted conversation can still be restored
+pub const CONVERSATION_RESTORE_WINDOW_DAYS: i64 = 30;
+
+/// Whether a conversation deleted at `deleted_at` is still within the restore window
+pub fn is_restorable(deleted_at: DateTime<Utc>, now: DateTime<Utc>) -> bool {
+    now - deleted_at <= chrono::Duration::days(CONVERSATION_RESTORE_WINDOW_DAYS)
+}
+
 // WebSocket conversation management handlers
 pub async fn handle_create_conversation(
     project_id: &str,
     title: Option<String>,
-    _first_message: Option<String>, // Unused - handled in websocket mod.rs
-    _file_ids: Option<Vec<String>>, // Unused - handled in websocket mod.rs
+    model: Option<String>,
     client_id_str: &str,
     user_id: &str,
     state: &AppState,
@@ -39,19 +48,23 @@ pub async fn handle_create_conversation(
     let user_uuid = uuid::Uuid::parse_str(user_id)
         .map_err(|_| crate::utils::AppError::BadRequest("Invalid user ID".to_string()))?;
 
+    // Models outside the allowed list are dropped, leaving the default in effect
+    let model = model.as_deref().and_then(|m| state.config.claude_models.validate(m));
+
     // Insert new conversation
-    sqlx::query!(
+    sqlx::query(
         r#"
-        INSERT INTO conversations (id, project_id, title, created_at, updated_at, is_title_manually_set, created_by_user_id, visibility)
-        VALUES ($1, $2, $3, NOW(), NOW(), $4, $5, $6)
+        INSERT INTO conversations (id, project_id, title, created_at, updated_at, is_title_manually_set, created_by_user_id, visibility, model)
+        VALUES ($1, $2, $3, NOW(), NOW(), $4, $5, $6, $7)
         "#,
-        conversation_id,
-        project_id,
-        title,
-        title.is_some(), // Set manually if title was provided
-        user_uuid,
-        "private" // Set as private by default
     )
+    .bind(&conversation_id)
+    .bind(project_id)
+    .bind(&title)
+    .bind(title.is_some()) // Set manually if title was provided
+    .bind(user_uuid)
+    .bind("private") // Set as private by default
+    .bind(&model)
     .execute(&state.db_pool)
     .await
     .map_err(|e| crate::utils::AppError::InternalServerError(format!("Failed to create conversation: {}", e)))?;
@@ -72,6 +85,7 @@ pub async fn handle_create_conversation(
         is_title_manually_set: Some(is_title_set),
         created_by_user_id: Some(user_uuid),
         visibility: Some(crate::models::ConversationVisibility::Private),
+        model,
     })
 }
 
@@ -96,10 +110,11 @@ pub async fn handle_list_conversations(
             ) AS message_count,
             c.created_at, 
             c.updated_at, 
-            c.is_title_manually_set 
+            c.is_title_manually_set,
+            c.model
          FROM conversations c
          JOIN projects p ON c.project_id = p.id
-         WHERE c.project_id = $1 AND p.client_id = $2
+         WHERE c.project_id = $1 AND p.client_id = $2 AND c.deleted_at IS NULL
          ORDER BY c.created_at DESC 
          LIMIT 100",
     )
@@ -143,6 +158,7 @@ pub async fn handle_list_conversations(
             created_by_user_id: None,
             visibility: Some(crate::models::ConversationVisibility::Private),
             is_title_manually_set: row.try_get("is_title_manually_set").ok(),
+            model: row.try_get("model").ok().flatten(),
         });
     }
 
@@ -170,10 +186,11 @@ pub async fn handle_get_conversation(
             ) AS message_count,
             c.created_at, 
             c.updated_at, 
-            c.is_title_manually_set 
+            c.is_title_manually_set,
+            c.model
          FROM conversations c
          JOIN projects p ON c.project_id = p.id
-         WHERE c.id = $1 AND p.client_id = $2",
+         WHERE c.id = $1 AND p.client_id = $2 AND c.deleted_at IS NULL",
     )
     .bind(conversation_id)
     .bind(client_id)
@@ -208,6 +225,7 @@ pub async fn handle_get_conversation(
         is_title_manually_set: conversation_row.try_get("is_title_manually_set").ok(),
         created_by_user_id: None,
         visibility: Some(crate::models::ConversationVisibility::Private),
+        model: conversation_row.try_get("model").ok().flatten(),
     })
 }
 
@@ -229,8 +247,8 @@ pub async fn handle_update_conversation(
                 UPDATE conversations c
                 SET title = $1, is_title_manually_set = true, updated_at = $2
                 FROM projects p
-                WHERE c.id = $3 AND c.project_id = p.id AND p.client_id = $4
-                RETURNING c.id, c.project_id, c.title, c.created_at, c.updated_at, c.is_title_manually_set
+                WHERE c.id = $3 AND c.project_id = p.id AND p.client_id = $4 AND c.deleted_at IS NULL
+                RETURNING c.id, c.project_id, c.title, c.created_at, c.updated_at, c.is_title_manually_set, c.model
             )
             SELECT
                 uc.id,
@@ -244,7 +262,8 @@ pub async fn handle_update_conversation(
                 ) AS message_count,
                 uc.created_at,
                 uc.updated_at,
-                uc.is_title_manually_set
+                uc.is_title_manually_set,
+                uc.model
             FROM updated_conv uc",
         )
         .bind(&title)
@@ -260,8 +279,8 @@ pub async fn handle_update_conversation(
                 UPDATE conversations c
                 SET updated_at = $1
                 FROM projects p
-                WHERE c.id = $2 AND c.project_id = p.id AND p.client_id = $3
-                RETURNING c.id, c.project_id, c.title, c.created_at, c.updated_at, c.is_title_manually_set
+                WHERE c.id = $2 AND c.project_id = p.id AND p.client_id = $3 AND c.deleted_at IS NULL
+                RETURNING c.id, c.project_id, c.title, c.created_at, c.updated_at, c.is_title_manually_set, c.model
             )
             SELECT
                 uc.id,
@@ -275,7 +294,8 @@ pub async fn handle_update_conversation(
                 ) AS message_count,
                 uc.created_at,
                 uc.updated_at,
-                uc.is_title_manually_set
+                uc.is_title_manually_set,
+                uc.model
             FROM updated_conv uc",
         )
         .bind(now)
@@ -309,6 +329,7 @@ pub async fn handle_update_conversation(
         is_title_manually_set: updated.try_get("is_title_manually_set").ok(),
         created_by_user_id: None,
         visibility: Some(crate::models::ConversationVisibility::Private),
+        model: updated.try_get("model").ok().flatten(),
     })
 }
 
@@ -319,11 +340,14 @@ pub async fn handle_delete_conversation(
 ) -> Result<(), crate::utils::AppError> {
     let client_id = uuid::Uuid::parse_str(client_id_str)
         .map_err(|_| crate::utils::AppError::BadRequest("Invalid client ID".to_string()))?;
-    // Delete from database with authorization check (messages will cascade delete)
+    // Soft delete with authorization check; the conversation and its messages
+    // stay restorable until the restore window passes
     let result = sqlx::query(
-        "DELETE FROM conversations 
-         USING projects p 
-         WHERE conversations.id = $1 AND conversations.project_id = p.id AND p.client_id = $2",
+        "UPDATE conversations
+         SET deleted_at = NOW()
+         FROM projects p
+         WHERE conversations.id = $1 AND conversations.project_id = p.id AND p.client_id = $2
+           AND conversations.deleted_at IS NULL",
     )
     .bind(conversation_id)
     .bind(client_id)
@@ -342,6 +366,51 @@ pub async fn handle_delete_conversation(
     Ok(())
 }
 
+/// Undo a soft delete made within the restore window
+pub async fn handle_restore_conversation(
+    conversation_id: &str,
+    client_id_str: &str,
+    state: &AppState,
+) -> Result<crate::models::Conversation, crate::utils::AppError> {
+    let client_id = uuid::Uuid::parse_str(client_id_str)
+        .map_err(|_| crate::utils::AppError::BadRequest("Invalid client ID".to_string()))?;
+
+    let deleted_at: Option<DateTime<Utc>> = sqlx::query_scalar(
+        "SELECT c.deleted_at
+         FROM conversations c
+         JOIN projects p ON c.project_id = p.id
+         WHERE c.id = $1 AND p.client_id = $2",
+    )
+    .bind(conversation_id)
+    .bind(client_id)
+    .fetch_optional(&state.db_pool)
+    .await
+    .map_err(|e| crate::utils::AppError::InternalServerError(format!("Database error: {}", e)))?
+    .ok_or(crate::utils::AppError::NotFound(format!(
+        "Conversation {} not found or access denied",
+        conversation_id
+    )))?;
+
+    let deleted_at = deleted_at.ok_or(crate::utils::AppError::BadRequest(format!(
+        "Conversation {} is not deleted",
+        conversation_id
+    )))?;
+    if !is_restorable(deleted_at, Utc::now()) {
+        return Err(crate::utils::AppError::Conflict(format!(
+            "Conversation {} was deleted more than {} days ago and can no longer be restored",
+            conversation_id, CONVERSATION_RESTORE_WINDOW_DAYS
+        )));
+    }
+
+    sqlx::query("UPDATE conversations SET deleted_at = NULL, updated_at = NOW() WHERE id = $1")
+        .bind(conversation_id)
+        .execute(&state.db_pool)
+        .await
+        .map_err(|e| crate::utils::AppError::InternalServerError(format!("Database error: {}", e)))?;
+
+    handle_get_conversation(conversation_id, client_id_str, state).await
+}
+
 pub async fn handle_get_conversation_messages(
     conversation_id: &str,
     client_id_str: &str,
@@ -355,7 +424,7 @@ pub async fn handle_get_conversation_messages(
         "SELECT c.id
          FROM conversations c
          JOIN projects p ON c.project_id = p.id
-         WHERE c.id = $1 AND p.client_id = $2"
+         WHERE c.id = $1 AND p.client_id = $2 AND c.deleted_at IS NULL"
     )
     .bind(conversation_id)
     .bind(client_id)
@@ -554,11 +623,9 @@ pub async fn store_ask_user_response(
         crate::utils::AppError::InternalServerError(format!("Failed to serialize response: {}", e))
     })?;
 
-    // Store as a system message with the interaction response
-    let message_content = format!(
-        "User response to interaction {}:\n{}",
-        interaction_id, response_json
-    );
+    // Store as a system message with the interaction response; ask_user_choice
+    // waits for this message
+    let message_content = interaction_response_message(interaction_id, &response_json);
 
     sqlx::query!(
         r#"
@@ -574,4 +641,20 @@ pub async fn store_ask_user_response(
     .map_err(|e| crate::utils::AppError::InternalServerError(format!("Database error: {}", e)))?;
 
     Ok(())
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn restore_window_covers_recent_deletes_only() {
+        let now = Utc::now();
+        assert!(is_restorable(now, now));
+        assert!(is_restorable(now - chrono::Duration::days(CONVERSATION_RESTORE_WINDOW_DAYS), now));
+        assert!(!is_restorable(
+            now - chrono::Duration::days(CONVERSATION_RESTORE_WINDOW_DAYS) - chrono::Duration::seconds(1),
+            now
+        ));
+    }
+}
@@ -0,0 +1,179 @@
+use serde_json::Value;
+use sqlx::PgPool;
+use tokio::sync::mpsc;
+
+use crate::api::projects::datasources::crud::insert_datasource;
+use crate::api::projects::datasources::types::CreateDatasourceRequest;
+use crate::api::websocket::types::ServerMessage;
+
+/// Create a datasource for a `CreateDatasource` message, with the same checks
+/// as the HTTP endpoint, and reply with the result
+#[allow(clippy::too_many_arguments)]
+pub async fn handle_create_datasource(
+    project_id: String,
+    name: String,
+    source_type: String,
+    config: Value,
+    user_id: &str,
+    role: &Option<String>,
+    sender: &mpsc::UnboundedSender<ServerMessage>,
+    db_pool: &PgPool,
+) {
+    let Ok(user_uuid) = uuid::Uuid::parse_str(user_id) else {
+        let _ = sender.send(ServerMessage::Error {
+            error: "Not authenticated".to_string(),
+            conversation_id: "".to_string(),
+        });
+        return;
+    };
+
+    let request = CreateDatasourceRequest { name, source_type, config };
+    let is_root = role.as_deref() == Some("root");
+    match insert_datasource(db_pool, user_uuid, is_root, &project_id, request).await {
+        Ok(datasource) => {
+            tracing::info!("User {} created datasource {} in project {}", user_id, datasource.id, project_id);
+            let _ = sender.send(ServerMessage::DatasourceCreated { datasource });
+        }
+        Err(e) => {
+            tracing::warn!("Failed to create datasource in project {}: {}", project_id, e);
+            let _ = sender.send(ServerMessage::Error {
+                error: format!("Failed to create datasource: {}", e),
+                conversation_id: "".to_string(),
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::websocket::types::ClientMessage;
+
+    async fn reply_to(message: &str, user_id: &str) -> ServerMessage {
+        // Invalid requests are answered before the database is touched
+        let db_pool = PgPool::connect_lazy("postgres://localhost/unused").unwrap();
+        reply_from(&db_pool, message, user_id).await
+    }
+
+    async fn reply_from(db_pool: &PgPool, message: &str, user_id: &str) -> ServerMessage {
+        let (tx, mut rx) = mpsc::unbounded_channel();
+
+        let Ok(ClientMessage::CreateDatasource { project_id, name, source_type, config }) =
+            serde_json::from_str::<ClientMessage>(message)
+        else {
+            panic!("not a create_datasource message: {}", message);
+        };
+        handle_create_datasource(project_id, name, source_type, config, user_id, &None, &tx, db_pool).await;
+        rx.try_recv().expect("a reply")
+    }
+
+    #[tokio::test]
+    async fn invalid_create_requests_are_answered_with_an_error() {
+        let user_id = uuid::Uuid::new_v4().to_string();
+        let message = r#"{
+            "type": "create_datasource",
+            "project_id": "proj-1",
+            "name": "Warehouse",
+            "source_type": "snowflake",
+            "config": { "host": "db.internal" }
+        }"#;
+        match reply_to(message, &user_id).await {
+            ServerMessage::Error { error, .. } => {
+                assert!(error.contains("Invalid source_type 'snowflake'"), "{}", error);
+            }
+            other => panic!("expected an error, got {:?}", other),
+        }
+
+        let valid = message.replace("snowflake", "postgres");
+        match reply_to(&valid, "anonymous").await {
+            ServerMessage::Error { error, .. } => assert_eq!(error, "Not authenticated"),
+            other => panic!("expected an error, got {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    #[ignore = "needs a migrated Clay Studio database in TEST_DATABASE_URL"]
+    async fn project_owner_creates_a_datasource() {
+        let url = std::env::var("TEST_DATABASE_URL").expect("Set TEST_DATABASE_URL");
+        let db_pool = PgPool::connect(&url).await.unwrap();
+
+        let client_id = uuid::Uuid::new_v4();
+        let owner_id = uuid::Uuid::new_v4();
+        let project_id = format!("ws-datasource-{}", client_id);
+        let now = chrono::Utc::now();
+        sqlx::query(
+            "INSERT INTO clients (id, name, description, status, install_path, config, created_at, updated_at)
+             VALUES ($1, 'ws-datasource-test', NULL, 'active', '', '{}'::jsonb, $2, $2)",
+        )
+        .bind(client_id)
+        .bind(now)
+        .execute(&db_pool)
+        .await
+        .unwrap();
+        sqlx::query(
+            "INSERT INTO users (id, client_id, username, password, role, created_at, updated_at)
+             VALUES ($1, $2, $3, 'unused', 'user', $4, $4)",
+        )
+        .bind(owner_id)
+        .bind(client_id)
+        .bind(format!("ws-owner-{}", owner_id))
+        .bind(now)
+        .execute(&db_pool)
+        .await
+        .unwrap();
+        sqlx::query("INSERT INTO projects (id, name, client_id, user_id) VALUES ($1, 'Sales', $2, $3)")
+            .bind(&project_id)
+            .bind(client_id)
+            .bind(owner_id)
+            .execute(&db_pool)
+            .await
+            .unwrap();
+
+        let message = serde_json::json!({
+            "type": "create_datasource",
+            "project_id": project_id,
+            "name": "Warehouse",
+            "source_type": "postgres",
+            "config": { "host": "db.internal", "port": 5432, "user": "u", "password": "hunter2" }
+        })
+        .to_string();
+        let created = reply_from(&db_pool, &message, &owner_id.to_string()).await;
+        let stranger = reply_from(&db_pool, &message, &uuid::Uuid::new_v4().to_string()).await;
+        let stored: Option<(String, Value)> = sqlx::query_as(
+            "SELECT source_type, connection_config FROM data_sources WHERE project_id = $1 AND name = 'Warehouse'",
+        )
+        .bind(&project_id)
+        .fetch_optional(&db_pool)
+        .await
+        .unwrap();
+
+        for cleanup in [
+            "DELETE FROM data_sources WHERE project_id = $1",
+            "DELETE FROM projects WHERE id = $1",
+        ] {
+            sqlx::query(cleanup).bind(&project_id).execute(&db_pool).await.unwrap();
+        }
+        sqlx::query("DELETE FROM users WHERE id = $1").bind(owner_id).execute(&db_pool).await.unwrap();
+        sqlx::query("DELETE FROM clients WHERE id = $1").bind(client_id).execute(&db_pool).await.unwrap();
+
+        match created {
+            ServerMessage::DatasourceCreated { datasource } => {
+                assert_eq!(datasource.project_id, project_id);
+                assert_eq!(datasource.name, "Warehouse");
+                assert_eq!(datasource.source_type, "postgresql");
+                // The reply never echoes the password back
+                assert!(!datasource.config.to_string().contains("hunter2"));
+            }
+            other => panic!("expected DatasourceCreated, got {:?}", other),
+        }
+        let (source_type, config) = stored.expect("the datasource row");
+        assert_eq!(source_type, "postgresql");
+        assert_eq!(config["password"], "hunter2");
+
+        // Someone else's project is reported as missing, not created in
+        match stranger {
+            ServerMessage::Error { error, .. } => assert!(error.contains("Project not found"), "{}", error),
+            other => panic!("expected an error, got {:?}", other),
+        }
+    }
+}
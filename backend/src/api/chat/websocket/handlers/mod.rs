@@ -1,3 +1,6 @@
 pub mod conversation;
+pub mod datasource;
+pub mod pagination;
 pub mod subscription;
-pub mod streaming;
\ No newline at end of file
+pub mod streaming;
+pub mod upload;
\ No newline at end of file
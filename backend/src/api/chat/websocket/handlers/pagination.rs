@@ -0,0 +1,97 @@
+use crate::models::Message;
+
+/// One page of a conversation, oldest message first
+pub struct MessagePage {
+    pub messages: Vec<Message>,
+    pub has_more: bool,
+    /// Id of the oldest message in the page, to pass as `before` for the previous page
+    pub next_cursor: Option<String>,
+}
+
+/// Page `messages` (sorted oldest first) from the newest end.
+///
+/// `before` is a message id or an RFC 3339 timestamp; only older messages are
+/// kept. Without `limit` every remaining message is returned, which is what
+/// clients that predate pagination expect.
+pub fn paginate_messages(messages: Vec<Message>, before: Option<&str>, limit: Option<usize>) -> MessagePage {
+    let mut messages = messages;
+
+    if let Some(before) = before {
+        let cutoff = match messages.iter().position(|m| m.id == before) {
+            Some(index) => index,
+            None => match chrono::DateTime::parse_from_rfc3339(before) {
+                Ok(timestamp) => messages
+                    .iter()
+                    .position(|m| {
+                        m.created_at
+                            .as_deref()
+                            .and_then(|created| chrono::DateTime::parse_from_rfc3339(created).ok())
+                            .is_some_and(|created| created >= timestamp)
+                    })
+                    .unwrap_or(messages.len()),
+                // An unknown cursor (e.g. a forgotten message) yields no older messages
+                Err(_) => 0,
+            },
+        };
+        messages.truncate(cutoff);
+    }
+
+    let has_more = match limit {
+        Some(limit) if messages.len() > limit => {
+            messages.drain(..messages.len() - limit);
+            true
+        }
+        _ => false,
+    };
+
+    let next_cursor = if has_more { messages.first().map(|m| m.id.clone()) } else { None };
+    MessagePage { messages, has_more, next_cursor }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::MessageRole;
+
+    fn message(id: &str, minute: u32) -> Message {
+        Message {
+            id: id.to_string(),
+            content: String::new(),
+            role: MessageRole::User,
+            created_at: Some(format!("2025-01-01T10:{:02}:00+00:00", minute)),
+            processing_time_ms: None,
+            file_attachments: None,
+            tool_usages: None,
+            progress_content: None,
+        }
+    }
+
+    fn ids(page: &MessagePage) -> Vec<&str> {
+        page.messages.iter().map(|m| m.id.as_str()).collect()
+    }
+
+    #[test]
+    fn pages_walk_back_from_the_newest_message() {
+        let all: Vec<Message> = (1..=5).map(|i| message(&format!("m{}", i), i)).collect();
+
+        let unpaged = paginate_messages(all.clone(), None, None);
+        assert_eq!(ids(&unpaged), ["m1", "m2", "m3", "m4", "m5"]);
+        assert!(!unpaged.has_more);
+
+        let latest = paginate_messages(all.clone(), None, Some(2));
+        assert_eq!(ids(&latest), ["m4", "m5"]);
+        assert!(latest.has_more);
+        assert_eq!(latest.next_cursor.as_deref(), Some("m4"));
+
+        let older = paginate_messages(all.clone(), latest.next_cursor.as_deref(), Some(2));
+        assert_eq!(ids(&older), ["m2", "m3"]);
+
+        let oldest = paginate_messages(all.clone(), Some("m2"), Some(2));
+        assert_eq!(ids(&oldest), ["m1"]);
+        assert!(!oldest.has_more);
+        assert_eq!(oldest.next_cursor, None);
+
+        let by_time = paginate_messages(all, Some("2025-01-01T10:03:00Z"), None);
+        assert_eq!(ids(&by_time), ["m1", "m2"]);
+    }
+}
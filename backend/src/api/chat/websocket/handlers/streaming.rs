@@ -1,16 +1,71 @@
-use crate::utils::AppState;
+use crate::utils::{AppState, StreamingState};
+use std::collections::HashMap;
+use tokio::sync::RwLock;
+use uuid::Uuid;
 
-pub async fn handle_stop_streaming(conversation_id: String, state: &AppState) {
+/// Stop relaying the generation running in a conversation. The conversation
+/// stays locked until the Claude process exits. Returns whether one was
+/// running; stopping an idle conversation is a no-op.
+pub async fn handle_stop_streaming(conversation_id: String, state: &AppState) -> bool {
     tracing::info!(
         "Received stop streaming request: conversation={}",
         conversation_id
     );
 
+    stop_conversation_stream(
+        &state.active_claude_streams,
+        &state.active_generations,
+        &conversation_id,
+    )
+    .await
+}
+
+async fn stop_conversation_stream(
+    streams: &RwLock<HashMap<String, StreamingState>>,
+    generations: &std::sync::Mutex<HashMap<String, Uuid>>,
+    conversation_id: &str,
+) -> bool {
     // Remove the streaming state for this conversation
-    {
-        let mut streams = state.active_claude_streams.write().await;
-        if streams.remove(&conversation_id).is_some() {
-            tracing::info!("Stopped streaming for conversation: {}", conversation_id);
-        }
+    let had_stream = {
+        let mut streams = streams.write().await;
+        streams.remove(conversation_id).is_some()
+    };
+    if had_stream {
+        tracing::info!("Stopped streaming for conversation: {}", conversation_id);
+    }
+
+    // The Claude process keeps running until it exits on its own, so its
+    // generation keeps the conversation locked until then
+    let had_generation = generations
+        .lock()
+        .map(|generations| generations.contains_key(conversation_id))
+        .unwrap_or(false);
+
+    had_stream || had_generation
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn stopping_is_idempotent() {
+        let streams = RwLock::new(HashMap::new());
+        let generations = std::sync::Mutex::new(HashMap::new());
+        streams
+            .write()
+            .await
+            .insert("conv-1".to_string(), StreamingState::new("msg-1".to_string()));
+        generations.lock().unwrap().insert("conv-1".to_string(), Uuid::new_v4());
+
+        assert!(stop_conversation_stream(&streams, &generations, "conv-1").await);
+        assert!(streams.read().await.is_empty());
+        // Still held by the generation that is winding down
+        assert!(generations.lock().unwrap().contains_key("conv-1"));
+
+        // Already stopped, and a conversation that never streamed
+        generations.lock().unwrap().clear();
+        assert!(!stop_conversation_stream(&streams, &generations, "conv-1").await);
+        assert!(!stop_conversation_stream(&streams, &generations, "conv-2").await);
     }
-}
\ No newline at end of file
+}
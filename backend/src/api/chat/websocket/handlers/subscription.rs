@@ -1,5 +1,7 @@
 use crate::api::websocket::types::{ServerMessage, UserConnection};
 use crate::utils::AppState;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
 use std::collections::HashMap;
 use std::sync::Arc;
 use tokio::sync::{mpsc, RwLock};
@@ -95,7 +97,7 @@ pub async fn handle_subscribe(
     if let Some(ref conv_id) = conversation_id {
         if conv_id != "new" {
             let conversation_exists = sqlx::query_scalar::<_, bool>(
-                "SELECT EXISTS(SELECT 1 FROM conversations WHERE id = $1)",
+                "SELECT EXISTS(SELECT 1 FROM conversations WHERE id = $1 AND deleted_at IS NULL)",
             )
             .bind(conv_id)
             .fetch_one(&state.db_pool)
@@ -144,6 +146,8 @@ pub async fn handle_subscribe(
                     let _ = sender.send(ServerMessage::ConversationMessages {
                         conversation_id: conv_id.clone(),
                         messages,
+                        has_more: false,
+                        next_cursor: None,
                     });
                 }
                 Err(e) => {
@@ -223,6 +227,13 @@ pub async fn handle_subscribe(
                                     });
                                 }
                             }
+                            "buffer_truncated" => {
+                                // Older events were dropped to cap the buffer; let the client know
+                                let _ = sender.send(ServerMessage::Progress {
+                                    content: event.clone(),
+                                    conversation_id: conv_id.clone(),
+                                });
+                            }
                             _ => {}
                         }
                     }
@@ -296,6 +307,8 @@ pub async fn add_connection(
         sender,
         project_id: None,
         conversation_id: None,
+        upload_ids: std::collections::HashSet::new(),
+        connected_at: chrono::Utc::now(),
     };
 
     {
@@ -313,6 +326,47 @@ pub async fn add_connection(
     }
 }
 
+/// Close every WebSocket connection of a user, dropping them from the connection
+/// manager and conversation subscriber sets. Returns how many were closed.
+pub async fn disconnect_user(user_id: &str, reason: &str, state: &AppState) -> usize {
+    let removed: Vec<(String, UserConnection)> = {
+        let mut connections = WS_CONNECTIONS.write().await;
+        let connection_ids: Vec<String> = connections
+            .iter()
+            .filter(|(_, conn)| conn.user_id == user_id)
+            .map(|(connection_id, _)| connection_id.clone())
+            .collect();
+        connection_ids
+            .into_iter()
+            .filter_map(|connection_id| {
+                connections
+                    .remove(&connection_id)
+                    .map(|conn| (connection_id, conn))
+            })
+            .collect()
+    };
+
+    for (connection_id, conn) in &removed {
+        if let Some(conv_id) = conn.conversation_id.as_ref().filter(|c| *c != "new") {
+            state
+                .remove_conversation_subscriber(conv_id, connection_id)
+                .await;
+        }
+
+        // The connection's sender task closes the socket after delivering this
+        let _ = conn.sender.send(ServerMessage::Disconnected {
+            reason: reason.to_string(),
+        });
+    }
+
+    tracing::info!(
+        "Force-disconnected {} WebSocket connections for user {}",
+        removed.len(),
+        user_id
+    );
+    removed.len()
+}
+
 pub async fn remove_connection(connection_id: &str, user_id: &str) {
     let mut connections = WS_CONNECTIONS.write().await;
     connections.remove(connection_id);
@@ -321,4 +375,33 @@ pub async fn remove_connection(connection_id: &str, user_id: &str) {
         connection_id,
         user_id
     );
-}
\ No newline at end of file
+}
+/// A live connection as listed for admins
+#[derive(Debug, Clone, Serialize)]
+pub struct ConnectionSummary {
+    pub connection_id: String,
+    pub user_id: String,
+    pub project_id: Option<String>,
+    pub conversation_id: Option<String>,
+    pub connected_at: DateTime<Utc>,
+    pub age_seconds: i64,
+}
+
+/// Every connection in the connection manager, oldest first
+pub async fn list_connections() -> Vec<ConnectionSummary> {
+    let now = Utc::now();
+    let connections = WS_CONNECTIONS.read().await;
+    let mut summaries: Vec<ConnectionSummary> = connections
+        .iter()
+        .map(|(connection_id, conn)| ConnectionSummary {
+            connection_id: connection_id.clone(),
+            user_id: conn.user_id.clone(),
+            project_id: conn.project_id.clone(),
+            conversation_id: conn.conversation_id.clone(),
+            connected_at: conn.connected_at,
+            age_seconds: (now - conn.connected_at).num_seconds().max(0),
+        })
+        .collect();
+    summaries.sort_by(|a, b| a.connected_at.cmp(&b.connected_at).then_with(|| a.connection_id.cmp(&b.connection_id)));
+    summaries
+}
@@ -0,0 +1,83 @@
+use crate::api::websocket::types::ServerMessage;
+use crate::utils::AppState;
+use sqlx::Row;
+use tokio::sync::mpsc;
+use uuid::Uuid;
+
+use super::subscription::WS_CONNECTIONS;
+
+pub async fn handle_subscribe_upload(
+    upload_id: String,
+    client_id: &Option<String>,
+    connection_id: &str,
+    sender: &mpsc::UnboundedSender<ServerMessage>,
+    state: &AppState,
+) {
+    let (Some(client_id), Ok(upload_uuid)) = (
+        client_id.as_ref().and_then(|id| Uuid::parse_str(id).ok()),
+        Uuid::parse_str(&upload_id),
+    ) else {
+        let _ = sender.send(ServerMessage::Error {
+            error: format!("Invalid upload subscription: {}", upload_id),
+            conversation_id: String::new(),
+        });
+        return;
+    };
+
+    // Only uploads belonging to the connection's client can be followed
+    let row = sqlx::query(
+        "SELECT extraction_status, extraction_progress, metadata FROM file_uploads WHERE id = $1 AND client_id = $2",
+    )
+    .bind(upload_uuid)
+    .bind(client_id)
+    .fetch_optional(&state.db_pool)
+    .await;
+
+    let row = match row {
+        Ok(Some(row)) => row,
+        Ok(None) => {
+            let _ = sender.send(ServerMessage::Error {
+                error: format!("Upload {} not found", upload_id),
+                conversation_id: String::new(),
+            });
+            return;
+        }
+        Err(e) => {
+            tracing::error!("Failed to load upload {} for subscription: {}", upload_id, e);
+            return;
+        }
+    };
+
+    {
+        let mut connections = WS_CONNECTIONS.write().await;
+        if let Some(conn) = connections.get_mut(connection_id) {
+            conn.upload_ids.insert(upload_id.clone());
+        }
+    }
+
+    // Send the current state so late subscribers learn about finished extractions
+    let status: String = row.get("extraction_status");
+    let metadata: Option<serde_json::Value> = row.get("metadata");
+    let error = metadata
+        .as_ref()
+        .and_then(|m| m.get("extraction_error"))
+        .and_then(|e| e.as_str())
+        .map(|e| e.to_string());
+
+    let _ = sender.send(ServerMessage::UploadProgress {
+        upload_id,
+        status,
+        unit: None,
+        processed: None,
+        total: None,
+        percent: row.get("extraction_progress"),
+        error,
+    });
+}
+
+pub async fn handle_unsubscribe_upload(upload_id: &str, connection_id: &str) {
+    let mut connections = WS_CONNECTIONS.write().await;
+    if let Some(conn) = connections.get_mut(connection_id) {
+        conn.upload_ids.remove(upload_id);
+    }
+}
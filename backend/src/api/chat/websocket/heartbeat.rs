@@ -0,0 +1,123 @@
+//! Server-side liveness checks for WebSocket connections
+//!
+//! A half-open socket never errors or closes, so without this it would stay in
+//! the connection manager and keep receiving broadcasts. The sender task pings
+//! the client every `interval`; any frame from the client (including the pong
+//! browsers send automatically) counts as a sign of life. A client that stays
+//! silent for longer than `timeout` is dropped.
+
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+use tokio::task::AbortHandle;
+
+pub use crate::utils::config::HeartbeatConfig;
+
+use super::handlers::subscription::remove_connection;
+
+/// When a connection last heard from its client
+pub struct Heartbeat {
+    config: HeartbeatConfig,
+    last_seen: Mutex<Instant>,
+}
+
+impl Heartbeat {
+    pub fn new(config: HeartbeatConfig) -> Self {
+        Self {
+            config,
+            last_seen: Mutex::new(Instant::now()),
+        }
+    }
+
+    /// Record that the client sent something
+    pub fn touch(&self) {
+        if let Ok(mut last_seen) = self.last_seen.lock() {
+            *last_seen = Instant::now();
+        }
+    }
+
+    fn is_expired(&self) -> bool {
+        self.last_seen
+            .lock()
+            .map(|last_seen| last_seen.elapsed() > self.config.timeout)
+            .unwrap_or(false)
+    }
+
+    /// Resolves once the client has been silent for longer than the timeout
+    pub async fn expired(&self) {
+        let mut ticker = tokio::time::interval(self.config.interval);
+        loop {
+            ticker.tick().await;
+            if self.is_expired() {
+                return;
+            }
+        }
+    }
+}
+
+/// Drop the connection once its client stops answering: stops the sender task
+/// (which also ends the receive loop) and removes it from the connection manager
+pub async fn watch_connection(
+    heartbeat: Arc<Heartbeat>,
+    connection_id: String,
+    user_id: String,
+    sender: AbortHandle,
+) {
+    heartbeat.expired().await;
+    tracing::warn!(
+        "WebSocket heartbeat timed out after {}s: user_id={}, connection_id={}",
+        heartbeat.config.timeout.as_secs(),
+        user_id,
+        connection_id
+    );
+    sender.abort();
+    remove_connection(&connection_id, &user_id).await;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::websocket::handlers::subscription::{add_connection, WS_CONNECTIONS};
+    use std::time::Duration;
+    use tokio::sync::mpsc;
+
+    #[tokio::test]
+    async fn silent_client_is_dropped() {
+        let config = HeartbeatConfig {
+            interval: Duration::from_millis(10),
+            timeout: Duration::from_millis(50),
+        };
+        let (tx, _rx) = mpsc::unbounded_channel();
+        add_connection("heartbeat-test".to_string(), "user-1".to_string(), tx).await;
+        let sender = tokio::spawn(std::future::pending::<()>());
+
+        // Nothing ever touches the heartbeat, as with a client that vanished
+        tokio::time::timeout(
+            Duration::from_secs(2),
+            watch_connection(
+                Arc::new(Heartbeat::new(config)),
+                "heartbeat-test".to_string(),
+                "user-1".to_string(),
+                sender.abort_handle(),
+            ),
+        )
+        .await
+        .expect("silent connection should time out");
+
+        assert!(sender.await.unwrap_err().is_cancelled());
+        assert!(!WS_CONNECTIONS.read().await.contains_key("heartbeat-test"));
+    }
+
+    #[tokio::test]
+    async fn activity_keeps_the_connection_alive() {
+        let heartbeat = Heartbeat::new(HeartbeatConfig {
+            interval: Duration::from_millis(10),
+            timeout: Duration::from_millis(60),
+        });
+
+        for _ in 0..5 {
+            tokio::time::sleep(Duration::from_millis(20)).await;
+            heartbeat.touch();
+            assert!(!heartbeat.is_expired());
+        }
+    }
+}
@@ -1,9 +1,12 @@
 use futures_util::{SinkExt, StreamExt};
 use salvo::prelude::*;
+use std::sync::Arc;
 use salvo::websocket::{Message as WsMessage, WebSocket, WebSocketUpgrade};
 use tokio::sync::mpsc;
 use uuid::Uuid;
 
+use crate::utils::query_limit::query_limit_message;
+use crate::utils::rate_limit::rate_limit_message;
 use crate::utils::{get_app_state, AppError, AppState};
 
 pub mod types;
@@ -11,21 +14,29 @@ pub mod auth;
 pub mod handlers;
 pub mod broadcast;
 pub mod claude_md;
+pub mod heartbeat;
+pub mod notifications;
+pub mod resume;
+pub mod schema_events;
 
 use types::{ClientMessage, ServerMessage};
 use auth::extract_session_data;
+use heartbeat::{watch_connection, Heartbeat};
 use handlers::{
     conversation::{
         handle_create_conversation, handle_list_conversations, handle_get_conversation,
-        handle_update_conversation, handle_delete_conversation, handle_get_conversation_messages,
-        store_ask_user_response
+        handle_update_conversation, handle_delete_conversation, handle_restore_conversation,
+        handle_get_conversation_messages, store_ask_user_response
     },
+    datasource::handle_create_datasource,
+    pagination::paginate_messages,
     subscription::{handle_subscribe, handle_unsubscribe, add_connection, remove_connection},
     streaming::handle_stop_streaming,
+    upload::{handle_subscribe_upload, handle_unsubscribe_upload},
 };
 
 // Re-export for backward compatibility
-pub use broadcast::{broadcast_to_subscribers, broadcast_activity_to_project};
+pub use broadcast::{broadcast_to_subscribers, broadcast_activity_to_project, broadcast_upload_progress};
 pub use types::{ServerMessage as WebSocketServerMessage};
 
 // Create placeholder handlers for missing exports
@@ -91,6 +102,9 @@ async fn handle_websocket_connection(
         add_connection(connection_id.clone(), user_id.clone(), msg_tx.clone()).await;
     }
 
+    // Lets the client get its subscription back if this connection drops
+    let resume_token = is_authenticated.then(|| state.ws_resume.issue(&user_id));
+
     // Send authentication status message
     if is_authenticated {
         let _ = msg_tx.send(ServerMessage::Connected {
@@ -98,6 +112,7 @@ async fn handle_websocket_connection(
             authenticated: true,
             client_id: client_id.clone(),
             role: role.clone(),
+            resume_token: resume_token.clone(),
         });
         tracing::info!(
             "WebSocket authenticated: user_id={}, client_id={:?}, role={:?}",
@@ -113,9 +128,30 @@ async fn handle_websocket_connection(
         );
     }
 
-    // Spawn task to send messages to WebSocket
-    let ws_sender = tokio::spawn(async move {
-        while let Some(msg) = msg_rx.recv().await {
+    let heartbeat = Arc::new(Heartbeat::new(state.config.ws_heartbeat));
+    let ping_interval = state.config.ws_heartbeat.interval;
+
+    // Spawn task to send messages to WebSocket, pinging the client between messages
+    let mut ws_sender = tokio::spawn(async move {
+        let mut ping_ticker = tokio::time::interval(ping_interval);
+        // The first tick completes immediately; the client was just heard from
+        ping_ticker.tick().await;
+
+        loop {
+            let msg = tokio::select! {
+                msg = msg_rx.recv() => match msg {
+                    Some(msg) => msg,
+                    None => break,
+                },
+                _ = ping_ticker.tick() => {
+                    if ws_tx.send(WsMessage::ping(Vec::new())).await.is_err() {
+                        tracing::info!("WebSocket connection closed, stopping sender");
+                        break;
+                    }
+                    continue;
+                }
+            };
+
             let json_msg = match serde_json::to_string(&msg) {
                 Ok(json) => json,
                 Err(e) => {
@@ -128,13 +164,38 @@ async fn handle_websocket_connection(
                 tracing::info!("WebSocket connection closed, stopping sender");
                 break;
             }
+
+            if let ServerMessage::Disconnected { reason } = msg {
+                // 1008 (policy violation) tells the client not to reconnect
+                let _ = ws_tx.send(WsMessage::close_with(1008u16, reason)).await;
+                tracing::info!("WebSocket connection closed by the server");
+                break;
+            }
         }
     });
 
-    // Handle incoming messages
-    while let Some(msg_result) = ws_rx.next().await {
+    // Drop the connection if the client stops answering pings
+    let watchdog = tokio::spawn(watch_connection(
+        heartbeat.clone(),
+        connection_id.clone(),
+        user_id.clone(),
+        ws_sender.abort_handle(),
+    ));
+
+    // Handle incoming messages until the client leaves or the sender task
+    // stops (e.g. after an admin force-disconnect or a heartbeat timeout)
+    loop {
+        let msg_result = tokio::select! {
+            msg_result = ws_rx.next() => match msg_result {
+                Some(msg_result) => msg_result,
+                None => break,
+            },
+            _ = &mut ws_sender => break,
+        };
         match msg_result {
             Ok(msg) => {
+                // Any frame, pongs included, shows the client is still there
+                heartbeat.touch();
                 if let Ok(text) = msg.as_str() {
                     tracing::info!("WebSocket received message: {}", text);
                     match serde_json::from_str::<ClientMessage>(text) {
@@ -143,11 +204,16 @@ async fn handle_websocket_connection(
                                 client_msg,
                                 &user_id,
                                 &client_id,
+                                &role,
                                 &connection_id,
                                 &msg_tx,
                                 &state,
                             )
                             .await;
+
+                            if let Some(token) = &resume_token {
+                                state.ws_resume.remember_connection(token, &connection_id).await;
+                            }
                         }
                         Err(e) => {
                             tracing::warn!("Failed to parse WebSocket message: {} - {}", text, e);
@@ -167,6 +233,7 @@ async fn handle_websocket_connection(
 
     // Cleanup
     ws_sender.abort();
+    watchdog.abort();
     tracing::info!(
         "WebSocket disconnected: user_id={}, connection_id={}",
         user_id,
@@ -175,12 +242,18 @@ async fn handle_websocket_connection(
 
     // Remove from connection manager
     remove_connection(&connection_id, &user_id).await;
+
+    // Keep the subscription resumable for a while
+    if let Some(token) = &resume_token {
+        state.ws_resume.release(token);
+    }
 }
 
 async fn handle_client_message(
     msg: ClientMessage,
     user_id: &str,
     client_id: &Option<String>,
+    role: &Option<String>,
     connection_id: &str,
     sender: &mpsc::UnboundedSender<ServerMessage>,
     state: &AppState,
@@ -205,6 +278,38 @@ async fn handle_client_message(
             handle_unsubscribe(connection_id, user_id, state).await;
         }
 
+        ClientMessage::Resume { resume_token } => match state.ws_resume.take(&resume_token, user_id) {
+            Ok(subscription) => {
+                tracing::info!(
+                    "Connection {} resumed subscription to project {} (conversation {:?})",
+                    connection_id,
+                    subscription.project_id,
+                    subscription.conversation_id
+                );
+                handle_subscribe(
+                    subscription.project_id,
+                    subscription.conversation_id,
+                    user_id,
+                    connection_id,
+                    sender,
+                    state,
+                )
+                .await;
+            }
+            Err(e) => {
+                tracing::info!("Connection {} could not resume: {}", connection_id, e);
+                let _ = sender.send(ServerMessage::ResumeFailed { reason: e.to_string() });
+            }
+        },
+
+        ClientMessage::SubscribeUpload { upload_id } => {
+            handle_subscribe_upload(upload_id, client_id, connection_id, sender, state).await;
+        }
+
+        ClientMessage::UnsubscribeUpload { upload_id } => {
+            handle_unsubscribe_upload(&upload_id, connection_id).await;
+        }
+
         ClientMessage::Ping => {
             let _ = sender.send(ServerMessage::Pong);
         }
@@ -238,6 +343,7 @@ async fn handle_client_message(
             conversation_id,
             content,
             file_ids,
+            model,
         } => {
             tracing::info!(
                 "Received send message request: project={}, conversation={}, client_id={:?}",
@@ -248,18 +354,59 @@ async fn handle_client_message(
 
             // Check if we have a client_id for Claude authentication
             if let Some(client_id_str) = client_id.clone() {
+                if let Err(retry_after) = state.prompt_rate_limiter.check(Some(&client_id_str), user_id) {
+                    tracing::info!("Rate limiting messages from user {}", user_id);
+                    let _ = sender.send(ServerMessage::Error {
+                        error: rate_limit_message(retry_after),
+                        conversation_id: conversation_id.clone(),
+                    });
+                    return;
+                }
+
+                // Reject rather than interleave with a generation already running here;
+                // "new" conversations are claimed once they get their real id
+                let generation_lock = if conversation_id == "new" {
+                    None
+                } else {
+                    match state.try_lock_conversation(&conversation_id) {
+                        Some(lock) => Some(lock),
+                        None => {
+                            tracing::info!(
+                                "Conversation {} is busy, rejecting message from user {}",
+                                conversation_id,
+                                user_id
+                            );
+                            let _ = sender.send(ServerMessage::conversation_busy(&conversation_id));
+                            return;
+                        }
+                    }
+                };
+
+                // Held by the query task, releasing the client's slot when it ends
+                let Some(query_permit) = state.claude_query_limiter.try_acquire(&client_id_str) else {
+                    tracing::info!("Client {} is at its concurrent query limit", client_id_str);
+                    let _ = sender.send(ServerMessage::Error {
+                        error: query_limit_message(state.config.max_concurrent_queries),
+                        conversation_id: conversation_id.clone(),
+                    });
+                    return;
+                };
+
                 tracing::info!(
                     "Starting chat message handler with client_id: {}",
                     client_id_str
                 );
                 let state_owned = state.clone();
                 tokio::spawn(async move {
+                    let _query_permit = query_permit;
                     if let Err(e) = crate::api::chat::chat_ws::handle_chat_message_ws(
                         project_id,
                         conversation_id,
                         content,
                         file_ids.unwrap_or_default(),
+                        model,
                         client_id_str,
+                        generation_lock,
                         state_owned,
                     )
                     .await
@@ -279,18 +426,46 @@ async fn handle_client_message(
             }
         }
 
-        ClientMessage::CreateConversation { project_id, title, first_message, file_ids } => {
+        ClientMessage::CreateConversation { project_id, title, first_message, file_ids, model } => {
             tracing::info!(
                 "Received create conversation request for project: {}",
                 project_id
             );
 
             if let Some(client_id_str) = client_id.clone() {
+                // A first message starts a prompt, so it counts against the rate limit
+                if first_message.is_some() {
+                    if let Err(retry_after) = state.prompt_rate_limiter.check(Some(&client_id_str), user_id) {
+                        tracing::info!("Rate limiting new conversation from user {}", user_id);
+                        let _ = sender.send(ServerMessage::Error {
+                            error: rate_limit_message(retry_after),
+                            conversation_id: "".to_string(),
+                        });
+                        return;
+                    }
+                }
+
+                // The first message runs a query, so it needs a free slot too
+                let query_permit = match first_message {
+                    Some(_) => match state.claude_query_limiter.try_acquire(&client_id_str) {
+                        Some(permit) => Some(permit),
+                        None => {
+                            tracing::info!("Client {} is at its concurrent query limit", client_id_str);
+                            let _ = sender.send(ServerMessage::Error {
+                                error: query_limit_message(state.config.max_concurrent_queries),
+                                conversation_id: "".to_string(),
+                            });
+                            return;
+                        }
+                    },
+                    None => None,
+                };
+
                 // Store first_message and file_ids for use after conversation creation
                 let first_msg = first_message.clone();
                 let files = file_ids.clone();
 
-                match handle_create_conversation(&project_id, title, None, None, &client_id_str, user_id, state).await {
+                match handle_create_conversation(&project_id, title, model, &client_id_str, user_id, state).await {
                     Ok(conversation) => {
                         // Automatically subscribe the connection to the new conversation
                         let conversation_id = conversation.id.clone();
@@ -331,12 +506,15 @@ async fn handle_client_message(
 
                             // Spawn async task to handle the first message
                             tokio::spawn(async move {
+                                let _query_permit = query_permit;
                                 if let Err(e) = crate::api::chat::chat_ws::handle_chat_message_ws(
                                     project_id_clone,
                                     conversation_id_clone,
                                     message_content,
                                     file_ids_clone,
+                                    None, // The conversation already stores its model
                                     client_id_clone,
+                                    None,
                                     state_clone,
                                 )
                                 .await
@@ -480,6 +658,30 @@ async fn handle_client_message(
             }
         }
 
+        ClientMessage::RestoreConversation { conversation_id } => {
+            tracing::info!("Received restore conversation request: {}", conversation_id);
+
+            if let Some(client_id_str) = client_id.clone() {
+                match handle_restore_conversation(&conversation_id, &client_id_str, state).await {
+                    Ok(conversation) => {
+                        let _ = sender.send(ServerMessage::ConversationRestored { conversation });
+                    }
+                    Err(e) => {
+                        tracing::error!("Failed to restore conversation: {}", e);
+                        let _ = sender.send(ServerMessage::Error {
+                            error: format!("Failed to restore conversation: {}", e),
+                            conversation_id: conversation_id.clone(),
+                        });
+                    }
+                }
+            } else {
+                let _ = sender.send(ServerMessage::Error {
+                    error: "Not authenticated".to_string(),
+                    conversation_id: conversation_id.clone(),
+                });
+            }
+        }
+
         ClientMessage::BulkDeleteConversations { conversation_ids } => {
             tracing::info!(
                 "Received bulk delete conversations request: {} conversations",
@@ -521,10 +723,12 @@ async fn handle_client_message(
             }
         }
 
-        ClientMessage::GetConversationMessages { conversation_id } => {
+        ClientMessage::GetConversationMessages { conversation_id, before, limit } => {
             tracing::info!(
-                "Received get conversation messages request: {}",
-                conversation_id
+                "Received get conversation messages request: {} (before={:?}, limit={:?})",
+                conversation_id,
+                before,
+                limit
             );
 
             if let Some(client_id_str) = client_id.clone() {
@@ -532,9 +736,12 @@ async fn handle_client_message(
                     .await
                 {
                     Ok(messages) => {
+                        let page = paginate_messages(messages, before.as_deref(), limit);
                         let _ = sender.send(ServerMessage::ConversationMessages {
                             conversation_id: conversation_id.clone(),
-                            messages,
+                            messages: page.messages,
+                            has_more: page.has_more,
+                            next_cursor: page.next_cursor,
                         });
                     }
                     Err(e) => {
@@ -552,5 +759,28 @@ async fn handle_client_message(
                 });
             }
         }
+
+        ClientMessage::CreateDatasource { project_id, name, source_type, config } => {
+            tracing::info!("Received create datasource request for project: {}", project_id);
+
+            if client_id.is_some() {
+                handle_create_datasource(
+                    project_id,
+                    name,
+                    source_type,
+                    config,
+                    user_id,
+                    role,
+                    sender,
+                    &state.db_pool,
+                )
+                .await;
+            } else {
+                let _ = sender.send(ServerMessage::Error {
+                    error: "Not authenticated".to_string(),
+                    conversation_id: "".to_string(),
+                });
+            }
+        }
     }
 }
\ No newline at end of file
@@ -0,0 +1,76 @@
+//! Events published with Postgres NOTIFY, by this process or the MCP server,
+//! forwarded to websocket subscribers
+
+use std::time::Duration;
+
+use sqlx::postgres::{PgListener, PgNotification};
+use sqlx::PgPool;
+
+use crate::core::datasources::schema_events::{SchemaRefreshed, SCHEMA_REFRESH_CHANNEL};
+use crate::core::mcp::handlers::ask_user_choice::{ChoicePrompt, ASK_USER_CHANNEL};
+
+use super::broadcast::broadcast_to_subscribers;
+use super::schema_events::broadcast_schema_refresh;
+use super::types::ServerMessage;
+
+/// Listen for notifications in the background, reconnecting when the
+/// listener's connection fails
+pub fn start_notification_listener(db: PgPool) {
+    tokio::spawn(async move {
+        loop {
+            if let Err(e) = listen(&db).await {
+                tracing::warn!("Notification listener stopped: {}; restarting", e);
+            }
+            tokio::time::sleep(Duration::from_secs(5)).await;
+        }
+    });
+}
+
+async fn listen(db: &PgPool) -> Result<(), sqlx::Error> {
+    let mut listener = PgListener::connect_with(db).await?;
+    listener.listen_all([SCHEMA_REFRESH_CHANNEL, ASK_USER_CHANNEL]).await?;
+
+    loop {
+        let notification = listener.recv().await?;
+        if let Err(e) = dispatch(&notification).await {
+            tracing::warn!("Ignoring malformed {} notification: {}", notification.channel(), e);
+        }
+    }
+}
+
+async fn dispatch(notification: &PgNotification) -> Result<(), serde_json::Error> {
+    match notification.channel() {
+        SCHEMA_REFRESH_CHANNEL => {
+            broadcast_schema_refresh(serde_json::from_str::<SchemaRefreshed>(notification.payload())?).await;
+        }
+        ASK_USER_CHANNEL => {
+            forward_choice_prompt(serde_json::from_str::<ChoicePrompt>(notification.payload())?).await;
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+/// Show an `ask_user_choice` prompt in its conversation as option buttons
+async fn forward_choice_prompt(prompt: ChoicePrompt) {
+    let options = prompt
+        .options
+        .iter()
+        .map(|option| serde_json::json!({ "value": option, "label": option }))
+        .collect();
+    broadcast_to_subscribers(
+        &prompt.project_id,
+        &prompt.conversation_id,
+        ServerMessage::AskUser {
+            prompt_type: "buttons".to_string(),
+            title: prompt.prompt,
+            options: Some(options),
+            input_type: None,
+            placeholder: None,
+            // Answered with an ask_user_response carrying this as interaction_id
+            tool_use_id: Some(prompt.interaction_id),
+            conversation_id: prompt.conversation_id.clone(),
+        },
+    )
+    .await;
+}
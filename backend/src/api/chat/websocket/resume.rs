@@ -0,0 +1,172 @@
+//! Resuming a WebSocket subscription after a reconnect
+//!
+//! Each authenticated connection gets a `resume_token` in its `Connected`
+//! message. The token tracks the connection's current subscription; once the
+//! connection drops it stays valid for `RESUME_TOKEN_TTL`, during which a new
+//! connection of the same user can send `Resume` to get the subscription back
+//! without re-sending the ids.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use thiserror::Error;
+use uuid::Uuid;
+
+use super::handlers::subscription::WS_CONNECTIONS;
+
+/// How long a token stays usable after its connection closes
+pub const RESUME_TOKEN_TTL: Duration = Duration::from_secs(5 * 60);
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ResumeSubscription {
+    pub project_id: String,
+    pub conversation_id: Option<String>,
+}
+
+#[derive(Debug, Error, PartialEq)]
+pub enum ResumeError {
+    #[error("Unknown resume token")]
+    Unknown,
+    #[error("Resume token has expired")]
+    Expired,
+    #[error("No subscription to resume")]
+    NothingToResume,
+}
+
+struct ResumeEntry {
+    user_id: String,
+    subscription: Option<ResumeSubscription>,
+    /// When the connection holding the token closed; None while it is open
+    released_at: Option<Instant>,
+}
+
+pub struct ResumeStore {
+    ttl: Duration,
+    entries: Mutex<HashMap<String, ResumeEntry>>,
+}
+
+impl ResumeStore {
+    pub fn new(ttl: Duration) -> Self {
+        Self {
+            ttl,
+            entries: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Create a token for a new connection of `user_id`
+    pub fn issue(&self, user_id: &str) -> String {
+        let token = Uuid::new_v4().to_string();
+        if let Ok(mut entries) = self.entries.lock() {
+            let ttl = self.ttl;
+            entries.retain(|_, entry| entry.released_at.is_none_or(|at| at.elapsed() < ttl));
+            entries.insert(
+                token.clone(),
+                ResumeEntry {
+                    user_id: user_id.to_string(),
+                    subscription: None,
+                    released_at: None,
+                },
+            );
+        }
+        token
+    }
+
+    /// Record the subscription a token should restore
+    pub fn remember(&self, token: &str, subscription: Option<ResumeSubscription>) {
+        if let Ok(mut entries) = self.entries.lock() {
+            if let Some(entry) = entries.get_mut(token) {
+                entry.subscription = subscription;
+            }
+        }
+    }
+
+    /// Record the current subscription of `connection_id` under its token
+    pub async fn remember_connection(&self, token: &str, connection_id: &str) {
+        let subscription = {
+            let connections = WS_CONNECTIONS.read().await;
+            connections.get(connection_id).and_then(|conn| {
+                conn.project_id.clone().map(|project_id| ResumeSubscription {
+                    project_id,
+                    conversation_id: conn.conversation_id.clone(),
+                })
+            })
+        };
+        self.remember(token, subscription);
+    }
+
+    /// Start the expiry clock once the token's connection has closed
+    pub fn release(&self, token: &str) {
+        if let Ok(mut entries) = self.entries.lock() {
+            if let Some(entry) = entries.get_mut(token) {
+                entry.released_at = Some(Instant::now());
+            }
+        }
+    }
+
+    /// Redeem a token for `user_id`. Tokens are single use; a token belonging
+    /// to another user is treated as unknown and left untouched.
+    pub fn take(&self, token: &str, user_id: &str) -> Result<ResumeSubscription, ResumeError> {
+        let mut entries = self.entries.lock().map_err(|_| ResumeError::Unknown)?;
+        match entries.get(token) {
+            Some(entry) if entry.user_id == user_id => {}
+            _ => return Err(ResumeError::Unknown),
+        }
+
+        let entry = entries.remove(token).ok_or(ResumeError::Unknown)?;
+        if entry.released_at.is_some_and(|at| at.elapsed() >= self.ttl) {
+            return Err(ResumeError::Expired);
+        }
+        entry.subscription.ok_or(ResumeError::NothingToResume)
+    }
+}
+
+impl Default for ResumeStore {
+    fn default() -> Self {
+        Self::new(RESUME_TOKEN_TTL)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn subscription() -> ResumeSubscription {
+        ResumeSubscription {
+            project_id: "project-1".to_string(),
+            conversation_id: Some("conversation-1".to_string()),
+        }
+    }
+
+    #[test]
+    fn valid_token_restores_the_subscription_once() {
+        let store = ResumeStore::default();
+        let token = store.issue("user-1");
+        store.remember(&token, Some(subscription()));
+        store.release(&token);
+
+        assert_eq!(store.take(&token, "user-1"), Ok(subscription()));
+        assert_eq!(store.take(&token, "user-1"), Err(ResumeError::Unknown));
+    }
+
+    #[test]
+    fn expired_token_is_rejected() {
+        let store = ResumeStore::new(Duration::ZERO);
+        let token = store.issue("user-1");
+        store.remember(&token, Some(subscription()));
+        store.release(&token);
+
+        assert_eq!(store.take(&token, "user-1"), Err(ResumeError::Expired));
+    }
+
+    #[test]
+    fn forged_or_foreign_tokens_are_rejected() {
+        let store = ResumeStore::default();
+        let token = store.issue("user-1");
+        store.remember(&token, Some(subscription()));
+
+        assert_eq!(store.take("not-a-token", "user-1"), Err(ResumeError::Unknown));
+        assert_eq!(store.take(&token, "user-2"), Err(ResumeError::Unknown));
+        // The owner can still use it after someone else tried
+        assert_eq!(store.take(&token, "user-1"), Ok(subscription()));
+    }
+}
@@ -0,0 +1,55 @@
+use crate::core::datasources::schema_events::SchemaRefreshed;
+
+use super::broadcast::broadcast_to_project;
+use super::types::ServerMessage;
+
+/// Tell a project's subscribers that one of its datasource schemas changed
+pub async fn broadcast_schema_refresh(event: SchemaRefreshed) {
+    broadcast_to_project(
+        &event.project_id,
+        ServerMessage::DatasourceUpdated {
+            datasource_id: event.datasource_id,
+            schema_version: event.schema_version,
+        },
+    )
+    .await;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::websocket::handlers::subscription::{add_connection, WS_CONNECTIONS};
+    use tokio::sync::mpsc;
+
+    async fn subscribe(connection_id: &str, project_id: &str) -> mpsc::UnboundedReceiver<ServerMessage> {
+        let (tx, rx) = mpsc::unbounded_channel();
+        add_connection(connection_id.to_string(), "user-1".to_string(), tx).await;
+        if let Some(conn) = WS_CONNECTIONS.write().await.get_mut(connection_id) {
+            conn.project_id = Some(project_id.to_string());
+        }
+        rx
+    }
+
+    #[tokio::test]
+    async fn an_inspection_notifies_subscribers_of_the_project() {
+        let mut viewer = subscribe("schema-viewer", "schema-proj-1").await;
+        let mut other_project = subscribe("schema-other", "schema-proj-2").await;
+
+        // The payload pg_notify carries after an inspection stores its schema
+        let payload = r#"{"project_id":"schema-proj-1","datasource_id":"ds-1","schema_version":"5d41402abc4b2a76"}"#;
+        broadcast_schema_refresh(serde_json::from_str(payload).unwrap()).await;
+
+        match viewer.try_recv() {
+            Ok(ServerMessage::DatasourceUpdated { datasource_id, schema_version }) => {
+                assert_eq!(datasource_id, "ds-1");
+                assert_eq!(schema_version, "5d41402abc4b2a76");
+            }
+            other => panic!("expected DatasourceUpdated, got {:?}", other),
+        }
+        assert!(other_project.try_recv().is_err());
+
+        let mut connections = WS_CONNECTIONS.write().await;
+        connections.remove("schema-viewer");
+        connections.remove("schema-other");
+    }
+}
@@ -1,4 +1,5 @@
 use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
 use tokio::sync::mpsc;
 
 // WebSocket message types from client
@@ -10,6 +11,17 @@ pub enum ClientMessage {
         conversation_id: Option<String>,
     },
     Unsubscribe,
+    // Restore the subscription of an earlier connection after a reconnect
+    Resume {
+        resume_token: String,
+    },
+    // Follow content extraction of an uploaded file
+    SubscribeUpload {
+        upload_id: String,
+    },
+    UnsubscribeUpload {
+        upload_id: String,
+    },
     Ping,
     AskUserResponse {
         conversation_id: String,
@@ -24,6 +36,8 @@ pub enum ClientMessage {
         conversation_id: String,
         content: String,
         file_ids: Option<Vec<String>>, // Changed from uploaded_file_paths to file_ids
+        // Switch the conversation to this model; unknown models fall back to the default
+        model: Option<String>,
     },
     // Conversation management
     CreateConversation {
@@ -31,6 +45,7 @@ pub enum ClientMessage {
         title: Option<String>,
         first_message: Option<String>,
         file_ids: Option<Vec<String>>,
+        model: Option<String>,
     },
     ListConversations {
         project_id: String,
@@ -45,11 +60,26 @@ pub enum ClientMessage {
     DeleteConversation {
         conversation_id: String,
     },
+    // Undo a delete within the restore window
+    RestoreConversation {
+        conversation_id: String,
+    },
     BulkDeleteConversations {
         conversation_ids: Vec<String>,
     },
     GetConversationMessages {
         conversation_id: String,
+        /// Only messages older than this message id or RFC 3339 timestamp
+        before: Option<String>,
+        /// Return at most this many of the most recent matching messages; all when absent
+        limit: Option<usize>,
+    },
+    // Datasource management
+    CreateDatasource {
+        project_id: String,
+        name: String,
+        source_type: String,
+        config: serde_json::Value,
     },
 }
 
@@ -63,6 +93,9 @@ pub enum ServerMessage {
         authenticated: bool,
         client_id: Option<String>,
         role: Option<String>,
+        /// Send back in a `Resume` after reconnecting to restore this connection's subscription
+        #[serde(skip_serializing_if = "Option::is_none")]
+        resume_token: Option<String>,
     },
     AuthenticationRequired,
     Subscribed {
@@ -74,6 +107,14 @@ pub enum ServerMessage {
         new_conversation_id: String,
     },
     Pong,
+    // The resume token was unknown or expired; the client has to subscribe again
+    ResumeFailed {
+        reason: String,
+    },
+    // Sent just before the server closes the connection on an admin's request
+    Disconnected {
+        reason: String,
+    },
     // Streaming messages
     Start {
         id: String,
@@ -95,7 +136,6 @@ pub enum ServerMessage {
         output: Option<serde_json::Value>,
         conversation_id: String,
     },
-    #[allow(dead_code)]
     AskUser {
         prompt_type: String,
         title: String,
@@ -119,6 +159,11 @@ pub enum ServerMessage {
         error: String,
         conversation_id: String,
     },
+    // Another generation is still running in this conversation; the message was not sent
+    ConversationBusy {
+        conversation_id: String,
+        message: String,
+    },
     ConversationActivity {
         conversation_id: String,
         user_id: String,
@@ -143,6 +188,9 @@ pub enum ServerMessage {
     ConversationDeleted {
         conversation_id: String,
     },
+    ConversationRestored {
+        conversation: crate::models::Conversation,
+    },
     ConversationsBulkDeleted {
         conversation_ids: Vec<String>,
         failed_ids: Vec<String>,
@@ -150,9 +198,41 @@ pub enum ServerMessage {
     ConversationMessages {
         conversation_id: String,
         messages: Vec<crate::models::Message>,
+        /// Older messages exist beyond this page
+        has_more: bool,
+        /// Pass as `before` to fetch the previous page
+        next_cursor: Option<String>,
+    },
+    // Upload extraction progress
+    UploadProgress {
+        upload_id: String,
+        status: String, // processing, ready or failed
+        unit: Option<String>, // sheets or pages
+        processed: Option<usize>,
+        total: Option<usize>,
+        percent: i32,
+        error: Option<String>,
+    },
+    DatasourceCreated {
+        datasource: crate::api::projects::datasources::types::DatasourceResponse,
+    },
+    // A datasource's stored schema was refreshed; clients re-fetch it
+    DatasourceUpdated {
+        datasource_id: String,
+        schema_version: String,
     },
 }
 
+impl ServerMessage {
+    /// Sent instead of starting a generation while another one holds the conversation
+    pub fn conversation_busy(conversation_id: &str) -> Self {
+        ServerMessage::ConversationBusy {
+            conversation_id: conversation_id.to_string(),
+            message: "A response is still being generated in this conversation. Wait for it to finish, then send your message again.".to_string(),
+        }
+    }
+}
+
 // User connection info
 #[derive(Clone, Debug)]
 pub struct UserConnection {
@@ -160,4 +240,6 @@ pub struct UserConnection {
     pub sender: mpsc::UnboundedSender<ServerMessage>,
     pub project_id: Option<String>,
     pub conversation_id: Option<String>,
+    pub upload_ids: HashSet<String>,
+    pub connected_at: chrono::DateTime<chrono::Utc>,
 }
\ No newline at end of file
@@ -0,0 +1,157 @@
+// Exporting a project as a JSON bundle and importing one as a new project
+
+use salvo::prelude::*;
+use uuid::Uuid;
+
+use crate::core::projects::bundle::{insert_project_rows, load_project_rows, BundleQuery, ProjectBundle};
+use crate::core::projects::{ProjectInfo, ProjectManager};
+use crate::utils::middleware::{get_current_client_id, get_current_user_id, is_current_user_root};
+use crate::utils::{get_app_state, AppError};
+
+use super::datasources::crud::validated_source_type;
+use super::queries::ensure_project_member;
+
+/// Download a project's datasources, saved queries, context and CLAUDE.md.
+/// Credentials are redacted; root users can keep them with `?include_secrets=true`.
+#[handler]
+pub async fn export_project(
+    req: &mut Request,
+    depot: &mut Depot,
+    res: &mut Response,
+) -> Result<(), AppError> {
+    let state = get_app_state(depot)?;
+    let project_id = req
+        .param::<String>("project_id")
+        .ok_or(AppError::BadRequest("Missing project_id".to_string()))?;
+
+    let include_secrets = req.query::<bool>("include_secrets") == Some(true);
+    if include_secrets && !is_current_user_root(depot) {
+        return Err(AppError::Forbidden("Only admins can export connection credentials".to_string()));
+    }
+
+    ensure_project_member(state, depot, &project_id).await?;
+    let client_id = get_current_client_id(depot)?;
+
+    let (project, datasources) = load_project_rows(&state.db_pool, &project_id, client_id)
+        .await
+        .map_err(|e| AppError::InternalServerError(format!("Database error: {}", e)))?
+        .ok_or_else(|| AppError::NotFound("Project not found".to_string()))?;
+
+    let project_manager = ProjectManager::new();
+    let claude_md = project_manager.get_claude_md_content(client_id, &project_id).ok();
+    let mut saved_queries = Vec::new();
+    for name in project_manager.list_queries(client_id, &project_id)? {
+        let content = project_manager.load_query(client_id, &project_id, &name)?;
+        saved_queries.push(BundleQuery { name, content });
+    }
+    saved_queries.sort_by(|a, b| a.name.cmp(&b.name));
+
+    let bundle = ProjectBundle::new(project, claude_md, saved_queries, datasources, include_secrets);
+    res.render(Json(bundle));
+    Ok(())
+}
+
+/// Create a project in the caller's client from an exported bundle
+#[handler]
+pub async fn import_project(
+    req: &mut Request,
+    depot: &mut Depot,
+    res: &mut Response,
+) -> Result<(), AppError> {
+    let state = get_app_state(depot)?;
+    let mut bundle: ProjectBundle = req
+        .parse_json()
+        .await
+        .map_err(|e| AppError::BadRequest(format!("Invalid project bundle: {}", e)))?;
+    bundle.validate().map_err(AppError::BadRequest)?;
+    normalize_source_types(&mut bundle)?;
+
+    let user_id = get_current_user_id(depot)?;
+    let client_id = get_current_client_id(depot)?;
+
+    let bundle = bundle.with_new_ids(&Uuid::new_v4().to_string());
+    insert_project_rows(&state.db_pool, &bundle, client_id, user_id)
+        .await
+        .map_err(|e| AppError::InternalServerError(format!("Failed to import project: {}", e)))?;
+
+    let project_manager = ProjectManager::new();
+    project_manager.ensure_project_directory(client_id, &bundle.project.id)?;
+    if let Some(claude_md) = &bundle.claude_md {
+        project_manager.save_claude_md_content(client_id, &bundle.project.id, claude_md)?;
+    }
+    for query in &bundle.saved_queries {
+        project_manager.save_query(client_id, &bundle.project.id, &query.name, &query.content)?;
+    }
+
+    let (created_at, updated_at): (chrono::DateTime<chrono::Utc>, chrono::DateTime<chrono::Utc>) =
+        sqlx::query_as("SELECT created_at, updated_at FROM projects WHERE id = $1")
+            .bind(&bundle.project.id)
+            .fetch_one(&state.db_pool)
+            .await
+            .map_err(|e| AppError::InternalServerError(format!("Database error: {}", e)))?;
+
+    res.status_code(StatusCode::CREATED);
+    res.render(Json(serde_json::json!({
+        "project": ProjectInfo {
+            id: bundle.project.id.clone(),
+            name: bundle.project.name.clone(),
+            created_at: created_at.to_rfc3339(),
+            updated_at: updated_at.to_rfc3339(),
+            client_id,
+        },
+        "datasources_imported": bundle.datasources.len(),
+        "saved_queries_imported": bundle.saved_queries.len(),
+        // Redacted credentials have to be re-entered before the datasources connect
+        "secrets_included": bundle.secrets_included,
+    })));
+    Ok(())
+}
+
+/// Map each datasource's source_type to the form we store ('postgres' →
+/// 'postgresql'), rejecting types we don't support
+fn normalize_source_types(bundle: &mut ProjectBundle) -> Result<(), AppError> {
+    for datasource in &mut bundle.datasources {
+        datasource.source_type = validated_source_type(&datasource.source_type)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::projects::bundle::{BundleDatasource, BundleProject};
+    use serde_json::json;
+
+    fn bundle_with_source_type(source_type: &str) -> ProjectBundle {
+        ProjectBundle::new(
+            BundleProject {
+                id: "proj-1".to_string(),
+                name: "Sales".to_string(),
+                settings: None,
+                organization_settings: None,
+                context: None,
+            },
+            None,
+            Vec::new(),
+            vec![BundleDatasource {
+                id: "ds-1".to_string(),
+                name: "Orders".to_string(),
+                source_type: source_type.to_string(),
+                connection_config: json!({ "host": "db.internal" }),
+                schema_info: None,
+                table_list: None,
+            }],
+            false,
+        )
+    }
+
+    #[test]
+    fn source_types_are_normalized_before_import() {
+        let mut bundle = bundle_with_source_type("Postgres");
+        normalize_source_types(&mut bundle).unwrap();
+        assert_eq!(bundle.datasources[0].source_type, "postgresql");
+
+        let mut unsupported = bundle_with_source_type("cassandra");
+        assert!(normalize_source_types(&mut unsupported).is_err());
+    }
+}
@@ -2,6 +2,7 @@ use crate::core::projects::{ProjectInfo, ProjectInfoWithStats, ProjectManager};
 use crate::core::tools::ToolApplicabilityChecker;
 use crate::models::*;
 use crate::utils::claude_md_template;
+use crate::utils::datasource::common::redaction::redact_connection_config;
 use crate::utils::middleware::{get_current_client_id, get_current_user_id, is_current_user_root};
 use crate::utils::AppError;
 use crate::utils::get_app_state;
@@ -103,7 +104,7 @@ pub async fn get_project_context(
             id: row.get("id"),
             name: row.get("name"),
             source_type: row.get("source_type"),
-            connection_config: row.get("connection_config"),
+            connection_config: redact_connection_config(&row.get::<serde_json::Value, _>("connection_config")),
             schema_info: row.get("schema_info"),
             preview_data: row.get("preview_data"),
             table_list,
@@ -155,7 +156,7 @@ pub async fn get_project_context(
 
     // Get total conversation count for this project
     let total_conversations =
-        sqlx::query_scalar::<_, i64>("SELECT COUNT(*) FROM conversations WHERE project_id = $1")
+        sqlx::query_scalar::<_, i64>("SELECT COUNT(*) FROM conversations WHERE project_id = $1 AND deleted_at IS NULL")
             .bind(&project_id)
             .fetch_one(&state.db_pool)
             .await
@@ -166,7 +167,7 @@ pub async fn get_project_context(
         "SELECT m.id, m.content, m.created_at, c.id as conversation_id, c.title 
          FROM messages m 
          JOIN conversations c ON m.conversation_id = c.id 
-         WHERE c.project_id = $1 
+         WHERE c.project_id = $1 AND c.deleted_at IS NULL
          ORDER BY m.created_at DESC 
          LIMIT 5",
     )
@@ -349,33 +350,39 @@ pub async fn create_project(
 }
 
 #[handler]
-pub async fn list_projects(depot: &mut Depot, res: &mut Response) -> Result<(), AppError> {
+pub async fn list_projects(
+    req: &mut Request,
+    depot: &mut Depot,
+    res: &mut Response,
+) -> Result<(), AppError> {
     let state = get_app_state(depot)?;
 
     // Get current user's ID for filtering
     let user_id = get_current_user_id(depot)?;
 
-    // Get projects where user is a member (via project_members table), excluding soft-deleted projects
-    let project_rows = if is_current_user_root(depot) {
-        sqlx::query(
-            "SELECT id, name, created_at, updated_at FROM projects WHERE deleted_at IS NULL ORDER BY created_at DESC"
-        )
-        .fetch_all(&state.db_pool)
-        .await
-        .map_err(|e| AppError::InternalServerError(format!("Failed to fetch projects: {}", e)))?
-    } else {
-        sqlx::query(
-            "SELECT DISTINCT p.id, p.name, p.created_at, p.updated_at
-             FROM projects p
-             JOIN project_members pm ON p.id = pm.project_id
-             WHERE pm.user_id = $1 AND p.deleted_at IS NULL
-             ORDER BY p.created_at DESC"
-        )
-        .bind(user_id)
-        .fetch_all(&state.db_pool)
-        .await
-        .map_err(|e| AppError::InternalServerError(format!("Failed to fetch projects: {}", e)))?
-    };
+    // Optional ?role=owner|member narrows the list to projects where the user holds that role
+    let role_filter = req
+        .query::<String>("role")
+        .map(|role| ProjectMemberRole::from_str(&role).map_err(AppError::BadRequest))
+        .transpose()?;
+
+    // Projects where the user is a member (root sees every project), excluding soft-deleted ones.
+    // The membership join also yields the user's role in each project.
+    let project_rows = sqlx::query(
+        "SELECT p.id, p.name, p.created_at, p.updated_at, pm.role
+         FROM projects p
+         LEFT JOIN project_members pm ON pm.project_id = p.id AND pm.user_id = $1
+         WHERE p.deleted_at IS NULL
+           AND ($2 OR pm.user_id IS NOT NULL)
+           AND ($3::text IS NULL OR pm.role = $3)
+         ORDER BY p.created_at DESC"
+    )
+    .bind(user_id)
+    .bind(is_current_user_root(depot))
+    .bind(role_filter.as_ref().map(|role| role.as_str()))
+    .fetch_all(&state.db_pool)
+    .await
+    .map_err(|e| AppError::InternalServerError(format!("Failed to fetch projects: {}", e)))?;
 
     // Get the first active client for directory info (if needed)
     let client_row = sqlx::query("SELECT id FROM clients WHERE status = 'active' LIMIT 1")
@@ -398,10 +405,13 @@ pub async fn list_projects(depot: &mut Depot, res: &mut Response) -> Result<(),
         let project_name: String = row.get("name");
         let created_at: chrono::DateTime<chrono::Utc> = row.get("created_at");
         let updated_at: chrono::DateTime<chrono::Utc> = row.get("updated_at");
+        let role = row
+            .get::<Option<String>, _>("role")
+            .and_then(|role| ProjectMemberRole::from_str(&role).ok());
 
         // Get conversation count for this project
         let conversation_count = sqlx::query_scalar::<_, i64>(
-            "SELECT COUNT(*) FROM conversations WHERE project_id = $1",
+            "SELECT COUNT(*) FROM conversations WHERE project_id = $1 AND deleted_at IS NULL",
         )
         .bind(&project_id)
         .fetch_one(&state.db_pool)
@@ -425,6 +435,7 @@ pub async fn list_projects(depot: &mut Depot, res: &mut Response) -> Result<(),
             client_id,
             conversation_count: Some(conversation_count as i32),
             datasource_count: Some(datasource_count as i32),
+            role,
         });
     }
 
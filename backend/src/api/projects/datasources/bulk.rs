@@ -0,0 +1,213 @@
+use chrono::Utc;
+use salvo::prelude::*;
+use serde_json::{json, Value};
+use sqlx::Connection;
+use std::collections::HashSet;
+use uuid::Uuid;
+
+use crate::utils::datasource::common::redaction::redact_connection_config;
+use crate::utils::middleware::{get_current_user_id, is_current_user_root};
+use crate::utils::{get_app_state, AppError};
+
+use super::crud::{name_conflict_or, normalize_database_type, VALID_SOURCE_TYPES};
+use super::types::{BulkDatasourceResult, CreateDatasourceRequest, DatasourceResponse};
+
+/// Most datasources accepted in one bulk request
+const MAX_BULK_DATASOURCES: usize = 100;
+
+/// Create many datasources at once
+///
+/// The body is an array of create requests. Each entry is validated and
+/// inserted on its own (inside one transaction, behind a savepoint), so a bad
+/// entry is reported in the results without stopping the rest.
+#[handler]
+pub async fn bulk_create_datasources(
+    req: &mut Request,
+    res: &mut Response,
+    depot: &mut Depot,
+) -> Result<(), AppError> {
+    let state = get_app_state(depot)?;
+    let user_id = get_current_user_id(depot)?;
+    let project_id = req.param::<String>("project_id")
+        .ok_or_else(|| AppError::BadRequest("Missing project_id".to_string()))?;
+
+    // Entries are parsed one by one so a malformed entry doesn't reject the batch
+    let items: Vec<Value> = req.parse_json().await
+        .map_err(|e| AppError::BadRequest(format!("Expected a JSON array of datasources: {}", e)))?;
+    if items.is_empty() {
+        return Err(AppError::BadRequest("No datasources to import".to_string()));
+    }
+    if items.len() > MAX_BULK_DATASOURCES {
+        return Err(AppError::BadRequest(format!(
+            "At most {} datasources can be imported per request, got {}",
+            MAX_BULK_DATASOURCES,
+            items.len()
+        )));
+    }
+
+    // Validate project ownership once for the whole batch (user owns project or is root)
+    let project_exists: Option<i32> = sqlx::query_scalar(
+        "SELECT 1 FROM projects WHERE id = $1 AND ($2 OR user_id = $3) AND deleted_at IS NULL"
+    )
+    .bind(&project_id)
+    .bind(is_current_user_root(depot))
+    .bind(user_id)
+    .fetch_optional(&state.db_pool)
+    .await
+    .map_err(|e| AppError::InternalServerError(format!("Database error: {}", e)))?;
+    if project_exists.is_none() {
+        return Err(AppError::NotFound("Project not found".to_string()));
+    }
+
+    // Names already used by live datasources, lowercased to match the unique index
+    let existing_names: Vec<String> = sqlx::query_scalar(
+        "SELECT LOWER(name) FROM data_sources WHERE project_id = $1 AND deleted_at IS NULL"
+    )
+    .bind(&project_id)
+    .fetch_all(&state.db_pool)
+    .await
+    .map_err(|e| AppError::InternalServerError(format!("Database error: {}", e)))?;
+    let mut taken_names: HashSet<String> = existing_names.into_iter().collect();
+
+    let mut tx = state.db_pool.begin().await
+        .map_err(|e| AppError::InternalServerError(format!("Failed to start transaction: {}", e)))?;
+    let mut results = Vec::with_capacity(items.len());
+
+    for (index, item) in items.into_iter().enumerate() {
+        let name = item.get("name").and_then(|v| v.as_str()).map(str::to_string);
+        let failed = |error: String| BulkDatasourceResult {
+            index,
+            name: name.clone(),
+            success: false,
+            datasource: None,
+            error: Some(error),
+        };
+
+        let request = match validate_bulk_item(item, &mut taken_names) {
+            Ok(request) => request,
+            Err(error) => {
+                results.push(failed(error));
+                continue;
+            }
+        };
+
+        let datasource_id = Uuid::new_v4().to_string();
+        let now = Utc::now();
+
+        // A savepoint per entry keeps one failed insert from aborting the transaction
+        let mut savepoint = tx.begin().await
+            .map_err(|e| AppError::InternalServerError(format!("Failed to create savepoint: {}", e)))?;
+        let inserted = sqlx::query(
+            r#"
+            INSERT INTO data_sources (id, name, source_type, connection_config, project_id, created_at, updated_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            "#
+        )
+        .bind(&datasource_id)
+        .bind(&request.name)
+        .bind(&request.source_type)
+        .bind(&request.config)
+        .bind(&project_id)
+        .bind(now)
+        .bind(now)
+        .execute(&mut *savepoint)
+        .await;
+
+        match inserted {
+            Ok(_) => {
+                savepoint.commit().await
+                    .map_err(|e| AppError::InternalServerError(format!("Failed to release savepoint: {}", e)))?;
+                results.push(BulkDatasourceResult {
+                    index,
+                    name: Some(request.name.clone()),
+                    success: true,
+                    datasource: Some(DatasourceResponse {
+                        id: datasource_id,
+                        name: request.name,
+                        source_type: request.source_type,
+                        config: redact_connection_config(&request.config),
+                        created_at: now.to_rfc3339(),
+                        updated_at: now.to_rfc3339(),
+                        project_id: project_id.clone(),
+                        schema_info: None,
+                        connection_status: Some("unknown".to_string()),
+                        connection_error: None,
+                    }),
+                    error: None,
+                });
+            }
+            Err(e) => {
+                savepoint.rollback().await
+                    .map_err(|e| AppError::InternalServerError(format!("Failed to roll back savepoint: {}", e)))?;
+                results.push(failed(name_conflict_or(e, &request.name, "Failed to create datasource").to_string()));
+            }
+        }
+    }
+
+    tx.commit().await
+        .map_err(|e| AppError::InternalServerError(format!("Failed to commit datasources: {}", e)))?;
+
+    let created = results.iter().filter(|r| r.success).count();
+    res.status_code(if created > 0 { StatusCode::CREATED } else { StatusCode::OK });
+    res.render(Json(json!({
+        "created": created,
+        "failed": results.len() - created,
+        "results": results,
+    })));
+    Ok(())
+}
+
+/// Parse one entry and normalize its source_type, claiming its name in `taken_names`
+fn validate_bulk_item(item: Value, taken_names: &mut HashSet<String>) -> Result<CreateDatasourceRequest, String> {
+    let mut request: CreateDatasourceRequest = serde_json::from_value(item)
+        .map_err(|e| format!("Invalid datasource: {}", e))?;
+
+    if request.name.trim().is_empty() {
+        return Err("Datasource name cannot be empty".to_string());
+    }
+
+    let normalized = normalize_database_type(&request.source_type);
+    if !VALID_SOURCE_TYPES.contains(&normalized.as_str()) {
+        return Err(format!(
+            "Invalid source_type '{}'. Must be one of: {}",
+            request.source_type,
+            VALID_SOURCE_TYPES.join(", ")
+        ));
+    }
+    request.source_type = normalized;
+
+    if !taken_names.insert(request.name.to_lowercase()) {
+        return Err(format!(
+            "A datasource named '{}' already exists in this project or earlier in the batch",
+            request.name
+        ));
+    }
+    Ok(request)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn valid_entries_pass_and_invalid_ones_are_reported() {
+        let mut taken: HashSet<String> = ["warehouse".to_string()].into_iter().collect();
+        let items = vec![
+            json!({ "name": "Orders", "source_type": "Postgres", "config": "postgres://u@h/db" }),
+            json!({ "name": "Legacy", "source_type": "dbase", "config": {} }),
+            json!({ "name": "Events", "source_type": "MSSQL", "config": { "host": "h" } }),
+            json!({ "name": "Warehouse", "source_type": "mysql", "config": {} }),
+            json!({ "name": "orders", "source_type": "sqlite", "config": { "path": "a.db" } }),
+            json!({ "source_type": "mysql", "config": {} }),
+        ];
+
+        let outcomes: Vec<_> = items.into_iter().map(|item| validate_bulk_item(item, &mut taken)).collect();
+
+        assert_eq!(outcomes[0].as_ref().unwrap().source_type, "postgresql");
+        assert!(outcomes[1].as_ref().unwrap_err().contains("Invalid source_type 'dbase'"));
+        assert_eq!(outcomes[2].as_ref().unwrap().source_type, "sqlserver");
+        assert!(outcomes[3].as_ref().unwrap_err().contains("already exists"));
+        assert!(outcomes[4].as_ref().unwrap_err().contains("earlier in the batch"));
+        assert!(outcomes[5].as_ref().unwrap_err().starts_with("Invalid datasource"));
+    }
+}
@@ -0,0 +1,226 @@
+use salvo::prelude::*;
+use serde_json::Value;
+use sqlx::Row;
+
+use crate::models::project_member::ProjectMemberRole;
+use crate::utils::datasource::common::identifiers::{known_columns, validate_column_name};
+use crate::utils::middleware::{get_current_user_id, is_current_user_root};
+use crate::utils::{get_app_state, AppError};
+
+use super::crud::get_cached_datasource;
+use super::types::{ColumnView, UpdateColumnViewsRequest};
+
+/// Get display names and hidden flags for a table's columns
+#[handler]
+pub async fn get_column_views(
+    req: &mut Request,
+    res: &mut Response,
+    depot: &mut Depot,
+) -> Result<(), AppError> {
+    let state = get_app_state(depot)?;
+    let user_id = get_current_user_id(depot)?;
+    let datasource_id = req.param::<String>("datasource_id")
+        .ok_or_else(|| AppError::BadRequest("Missing datasource_id".to_string()))?;
+    let table_name = req.param::<String>("table_name")
+        .ok_or_else(|| AppError::BadRequest("Missing table_name".to_string()))?;
+
+    get_cached_datasource(&datasource_id, &user_id, is_current_user_root(depot), &state.db_pool).await?;
+
+    let views = load_column_views(&state.db_pool, &datasource_id, &table_name).await
+        .map_err(|e| AppError::InternalServerError(format!("Database error: {}", e)))?;
+
+    res.render(Json(serde_json::json!({
+        "table_name": table_name,
+        "columns": views
+    })));
+    Ok(())
+}
+
+/// Set display names and hidden flags for a table's columns
+#[handler]
+pub async fn update_column_views(
+    req: &mut Request,
+    res: &mut Response,
+    depot: &mut Depot,
+) -> Result<(), AppError> {
+    let state = get_app_state(depot)?;
+    let user_id = get_current_user_id(depot)?;
+    let datasource_id = req.param::<String>("datasource_id")
+        .ok_or_else(|| AppError::BadRequest("Missing datasource_id".to_string()))?;
+    let table_name = req.param::<String>("table_name")
+        .ok_or_else(|| AppError::BadRequest("Missing table_name".to_string()))?;
+
+    let update_request: UpdateColumnViewsRequest = req.parse_json().await
+        .map_err(|e| AppError::BadRequest(format!("Invalid request body: {}", e)))?;
+
+    let is_root = is_current_user_root(depot);
+    let datasource = get_cached_datasource(&datasource_id, &user_id, is_root, &state.db_pool).await?;
+    let role = if is_root {
+        None
+    } else {
+        sqlx::query_scalar::<_, String>("SELECT role FROM project_members WHERE project_id = $1 AND user_id = $2")
+            .bind(&datasource.project_id)
+            .bind(user_id)
+            .fetch_optional(&state.db_pool)
+            .await
+            .map_err(|e| AppError::InternalServerError(format!("Database error: {}", e)))?
+    };
+    ensure_can_edit_views(is_root, role.as_deref())?;
+
+    let schema: Option<Value> = sqlx::query_scalar("SELECT schema_info FROM data_sources WHERE id = $1")
+        .bind(&datasource_id)
+        .fetch_optional(&state.db_pool)
+        .await
+        .map_err(|e| AppError::InternalServerError(format!("Database error: {}", e)))?
+        .flatten();
+    check_view_columns(&update_request.columns, &table_name, schema.as_ref()).map_err(AppError::BadRequest)?;
+
+    let mut tx = state.db_pool.begin().await
+        .map_err(|e| AppError::InternalServerError(format!("Database error: {}", e)))?;
+
+    for view in &update_request.columns {
+        let display_name = view.display_name.as_deref()
+            .map(str::trim)
+            .filter(|name| !name.is_empty());
+
+        // A column with no overrides is stored as no row at all
+        if display_name.is_none() && !view.hidden {
+            sqlx::query(
+                "DELETE FROM datasource_column_views WHERE datasource_id = $1 AND table_name = $2 AND column_name = $3"
+            )
+            .bind(&datasource_id)
+            .bind(&table_name)
+            .bind(&view.column_name)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| AppError::InternalServerError(format!("Database error: {}", e)))?;
+            continue;
+        }
+
+        sqlx::query(
+            r#"
+            INSERT INTO datasource_column_views (id, datasource_id, table_name, column_name, display_name, hidden, updated_at)
+            VALUES ($1, $2, $3, $4, $5, $6, NOW())
+            ON CONFLICT (datasource_id, table_name, column_name)
+            DO UPDATE SET display_name = EXCLUDED.display_name, hidden = EXCLUDED.hidden, updated_at = NOW()
+            "#
+        )
+        .bind(uuid::Uuid::new_v4())
+        .bind(&datasource_id)
+        .bind(&table_name)
+        .bind(&view.column_name)
+        .bind(display_name)
+        .bind(view.hidden)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| AppError::InternalServerError(format!("Database error: {}", e)))?;
+    }
+
+    tx.commit().await
+        .map_err(|e| AppError::InternalServerError(format!("Database error: {}", e)))?;
+
+    let views = load_column_views(&state.db_pool, &datasource_id, &table_name).await
+        .map_err(|e| AppError::InternalServerError(format!("Database error: {}", e)))?;
+
+    res.render(Json(serde_json::json!({
+        "table_name": table_name,
+        "columns": views
+    })));
+    Ok(())
+}
+
+/// Load the stored view metadata for one table of a datasource
+pub async fn load_column_views(
+    db_pool: &sqlx::PgPool,
+    datasource_id: &str,
+    table_name: &str,
+) -> Result<Vec<ColumnView>, sqlx::Error> {
+    let rows = sqlx::query(
+        "SELECT column_name, display_name, hidden FROM datasource_column_views WHERE datasource_id = $1 AND table_name = $2 ORDER BY column_name"
+    )
+    .bind(datasource_id)
+    .bind(table_name)
+    .fetch_all(db_pool)
+    .await?;
+
+    Ok(rows
+        .into_iter()
+        .map(|row| ColumnView {
+            column_name: row.get("column_name"),
+            display_name: row.get("display_name"),
+            hidden: row.get("hidden"),
+        })
+        .collect())
+}
+
+/// Column views change what every member sees, so only owners (and root) may edit them
+fn ensure_can_edit_views(is_root: bool, role: Option<&str>) -> Result<(), AppError> {
+    if is_root {
+        return Ok(());
+    }
+    match role.map(ProjectMemberRole::from_str) {
+        Some(Ok(ProjectMemberRole::Owner)) => Ok(()),
+        Some(_) => Err(AppError::Forbidden("Only project owners can change column views".to_string())),
+        None => Err(AppError::Forbidden("You don't have access to this project".to_string())),
+    }
+}
+
+/// Every view must name a column of the table: one the cached schema lists
+/// for it, or a plain identifier when the table isn't in the cache
+fn check_view_columns(views: &[ColumnView], table_name: &str, schema: Option<&Value>) -> Result<(), String> {
+    let known = schema.and_then(|schema| known_columns(schema, table_name));
+    for view in views {
+        if view.column_name.trim().is_empty() {
+            return Err("column_name cannot be empty".to_string());
+        }
+        match &known {
+            Some(columns) if !columns.contains(&view.column_name) => {
+                return Err(format!("Table '{}' has no column '{}'", table_name, view.column_name));
+            }
+            Some(_) => {}
+            None => validate_column_name(&view.column_name, table_name, None)?,
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn view(column_name: &str) -> ColumnView {
+        ColumnView {
+            column_name: column_name.to_string(),
+            display_name: Some("Label".to_string()),
+            hidden: false,
+        }
+    }
+
+    #[test]
+    fn only_owners_and_root_can_edit_column_views() {
+        assert!(ensure_can_edit_views(false, Some("owner")).is_ok());
+        assert!(ensure_can_edit_views(true, None).is_ok());
+
+        for role in [Some("member"), None] {
+            let err = ensure_can_edit_views(false, role).unwrap_err();
+            assert_eq!(err.status_code(), StatusCode::FORBIDDEN, "role {:?}", role);
+        }
+    }
+
+    #[test]
+    fn views_must_name_columns_of_the_table() {
+        let schema = json!({
+            "tables": { "orders": { "columns": [{ "name": "id" }, { "name": "order total" }] } }
+        });
+
+        assert!(check_view_columns(&[view("id"), view("order total")], "orders", Some(&schema)).is_ok());
+        let err = check_view_columns(&[view("id"), view("discount")], "orders", Some(&schema)).unwrap_err();
+        assert!(err.contains("discount"));
+        assert!(check_view_columns(&[view(" ")], "orders", Some(&schema)).is_err());
+
+        // Without a cached schema only plain identifiers are accepted
+        assert!(check_view_columns(&[view("discount")], "orders", None).is_ok());
+        assert!(check_view_columns(&[view("id; DROP TABLE orders")], "orders", None).is_err());
+    }
+}
@@ -5,11 +5,36 @@ use sqlx::Row;
 use uuid::Uuid;
 
 use crate::core::datasources::cache::{get_datasource_cache, CachedDatasource};
+use crate::core::datasources::connection_status::ConnectionHealth;
+use crate::utils::datasource::common::connection_config::tag_connection_owner;
+use crate::utils::datasource::common::redaction::{redact_connection_config, restore_redacted_secrets};
 use crate::utils::middleware::{get_current_user_id, is_current_user_root};
 use crate::utils::{get_app_state, AppError};
 
+use super::reconnect::drop_cached_connections;
 use super::types::{CreateDatasourceRequest, DatasourceResponse, UpdateDatasourceRequest};
 
+/// Whether the response may carry unredacted connection configs: only root
+/// users can ask for them, with `?reveal=true`
+fn reveal_requested(req: &Request, depot: &Depot) -> Result<bool, AppError> {
+    if req.query::<bool>("reveal") != Some(true) {
+        return Ok(false);
+    }
+    if !is_current_user_root(depot) {
+        return Err(AppError::Forbidden("Only admins can reveal connection credentials".to_string()));
+    }
+    Ok(true)
+}
+
+/// Connection config as returned to clients
+fn response_config(config: Value, reveal: bool) -> Value {
+    if reveal {
+        config
+    } else {
+        redact_connection_config(&config)
+    }
+}
+
 /// List all datasources for a project
 #[handler]
 pub async fn list_datasources(
@@ -21,6 +46,7 @@ pub async fn list_datasources(
     let user_id = get_current_user_id(depot)?;
     let project_id = req.param::<String>("project_id")
         .ok_or_else(|| AppError::BadRequest("Missing project_id".to_string()))?;
+    let reveal = reveal_requested(req, depot)?;
 
     // Validate project ownership (user owns project or is root)
     let project_exists = if is_current_user_root(depot) {
@@ -49,7 +75,7 @@ pub async fn list_datasources(
     // Get datasources for the project
     let rows = sqlx::query(
         r#"
-        SELECT id, name, source_type, connection_config as config, created_at, updated_at, project_id, schema_info, last_tested_at
+        SELECT id, name, source_type, connection_config as config, created_at, updated_at, project_id, schema_info, connection_status, connection_error
         FROM data_sources 
         WHERE project_id = $1 AND deleted_at IS NULL
         ORDER BY created_at DESC
@@ -65,26 +91,20 @@ pub async fn list_datasources(
         .map(|row| {
             let config_json: Value = row.get("config");
             let schema_info_json: Option<Value> = row.get("schema_info");
-            let last_tested_at: Option<chrono::DateTime<Utc>> = row.get("last_tested_at");
-            
-            // Determine connection status based on last_tested_at
-            let connection_status = if last_tested_at.is_some() {
-                Some("connected".to_string()) // If it was tested before, assume connected
-            } else {
-                Some("unknown".to_string()) // Never tested
-            };
+            let (connection_status, connection_error) =
+                ConnectionHealth::reported(row.get("connection_status"), row.get("connection_error"));
             
             DatasourceResponse {
                 id: row.get("id"),
                 name: row.get("name"),
                 source_type: row.get("source_type"),
-                config: config_json,
+                config: response_config(config_json, reveal),
                 created_at: row.get::<chrono::DateTime<Utc>, _>("created_at").to_rfc3339(),
                 updated_at: row.get::<chrono::DateTime<Utc>, _>("updated_at").to_rfc3339(),
                 project_id: row.get("project_id"),
                 schema_info: schema_info_json,
                 connection_status,
-                connection_error: None, // TODO: Store connection errors in database
+                connection_error,
             }
         })
         .collect();
@@ -108,22 +128,48 @@ pub async fn create_datasource(
     let request_data: CreateDatasourceRequest = req.parse_json().await
         .map_err(|e| AppError::BadRequest(format!("Invalid JSON: {}", e)))?;
 
+    let created_datasource = insert_datasource(
+        &state.db_pool,
+        user_id,
+        is_current_user_root(depot),
+        &project_id,
+        request_data,
+    )
+    .await?;
+
+    res.status_code(StatusCode::CREATED);
+    res.render(Json(created_datasource));
+    Ok(())
+}
+
+/// Create a datasource in a project the user owns (any project for root),
+/// shared by the HTTP and WebSocket create paths
+pub(crate) async fn insert_datasource(
+    db_pool: &sqlx::PgPool,
+    user_id: Uuid,
+    is_root: bool,
+    project_id: &str,
+    request_data: CreateDatasourceRequest,
+) -> Result<DatasourceResponse, AppError> {
+    // Normalize and validate source_type
+    let normalized_source_type = validated_source_type(&request_data.source_type)?;
+
     // Validate project ownership (user owns project or is root)
-    let project_exists = if is_current_user_root(depot) {
+    let project_exists = if is_root {
         sqlx::query(
             "SELECT 1 FROM projects WHERE id = $1 AND deleted_at IS NULL"
         )
-        .bind(&project_id)
-        .fetch_optional(&state.db_pool)
+        .bind(project_id)
+        .fetch_optional(db_pool)
         .await
         .map_err(|e| AppError::InternalServerError(format!("Database error: {}", e)))?
     } else {
         sqlx::query(
             "SELECT 1 FROM projects WHERE id = $1 AND user_id = $2 AND deleted_at IS NULL"
         )
-        .bind(&project_id)
+        .bind(project_id)
         .bind(user_id)
-        .fetch_optional(&state.db_pool)
+        .fetch_optional(db_pool)
         .await
         .map_err(|e| AppError::InternalServerError(format!("Database error: {}", e)))?
     };
@@ -132,13 +178,7 @@ pub async fn create_datasource(
         return Err(AppError::NotFound("Project not found".to_string()));
     }
 
-    // Normalize and validate source_type
-    let normalized_source_type = normalize_database_type(&request_data.source_type);
-    
-    let valid_types = ["postgresql", "mysql", "clickhouse", "sqlite", "oracle", "sqlserver", "csv", "excel", "json"];
-    if !valid_types.contains(&normalized_source_type.as_str()) {
-        return Err(AppError::BadRequest(format!("Invalid source_type '{}'. Must be one of: {}. Common variations are automatically normalized (e.g., 'postgres' → 'postgresql', 'MSSQL' → 'sqlserver', 'TSV' → 'csv')", request_data.source_type, valid_types.join(", "))));
-    }
+    ensure_unique_datasource_name(db_pool, project_id, &request_data.name, None).await?;
 
     let datasource_id = Uuid::new_v4().to_string();
 
@@ -154,30 +194,25 @@ pub async fn create_datasource(
     .bind(&request_data.name)
     .bind(&normalized_source_type)
     .bind(&request_data.config)
-    .bind(&project_id)
+    .bind(project_id)
     .bind(now)
     .bind(now)
-    .execute(&state.db_pool)
+    .execute(db_pool)
     .await
-    .map_err(|e| AppError::InternalServerError(format!("Failed to create datasource: {}", e)))?;
+    .map_err(|e| name_conflict_or(e, &request_data.name, "Failed to create datasource"))?;
 
-    // Return the created datasource
-    let created_datasource = DatasourceResponse {
+    Ok(DatasourceResponse {
         id: datasource_id,
         name: request_data.name,
         source_type: normalized_source_type,
-        config: request_data.config,
+        config: redact_connection_config(&request_data.config),
         created_at: now.to_rfc3339(),
         updated_at: now.to_rfc3339(),
-        project_id,
+        project_id: project_id.to_string(),
         schema_info: None,
         connection_status: Some("unknown".to_string()),
         connection_error: None,
-    };
-
-    res.status_code(StatusCode::CREATED);
-    res.render(Json(created_datasource));
-    Ok(())
+    })
 }
 
 /// Update a datasource
@@ -192,8 +227,9 @@ pub async fn update_datasource(
     let datasource_id = req.param::<String>("datasource_id")
         .ok_or_else(|| AppError::BadRequest("Missing datasource_id".to_string()))?;
 
-    let request_data: UpdateDatasourceRequest = req.parse_json().await
+    let mut request_data: UpdateDatasourceRequest = req.parse_json().await
         .map_err(|e| AppError::BadRequest(format!("Invalid JSON: {}", e)))?;
+    let reveal = reveal_requested(req, depot)?;
 
     // Check if datasource exists and belongs to user's project (or user is root)
     let existing = if is_current_user_root(depot) {
@@ -225,83 +261,149 @@ pub async fn update_datasource(
         .map_err(|e| AppError::InternalServerError(format!("Database error: {}", e)))?
     };
 
-    let _existing_row = existing.ok_or_else(|| AppError::NotFound("Datasource not found".to_string()))?;
+    let existing_row = existing.ok_or_else(|| AppError::NotFound("Datasource not found".to_string()))?;
+
+    // Configs read from the API carry masked credentials; keep the stored ones
+    // as long as the config still points at the same server
+    if let Some(config) = request_data.config.as_mut() {
+        *config = restore_redacted_secrets(config, &existing_row.get::<Value, _>("connection_config"))
+            .map_err(AppError::BadRequest)?;
+    }
+
+    // A corrected source_type needs a config the new connector understands
+    let source_type_change = match &request_data.source_type {
+        Some(requested) => {
+            let new_type = normalize_database_type(requested);
+            if !VALID_SOURCE_TYPES.contains(&new_type.as_str()) {
+                return Err(AppError::BadRequest(format!("Invalid source_type '{}'. Must be one of: {}", requested, VALID_SOURCE_TYPES.join(", "))));
+            }
+            let current_type: String = existing_row.get("source_type");
+            if new_type != current_type {
+                let base_config = request_data.config.clone()
+                    .unwrap_or_else(|| existing_row.get("connection_config"));
+                let migrated = migrate_connection_config(&base_config, &current_type, &new_type)
+                    .map_err(AppError::BadRequest)?;
+                Some((new_type, migrated))
+            } else {
+                None
+            }
+        }
+        None => None,
+    };
 
-    if request_data.name.is_none() && request_data.config.is_none() {
+    if request_data.name.is_none() && request_data.config.is_none() && source_type_change.is_none() {
         return Err(AppError::BadRequest("No fields to update".to_string()));
     }
 
+    // Forms often resubmit the config unchanged; treating that as no config
+    // update keeps the warm connection pool and cached schema
+    let existing_config: Value = existing_row.get("connection_config");
+    let new_config = request_data
+        .config
+        .as_ref()
+        .filter(|config| connection_config_changed(&existing_config, config));
+    let config_changed = source_type_change.is_some() || new_config.is_some();
+
+    if let Some(name) = &request_data.name {
+        let project_id: String = existing_row.get("project_id");
+        ensure_unique_datasource_name(&state.db_pool, &project_id, name, Some(&datasource_id)).await?;
+    }
+
     let now = Utc::now();
     
     // Handle different update scenarios
-    let updated_row = match (&request_data.name, &request_data.config) {
-        (Some(name), Some(config)) => {
-            // When config changes, invalidate cache
-            sqlx::query(
-                "UPDATE data_sources SET name = $1, connection_config = $2, table_list = NULL, schema_info = NULL, updated_at = $3 WHERE id = $4 RETURNING *, connection_config as config, last_tested_at"
-            )
-            .bind(name)
-            .bind(config)
-            .bind(now)
-            .bind(&datasource_id)
-            .fetch_one(&state.db_pool)
-            .await
-            .map_err(|e| AppError::InternalServerError(format!("Failed to update datasource: {}", e)))?
-        },
-        (Some(name), None) => {
-            // Name-only update doesn't affect cache
-            sqlx::query(
-                "UPDATE data_sources SET name = $1, updated_at = $2 WHERE id = $3 RETURNING *, connection_config as config, last_tested_at"
-            )
-            .bind(name)
-            .bind(now)
-            .bind(&datasource_id)
-            .fetch_one(&state.db_pool)
-            .await
-            .map_err(|e| AppError::InternalServerError(format!("Failed to update datasource: {}", e)))?
-        },
-        (None, Some(config)) => {
-            // When config changes, invalidate cache
-            sqlx::query(
-                "UPDATE data_sources SET connection_config = $1, table_list = NULL, schema_info = NULL, updated_at = $2 WHERE id = $3 RETURNING *, connection_config as config, last_tested_at"
-            )
-            .bind(config)
-            .bind(now)
-            .bind(&datasource_id)
-            .fetch_one(&state.db_pool)
-            .await
-            .map_err(|e| AppError::InternalServerError(format!("Failed to update datasource: {}", e)))?
-        },
-        (None, None) => unreachable!(), // We already checked this case above
+    let updated_row = if let Some((source_type, config)) = &source_type_change {
+        // Type change: cached schema and table list belong to the old connector
+        sqlx::query(
+            "UPDATE data_sources SET name = COALESCE($1, name), source_type = $2, connection_config = $3, table_list = NULL, schema_info = NULL, schema_fetched_at = NULL, connection_status = NULL, connection_error = NULL, updated_at = $4 WHERE id = $5 RETURNING *, connection_config as config, last_tested_at"
+        )
+        .bind(&request_data.name)
+        .bind(source_type)
+        .bind(config)
+        .bind(now)
+        .bind(&datasource_id)
+        .fetch_one(&state.db_pool)
+        .await
+        .map_err(|e| name_conflict_or(e, request_data.name.as_deref().unwrap_or_default(), "Failed to update datasource"))?
+    } else {
+        match (&request_data.name, new_config) {
+            (Some(name), Some(config)) => {
+                // When config changes, invalidate cache
+                sqlx::query(
+                    "UPDATE data_sources SET name = $1, connection_config = $2, table_list = NULL, schema_info = NULL, schema_fetched_at = NULL, connection_status = NULL, connection_error = NULL, updated_at = $3 WHERE id = $4 RETURNING *, connection_config as config, last_tested_at"
+                )
+                .bind(name)
+                .bind(config)
+                .bind(now)
+                .bind(&datasource_id)
+                .fetch_one(&state.db_pool)
+                .await
+                .map_err(|e| name_conflict_or(e, request_data.name.as_deref().unwrap_or_default(), "Failed to update datasource"))?
+            },
+            (Some(name), None) => {
+                // Name-only update doesn't affect cache
+                sqlx::query(
+                    "UPDATE data_sources SET name = $1, updated_at = $2 WHERE id = $3 RETURNING *, connection_config as config, last_tested_at"
+                )
+                .bind(name)
+                .bind(now)
+                .bind(&datasource_id)
+                .fetch_one(&state.db_pool)
+                .await
+                .map_err(|e| name_conflict_or(e, request_data.name.as_deref().unwrap_or_default(), "Failed to update datasource"))?
+            },
+            (None, Some(config)) => {
+                // When config changes, invalidate cache
+                sqlx::query(
+                    "UPDATE data_sources SET connection_config = $1, table_list = NULL, schema_info = NULL, schema_fetched_at = NULL, connection_status = NULL, connection_error = NULL, updated_at = $2 WHERE id = $3 RETURNING *, connection_config as config, last_tested_at"
+                )
+                .bind(config)
+                .bind(now)
+                .bind(&datasource_id)
+                .fetch_one(&state.db_pool)
+                .await
+                .map_err(|e| name_conflict_or(e, request_data.name.as_deref().unwrap_or_default(), "Failed to update datasource"))?
+            },
+            (None, None) => {
+                // Only an identical config was submitted: nothing to write
+                sqlx::query("SELECT *, connection_config as config FROM data_sources WHERE id = $1")
+                    .bind(&datasource_id)
+                    .fetch_one(&state.db_pool)
+                    .await
+                    .map_err(|e| AppError::InternalServerError(format!("Database error: {}", e)))?
+            },
+        }
     };
 
     // Invalidate cache for this datasource
-    let cache = get_datasource_cache().await;
-    cache.invalidate(&datasource_id, None).await;
+    if config_changed || request_data.name.is_some() {
+        let cache = get_datasource_cache().await;
+        cache.invalidate(&datasource_id, None).await;
+    }
+
+    // Pools built from the old config would keep connecting to the old
+    // server, or with the old credentials
+    if config_changed {
+        drop_cached_connections(&datasource_id, &existing_config).await;
+    }
 
     // Return updated datasource
     let config_json: Value = updated_row.get("config");
     let schema_info_json: Option<Value> = updated_row.get("schema_info");
-    let last_tested_at: Option<chrono::DateTime<Utc>> = updated_row.get("last_tested_at");
-    
-    // Determine connection status
-    let connection_status = if last_tested_at.is_some() {
-        Some("connected".to_string())
-    } else {
-        Some("unknown".to_string())
-    };
+    let (connection_status, connection_error) =
+        ConnectionHealth::reported(updated_row.get("connection_status"), updated_row.get("connection_error"));
     
     let updated_datasource = DatasourceResponse {
         id: updated_row.get("id"),
         name: updated_row.get("name"),
         source_type: updated_row.get("source_type"),
-        config: config_json,
+        config: response_config(config_json, reveal),
         created_at: updated_row.get::<chrono::DateTime<Utc>, _>("created_at").to_rfc3339(),
         updated_at: updated_row.get::<chrono::DateTime<Utc>, _>("updated_at").to_rfc3339(),
         project_id: updated_row.get("project_id"),
         schema_info: schema_info_json,
         connection_status,
-        connection_error: None,
+        connection_error,
     };
 
     res.render(Json(updated_datasource));
@@ -324,7 +426,7 @@ pub async fn delete_datasource(
     let existing = if is_current_user_root(depot) {
         sqlx::query(
             r#"
-            SELECT ds.id 
+            SELECT ds.id, ds.connection_config
             FROM data_sources ds
             JOIN projects p ON ds.project_id = p.id
             WHERE ds.id = $1 AND ds.deleted_at IS NULL AND p.deleted_at IS NULL
@@ -337,7 +439,7 @@ pub async fn delete_datasource(
     } else {
         sqlx::query(
             r#"
-            SELECT ds.id 
+            SELECT ds.id, ds.connection_config
             FROM data_sources ds
             JOIN projects p ON ds.project_id = p.id
             WHERE ds.id = $1 AND p.user_id = $2 AND ds.deleted_at IS NULL AND p.deleted_at IS NULL
@@ -350,7 +452,8 @@ pub async fn delete_datasource(
         .map_err(|e| AppError::InternalServerError(format!("Database error: {}", e)))?
     };
 
-    let _existing_row = existing.ok_or_else(|| AppError::NotFound("Datasource not found".to_string()))?;
+    let existing_row = existing.ok_or_else(|| AppError::NotFound("Datasource not found".to_string()))?;
+    let existing_config: Value = existing_row.get("connection_config");
 
     // Soft delete the datasource
     sqlx::query(
@@ -362,14 +465,76 @@ pub async fn delete_datasource(
     .await
     .map_err(|e| AppError::InternalServerError(format!("Failed to delete datasource: {}", e)))?;
 
-    // Invalidate cache for this datasource
+    // Invalidate cache for this datasource and close its connections
     let cache = get_datasource_cache().await;
     cache.invalidate(&datasource_id, None).await;
+    drop_cached_connections(&datasource_id, &existing_config).await;
 
     res.status_code(StatusCode::NO_CONTENT);
     Ok(())
 }
 
+/// Reject a datasource name already used by another live datasource in the project.
+/// Names are compared case-insensitively, matching the partial unique index.
+async fn ensure_unique_datasource_name(
+    db_pool: &sqlx::PgPool,
+    project_id: &str,
+    name: &str,
+    exclude_id: Option<&str>,
+) -> Result<(), AppError> {
+    let conflicting: Option<String> = sqlx::query_scalar(
+        r#"
+        SELECT id FROM data_sources
+        WHERE project_id = $1 AND LOWER(name) = LOWER($2) AND deleted_at IS NULL
+          AND ($3::text IS NULL OR id <> $3)
+        LIMIT 1
+        "#
+    )
+    .bind(project_id)
+    .bind(name)
+    .bind(exclude_id)
+    .fetch_optional(db_pool)
+    .await
+    .map_err(|e| AppError::InternalServerError(format!("Database error: {}", e)))?;
+
+    match conflicting {
+        Some(id) => Err(AppError::Conflict(format!(
+            "A datasource named '{}' already exists in this project (id {}). Choose a different name.",
+            name, id
+        ))),
+        None => Ok(()),
+    }
+}
+
+/// Whether a submitted connection config differs from the stored one. The
+/// datasource id and owner tags stamped into configs by the server are ignored.
+fn connection_config_changed(stored: &Value, submitted: &Value) -> bool {
+    let settings = |config: &Value| {
+        let mut config = config.clone();
+        if let Some(obj) = config.as_object_mut() {
+            for key in ["id", "project_id", "client_id"] {
+                obj.remove(key);
+            }
+        }
+        config
+    };
+    settings(stored) != settings(submitted)
+}
+
+/// Map a unique violation on the datasource name index to a conflict; a concurrent
+/// request can still win the race after `ensure_unique_datasource_name` passed.
+pub(super) fn name_conflict_or(e: sqlx::Error, name: &str, context: &str) -> AppError {
+    if let sqlx::Error::Database(db_err) = &e {
+        if db_err.constraint() == Some("idx_data_sources_project_name_unique") {
+            return AppError::Conflict(format!(
+                "A datasource named '{}' already exists in this project. Choose a different name.",
+                name
+            ));
+        }
+    }
+    AppError::InternalServerError(format!("{}: {}", context, e))
+}
+
 /// Get a cached datasource with ownership validation
 pub async fn get_cached_datasource(
     datasource_id: &str,
@@ -388,7 +553,7 @@ pub async fn get_cached_datasource(
     let datasource_row = if is_root {
         sqlx::query(
             r#"
-            SELECT ds.id, ds.name, ds.source_type, ds.connection_config, ds.project_id, p.user_id
+            SELECT ds.id, ds.name, ds.source_type, ds.connection_config, ds.project_id, p.user_id, p.client_id
             FROM data_sources ds
             JOIN projects p ON ds.project_id = p.id
             WHERE ds.id = $1 AND ds.deleted_at IS NULL AND p.deleted_at IS NULL
@@ -401,7 +566,7 @@ pub async fn get_cached_datasource(
     } else {
         sqlx::query(
             r#"
-            SELECT ds.id, ds.name, ds.source_type, ds.connection_config, ds.project_id, p.user_id
+            SELECT ds.id, ds.name, ds.source_type, ds.connection_config, ds.project_id, p.user_id, p.client_id
             FROM data_sources ds
             JOIN projects p ON ds.project_id = p.id
             WHERE ds.id = $1 AND p.user_id = $2 AND ds.deleted_at IS NULL AND p.deleted_at IS NULL
@@ -417,8 +582,11 @@ pub async fn get_cached_datasource(
     let row = datasource_row.ok_or_else(|| AppError::NotFound("Datasource not found".to_string()))?;
     
     // Parse connection config
-    let connection_config: Value = row.get("connection_config");
+    let mut connection_config: Value = row.get("connection_config");
     let owner_user_id: Uuid = row.get("user_id");
+    let client_id: Uuid = row.get("client_id");
+    let project_id: String = row.get("project_id");
+    tag_connection_owner(&mut connection_config, Some(&client_id.to_string()), &project_id);
     
     // Create cached datasource
     let cached = CachedDatasource {
@@ -427,7 +595,7 @@ pub async fn get_cached_datasource(
         datasource_type: row.get("source_type"),
         connection_config,
         user_id: owner_user_id,
-        project_id: row.get("project_id"),
+        project_id,
         cached_at: std::time::Instant::now(),
     };
     
@@ -437,6 +605,107 @@ pub async fn get_cached_datasource(
     Ok(cached)
 }
 
+pub(super) const VALID_SOURCE_TYPES: [&str; 10] = ["postgresql", "mysql", "clickhouse", "sqlite", "oracle", "sqlserver", "mongodb", "csv", "excel", "json"];
+
+/// The normalized form of a requested source_type, if it is one we support
+pub(crate) fn validated_source_type(requested: &str) -> Result<String, AppError> {
+    let normalized = normalize_database_type(requested);
+    if !VALID_SOURCE_TYPES.contains(&normalized.as_str()) {
+        return Err(AppError::BadRequest(format!("Invalid source_type '{}'. Must be one of: {}. Common variations are automatically normalized (e.g., 'postgres' → 'postgresql', 'MSSQL' → 'sqlserver', 'TSV' → 'csv')", requested, VALID_SOURCE_TYPES.join(", "))));
+    }
+    Ok(normalized)
+}
+
+fn default_port(source_type: &str) -> Option<u64> {
+    match source_type {
+        "postgresql" => Some(5432),
+        "mysql" => Some(3306),
+        "clickhouse" => Some(8123),
+        "oracle" => Some(1521),
+        "sqlserver" => Some(1433),
+        "mongodb" => Some(27017),
+        _ => None,
+    }
+}
+
+fn url_schemes(source_type: &str) -> &'static [&'static str] {
+    match source_type {
+        "postgresql" => &["postgres", "postgresql"],
+        "mysql" => &["mysql", "mariadb"],
+        "clickhouse" => &["clickhouse", "http", "https", "tcp"],
+        "oracle" => &["oracle"],
+        "sqlserver" => &["sqlserver", "mssql", "jdbc:sqlserver"],
+        "sqlite" => &["sqlite", "file"],
+        "mongodb" => &["mongodb", "mongodb+srv"],
+        _ => &[],
+    }
+}
+
+/// Adapt a stored connection config to a corrected source_type.
+/// Default ports are swapped for the new type's default (custom ports are kept);
+/// configs that cannot work for the new type are rejected with guidance.
+fn migrate_connection_config(config: &Value, from: &str, to: &str) -> Result<Value, String> {
+    let is_file_type = |t: &str| matches!(t, "csv" | "excel" | "json");
+    if is_file_type(from) != is_file_type(to) {
+        return Err(format!(
+            "Cannot change a {} datasource to {}: file and database datasources use different configurations. Create a new datasource instead.",
+            from, to
+        ));
+    }
+    if is_file_type(to) {
+        // Uploaded files keep their path; the new connector parses them by type
+        return Ok(config.clone());
+    }
+
+    let check_url_scheme = |url: &str| -> Result<(), String> {
+        if let Some((scheme, _)) = url.split_once("://") {
+            if !url_schemes(to).contains(&scheme.to_lowercase().as_str()) {
+                return Err(format!(
+                    "The connection URL uses '{}://', which doesn't match {}. Provide a config with a {} URL (e.g. {}://...) or host/port fields.",
+                    scheme, to, to, url_schemes(to).first().unwrap_or(&to)
+                ));
+            }
+        }
+        Ok(())
+    };
+
+    match config {
+        Value::String(url) => {
+            check_url_scheme(url)?;
+            Ok(config.clone())
+        }
+        Value::Object(obj) => {
+            let mut obj = obj.clone();
+            if let Some(url) = obj.get("url").and_then(|v| v.as_str()) {
+                check_url_scheme(url)?;
+                return Ok(Value::Object(obj));
+            }
+
+            if to == "sqlite" {
+                if !obj.contains_key("path") {
+                    return Err("A sqlite datasource needs a 'path' (or 'url') in its config".to_string());
+                }
+                return Ok(Value::Object(obj));
+            }
+
+            if !obj.contains_key("host") {
+                return Err(format!("A {} datasource needs a 'host' (or 'url') in its config", to));
+            }
+
+            let port = obj.get("port").and_then(|v| {
+                v.as_u64().or_else(|| v.as_str().and_then(|s| s.parse::<u64>().ok()))
+            });
+            if port.is_none() || port == default_port(from) {
+                if let Some(new_port) = default_port(to) {
+                    obj.insert("port".to_string(), Value::from(new_port));
+                }
+            }
+            Ok(Value::Object(obj))
+        }
+        _ => Err("Connection config must be a URL string or an object".to_string()),
+    }
+}
+
 /// Normalize database type names to standard values
 pub fn normalize_database_type(input: &str) -> String {
     // Convert to lowercase and remove spaces, hyphens, underscores
@@ -465,6 +734,9 @@ pub fn normalize_database_type(input: &str) -> String {
         // SQL Server variations
         "sqlserver" | "mssql" | "microsoftsqlserver" | "microsoft" | "tsql" | "mssqlserver" => "sqlserver".to_string(),
 
+        // MongoDB variations
+        "mongodb" | "mongo" | "mongodbatlas" | "atlas" => "mongodb".to_string(),
+
         // CSV variations
         "csv" | "tsv" | "txt" | "delimited" => "csv".to_string(),
 
@@ -477,4 +749,131 @@ pub fn normalize_database_type(input: &str) -> String {
         // Return as-is if no match (will be caught by validation)
         _ => normalized,
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn resubmitted_config_does_not_count_as_a_change() {
+        let stored = json!({ "id": "ds-1", "host": "db", "port": 5432, "database": "app", "user": "u" });
+        // Same settings in a different key order, without the server-stamped id
+        let resubmitted = json!({ "database": "app", "user": "u", "host": "db", "port": 5432 });
+        assert!(!connection_config_changed(&stored, &resubmitted));
+
+        let moved = json!({ "database": "app", "user": "u", "host": "db-2", "port": 5432 });
+        assert!(connection_config_changed(&stored, &moved));
+        assert!(connection_config_changed(&json!("postgres://u@db/app"), &json!("postgres://u@db/other")));
+    }
+
+    #[test]
+    fn returned_configs_hide_passwords_unless_revealed() {
+        let stored = json!({ "host": "db", "user": "u", "password": "hunter2", "url": "postgres://u:hunter2@db/app" });
+        let listed = serde_json::to_string(&response_config(stored.clone(), false)).unwrap();
+        assert!(!listed.contains("hunter2"));
+        assert!(listed.contains("\"host\":\"db\""));
+
+        assert_eq!(response_config(stored.clone(), true), stored);
+
+        // Saving a config as it was listed doesn't overwrite the password with the mask
+        let resubmitted = restore_redacted_secrets(&response_config(stored.clone(), false), &stored).unwrap();
+        assert!(!connection_config_changed(&stored, &resubmitted));
+    }
+
+    fn assert_migrates(before: Value, from: &str, to: &str, after: Value) {
+        assert_eq!(migrate_connection_config(&before, from, to), Ok(after), "{} -> {}", from, to);
+    }
+
+    fn assert_rejected(before: Value, from: &str, to: &str, mentions: &str) {
+        let err = migrate_connection_config(&before, from, to).unwrap_err();
+        assert!(err.contains(mentions), "{} -> {}: {}", from, to, err);
+    }
+
+    #[test]
+    fn host_config_on_default_port_moves_to_new_default() {
+        assert_migrates(
+            json!({ "host": "db", "port": 3306, "database": "app", "username": "u" }),
+            "mysql",
+            "postgresql",
+            json!({ "host": "db", "port": 5432, "database": "app", "username": "u" }),
+        );
+    }
+
+    #[test]
+    fn host_config_with_string_default_port_moves_to_new_default() {
+        assert_migrates(
+            json!({ "host": "db", "port": "5432", "database": "app" }),
+            "postgresql",
+            "sqlserver",
+            json!({ "host": "db", "port": 1433, "database": "app" }),
+        );
+    }
+
+    #[test]
+    fn host_config_without_port_gains_new_default() {
+        assert_migrates(
+            json!({ "host": "db", "database": "app" }),
+            "postgresql",
+            "mysql",
+            json!({ "host": "db", "port": 3306, "database": "app" }),
+        );
+    }
+
+    #[test]
+    fn host_config_with_custom_port_keeps_it() {
+        assert_migrates(
+            json!({ "host": "db", "port": 6543, "database": "app" }),
+            "postgresql",
+            "mysql",
+            json!({ "host": "db", "port": 6543, "database": "app" }),
+        );
+    }
+
+    #[test]
+    fn url_string_config_is_kept_when_scheme_matches() {
+        assert_migrates(json!("mysql://u@db/app"), "postgresql", "mysql", json!("mysql://u@db/app"));
+        assert_rejected(json!("postgres://u@db/app"), "postgresql", "mysql", "'postgres://'");
+    }
+
+    #[test]
+    fn url_field_config_is_kept_when_scheme_matches() {
+        assert_migrates(
+            json!({ "url": "postgresql://u@db/app", "port": 3306 }),
+            "mysql",
+            "postgresql",
+            json!({ "url": "postgresql://u@db/app", "port": 3306 }),
+        );
+        assert_rejected(json!({ "url": "mysql://u@db/app" }), "mysql", "postgresql", "'mysql://'");
+    }
+
+    #[test]
+    fn sqlite_config_needs_a_path() {
+        assert_migrates(
+            json!({ "path": "/data/app.db" }),
+            "postgresql",
+            "sqlite",
+            json!({ "path": "/data/app.db" }),
+        );
+        assert_rejected(json!({ "host": "db", "port": 5432 }), "postgresql", "sqlite", "'path'");
+    }
+
+    #[test]
+    fn file_config_is_kept_between_file_types() {
+        assert_migrates(
+            json!({ "file_path": "uploads/sales.csv", "delimiter": "," }),
+            "csv",
+            "excel",
+            json!({ "file_path": "uploads/sales.csv", "delimiter": "," }),
+        );
+    }
+
+    #[test]
+    fn incompatible_configs_are_rejected() {
+        assert_rejected(json!({ "file_path": "uploads/sales.csv" }), "csv", "postgresql", "Create a new datasource");
+        assert_rejected(json!({ "host": "db" }), "postgresql", "json", "Create a new datasource");
+        assert_rejected(json!({ "database": "app" }), "postgresql", "mysql", "'host'");
+        assert_rejected(json!(["db", 5432]), "postgresql", "mysql", "URL string or an object");
+    }
+}
@@ -0,0 +1,67 @@
+use futures::future::join_all;
+use salvo::prelude::*;
+use serde_json::{json, Value};
+use sqlx::Row;
+
+use crate::core::datasources::health::check_datasource_health;
+use crate::utils::middleware::{get_current_user_id, is_current_user_root};
+use crate::utils::{get_app_state, AppError};
+
+/// Whether each datasource in a project can currently be queried through the
+/// connector path the MCP tools use. Pass `?refresh=true` to skip cached results.
+#[handler]
+pub async fn datasource_health(
+    req: &mut Request,
+    res: &mut Response,
+    depot: &mut Depot,
+) -> Result<(), AppError> {
+    let state = get_app_state(depot)?;
+    let user_id = get_current_user_id(depot)?;
+    let project_id = req.param::<String>("project_id")
+        .ok_or_else(|| AppError::BadRequest("Missing project_id".to_string()))?;
+    let refresh = req.query::<bool>("refresh").unwrap_or(false);
+
+    // Validate project ownership (user owns project or is root)
+    let project_exists: Option<i32> = sqlx::query_scalar(
+        "SELECT 1 FROM projects WHERE id = $1 AND ($2 OR user_id = $3) AND deleted_at IS NULL"
+    )
+    .bind(&project_id)
+    .bind(is_current_user_root(depot))
+    .bind(user_id)
+    .fetch_optional(&state.db_pool)
+    .await
+    .map_err(|e| AppError::InternalServerError(format!("Database error: {}", e)))?;
+    if project_exists.is_none() {
+        return Err(AppError::NotFound("Project not found".to_string()));
+    }
+
+    let rows = sqlx::query(
+        "SELECT id, name, source_type, connection_config FROM data_sources
+         WHERE project_id = $1 AND deleted_at IS NULL
+         ORDER BY name"
+    )
+    .bind(&project_id)
+    .fetch_all(&state.db_pool)
+    .await
+    .map_err(|e| AppError::InternalServerError(format!("Database error: {}", e)))?;
+
+    let datasources: Vec<(String, String, String, Value)> = rows
+        .iter()
+        .map(|row| (row.get("id"), row.get("name"), row.get("source_type"), row.get("connection_config")))
+        .collect();
+
+    // Probe concurrently so one slow datasource doesn't hold up the rest
+    let results = join_all(datasources.iter().map(|(id, name, source_type, config)| {
+        check_datasource_health(id, name, source_type, config, refresh)
+    }))
+    .await;
+
+    let usable = results.iter().filter(|r| r.usable).count();
+    res.render(Json(json!({
+        "project_id": project_id,
+        "usable": usable,
+        "unusable": results.len() - usable,
+        "datasources": results,
+    })));
+    Ok(())
+}
@@ -1,11 +1,19 @@
 // Module organization for datasources API
 pub mod types;
 pub mod crud;
+pub mod bulk;
 pub mod connection;
+pub mod health;
 pub mod schema;
 pub mod query;
+pub mod query_stream;
+pub mod reconnect;
 pub mod mutations;
 pub mod upload;
+pub mod column_views;
+pub mod schema_diff;
+pub mod schema_versions;
+pub mod slow_queries;
 
 use salvo::prelude::*;
 
@@ -17,18 +25,26 @@ pub fn datasource_routes() -> Router {
     Router::new()
         // Project-scoped routes
         .push(Router::with_path("/projects/{project_id}/datasources").get(crud::list_datasources).post(crud::create_datasource))
+        .push(Router::with_path("/projects/{project_id}/datasources/bulk").post(bulk::bulk_create_datasources))
+        .push(Router::with_path("/projects/{project_id}/datasources/health").get(health::datasource_health))
         // File upload routes
         .push(Router::with_path("/projects/{project_id}/datasources/upload").post(upload::upload_file_datasource))
         .push(Router::with_path("/projects/{project_id}/datasources/preview").post(upload::preview_file))
         // Datasource-specific routes
         .push(Router::with_path("/datasources/{datasource_id}").put(crud::update_datasource).delete(crud::delete_datasource))
         .push(Router::with_path("/datasources/{datasource_id}/test").post(connection::test_connection))
+        .push(Router::with_path("/datasources/{datasource_id}/reconnect").post(reconnect::reconnect_datasource))
         .push(Router::with_path("/datasources/{datasource_id}/schema").get(schema::get_schema))
+        .push(Router::with_path("/datasources/{datasource_id}/schema-diff").get(schema_diff::get_schema_diff))
+        .push(Router::with_path("/datasources/{datasource_id}/schema-versions").get(schema_versions::list_versions).post(schema_versions::create_version))
         // Data browser routes
         .push(Router::with_path("/datasources/{datasource_id}/query").post(query::execute_query))
+        .push(Router::with_path("/datasources/{datasource_id}/query/stream").post(query_stream::execute_query_stream))
+        .push(Router::with_path("/datasources/{datasource_id}/slow-queries").get(slow_queries::list_slow_queries_handler).delete(slow_queries::clear_slow_queries_handler))
         .push(Router::with_path("/datasources/{datasource_id}/tables").get(schema::get_tables))
         .push(Router::with_path("/datasources/{datasource_id}/tables/{table_name}/data").post(query::get_table_data))
         .push(Router::with_path("/datasources/{datasource_id}/tables/{table_name}/structure").get(schema::get_table_structure))
+        .push(Router::with_path("/datasources/{datasource_id}/tables/{table_name}/column-views").get(column_views::get_column_views).put(column_views::update_column_views))
         .push(Router::with_path("/datasources/{datasource_id}/tables/{table_name}/distinct").post(query::get_distinct_values))
         .push(Router::with_path("/datasources/{datasource_id}/tables/{table_name}/row-ids").post(query::get_table_row_ids))
         .push(Router::with_path("/datasources/{datasource_id}/tables/{table_name}/rows").delete(mutations::delete_rows).put(mutations::update_rows).post(mutations::insert_rows))
@@ -3,10 +3,19 @@ use serde_json::Value;
 
 use crate::utils::middleware::{get_current_user_id, is_current_user_root};
 use crate::utils::{get_app_state, AppError};
+use crate::utils::datasource::common::identifiers::{primary_keys, validate_column_name};
+use crate::utils::datasource::common::row_mutations::{
+    affected_rows_sample_query, delete_statements, dry_run_row_statements, execute_row_statements, insert_statements,
+    update_statements, validate_conflict_target, MutationDialect, MutationOutcome, OnConflict, RowStatement,
+};
+use crate::utils::datasource::{create_connector, get_pool_manager};
 
 use super::crud::get_cached_datasource;
 use super::types::{DeleteRowsRequest, UpdateRowsRequest, InsertRowsRequest};
 
+/// Affected rows returned with a dry run
+const DRY_RUN_SAMPLE_ROWS: usize = 20;
+
 
 /// Delete rows from a table
 #[handler]
@@ -41,11 +50,25 @@ pub async fn delete_rows(
 
     // Execute delete based on source type
     let result = match source_type.as_str() {
+        "postgresql" | "mysql" | "sqlite" if request_data.dry_run.unwrap_or(false) => {
+            let dialect = sql_dialect(&source_type)
+                .map_err(|e| AppError::BadRequest(e.to_string()))?;
+            let statements = delete_statements(dialect, &table_name, request_data.id_column.as_deref(), &request_data.row_ids);
+            let row_ids: Vec<&String> = request_data.row_ids.iter().collect();
+            preview_row_statements(res, &datasource_id, &config, &source_type, &table_name,
+                                   request_data.id_column.as_deref(), &row_ids, &statements).await
+                .map_err(|e| AppError::InternalServerError(format!("Delete dry run failed: {}", e)))?
+        },
         "postgresql" | "mysql" | "sqlite" => {
-            execute_delete_rows_query(&datasource_id, &config, &table_name, 
+            let outcome = execute_delete_rows_query(&datasource_id, &config, &table_name, 
                                     &request_data.row_ids,
-                                    request_data.id_column.as_deref(), &source_type).await
-                .map_err(|e| AppError::InternalServerError(format!("Delete execution failed: {}", e)))?
+                                    request_data.id_column.as_deref(), &source_type,
+                                    request_data.atomic.unwrap_or(true)).await
+                .map_err(|e| AppError::InternalServerError(format!("Delete execution failed: {}", e)))?;
+            mutation_response(res, outcome)
+        },
+        _ if request_data.dry_run.unwrap_or(false) => {
+            return Err(AppError::BadRequest(format!("dry_run is not supported for {} datasources", source_type)));
         },
         "clickhouse" => {
             execute_clickhouse_delete_rows_query(&datasource_id, &config, &table_name, 
@@ -107,11 +130,26 @@ pub async fn update_rows(
 
     // Execute update based on source type
     let result = match source_type.as_str() {
+        "postgresql" | "mysql" | "sqlite" if request_data.dry_run.unwrap_or(false) => {
+            let dialect = sql_dialect(&source_type)
+                .map_err(|e| AppError::BadRequest(e.to_string()))?;
+            let statements = update_statements(dialect, &table_name, request_data.id_column.as_deref(), &request_data.updates);
+            let mut row_ids: Vec<&String> = request_data.updates.keys().collect();
+            row_ids.sort();
+            preview_row_statements(res, &datasource_id, &config, &source_type, &table_name,
+                                   request_data.id_column.as_deref(), &row_ids, &statements).await
+                .map_err(|e| AppError::InternalServerError(format!("Update dry run failed: {}", e)))?
+        },
         "postgresql" | "mysql" | "sqlite" => {
-            execute_update_rows_query(&datasource_id, &config, &table_name, 
+            let outcome = execute_update_rows_query(&datasource_id, &config, &table_name, 
                                     &request_data.updates,
-                                    request_data.id_column.as_deref(), &source_type).await
-                .map_err(|e| AppError::InternalServerError(format!("Update execution failed: {}", e)))?
+                                    request_data.id_column.as_deref(), &source_type,
+                                    request_data.atomic.unwrap_or(true)).await
+                .map_err(|e| AppError::InternalServerError(format!("Update execution failed: {}", e)))?;
+            mutation_response(res, outcome)
+        },
+        _ if request_data.dry_run.unwrap_or(false) => {
+            return Err(AppError::BadRequest(format!("dry_run is not supported for {} datasources", source_type)));
         },
         "clickhouse" => {
             execute_clickhouse_update_rows_query(&datasource_id, &config, &table_name, 
@@ -164,6 +202,12 @@ pub async fn insert_rows(
     // Get datasource and verify ownership using cache
     let cached_datasource = get_cached_datasource(&datasource_id, &user_id, is_current_user_root(depot), &state.db_pool).await?;
     let source_type = cached_datasource.datasource_type.clone();
+
+    let on_conflict = request_data.on_conflict.unwrap_or_default();
+    let conflict_columns = request_data.conflict_columns.clone().unwrap_or_default();
+    if on_conflict != OnConflict::Error {
+        validate_conflict_columns(&state.db_pool, &datasource_id, &table_name, on_conflict, &conflict_columns, &request_data.rows).await?;
+    }
     let mut config = cached_datasource.connection_config.clone();
     
     // Add datasource ID to config for the connector
@@ -174,9 +218,15 @@ pub async fn insert_rows(
     // Execute insert based on source type
     let result = match source_type.as_str() {
         "postgresql" | "mysql" | "sqlite" => {
-            execute_insert_rows_query(&datasource_id, &config, &table_name, 
-                                    &request_data.rows, &source_type).await
-                .map_err(|e| AppError::InternalServerError(format!("Insert execution failed: {}", e)))?
+            let outcome = execute_insert_rows_query(&datasource_id, &config, &table_name, 
+                                    &request_data.rows, &source_type,
+                                    request_data.atomic.unwrap_or(true),
+                                    on_conflict, &conflict_columns).await
+                .map_err(|e| AppError::InternalServerError(format!("Insert execution failed: {}", e)))?;
+            mutation_response(res, outcome)
+        },
+        _ if on_conflict != OnConflict::Error => {
+            return Err(AppError::BadRequest(format!("on_conflict is not supported for {} datasources", source_type)));
         },
         "clickhouse" => {
             execute_clickhouse_insert_rows_query(&datasource_id, &config, &table_name, 
@@ -202,20 +252,102 @@ pub async fn insert_rows(
     Ok(())
 }
 
-// Helper functions - these are implementation stubs, the actual implementations
-// need to be moved from the original datasources.rs file
+/// Check the conflict columns of an upsert against the cached schema's
+/// primary key; tables missing from the cache are left to the database
+async fn validate_conflict_columns(
+    db_pool: &sqlx::PgPool,
+    datasource_id: &str,
+    table_name: &str,
+    on_conflict: OnConflict,
+    conflict_columns: &[String],
+    rows: &[std::collections::HashMap<String, Value>],
+) -> Result<(), AppError> {
+    let schema: Option<Value> = sqlx::query_scalar("SELECT schema_info FROM data_sources WHERE id = $1")
+        .bind(datasource_id)
+        .fetch_optional(db_pool)
+        .await
+        .map_err(|e| AppError::InternalServerError(format!("Database error: {}", e)))?
+        .flatten();
+
+    for column in conflict_columns {
+        validate_column_name(column, table_name, schema.as_ref()).map_err(AppError::BadRequest)?;
+    }
+    let keys = schema.as_ref().and_then(|schema| primary_keys(schema, table_name));
+    validate_conflict_target(on_conflict, conflict_columns, keys.as_deref(), rows).map_err(AppError::BadRequest)
+}
 
+/// Outcome as the response body; a batch rolled back because a row failed is
+/// reported as 422
+fn mutation_response(res: &mut Response, outcome: MutationOutcome) -> Value {
+    if !outcome.committed && !outcome.failures.is_empty() {
+        res.status_code(StatusCode::UNPROCESSABLE_ENTITY);
+    }
+    serde_json::to_value(outcome).unwrap_or_default()
+}
 
-async fn execute_delete_rows_query(
-    _datasource_id: &str,
-    _config: &Value,
-    _table_name: &str,
-    _row_ids: &[String],
-    _id_column: Option<&str>,
-    _source_type: &str
+/// Run `statements` in a transaction that is rolled back, and report how many
+/// rows they would affect along with a sample of those rows as they are now
+#[allow(clippy::too_many_arguments)]
+async fn preview_row_statements(
+    res: &mut Response,
+    datasource_id: &str,
+    config: &Value,
+    source_type: &str,
+    table_name: &str,
+    id_column: Option<&str>,
+    row_ids: &[&String],
+    statements: &[RowStatement],
 ) -> Result<Value, Box<dyn std::error::Error + Send + Sync>> {
-    // TODO: Move implementation from original datasources.rs
-    Ok(serde_json::json!({"success": true, "rows_affected": 0}))
+    let dialect = sql_dialect(source_type)?;
+    let connector = create_connector(source_type, config).await
+        .map_err(|e| format!("Failed to create connector: {}", e))?;
+    let sample_query = affected_rows_sample_query(dialect, table_name, id_column, row_ids, DRY_RUN_SAMPLE_ROWS);
+    let sample = connector.execute_query(&sample_query, DRY_RUN_SAMPLE_ROWS as i32).await
+        .map_err(|e| format!("Failed to sample affected rows: {}", e))?;
+
+    let pool = get_pool_manager().await.get_pool(datasource_id, source_type, config).await?;
+    let outcome = dry_run_row_statements(&pool, statements).await?;
+
+    let mut body = mutation_response(res, outcome);
+    body["sample"] = serde_json::json!({
+        "columns": sample.get("columns"),
+        "rows": sample.get("rows"),
+    });
+    Ok(body)
+}
+
+fn sql_dialect(source_type: &str) -> Result<MutationDialect, Box<dyn std::error::Error + Send + Sync>> {
+    MutationDialect::from_source_type(source_type)
+        .ok_or_else(|| format!("Row edits are not supported for {}", source_type).into())
+}
+
+async fn run_row_statements(
+    datasource_id: &str,
+    config: &Value,
+    source_type: &str,
+    statements: &[RowStatement],
+    atomic: bool,
+) -> Result<MutationOutcome, Box<dyn std::error::Error + Send + Sync>> {
+    let pool = get_pool_manager().await.get_pool(datasource_id, source_type, config).await?;
+    execute_row_statements(&pool, statements, atomic).await
+}
+
+// Helper functions - the remaining ones are implementation stubs, the actual
+// implementations need to be moved from the original datasources.rs file
+
+
+async fn execute_delete_rows_query(
+    datasource_id: &str,
+    config: &Value,
+    table_name: &str,
+    row_ids: &[String],
+    id_column: Option<&str>,
+    source_type: &str,
+    atomic: bool,
+) -> Result<MutationOutcome, Box<dyn std::error::Error + Send + Sync>> {
+    let dialect = sql_dialect(source_type)?;
+    let statements = delete_statements(dialect, table_name, id_column, row_ids);
+    run_row_statements(datasource_id, config, source_type, &statements, atomic).await
 }
 
 async fn execute_clickhouse_delete_rows_query(
@@ -252,15 +384,17 @@ async fn execute_sqlserver_delete_rows_query(
 }
 
 async fn execute_update_rows_query(
-    _datasource_id: &str,
-    _config: &Value,
-    _table_name: &str,
-    _updates: &std::collections::HashMap<String, std::collections::HashMap<String, Value>>,
-    _id_column: Option<&str>,
-    _source_type: &str
-) -> Result<Value, Box<dyn std::error::Error + Send + Sync>> {
-    // TODO: Move implementation from original datasources.rs
-    Ok(serde_json::json!({"success": true, "rows_affected": 0}))
+    datasource_id: &str,
+    config: &Value,
+    table_name: &str,
+    updates: &std::collections::HashMap<String, std::collections::HashMap<String, Value>>,
+    id_column: Option<&str>,
+    source_type: &str,
+    atomic: bool,
+) -> Result<MutationOutcome, Box<dyn std::error::Error + Send + Sync>> {
+    let dialect = sql_dialect(source_type)?;
+    let statements = update_statements(dialect, table_name, id_column, updates);
+    run_row_statements(datasource_id, config, source_type, &statements, atomic).await
 }
 
 async fn execute_clickhouse_update_rows_query(
@@ -296,15 +430,20 @@ async fn execute_sqlserver_update_rows_query(
     Ok(serde_json::json!({"success": true, "rows_affected": 0}))
 }
 
+#[allow(clippy::too_many_arguments)]
 async fn execute_insert_rows_query(
-    _datasource_id: &str,
-    _config: &Value,
-    _table_name: &str,
-    _rows: &[std::collections::HashMap<String, Value>],
-    _source_type: &str
-) -> Result<Value, Box<dyn std::error::Error + Send + Sync>> {
-    // TODO: Move implementation from original datasources.rs
-    Ok(serde_json::json!({"success": true, "rows_affected": 0}))
+    datasource_id: &str,
+    config: &Value,
+    table_name: &str,
+    rows: &[std::collections::HashMap<String, Value>],
+    source_type: &str,
+    atomic: bool,
+    on_conflict: OnConflict,
+    conflict_columns: &[String],
+) -> Result<MutationOutcome, Box<dyn std::error::Error + Send + Sync>> {
+    let dialect = sql_dialect(source_type)?;
+    let statements = insert_statements(dialect, table_name, rows, on_conflict, conflict_columns);
+    run_row_statements(datasource_id, config, source_type, &statements, atomic).await
 }
 
 async fn execute_clickhouse_insert_rows_query(
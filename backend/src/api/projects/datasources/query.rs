@@ -1,13 +1,106 @@
 use salvo::prelude::*;
 use serde_json::Value;
 
+use crate::core::datasources::query_history::{record_query, ExecutedQuery};
+use crate::core::datasources::slow_queries::{record_if_slow, result_row_count, SlowQuery};
 use crate::utils::middleware::{get_current_user_id, is_current_user_root};
 use crate::utils::{get_app_state, AppError};
-use crate::utils::datasource::{create_connector, get_pool_manager};
+use crate::utils::datasource::create_connector;
+use crate::utils::datasource::common::error_handling::{ConnectorError, ConnectorErrorKind};
+use crate::utils::datasource::common::identifiers::{known_columns, validate_column_name, validate_table_name};
+use crate::utils::datasource::common::projection::{project_result_columns, ColumnRequest, Projection};
+use crate::utils::datasource::common::timeouts::{is_query_timeout, with_query_timeout};
+use crate::utils::datasource::connectors::cell_value::stringify_rows;
+use crate::utils::datasource::connectors::common::sample_query_sources;
+use crate::utils::datasource::connectors::table_filters::{TableFilters, UnknownFilterColumns};
+use crate::utils::datasource::connectors::table_keyset::{keyset_key, next_cursor};
+use crate::utils::datasource::connectors::table_sort::{check_sort_columns, sort_keys, SortKey};
 
 use super::crud::get_cached_datasource;
 use super::types::{QueryRequest, TableDataRequest, DistinctValuesRequest, RowIdsRequest};
 
+const DEFAULT_PREVIEW_SAMPLE_ROWS: i32 = 1000;
+const MAX_PREVIEW_SAMPLE_ROWS: i32 = 100_000;
+
+/// Map a connector failure to a normalized, dialect-independent API error.
+/// Queries that outran their timeout are a 504; connection timeouts stay a 503.
+pub(crate) fn connector_query_error(error: &(dyn std::error::Error + 'static)) -> AppError {
+    if is_query_timeout(error) {
+        return AppError::GatewayTimeout(format!("Query timed out: {}", error));
+    }
+    if let Some(unknown) = error.downcast_ref::<UnknownFilterColumns>() {
+        return AppError::BadRequest(unknown.to_string());
+    }
+    let classified = ConnectorError::from_error(error);
+    let message = format!("Query execution failed: {}", classified);
+    match classified.kind {
+        ConnectorErrorKind::SyntaxError => AppError::BadRequest(message),
+        ConnectorErrorKind::Timeout | ConnectorErrorKind::HostUnreachable => AppError::ServiceUnavailable(message),
+        _ => AppError::InternalServerError(message),
+    }
+}
+
+/// Reject table and column names that could change the SQL they are spliced
+/// into. Names that aren't plain identifiers must appear in the datasource's
+/// cached schema, which is only loaded when needed.
+async fn validate_identifiers(
+    db_pool: &sqlx::PgPool,
+    datasource_id: &str,
+    table_name: &str,
+    columns: &[&str],
+) -> Result<(), AppError> {
+    let all_plain = validate_table_name(table_name, None).is_ok()
+        && columns.iter().all(|column| validate_column_name(column, table_name, None).is_ok());
+    if all_plain {
+        return Ok(());
+    }
+
+    let schema: Option<Value> = sqlx::query_scalar("SELECT schema_info FROM data_sources WHERE id = $1")
+        .bind(datasource_id)
+        .fetch_optional(db_pool)
+        .await
+        .map_err(|e| AppError::InternalServerError(format!("Database error: {}", e)))?
+        .flatten();
+
+    validate_table_name(table_name, schema.as_ref()).map_err(AppError::BadRequest)?;
+    for column in columns {
+        validate_column_name(column, table_name, schema.as_ref()).map_err(AppError::BadRequest)?;
+    }
+    Ok(())
+}
+
+/// Columns the datasource's cached schema lists for the table, or `None` when
+/// the table is missing from the cache and the connector has to check names
+async fn cached_table_columns(
+    db_pool: &sqlx::PgPool,
+    datasource_id: &str,
+    table_name: &str,
+) -> Result<Option<Vec<String>>, AppError> {
+    let schema: Option<Value> = sqlx::query_scalar("SELECT schema_info FROM data_sources WHERE id = $1")
+        .bind(datasource_id)
+        .fetch_optional(db_pool)
+        .await
+        .map_err(|e| AppError::InternalServerError(format!("Database error: {}", e)))?
+        .flatten();
+
+    Ok(schema.as_ref().and_then(|schema| known_columns(schema, table_name)))
+}
+
+/// The columns to select for a table page: the projected ones plus any sort
+/// columns, which the next cursor is read from. `None` selects every column.
+fn page_select_columns(projection: &Projection, sort: &[SortKey]) -> Option<Vec<String>> {
+    if projection.selects_all {
+        return None;
+    }
+    let mut columns = projection.columns.clone();
+    for key in sort {
+        if !columns.contains(&key.column) {
+            columns.push(key.column.clone());
+        }
+    }
+    Some(columns)
+}
+
 /// Execute a custom query on a datasource
 #[handler]
 #[allow(dead_code)]
@@ -40,9 +133,72 @@ pub async fn execute_query(
         .await
         .map_err(|e| AppError::InternalServerError(format!("Failed to create connector: {}", e)))?;
 
-    // Execute query using connector
-    let result = connector.execute_query(&request_data.query, 1000000).await
-        .map_err(|e| AppError::InternalServerError(format!("Query execution failed: {}", e)))?;
+    // In preview mode every source table is swapped for a small sample so
+    // expensive queries can be checked for correctness cheaply
+    let preview = request_data.preview.unwrap_or(false);
+    let sample_rows = request_data.sample_rows
+        .unwrap_or(DEFAULT_PREVIEW_SAMPLE_ROWS)
+        .clamp(1, MAX_PREVIEW_SAMPLE_ROWS);
+    let (query, sampled_tables) = if preview {
+        let lower = request_data.query.trim_start().to_ascii_lowercase();
+        if !lower.starts_with("select") && !lower.starts_with("with") {
+            return Err(AppError::BadRequest("Preview mode only supports SELECT queries".to_string()));
+        }
+        sample_query_sources(&request_data.query, sample_rows, connector.limit_syntax(), connector.identifier_quote())
+    } else {
+        (request_data.query.clone(), 0)
+    };
+
+    // Execute query using connector, capped at the configured max page size
+    // and the datasource's result budget
+    let limit = state.config.effective_page_size(request_data.limit, state.config.max_page_size);
+    let budget = state.config.result_budget.for_datasource(&cached_datasource.connection_config);
+    let query_start = std::time::Instant::now();
+    let timeouts = state.config.datasource_timeouts.for_datasource(&cached_datasource.connection_config);
+    let mut result = with_query_timeout(timeouts.query, connector.execute_query(&query, budget.fetch_limit(Some(limit)))).await
+        .map_err(|e| connector_query_error(&*e))?;
+    budget.apply(&mut result);
+
+    // Previews run against samples, so their timing says little about the real query
+    if !preview {
+        record_query(&state.db_pool, ExecutedQuery {
+            datasource_id: datasource_id.clone(),
+            project_id: cached_datasource.project_id.clone(),
+            source: "rest",
+            query: query.clone(),
+            duration: query_start.elapsed(),
+            row_count: result_row_count(&result),
+        });
+        record_if_slow(&state.db_pool, &state.config.slow_query, &source_type, &cached_datasource.connection_config, SlowQuery {
+            datasource_id: datasource_id.clone(),
+            project_id: cached_datasource.project_id.clone(),
+            source: "rest",
+            query: query.clone(),
+            duration: query_start.elapsed(),
+            row_count: result_row_count(&result),
+        });
+    }
+
+    if request_data.stringify.unwrap_or(false) {
+        stringify_rows(&mut result);
+    }
+
+    if let Some(obj) = result.as_object_mut() {
+        obj.insert("page_size".to_string(), Value::from(limit));
+        if preview {
+            obj.insert("preview".to_string(), Value::Bool(true));
+            obj.insert("sample_rows_per_table".to_string(), Value::from(sample_rows));
+            obj.insert("sampled_tables".to_string(), Value::from(sampled_tables));
+            obj.insert("executed_query".to_string(), Value::String(query.clone()));
+            obj.insert(
+                "note".to_string(),
+                Value::String(format!(
+                    "Preview ran against at most {} rows per source table; results may not reflect the full data",
+                    sample_rows
+                )),
+            );
+        }
+    }
 
     res.render(Json(result));
     Ok(())
@@ -75,13 +231,49 @@ pub async fn get_table_data(
     let db_query_time = db_query_start.elapsed().as_millis();
     tracing::info!("Datasource validation query took {}ms", db_query_time);
 
+    let sort = sort_keys(
+        request_data.sort.as_deref(),
+        request_data.sort_column.as_deref(),
+        request_data.sort_direction.as_deref(),
+        request_data.nulls,
+    );
+    let mut referenced_columns: Vec<&str> = sort.iter().map(|key| key.column.as_str()).collect();
+    if let Some(filters) = request_data.filters.as_ref().and_then(|f| f.as_object()) {
+        referenced_columns.extend(filters.keys().map(String::as_str));
+    }
+    validate_identifiers(&state.db_pool, &datasource_id, &table_name, &referenced_columns).await?;
+
+    // Sort keys, filters and the column projection are checked against the
+    // cached schema, so only the chosen columns are selected from the table
+    let filters = TableFilters::from_value(request_data.filters.as_ref());
+    let table_columns = cached_table_columns(&state.db_pool, &datasource_id, &table_name).await?;
+    if let Some(columns) = &table_columns {
+        check_sort_columns(&sort, columns).map_err(AppError::BadRequest)?;
+        filters.check_columns(columns).map_err(|e| AppError::BadRequest(e.to_string()))?;
+    }
+    let projection = table_columns
+        .as_deref()
+        .map(|available| {
+            ColumnRequest {
+                requested: request_data.columns.as_deref(),
+                max_columns: state.config.max_result_columns,
+            }
+            .plan(available)
+        })
+        .transpose()
+        .map_err(AppError::BadRequest)?;
+    let select_columns = projection.as_ref().and_then(|projection| page_select_columns(projection, &sort));
+
     let source_type = cached_datasource.datasource_type.clone();
     let mut config = cached_datasource.connection_config.clone();
     
     // Add datasource ID to config for the connector
-    config.as_object_mut()
-        .ok_or_else(|| AppError::InternalServerError("Invalid config format".to_string()))?
-        .insert("id".to_string(), Value::String(datasource_id.clone()));
+    let config_obj = config.as_object_mut()
+        .ok_or_else(|| AppError::InternalServerError("Invalid config format".to_string()))?;
+    config_obj.insert("id".to_string(), Value::String(datasource_id.clone()));
+    if let Some(approximate) = request_data.approximate_count {
+        config_obj.insert("approximate_count".to_string(), Value::Bool(approximate));
+    }
 
     // Create connector using factory
     let connector = create_connector(&source_type, &config)
@@ -90,17 +282,65 @@ pub async fn get_table_data(
 
     // Get pagination parameters
     let page = request_data.page.unwrap_or(1);
-    let limit = request_data.limit.unwrap_or(50);
-
-    // Execute table data query using connector
-    let result = connector.get_table_data_with_pagination(
-        &table_name, 
-        page, 
-        limit, 
-        request_data.sort_column.as_deref(), 
-        request_data.sort_direction.as_deref()
-    ).await
-        .map_err(|e| AppError::InternalServerError(format!("Query execution failed: {}", e)))?;
+    let limit = state.config.effective_page_size(request_data.limit, state.config.default_page_size);
+
+    // Execute table data query using connector, narrowed by column filters and the global search
+    let timeouts = state.config.datasource_timeouts.for_datasource(&cached_datasource.connection_config);
+    let cursor = request_data.cursor.as_ref().filter(|c| !c.is_null());
+    let mut result = if let Some(cursor) = cursor {
+        // Keyset mode: continue after the cursor instead of skipping `page` rows
+        let key = keyset_key(&sort).map_err(AppError::BadRequest)?;
+        if !connector.supports_cursor_pagination() {
+            return Err(AppError::BadRequest(format!(
+                "Cursor pagination is not supported for {} datasources",
+                source_type
+            )));
+        }
+        with_query_timeout(timeouts.query, connector.get_table_data_after_cursor(
+            &table_name,
+            limit,
+            key,
+            cursor,
+            &filters,
+            select_columns.as_deref(),
+        )).await
+            .map_err(|e| connector_query_error(&*e))?
+    } else {
+        with_query_timeout(timeouts.query, connector.get_filtered_table_data(
+            &table_name, 
+            page, 
+            limit, 
+            &sort,
+            &filters,
+            select_columns.as_deref(),
+        )).await
+            .map_err(|e| connector_query_error(&*e))?
+    };
+
+    // Computed before projection, which may drop the sort column
+    let next_page_cursor = match sort.as_slice() {
+        [key] if connector.supports_cursor_pagination() => next_cursor(&result, &key.column, limit),
+        _ => Value::Null,
+    };
+
+    // Drop the sort columns selected only for the cursor. Tables missing from
+    // the schema cache are projected after the fetch instead, which still keeps
+    // pathologically wide tables manageable.
+    match &projection {
+        Some(projection) => {
+            if select_columns.is_some() {
+                project_result_columns(&mut result, Some(&projection.columns), usize::MAX)
+                    .map_err(AppError::BadRequest)?;
+            }
+            projection.annotate(&mut result);
+        }
+        None => project_result_columns(&mut result, request_data.columns.as_deref(), state.config.max_result_columns)
+            .map_err(AppError::BadRequest)?,
+    }
+
+    if request_data.stringify.unwrap_or(false) {
+        stringify_rows(&mut result);
+    }
 
     // Convert result format to match expected response structure
     let formatted_result = if let Some(columns) = result.get("columns") {
@@ -110,6 +350,10 @@ pub async fn get_table_data(
                     "columns": columns,
                     "data": rows,
                     "total": total_rows,
+                    "approximate_total": result.get("approximate_total").cloned().unwrap_or(Value::Bool(false)),
+                    "total_columns": result.get("total_columns"),
+                    "columns_truncated": result.get("columns_truncated").cloned().unwrap_or(Value::Bool(false)),
+                    "note": result.get("note"),
                     "execution_time_ms": result.get("execution_time_ms"),
                     "timing_breakdown": result.get("timing_breakdown")
                 })
@@ -118,6 +362,9 @@ pub async fn get_table_data(
                     "columns": columns,
                     "data": rows,
                     "total": 0,
+                    "total_columns": result.get("total_columns"),
+                    "columns_truncated": result.get("columns_truncated").cloned().unwrap_or(Value::Bool(false)),
+                    "note": result.get("note"),
                     "execution_time_ms": result.get("execution_time_ms")
                 })
             }
@@ -150,6 +397,8 @@ pub async fn get_table_data(
             .unwrap_or(0) as u128;
         let api_overhead = total_time.saturating_sub(db_execution_time);
         result_obj.insert("api_overhead_ms".to_string(), Value::Number(serde_json::Number::from(api_overhead as u64)));
+        result_obj.insert("page_size".to_string(), Value::from(limit));
+        result_obj.insert("next_cursor".to_string(), next_page_cursor);
         
         res.render(Json(Value::Object(result_obj)));
     } else {
@@ -181,12 +430,20 @@ pub async fn get_distinct_values(
     let source_type = cached_datasource.datasource_type.clone();
     let config = cached_datasource.connection_config.clone();
 
+    validate_identifiers(&state.db_pool, &datasource_id, &table_name, &[request_data.column.as_str()]).await?;
+
+    let limit = state.config.effective_page_size(request_data.limit, state.config.default_distinct_values);
+
     // Execute distinct values query using pool manager
-    let result = execute_distinct_values_query(&datasource_id, &config, &table_name, 
+    let mut result = execute_distinct_values_query(&datasource_id, &config, &table_name, 
                                         &request_data.column, 
-                                        request_data.limit,
+                                        limit,
                                         request_data.search.as_deref(), &source_type).await
-        .map_err(|e| AppError::InternalServerError(format!("Query execution failed: {}", e)))?;
+        .map_err(|e| connector_query_error(&*e))?;
+
+    if let Some(obj) = result.as_object_mut() {
+        obj.insert("page_size".to_string(), Value::from(limit));
+    }
 
     let total_time = request_start.elapsed().as_millis();
     tracing::info!("Distinct values request took {}ms", total_time);
@@ -195,14 +452,13 @@ pub async fn get_distinct_values(
     Ok(())
 }
 
-// Execute distinct values query using connection pool
-#[allow(dead_code)]
+// Execute distinct values query through the datasource's connector
 async fn execute_distinct_values_query(
-    _datasource_id: &str,
+    datasource_id: &str,
     config: &Value,
     table_name: &str,
     column_name: &str,
-    limit: Option<i32>,
+    limit: i32,
     search: Option<&str>,
     source_type: &str
 ) -> Result<Value, Box<dyn std::error::Error + Send + Sync>> {
@@ -210,13 +466,9 @@ async fn execute_distinct_values_query(
     use std::time::Instant;
     
     let pool_start = Instant::now();
-    let _pool_manager = get_pool_manager().await;
-    
-    // For now, fall back to using the connector approach
-    // This maintains backward compatibility while we transition
     let mut config_with_id = config.clone();
     if let Some(obj) = config_with_id.as_object_mut() {
-        obj.insert("id".to_string(), Value::String(_datasource_id.to_string()));
+        obj.insert("id".to_string(), Value::String(datasource_id.to_string()));
     }
     let connector = create_connector(source_type, &config_with_id).await
         .map_err(|e| format!("Failed to create connector: {}", e))?;
@@ -224,27 +476,12 @@ async fn execute_distinct_values_query(
     let pool_time = pool_start.elapsed().as_millis() as u64;
     let start = Instant::now();
     
-    // Execute query using connector
-    let result = connector.execute_query(
-        &build_distinct_values_query(source_type, table_name, column_name, limit, search),
-        1000000
-    ).await
-        .map_err(|e| format!("Query execution failed: {}", e))?;
+    let values = connector
+        .get_distinct_values(table_name, column_name, search, limit)
+        .await?;
     
     let execution_time_ms = start.elapsed().as_millis() as u64;
     
-    // Extract values from result
-    let mut values = Vec::new();
-    if let Some(data) = result.get("data").and_then(|d| d.as_array()) {
-        for row in data {
-            if let Some(obj) = row.as_object() {
-                if let Some(val) = obj.values().next().and_then(|v| v.as_str()) {
-                    values.push(val.to_string());
-                }
-            }
-        }
-    }
-    
     Ok(json!({
         "values": values,
         "count": values.len(),
@@ -256,32 +493,6 @@ async fn execute_distinct_values_query(
     }))
 }
 
-// Helper function to build distinct values query
-fn build_distinct_values_query(
-    source_type: &str,
-    table_name: &str,
-    column_name: &str,
-    limit: Option<i32>,
-    search: Option<&str>,
-) -> String {
-    let limit_val = limit.unwrap_or(100);
-    let mut query = format!("SELECT DISTINCT {} FROM {}", column_name, table_name);
-    
-    if let Some(search_term) = search {
-        if !search_term.is_empty() {
-            let like_operator = match source_type.to_lowercase().as_str() {
-                "postgresql" | "postgres" => "ILIKE",
-                "mysql" | "sqlite" => "LIKE",
-                _ => "LIKE"
-            };
-            query.push_str(&format!(" WHERE {} {} '%{}%'", column_name, like_operator, search_term));
-        }
-    }
-    
-    query.push_str(&format!(" LIMIT {}", limit_val));
-    query
-}
-
 /// Get all row IDs for a table (for bulk selection)
 #[handler]
 pub async fn get_table_row_ids(
@@ -304,6 +515,9 @@ pub async fn get_table_row_ids(
     let cached_datasource = get_cached_datasource(&datasource_id, &user_id, is_current_user_root(depot), &state.db_pool).await?;
     let source_type = cached_datasource.datasource_type.clone();
     let mut config = cached_datasource.connection_config.clone();
+
+    let requested_id_column: Vec<&str> = request_data.id_column.iter().map(String::as_str).collect();
+    validate_identifiers(&state.db_pool, &datasource_id, &table_name, &requested_id_column).await?;
     
     // Add datasource ID to config for the connector
     config.as_object_mut()
@@ -323,7 +537,7 @@ pub async fn get_table_row_ids(
     
     // For now, let's try a more robust approach - get the first column
     // This matches what the table data query does
-    let query = format!("SELECT * FROM {} LIMIT 1", table_name);
+    let query = connector.apply_limit(&format!("SELECT * FROM {}", table_name), 1);
     let structure_result = connector.execute_query(&query, 1).await
         .map_err(|e| AppError::InternalServerError(format!("Failed to get table structure: {}", e)))?;
     
@@ -335,12 +549,12 @@ pub async fn get_table_row_ids(
         }
     }
     
-    let actual_query = format!("SELECT {} FROM {} LIMIT {}", id_column, table_name, limit);
+    let actual_query = connector.apply_limit(&format!("SELECT {} FROM {}", id_column, table_name), limit);
 
     // Execute the actual query using connector
     tracing::info!("Executing query: {}", actual_query);
     let result = connector.execute_query(&actual_query, limit).await
-        .map_err(|e| AppError::InternalServerError(format!("Query execution failed: {}", e)))?;
+        .map_err(|e| connector_query_error(&*e))?;
 
     // Extract row IDs from result
     let mut row_ids = Vec::new();
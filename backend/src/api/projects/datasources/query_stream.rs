@@ -0,0 +1,79 @@
+// Streaming query results as newline-delimited JSON
+
+use futures::StreamExt;
+use salvo::http::HeaderValue;
+use salvo::hyper::body::Bytes;
+use salvo::prelude::*;
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+use crate::utils::datasource::core::row_stream::{batch_size, ndjson_lines};
+use crate::utils::datasource::common::timeouts::with_query_timeout;
+use crate::utils::datasource::create_connector;
+use crate::utils::middleware::{get_current_user_id, is_current_user_root};
+use crate::utils::{get_app_state, AppError};
+
+use super::crud::get_cached_datasource;
+use super::query::connector_query_error;
+
+#[derive(Debug, Deserialize)]
+pub struct StreamQueryRequest {
+    pub query: String,
+    pub limit: Option<i32>, // No limit unless given
+    pub batch_size: Option<usize>, // Rows read per batch, at most 10,000
+}
+
+/// Run a query and stream its rows to the client as NDJSON, one object per
+/// row, without holding the whole result in memory. The query timeout covers
+/// starting the query; a failure after rows were sent ends the stream with an
+/// `{"error": ...}` line.
+#[handler]
+pub async fn execute_query_stream(
+    req: &mut Request,
+    res: &mut Response,
+    depot: &mut Depot,
+) -> Result<(), AppError> {
+    let state = get_app_state(depot)?;
+    let user_id = get_current_user_id(depot)?;
+    let datasource_id = req.param::<String>("datasource_id")
+        .ok_or_else(|| AppError::BadRequest("Missing datasource_id".to_string()))?;
+
+    let request_data: StreamQueryRequest = req.parse_json().await
+        .map_err(|e| AppError::BadRequest(format!("Invalid JSON: {}", e)))?;
+    if let Some(limit) = request_data.limit.filter(|limit| *limit < 1) {
+        return Err(AppError::BadRequest(format!("limit must be positive, got {}", limit)));
+    }
+
+    let cached_datasource = get_cached_datasource(&datasource_id, &user_id, is_current_user_root(depot), &state.db_pool).await?;
+    let mut config = cached_datasource.connection_config.clone();
+    config.as_object_mut()
+        .ok_or_else(|| AppError::InternalServerError("Invalid config format".to_string()))?
+        .insert("id".to_string(), Value::String(datasource_id.clone()));
+
+    let connector = create_connector(&cached_datasource.datasource_type, &config)
+        .await
+        .map_err(|e| AppError::InternalServerError(format!("Failed to create connector: {}", e)))?;
+
+    let timeouts = state.config.datasource_timeouts.for_datasource(&cached_datasource.connection_config);
+    let batches = with_query_timeout(
+        timeouts.query,
+        connector.execute_query_stream(&request_data.query, request_data.limit, batch_size(request_data.batch_size)),
+    )
+    .await
+    .map_err(|e| connector_query_error(&*e))?;
+
+    let body = batches.map(|batch| {
+        let lines = match batch {
+            Ok(batch) => ndjson_lines(&batch),
+            Err(e) => format!("{}\n", json!({ "error": format!("Query execution failed: {}", e) })),
+        };
+        Ok::<_, std::io::Error>(Bytes::from(lines))
+    });
+
+    res.headers_mut().insert(
+        "Content-Type",
+        HeaderValue::from_static("application/x-ndjson; charset=utf-8"),
+    );
+    res.stream(body);
+    Ok(())
+}
@@ -0,0 +1,60 @@
+use salvo::prelude::*;
+use serde_json::Value;
+
+use crate::core::datasources::cache::get_datasource_cache;
+use crate::utils::datasource::pooling::clickhouse_client_pool::get_clickhouse_client_pool;
+use crate::utils::datasource::get_pool_manager;
+use crate::utils::middleware::{get_current_user_id, is_current_user_root};
+use crate::utils::{get_app_state, AppError};
+
+use super::crud::get_cached_datasource;
+
+/// Drop the cached pools and clients of a datasource so the next query
+/// connects with its current config. Returns how many pools were closed.
+pub(crate) async fn drop_cached_connections(datasource_id: &str, config: &Value) -> usize {
+    get_clickhouse_client_pool().await.remove_client(datasource_id, config).await;
+    get_pool_manager().await.remove_datasource_pools(datasource_id).await
+}
+
+/// Close a datasource's cached connections and open a new pool, e.g. after
+/// its credentials were rotated on the server
+#[handler]
+pub async fn reconnect_datasource(
+    req: &mut Request,
+    res: &mut Response,
+    depot: &mut Depot,
+) -> Result<(), AppError> {
+    let state = get_app_state(depot)?;
+    let user_id = get_current_user_id(depot)?;
+    let datasource_id = req.param::<String>("datasource_id")
+        .ok_or_else(|| AppError::BadRequest("Missing datasource_id".to_string()))?;
+
+    // Re-read the config from the database rather than trusting the cache
+    get_datasource_cache().await.invalidate(&datasource_id, None).await;
+    let datasource = get_cached_datasource(&datasource_id, &user_id, is_current_user_root(depot), &state.db_pool).await?;
+
+    let pools_closed = drop_cached_connections(&datasource_id, &datasource.connection_config).await;
+
+    // Only SQL datasources are pooled by the pool manager; the others
+    // connect on their next query
+    let pooled = matches!(
+        datasource.datasource_type.to_lowercase().as_str(),
+        "postgresql" | "postgres" | "mysql" | "sqlite"
+    );
+    let error = if pooled {
+        get_pool_manager().await
+            .get_pool(&datasource_id, &datasource.datasource_type, &datasource.connection_config)
+            .await
+            .err()
+    } else {
+        None
+    };
+
+    res.render(Json(serde_json::json!({
+        "datasource_id": datasource_id,
+        "pools_closed": pools_closed,
+        "reconnected": error.is_none(),
+        "error": error,
+    })));
+    Ok(())
+}
@@ -2,9 +2,12 @@ use salvo::prelude::*;
 use serde_json::Value;
 use sqlx::Row;
 
+use crate::core::datasources::schema_events::notify_schema_refreshed;
+use crate::utils::datasource::common::schema_shape::find_table_entry;
 use crate::utils::middleware::{get_current_user_id, is_current_user_root};
 use crate::utils::{get_app_state, AppError};
 
+use super::column_views::load_column_views;
 use super::crud::get_cached_datasource;
 use super::types::TableStructure;
 
@@ -103,36 +106,12 @@ pub async fn get_tables(
         .ok_or_else(|| AppError::InternalServerError("Invalid config format".to_string()))?
         .insert("id".to_string(), Value::String(datasource_id.clone()));
 
-    // Get tables based on source type using cached connection pools
-    let result = match source_type.as_str() {
-        "postgresql" | "mysql" | "sqlite" => {
-            list_tables(&datasource_id, &config, &source_type).await
-                .map_err(|e| {
-                    tracing::error!("❌ Failed to list tables for datasource {}: {}", datasource_id, e);
-                    AppError::InternalServerError(format!("Failed to list tables: {}", e))
-                })?
-        },
-        "clickhouse" => {
-            list_clickhouse_tables(&datasource_id, &config).await
-                .map_err(|e| AppError::InternalServerError(format!("Failed to list tables: {}", e)))?
-        },
-        "oracle" => {
-            list_oracle_tables(&datasource_id, &config).await
-                .map_err(|e| AppError::InternalServerError(format!("Failed to list tables: {}", e)))?
-        },
-        "sqlserver" => {
-            list_sqlserver_tables(&datasource_id, &config).await
-                .map_err(|e| AppError::InternalServerError(format!("Failed to list tables: {}", e)))?
-        },
-        "csv" | "excel" | "json" => {
-            // For file datasources, use the connector factory directly
-            list_file_tables(&datasource_id, &config, &source_type).await
-                .map_err(|e| AppError::InternalServerError(format!("Failed to list tables: {}", e)))?
-        },
-        _ => {
-            return Err(AppError::BadRequest(format!("Unsupported datasource type: {}", source_type)));
-        }
-    };
+    // Every dialect, files included, lists its tables through the connector
+    let result = list_tables(&config, &source_type).await
+        .map_err(|e| {
+            tracing::error!("❌ Failed to list tables for datasource {}: {}", datasource_id, e);
+            AppError::InternalServerError(format!("Failed to list tables: {}", e))
+        })?;
 
     // Update the table_list in database
     let table_list_json = serde_json::to_value(&result)
@@ -183,6 +162,9 @@ pub async fn get_table_structure(
                             // Check if the cached structure has actual data (not empty columns)
                             if !structure.columns.is_empty() {
                                 println!("DEBUG: Returning cached table structure with {} columns", structure.columns.len());
+                                let mut structure = structure;
+                                structure.column_views = load_column_views(&state.db_pool, &datasource_id, &table_name).await
+                                    .map_err(|e| AppError::InternalServerError(format!("Database error: {}", e)))?;
                                 res.render(Json(structure));
                                 return Ok(());
                             }
@@ -213,45 +195,22 @@ pub async fn get_table_structure(
 
     println!("DEBUG: Config after adding ID: {:?}", config);
     
-    // Get table structure based on source type using cached connection pools
+    // Postgres has a detailed implementation (indexes, precision); everything else
+    // goes through the connector's schema introspection
     let result = match source_type.as_str() {
-        "postgresql" => {
-            get_postgres_table_structure(&datasource_id, &config, &table_name).await
-                .map_err(|e| AppError::InternalServerError(format!("Failed to get table structure: {}", e)))?
-        },
-        "mysql" => {
-            get_mysql_table_structure(&datasource_id, &config, &table_name).await
-                .map_err(|e| AppError::InternalServerError(format!("Failed to get table structure: {}", e)))?
-        },
-        "sqlite" => {
-            get_sqlite_table_structure(&datasource_id, &config, &table_name).await
-                .map_err(|e| AppError::InternalServerError(format!("Failed to get table structure: {}", e)))?
-        },
-        "clickhouse" => {
-            get_clickhouse_table_structure(&datasource_id, &config, &table_name).await
-                .map_err(|e| AppError::InternalServerError(format!("Failed to get table structure: {}", e)))?
-        },
-        "oracle" => {
-            get_oracle_table_structure(&datasource_id, &config, &table_name).await
-                .map_err(|e| AppError::InternalServerError(format!("Failed to get table structure: {}", e)))?
-        },
-        "sqlserver" => {
-            get_sqlserver_table_structure(&datasource_id, &config, &table_name).await
-                .map_err(|e| AppError::InternalServerError(format!("Failed to get table structure: {}", e)))?
-        },
-        "csv" | "excel" | "json" => {
-            // For file datasources, use the connector factory directly
-            get_file_table_structure(&datasource_id, &config, &source_type, &table_name).await
-                .map_err(|e| AppError::InternalServerError(format!("Failed to get table structure: {}", e)))?
-        },
-        _ => {
-            return Err(AppError::BadRequest(format!("Unsupported datasource type: {}", source_type)));
-        }
-    };
+        "postgresql" => get_postgres_table_structure(&datasource_id, &config, &table_name).await,
+        _ => get_connector_table_structure(&config, &source_type, &table_name).await,
+    }
+    .map_err(|e| AppError::InternalServerError(format!("Failed to get table structure: {}", e)))?;
 
     // Update schema_info with the new table structure
     update_schema_info_with_table_structure(&state.db_pool, &datasource_id, &table_name, &result).await
         .map_err(|e| AppError::InternalServerError(format!("Failed to update schema info: {}", e)))?;
+    notify_schema_refreshed(&state.db_pool, &datasource_id).await;
+
+    let mut result = result;
+    result.column_views = load_column_views(&state.db_pool, &datasource_id, &table_name).await
+        .map_err(|e| AppError::InternalServerError(format!("Database error: {}", e)))?;
 
     res.render(Json(result));
     Ok(())
@@ -297,84 +256,16 @@ pub async fn update_schema_info_with_table_structure(
 }
 
 async fn list_tables(
-    _datasource_id: &str,
     config: &Value,
     source_type: &str,
 ) -> Result<Vec<String>, Box<dyn std::error::Error + Send + Sync>> {
     use crate::utils::datasource::create_connector;
 
-    // Create connector using factory
     let connector = create_connector(source_type, config).await
         .map_err(|e| -> Box<dyn std::error::Error + Send + Sync> {
             Box::new(std::io::Error::other(e.to_string()))
         })?;
 
-    // Get tables using connector's list_tables method
-    connector.list_tables().await
-}
-
-async fn list_clickhouse_tables(
-    _datasource_id: &str,
-    config: &Value,
-) -> Result<Vec<String>, Box<dyn std::error::Error + Send + Sync>> {
-    use crate::utils::datasource::create_connector;
-
-    // Create connector using factory
-    let connector = create_connector("clickhouse", config).await
-        .map_err(|e| -> Box<dyn std::error::Error + Send + Sync> {
-            Box::new(std::io::Error::other(e.to_string()))
-        })?;
-
-    // Get tables using connector's list_tables method
-    connector.list_tables().await
-}
-
-async fn list_oracle_tables(
-    _datasource_id: &str,
-    config: &Value,
-) -> Result<Vec<String>, Box<dyn std::error::Error + Send + Sync>> {
-    use crate::utils::datasource::create_connector;
-
-    // Create connector using factory
-    let connector = create_connector("oracle", config).await
-        .map_err(|e| -> Box<dyn std::error::Error + Send + Sync> {
-            Box::new(std::io::Error::other(e.to_string()))
-        })?;
-
-    // Get tables using connector's list_tables method
-    connector.list_tables().await
-}
-
-async fn list_sqlserver_tables(
-    _datasource_id: &str,
-    config: &Value,
-) -> Result<Vec<String>, Box<dyn std::error::Error + Send + Sync>> {
-    use crate::utils::datasource::create_connector;
-
-    // Create connector using factory
-    let connector = create_connector("sqlserver", config).await
-        .map_err(|e| -> Box<dyn std::error::Error + Send + Sync> {
-            Box::new(std::io::Error::other(e.to_string()))
-        })?;
-
-    // Get tables using connector's list_tables method
-    connector.list_tables().await
-}
-
-async fn list_file_tables(
-    _datasource_id: &str,
-    config: &Value,
-    source_type: &str,
-) -> Result<Vec<String>, Box<dyn std::error::Error + Send + Sync>> {
-    use crate::utils::datasource::create_connector;
-
-    // Create connector using factory
-    let connector = create_connector(source_type, config).await
-        .map_err(|e| -> Box<dyn std::error::Error + Send + Sync> {
-            Box::new(std::io::Error::other(e.to_string()))
-        })?;
-
-    // Get tables using connector's list_tables method
     connector.list_tables().await
 }
 
@@ -553,147 +444,160 @@ async fn get_postgres_table_structure(
         primary_keys,
         foreign_keys,
         indexes,
+        column_views: Vec::new(),
     })
 }
 
-async fn get_mysql_table_structure(
-    _datasource_id: &str,
-    _config: &Value,
-    _table_name: &str,
-) -> Result<TableStructure, Box<dyn std::error::Error + Send + Sync>> {
-    // TODO: Move implementation from original datasources.rs
-    Ok(TableStructure {
-        table_name: _table_name.to_string(),
-        columns: vec![],
-        primary_keys: vec![],
-        foreign_keys: vec![],
-        indexes: vec![],
-    })
-}
-
-async fn get_sqlite_table_structure(
-    _datasource_id: &str,
-    _config: &Value,
-    _table_name: &str,
-) -> Result<TableStructure, Box<dyn std::error::Error + Send + Sync>> {
-    // TODO: Move implementation from original datasources.rs
-    Ok(TableStructure {
-        table_name: _table_name.to_string(),
-        columns: vec![],
-        primary_keys: vec![],
-        foreign_keys: vec![],
-        indexes: vec![],
-    })
-}
-
-async fn get_clickhouse_table_structure(
-    _datasource_id: &str,
-    _config: &Value,
-    _table_name: &str,
-) -> Result<TableStructure, Box<dyn std::error::Error + Send + Sync>> {
-    // TODO: Move implementation from original datasources.rs
-    Ok(TableStructure {
-        table_name: _table_name.to_string(),
-        columns: vec![],
-        primary_keys: vec![],
-        foreign_keys: vec![],
-        indexes: vec![],
-    })
-}
-
-async fn get_oracle_table_structure(
-    _datasource_id: &str,
-    _config: &Value,
-    _table_name: &str,
-) -> Result<TableStructure, Box<dyn std::error::Error + Send + Sync>> {
-    // TODO: Move implementation from original datasources.rs
-    Ok(TableStructure {
-        table_name: _table_name.to_string(),
-        columns: vec![],
-        primary_keys: vec![],
-        foreign_keys: vec![],
-        indexes: vec![],
-    })
-}
-
-async fn get_sqlserver_table_structure(
-    _datasource_id: &str,
-    _config: &Value,
-    _table_name: &str,
-) -> Result<TableStructure, Box<dyn std::error::Error + Send + Sync>> {
-    // TODO: Move implementation from original datasources.rs
-    Ok(TableStructure {
-        table_name: _table_name.to_string(),
-        columns: vec![],
-        primary_keys: vec![],
-        foreign_keys: vec![],
-        indexes: vec![],
-    })
-}
-
-async fn get_file_table_structure(
-    _datasource_id: &str,
+/// Build a table structure from the connector's `get_tables_schema` output.
+/// Connectors differ in shape (keyed by table, `{"tables": ...}`, or an array of
+/// `{name, columns}`) and in column key names, so both are read tolerantly.
+async fn get_connector_table_structure(
     config: &Value,
     source_type: &str,
     table_name: &str,
 ) -> Result<TableStructure, Box<dyn std::error::Error + Send + Sync>> {
-    use super::types::TableColumn;
     use crate::utils::datasource::create_connector;
 
-    // Create connector using factory
     let connector = create_connector(source_type, config).await
         .map_err(|e| -> Box<dyn std::error::Error + Send + Sync> {
             Box::new(std::io::Error::other(e.to_string()))
         })?;
 
-    // Get schema from connector
-    let schema = connector.fetch_schema().await
-        .map_err(|e| -> Box<dyn std::error::Error + Send + Sync> {
-            Box::new(std::io::Error::other(e.to_string()))
-        })?;
+    let schema = connector.get_tables_schema(vec![table_name]).await?;
+    let table = match find_table_entry(&schema, table_name) {
+        Some(table) => table.clone(),
+        None => {
+            // Some connectors only describe their tables through fetch_schema
+            let full_schema = connector.fetch_schema().await?;
+            find_table_entry(&full_schema, table_name)
+                .cloned()
+                .ok_or_else(|| format!("Table '{}' not found in schema", table_name))?
+        }
+    };
 
-    // Extract table structure from schema
-    if let Some(tables) = schema.get("tables") {
-        if let Some(table_data) = tables.get(table_name) {
-            // Extract columns from the table data
-            let columns_array = table_data.get("columns")
-                .and_then(|c| c.as_array())
-                .unwrap_or(&serde_json::json!([]));
-
-            let mut columns = Vec::new();
-            for col_data in columns_array {
-                if let Some(col_obj) = col_data.as_object() {
-                    columns.push(TableColumn {
-                        name: col_obj.get("column_name")
-                            .and_then(|s| s.as_str())
-                            .unwrap_or("unknown").to_string(),
-                        data_type: col_obj.get("data_type")
-                            .and_then(|s| s.as_str())
-                            .unwrap_or("text").to_string(),
-                        is_nullable: col_obj.get("is_nullable")
-                            .and_then(|s| s.as_str())
-                            .unwrap_or("YES") == "YES",
-                        column_default: None,
-                        is_primary_key: false, // File datasources don't have primary keys
-                        is_foreign_key: false, // File datasources don't have foreign keys
-                        character_maximum_length: None,
-                        numeric_precision: None,
-                        numeric_scale: None,
-                    });
-                }
-            }
+    Ok(parse_table_structure(table_name, &table))
+}
 
-            Ok(TableStructure {
-                table_name: table_name.to_string(),
-                columns,
-                primary_keys: vec![],
-                foreign_keys: vec![],
-                indexes: vec![],
-            })
-        } else {
-            Err(format!("Table '{}' not found in schema", table_name).into())
+fn parse_table_structure(table_name: &str, table: &Value) -> TableStructure {
+    use super::types::{ForeignKeyInfo, TableColumn};
+
+    let str_field = |obj: &Value, keys: &[&str]| -> Option<String> {
+        keys.iter().find_map(|k| obj.get(*k).and_then(|v| v.as_str()).map(|s| s.to_string()))
+    };
+    let int_field = |obj: &Value, keys: &[&str]| -> Option<i32> {
+        keys.iter().find_map(|k| obj.get(*k).and_then(|v| v.as_i64()).map(|n| n as i32))
+    };
+
+    let mut primary_keys: Vec<String> = table.get("primary_keys")
+        .and_then(|v| v.as_array())
+        .map(|keys| keys.iter().filter_map(|k| k.as_str().map(|s| s.to_string())).collect())
+        .unwrap_or_default();
+
+    let foreign_keys: Vec<ForeignKeyInfo> = table.get("foreign_keys")
+        .and_then(|v| v.as_array())
+        .map(|fks| {
+            fks.iter()
+                .filter_map(|fk| {
+                    Some(ForeignKeyInfo {
+                        column_name: str_field(fk, &["column", "column_name"])?,
+                        referenced_table: str_field(fk, &["references_table", "referenced_table"])?,
+                        referenced_column: str_field(fk, &["references_column", "referenced_column"])?,
+                    })
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+
+    let mut columns = Vec::new();
+    for col in table.get("columns").and_then(|c| c.as_array()).into_iter().flatten() {
+        let Some(name) = str_field(col, &["name", "column_name"]) else {
+            continue;
+        };
+        let is_nullable = match col.get("nullable").or_else(|| col.get("is_nullable")) {
+            Some(Value::Bool(b)) => *b,
+            Some(Value::String(s)) => s.eq_ignore_ascii_case("yes") || s.eq_ignore_ascii_case("y"),
+            _ => true,
+        };
+        let is_primary_key = col.get("primary_key").and_then(|v| v.as_bool()).unwrap_or(false)
+            || col.get("key").and_then(|v| v.as_str()) == Some("PRI")
+            || primary_keys.contains(&name);
+        if is_primary_key && !primary_keys.contains(&name) {
+            primary_keys.push(name.clone());
         }
-    } else {
-        Err("No tables found in schema".into())
+
+        columns.push(TableColumn {
+            is_foreign_key: foreign_keys.iter().any(|fk| fk.column_name == name),
+            data_type: str_field(col, &["type", "data_type"]).unwrap_or_else(|| "text".to_string()),
+            column_default: str_field(col, &["default", "column_default"]),
+            character_maximum_length: int_field(col, &["max_length", "character_maximum_length"]),
+            numeric_precision: int_field(col, &["numeric_precision"]),
+            numeric_scale: int_field(col, &["numeric_scale"]),
+            name,
+            is_nullable,
+            is_primary_key,
+        });
     }
-}
\ No newline at end of file
+
+    TableStructure {
+        table_name: table_name.to_string(),
+        columns,
+        primary_keys,
+        foreign_keys,
+        indexes: vec![],
+        column_views: Vec::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn parses_table_keyed_schema() {
+        let schema = json!({
+            "orders": {
+                "columns": [
+                    {"name": "id", "type": "int", "nullable": false, "key": "PRI"},
+                    {"name": "customer_id", "type": "int", "nullable": true, "default": null}
+                ],
+                "primary_keys": [],
+                "foreign_keys": [
+                    {"column": "customer_id", "references_table": "customers", "references_column": "id"}
+                ]
+            }
+        });
+
+        let table = find_table_entry(&schema, "orders").expect("table entry");
+        let structure = parse_table_structure("orders", table);
+        assert_eq!(structure.primary_keys, vec!["id".to_string()]);
+        assert!(structure.columns[0].is_primary_key);
+        assert!(!structure.columns[0].is_nullable);
+        assert!(structure.columns[1].is_foreign_key);
+        assert_eq!(structure.foreign_keys[0].referenced_table, "customers");
+    }
+
+    #[test]
+    fn finds_tables_in_nested_and_array_shapes() {
+        let clickhouse = json!({
+            "tables": {
+                "default.events": {"name": "default.events", "table": "events", "columns": [
+                    {"name": "ts", "type": "DateTime", "nullable": false}
+                ]}
+            }
+        });
+        assert!(find_table_entry(&clickhouse, "events").is_some());
+        assert!(find_table_entry(&clickhouse, "default.events").is_some());
+
+        let oracle = json!([{"name": "EMP", "columns": [{"name": "ID", "type": "NUMBER", "nullable": false}]}]);
+        let structure = parse_table_structure("EMP", find_table_entry(&oracle, "EMP").unwrap());
+        assert_eq!(structure.columns[0].data_type, "NUMBER");
+
+        let files = json!({"tables": {"sheet": {"columns": [
+            {"column_name": "a", "data_type": "text", "is_nullable": "YES"}
+        ]}}});
+        let structure = parse_table_structure("sheet", find_table_entry(&files, "sheet").unwrap());
+        assert_eq!(structure.columns[0].name, "a");
+        assert!(structure.columns[0].is_nullable);
+    }
+}
@@ -0,0 +1,33 @@
+use salvo::prelude::*;
+
+use crate::core::datasources::schema_diff::stored_schema_diff;
+use crate::utils::middleware::{get_current_user_id, is_current_user_root};
+use crate::utils::{get_app_state, AppError};
+
+use super::crud::get_cached_datasource;
+
+/// What changed between the datasource's last two schema inspections
+#[handler]
+pub async fn get_schema_diff(
+    req: &mut Request,
+    res: &mut Response,
+    depot: &mut Depot,
+) -> Result<(), AppError> {
+    let state = get_app_state(depot)?;
+    let user_id = get_current_user_id(depot)?;
+    let datasource_id = req.param::<String>("datasource_id")
+        .ok_or_else(|| AppError::BadRequest("Missing datasource_id".to_string()))?;
+
+    get_cached_datasource(&datasource_id, &user_id, is_current_user_root(depot), &state.db_pool).await?;
+
+    let diff = stored_schema_diff(&state.db_pool, &datasource_id).await
+        .map_err(|e| AppError::InternalServerError(format!("Database error: {}", e)))?;
+
+    res.render(Json(serde_json::json!({
+        "datasource_id": datasource_id,
+        "has_previous_snapshot": diff.is_some(),
+        "changed": diff.as_ref().is_some_and(|diff| !diff.is_empty()),
+        "diff": diff
+    })));
+    Ok(())
+}
@@ -0,0 +1,56 @@
+use salvo::prelude::*;
+
+use crate::core::analysis::schema_pins::{list_schema_versions, snapshot_schema};
+use crate::core::datasources::shared_service::get_datasource_with_validation;
+use crate::utils::middleware::{get_current_user_id, is_current_user_root};
+use crate::utils::{get_app_state, AppError};
+
+use super::crud::get_cached_datasource;
+
+/// Schema versions recorded for a datasource, newest first
+#[handler]
+pub async fn list_versions(
+    req: &mut Request,
+    res: &mut Response,
+    depot: &mut Depot,
+) -> Result<(), AppError> {
+    let state = get_app_state(depot)?;
+    let user_id = get_current_user_id(depot)?;
+    let datasource_id = req.param::<String>("datasource_id")
+        .ok_or_else(|| AppError::BadRequest("Missing datasource_id".to_string()))?;
+
+    get_cached_datasource(&datasource_id, &user_id, is_current_user_root(depot), &state.db_pool).await?;
+
+    let versions = list_schema_versions(&state.db_pool, &datasource_id).await
+        .map_err(|e| AppError::InternalServerError(format!("Database error: {}", e)))?;
+
+    res.render(Json(serde_json::json!({
+        "datasource_id": datasource_id,
+        "versions": versions
+    })));
+    Ok(())
+}
+
+/// Snapshot the live schema; an unchanged schema returns its existing version
+#[handler]
+pub async fn create_version(
+    req: &mut Request,
+    res: &mut Response,
+    depot: &mut Depot,
+) -> Result<(), AppError> {
+    let state = get_app_state(depot)?;
+    let user_id = get_current_user_id(depot)?;
+    let datasource_id = req.param::<String>("datasource_id")
+        .ok_or_else(|| AppError::BadRequest("Missing datasource_id".to_string()))?;
+
+    let cached = get_cached_datasource(&datasource_id, &user_id, is_current_user_root(depot), &state.db_pool).await?;
+    let datasource = get_datasource_with_validation(&datasource_id, &cached.project_id, &state.db_pool).await
+        .map_err(|e| AppError::NotFound(e.to_string()))?;
+
+    let version = snapshot_schema(&state.db_pool, &datasource).await
+        .map_err(|e| AppError::InternalServerError(format!("Failed to snapshot schema: {}", e)))?;
+
+    res.status_code(StatusCode::CREATED);
+    res.render(Json(version));
+    Ok(())
+}
@@ -0,0 +1,61 @@
+use salvo::prelude::*;
+
+use crate::core::datasources::slow_queries::{clear_slow_queries, list_slow_queries};
+use crate::utils::middleware::{get_current_user_id, is_current_user_root};
+use crate::utils::{get_app_state, AppError};
+
+use super::crud::get_cached_datasource;
+
+const DEFAULT_SLOW_QUERY_LIMIT: i64 = 50;
+const MAX_SLOW_QUERY_LIMIT: i64 = 500;
+
+/// Slow queries logged for a datasource; `?sort=recent` orders by time instead of duration
+#[handler]
+pub async fn list_slow_queries_handler(
+    req: &mut Request,
+    res: &mut Response,
+    depot: &mut Depot,
+) -> Result<(), AppError> {
+    let state = get_app_state(depot)?;
+    let user_id = get_current_user_id(depot)?;
+    let datasource_id = req.param::<String>("datasource_id")
+        .ok_or_else(|| AppError::BadRequest("Missing datasource_id".to_string()))?;
+    let limit = req.query::<i64>("limit")
+        .unwrap_or(DEFAULT_SLOW_QUERY_LIMIT)
+        .clamp(1, MAX_SLOW_QUERY_LIMIT);
+    let by_duration = req.query::<String>("sort").as_deref() != Some("recent");
+
+    get_cached_datasource(&datasource_id, &user_id, is_current_user_root(depot), &state.db_pool).await?;
+
+    let queries = list_slow_queries(&state.db_pool, &datasource_id, limit, by_duration).await
+        .map_err(|e| AppError::InternalServerError(format!("Database error: {}", e)))?;
+
+    res.render(Json(serde_json::json!({
+        "datasource_id": datasource_id,
+        "threshold_ms": state.config.slow_query.threshold.as_millis() as u64,
+        "capture_plan": state.config.slow_query.capture_plan,
+        "queries": queries
+    })));
+    Ok(())
+}
+
+/// Clear the slow query log of a datasource
+#[handler]
+pub async fn clear_slow_queries_handler(
+    req: &mut Request,
+    res: &mut Response,
+    depot: &mut Depot,
+) -> Result<(), AppError> {
+    let state = get_app_state(depot)?;
+    let user_id = get_current_user_id(depot)?;
+    let datasource_id = req.param::<String>("datasource_id")
+        .ok_or_else(|| AppError::BadRequest("Missing datasource_id".to_string()))?;
+
+    get_cached_datasource(&datasource_id, &user_id, is_current_user_root(depot), &state.db_pool).await?;
+
+    let removed = clear_slow_queries(&state.db_pool, &datasource_id).await
+        .map_err(|e| AppError::InternalServerError(format!("Database error: {}", e)))?;
+
+    res.render(Json(serde_json::json!({ "datasource_id": datasource_id, "removed": removed })));
+    Ok(())
+}
@@ -1,6 +1,9 @@
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
+use crate::utils::datasource::common::row_mutations::OnConflict;
+use crate::utils::datasource::connectors::table_sort::{NullsOrder, SortKey};
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct CreateDatasourceRequest {
     pub name: String,
@@ -12,9 +15,10 @@ pub struct CreateDatasourceRequest {
 pub struct UpdateDatasourceRequest {
     pub name: Option<String>,
     pub config: Option<Value>,
+    pub source_type: Option<String>, // Corrects the type; config is remapped or rejected if incompatible
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DatasourceResponse {
     pub id: String,
     pub name: String,
@@ -28,17 +32,36 @@ pub struct DatasourceResponse {
     pub connection_error: Option<String>,
 }
 
+/// Outcome of one entry in a bulk datasource import
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BulkDatasourceResult {
+    pub index: usize,
+    pub name: Option<String>,
+    pub success: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub datasource: Option<DatasourceResponse>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct TestConnectionResponse {
     pub success: bool,
     pub message: String,
     pub error: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub category: Option<String>, // Normalized failure class, e.g. "auth" or "host_unreachable"
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub field: Option<String>, // Config field most likely at fault, e.g. "port"
 }
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct QueryRequest {
     pub query: String,
     pub limit: Option<i32>,
+    pub preview: Option<bool>, // Run against a row-limited sample of each source table
+    pub sample_rows: Option<i32>, // Rows sampled per source table in preview mode
+    pub stringify: Option<bool>, // Render every cell as a string, NULL as "NULL" (legacy clients)
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -47,7 +70,13 @@ pub struct TableDataRequest {
     pub limit: Option<i32>,
     pub sort_column: Option<String>,
     pub sort_direction: Option<String>, // "asc" or "desc"
+    pub nulls: Option<NullsOrder>, // NULL placement for sort_column: "first" or "last"
+    pub sort: Option<Vec<SortKey>>, // Multi-column sort; takes precedence over sort_column
     pub filters: Option<Value>,
+    pub columns: Option<Vec<String>>, // Explicit column projection for wide tables
+    pub stringify: Option<bool>, // Render every cell as a string, NULL as "NULL" (legacy clients)
+    pub cursor: Option<Value>, // Last-seen value of the single sort column; pages by keyset instead of offset
+    pub approximate_count: Option<bool>, // Use the catalog's row estimate for `total` where supported (ClickHouse)
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -67,17 +96,24 @@ pub struct RowIdsRequest {
 pub struct DeleteRowsRequest {
     pub row_ids: Vec<String>, // IDs or conditions to identify rows to delete
     pub id_column: Option<String>, // Primary key column name (defaults to 'id')
+    pub atomic: Option<bool>, // All rows or none (default); false keeps the rows that succeed
+    pub dry_run: Option<bool>, // Roll back and report what would be affected
 }
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct UpdateRowsRequest {
     pub updates: std::collections::HashMap<String, std::collections::HashMap<String, Value>>, // rowId -> columnKey -> newValue
     pub id_column: Option<String>, // Primary key column name (defaults to 'id')
+    pub atomic: Option<bool>, // All rows or none (default); false keeps the rows that succeed
+    pub dry_run: Option<bool>, // Roll back and report what would be affected
 }
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct InsertRowsRequest {
     pub rows: Vec<std::collections::HashMap<String, Value>>, // Array of row objects
+    pub atomic: Option<bool>, // All rows or none (default); false keeps the rows that succeed
+    pub on_conflict: Option<OnConflict>, // error (default), ignore or update rows whose key exists
+    pub conflict_columns: Option<Vec<String>>, // Key the conflict is on; required for update
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -114,4 +150,20 @@ pub struct TableStructure {
     pub primary_keys: Vec<String>,
     pub foreign_keys: Vec<ForeignKeyInfo>,
     pub indexes: Vec<IndexInfo>,
+    /// User-defined display names and hidden flags, overlaid at response time (never cached)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub column_views: Vec<ColumnView>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ColumnView {
+    pub column_name: String,
+    pub display_name: Option<String>,
+    #[serde(default)]
+    pub hidden: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct UpdateColumnViewsRequest {
+    pub columns: Vec<ColumnView>, // Entries with no display name and not hidden are cleared
 }
\ No newline at end of file
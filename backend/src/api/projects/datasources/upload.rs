@@ -1,14 +1,26 @@
 use chrono::Utc;
+use salvo::http::form::FormData;
 use salvo::prelude::*;
 use serde_json::{json, Value};
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use uuid::Uuid;
 
-use crate::utils::middleware::{get_current_user_id, is_current_user_root};
+use crate::core::datasources::column_profile;
+use crate::core::datasources::csv_import::{self, CsvImportOptions, CsvSchema};
+use crate::core::projects::manager::ProjectManager;
+use crate::utils::datasource::common::redaction::redact_connection_config;
+use crate::utils::middleware::{get_current_client_id, get_current_user_id, is_current_user_root};
+use crate::utils::datasource::connectors::excel::ExcelConnector;
+use crate::utils::datasource::connectors::json::JsonConnector;
 use crate::utils::{get_app_state, AppError};
 
-use super::types::{CreateDatasourceRequest, DatasourceResponse};
+/// Upper bound on `sample_size`
+const MAX_SAMPLE_ROWS: usize = 100_000;
+/// Rows echoed back as `sample_data` in previews
+const PREVIEW_ROWS: usize = 10;
+
+use super::types::DatasourceResponse;
 
 /// Upload a file and create a datasource
 #[handler]
@@ -52,14 +64,12 @@ pub async fn upload_file_datasource(
 
     // Extract fields from form
     let name = form_data.fields.get("name")
-        .and_then(|v| v.first())
-        .and_then(|s| s.as_str())
-        .ok_or_else(|| AppError::BadRequest("Missing name field"))?;
+        .map(String::as_str)
+        .ok_or_else(|| AppError::BadRequest("Missing name field".to_string()))?;
 
     let source_type = form_data.fields.get("source_type")
-        .and_then(|v| v.first())
-        .and_then(|s| s.as_str())
-        .ok_or_else(|| AppError::BadRequest("Missing source_type field"))?;
+        .map(String::as_str)
+        .ok_or_else(|| AppError::BadRequest("Missing source_type field".to_string()))?;
 
     // Validate source type
     let valid_types = ["csv", "excel", "json"];
@@ -67,13 +77,24 @@ pub async fn upload_file_datasource(
         return Err(AppError::BadRequest(format!("Invalid source_type '{}'. Must be one of: {}", source_type, valid_types.join(", "))));
     }
 
+    // "duckdb" loads a CSV into a DuckDB file so it can be queried with full SQL
+    let import_mode = form_data.fields.get("import_mode")
+        .map(String::as_str)
+        .unwrap_or("file");
+    match import_mode {
+        "file" => {},
+        "duckdb" if source_type == "csv" => {},
+        "duckdb" => return Err(AppError::BadRequest("import_mode 'duckdb' is only supported for csv uploads".to_string())),
+        other => return Err(AppError::BadRequest(format!("Invalid import_mode '{}'. Must be one of: file, duckdb", other))),
+    }
+
     // Get file from form
     let file = form_data.files.get("file")
-        .and_then(|files| files.first())
-        .ok_or_else(|| AppError::BadRequest("Missing file field"))?;
+        .ok_or_else(|| AppError::BadRequest("Missing file field".to_string()))?;
+    let original_name = file.name().unwrap_or("upload").to_string();
 
     // Validate file
-    let file_extension = Path::new(&file.name)
+    let file_extension = Path::new(&original_name)
         .extension()
         .and_then(|s| s.to_str())
         .map(|s| s.to_lowercase())
@@ -108,7 +129,7 @@ pub async fn upload_file_datasource(
     let file_path = format!("{}/{}", upload_dir, file_name);
 
     // Save file
-    fs::write(&file_path, &file.data)
+    fs::copy(file.path(), &file_path)
         .map_err(|e| AppError::InternalServerError(format!("Failed to save file: {}", e)))?;
 
     // Get file metadata
@@ -121,13 +142,11 @@ pub async fn upload_file_datasource(
         "csv" => {
             // Extract parsing options from form
             let delimiter = form_data.fields.get("delimiter")
-                .and_then(|v| v.first())
-                .and_then(|s| s.as_str())
+                .map(String::as_str)
                 .unwrap_or(",");
 
             let has_header = form_data.fields.get("has_header")
-                .and_then(|v| v.first())
-                .and_then(|s| s.as_str())
+                .map(String::as_str)
                 .unwrap_or("true") == "true";
 
             json!({
@@ -142,12 +161,10 @@ pub async fn upload_file_datasource(
         },
         "excel" => {
             let sheet_name = form_data.fields.get("sheet_name")
-                .and_then(|v| v.first())
-                .and_then(|s| s.as_str());
+                .map(String::as_str);
 
             let header_row = form_data.fields.get("header_row")
-                .and_then(|v| v.first())
-                .and_then(|s| s.as_str())
+                .map(String::as_str)
                 .and_then(|s| s.parse::<u64>().ok());
 
             let mut config = json!({
@@ -167,12 +184,10 @@ pub async fn upload_file_datasource(
         },
         "json" => {
             let root_path = form_data.fields.get("root_path")
-                .and_then(|v| v.first())
-                .and_then(|s| s.as_str());
+                .map(String::as_str);
 
             let array_path = form_data.fields.get("array_path")
-                .and_then(|v| v.first())
-                .and_then(|s| s.as_str());
+                .map(String::as_str);
 
             let mut config = json!({
                 "file_path": file_path
@@ -191,12 +206,43 @@ pub async fn upload_file_datasource(
         _ => return Err(AppError::BadRequest("Invalid source type".to_string())),
     };
 
+    // In DuckDB mode the database file replaces the uploaded CSV
+    let (source_type, file_path, file_size, connection_config, schema_info) = if import_mode == "duckdb" {
+        let client_id = get_current_client_id(depot)?;
+        let db_path = ProjectManager::new()
+            .get_project_directory(client_id, &project_id)
+            .join("datasources")
+            .join(format!("{}.duckdb", datasource_id));
+        let table_name = csv_import::table_name_for(&original_name);
+
+        let import = import_csv_to_duckdb(&file_path, db_path.clone(), &table_name, csv_import_options(form_data)).await;
+        let _ = fs::remove_file(&file_path);
+        let (schema, row_count) = import?;
+
+        let db_path = db_path.to_string_lossy().to_string();
+        let db_size = fs::metadata(&db_path).map(|m| m.len()).unwrap_or(0);
+        let config = json!({
+            "file_path": db_path,
+            "table_name": table_name,
+            "source_file": original_name,
+            "row_count": row_count,
+            "sampled_rows": schema.sampled_rows
+        });
+        let schema_info = json!({
+            "tables": { table_name: schema_columns(&schema) },
+            "refreshed_at": Utc::now().to_rfc3339()
+        });
+        ("duckdb", db_path, db_size, config, Some(schema_info))
+    } else {
+        (source_type, file_path, file_size, connection_config, None)
+    };
+
     // Insert datasource with file metadata
     let now = Utc::now();
     sqlx::query(
         r#"
-        INSERT INTO data_sources (id, name, source_type, connection_config, project_id, file_path, file_size, file_type, file_metadata, created_at, updated_at)
-        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)
+        INSERT INTO data_sources (id, name, source_type, connection_config, project_id, file_path, file_size, file_type, file_metadata, schema_info, created_at, updated_at)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12)
         "#
     )
     .bind(&datasource_id)
@@ -208,10 +254,11 @@ pub async fn upload_file_datasource(
     .bind(file_size as i64)
     .bind(&file_extension)
     .bind(json!({
-        "original_name": file.name,
+        "original_name": original_name,
         "uploaded_at": now.to_rfc3339(),
-        "mime_type": file.mime_type
+        "mime_type": file.content_type().map(|mime| mime.to_string())
     }))
+    .bind(&schema_info)
     .bind(now)
     .bind(now)
     .execute(&state.db_pool)
@@ -223,11 +270,11 @@ pub async fn upload_file_datasource(
         id: datasource_id,
         name: name.to_string(),
         source_type: source_type.to_string(),
-        config: connection_config,
+        config: redact_connection_config(&connection_config),
         created_at: now.to_rfc3339(),
         updated_at: now.to_rfc3339(),
         project_id,
-        schema_info: None,
+        schema_info,
         connection_status: Some("uploaded".to_string()),
         connection_error: None,
     };
@@ -244,8 +291,8 @@ pub async fn preview_file(
     res: &mut Response,
     depot: &mut Depot,
 ) -> Result<(), AppError> {
-    let state = get_app_state(depot)?;
-    let user_id = get_current_user_id(depot)?;
+    // Previews need a signed-in user but touch no project
+    get_current_user_id(depot)?;
 
     // Get form data
     let form_data = req.form_data().await
@@ -253,9 +300,8 @@ pub async fn preview_file(
 
     // Get source type from form
     let source_type = form_data.fields.get("source_type")
-        .and_then(|v| v.first())
-        .and_then(|s| s.as_str())
-        .ok_or_else(|| AppError::BadRequest("Missing source_type field"))?;
+        .map(String::as_str)
+        .ok_or_else(|| AppError::BadRequest("Missing source_type field".to_string()))?;
 
     // Validate source type
     let valid_types = ["csv", "excel", "json"];
@@ -265,32 +311,35 @@ pub async fn preview_file(
 
     // Get file from form
     let file = form_data.files.get("file")
-        .and_then(|files| files.first())
-        .ok_or_else(|| AppError::BadRequest("Missing file field"))?;
+        .ok_or_else(|| AppError::BadRequest("Missing file field".to_string()))?;
 
     // Create temporary file for preview
     let temp_dir = "temp/previews";
     fs::create_dir_all(temp_dir)
         .map_err(|e| AppError::InternalServerError(format!("Failed to create temp directory: {}", e)))?;
 
-    let temp_filename = format!("preview_{}_{}", Uuid::new_v4(), file.name);
+    // Only the extension of the client's file name is kept; connectors read it
+    let extension = file.name()
+        .and_then(|name| Path::new(name).extension())
+        .and_then(|ext| ext.to_str())
+        .map(|ext| format!(".{}", ext))
+        .unwrap_or_default();
+    let temp_filename = format!("preview_{}{}", Uuid::new_v4(), extension);
     let temp_path = format!("{}/{}", temp_dir, temp_filename);
 
     // Save temporary file
-    fs::write(&temp_path, &file.data)
+    fs::copy(file.path(), &temp_path)
         .map_err(|e| AppError::InternalServerError(format!("Failed to save temp file: {}", e)))?;
 
     // Create a temporary connector to preview the data
     let preview_result = match source_type {
         "csv" => {
             let delimiter = form_data.fields.get("delimiter")
-                .and_then(|v| v.first())
-                .and_then(|s| s.as_str())
+                .map(String::as_str)
                 .unwrap_or(",");
 
             let has_header = form_data.fields.get("has_header")
-                .and_then(|v| v.first())
-                .and_then(|s| s.as_str())
+                .map(String::as_str)
                 .unwrap_or("true") == "true";
 
             let config = json!({
@@ -303,16 +352,14 @@ pub async fn preview_file(
                 "flexible": false
             });
 
-            preview_csv_data(&config).await
+            preview_csv_data(&config, &temp_path, csv_import_options(form_data)).await
         },
         "excel" => {
             let sheet_name = form_data.fields.get("sheet_name")
-                .and_then(|v| v.first())
-                .and_then(|s| s.as_str());
+                .map(String::as_str);
 
             let header_row = form_data.fields.get("header_row")
-                .and_then(|v| v.first())
-                .and_then(|s| s.as_str())
+                .map(String::as_str)
                 .and_then(|s| s.parse::<usize>().ok());
 
             let mut config = json!({
@@ -329,16 +376,14 @@ pub async fn preview_file(
                 config["data_start_row"] = json!(header + 1);
             }
 
-            preview_excel_data(&config).await
+            preview_excel_data(&config, sample_size(form_data)).await
         },
         "json" => {
             let root_path = form_data.fields.get("root_path")
-                .and_then(|v| v.first())
-                .and_then(|s| s.as_str());
+                .map(String::as_str);
 
             let array_path = form_data.fields.get("array_path")
-                .and_then(|v| v.first())
-                .and_then(|s| s.as_str());
+                .map(String::as_str);
 
             let mut config = json!({
                 "file_path": temp_path
@@ -352,7 +397,7 @@ pub async fn preview_file(
                 config["array_path"] = json!(array);
             }
 
-            preview_json_data(&config).await
+            preview_json_data(&config, sample_size(form_data)).await
         },
         _ => Err(AppError::BadRequest("Invalid source type".to_string())),
     };
@@ -362,44 +407,134 @@ pub async fn preview_file(
 
     match preview_result {
         Ok(preview) => {
-            res.render(json!(preview));
+            res.render(Json(preview));
             Ok(())
         },
         Err(e) => Err(e),
     }
 }
 
+/// Rows read to infer column types, from the form's `sample_size`. Previews
+/// and imports never read more than this, which bounds memory for big files.
+fn sample_size(form_data: &FormData) -> usize {
+    form_data.fields.get("sample_size")
+        .map(String::as_str)
+        .and_then(|s| s.parse::<usize>().ok())
+        .filter(|n| *n > 0)
+        .map(|n| n.min(MAX_SAMPLE_ROWS))
+        .unwrap_or(csv_import::DEFAULT_SAMPLE_ROWS)
+}
+
+/// CSV parsing options from the form
+fn csv_import_options(form_data: &FormData) -> CsvImportOptions {
+    let defaults = CsvImportOptions::default();
+
+    let delimiter = form_data.fields.get("delimiter")
+        .map(String::as_str)
+        .and_then(|s| if s == "\\t" { Some(b'\t') } else { s.bytes().next() })
+        .unwrap_or(defaults.delimiter);
+
+    let has_header = form_data.fields.get("has_header")
+        .map(String::as_str)
+        .map(|s| s == "true")
+        .unwrap_or(defaults.has_header);
+
+    CsvImportOptions { delimiter, has_header, sample_rows: sample_size(form_data) }
+}
+
+/// Inferred columns in the shape connectors report from `fetch_schema`
+fn schema_columns(schema: &CsvSchema) -> Value {
+    json!(schema.columns.iter().map(|c| json!({
+        "column_name": c.name,
+        "data_type": c.data_type.sql_type(),
+        "is_nullable": c.nullable
+    })).collect::<Vec<_>>())
+}
+
+/// Infer the CSV's schema and load it into a new DuckDB file
+async fn import_csv_to_duckdb(
+    csv_path: &str,
+    db_path: PathBuf,
+    table_name: &str,
+    options: CsvImportOptions,
+) -> Result<(CsvSchema, u64), AppError> {
+    let csv_path = PathBuf::from(csv_path);
+    let table_name = table_name.to_string();
+
+    tokio::task::spawn_blocking(move || -> anyhow::Result<(CsvSchema, u64)> {
+        let schema = csv_import::infer_schema(fs::File::open(&csv_path)?, &options)?;
+        let row_count = csv_import::import_into_duckdb(&csv_path, &db_path, &table_name, &schema, &options)?;
+        Ok((schema, row_count))
+    })
+    .await
+    .map_err(|e| AppError::InternalServerError(format!("CSV import task failed: {}", e)))?
+    .map_err(|e| AppError::BadRequest(format!("{:#}", e)))
+}
+
 // Preview functions (simplified versions that don't require the full connectors)
-async fn preview_csv_data(config: &Value) -> Result<Value, AppError> {
-    // This is a simplified preview - in a real implementation, you'd use the CSV connector
+async fn preview_csv_data(config: &Value, path: &str, options: CsvImportOptions) -> Result<Value, AppError> {
+    // Only the sampled records are read, so this stays cheap for large files
+    let path = path.to_string();
+    let sample_rows = options.sample_rows;
+    let schema = tokio::task::spawn_blocking(move || -> anyhow::Result<CsvSchema> {
+        csv_import::infer_schema(fs::File::open(&path)?, &options)
+    })
+    .await
+    .map_err(|e| AppError::InternalServerError(format!("CSV preview task failed: {}", e)))?
+    .map_err(|e| AppError::BadRequest(format!("{:#}", e)))?;
+
     Ok(json!({
         "preview_type": "csv",
         "estimated_rows": "unknown",
-        "columns": ["Will be detected from file"],
-        "sample_data": [],
+        "columns": schema.columns.iter().map(|c| c.name.clone()).collect::<Vec<_>>(),
+        "schema": schema_columns(&schema),
+        "sampled_rows": schema.sampled_rows,
+        "sample_size": sample_rows,
+        "sample_data": schema.sample_data,
+        "column_profiles": schema.profiles,
         "config": config
     }))
 }
 
-async fn preview_excel_data(config: &Value) -> Result<Value, AppError> {
-    // This is a simplified preview - in a real implementation, you'd use the Excel connector
+async fn preview_excel_data(config: &Value, sample_rows: usize) -> Result<Value, AppError> {
+    let connector_config = config.clone();
+    let (sheets, headers, rows) = tokio::task::spawn_blocking(move || {
+        ExcelConnector::new(&connector_config)?.preview_rows(sample_rows)
+    })
+    .await
+    .map_err(|e| AppError::InternalServerError(format!("Excel preview task failed: {}", e)))?
+    .map_err(|e| AppError::BadRequest(format!("Failed to read Excel file: {}", e)))?;
+
     Ok(json!({
         "preview_type": "excel",
-        "available_sheets": ["Sheet1", "Sheet2"], // Would be detected from file
+        "available_sheets": sheets,
         "estimated_rows": "unknown",
-        "columns": ["Will be detected from file"],
-        "sample_data": [],
+        "sampled_rows": rows.len(),
+        "sample_size": sample_rows,
+        "column_profiles": column_profile::profile_rows(&headers, &rows),
+        "sample_data": rows.iter().take(PREVIEW_ROWS).collect::<Vec<_>>(),
+        "columns": headers,
         "config": config
     }))
 }
 
-async fn preview_json_data(config: &Value) -> Result<Value, AppError> {
-    // This is a simplified preview - in a real implementation, you'd use the JSON connector
+async fn preview_json_data(config: &Value, sample_rows: usize) -> Result<Value, AppError> {
+    let connector_config = config.clone();
+    let (headers, rows) = tokio::task::spawn_blocking(move || {
+        JsonConnector::new(&connector_config)?.preview_rows(sample_rows)
+    })
+    .await
+    .map_err(|e| AppError::InternalServerError(format!("JSON preview task failed: {}", e)))?
+    .map_err(|e| AppError::BadRequest(format!("Failed to read JSON file: {}", e)))?;
+
     Ok(json!({
         "preview_type": "json",
         "estimated_objects": "unknown",
-        "structure": "Will be detected from file",
-        "sample_data": [],
+        "sampled_rows": rows.len(),
+        "sample_size": sample_rows,
+        "column_profiles": column_profile::profile_rows(&headers, &rows),
+        "sample_data": rows.iter().take(PREVIEW_ROWS).collect::<Vec<_>>(),
+        "columns": headers,
         "config": config
     }))
-}
\ No newline at end of file
+}
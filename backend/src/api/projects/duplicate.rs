@@ -0,0 +1,133 @@
+// Duplicating a project into a new one owned by the caller
+
+use salvo::prelude::*;
+use serde::Deserialize;
+
+use crate::core::projects::duplicate::{copy_project_files, delete_project_rows, duplicate_project_rows};
+use crate::core::projects::{ProjectInfo, ProjectManager};
+use crate::models::project_member::ProjectMemberRole;
+use crate::utils::middleware::{get_current_client_id, get_current_user_id, is_current_user_root};
+use crate::utils::{get_app_state, AppError};
+
+#[derive(Debug, Default, Deserialize)]
+pub struct DuplicateProjectRequest {
+    /// Defaults to "<source name> (copy)"
+    pub name: Option<String>,
+}
+
+/// Copy a project's datasources, context and saved queries into a new project
+/// in the caller's client. Conversations and uploads are not copied.
+#[handler]
+pub async fn duplicate_project(
+    req: &mut Request,
+    depot: &mut Depot,
+    res: &mut Response,
+) -> Result<(), AppError> {
+    let state = get_app_state(depot)?;
+    let project_id = req
+        .param::<String>("project_id")
+        .ok_or(AppError::BadRequest("Missing project_id".to_string()))?;
+
+    // The body is optional
+    let body = req
+        .payload()
+        .await
+        .map_err(|_| AppError::BadRequest("Invalid request body".to_string()))?;
+    let duplicate_req: DuplicateProjectRequest = if body.iter().all(u8::is_ascii_whitespace) {
+        DuplicateProjectRequest::default()
+    } else {
+        serde_json::from_slice(body).map_err(|_| AppError::BadRequest("Invalid request body".to_string()))?
+    };
+    let name = duplicate_req.name.map(|name| name.trim().to_string());
+    if name.as_deref() == Some("") {
+        return Err(AppError::BadRequest("Project name cannot be empty".to_string()));
+    }
+
+    let user_id = get_current_user_id(depot)?;
+    let is_root = is_current_user_root(depot);
+    let role = if is_root {
+        None
+    } else {
+        sqlx::query_scalar::<_, String>("SELECT role FROM project_members WHERE project_id = $1 AND user_id = $2")
+            .bind(&project_id)
+            .bind(user_id)
+            .fetch_optional(&state.db_pool)
+            .await
+            .map_err(|e| AppError::InternalServerError(format!("Database error: {}", e)))?
+    };
+    ensure_can_duplicate(is_root, role.as_deref())?;
+    let client_id = get_current_client_id(depot)?;
+
+    let duplicated = duplicate_project_rows(&state.db_pool, &project_id, client_id, user_id, name)
+        .await
+        .map_err(|e| AppError::InternalServerError(format!("Failed to duplicate project: {}", e)))?
+        .ok_or_else(|| AppError::NotFound("Project not found".to_string()))?;
+
+    let project_manager = ProjectManager::new();
+    let copied = project_manager
+        .ensure_project_directory(client_id, &duplicated.project_id)
+        .and_then(|target_dir| {
+            copy_project_files(
+                &project_manager.get_project_directory(client_id, &project_id),
+                &target_dir,
+                &duplicated.id_replacements(&project_id),
+            )
+        });
+    if let Err(e) = copied {
+        // Don't leave a copy without its CLAUDE.md and saved queries behind
+        let _ = std::fs::remove_dir_all(project_manager.get_project_directory(client_id, &duplicated.project_id));
+        if let Err(cleanup) = delete_project_rows(&state.db_pool, &duplicated.project_id).await {
+            tracing::error!("Failed to remove partial copy {}: {}", duplicated.project_id, cleanup);
+        }
+        return Err(e);
+    }
+
+    let (created_at, updated_at): (chrono::DateTime<chrono::Utc>, chrono::DateTime<chrono::Utc>) =
+        sqlx::query_as("SELECT created_at, updated_at FROM projects WHERE id = $1")
+            .bind(&duplicated.project_id)
+            .fetch_one(&state.db_pool)
+            .await
+            .map_err(|e| AppError::InternalServerError(format!("Database error: {}", e)))?;
+
+    res.status_code(StatusCode::CREATED);
+    res.render(Json(serde_json::json!({
+        "project": ProjectInfo {
+            id: duplicated.project_id,
+            name: duplicated.name,
+            created_at: created_at.to_rfc3339(),
+            updated_at: updated_at.to_rfc3339(),
+            client_id,
+        },
+        "datasources_copied": duplicated.datasource_ids.len(),
+    })));
+    Ok(())
+}
+
+/// The copy carries every datasource's credentials, so only owners (and root)
+/// may make one; plain members can use the project but not take its secrets
+fn ensure_can_duplicate(is_root: bool, role: Option<&str>) -> Result<(), AppError> {
+    if is_root {
+        return Ok(());
+    }
+    match role.map(ProjectMemberRole::from_str) {
+        Some(Ok(ProjectMemberRole::Owner)) => Ok(()),
+        Some(_) => Err(AppError::Forbidden("Only project owners can duplicate a project".to_string())),
+        None => Err(AppError::Forbidden("You don't have access to this project".to_string())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_owners_and_root_can_duplicate() {
+        assert!(ensure_can_duplicate(false, Some("owner")).is_ok());
+        assert!(ensure_can_duplicate(true, None).is_ok());
+
+        for role in [Some("member"), Some("viewer"), None] {
+            let err = ensure_can_duplicate(false, role).unwrap_err();
+            assert_eq!(err.status_code(), StatusCode::FORBIDDEN, "role {:?}", role);
+        }
+    }
+}
@@ -1,3 +1,4 @@
+use crate::core::audit::{self, record_audit, AuditEntry};
 use crate::models::*;
 use crate::utils::middleware::{get_current_user_id, is_current_user_root};
 use crate::utils::{get_app_state, AppError};
@@ -346,6 +347,17 @@ pub async fn transfer_project_ownership(
         })?;
     }
 
+    record_audit(
+        &state.db_pool,
+        AuditEntry::by_current_user(
+            depot,
+            audit::PROJECT_OWNERSHIP_TRANSFER,
+            &project_id,
+            serde_json::json!({ "new_owner_user_id": transfer_req.new_owner_user_id }),
+        ),
+    )
+    .await;
+
     #[derive(Serialize)]
     struct TransferOwnershipResponse {
         message: String,
@@ -1,8 +1,11 @@
 // Project management
+pub mod bundle;
 pub mod crud;
 pub mod datasources;
 pub mod context;
+pub mod duplicate;
 pub mod members;
+pub mod queries;
 
 use salvo::prelude::*;
 use crate::utils::middleware::auth::auth_required;
@@ -14,7 +17,10 @@ pub fn project_routes() -> Router {
         .hoop(auth_required)
         .hoop(client_scoped)
         .push(Router::with_path("/projects").get(crud::list_projects).post(crud::create_project))
+        .push(Router::with_path("/projects/import").post(bundle::import_project))
         .push(Router::with_path("/projects/{project_id}").get(crud::get_project).delete(crud::delete_project))
+        .push(Router::with_path("/projects/{project_id}/export").get(bundle::export_project))
+        .push(Router::with_path("/projects/{project_id}/duplicate").post(duplicate::duplicate_project))
         .push(Router::with_path("/projects/{project_id}/context")
             .get(context::get_project_context)
             .put(context::update_project_context))
@@ -22,6 +28,8 @@ pub fn project_routes() -> Router {
         .push(Router::with_path("/projects/{project_id}/context/preview").get(context::preview_project_context))
         .push(Router::with_path("/projects/{project_id}/context/cache").delete(context::clear_context_cache))
         .push(Router::with_path("/projects/{project_id}/queries").get(crud::list_queries).post(crud::save_query))
+        .push(Router::with_path("/projects/{project_id}/queries/recent").get(queries::list_recent_queries_handler))
+        .push(Router::with_path("/projects/{project_id}/queries/{query_id}/replay").post(queries::replay_query))
         .push(Router::with_path("/projects/{project_id}/members")
             .get(members::list_project_members)
             .post(members::add_project_member))
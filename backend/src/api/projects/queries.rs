@@ -0,0 +1,120 @@
+// Recently run queries of a project and replaying them
+
+use salvo::prelude::*;
+use serde_json::Value;
+use uuid::Uuid;
+
+use crate::core::datasources::query_history::{get_recent_query, list_recent_queries, record_query};
+use crate::core::datasources::slow_queries::result_row_count;
+use crate::utils::datasource::common::timeouts::with_query_timeout;
+use crate::utils::datasource::create_connector;
+use crate::utils::middleware::{get_current_user_id, is_current_user_root};
+use crate::utils::{get_app_state, AppError, AppState};
+
+use super::datasources::crud::get_cached_datasource;
+use super::datasources::query::connector_query_error;
+
+const DEFAULT_RECENT_QUERY_LIMIT: i64 = 50;
+const MAX_RECENT_QUERY_LIMIT: i64 = 500;
+
+pub(crate) async fn ensure_project_member(state: &AppState, depot: &Depot, project_id: &str) -> Result<(), AppError> {
+    if is_current_user_root(depot) {
+        return Ok(());
+    }
+    let user_id = get_current_user_id(depot)?;
+    let is_member = sqlx::query_scalar::<_, bool>(
+        "SELECT EXISTS(SELECT 1 FROM project_members WHERE project_id = $1 AND user_id = $2)",
+    )
+    .bind(project_id)
+    .bind(user_id)
+    .fetch_one(&state.db_pool)
+    .await
+    .unwrap_or(false);
+
+    if !is_member {
+        return Err(AppError::Forbidden("You don't have access to this project".to_string()));
+    }
+    Ok(())
+}
+
+/// Queries run from the data browser and MCP, newest first
+#[handler]
+pub async fn list_recent_queries_handler(
+    req: &mut Request,
+    depot: &mut Depot,
+    res: &mut Response,
+) -> Result<(), AppError> {
+    let state = get_app_state(depot)?;
+    let project_id = req
+        .param::<String>("project_id")
+        .ok_or(AppError::BadRequest("Missing project_id".to_string()))?;
+    let limit = req.query::<i64>("limit")
+        .unwrap_or(DEFAULT_RECENT_QUERY_LIMIT)
+        .clamp(1, MAX_RECENT_QUERY_LIMIT);
+
+    ensure_project_member(state, depot, &project_id).await?;
+
+    let queries = list_recent_queries(&state.db_pool, &project_id, limit).await
+        .map_err(|e| AppError::InternalServerError(format!("Database error: {}", e)))?;
+
+    res.render(Json(serde_json::json!({
+        "project_id": project_id,
+        "queries": queries
+    })));
+    Ok(())
+}
+
+/// Run a query from the project's history again and return fresh results
+#[handler]
+pub async fn replay_query(
+    req: &mut Request,
+    depot: &mut Depot,
+    res: &mut Response,
+) -> Result<(), AppError> {
+    let state = get_app_state(depot)?;
+    let user_id = get_current_user_id(depot)?;
+    let project_id = req
+        .param::<String>("project_id")
+        .ok_or(AppError::BadRequest("Missing project_id".to_string()))?;
+    let query_id = req
+        .param::<String>("query_id")
+        .and_then(|id| Uuid::parse_str(&id).ok())
+        .ok_or(AppError::BadRequest("Invalid query_id".to_string()))?;
+
+    ensure_project_member(state, depot, &project_id).await?;
+
+    let entry = get_recent_query(&state.db_pool, &project_id, query_id).await
+        .map_err(|e| AppError::InternalServerError(format!("Database error: {}", e)))?
+        .ok_or_else(|| AppError::NotFound("Query not found".to_string()))?;
+
+    let datasource = get_cached_datasource(&entry.datasource_id, &user_id, is_current_user_root(depot), &state.db_pool).await?;
+    if datasource.project_id != project_id {
+        return Err(AppError::NotFound("Query not found".to_string()));
+    }
+
+    let mut config = datasource.connection_config.clone();
+    config.as_object_mut()
+        .ok_or_else(|| AppError::InternalServerError("Invalid config format".to_string()))?
+        .insert("id".to_string(), Value::String(entry.datasource_id.clone()));
+    let connector = create_connector(&datasource.datasource_type, &config)
+        .await
+        .map_err(|e| AppError::InternalServerError(format!("Failed to create connector: {}", e)))?;
+
+    let limit = state.config.effective_page_size(req.query::<i32>("limit"), state.config.max_page_size);
+    let query_start = std::time::Instant::now();
+    let timeouts = state.config.datasource_timeouts.for_datasource(&datasource.connection_config);
+    let mut result = with_query_timeout(timeouts.query, connector.execute_query(&entry.query, limit)).await
+        .map_err(|e| connector_query_error(&*e))?;
+
+    record_query(&state.db_pool, entry.replayed(&project_id, query_start.elapsed(), result_row_count(&result)));
+
+    if let Some(obj) = result.as_object_mut() {
+        obj.insert("page_size".to_string(), Value::from(limit));
+        obj.insert("replayed_query_id".to_string(), Value::String(entry.id.to_string()));
+        obj.insert("datasource_id".to_string(), Value::String(entry.datasource_id.clone()));
+        obj.insert("query".to_string(), Value::String(entry.query.clone()));
+    }
+
+    res.render(Json(result));
+    Ok(())
+}
@@ -0,0 +1,289 @@
+// ZIP uploads expanded into one upload per member file
+
+use salvo::http::StatusError;
+use salvo::prelude::*;
+use std::io::{Cursor, Read};
+use std::path::{Component, Path};
+use uuid::Uuid;
+use chrono::Utc;
+
+use super::paths::validate_path_component;
+use super::process_upload_content;
+use crate::api::projects::queries::ensure_project_member;
+use crate::utils::middleware::get_current_user_id;
+use crate::utils::storage::{storage, upload_key, LocalFile};
+use crate::utils::AppState;
+
+pub use crate::utils::config::ArchiveLimits;
+
+#[derive(Debug, thiserror::Error, PartialEq)]
+pub enum ArchiveError {
+    #[error("Not a valid ZIP archive: {0}")]
+    Invalid(String),
+    #[error("Archive entry '{0}' has an unsafe path")]
+    UnsafePath(String),
+    #[error("Archive has more than {0} files")]
+    TooManyMembers(usize),
+    #[error("Archive expands to more than {0} bytes")]
+    TooLarge(u64),
+}
+
+#[derive(Debug)]
+pub struct ArchiveMember {
+    /// Path inside the archive, e.g. `sales/2024.csv`
+    pub path: String,
+    pub data: Vec<u8>,
+}
+
+/// A relative path made only of normal components; anything with `..`, a
+/// root or a drive prefix could land outside the uploads directory
+fn safe_member_path(name: &str) -> Option<String> {
+    let normalized = name.replace('\\', "/");
+    let path = Path::new(&normalized);
+    if normalized.is_empty()
+        || !path.components().all(|c| matches!(c, Component::Normal(_)))
+    {
+        return None;
+    }
+    Some(normalized)
+}
+
+/// Read the member files of a ZIP archive, rejecting the whole archive if an
+/// entry has an unsafe path or the limits are exceeded
+pub fn read_archive(bytes: &[u8], limits: &ArchiveLimits) -> Result<Vec<ArchiveMember>, ArchiveError> {
+    let mut archive = zip::ZipArchive::new(Cursor::new(bytes))
+        .map_err(|e| ArchiveError::Invalid(e.to_string()))?;
+
+    let mut members = Vec::new();
+    let mut total: u64 = 0;
+    for index in 0..archive.len() {
+        let entry = archive
+            .by_index(index)
+            .map_err(|e| ArchiveError::Invalid(e.to_string()))?;
+        let name = entry.name().to_string();
+        let path = safe_member_path(&name).ok_or_else(|| ArchiveError::UnsafePath(name.clone()))?;
+
+        // Folders and macOS resource forks aren't files anyone uploaded
+        if entry.is_dir() || path.starts_with("__MACOSX/") {
+            continue;
+        }
+        if members.len() == limits.max_members {
+            return Err(ArchiveError::TooManyMembers(limits.max_members));
+        }
+
+        // Headers can lie about sizes, so count what actually decompresses
+        let remaining = limits.max_total_uncompressed - total;
+        let mut data = Vec::new();
+        entry
+            .take(remaining + 1)
+            .read_to_end(&mut data)
+            .map_err(|e| ArchiveError::Invalid(e.to_string()))?;
+        total += data.len() as u64;
+        if total > limits.max_total_uncompressed {
+            return Err(ArchiveError::TooLarge(limits.max_total_uncompressed));
+        }
+
+        members.push(ArchiveMember { path, data });
+    }
+    Ok(members)
+}
+
+fn is_zip(file_name: &str, mime_type: Option<&str>) -> bool {
+    matches!(
+        mime_type,
+        Some("application/zip" | "application/x-zip-compressed")
+    ) || file_name.to_lowercase().ends_with(".zip")
+}
+
+fn mime_for(path: &str) -> String {
+    let extension = Path::new(path)
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .unwrap_or("")
+        .to_lowercase();
+    match extension.as_str() {
+        "csv" => "text/csv",
+        "txt" | "md" | "log" => "text/plain",
+        "json" => "application/json",
+        "pdf" => "application/pdf",
+        "xlsx" => "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet",
+        "xls" => "application/vnd.ms-excel",
+        "docx" => "application/vnd.openxmlformats-officedocument.wordprocessingml.document",
+        _ => "application/octet-stream",
+    }
+    .to_string()
+}
+
+/// POST /upload/archive?client_id=..&project_id=..: store each file of an
+/// uploaded ZIP as its own upload, grouped by a shared `archive_id`
+#[handler]
+pub async fn handle_archive_upload(req: &mut Request, res: &mut Response, depot: &mut Depot) -> Result<(), salvo::Error> {
+    let state = depot.obtain::<AppState>().map_err(|_| {
+        salvo::Error::other("App state not found")
+    })?;
+
+    let client_id = req.query::<String>("client_id").ok_or_else(|| {
+        salvo::Error::other("Missing client_id parameter")
+    })?;
+    let project_id = req.query::<String>("project_id").ok_or_else(|| {
+        salvo::Error::other("Missing project_id parameter")
+    })?;
+    let client_uuid = Uuid::parse_str(&client_id).map_err(|_| {
+        salvo::Error::other("Invalid client_id format")
+    })?;
+    // Both ids become directories under the uploads root
+    validate_path_component("client_id", &client_id)?;
+    validate_path_component("project_id", &project_id)?;
+    ensure_project_member(state, depot, &project_id).await.map_err(|_| {
+        StatusError::forbidden().brief("You don't have access to this project")
+    })?;
+
+    let file = req.file("file").await.ok_or_else(|| {
+        salvo::Error::other("No file provided")
+    })?;
+    let archive_name = file.name().unwrap_or("archive.zip").to_string();
+    let content_type = file.content_type();
+    if !is_zip(&archive_name, content_type.as_ref().map(|ct| ct.essence_str())) {
+        return Err(StatusError::bad_request().brief("Only ZIP archives can be expanded").into());
+    }
+    let bytes = tokio::fs::read(file.path()).await.map_err(|e| {
+        salvo::Error::other(format!("Failed to read uploaded file: {}", e))
+    })?;
+
+    let limits = state.config.archive_limits;
+    let members = tokio::task::spawn_blocking(move || read_archive(&bytes, &limits))
+        .await
+        .map_err(|e| salvo::Error::other(format!("Failed to read archive: {}", e)))?
+        .map_err(|e| StatusError::bad_request().brief(e.to_string()))?;
+
+    let archive_id = Uuid::new_v4();
+    let uploaded_by = get_current_user_id(depot).ok();
+    let storage = storage();
+    let mut files = Vec::with_capacity(members.len());
+
+    for member in members {
+        let file_id = Uuid::new_v4();
+        let stored_filename = match Path::new(&member.path).extension().and_then(|ext| ext.to_str()) {
+            Some(extension) => format!("{}.{}", file_id, extension),
+            None => file_id.to_string(),
+        };
+        let mime = mime_for(&member.path);
+        let file_size = member.data.len() as i64;
+        let storage_key = upload_key(&client_id, &project_id, &stored_filename);
+        let file_path = storage.location(&storage_key);
+
+        let working_file = match storage.local_path(&storage_key) {
+            Some(path) => {
+                storage.put(&storage_key, member.data, Some(&mime)).await
+                    .map_err(|e| salvo::Error::other(format!("Failed to save file: {}", e)))?;
+                LocalFile::Stored(path)
+            }
+            None => {
+                let working_file = LocalFile::temp_with_data(Path::new(&stored_filename), &member.data)
+                    .map_err(|e| salvo::Error::other(format!("Failed to stage file for extraction: {}", e)))?;
+                storage.put(&storage_key, member.data, Some(&mime)).await
+                    .map_err(|e| salvo::Error::other(format!("Failed to save file: {}", e)))?;
+                working_file
+            }
+        };
+
+        let now = Utc::now();
+        sqlx::query(
+            "INSERT INTO file_uploads
+            (id, client_id, project_id, file_name, original_name, file_path, file_size,
+             mime_type, uploaded_by, extraction_status, extraction_progress, archive_id, created_at, updated_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, 'processing', 0, $10, $11, $11)"
+        )
+        .bind(file_id)
+        .bind(client_uuid)
+        .bind(&project_id)
+        .bind(&stored_filename)
+        .bind(&member.path)
+        .bind(&file_path)
+        .bind(file_size)
+        .bind(&mime)
+        .bind(uploaded_by)
+        .bind(archive_id)
+        .bind(now)
+        .execute(&state.db_pool)
+        .await
+        .map_err(|e| salvo::Error::other(format!("Database error: {}", e)))?;
+
+        // Members are extracted in the background like large single uploads;
+        // clients follow each one with subscribe_upload
+        tokio::spawn(process_upload_content(
+            state.db_pool.clone(),
+            file_id,
+            working_file,
+            member.path.clone(),
+            mime.clone(),
+        ));
+
+        files.push(serde_json::json!({
+            "id": file_id,
+            "file_name": stored_filename,
+            "original_name": member.path,
+            "file_size": file_size,
+            "mime_type": mime,
+            "uploaded_by": uploaded_by,
+            "created_at": now,
+            "extraction_status": "processing",
+            "extraction_progress": 0
+        }));
+    }
+
+    res.render(Json(serde_json::json!({
+        "archive_id": archive_id,
+        "archive_name": archive_name,
+        "files": files
+    })));
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use zip::write::SimpleFileOptions;
+
+    fn zip_of(entries: &[(&str, &str)]) -> Vec<u8> {
+        let mut writer = zip::ZipWriter::new(Cursor::new(Vec::new()));
+        for (name, content) in entries {
+            writer.start_file(*name, SimpleFileOptions::default()).unwrap();
+            writer.write_all(content.as_bytes()).unwrap();
+        }
+        writer.finish().unwrap().into_inner()
+    }
+
+    #[test]
+    fn members_of_a_normal_archive_are_read() {
+        let bytes = zip_of(&[
+            ("sales/2023.csv", "region,total\nnorth,10\n"),
+            ("sales/2024.csv", "region,total\nnorth,12\n"),
+            ("__MACOSX/sales/._2024.csv", "resource fork"),
+        ]);
+
+        let members = read_archive(&bytes, &ArchiveLimits::default()).unwrap();
+        let paths: Vec<&str> = members.iter().map(|m| m.path.as_str()).collect();
+        assert_eq!(paths, vec!["sales/2023.csv", "sales/2024.csv"]);
+        assert_eq!(members[1].data, b"region,total\nnorth,12\n");
+        assert_eq!(mime_for(&members[0].path), "text/csv");
+    }
+
+    #[test]
+    fn traversal_entries_and_oversized_archives_are_rejected() {
+        let bytes = zip_of(&[("ok.csv", "a\n"), ("../../etc/cron.d/evil", "* * * * * root sh")]);
+        assert_eq!(
+            read_archive(&bytes, &ArchiveLimits::default()).unwrap_err(),
+            ArchiveError::UnsafePath("../../etc/cron.d/evil".to_string())
+        );
+        assert!(safe_member_path("/etc/passwd").is_none());
+        assert!(safe_member_path("..\\windows\\evil.dll").is_none());
+
+        let bytes = zip_of(&[("a.csv", "0123456789"), ("b.csv", "0123456789")]);
+        let limits = ArchiveLimits { max_members: 10, max_total_uncompressed: 15 };
+        assert_eq!(read_archive(&bytes, &limits).unwrap_err(), ArchiveError::TooLarge(15));
+        let limits = ArchiveLimits { max_members: 1, max_total_uncompressed: 1024 };
+        assert_eq!(read_archive(&bytes, &limits).unwrap_err(), ArchiveError::TooManyMembers(1));
+    }
+}
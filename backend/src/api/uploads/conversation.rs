@@ -0,0 +1,166 @@
+// Linking an upload to the conversation that references it
+
+use salvo::http::StatusError;
+use salvo::prelude::*;
+use uuid::Uuid;
+use chrono::Utc;
+
+use crate::models::file_upload::FileUpload;
+use crate::utils::AppState;
+
+/// Owner of a conversation, as stored on the conversation and its project
+#[derive(Debug, Clone, PartialEq, sqlx::FromRow)]
+pub struct ConversationOwner {
+    pub client_id: Uuid,
+    pub project_id: String,
+}
+
+/// Read `conversation_id` from the request body: a string links, `null`
+/// unlinks. The field has to be present so an empty body can't unlink by
+/// accident.
+fn requested_conversation(body: &serde_json::Value) -> Result<Option<String>, &'static str> {
+    match body.get("conversation_id") {
+        None => Err("Missing conversation_id field"),
+        Some(serde_json::Value::Null) => Ok(None),
+        Some(serde_json::Value::String(id)) if !id.trim().is_empty() => Ok(Some(id.clone())),
+        Some(_) => Err("conversation_id must be a string or null"),
+    }
+}
+
+/// An upload can only be linked to a conversation in its own client's project
+fn check_same_project(upload: &FileUpload, owner: Option<&ConversationOwner>) -> Result<(), StatusError> {
+    match owner {
+        None => Err(StatusError::not_found().brief("Conversation not found")),
+        Some(owner) if owner.client_id != upload.client_id || owner.project_id != upload.project_id => {
+            Err(StatusError::bad_request().brief("Conversation belongs to a different project than the upload"))
+        }
+        Some(_) => Ok(()),
+    }
+}
+
+/// PUT /uploads/{file_id}/conversation with `{ "conversation_id": ... }`
+#[handler]
+pub async fn handle_update_file_conversation(req: &mut Request, res: &mut Response, depot: &mut Depot) -> Result<(), salvo::Error> {
+    let state = depot.obtain::<AppState>().map_err(|_| {
+        salvo::Error::other("App state not found")
+    })?;
+
+    let file_id = req.param::<String>("file_id").ok_or_else(|| {
+        salvo::Error::other("Missing file_id parameter")
+    })?;
+
+    let file_uuid = Uuid::parse_str(&file_id).map_err(|_| {
+        salvo::Error::other("Invalid file_id format")
+    })?;
+
+    let body: serde_json::Value = req.parse_json().await.map_err(|_| {
+        salvo::Error::other("Invalid JSON body")
+    })?;
+    let conversation_id = requested_conversation(&body)
+        .map_err(|e| StatusError::bad_request().brief(e))?;
+
+    let file = sqlx::query_as::<_, FileUpload>(
+        "SELECT * FROM file_uploads WHERE id = $1"
+    )
+    .bind(file_uuid)
+    .fetch_optional(&state.db_pool)
+    .await
+    .map_err(|e| {
+        salvo::Error::other(format!("Database error: {}", e))
+    })?
+    .ok_or_else(|| StatusError::not_found().brief("File not found"))?;
+
+    if let Some(conversation_id) = &conversation_id {
+        let owner = sqlx::query_as::<_, ConversationOwner>(
+            "SELECT p.client_id, c.project_id
+             FROM conversations c
+             JOIN projects p ON c.project_id = p.id
+             WHERE c.id = $1"
+        )
+        .bind(conversation_id)
+        .fetch_optional(&state.db_pool)
+        .await
+        .map_err(|e| {
+            salvo::Error::other(format!("Database error: {}", e))
+        })?;
+        check_same_project(&file, owner.as_ref())?;
+    }
+
+    sqlx::query(
+        "UPDATE file_uploads SET conversation_id = $1, updated_at = $2 WHERE id = $3"
+    )
+    .bind(&conversation_id)
+    .bind(Utc::now())
+    .bind(file_uuid)
+    .execute(&state.db_pool)
+    .await
+    .map_err(|e| {
+        salvo::Error::other(format!("Database error: {}", e))
+    })?;
+
+    res.render(Json(serde_json::json!({
+        "id": file.id,
+        "conversation_id": conversation_id
+    })));
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn upload(client_id: Uuid, project_id: &str) -> FileUpload {
+        let now = Utc::now();
+        FileUpload {
+            id: Uuid::new_v4(),
+            client_id,
+            project_id: project_id.to_string(),
+            conversation_id: None,
+            file_name: "report.csv".to_string(),
+            original_name: "report.csv".to_string(),
+            file_path: "uploads/report.csv".to_string(),
+            file_size: 12,
+            mime_type: Some("text/csv".to_string()),
+            description: None,
+            auto_description: None,
+            file_content: None,
+            metadata: None,
+            uploaded_by: None,
+            extraction_status: "ready".to_string(),
+            extraction_progress: 100,
+            archive_id: None,
+            created_at: now,
+            updated_at: now,
+        }
+    }
+
+    #[test]
+    fn uploads_link_to_conversations_in_their_project_and_unlink_with_null() {
+        let client_id = Uuid::new_v4();
+        let file = upload(client_id, "sales");
+
+        assert_eq!(requested_conversation(&json!({ "conversation_id": "conv-1" })), Ok(Some("conv-1".to_string())));
+        let same_project = ConversationOwner { client_id, project_id: "sales".to_string() };
+        assert!(check_same_project(&file, Some(&same_project)).is_ok());
+
+        assert_eq!(requested_conversation(&json!({ "conversation_id": null })), Ok(None));
+        assert!(requested_conversation(&json!({})).is_err());
+        assert!(requested_conversation(&json!({ "conversation_id": 5 })).is_err());
+    }
+
+    #[test]
+    fn conversations_from_another_project_or_client_are_rejected() {
+        let client_id = Uuid::new_v4();
+        let file = upload(client_id, "sales");
+
+        let other_project = ConversationOwner { client_id, project_id: "marketing".to_string() };
+        let err = check_same_project(&file, Some(&other_project)).unwrap_err();
+        assert_eq!(err.code, StatusCode::BAD_REQUEST);
+
+        let other_client = ConversationOwner { client_id: Uuid::new_v4(), project_id: "sales".to_string() };
+        assert_eq!(check_same_project(&file, Some(&other_client)).unwrap_err().code, StatusCode::BAD_REQUEST);
+
+        assert_eq!(check_same_project(&file, None).unwrap_err().code, StatusCode::NOT_FOUND);
+    }
+}
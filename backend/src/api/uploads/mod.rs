@@ -1,15 +1,27 @@
 // File upload functionality with content extraction
 
+mod archive;
+mod conversation;
+mod paths;
+mod reextract;
+
 use salvo::prelude::*;
 use salvo::fs::NamedFile;
+use salvo::http::header::{HeaderMap, HeaderValue, ACCEPT_RANGES, CONTENT_LENGTH, CONTENT_RANGE, CONTENT_TYPE, RANGE};
+use crate::api::websocket::{broadcast_upload_progress, WebSocketServerMessage as ServerMessage};
 use crate::models::file_upload::FileUpload;
-use crate::utils::content_extractor::ContentExtractor;
+use crate::utils::content_extractor::{ContentExtractionError, ContentExtractor, ExtractedContent, ExtractionProgress};
+use crate::utils::storage::{excel_export_prefix, storage, upload_key, LocalFile, StorageBackend};
+use crate::utils::middleware::get_current_user_id;
 use crate::utils::AppState;
+use sqlx::PgPool;
+use tokio::sync::mpsc;
 use uuid::Uuid;
-use std::fs;
 use std::path::Path;
 use chrono::Utc;
 
+use paths::{ensure_within, validate_path_component};
+
 #[handler]
 pub async fn handle_excel_download(req: &mut Request, res: &mut Response) -> Result<(), salvo::Error> {
     let client_id = req.param::<String>("client_id").ok_or_else(|| {
@@ -24,39 +36,27 @@ pub async fn handle_excel_download(req: &mut Request, res: &mut Response) -> Res
         salvo::Error::other("Missing export_id parameter")
     })?;
 
-    // Find the Excel file in the excel_exports directory
-    let excel_dir = format!(".clients/{}/{}/excel_exports", client_id, project_id);
-    
-    if !Path::new(&excel_dir).exists() {
-        return Err(salvo::Error::other("Excel export directory not found"));
-    }
+    validate_path_component("client_id", &client_id)?;
+    validate_path_component("project_id", &project_id)?;
+    validate_path_component("export_id", &export_id)?;
 
-    // Look for files that start with the export_id
-    let dir_entries = fs::read_dir(&excel_dir).map_err(|e| {
-        salvo::Error::other(format!("Failed to read excel directory: {}", e))
+    // Exports are stored as {export_id}_{pretty_name}.xlsx
+    let storage = storage();
+    let exports_dir = excel_export_prefix(&client_id, &project_id);
+    let export_prefix = format!("{}{}", exports_dir, export_id);
+    let exports = storage.list(&export_prefix).await.map_err(|e| {
+        salvo::Error::other(format!("Failed to list excel exports: {}", e))
     })?;
 
-    let mut excel_file_path = None;
-    for entry in dir_entries {
-        if let Ok(entry) = entry {
-            let file_name = entry.file_name();
-            let file_name_str = file_name.to_string_lossy();
-            if file_name_str.starts_with(&export_id) && file_name_str.ends_with(".xlsx") {
-                excel_file_path = Some(entry.path());
-                break;
-            }
-        }
-    }
+    let export_key = exports
+        .into_iter()
+        .map(|object| object.key)
+        .find(|key| key.ends_with(".xlsx"))
+        .ok_or_else(|| salvo::Error::other("Excel file not found"))?;
 
-    let file_path = excel_file_path.ok_or_else(|| {
-        salvo::Error::other("Excel file not found")
-    })?;
-
-    // Extract pretty filename from the file path
+    // Extract pretty filename from the key
     // Format is: {export_id}_{pretty_name}.xlsx
-    let file_name = file_path.file_name()
-        .and_then(|name| name.to_str())
-        .unwrap_or("export.xlsx");
+    let file_name = export_key.rsplit('/').next().unwrap_or("export.xlsx");
     
     let pretty_filename = if let Some(underscore_pos) = file_name.find('_') {
         // Extract everything after the first underscore
@@ -67,26 +67,139 @@ pub async fn handle_excel_download(req: &mut Request, res: &mut Response) -> Res
     };
 
     // Set Content-Disposition header for pretty download filename
+    if let Ok(value) = format!("attachment; filename=\"{}\"", pretty_filename).parse() {
+        res.headers_mut().insert("Content-Disposition", value);
+    }
+
+    // NamedFile answers Range requests itself; remote objects go through send_stored_object
+    if let Some(local_path) = storage.local_path(&export_key) {
+        let exports_root = storage.local_path(&exports_dir).unwrap_or_default();
+        let local_path = ensure_within(&exports_root, &local_path)?;
+        let named_file = NamedFile::builder(local_path).build().await.map_err(|e| {
+            salvo::Error::other(format!("Failed to serve excel file: {}", e))
+        })?;
+        named_file.send(req.headers(), res).await;
+        return Ok(());
+    }
+
+    send_stored_object(
+        req.headers(),
+        res,
+        storage.as_ref(),
+        &export_key,
+        "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet",
+    )
+    .await
+}
+
+/// What a `Range` request header asks for, given the object size
+#[derive(Debug, PartialEq, Eq)]
+enum ByteRange {
+    Full,
+    Partial { start: u64, end: u64 },
+    Unsatisfiable,
+}
+
+/// Parse a single `bytes=` range (`a-b`, `a-` or `-suffix`). Malformed or
+/// multi-range headers are ignored and the whole object is sent, as RFC 9110 allows.
+fn parse_byte_range(header: Option<&str>, size: u64) -> ByteRange {
+    let Some(spec) = header.and_then(|h| h.trim().strip_prefix("bytes=")) else {
+        return ByteRange::Full;
+    };
+    if spec.contains(',') {
+        return ByteRange::Full;
+    }
+    let Some((start, end)) = spec.trim().split_once('-') else {
+        return ByteRange::Full;
+    };
+    let (start, end) = (start.trim(), end.trim());
+
+    let (start, end) = if start.is_empty() {
+        // Suffix range: the last N bytes
+        match end.parse::<u64>() {
+            Ok(0) => return ByteRange::Unsatisfiable,
+            Ok(suffix) => (size.saturating_sub(suffix), size.saturating_sub(1)),
+            Err(_) => return ByteRange::Full,
+        }
+    } else {
+        let Ok(start) = start.parse::<u64>() else {
+            return ByteRange::Full;
+        };
+        let end = if end.is_empty() {
+            size.saturating_sub(1)
+        } else {
+            match end.parse::<u64>() {
+                Ok(end) if end >= start => end.min(size.saturating_sub(1)),
+                _ => return ByteRange::Full,
+            }
+        };
+        (start, end)
+    };
+
+    if size == 0 || start >= size {
+        return ByteRange::Unsatisfiable;
+    }
+    ByteRange::Partial { start, end }
+}
+
+/// Send an object that isn't on local disk, honouring a byte `Range` request so
+/// interrupted downloads of large files can resume
+async fn send_stored_object(
+    req_headers: &HeaderMap,
+    res: &mut Response,
+    storage: &dyn StorageBackend,
+    key: &str,
+    content_type: &str,
+) -> Result<(), salvo::Error> {
+    let size = storage.size(key).await.map_err(|e| {
+        salvo::Error::other(format!("Failed to read file: {}", e))
+    })?;
+    let range_header = req_headers.get(RANGE).and_then(|v| v.to_str().ok());
+
+    res.headers_mut().insert(ACCEPT_RANGES, HeaderValue::from_static("bytes"));
     res.headers_mut().insert(
-        "Content-Disposition",
-        format!("attachment; filename=\"{}\"", pretty_filename).parse().unwrap()
+        CONTENT_TYPE,
+        content_type
+            .parse()
+            .unwrap_or_else(|_| HeaderValue::from_static("application/octet-stream")),
     );
 
-    let named_file = NamedFile::builder(file_path).build().await.map_err(|e| {
-        salvo::Error::other(format!("Failed to serve excel file: {}", e))
-    })?;
+    let data = match parse_byte_range(range_header, size) {
+        ByteRange::Full => storage.get(key).await,
+        ByteRange::Partial { start, end } => {
+            res.status_code(StatusCode::PARTIAL_CONTENT);
+            if let Ok(value) = format!("bytes {}-{}/{}", start, end, size).parse() {
+                res.headers_mut().insert(CONTENT_RANGE, value);
+            }
+            storage.get_range(key, start, end).await
+        }
+        ByteRange::Unsatisfiable => {
+            res.status_code(StatusCode::RANGE_NOT_SATISFIABLE);
+            if let Ok(value) = format!("bytes */{}", size).parse() {
+                res.headers_mut().insert(CONTENT_RANGE, value);
+            }
+            return Ok(());
+        }
+    }
+    .map_err(|e| salvo::Error::other(format!("Failed to read file: {}", e)))?;
 
-    named_file.send(req.headers(), res).await;
+    res.headers_mut().insert(CONTENT_LENGTH, HeaderValue::from(data.len()));
+    res.write_body(data).map_err(|e| {
+        salvo::Error::other(format!("Failed to send file: {}", e))
+    })?;
     Ok(())
 }
 
 pub fn upload_routes() -> Router {
     Router::new()
         .push(Router::with_path("/upload").post(handle_file_upload))
+        .push(Router::with_path("/upload/archive").post(archive::handle_archive_upload))
         .push(Router::with_path("/uploads").get(handle_list_uploads))
         .push(Router::with_path("/uploads/{client_id}/{project_id}/{file_name}").get(handle_file_download))
         .push(Router::with_path("/uploads/{file_id}").delete(handle_delete_upload))
         .push(Router::with_path("/uploads/{file_id}/description").put(handle_update_file_description))
+        .push(Router::with_path("/uploads/{file_id}/conversation").put(conversation::handle_update_file_conversation))
+        .push(Router::with_path("/uploads/{file_id}/reextract").post(reextract::handle_reextract_upload))
         .push(Router::with_path("/files/excel/{client_id}/{project_id}/{export_id}").get(handle_excel_download))
 }
 
@@ -119,12 +232,6 @@ pub async fn handle_file_upload(req: &mut Request, res: &mut Response, depot: &m
     let mime_type = file.content_type().map(|ct| ct.to_string());
     let file_size = file.size();
 
-    // Create upload directory
-    let upload_dir = format!(".clients/{}/{}/uploads", client_id, project_id);
-    fs::create_dir_all(&upload_dir).map_err(|e| {
-        salvo::Error::other(format!("Failed to create upload directory: {}", e))
-    })?;
-
     // Generate unique filename
     let file_id = Uuid::new_v4();
     let file_extension = Path::new(&original_name)
@@ -136,32 +243,31 @@ pub async fn handle_file_upload(req: &mut Request, res: &mut Response, depot: &m
     } else {
         format!("{}.{}", file_id, file_extension)
     };
-    let file_path = format!("{}/{}", upload_dir, stored_filename);
-
-    // Save file to filesystem
+    // Save file to the configured storage backend
+    let storage = storage();
+    let storage_key = upload_key(&client_id, &project_id, &stored_filename);
+    let file_path = storage.location(&storage_key);
     let temp_path = file.path();
-    fs::copy(temp_path, &file_path).map_err(|e| {
+    storage.put_file(&storage_key, temp_path, mime_type.as_deref()).await.map_err(|e| {
         salvo::Error::other(format!("Failed to save file: {}", e))
     })?;
 
-    // Extract content using our content extractor with large file handling
-    let extracted = ContentExtractor::extract_content(
-        Path::new(&file_path),
-        &original_name,
-        &mime_type.clone().unwrap_or_else(|| "application/octet-stream".to_string()),
-    ).await.map_err(|e| {
-        salvo::Error::other(format!("Content extraction failed: {}", e))
-    })?;
+    // Extraction needs the file on disk; remote backends parse a temp copy of the upload
+    let working_file = match storage.local_path(&storage_key) {
+        Some(path) => LocalFile::Stored(path),
+        None => LocalFile::temp_copy(temp_path).map_err(|e| {
+            salvo::Error::other(format!("Failed to stage file for extraction: {}", e))
+        })?,
+    };
 
-    // Get file size for response metadata
-    let file_metadata = fs::metadata(&file_path).map_err(|e| {
-        salvo::Error::other(format!("Failed to get file metadata: {}", e))
-    })?;
-    let file_size_mb = file_metadata.len() as f64 / (1024.0 * 1024.0);
-    let limits = ContentExtractor::get_limits();
-    let is_large_file = file_metadata.len() > limits.max_full_parse_size;
+    let mime = mime_type.clone().unwrap_or_else(|| "application/octet-stream".to_string());
+    let limits = state.config.extraction_limits;
+    let file_size_mb = file_size as f64 / (1024.0 * 1024.0);
+    let is_large_file = limits.is_large_file(file_size);
+    // Files past the parse limit only get basic metadata, which is quick either way
+    let extract_in_background = !is_large_file && file_size > state.config.upload_async_extraction_bytes;
 
-    // Create file upload record
+    // Create file upload record; content is filled in once extraction finishes
     let file_upload = FileUpload {
         id: file_id,
         client_id: client_uuid,
@@ -173,10 +279,13 @@ pub async fn handle_file_upload(req: &mut Request, res: &mut Response, depot: &m
         file_size: file_size as i64,
         mime_type: mime_type.clone(),
         description: None,
-        auto_description: extracted.description.clone(),
-        file_content: extracted.text_content.clone(),
-        metadata: extracted.structured_data.clone(),
-        uploaded_by: None, // TODO: Get from auth context
+        auto_description: None,
+        file_content: None,
+        metadata: None,
+        uploaded_by: get_current_user_id(depot).ok(),
+        extraction_status: "processing".to_string(),
+        extraction_progress: 0,
+        archive_id: None,
         created_at: Utc::now(),
         updated_at: Utc::now(),
     };
@@ -185,7 +294,7 @@ pub async fn handle_file_upload(req: &mut Request, res: &mut Response, depot: &m
     sqlx::query(
         "INSERT INTO file_uploads 
         (id, client_id, project_id, file_name, original_name, file_path, file_size, 
-         mime_type, auto_description, file_content, metadata, created_at, updated_at)
+         mime_type, uploaded_by, extraction_status, extraction_progress, created_at, updated_at)
         VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13)"
     )
     .bind(file_upload.id)
@@ -196,9 +305,9 @@ pub async fn handle_file_upload(req: &mut Request, res: &mut Response, depot: &m
     .bind(&file_upload.file_path)
     .bind(file_upload.file_size)
     .bind(&file_upload.mime_type)
-    .bind(&file_upload.auto_description)
-    .bind(&file_upload.file_content)
-    .bind(&file_upload.metadata)
+    .bind(file_upload.uploaded_by)
+    .bind(&file_upload.extraction_status)
+    .bind(file_upload.extraction_progress)
     .bind(file_upload.created_at)
     .bind(file_upload.updated_at)
     .execute(&state.db_pool)
@@ -207,6 +316,42 @@ pub async fn handle_file_upload(req: &mut Request, res: &mut Response, depot: &m
         salvo::Error::other(format!("Database error: {}", e))
     })?;
 
+    if extract_in_background {
+        // The client follows progress with a subscribe_upload WebSocket message
+        tokio::spawn(process_upload_content(
+            state.db_pool.clone(),
+            file_upload.id,
+            working_file,
+            original_name.clone(),
+            mime,
+        ));
+
+        res.render(Json(serde_json::json!({
+            "id": file_upload.id,
+            "file_name": file_upload.file_name,
+            "original_name": file_upload.original_name,
+            "file_size": file_upload.file_size,
+            "file_size_mb": file_size_mb,
+            "mime_type": file_upload.mime_type,
+            "uploaded_by": file_upload.uploaded_by,
+            "created_at": file_upload.created_at,
+            "is_large_file": is_large_file,
+            "extraction_status": "processing",
+            "extraction_progress": 0
+        })));
+        return Ok(());
+    }
+
+    let extracted = process_upload_content(
+        state.db_pool.clone(),
+        file_upload.id,
+        working_file,
+        original_name.clone(),
+        mime,
+    ).await.map_err(|e| {
+        salvo::Error::other(format!("Content extraction failed: {}", e))
+    })?;
+
     // Return success response with file info and large file handling details
     let mut response = serde_json::json!({
         "id": file_upload.id,
@@ -215,12 +360,15 @@ pub async fn handle_file_upload(req: &mut Request, res: &mut Response, depot: &m
         "file_size": file_upload.file_size,
         "file_size_mb": file_size_mb,
         "mime_type": file_upload.mime_type,
-        "description": file_upload.auto_description,
-        "auto_description": file_upload.auto_description,
-        "has_text_content": file_upload.file_content.is_some(),
+        "uploaded_by": file_upload.uploaded_by,
+        "description": extracted.description,
+        "auto_description": extracted.description,
+        "has_text_content": extracted.text_content.is_some(),
         "preview": extracted.preview,
         "created_at": file_upload.created_at,
-        "is_large_file": is_large_file
+        "is_large_file": is_large_file,
+        "extraction_status": "ready",
+        "extraction_progress": 100
     });
 
     // Add large file handling info if applicable
@@ -244,6 +392,111 @@ pub async fn handle_file_upload(req: &mut Request, res: &mut Response, depot: &m
     Ok(())
 }
 
+/// Extract an upload's content, pushing progress to WebSocket subscribers of the
+/// upload and recording the outcome on its file record
+async fn process_upload_content(
+    db_pool: PgPool,
+    upload_id: Uuid,
+    working_file: LocalFile,
+    original_name: String,
+    mime_type: String,
+) -> Result<ExtractedContent, ContentExtractionError> {
+    let upload_key = upload_id.to_string();
+
+    // The extractor reports synchronously; forward to an async task that broadcasts
+    let (progress_tx, mut progress_rx) = mpsc::unbounded_channel::<ExtractionProgress>();
+    let forward_pool = db_pool.clone();
+    let forward_key = upload_key.clone();
+    let forwarder = tokio::spawn(async move {
+        let mut last_percent = 0;
+        while let Some(progress) = progress_rx.recv().await {
+            let percent = progress.percent() as i32;
+            broadcast_upload_progress(&forward_key, ServerMessage::UploadProgress {
+                upload_id: forward_key.clone(),
+                status: "processing".to_string(),
+                unit: Some(progress.unit.to_string()),
+                processed: Some(progress.processed),
+                total: Some(progress.total),
+                percent,
+                error: None,
+            }).await;
+
+            // Only touch the record when the percentage actually moves
+            if percent != last_percent {
+                last_percent = percent;
+                let _ = sqlx::query(
+                    "UPDATE file_uploads SET extraction_progress = $1, updated_at = NOW() WHERE id = $2"
+                )
+                .bind(percent)
+                .bind(upload_id)
+                .execute(&forward_pool)
+                .await;
+            }
+        }
+    });
+
+    let report = move |progress: ExtractionProgress| {
+        let _ = progress_tx.send(progress);
+    };
+    let result = ContentExtractor::extract_content_with_progress(
+        working_file.path(),
+        &original_name,
+        &mime_type,
+        Some(&report),
+    ).await;
+
+    // Dropping the callback closes the channel so the forwarder drains and exits
+    drop(report);
+    let _ = forwarder.await;
+
+    let (status, error) = match &result {
+        Ok(extracted) => {
+            let update = sqlx::query(
+                "UPDATE file_uploads
+                 SET auto_description = $1, file_content = $2, metadata = $3,
+                     extraction_status = 'ready', extraction_progress = 100, updated_at = NOW()
+                 WHERE id = $4"
+            )
+            .bind(&extracted.description)
+            .bind(&extracted.text_content)
+            .bind(&extracted.structured_data)
+            .bind(upload_id)
+            .execute(&db_pool)
+            .await;
+
+            if let Err(e) = update {
+                tracing::error!("Failed to store extracted content for upload {}: {}", upload_id, e);
+            }
+            ("ready", None)
+        }
+        Err(e) => {
+            tracing::error!("Content extraction failed for upload {}: {}", upload_id, e);
+            let _ = sqlx::query(
+                "UPDATE file_uploads
+                 SET metadata = $1, extraction_status = 'failed', extraction_progress = 100, updated_at = NOW()
+                 WHERE id = $2"
+            )
+            .bind(serde_json::json!({ "extraction_error": e.to_string() }))
+            .bind(upload_id)
+            .execute(&db_pool)
+            .await;
+            ("failed", Some(e.to_string()))
+        }
+    };
+
+    broadcast_upload_progress(&upload_key, ServerMessage::UploadProgress {
+        upload_id: upload_key.clone(),
+        status: status.to_string(),
+        unit: None,
+        processed: None,
+        total: None,
+        percent: 100,
+        error,
+    }).await;
+
+    result
+}
+
 #[handler]
 pub async fn handle_list_uploads(req: &mut Request, res: &mut Response, depot: &mut Depot) -> Result<(), salvo::Error> {
     let state = depot.obtain::<AppState>().map_err(|_| {
@@ -281,7 +534,11 @@ pub async fn handle_list_uploads(req: &mut Request, res: &mut Response, depot: &
 }
 
 #[handler]
-pub async fn handle_file_download(req: &mut Request, res: &mut Response) -> Result<(), salvo::Error> {
+pub async fn handle_file_download(req: &mut Request, res: &mut Response, depot: &mut Depot) -> Result<(), salvo::Error> {
+    let state = depot.obtain::<AppState>().map_err(|_| {
+        salvo::Error::other("App state not found")
+    })?;
+
     let client_id = req.param::<String>("client_id").ok_or_else(|| {
         salvo::Error::other("Missing client_id parameter")
     })?;
@@ -294,18 +551,49 @@ pub async fn handle_file_download(req: &mut Request, res: &mut Response) -> Resu
         salvo::Error::other("Missing file_name parameter")
     })?;
 
-    let file_path = format!(".clients/{}/{}/uploads/{}", client_id, project_id, file_name);
-    
-    if !Path::new(&file_path).exists() {
-        return Err(salvo::Error::other("File not found"));
-    }
+    validate_path_component("client_id", &client_id)?;
+    validate_path_component("project_id", &project_id)?;
+    validate_path_component("file_name", &file_name)?;
 
-    let named_file = NamedFile::builder(file_path).build().await.map_err(|e| {
-        salvo::Error::other(format!("Failed to serve file: {}", e))
-    })?;
+    let storage = storage();
+    let storage_key = upload_key(&client_id, &project_id, &file_name);
 
-    named_file.send(req.headers(), res).await;
-    Ok(())
+    // NamedFile answers Range requests itself; remote objects go through send_stored_object
+    if let Some(local_path) = storage.local_path(&storage_key) {
+        if !local_path.exists() {
+            return Err(salvo::Error::other("File not found"));
+        }
+        let uploads_root = storage.local_path(&upload_key(&client_id, &project_id, "")).unwrap_or_default();
+        let local_path = ensure_within(&uploads_root, &local_path)?;
+
+        let named_file = NamedFile::builder(local_path).build().await.map_err(|e| {
+            salvo::Error::other(format!("Failed to serve file: {}", e))
+        })?;
+
+        named_file.send(req.headers(), res).await;
+        return Ok(());
+    }
+
+    // Remote objects don't carry a usable type here, so use the one recorded at upload
+    let mime_type: Option<String> = sqlx::query_scalar(
+        "SELECT mime_type FROM file_uploads WHERE file_name = $1 AND project_id = $2"
+    )
+    .bind(&file_name)
+    .bind(&project_id)
+    .fetch_optional(&state.db_pool)
+    .await
+    .ok()
+    .flatten()
+    .flatten();
+
+    send_stored_object(
+        req.headers(),
+        res,
+        storage.as_ref(),
+        &storage_key,
+        mime_type.as_deref().unwrap_or("application/octet-stream"),
+    )
+    .await
 }
 
 #[handler]
@@ -334,12 +622,11 @@ pub async fn handle_delete_upload(req: &mut Request, res: &mut Response, depot:
     })?;
 
     if let Some(file) = file_info {
-        // Delete file from filesystem
-        if Path::new(&file.file_path).exists() {
-            fs::remove_file(&file.file_path).map_err(|e| {
-                salvo::Error::other(format!("Failed to delete file: {}", e))
-            })?;
-        }
+        // Delete file from storage
+        let storage = storage();
+        storage.delete(&storage.key_from_location(&file.file_path)).await.map_err(|e| {
+            salvo::Error::other(format!("Failed to delete file: {}", e))
+        })?;
 
         // Delete from database
         sqlx::query("DELETE FROM file_uploads WHERE id = $1")
@@ -403,4 +690,133 @@ pub async fn handle_update_file_description(req: &mut Request, res: &mut Respons
     })));
     
     Ok(())
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::storage::LocalStorage;
+
+    const FILE_SIZE: usize = 1000;
+
+    fn range_headers(range: &str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(RANGE, HeaderValue::from_str(range).unwrap());
+        headers
+    }
+
+    #[test]
+    fn parses_byte_ranges() {
+        assert_eq!(parse_byte_range(None, 1000), ByteRange::Full);
+        assert_eq!(
+            parse_byte_range(Some("bytes=0-99"), 1000),
+            ByteRange::Partial { start: 0, end: 99 }
+        );
+        assert_eq!(
+            parse_byte_range(Some("bytes=900-"), 1000),
+            ByteRange::Partial { start: 900, end: 999 }
+        );
+        assert_eq!(
+            parse_byte_range(Some("bytes=-100"), 1000),
+            ByteRange::Partial { start: 900, end: 999 }
+        );
+        assert_eq!(
+            parse_byte_range(Some("bytes=950-2000"), 1000),
+            ByteRange::Partial { start: 950, end: 999 }
+        );
+        assert_eq!(parse_byte_range(Some("bytes=1000-"), 1000), ByteRange::Unsatisfiable);
+        assert_eq!(parse_byte_range(Some("bytes=0-1,5-6"), 1000), ByteRange::Full);
+        assert_eq!(parse_byte_range(Some("items=0-10"), 1000), ByteRange::Full);
+    }
+
+    #[tokio::test]
+    async fn stored_object_serves_requested_range() {
+        let dir = tempfile::tempdir().unwrap();
+        let storage = LocalStorage::new(dir.path());
+        storage.put("client/project/uploads/data.csv", vec![b'x'; FILE_SIZE], None).await.unwrap();
+
+        let mut res = Response::new();
+        send_stored_object(
+            &range_headers("bytes=0-99"),
+            &mut res,
+            &storage,
+            "client/project/uploads/data.csv",
+            "text/csv",
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(res.status_code, Some(StatusCode::PARTIAL_CONTENT));
+        assert_eq!(res.headers().get(CONTENT_LENGTH).unwrap(), "100");
+        assert_eq!(res.headers().get(CONTENT_RANGE).unwrap(), "bytes 0-99/1000");
+        assert_eq!(res.headers().get(ACCEPT_RANGES).unwrap(), "bytes");
+    }
+
+    #[tokio::test]
+    async fn stored_object_rejects_range_past_the_end() {
+        let dir = tempfile::tempdir().unwrap();
+        let storage = LocalStorage::new(dir.path());
+        storage.put("k", vec![0; FILE_SIZE], None).await.unwrap();
+
+        let mut res = Response::new();
+        send_stored_object(&range_headers("bytes=5000-"), &mut res, &storage, "k", "text/plain")
+            .await
+            .unwrap();
+
+        assert_eq!(res.status_code, Some(StatusCode::RANGE_NOT_SATISFIABLE));
+        assert_eq!(res.headers().get(CONTENT_RANGE).unwrap(), "bytes */1000");
+    }
+
+    #[tokio::test]
+    async fn local_file_serves_requested_range() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("data.csv");
+        std::fs::write(&path, vec![b'x'; FILE_SIZE]).unwrap();
+
+        let mut res = Response::new();
+        NamedFile::builder(&path)
+            .build()
+            .await
+            .unwrap()
+            .send(&range_headers("bytes=0-99"), &mut res)
+            .await;
+
+        assert_eq!(res.status_code, Some(StatusCode::PARTIAL_CONTENT));
+        assert_eq!(res.headers().get(CONTENT_LENGTH).unwrap(), "100");
+    }
+
+    #[test]
+    fn uploader_comes_from_the_auth_context_and_is_returned() {
+        let user_id = Uuid::new_v4();
+        let mut depot = Depot::new();
+        depot.insert("current_user_id", user_id.to_string());
+
+        let now = Utc::now();
+        let upload = FileUpload {
+            id: Uuid::new_v4(),
+            client_id: Uuid::new_v4(),
+            project_id: "sales".to_string(),
+            conversation_id: None,
+            file_name: "report.csv".to_string(),
+            original_name: "report.csv".to_string(),
+            file_path: "uploads/report.csv".to_string(),
+            file_size: 12,
+            mime_type: Some("text/csv".to_string()),
+            description: None,
+            auto_description: None,
+            file_content: None,
+            metadata: None,
+            uploaded_by: get_current_user_id(&depot).ok(),
+            extraction_status: "ready".to_string(),
+            extraction_progress: 100,
+            archive_id: None,
+            created_at: now,
+            updated_at: now,
+        };
+
+        assert_eq!(upload.uploaded_by, Some(user_id));
+        assert_eq!(upload.to_response().uploaded_by, Some(user_id.to_string()));
+
+        // Requests without a signed-in user still upload, just unattributed
+        assert_eq!(get_current_user_id(&Depot::new()).ok(), None);
+    }
+}
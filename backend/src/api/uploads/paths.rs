@@ -0,0 +1,88 @@
+// Guards for storage paths built from URL parameters
+
+use salvo::http::StatusError;
+use std::path::{Path, PathBuf};
+
+/// Reject a URL parameter that could step outside the directory it is joined onto
+pub fn validate_path_component(name: &str, value: &str) -> Result<(), salvo::Error> {
+    if value.is_empty()
+        || value.contains('/')
+        || value.contains('\\')
+        || value.contains("..")
+        || value.contains('\0')
+    {
+        return Err(StatusError::bad_request()
+            .brief(format!("Invalid {} parameter", name))
+            .into());
+    }
+    Ok(())
+}
+
+/// Resolve symlinks and `..` in `path` and confirm it still lives under `base`
+///
+/// Both paths must exist; the canonical path of the file is returned.
+pub fn ensure_within(base: &Path, path: &Path) -> Result<PathBuf, salvo::Error> {
+    let canonical_base = base
+        .canonicalize()
+        .map_err(|_| salvo::Error::other("File not found"))?;
+    let canonical_path = path
+        .canonicalize()
+        .map_err(|_| salvo::Error::other("File not found"))?;
+
+    if !canonical_path.starts_with(&canonical_base) {
+        return Err(StatusError::bad_request().brief("Invalid file path").into());
+    }
+    Ok(canonical_path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn is_bad_request(result: Result<impl std::fmt::Debug, salvo::Error>) -> bool {
+        matches!(result, Err(salvo::Error::HttpStatus(e)) if e.code == salvo::http::StatusCode::BAD_REQUEST)
+    }
+
+    #[test]
+    fn rejects_traversal_components() {
+        for value in [
+            "../../../../etc/passwd",
+            "..",
+            "uploads/../secret.csv",
+            "nested/file.csv",
+            "..\\..\\windows\\win.ini",
+            "",
+        ] {
+            assert!(is_bad_request(validate_path_component("file_name", value)), "{:?}", value);
+        }
+        assert!(validate_path_component("file_name", "1712_report.csv").is_ok());
+        assert!(validate_path_component("client_id", "9f1c2d3e-client").is_ok());
+    }
+
+    #[test]
+    fn ensure_within_rejects_paths_outside_base() {
+        let dir = tempfile::tempdir().unwrap();
+        let uploads = dir.path().join("client/project/uploads");
+        std::fs::create_dir_all(&uploads).unwrap();
+        std::fs::write(uploads.join("data.csv"), b"a,b").unwrap();
+        std::fs::write(dir.path().join("secret.txt"), b"secret").unwrap();
+
+        let inside = ensure_within(&uploads, &uploads.join("data.csv")).unwrap();
+        assert!(inside.ends_with("data.csv"));
+
+        let escaped = uploads.join("../../../secret.txt");
+        assert!(is_bad_request(ensure_within(&uploads, &escaped)));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn ensure_within_rejects_symlinks_out_of_base() {
+        let dir = tempfile::tempdir().unwrap();
+        let uploads = dir.path().join("uploads");
+        std::fs::create_dir_all(&uploads).unwrap();
+        std::fs::write(dir.path().join("secret.txt"), b"secret").unwrap();
+        std::os::unix::fs::symlink(dir.path().join("secret.txt"), uploads.join("link.csv")).unwrap();
+
+        assert!(is_bad_request(ensure_within(&uploads, &uploads.join("link.csv"))));
+    }
+}
@@ -0,0 +1,201 @@
+// Re-running content extraction on an existing upload
+
+use salvo::http::StatusError;
+use salvo::prelude::*;
+use sqlx::PgPool;
+use std::path::Path;
+use uuid::Uuid;
+
+use super::process_upload_content;
+use crate::api::projects::queries::ensure_project_member;
+use crate::models::file_upload::FileUpload;
+use crate::utils::content_extractor::ExtractedContent;
+use crate::utils::storage::{storage, LocalFile, StorageBackend, StorageError};
+use crate::utils::AppState;
+
+/// The stored file on local disk for the extractor: the file itself when the
+/// backend keeps it locally, otherwise a temp copy of the object
+async fn working_copy(
+    storage: &dyn StorageBackend,
+    key: &str,
+    file_name: &str,
+) -> Result<LocalFile, StorageError> {
+    if let Some(path) = storage.local_path(key) {
+        if !path.exists() {
+            return Err(StorageError::NotFound(key.to_string()));
+        }
+        return Ok(LocalFile::Stored(path));
+    }
+    let data = storage.get(key).await?;
+    Ok(LocalFile::temp_with_data(Path::new(file_name), &data)?)
+}
+
+/// Extract `file` from storage again, replacing whatever extracted content,
+/// description and metadata were recorded for it
+async fn reextract(
+    db_pool: &PgPool,
+    storage: &dyn StorageBackend,
+    file: &FileUpload,
+) -> Result<ExtractedContent, salvo::Error> {
+    let working_file = working_copy(
+        storage,
+        &storage.key_from_location(&file.file_path),
+        &file.file_name,
+    )
+    .await
+    .map_err(|e| {
+        salvo::Error::other(format!("Failed to load stored file: {}", e))
+    })?;
+
+    sqlx::query(
+        "UPDATE file_uploads SET extraction_status = 'processing', extraction_progress = 0, updated_at = NOW() WHERE id = $1"
+    )
+    .bind(file.id)
+    .execute(db_pool)
+    .await
+    .map_err(|e| {
+        salvo::Error::other(format!("Database error: {}", e))
+    })?;
+
+    let mime = file.mime_type.clone().unwrap_or_else(|| "application/octet-stream".to_string());
+    process_upload_content(
+        db_pool.clone(),
+        file.id,
+        working_file,
+        file.original_name.clone(),
+        mime,
+    ).await.map_err(|e| {
+        salvo::Error::other(format!("Content extraction failed: {}", e))
+    })
+}
+
+/// POST /uploads/{file_id}/reextract: extract the stored file again and
+/// replace the upload's extracted content, description and metadata. Only
+/// members of the upload's project may do this.
+#[handler]
+pub async fn handle_reextract_upload(req: &mut Request, res: &mut Response, depot: &mut Depot) -> Result<(), salvo::Error> {
+    let state = depot.obtain::<AppState>().map_err(|_| {
+        salvo::Error::other("App state not found")
+    })?;
+
+    let file_id = req.param::<String>("file_id").ok_or_else(|| {
+        salvo::Error::other("Missing file_id parameter")
+    })?;
+
+    let file_uuid = Uuid::parse_str(&file_id).map_err(|_| {
+        salvo::Error::other("Invalid file_id format")
+    })?;
+
+    let file = sqlx::query_as::<_, FileUpload>(
+        "SELECT * FROM file_uploads WHERE id = $1"
+    )
+    .bind(file_uuid)
+    .fetch_optional(&state.db_pool)
+    .await
+    .map_err(|e| {
+        salvo::Error::other(format!("Database error: {}", e))
+    })?
+    .ok_or_else(|| StatusError::not_found().brief("File not found"))?;
+
+    ensure_project_member(state, depot, &file.project_id).await.map_err(|_| {
+        StatusError::forbidden().brief("You don't have access to this upload")
+    })?;
+
+    let extracted = reextract(&state.db_pool, storage().as_ref(), &file).await?;
+
+    // The extractor applies the same size limits as at upload time: files past
+    // the parse limit only get basic metadata
+    let is_large_file = state.config.extraction_limits.is_large_file(file.file_size as u64);
+
+    res.render(Json(serde_json::json!({
+        "id": file.id,
+        "file_name": file.file_name,
+        "original_name": file.original_name,
+        "auto_description": extracted.description,
+        "has_text_content": extracted.text_content.is_some(),
+        "preview": extracted.preview,
+        "is_large_file": is_large_file,
+        "extraction_status": "ready",
+        "extraction_progress": 100
+    })));
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::content_extractor::ContentExtractor;
+    use crate::utils::storage::LocalStorage;
+
+    #[tokio::test]
+    async fn stored_upload_is_extracted_again_from_storage() {
+        let dir = tempfile::tempdir().unwrap();
+        let storage = LocalStorage::new(dir.path());
+        let key = "client/project/uploads/notes.txt";
+        let text = "quarterly revenue grew 12% in the north region";
+        storage.put(key, text.as_bytes().to_vec(), Some("text/plain")).await.unwrap();
+
+        // Whatever was stored for the upload before, the file itself is the source
+        let working_file = working_copy(&storage, key, "notes.txt").await.unwrap();
+        let extracted = ContentExtractor::extract_content(working_file.path(), "notes.txt", "text/plain")
+            .await
+            .unwrap();
+        assert!(extracted.text_content.unwrap().contains(text));
+
+        let missing = working_copy(&storage, "client/project/uploads/gone.txt", "gone.txt").await;
+        assert!(matches!(missing, Err(StorageError::NotFound(_))));
+    }
+
+    #[tokio::test]
+    #[ignore = "needs a migrated Clay Studio database in TEST_DATABASE_URL"]
+    async fn corrupted_extraction_is_restored_from_the_stored_file() {
+        let url = std::env::var("TEST_DATABASE_URL").expect("Set TEST_DATABASE_URL");
+        let pool = PgPool::connect(&url).await.unwrap();
+
+        let dir = tempfile::tempdir().unwrap();
+        let storage = LocalStorage::new(dir.path());
+        let key = "client/project/uploads/notes.txt";
+        let text = "quarterly revenue grew 12% in the north region";
+        storage.put(key, text.as_bytes().to_vec(), Some("text/plain")).await.unwrap();
+
+        // An upload whose recorded extraction no longer matches its file
+        let file_id = Uuid::new_v4();
+        sqlx::query(
+            "INSERT INTO file_uploads
+                (id, client_id, project_id, file_name, original_name, file_path, file_size, mime_type,
+                 auto_description, file_content, metadata, extraction_status, extraction_progress)
+             VALUES ($1, $2, 'reextract-test', 'notes.txt', 'notes.txt', $3, $4, 'text/plain',
+                 'garbled', '\u{fffd}\u{fffd}', '{\"corrupt\": true}', 'failed', 37)",
+        )
+        .bind(file_id)
+        .bind(Uuid::new_v4())
+        .bind(storage.location(key))
+        .bind(text.len() as i64)
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        let load = || {
+            sqlx::query_as::<_, FileUpload>("SELECT * FROM file_uploads WHERE id = $1")
+                .bind(file_id)
+                .fetch_one(&pool)
+        };
+        let corrupted = load().await.unwrap();
+        assert_eq!(corrupted.extraction_status, "failed");
+
+        let extracted = reextract(&pool, &storage, &corrupted).await;
+        let restored = load().await.unwrap();
+        sqlx::query("DELETE FROM file_uploads WHERE id = $1")
+            .bind(file_id)
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        let extracted = extracted.unwrap();
+        assert_eq!(restored.extraction_status, "ready");
+        assert_eq!(restored.extraction_progress, 100);
+        assert!(restored.file_content.unwrap().contains(text));
+        assert_eq!(restored.auto_description, extracted.description);
+        assert_ne!(restored.metadata, Some(serde_json::json!({ "corrupt": true })));
+    }
+}
@@ -266,7 +266,7 @@ impl AnalysisManager {
             version_number: row.version_number,
             script_content: row.script_content,
             change_description: row.change_description,
-            created_at: row.created_at.map(|dt| chrono::DateTime::<chrono::Utc>::from_timestamp(dt.unix_timestamp(), 0).unwrap_or_else(|| chrono::Utc::now())).unwrap_or_else(|| chrono::Utc::now()),
+            created_at: row.created_at.map(|dt| chrono::DateTime::<chrono::Utc>::from_timestamp(dt.unix_timestamp(), 0).unwrap_or_else(chrono::Utc::now)).unwrap_or_else(chrono::Utc::now),
             created_by: row.created_by.unwrap_or_else(|| "system".to_string()),
             metadata: row.metadata.unwrap_or_else(|| serde_json::json!({})),
         }).collect();
@@ -294,7 +294,7 @@ impl AnalysisManager {
             version_number: row.version_number,
             script_content: row.script_content,
             change_description: row.change_description,
-            created_at: row.created_at.map(|dt| chrono::DateTime::<chrono::Utc>::from_timestamp(dt.unix_timestamp(), 0).unwrap_or_else(|| chrono::Utc::now())).unwrap_or_else(|| chrono::Utc::now()),
+            created_at: row.created_at.map(|dt| chrono::DateTime::<chrono::Utc>::from_timestamp(dt.unix_timestamp(), 0).unwrap_or_else(chrono::Utc::now)).unwrap_or_else(chrono::Utc::now),
             created_by: row.created_by.unwrap_or_else(|| "system".to_string()),
             metadata: row.metadata.unwrap_or_else(|| serde_json::json!({})),
         })
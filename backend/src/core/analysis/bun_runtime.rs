@@ -605,9 +605,9 @@ main();
             .stderr(Stdio::piped())
             .spawn()?;
 
-        let stdin = child.stdin.take().expect("Failed to open stdin");
-        let stdout = child.stdout.take().expect("Failed to open stdout");
-        let stderr = child.stderr.take().expect("Failed to open stderr");
+        let stdin = child.stdin.take().ok_or_else(|| anyhow!("Failed to open stdin"))?;
+        let stdout = child.stdout.take().ok_or_else(|| anyhow!("Failed to open stdout"))?;
+        let stderr = child.stderr.take().ok_or_else(|| anyhow!("Failed to open stderr"))?;
 
         let mut stdout_reader = BufReader::new(stdout).lines();
         let stderr_reader = BufReader::new(stderr);
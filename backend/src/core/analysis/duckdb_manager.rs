@@ -105,28 +105,36 @@ impl DuckDBManager {
     }
 
     async fn extract_duckdb(zip_path: &Path, target_path: &Path) -> Result<()> {
-        let file = std::fs::File::open(zip_path)?;
-        let mut archive = zip::ZipArchive::new(file)?;
-
         let executable_name = if cfg!(target_os = "windows") {
             "duckdb.exe"
         } else {
             "duckdb"
         };
 
+        // Read the executable before awaiting: zip entries aren't Send, and
+        // MCP tool calls run this on a spawned task
+        let contents = Self::read_executable(zip_path, executable_name)?
+            .ok_or_else(|| anyhow!("DuckDB executable not found in archive"))?;
+        fs::write(target_path, contents).await?;
+        Ok(())
+    }
+
+    fn read_executable(zip_path: &Path, executable_name: &str) -> Result<Option<Vec<u8>>> {
+        let file = std::fs::File::open(zip_path)?;
+        let mut archive = zip::ZipArchive::new(file)?;
+
         // Find and extract the executable
         for i in 0..archive.len() {
             let mut file = archive.by_index(i)?;
-            
+
             if file.name().ends_with(executable_name) || file.name() == executable_name {
                 let mut contents = Vec::new();
                 std::io::Read::read_to_end(&mut file, &mut contents)?;
-                fs::write(target_path, contents).await?;
-                return Ok(());
+                return Ok(Some(contents));
             }
         }
 
-        Err(anyhow!("DuckDB executable not found in archive"))
+        Ok(None)
     }
 
     pub async fn execute_query(&self, database_path: &Path, query: &str) -> Result<String> {
@@ -145,6 +153,30 @@ impl DuckDBManager {
         Ok(String::from_utf8_lossy(&output.stdout).to_string())
     }
 
+    /// Run a query and return its rows as JSON objects (DuckDB CLI `-json` mode)
+    pub async fn execute_query_json(&self, database_path: &Path, query: &str) -> Result<serde_json::Value> {
+        let output = AsyncCommand::new(&self.executable_path)
+            .arg(database_path.to_string_lossy().as_ref())
+            .arg("-json")
+            .arg("-c")
+            .arg(query)
+            .output()
+            .await?;
+
+        if !output.status.success() {
+            let error = String::from_utf8_lossy(&output.stderr);
+            return Err(anyhow!("DuckDB query failed: {}", error));
+        }
+
+        // The CLI prints nothing at all for an empty result set
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        if stdout.trim().is_empty() {
+            return Ok(serde_json::Value::Array(Vec::new()));
+        }
+
+        Ok(serde_json::from_str(stdout.trim())?)
+    }
+
     pub async fn execute_script(&self, database_path: &Path, script_path: &Path) -> Result<String> {
         let output = AsyncCommand::new(&self.executable_path)
             .arg(database_path.to_string_lossy().as_ref())
@@ -201,7 +233,7 @@ impl DuckDBManager {
             .skip(1) // Skip header
             .filter_map(|line| {
                 let parts: Vec<&str> = line.split('|').collect();
-                if parts.len() >= 1 {
+                if !parts.is_empty() {
                     Some(parts[0].trim().to_string())
                 } else {
                     None
@@ -221,9 +221,9 @@ impl JobManager {
             result: row.result,
             error_message: row.error_message,
             logs: row.logs.unwrap_or_default(),
-            started_at: row.started_at.map(|dt| chrono::DateTime::<chrono::Utc>::from_timestamp(dt.unix_timestamp(), 0).unwrap_or_else(|| chrono::Utc::now())),
-            completed_at: row.completed_at.map(|dt| chrono::DateTime::<chrono::Utc>::from_timestamp(dt.unix_timestamp(), 0).unwrap_or_else(|| chrono::Utc::now())),
-            created_at: row.created_at.map(|dt| chrono::DateTime::<chrono::Utc>::from_timestamp(dt.unix_timestamp(), 0).unwrap_or_else(|| chrono::Utc::now())).unwrap_or_else(|| chrono::Utc::now()),
+            started_at: row.started_at.map(|dt| chrono::DateTime::<chrono::Utc>::from_timestamp(dt.unix_timestamp(), 0).unwrap_or_else(chrono::Utc::now)),
+            completed_at: row.completed_at.map(|dt| chrono::DateTime::<chrono::Utc>::from_timestamp(dt.unix_timestamp(), 0).unwrap_or_else(chrono::Utc::now)),
+            created_at: row.created_at.map(|dt| chrono::DateTime::<chrono::Utc>::from_timestamp(dt.unix_timestamp(), 0).unwrap_or_else(chrono::Utc::now)).unwrap_or_else(chrono::Utc::now),
             execution_time_ms: row.execution_time_ms,
             triggered_by: row.triggered_by.unwrap_or_else(|| "system".to_string()),
         })
@@ -277,8 +277,8 @@ impl JobManager {
             title: row.title,
             script_content: row.script_content,
             project_id: uuid::Uuid::parse_str(&row.project_id).unwrap_or_default(),
-            created_at: row.created_at.map(|dt| chrono::DateTime::<chrono::Utc>::from_timestamp(dt.unix_timestamp(), 0).unwrap_or_else(|| chrono::Utc::now())).unwrap_or_else(|| chrono::Utc::now()),
-            updated_at: row.updated_at.and_then(|dt| chrono::DateTime::<chrono::Utc>::from_timestamp(dt.unix_timestamp(), 0)).unwrap_or_else(|| chrono::Utc::now()),
+            created_at: row.created_at.map(|dt| chrono::DateTime::<chrono::Utc>::from_timestamp(dt.unix_timestamp(), 0).unwrap_or_else(chrono::Utc::now)).unwrap_or_else(chrono::Utc::now),
+            updated_at: row.updated_at.and_then(|dt| chrono::DateTime::<chrono::Utc>::from_timestamp(dt.unix_timestamp(), 0)).unwrap_or_else(chrono::Utc::now),
             created_by: row.created_by.or(Some(uuid::Uuid::nil())),
             version: row.version.unwrap_or(1),
             is_active: row.is_active.unwrap_or(true),
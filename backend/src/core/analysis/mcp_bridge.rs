@@ -56,6 +56,8 @@ impl McpBridge {
                 client_id: job_id,
                 server_type: "analysis".to_string(),
                 db_pool,
+                conversation_id: None,
+                turn_id: None,
             },
         }
     }
@@ -9,6 +9,7 @@ pub mod job_manager;
 pub mod mcp_bridge;
 pub mod monitoring;
 pub mod result_storage;
+pub mod schema_pins;
 pub mod sandbox;
 pub mod scheduler;
 pub mod service;
@@ -314,7 +314,7 @@ impl MonitoringService {
         if job_metrics.len() > 1000 {
             // Keep only the most recent 1000 job metrics
             let mut metrics_vec: Vec<_> = job_metrics.drain().collect();
-            metrics_vec.sort_by(|a, b| b.1.start_time.cmp(&a.1.start_time));
+            metrics_vec.sort_by_key(|(_, metrics)| std::cmp::Reverse(metrics.start_time));
             metrics_vec.truncate(1000);
             
             *job_metrics = metrics_vec.into_iter().collect();
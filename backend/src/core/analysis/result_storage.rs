@@ -100,7 +100,7 @@ impl ResultStorage {
 
     pub async fn cleanup_old_results(&self, older_than_days: i64) -> Result<usize> {
         let cutoff_date = Utc::now() - chrono::Duration::days(older_than_days);
-        let cutoff_offset = sqlx::types::time::OffsetDateTime::from_unix_timestamp(cutoff_date.timestamp()).unwrap();
+        let cutoff_offset = sqlx::types::time::OffsetDateTime::from_unix_timestamp(cutoff_date.timestamp())?;
 
         // Get old result files
         let old_results = sqlx::query!(
@@ -244,7 +244,7 @@ impl ResultStorage {
             storage_path: row.storage_path,
             size_bytes: row.size_bytes,
             checksum: row.checksum,
-            created_at: row.created_at.and_then(|dt| chrono::DateTime::<chrono::Utc>::from_timestamp(dt.unix_timestamp(), 0)).unwrap_or_else(|| chrono::Utc::now()),
+            created_at: row.created_at.and_then(|dt| chrono::DateTime::<chrono::Utc>::from_timestamp(dt.unix_timestamp(), 0)).unwrap_or_else(chrono::Utc::now),
         })
     }
 }
@@ -4,45 +4,67 @@ use cron::Schedule;
 use serde_json::Value;
 use sqlx::PgPool;
 use sqlx::types::time::OffsetDateTime;
+use std::future::Future;
 use std::str::FromStr;
 use std::sync::Arc;
-use tokio::time::{interval, Duration};
+use tokio::time::{interval, Duration, MissedTickBehavior};
 use super::job_manager::JobManager;
 
-pub struct AnalysisScheduler {
-    db: PgPool,
-    job_manager: Arc<JobManager>,
+/// Runs a task every `period` on a background task until stopped. Drives the
+/// analysis schedule check and scheduled database backups.
+pub struct IntervalRunner {
+    name: &'static str,
+    period: Duration,
+    delay_first_run: bool,
     is_running: Arc<tokio::sync::RwLock<bool>>,
 }
 
-impl AnalysisScheduler {
-    pub async fn new(db: PgPool, job_manager: Arc<JobManager>) -> Self {
+impl IntervalRunner {
+    pub fn new(name: &'static str, period: Duration) -> Self {
         Self {
-            db,
-            job_manager,
+            name,
+            period,
+            delay_first_run: false,
             is_running: Arc::new(tokio::sync::RwLock::new(false)),
         }
     }
 
-    pub async fn start(&self) -> Result<()> {
+    /// Wait a full period before the first run instead of running right away
+    pub fn delay_first_run(mut self) -> Self {
+        self.delay_first_run = true;
+        self
+    }
+
+    /// Start running `task`; does nothing if this runner is already started
+    pub async fn start<F, Fut>(&self, task: F)
+    where
+        F: Fn() -> Fut + Send + 'static,
+        Fut: Future<Output = ()> + Send,
+    {
         {
             let mut is_running = self.is_running.write().await;
             if *is_running {
-                return Ok(());
+                return;
             }
             *is_running = true;
         }
 
-        let db = self.db.clone();
-        let job_manager = Arc::clone(&self.job_manager);
+        let name = self.name;
+        let period = self.period;
+        let delay_first_run = self.delay_first_run;
         let is_running = Arc::clone(&self.is_running);
 
         tokio::spawn(async move {
-            let mut interval_timer = interval(Duration::from_secs(60)); // Check every minute
+            let mut interval_timer = interval(period);
+            interval_timer.set_missed_tick_behavior(MissedTickBehavior::Delay);
+            if delay_first_run {
+                // The first tick fires immediately
+                interval_timer.tick().await;
+            }
 
             loop {
                 interval_timer.tick().await;
-                
+
                 {
                     let running = is_running.read().await;
                     if !*running {
@@ -50,21 +72,57 @@ impl AnalysisScheduler {
                     }
                 }
 
-                if let Err(e) = Self::check_and_run_scheduled_analyses(&db, &job_manager).await {
-                    tracing::error!("Error checking scheduled analyses: {}", e);
-                }
+                task().await;
             }
 
-            tracing::info!("Analysis scheduler stopped");
+            tracing::info!("{} stopped", name);
         });
+    }
+
+    pub async fn stop(&self) {
+        let mut is_running = self.is_running.write().await;
+        *is_running = false;
+    }
+}
+
+pub struct AnalysisScheduler {
+    db: PgPool,
+    job_manager: Arc<JobManager>,
+    runner: IntervalRunner,
+}
+
+impl AnalysisScheduler {
+    pub async fn new(db: PgPool, job_manager: Arc<JobManager>) -> Self {
+        Self {
+            db,
+            job_manager,
+            // Check every minute
+            runner: IntervalRunner::new("Analysis scheduler", Duration::from_secs(60)),
+        }
+    }
+
+    pub async fn start(&self) -> Result<()> {
+        let db = self.db.clone();
+        let job_manager = Arc::clone(&self.job_manager);
+
+        self.runner
+            .start(move || {
+                let db = db.clone();
+                let job_manager = Arc::clone(&job_manager);
+                async move {
+                    if let Err(e) = Self::check_and_run_scheduled_analyses(&db, &job_manager).await {
+                        tracing::error!("Error checking scheduled analyses: {}", e);
+                    }
+                }
+            })
+            .await;
 
         tracing::info!("Analysis scheduler started");
         Ok(())
     }
 
     pub async fn stop(&self) -> Result<()> {
-        let mut is_running = self.is_running.write().await;
-        *is_running = false;
+        self.runner.stop().await;
         Ok(())
     }
 
@@ -75,7 +133,7 @@ impl AnalysisScheduler {
         let now = Utc::now();
 
         // Get all enabled schedules that should run
-        let now_offset = OffsetDateTime::from_unix_timestamp(now.timestamp()).unwrap();
+        let now_offset = OffsetDateTime::from_unix_timestamp(now.timestamp())?;
         let schedules = sqlx::query!(
             r#"
             SELECT s.id, s.analysis_id, s.cron_expression, s.timezone, s.last_run_at,
@@ -93,7 +151,7 @@ impl AnalysisScheduler {
 
         for schedule in schedules {
             let timezone_str = schedule.timezone.as_deref().unwrap_or("UTC");
-            let last_run = schedule.last_run_at.map(|dt| chrono::DateTime::<chrono::Utc>::from_timestamp(dt.unix_timestamp(), 0).unwrap_or_else(|| chrono::Utc::now()));
+            let last_run = schedule.last_run_at.map(|dt| chrono::DateTime::<chrono::Utc>::from_timestamp(dt.unix_timestamp(), 0).unwrap_or_else(chrono::Utc::now));
             match Self::should_run_now(&schedule.cron_expression, timezone_str, last_run) {
                 Ok(should_run) => {
                     if should_run {
@@ -113,8 +171,8 @@ impl AnalysisScheduler {
                                 
                                 // Update last run time and calculate next run
                                 if let Ok(next_run) = Self::calculate_next_run(&schedule.cron_expression, timezone_str) {
-                                    let now_offset = OffsetDateTime::from_unix_timestamp(now.timestamp()).unwrap();
-                                    let next_offset = OffsetDateTime::from_unix_timestamp(next_run.timestamp()).unwrap();
+                                    let now_offset = OffsetDateTime::from_unix_timestamp(now.timestamp())?;
+                                    let next_offset = OffsetDateTime::from_unix_timestamp(next_run.timestamp())?;
                                     let _ = sqlx::query!(
                                         r#"
                                         UPDATE analysis_schedules
@@ -166,7 +224,7 @@ impl AnalysisScheduler {
         
         if let Some(next_run) = schedule.after(&from_time).next() {
             // Should run if the next scheduled time has passed and we haven't run since then
-            Ok(next_run <= now && (last_run_at.map_or(true, |last| last < next_run)))
+            Ok(next_run <= now && (last_run_at.is_none_or(|last| last < next_run)))
         } else {
             Ok(false)
         }
@@ -212,7 +270,7 @@ impl AnalysisScheduler {
         for schedule in schedules {
             let timezone_str = schedule.timezone.as_deref().unwrap_or("UTC");
             if let Ok(next_run) = Self::calculate_next_run(&schedule.cron_expression, timezone_str) {
-                let next_offset = OffsetDateTime::from_unix_timestamp(next_run.timestamp()).unwrap();
+                let next_offset = OffsetDateTime::from_unix_timestamp(next_run.timestamp())?;
                 let _ = sqlx::query!(
                     "UPDATE analysis_schedules SET next_run_at = $1 WHERE id = $2",
                     next_offset,
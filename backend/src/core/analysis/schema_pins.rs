@@ -0,0 +1,491 @@
+//! Schema versions pinned to analyses
+//!
+//! A snapshot records a datasource's tables and column types as a numbered
+//! version in `datasource_schema_versions`; identical snapshots reuse the
+//! existing version. Pinning a version to an analysis makes each run hand the
+//! pinned schema to the script and compare it with the live schema, so drift
+//! shows up as a warning in the job logs instead of a confusing script error.
+
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+use sqlx::{PgPool, Row};
+use std::collections::{BTreeMap, HashMap};
+use uuid::Uuid;
+
+use crate::core::datasources::shared_service::{get_datasource_with_validation, SharedDatasourceInfo};
+use crate::utils::datasource::create_connector;
+
+/// Table name -> column name -> column type
+pub type NormalizedSchema = BTreeMap<String, BTreeMap<String, String>>;
+
+/// Flatten the connector-specific `fetch_schema` shapes into one comparable form
+///
+/// Handles `{"tables": {name: [columns]}}` (Postgres, MySQL, SQLite, SQL
+/// Server), `{name: {"columns": [...]}}` (ClickHouse) and `[{name, columns}]`
+/// (Oracle).
+pub fn normalize_schema(raw: &Value) -> NormalizedSchema {
+    let mut schema = NormalizedSchema::new();
+
+    match raw {
+        Value::Array(tables) => {
+            for table in tables {
+                if let Some(name) = table.get("name").and_then(|n| n.as_str()) {
+                    schema.insert(name.to_string(), normalize_columns(table));
+                }
+            }
+        }
+        Value::Object(map) => {
+            let tables = match map.get("tables") {
+                Some(Value::Object(tables)) => tables,
+                Some(Value::Array(_)) => return normalize_schema(&map["tables"]),
+                _ => map,
+            };
+            for (name, table) in tables {
+                schema.insert(name.clone(), normalize_columns(table));
+            }
+        }
+        _ => {}
+    }
+
+    schema
+}
+
+fn normalize_columns(table: &Value) -> BTreeMap<String, String> {
+    let columns = match table {
+        Value::Array(columns) => columns,
+        Value::Object(obj) => match obj.get("columns") {
+            Some(Value::Array(columns)) => columns,
+            _ => return BTreeMap::new(),
+        },
+        _ => return BTreeMap::new(),
+    };
+
+    columns
+        .iter()
+        .filter_map(|column| {
+            let name = ["column_name", "name"]
+                .iter()
+                .find_map(|key| column.get(*key).and_then(|v| v.as_str()))?;
+            let data_type = ["data_type", "type", "column_type"]
+                .iter()
+                .find_map(|key| column.get(*key).and_then(|v| v.as_str()))
+                .unwrap_or("unknown");
+            Some((name.to_string(), data_type.to_lowercase()))
+        })
+        .collect()
+}
+
+/// Stable hash of a normalized schema; BTreeMap keeps the serialization ordered
+pub fn schema_hash(schema: &NormalizedSchema) -> String {
+    let bytes = serde_json::to_vec(schema).unwrap_or_default();
+    hex::encode(Sha256::digest(&bytes))
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ColumnTypeChange {
+    pub column: String,
+    pub pinned_type: String,
+    pub live_type: String,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct TableDrift {
+    pub table: String,
+    pub added_columns: Vec<String>,
+    pub removed_columns: Vec<String>,
+    pub changed_columns: Vec<ColumnTypeChange>,
+}
+
+/// Differences between a pinned schema and the live one
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct SchemaDrift {
+    pub added_tables: Vec<String>,
+    pub removed_tables: Vec<String>,
+    pub changed_tables: Vec<TableDrift>,
+}
+
+impl SchemaDrift {
+    pub fn between(pinned: &NormalizedSchema, live: &NormalizedSchema) -> Self {
+        let mut drift = SchemaDrift::default();
+
+        for (table, pinned_columns) in pinned {
+            let Some(live_columns) = live.get(table) else {
+                drift.removed_tables.push(table.clone());
+                continue;
+            };
+
+            let mut table_drift = TableDrift {
+                table: table.clone(),
+                ..Default::default()
+            };
+            for (column, pinned_type) in pinned_columns {
+                match live_columns.get(column) {
+                    None => table_drift.removed_columns.push(column.clone()),
+                    Some(live_type) if live_type != pinned_type => {
+                        table_drift.changed_columns.push(ColumnTypeChange {
+                            column: column.clone(),
+                            pinned_type: pinned_type.clone(),
+                            live_type: live_type.clone(),
+                        })
+                    }
+                    Some(_) => {}
+                }
+            }
+            table_drift.added_columns = live_columns
+                .keys()
+                .filter(|column| !pinned_columns.contains_key(*column))
+                .cloned()
+                .collect();
+
+            if !table_drift.added_columns.is_empty()
+                || !table_drift.removed_columns.is_empty()
+                || !table_drift.changed_columns.is_empty()
+            {
+                drift.changed_tables.push(table_drift);
+            }
+        }
+
+        drift.added_tables = live
+            .keys()
+            .filter(|table| !pinned.contains_key(*table))
+            .cloned()
+            .collect();
+
+        drift
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.added_tables.is_empty() && self.removed_tables.is_empty() && self.changed_tables.is_empty()
+    }
+
+    /// One-line description for job logs, e.g. `removed tables: orders; users: -email, id int4 -> int8`
+    pub fn summary(&self) -> String {
+        let mut parts = Vec::new();
+        if !self.removed_tables.is_empty() {
+            parts.push(format!("removed tables: {}", self.removed_tables.join(", ")));
+        }
+        if !self.added_tables.is_empty() {
+            parts.push(format!("added tables: {}", self.added_tables.join(", ")));
+        }
+        for table in &self.changed_tables {
+            let mut changes: Vec<String> = Vec::new();
+            changes.extend(table.removed_columns.iter().map(|c| format!("-{}", c)));
+            changes.extend(table.added_columns.iter().map(|c| format!("+{}", c)));
+            changes.extend(
+                table
+                    .changed_columns
+                    .iter()
+                    .map(|c| format!("{} {} -> {}", c.column, c.pinned_type, c.live_type)),
+            );
+            parts.push(format!("{}: {}", table.table, changes.join(", ")));
+        }
+        parts.join("; ")
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SchemaVersion {
+    pub id: Uuid,
+    pub datasource_id: String,
+    pub version: i32,
+    pub schema_hash: String,
+    pub table_count: i64,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SchemaPin {
+    pub analysis_id: Uuid,
+    pub datasource_id: String,
+    pub datasource_name: String,
+    pub schema_version_id: Uuid,
+    pub version: i32,
+    pub schema_hash: String,
+    pub pinned_at: DateTime<Utc>,
+}
+
+/// Fetch and normalize the datasource's current schema from the database itself
+pub async fn fetch_live_schema(datasource: &SharedDatasourceInfo) -> Result<NormalizedSchema> {
+    let mut config = datasource.connection_config.clone();
+    if let Some(obj) = config.as_object_mut() {
+        obj.insert("id".to_string(), Value::String(datasource.id.clone()));
+    }
+
+    let connector = create_connector(&datasource.source_type, &config)
+        .await
+        .map_err(|e| anyhow!("Failed to create connector: {}", e))?;
+    let raw = connector
+        .fetch_schema()
+        .await
+        .map_err(|e| anyhow!("Failed to fetch schema: {}", e))?;
+
+    Ok(normalize_schema(&raw))
+}
+
+/// Record the live schema as a version, reusing the newest version with the same hash
+pub async fn snapshot_schema(db: &PgPool, datasource: &SharedDatasourceInfo) -> Result<SchemaVersion> {
+    let schema = fetch_live_schema(datasource).await?;
+    let hash = schema_hash(&schema);
+
+    let existing = sqlx::query(&format!(
+        "{} WHERE datasource_id = $1 AND schema_hash = $2 ORDER BY version DESC LIMIT 1",
+        SELECT_VERSION
+    ))
+    .bind(&datasource.id)
+    .bind(&hash)
+    .fetch_optional(db)
+    .await?;
+    if let Some(row) = existing {
+        return Ok(version_from_row(&row));
+    }
+
+    let row = sqlx::query(
+        r#"
+        INSERT INTO datasource_schema_versions (id, datasource_id, version, schema_hash, schema, created_at)
+        SELECT $1, $2, COALESCE(MAX(version), 0) + 1, $3, $4, NOW()
+        FROM datasource_schema_versions
+        WHERE datasource_id = $2
+        RETURNING id, datasource_id, version, schema_hash,
+                  (SELECT COUNT(*) FROM jsonb_object_keys(schema)) AS table_count, created_at
+        "#,
+    )
+    .bind(Uuid::new_v4())
+    .bind(&datasource.id)
+    .bind(&hash)
+    .bind(serde_json::to_value(&schema)?)
+    .fetch_one(db)
+    .await?;
+
+    Ok(version_from_row(&row))
+}
+
+const SELECT_VERSION: &str = r#"
+    SELECT id, datasource_id, version, schema_hash,
+           (SELECT COUNT(*) FROM jsonb_object_keys(schema)) AS table_count, created_at
+    FROM datasource_schema_versions
+"#;
+
+fn version_from_row(row: &sqlx::postgres::PgRow) -> SchemaVersion {
+    SchemaVersion {
+        id: row.get("id"),
+        datasource_id: row.get("datasource_id"),
+        version: row.get("version"),
+        schema_hash: row.get("schema_hash"),
+        table_count: row.get::<Option<i64>, _>("table_count").unwrap_or(0),
+        created_at: row.get("created_at"),
+    }
+}
+
+/// Versions recorded for a datasource, newest first
+pub async fn list_schema_versions(db: &PgPool, datasource_id: &str) -> Result<Vec<SchemaVersion>> {
+    let rows = sqlx::query(&format!("{} WHERE datasource_id = $1 ORDER BY version DESC", SELECT_VERSION))
+        .bind(datasource_id)
+        .fetch_all(db)
+        .await?;
+    Ok(rows.iter().map(version_from_row).collect())
+}
+
+pub async fn get_schema_version(db: &PgPool, datasource_id: &str, version: i32) -> Result<Option<SchemaVersion>> {
+    let row = sqlx::query(&format!("{} WHERE datasource_id = $1 AND version = $2", SELECT_VERSION))
+        .bind(datasource_id)
+        .bind(version)
+        .fetch_optional(db)
+        .await?;
+    Ok(row.as_ref().map(version_from_row))
+}
+
+/// Pin a version to an analysis, replacing any earlier pin for the datasource
+pub async fn pin_schema_version(db: &PgPool, analysis_id: Uuid, version: &SchemaVersion) -> Result<()> {
+    sqlx::query(
+        r#"
+        INSERT INTO analysis_schema_pins (analysis_id, datasource_id, schema_version_id, pinned_at)
+        VALUES ($1, $2, $3, NOW())
+        ON CONFLICT (analysis_id, datasource_id)
+        DO UPDATE SET schema_version_id = EXCLUDED.schema_version_id, pinned_at = NOW()
+        "#,
+    )
+    .bind(analysis_id)
+    .bind(&version.datasource_id)
+    .bind(version.id)
+    .execute(db)
+    .await?;
+    Ok(())
+}
+
+/// Returns false when the analysis had no pin for the datasource
+pub async fn unpin_schema(db: &PgPool, analysis_id: Uuid, datasource_id: &str) -> Result<bool> {
+    let result = sqlx::query("DELETE FROM analysis_schema_pins WHERE analysis_id = $1 AND datasource_id = $2")
+        .bind(analysis_id)
+        .bind(datasource_id)
+        .execute(db)
+        .await?;
+    Ok(result.rows_affected() > 0)
+}
+
+pub async fn list_schema_pins(db: &PgPool, analysis_id: Uuid) -> Result<Vec<SchemaPin>> {
+    let rows = sqlx::query(
+        r#"
+        SELECT p.analysis_id, p.datasource_id, ds.name AS datasource_name, p.schema_version_id,
+               v.version, v.schema_hash, p.pinned_at
+        FROM analysis_schema_pins p
+        JOIN datasource_schema_versions v ON v.id = p.schema_version_id
+        JOIN data_sources ds ON ds.id = p.datasource_id
+        WHERE p.analysis_id = $1
+        ORDER BY ds.name
+        "#,
+    )
+    .bind(analysis_id)
+    .fetch_all(db)
+    .await?;
+
+    Ok(rows
+        .iter()
+        .map(|row| SchemaPin {
+            analysis_id: row.get("analysis_id"),
+            datasource_id: row.get("datasource_id"),
+            datasource_name: row.get("datasource_name"),
+            schema_version_id: row.get("schema_version_id"),
+            version: row.get("version"),
+            schema_hash: row.get("schema_hash"),
+            pinned_at: row.get("pinned_at"),
+        })
+        .collect())
+}
+
+pub async fn load_pinned_schema(db: &PgPool, schema_version_id: Uuid) -> Result<NormalizedSchema> {
+    let schema: Value = sqlx::query_scalar("SELECT schema FROM datasource_schema_versions WHERE id = $1")
+        .bind(schema_version_id)
+        .fetch_one(db)
+        .await?;
+    Ok(serde_json::from_value(schema)?)
+}
+
+/// Compare a pin with the live schema; `Err` means the live schema could not be read
+pub async fn check_pin_drift(db: &PgPool, pin: &SchemaPin, project_id: &str) -> Result<SchemaDrift> {
+    let pinned = load_pinned_schema(db, pin.schema_version_id).await?;
+    let datasource = get_datasource_with_validation(&pin.datasource_id, project_id, db)
+        .await
+        .map_err(|e| anyhow!("{}", e))?;
+    let live = fetch_live_schema(&datasource).await?;
+    Ok(SchemaDrift::between(&pinned, &live))
+}
+
+/// Attach pinned schemas to an analysis run's datasource context
+///
+/// Each pinned datasource gets a `pinned_schema` entry with the version and
+/// tables the script should rely on. Returns a warning per datasource whose
+/// live schema drifted from the pin or could not be checked.
+pub async fn apply_schema_pins(
+    db: &PgPool,
+    analysis_id: Uuid,
+    project_id: &str,
+    datasources: &mut HashMap<String, Value>,
+) -> Result<Vec<String>> {
+    let mut warnings = Vec::new();
+
+    for pin in list_schema_pins(db, analysis_id).await? {
+        let pinned = load_pinned_schema(db, pin.schema_version_id).await?;
+
+        if let Some(Value::Object(entry)) = datasources.get_mut(&pin.datasource_name) {
+            entry.insert(
+                "pinned_schema".to_string(),
+                json!({
+                    "version": pin.version,
+                    "schema_hash": pin.schema_hash,
+                    "tables": pinned,
+                }),
+            );
+        }
+
+        match check_pin_drift(db, &pin, project_id).await {
+            Ok(drift) if drift.is_empty() => {}
+            Ok(drift) => warnings.push(format!(
+                "Schema of datasource '{}' has drifted from pinned version {}: {}",
+                pin.datasource_name,
+                pin.version,
+                drift.summary()
+            )),
+            Err(e) => warnings.push(format!(
+                "Could not verify datasource '{}' against pinned schema version {}: {}",
+                pin.datasource_name, pin.version, e
+            )),
+        }
+    }
+
+    Ok(warnings)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn postgres_schema(columns: Value) -> Value {
+        json!({ "tables": { "orders": columns } })
+    }
+
+    #[test]
+    fn normalizes_connector_shapes_to_the_same_form() {
+        let postgres = postgres_schema(json!([
+            { "column_name": "id", "data_type": "INTEGER", "is_nullable": "NO" },
+            { "column_name": "total", "data_type": "numeric", "is_nullable": "YES" }
+        ]));
+        let clickhouse = json!({
+            "orders": { "columns": [
+                { "name": "id", "type": "integer" },
+                { "name": "total", "type": "numeric" }
+            ]}
+        });
+        let oracle = json!([
+            { "name": "orders", "columns": [
+                { "name": "id", "data_type": "integer" },
+                { "name": "total", "data_type": "NUMERIC" }
+            ]}
+        ]);
+
+        let expected = normalize_schema(&postgres);
+        assert_eq!(expected["orders"]["id"], "integer");
+        assert_eq!(normalize_schema(&clickhouse), expected);
+        assert_eq!(normalize_schema(&oracle), expected);
+        assert_eq!(schema_hash(&normalize_schema(&oracle)), schema_hash(&expected));
+    }
+
+    #[test]
+    fn detects_table_and_column_drift() {
+        let pinned = normalize_schema(&json!({ "tables": {
+            "orders": [
+                { "column_name": "id", "data_type": "int4" },
+                { "column_name": "note", "data_type": "text" }
+            ],
+            "legacy": [{ "column_name": "id", "data_type": "int4" }]
+        }}));
+        let live = normalize_schema(&json!({ "tables": {
+            "orders": [
+                { "column_name": "id", "data_type": "int8" },
+                { "column_name": "status", "data_type": "text" }
+            ],
+            "customers": [{ "column_name": "id", "data_type": "int4" }]
+        }}));
+
+        let drift = SchemaDrift::between(&pinned, &live);
+        assert_eq!(drift.removed_tables, vec!["legacy"]);
+        assert_eq!(drift.added_tables, vec!["customers"]);
+        assert_eq!(drift.changed_tables.len(), 1);
+        assert_eq!(
+            drift.summary(),
+            "removed tables: legacy; added tables: customers; orders: -note, +status, id int4 -> int8"
+        );
+    }
+
+    #[test]
+    fn identical_schemas_have_no_drift() {
+        let schema = normalize_schema(&postgres_schema(json!([
+            { "column_name": "id", "data_type": "int4" }
+        ])));
+        let drift = SchemaDrift::between(&schema, &schema.clone());
+        assert!(drift.is_empty());
+        assert_eq!(drift.summary(), "");
+    }
+}
@@ -70,22 +70,40 @@ impl AnalysisService {
         .fetch_optional(&self.db_pool)
         .await?;
 
-        if analysis.is_none() {
+        let Some(analysis_row) = analysis else {
             self.update_job_status(job_id, "failed", None, Some("Analysis not found".to_string())).await?;
             return Ok(());
-        }
-
-        let analysis_row = analysis.unwrap();
+        };
         let script_content = analysis_row.script_content;
         let project_id = Uuid::parse_str(&analysis_row.project_id)?;
 
         // Get datasources for the project
-        let datasources = self.get_project_datasources(project_id).await?;
+        let mut datasources = self.get_project_datasources(project_id).await?;
+
+        // Hand pinned schemas to the script and flag any drift in the job logs
+        let schema_warnings = match super::schema_pins::apply_schema_pins(
+            &self.db_pool,
+            analysis_id,
+            &project_id.to_string(),
+            &mut datasources,
+        )
+        .await
+        {
+            Ok(warnings) => warnings,
+            Err(e) => vec![format!("Could not load pinned schemas: {}", e)],
+        };
+        if !schema_warnings.is_empty() {
+            for warning in &schema_warnings {
+                tracing::warn!("Job {}: {}", job_id, warning);
+            }
+            self.append_job_logs(job_id, &schema_warnings).await?;
+        }
 
         // Build context
         let context = serde_json::json!({
             "datasources": datasources,
             "metadata": {},
+            "schema_warnings": schema_warnings,
         });
 
         // Get backend URL from environment
@@ -135,6 +153,15 @@ impl AnalysisService {
         Ok(())
     }
 
+    async fn append_job_logs(&self, job_id: Uuid, lines: &[String]) -> Result<()> {
+        sqlx::query("UPDATE analysis_jobs SET logs = COALESCE(logs, ARRAY[]::text[]) || $1::text[] WHERE id = $2")
+            .bind(lines)
+            .bind(job_id)
+            .execute(&self.db_pool)
+            .await?;
+        Ok(())
+    }
+
     async fn get_project_datasources(&self, project_id: Uuid) -> Result<HashMap<String, Value>> {
         let rows = sqlx::query!(
             r#"
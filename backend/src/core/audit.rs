@@ -0,0 +1,177 @@
+//! Audit trail of admin actions
+//!
+//! Handlers that change clients, user roles or project ownership call
+//! `record_audit` after the change succeeds. A failed write is logged rather
+//! than failing the action that was already carried out.
+
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use salvo::Depot;
+use serde::Serialize;
+use serde_json::Value;
+use sqlx::{PgPool, Row};
+use uuid::Uuid;
+
+pub const CLIENT_CREATE: &str = "client.create";
+pub const CLIENT_DELETE: &str = "client.delete";
+pub const USER_ROLE_CHANGE: &str = "user.role_change";
+pub const PROJECT_OWNERSHIP_TRANSFER: &str = "project.ownership_transfer";
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct AuditEntry {
+    pub actor_user_id: Option<Uuid>,
+    /// Client of the actor; admins only see their own client's entries
+    pub client_id: Option<Uuid>,
+    pub action: &'static str,
+    pub target_id: Option<String>,
+    pub details: Value,
+}
+
+impl AuditEntry {
+    /// An entry for `action` by the current user
+    pub fn by_current_user(depot: &Depot, action: &'static str, target_id: impl ToString, details: Value) -> Self {
+        let depot_uuid = |key: &str| depot.get::<String>(key).ok().and_then(|id| Uuid::parse_str(id).ok());
+        Self {
+            actor_user_id: depot_uuid("current_user_id"),
+            client_id: depot_uuid("current_user_client_id"),
+            action,
+            target_id: Some(target_id.to_string()),
+            details,
+        }
+    }
+}
+
+/// The audit entry for an update of a user, if it changed the user's role
+pub fn role_change_entry(
+    depot: &Depot,
+    user_id: Uuid,
+    previous_role: &str,
+    new_role: Option<&str>,
+) -> Option<AuditEntry> {
+    let new_role = new_role.filter(|role| *role != previous_role)?;
+    Some(AuditEntry::by_current_user(
+        depot,
+        USER_ROLE_CHANGE,
+        user_id,
+        serde_json::json!({ "from": previous_role, "to": new_role }),
+    ))
+}
+
+/// Write `entry` to the audit log
+pub async fn record_audit(db: &PgPool, entry: AuditEntry) {
+    let result = sqlx::query(
+        r#"
+        INSERT INTO audit_log (id, actor_user_id, client_id, action, target_id, details, created_at)
+        VALUES ($1, $2, $3, $4, $5, $6, NOW())
+        "#,
+    )
+    .bind(Uuid::new_v4())
+    .bind(entry.actor_user_id)
+    .bind(entry.client_id)
+    .bind(entry.action)
+    .bind(&entry.target_id)
+    .bind(&entry.details)
+    .execute(db)
+    .await;
+
+    if let Err(e) = result {
+        tracing::error!("Failed to record audit entry {} for {:?}: {}", entry.action, entry.target_id, e);
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct AuditLogRecord {
+    pub id: Uuid,
+    pub actor_user_id: Option<Uuid>,
+    pub client_id: Option<Uuid>,
+    pub action: String,
+    pub target_id: Option<String>,
+    pub details: Option<Value>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct AuditLogFilter {
+    pub actor_user_id: Option<Uuid>,
+    pub action: Option<String>,
+    /// `None` for every client (root)
+    pub client_id: Option<Uuid>,
+    pub limit: i64,
+    pub offset: i64,
+}
+
+/// Entries matching `filter`, newest first, and the total number of matches
+pub async fn list_audit_log(db: &PgPool, filter: &AuditLogFilter) -> Result<(Vec<AuditLogRecord>, i64)> {
+    const CONDITIONS: &str = "($1::uuid IS NULL OR actor_user_id = $1) \
+         AND ($2::text IS NULL OR action = $2) \
+         AND ($3::uuid IS NULL OR client_id = $3)";
+
+    let total: i64 = sqlx::query_scalar(&format!("SELECT COUNT(*) FROM audit_log WHERE {}", CONDITIONS))
+        .bind(filter.actor_user_id)
+        .bind(&filter.action)
+        .bind(filter.client_id)
+        .fetch_one(db)
+        .await?;
+
+    let rows = sqlx::query(&format!(
+        "SELECT id, actor_user_id, client_id, action, target_id, details, created_at FROM audit_log \
+         WHERE {} ORDER BY created_at DESC LIMIT $4 OFFSET $5",
+        CONDITIONS
+    ))
+    .bind(filter.actor_user_id)
+    .bind(&filter.action)
+    .bind(filter.client_id)
+    .bind(filter.limit)
+    .bind(filter.offset)
+    .fetch_all(db)
+    .await?;
+
+    let records = rows
+        .iter()
+        .map(|row| AuditLogRecord {
+            id: row.get("id"),
+            actor_user_id: row.get("actor_user_id"),
+            client_id: row.get("client_id"),
+            action: row.get("action"),
+            target_id: row.get("target_id"),
+            details: row.get("details"),
+            created_at: row.get("created_at"),
+        })
+        .collect();
+    Ok((records, total))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn admin_depot(user_id: Uuid, client_id: Uuid) -> Depot {
+        let mut depot = Depot::new();
+        depot.insert("current_user_id", user_id.to_string());
+        depot.insert("current_user_client_id", client_id.to_string());
+        depot
+    }
+
+    #[test]
+    fn a_role_change_produces_an_audit_entry() {
+        let (admin, client, user) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+        let depot = admin_depot(admin, client);
+
+        let entry = role_change_entry(&depot, user, "user", Some("admin")).unwrap();
+        assert_eq!(
+            entry,
+            AuditEntry {
+                actor_user_id: Some(admin),
+                client_id: Some(client),
+                action: USER_ROLE_CHANGE,
+                target_id: Some(user.to_string()),
+                details: json!({ "from": "user", "to": "admin" }),
+            }
+        );
+
+        // Renaming a user or re-saving the same role isn't a role change
+        assert!(role_change_entry(&depot, user, "user", None).is_none());
+        assert!(role_change_entry(&depot, user, "admin", Some("admin")).is_none());
+    }
+}
@@ -9,6 +9,7 @@ use super::types::{AskUserOption, ClaudeMessage, QueryRequest};
 use crate::utils::log_organizer::auto_organize_logs;
 use crate::utils::command_logger::{CommandLogger, CommandExecution};
 use crate::core::mcp::handlers::tools::get_all_available_mcp_tools;
+use crate::core::mcp_process::{mcp_server_base_url, mcp_server_port};
 
 #[derive(Debug, Clone)]
 pub struct ClaudeSDK {
@@ -63,6 +64,7 @@ impl ClaudeSDK {
             }
 
             // Check if existing config is valid, otherwise overwrite it
+            let mcp_port = mcp_server_port();
             let force_update = if mcp_servers_file.exists() {
                 match std::fs::read_to_string(&mcp_servers_file) {
                     Ok(content) => {
@@ -75,21 +77,21 @@ impl ClaudeSDK {
                                         .get("operation")
                                         .and_then(|op| op.get("url"))
                                         .and_then(|url| url.as_str())
-                                        .map(|url| url.contains(":7670/operation/"))
+                                        .map(|url| url.contains(&format!(":{}/operation/", mcp_port)))
                                         .unwrap_or(false);
                                     
                                     let analysis_valid = mcp_servers
                                         .get("analysis")
                                         .and_then(|an| an.get("url"))
                                         .and_then(|url| url.as_str())
-                                        .map(|url| url.contains(":7670/analysis/"))
+                                        .map(|url| url.contains(&format!(":{}/analysis/", mcp_port)))
                                         .unwrap_or(false);
                                     
                                     let interaction_valid = mcp_servers
                                         .get("interaction")
                                         .and_then(|ia| ia.get("url"))
                                         .and_then(|url| url.as_str())
-                                        .map(|url| url.contains(":7670/interaction/"))
+                                        .map(|url| url.contains(&format!(":{}/interaction/", mcp_port)))
                                         .unwrap_or(false);
                                     
                                     operation_valid && analysis_valid && interaction_valid
@@ -123,7 +125,7 @@ impl ClaudeSDK {
 
             // No longer need MCP server path since we use centralized server
 
-            // Check if centralized MCP server is ready on port 7670
+            // Check if centralized MCP server is ready
             if !force_update {
                 if Self::check_centralized_mcp_server_ready().await {
                     tracing::debug!("✅ Centralized MCP server already ready for project {}", project_id);
@@ -133,33 +135,48 @@ impl ClaudeSDK {
                 }
             }
 
-            // Use centralized MCP server on port 7670 with URL path-based routing
-            let mcp_servers = json!({
-                "mcpServers": {
-                    "operation": {
-                        "type": "http",
-                        "url": format!("http://localhost:7670/operation/{}/{}", self.client_id, project_id)
-                    },
-                    "analysis": {
-                        "type": "http",
-                        "url": format!("http://localhost:7670/analysis/{}/{}", self.client_id, project_id)
-                    },
-                    "interaction": {
-                        "type": "http",
-                        "url": format!("http://localhost:7670/interaction/{}/{}", self.client_id, project_id)
-                    }
-                }
-            });
+            // Use centralized MCP server with URL path-based routing
+            let mcp_servers = Self::mcp_servers_config(self.client_id, project_id, None);
 
             // Write the configuration - no need to start servers as they're managed by the backend
             let _ = std::fs::write(
                 &mcp_servers_file,
                 serde_json::to_string_pretty(&mcp_servers).unwrap_or_default(),
             );
-            tracing::info!("✅ MCP configuration written for project {} using centralized server on port 7670", project_id);
+            tracing::info!("✅ MCP configuration written for project {} using centralized server on port {}", project_id, mcp_server_port());
         }
     }
 
+    /// MCP server configuration for a project. When a conversation is given its id
+    /// (and the id of the current turn) is appended to each server URL so tools can
+    /// apply conversation-scoped restrictions and per-turn result caching.
+    fn mcp_servers_config(
+        client_id: Uuid,
+        project_id: &str,
+        conversation: Option<(&str, &str)>,
+    ) -> serde_json::Value {
+        let query = conversation
+            .map(|(conversation_id, turn_id)| {
+                format!("?conversation_id={}&turn_id={}", conversation_id, turn_id)
+            })
+            .unwrap_or_default();
+        let base_url = mcp_server_base_url();
+        let server = |server_type: &str| {
+            json!({
+                "type": "http",
+                "url": format!("{}/{}/{}/{}{}", base_url, server_type, client_id, project_id, query)
+            })
+        };
+
+        json!({
+            "mcpServers": {
+                "operation": server("operation"),
+                "analysis": server("analysis"),
+                "interaction": server("interaction")
+            }
+        })
+    }
+
     async fn ensure_requirements_met(
         &self,
         tx: &mpsc::Sender<ClaudeMessage>,
@@ -245,6 +262,8 @@ impl ClaudeSDK {
         let working_dir_for_auto_organize = working_dir.clone();
         let client_id = self.client_id;
         let prompt = request.prompt.clone();
+        let conversation_id = request.options.as_ref().and_then(|o| o.conversation_id.clone());
+        let model = request.options.as_ref().and_then(|o| o.model.clone());
         let _project_dir = self.project_dir.clone();
 
         tracing::info!("Claude SDK working directory: {:?}", working_dir_clone);
@@ -274,10 +293,27 @@ impl ClaudeSDK {
             cmd_builder.arg(&claude_cli_path_clone);
 
             // Add MCP config if we have a project directory
+            let mut mcp_turn: Option<(String, String)> = None;
             if let Some(ref project_dir) = _project_dir {
                 let mcp_config_path = project_dir.join(".claude/mcp_servers.json");
                 if mcp_config_path.exists() {
-                    cmd_builder.arg("--mcp-config").arg(".claude/mcp_servers.json");
+                    match (conversation_id.as_deref(), project_dir.file_name().and_then(|n| n.to_str())) {
+                        // Inline config tags MCP calls with the conversation they belong to
+                        (Some(conversation_id), Some(project_id)) => {
+                            // Each query is one model turn; a fresh id scopes MCP result caching to it
+                            let turn_id = Uuid::new_v4().to_string();
+                            let config = Self::mcp_servers_config(
+                                client_id,
+                                project_id,
+                                Some((conversation_id, turn_id.as_str())),
+                            );
+                            cmd_builder.arg("--mcp-config").arg(config.to_string());
+                            mcp_turn = Some((conversation_id.to_string(), turn_id));
+                        }
+                        _ => {
+                            cmd_builder.arg("--mcp-config").arg(".claude/mcp_servers.json");
+                        }
+                    }
                 }
             }
 
@@ -289,6 +325,10 @@ impl ClaudeSDK {
                 .arg("-") // Read from stdin
                 .arg("--verbose");
 
+            if let Some(ref model) = model {
+                cmd_builder.arg("--model").arg(model);
+            }
+
             // Add allowed tools - dynamically get all MCP tools plus native tools
             let mut allowed_mcp_tools = get_all_available_mcp_tools();
             // Add native tools to allowed tools
@@ -837,6 +877,11 @@ impl ClaudeSDK {
                 }
             }
 
+            // The turn is over; its cached MCP tool results must not outlive it
+            if let Some((conversation_id, turn_id)) = mcp_turn {
+                Self::end_mcp_turn(&conversation_id, &turn_id).await;
+            }
+
             // Wait for stdout processing to complete before dropping tx
             if let Some(handle) = stdout_handle {
                 tracing::debug!("Waiting for stdout processing to complete...");
@@ -874,7 +919,21 @@ impl ClaudeSDK {
         guard.clone()
     }
 
-    /// Check if centralized MCP server is ready on port 7670
+    /// Tell the MCP server a turn has ended so it drops the tool results
+    /// cached for it. Failures only leave the entries to expire.
+    async fn end_mcp_turn(conversation_id: &str, turn_id: &str) {
+        let url = format!("{}/turns/{}/{}", mcp_server_base_url(), conversation_id, turn_id);
+        let result = reqwest::Client::new()
+            .delete(&url)
+            .timeout(std::time::Duration::from_secs(2))
+            .send()
+            .await;
+        if let Err(e) = result {
+            tracing::debug!("Could not end MCP turn {}: {}", turn_id, e);
+        }
+    }
+
+    /// Check if centralized MCP server is ready
     async fn check_centralized_mcp_server_ready() -> bool {
         use tokio::time::{timeout, Duration};
         
@@ -887,7 +946,7 @@ impl ClaudeSDK {
         };
         
         // Test with a dummy client/project ID to see if server responds
-        let url = "http://localhost:7670/operation/test-client/test-project";
+        let url = format!("{}/operation/test-client/test-project", mcp_server_base_url());
         
         let test_request = serde_json::json!({
             "jsonrpc": "2.0",
@@ -901,7 +960,7 @@ impl ClaudeSDK {
         });
         
         let request_future = client
-            .post(url)
+            .post(&url)
             .header("Content-Type", "application/json")
             .json(&test_request)
             .send();
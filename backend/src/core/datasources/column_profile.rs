@@ -0,0 +1,130 @@
+//! Per-column statistics for file previews
+//!
+//! Users check these before committing an upload: the type each column will
+//! be imported as, how many values are missing, roughly how many distinct
+//! values there are and a few examples. Only sampled rows are profiled.
+
+use serde::Serialize;
+use std::collections::HashSet;
+
+use super::csv_import::ColumnType;
+
+/// Distinct values tracked per column before the count becomes a lower bound
+const MAX_TRACKED_DISTINCT: usize = 10_000;
+/// Example values reported per column
+const SAMPLE_VALUES: usize = 5;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ColumnProfile {
+    pub name: String,
+    /// integer, float, boolean, date, timestamp or string
+    pub inferred_type: &'static str,
+    pub null_count: usize,
+    pub distinct_estimate: usize,
+    /// More distinct values exist than were tracked
+    pub distinct_capped: bool,
+    pub sample_values: Vec<String>,
+}
+
+pub struct ColumnProfiler {
+    name: String,
+    data_type: Option<ColumnType>,
+    null_count: usize,
+    distinct: HashSet<String>,
+    distinct_capped: bool,
+    sample_values: Vec<String>,
+}
+
+impl ColumnProfiler {
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            data_type: None,
+            null_count: 0,
+            distinct: HashSet::new(),
+            distinct_capped: false,
+            sample_values: Vec::new(),
+        }
+    }
+
+    /// Record one value; blank values count as nulls
+    pub fn observe(&mut self, value: &str) {
+        if value.trim().is_empty() {
+            self.null_count += 1;
+            return;
+        }
+
+        let value_type = ColumnType::of(value);
+        self.data_type = Some(self.data_type.map_or(value_type, |t| t.widen(value_type)));
+
+        if self.distinct.contains(value) {
+            return;
+        }
+        if self.sample_values.len() < SAMPLE_VALUES {
+            self.sample_values.push(value.to_string());
+        }
+        if self.distinct.len() < MAX_TRACKED_DISTINCT {
+            self.distinct.insert(value.to_string());
+        } else {
+            self.distinct_capped = true;
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Inferred type so far; columns without any values are text
+    pub fn data_type(&self) -> ColumnType {
+        self.data_type.unwrap_or(ColumnType::Varchar)
+    }
+
+    pub fn null_count(&self) -> usize {
+        self.null_count
+    }
+
+    pub fn finish(self) -> ColumnProfile {
+        ColumnProfile {
+            inferred_type: self.data_type().kind(),
+            name: self.name,
+            null_count: self.null_count,
+            distinct_estimate: self.distinct.len(),
+            distinct_capped: self.distinct_capped,
+            sample_values: self.sample_values,
+        }
+    }
+}
+
+/// Profile sampled rows; missing trailing cells count as nulls
+pub fn profile_rows(headers: &[String], rows: &[Vec<String>]) -> Vec<ColumnProfile> {
+    let mut profilers: Vec<ColumnProfiler> = headers.iter().map(ColumnProfiler::new).collect();
+    for row in rows {
+        for (i, profiler) in profilers.iter_mut().enumerate() {
+            profiler.observe(row.get(i).map(String::as_str).unwrap_or(""));
+        }
+    }
+    profilers.into_iter().map(ColumnProfiler::finish).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn one_non_numeric_value_makes_a_column_text() {
+        let headers = vec!["qty".to_string(), "price".to_string()];
+        let rows: Vec<Vec<String>> = [["1", "2.5"], ["2", ""], ["n/a", "3"], ["2", "4"]]
+            .iter()
+            .map(|row| row.iter().map(|v| v.to_string()).collect())
+            .collect();
+
+        let profiles = profile_rows(&headers, &rows);
+        assert_eq!(profiles[0].inferred_type, "string");
+        assert_eq!(profiles[0].null_count, 0);
+        assert_eq!(profiles[0].distinct_estimate, 3);
+        assert_eq!(profiles[0].sample_values, ["1", "2", "n/a"]);
+
+        assert_eq!(profiles[1].inferred_type, "float");
+        assert_eq!(profiles[1].null_count, 1);
+    }
+}
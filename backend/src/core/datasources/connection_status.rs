@@ -0,0 +1,92 @@
+//! Persisted connection health
+//!
+//! Connection tests, from the API and from the MCP `datasource_test` tool,
+//! store their outcome on the datasource: `connection_status` is `connected`
+//! or `error`, and `connection_error` holds the failure message until a later
+//! test succeeds. A datasource that was never tested, or whose connection
+//! config changed since, reports `unknown`.
+
+use sqlx::PgPool;
+
+pub const STATUS_CONNECTED: &str = "connected";
+pub const STATUS_ERROR: &str = "error";
+pub const STATUS_UNKNOWN: &str = "unknown";
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConnectionHealth {
+    pub status: &'static str,
+    pub error: Option<String>,
+}
+
+impl ConnectionHealth {
+    /// The health recorded for a connection test: a success clears any
+    /// earlier error
+    pub fn from_test(result: Result<(), String>) -> Self {
+        match result {
+            Ok(()) => Self { status: STATUS_CONNECTED, error: None },
+            Err(error) => Self { status: STATUS_ERROR, error: Some(error) },
+        }
+    }
+
+    /// The `connection_status` / `connection_error` pair returned to clients
+    /// for a datasource's stored columns
+    pub fn reported(status: Option<String>, error: Option<String>) -> (Option<String>, Option<String>) {
+        match status {
+            Some(status) => {
+                let error = if status == STATUS_ERROR { error } else { None };
+                (Some(status), error)
+            }
+            None => (Some(STATUS_UNKNOWN.to_string()), None),
+        }
+    }
+}
+
+/// Store the outcome of a connection test. A failed write is logged rather
+/// than failing the test that was already carried out.
+pub async fn record_connection_test(db: &PgPool, datasource_id: &str, health: &ConnectionHealth) {
+    let result = sqlx::query(
+        "UPDATE data_sources SET connection_status = $1, connection_error = $2, last_tested_at = NOW() WHERE id = $3",
+    )
+    .bind(health.status)
+    .bind(&health.error)
+    .bind(datasource_id)
+    .execute(db)
+    .await;
+
+    if let Err(e) = result {
+        tracing::warn!("Failed to record connection test of datasource {}: {}", datasource_id, e);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn a_failing_test_stores_the_error_and_a_passing_one_clears_it() {
+        let failed = ConnectionHealth::from_test(Err("password authentication failed".to_string()));
+        assert_eq!(failed.status, STATUS_ERROR);
+        assert_eq!(failed.error.as_deref(), Some("password authentication failed"));
+        assert_eq!(
+            ConnectionHealth::reported(Some(failed.status.to_string()), failed.error.clone()),
+            (Some("error".to_string()), Some("password authentication failed".to_string()))
+        );
+
+        let passed = ConnectionHealth::from_test(Ok(()));
+        assert_eq!(passed, ConnectionHealth { status: STATUS_CONNECTED, error: None });
+        assert_eq!(
+            ConnectionHealth::reported(Some(passed.status.to_string()), passed.error),
+            (Some("connected".to_string()), None)
+        );
+    }
+
+    #[test]
+    fn untested_datasources_report_unknown() {
+        assert_eq!(ConnectionHealth::reported(None, None), (Some("unknown".to_string()), None));
+        // A stale error isn't shown next to a connected status
+        assert_eq!(
+            ConnectionHealth::reported(Some("connected".to_string()), Some("old".to_string())),
+            (Some("connected".to_string()), None)
+        );
+    }
+}
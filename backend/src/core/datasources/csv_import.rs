@@ -0,0 +1,374 @@
+//! Importing uploaded CSV files into DuckDB
+//!
+//! Column types are inferred by stream-parsing the first `sample_rows` records,
+//! so large files are never read into memory. The file is then loaded into a
+//! DuckDB database with those types, which makes it queryable with full SQL
+//! through the `duckdb` connector.
+
+use anyhow::{anyhow, Context, Result};
+use serde::Serialize;
+use std::io::Read;
+use std::path::Path;
+
+use super::column_profile::{ColumnProfile, ColumnProfiler};
+
+/// Records sampled for type inference when the upload doesn't say otherwise
+pub const DEFAULT_SAMPLE_ROWS: usize = 1000;
+/// Sampled records echoed back in previews
+const PREVIEW_ROWS: usize = 10;
+
+#[derive(Debug, Clone)]
+pub struct CsvImportOptions {
+    pub delimiter: u8,
+    pub has_header: bool,
+    /// Records read to infer column types
+    pub sample_rows: usize,
+}
+
+impl Default for CsvImportOptions {
+    fn default() -> Self {
+        Self {
+            delimiter: b',',
+            has_header: true,
+            sample_rows: DEFAULT_SAMPLE_ROWS,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ColumnType {
+    Boolean,
+    BigInt,
+    Double,
+    Date,
+    Timestamp,
+    Varchar,
+}
+
+impl ColumnType {
+    pub fn sql_type(self) -> &'static str {
+        match self {
+            ColumnType::Boolean => "BOOLEAN",
+            ColumnType::BigInt => "BIGINT",
+            ColumnType::Double => "DOUBLE",
+            ColumnType::Date => "DATE",
+            ColumnType::Timestamp => "TIMESTAMP",
+            ColumnType::Varchar => "VARCHAR",
+        }
+    }
+
+    /// Name shown in previews
+    pub fn kind(self) -> &'static str {
+        match self {
+            ColumnType::Boolean => "boolean",
+            ColumnType::BigInt => "integer",
+            ColumnType::Double => "float",
+            ColumnType::Date => "date",
+            ColumnType::Timestamp => "timestamp",
+            ColumnType::Varchar => "string",
+        }
+    }
+
+    /// Narrowest type that can hold a single non-empty value
+    pub(crate) fn of(value: &str) -> Self {
+        let value = value.trim();
+        if value.eq_ignore_ascii_case("true") || value.eq_ignore_ascii_case("false") {
+            ColumnType::Boolean
+        } else if is_integer(value) {
+            ColumnType::BigInt
+        } else if is_decimal(value) {
+            ColumnType::Double
+        } else if chrono::NaiveDate::parse_from_str(value, "%Y-%m-%d").is_ok() {
+            ColumnType::Date
+        } else if ["%Y-%m-%d %H:%M:%S%.f", "%Y-%m-%dT%H:%M:%S%.f"]
+            .iter()
+            .any(|format| chrono::NaiveDateTime::parse_from_str(value, format).is_ok())
+        {
+            ColumnType::Timestamp
+        } else {
+            ColumnType::Varchar
+        }
+    }
+
+    /// Type that can hold values of both types
+    pub(crate) fn widen(self, other: Self) -> Self {
+        use ColumnType::*;
+        match (self, other) {
+            (a, b) if a == b => a,
+            (BigInt, Double) | (Double, BigInt) => Double,
+            (Date, Timestamp) | (Timestamp, Date) => Timestamp,
+            _ => Varchar,
+        }
+    }
+}
+
+/// Integers without leading zeros; "007" or ZIP codes stay text
+fn is_integer(value: &str) -> bool {
+    let digits = value.strip_prefix('-').unwrap_or(value);
+    !digits.is_empty()
+        && digits.bytes().all(|b| b.is_ascii_digit())
+        && (digits == "0" || !digits.starts_with('0'))
+        && value.parse::<i64>().is_ok()
+}
+
+/// Plain decimal notation; rejects "NaN", "inf", thousands separators and,
+/// like `is_integer`, leading zeros such as "02139"
+fn is_decimal(value: &str) -> bool {
+    let unsigned = value.strip_prefix(['-', '+']).unwrap_or(value);
+    let whole = unsigned.split(['.', 'e', 'E']).next().unwrap_or("");
+    (whole.len() <= 1 || !whole.starts_with('0'))
+        && value.bytes().any(|b| b.is_ascii_digit())
+        && value
+            .bytes()
+            .all(|b| b.is_ascii_digit() || matches!(b, b'.' | b'-' | b'+' | b'e' | b'E'))
+        && value.parse::<f64>().is_ok()
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct InferredColumn {
+    pub name: String,
+    pub data_type: ColumnType,
+    /// An empty value was seen in the sample
+    pub nullable: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CsvSchema {
+    pub columns: Vec<InferredColumn>,
+    /// Records the types were inferred from
+    pub sampled_rows: usize,
+    /// First few sampled records, for previews
+    pub sample_data: Vec<Vec<String>>,
+    /// Null counts, distinct values and examples per column
+    pub profiles: Vec<ColumnProfile>,
+}
+
+/// Infer column names and types from the first `options.sample_rows` records
+pub fn infer_schema<R: Read>(input: R, options: &CsvImportOptions) -> Result<CsvSchema> {
+    let mut reader = csv::ReaderBuilder::new()
+        .delimiter(options.delimiter)
+        .has_headers(options.has_header)
+        .flexible(true)
+        .from_reader(input);
+
+    let names: Vec<String> = if options.has_header {
+        reader
+            .headers()
+            .context("Failed to read CSV header")?
+            .iter()
+            .map(|name| name.trim().to_string())
+            .collect()
+    } else {
+        Vec::new()
+    };
+    let mut profilers: Vec<ColumnProfiler> = names.iter().map(ColumnProfiler::new).collect();
+    let mut sample_data = Vec::new();
+    let mut sampled_rows = 0;
+
+    for record in reader.records().take(options.sample_rows.max(1)) {
+        let record = record.with_context(|| format!("Malformed CSV near record {}", sampled_rows + 1))?;
+        while profilers.len() < record.len() {
+            // Earlier records lacked this column and are padded with NULLs on import
+            let mut profiler = ColumnProfiler::new(String::new());
+            (0..sampled_rows).for_each(|_| profiler.observe(""));
+            profilers.push(profiler);
+        }
+        for (i, profiler) in profilers.iter_mut().enumerate() {
+            profiler.observe(record.get(i).unwrap_or(""));
+        }
+        if sample_data.len() < PREVIEW_ROWS {
+            sample_data.push(record.iter().map(str::to_string).collect());
+        }
+        sampled_rows += 1;
+    }
+
+    if profilers.is_empty() {
+        return Err(anyhow!("CSV file has no columns"));
+    }
+
+    let mut seen = std::collections::HashSet::new();
+    let mut columns = Vec::with_capacity(profilers.len());
+    let mut profiles = Vec::with_capacity(profilers.len());
+    for (i, profiler) in profilers.into_iter().enumerate() {
+        let base = if profiler.name().is_empty() {
+            format!("column_{}", i + 1)
+        } else {
+            profiler.name().to_string()
+        };
+        let mut name = base.clone();
+        let mut suffix = 2;
+        while !seen.insert(name.to_lowercase()) {
+            name = format!("{}_{}", base, suffix);
+            suffix += 1;
+        }
+
+        columns.push(InferredColumn {
+            name: name.clone(),
+            // Columns that were empty throughout the sample stay text
+            data_type: profiler.data_type(),
+            nullable: profiler.null_count() > 0,
+        });
+        let mut profile = profiler.finish();
+        profile.name = name;
+        profiles.push(profile);
+    }
+
+    Ok(CsvSchema {
+        columns,
+        sampled_rows,
+        sample_data,
+        profiles,
+    })
+}
+
+/// Table name derived from a file or datasource name
+pub fn table_name_for(name: &str) -> String {
+    let stem = Path::new(name)
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .unwrap_or(name);
+    let table: String = stem
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c.to_ascii_lowercase() } else { '_' })
+        .collect::<String>()
+        .trim_matches('_')
+        .to_string();
+
+    match table.chars().next() {
+        None => "data".to_string(),
+        Some(c) if c.is_ascii_digit() => format!("t_{}", table),
+        Some(_) => table,
+    }
+}
+
+fn sql_string(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "''"))
+}
+
+/// Load `csv_path` into `table_name` of a new DuckDB database at `db_path`
+/// using the inferred schema, returning the number of rows imported. Blocking;
+/// call from `spawn_blocking`.
+pub fn import_into_duckdb(
+    csv_path: &Path,
+    db_path: &Path,
+    table_name: &str,
+    schema: &CsvSchema,
+    options: &CsvImportOptions,
+) -> Result<u64> {
+    if let Some(parent) = db_path.parent() {
+        std::fs::create_dir_all(parent)
+            .with_context(|| format!("Failed to create {}", parent.display()))?;
+    }
+
+    let columns = schema
+        .columns
+        .iter()
+        .map(|c| format!("{}: {}", sql_string(&c.name), sql_string(c.data_type.sql_type())))
+        .collect::<Vec<_>>()
+        .join(", ");
+    let statement = format!(
+        "CREATE TABLE \"{}\" AS SELECT * FROM read_csv({}, header = {}, delim = {}, quote = '\"', escape = '\"', null_padding = true, parallel = false, auto_detect = false, columns = {{{}}})",
+        table_name.replace('"', "\"\""),
+        sql_string(&csv_path.to_string_lossy()),
+        options.has_header,
+        sql_string(&(options.delimiter as char).to_string()),
+        columns
+    );
+
+    let connection = duckdb::Connection::open(db_path)
+        .with_context(|| format!("Failed to create DuckDB file {}", db_path.display()))?;
+    if let Err(e) = connection.execute_batch(&statement) {
+        drop(connection);
+        let _ = std::fs::remove_file(db_path);
+        return Err(anyhow!(
+            "Failed to import CSV: {}. A value after the first {} rows may not match the inferred column types; retry with a larger sample size.",
+            e,
+            schema.sampled_rows
+        ));
+    }
+
+    let row_count: i64 = connection.query_row(
+        &format!("SELECT COUNT(*) FROM \"{}\"", table_name.replace('"', "\"\"")),
+        [],
+        |row| row.get(0),
+    )?;
+    Ok(row_count as u64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MESSY_CSV: &str = "id,name,score,joined,active,notes,zip,\n\
+        1,\"Smith, Jane\",9.5,2024-01-02,true,\"said \"\"hi\"\"\",02139,\n\
+        2,Bob,10,2024-02-03,FALSE,\"line one\nline two\",94105,x\n\
+        3,,,,,,,\n\
+        4,\"Ann\",7,2024-03-04 10:15:00,true\n";
+
+    fn column<'a>(schema: &'a CsvSchema, name: &str) -> &'a InferredColumn {
+        schema.columns.iter().find(|c| c.name == name).unwrap()
+    }
+
+    #[test]
+    fn infers_types_from_a_messy_csv() {
+        let schema = infer_schema(MESSY_CSV.as_bytes(), &CsvImportOptions::default()).unwrap();
+
+        assert_eq!(schema.sampled_rows, 4);
+        assert_eq!(column(&schema, "id").data_type, ColumnType::BigInt);
+        assert!(!column(&schema, "id").nullable);
+        assert_eq!(column(&schema, "name").data_type, ColumnType::Varchar);
+        assert_eq!(column(&schema, "score").data_type, ColumnType::Double);
+        assert_eq!(column(&schema, "joined").data_type, ColumnType::Timestamp);
+        assert_eq!(column(&schema, "active").data_type, ColumnType::Boolean);
+        // Leading zeros are kept as text
+        assert_eq!(column(&schema, "zip").data_type, ColumnType::Varchar);
+        // The unnamed trailing column gets a generated name
+        assert_eq!(schema.columns[7].name, "column_8");
+
+        // Quoted delimiters, escaped quotes and embedded newlines stay in one field
+        assert_eq!(schema.sample_data[0][1], "Smith, Jane");
+        assert_eq!(schema.sample_data[0][5], "said \"hi\"");
+        assert_eq!(schema.sample_data[1][5], "line one\nline two");
+    }
+
+    #[test]
+    fn sample_size_limits_inference() {
+        let csv = "value\n1\n2\nthree\n";
+        let small = CsvImportOptions { sample_rows: 2, ..Default::default() };
+        let schema = infer_schema(csv.as_bytes(), &small).unwrap();
+        assert_eq!(schema.sampled_rows, 2);
+        assert_eq!(schema.columns[0].data_type, ColumnType::BigInt);
+
+        let schema = infer_schema(csv.as_bytes(), &CsvImportOptions::default()).unwrap();
+        assert_eq!(schema.columns[0].data_type, ColumnType::Varchar);
+
+        assert_eq!(schema.profiles[0].inferred_type, "string");
+        assert_eq!(schema.profiles[0].sample_values, ["1", "2", "three"]);
+
+        assert_eq!(table_name_for("2024 Sales-Report.csv"), "t_2024_sales_report");
+    }
+
+    #[test]
+    fn imports_a_messy_csv_into_duckdb() {
+        let dir = tempfile::tempdir().unwrap();
+        let csv_path = dir.path().join("messy.csv");
+        std::fs::write(&csv_path, MESSY_CSV).unwrap();
+        let db_path = dir.path().join("nested").join("messy.duckdb");
+
+        let options = CsvImportOptions::default();
+        let schema = infer_schema(std::fs::File::open(&csv_path).unwrap(), &options).unwrap();
+        let rows = import_into_duckdb(&csv_path, &db_path, "messy", &schema, &options).unwrap();
+        assert_eq!(rows, 4);
+
+        let connection = duckdb::Connection::open(&db_path).unwrap();
+        let notes: String = connection
+            .query_row("SELECT notes FROM messy WHERE id = 2", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(notes, "line one\nline two");
+        let total: f64 = connection
+            .query_row("SELECT SUM(score) FROM messy", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(total, 26.5);
+    }
+}
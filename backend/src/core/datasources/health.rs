@@ -0,0 +1,120 @@
+//! Datasource usability checks
+//!
+//! Answers "can the model actually query this datasource?" by running a tiny
+//! read-only probe through the same connector path the MCP `datasource_query`
+//! tool uses. Results are cached for `HEALTH_CACHE_TTL` so dashboards polling
+//! the endpoint don't hammer the databases.
+
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use crate::utils::datasource::create_connector;
+
+/// How long a probe result is reused
+const HEALTH_CACHE_TTL: Duration = Duration::from_secs(30);
+/// Probes slower than this count as failures
+const PROBE_TIMEOUT: Duration = Duration::from_secs(5);
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DatasourceHealth {
+    pub datasource_id: String,
+    pub name: String,
+    pub source_type: String,
+    pub usable: bool,
+    pub latency_ms: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    pub checked_at: chrono::DateTime<chrono::Utc>,
+    /// True when this result came from the cache rather than a fresh probe
+    pub cached: bool,
+}
+
+lazy_static::lazy_static! {
+    static ref HEALTH_CACHE: Mutex<HashMap<String, (Instant, DatasourceHealth)>> = Mutex::new(HashMap::new());
+}
+
+/// Statement used to probe a datasource; None for types probed by listing tables
+fn probe_query(source_type: &str) -> Option<&'static str> {
+    match source_type.to_lowercase().as_str() {
+        "postgresql" | "postgres" | "mysql" | "sqlite" | "clickhouse" | "sqlserver" => Some("SELECT 1"),
+        "oracle" => Some("SELECT 1 FROM dual"),
+        _ => None,
+    }
+}
+
+/// Probe one datasource, reusing a recent result unless `refresh` is set
+pub async fn check_datasource_health(
+    datasource_id: &str,
+    name: &str,
+    source_type: &str,
+    connection_config: &Value,
+    refresh: bool,
+) -> DatasourceHealth {
+    if !refresh {
+        if let Some(cached) = cached_health(datasource_id) {
+            return cached;
+        }
+    }
+
+    let started = Instant::now();
+    let outcome = match tokio::time::timeout(PROBE_TIMEOUT, probe(datasource_id, source_type, connection_config)).await {
+        Ok(outcome) => outcome,
+        Err(_) => Err(format!("No response within {}s", PROBE_TIMEOUT.as_secs())),
+    };
+
+    let health = DatasourceHealth {
+        datasource_id: datasource_id.to_string(),
+        name: name.to_string(),
+        source_type: source_type.to_string(),
+        usable: outcome.is_ok(),
+        latency_ms: started.elapsed().as_millis() as u64,
+        error: outcome.err(),
+        checked_at: chrono::Utc::now(),
+        cached: false,
+    };
+
+    if let Ok(mut cache) = HEALTH_CACHE.lock() {
+        cache.retain(|_, (at, _)| at.elapsed() < HEALTH_CACHE_TTL);
+        cache.insert(datasource_id.to_string(), (Instant::now(), health.clone()));
+    }
+    health
+}
+
+fn cached_health(datasource_id: &str) -> Option<DatasourceHealth> {
+    let cache = HEALTH_CACHE.lock().ok()?;
+    let (at, health) = cache.get(datasource_id)?;
+    (at.elapsed() < HEALTH_CACHE_TTL).then(|| DatasourceHealth { cached: true, ..health.clone() })
+}
+
+async fn probe(datasource_id: &str, source_type: &str, connection_config: &Value) -> Result<(), String> {
+    let mut config = connection_config.clone();
+    if let Some(obj) = config.as_object_mut() {
+        obj.insert("id".to_string(), Value::String(datasource_id.to_string()));
+    }
+    let connector = create_connector(source_type, &config).await.map_err(|e| e.to_string())?;
+
+    match probe_query(source_type) {
+        Some(query) => connector
+            .execute_read_only_query(query, 1)
+            .await
+            .map(|_| ())
+            .map_err(|e| e.to_string()),
+        None => connector.list_tables().await.map(|_| ()).map_err(|e| e.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn probe_query_matches_dialect() {
+        assert_eq!(probe_query("PostgreSQL"), Some("SELECT 1"));
+        assert_eq!(probe_query("oracle"), Some("SELECT 1 FROM dual"));
+        assert_eq!(probe_query("mongodb"), None);
+        assert_eq!(probe_query("csv"), None);
+    }
+}
@@ -1,2 +1,10 @@
 pub mod cache;
-pub mod shared_service;
\ No newline at end of file
+pub mod column_profile;
+pub mod connection_status;
+pub mod csv_import;
+pub mod health;
+pub mod query_history;
+pub mod schema_diff;
+pub mod schema_events;
+pub mod shared_service;
+pub mod slow_queries;
\ No newline at end of file
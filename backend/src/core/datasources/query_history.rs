@@ -0,0 +1,222 @@
+//! Recent query history
+//!
+//! Every query run through the REST query endpoint or the MCP
+//! `datasource_query` tool is written to `query_history` with its datasource,
+//! row count and duration, so it can be listed and replayed later. Running the
+//! same query on the same datasource twice in a row bumps the latest entry's
+//! `run_count` and timestamp instead of adding a duplicate. Recording happens
+//! in a background task so it never delays the query response.
+
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sqlx::{PgPool, Row};
+use std::time::Duration;
+use uuid::Uuid;
+
+/// Longer queries aren't recorded; a truncated query couldn't be replayed
+const MAX_RECORDED_QUERY_CHARS: usize = 100_000;
+
+/// A query that has just run, before it is written
+#[derive(Debug, Clone)]
+pub struct ExecutedQuery {
+    pub datasource_id: String,
+    pub project_id: String,
+    /// Where the query came from: "rest", "mcp" or "replay"
+    pub source: &'static str,
+    pub query: String,
+    pub duration: Duration,
+    pub row_count: Option<i64>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RecentQuery {
+    pub id: Uuid,
+    pub datasource_id: String,
+    pub source: String,
+    pub query: String,
+    pub row_count: Option<i64>,
+    pub duration_ms: i64,
+    /// How many consecutive times the query was run
+    pub run_count: i32,
+    pub created_at: DateTime<Utc>,
+}
+
+impl RecentQuery {
+    /// The history record for running this entry again
+    pub fn replayed(&self, project_id: &str, duration: Duration, row_count: Option<i64>) -> ExecutedQuery {
+        ExecutedQuery {
+            datasource_id: self.datasource_id.clone(),
+            project_id: project_id.to_string(),
+            source: "replay",
+            query: self.query.clone(),
+            duration,
+            row_count,
+        }
+    }
+}
+
+/// Query text compared for deduplication: surrounding whitespace and a
+/// trailing semicolon don't make a query different
+fn normalized(query: &str) -> &str {
+    query.trim().trim_end_matches(';').trim_end()
+}
+
+/// Whether `query` repeats the latest history entry of its project
+fn is_repeat(latest_datasource_id: &str, latest_query: &str, query: &ExecutedQuery) -> bool {
+    latest_datasource_id == query.datasource_id && normalized(latest_query) == normalized(&query.query)
+}
+
+/// Add `query` to its project's history in the background
+pub fn record_query(db: &PgPool, query: ExecutedQuery) {
+    if query.query.chars().count() > MAX_RECORDED_QUERY_CHARS {
+        return;
+    }
+
+    let db = db.clone();
+    tokio::spawn(async move {
+        if let Err(e) = store_query(&db, &query).await {
+            tracing::error!("Failed to record query history for datasource {}: {}", query.datasource_id, e);
+        }
+    });
+}
+
+async fn store_query(db: &PgPool, query: &ExecutedQuery) -> Result<()> {
+    let latest: Option<(Uuid, String, String)> = sqlx::query_as(
+        "SELECT id, datasource_id, query FROM query_history WHERE project_id = $1 ORDER BY created_at DESC LIMIT 1",
+    )
+    .bind(&query.project_id)
+    .fetch_optional(db)
+    .await?;
+
+    if let Some((id, datasource_id, text)) = latest {
+        if is_repeat(&datasource_id, &text, query) {
+            sqlx::query(
+                r#"
+                UPDATE query_history
+                SET run_count = run_count + 1, source = $2, row_count = $3, duration_ms = $4, created_at = NOW()
+                WHERE id = $1
+                "#,
+            )
+            .bind(id)
+            .bind(query.source)
+            .bind(query.row_count)
+            .bind(query.duration.as_millis() as i64)
+            .execute(db)
+            .await?;
+            return Ok(());
+        }
+    }
+
+    sqlx::query(
+        r#"
+        INSERT INTO query_history (id, project_id, datasource_id, source, query, row_count, duration_ms, run_count, created_at)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, 1, NOW())
+        "#,
+    )
+    .bind(Uuid::new_v4())
+    .bind(&query.project_id)
+    .bind(&query.datasource_id)
+    .bind(query.source)
+    .bind(&query.query)
+    .bind(query.row_count)
+    .bind(query.duration.as_millis() as i64)
+    .execute(db)
+    .await?;
+    Ok(())
+}
+
+fn recent_query_from_row(row: &sqlx::postgres::PgRow) -> RecentQuery {
+    RecentQuery {
+        id: row.get("id"),
+        datasource_id: row.get("datasource_id"),
+        source: row.get("source"),
+        query: row.get("query"),
+        row_count: row.get("row_count"),
+        duration_ms: row.get("duration_ms"),
+        run_count: row.get("run_count"),
+        created_at: row.get("created_at"),
+    }
+}
+
+/// The project's most recently run queries, newest first
+pub async fn list_recent_queries(db: &PgPool, project_id: &str, limit: i64) -> Result<Vec<RecentQuery>> {
+    let rows = sqlx::query(
+        r#"
+        SELECT id, datasource_id, source, query, row_count, duration_ms, run_count, created_at
+        FROM query_history
+        WHERE project_id = $1
+        ORDER BY created_at DESC
+        LIMIT $2
+        "#,
+    )
+    .bind(project_id)
+    .bind(limit)
+    .fetch_all(db)
+    .await?;
+
+    Ok(rows.iter().map(recent_query_from_row).collect())
+}
+
+/// One history entry of a project
+pub async fn get_recent_query(db: &PgPool, project_id: &str, id: Uuid) -> Result<Option<RecentQuery>> {
+    let row = sqlx::query(
+        r#"
+        SELECT id, datasource_id, source, query, row_count, duration_ms, run_count, created_at
+        FROM query_history
+        WHERE project_id = $1 AND id = $2
+        "#,
+    )
+    .bind(project_id)
+    .bind(id)
+    .fetch_optional(db)
+    .await?;
+
+    Ok(row.as_ref().map(recent_query_from_row))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn executed(datasource_id: &str, query: &str) -> ExecutedQuery {
+        ExecutedQuery {
+            datasource_id: datasource_id.to_string(),
+            project_id: "sales".to_string(),
+            source: "rest",
+            query: query.to_string(),
+            duration: Duration::from_millis(12),
+            row_count: Some(3),
+        }
+    }
+
+    #[test]
+    fn consecutive_identical_queries_are_deduplicated() {
+        let latest = "SELECT * FROM orders";
+        assert!(is_repeat("ds-1", latest, &executed("ds-1", "  SELECT * FROM orders;\n")));
+        // Same text on another datasource is a different query
+        assert!(!is_repeat("ds-1", latest, &executed("ds-2", latest)));
+        assert!(!is_repeat("ds-1", latest, &executed("ds-1", "SELECT * FROM orders LIMIT 5")));
+    }
+
+    #[test]
+    fn replaying_records_the_same_query_on_the_same_datasource() {
+        let entry = RecentQuery {
+            id: Uuid::new_v4(),
+            datasource_id: "ds-1".to_string(),
+            source: "mcp".to_string(),
+            query: "SELECT count(*) FROM orders".to_string(),
+            row_count: Some(1),
+            duration_ms: 40,
+            run_count: 2,
+            created_at: Utc::now(),
+        };
+
+        let replay = entry.replayed("sales", Duration::from_millis(25), Some(1));
+        assert_eq!(replay.source, "replay");
+        assert_eq!(replay.datasource_id, "ds-1");
+        assert_eq!(replay.query, entry.query);
+        // A replay directly after the original run bumps the same entry
+        assert!(is_repeat(&entry.datasource_id, &entry.query, &replay));
+    }
+}
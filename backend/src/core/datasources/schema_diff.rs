@@ -0,0 +1,203 @@
+//! Differences between the last two schema inspections of a datasource
+//!
+//! Each inspection overwrites `data_sources.schema_info` and moves the
+//! snapshot it replaces to `schema_info_previous`. A full inspection only lists
+//! table names; column structures are present for tables inspected through a
+//! scoped `datasource_inspect` or the table structure endpoint. Column changes
+//! are therefore reported for tables described in both snapshots, and the rest
+//! are listed as `tables_without_columns`.
+
+use serde::Serialize;
+use serde_json::Value;
+use sqlx::PgPool;
+use std::collections::BTreeSet;
+
+use crate::core::analysis::schema_pins::{normalize_schema, NormalizedSchema, SchemaDrift};
+
+/// Table names and whatever column structures one `schema_info` snapshot holds
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SchemaSnapshot {
+    pub table_names: BTreeSet<String>,
+    pub columns: NormalizedSchema,
+}
+
+impl SchemaSnapshot {
+    pub fn from_schema_info(schema_info: &Value) -> Self {
+        let columns: NormalizedSchema = match schema_info.get("tables") {
+            Some(tables @ (Value::Object(_) | Value::Array(_))) => normalize_schema(tables)
+                .into_iter()
+                .filter(|(_, columns)| !columns.is_empty())
+                .collect(),
+            _ => NormalizedSchema::new(),
+        };
+
+        let mut table_names: BTreeSet<String> = schema_info
+            .get("table_names")
+            .and_then(|names| names.as_array())
+            .map(|names| names.iter().filter_map(|n| n.as_str()).map(str::to_string).collect())
+            .unwrap_or_default();
+        table_names.extend(columns.keys().cloned());
+
+        Self { table_names, columns }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ColumnTypeDiff {
+    pub column: String,
+    pub previous_type: String,
+    pub current_type: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TableDiff {
+    pub table: String,
+    pub added_columns: Vec<String>,
+    pub removed_columns: Vec<String>,
+    pub type_changes: Vec<ColumnTypeDiff>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct SchemaDiff {
+    pub added_tables: Vec<String>,
+    pub removed_tables: Vec<String>,
+    pub changed_tables: Vec<TableDiff>,
+    /// Tables in both snapshots whose columns weren't inspected in both
+    pub tables_without_columns: Vec<String>,
+}
+
+impl SchemaDiff {
+    pub fn between(previous: &SchemaSnapshot, current: &SchemaSnapshot) -> Self {
+        let mut compared_previous = NormalizedSchema::new();
+        let mut compared_current = NormalizedSchema::new();
+        let mut tables_without_columns = Vec::new();
+        for table in previous.table_names.intersection(&current.table_names) {
+            match (previous.columns.get(table), current.columns.get(table)) {
+                (Some(before), Some(after)) => {
+                    compared_previous.insert(table.clone(), before.clone());
+                    compared_current.insert(table.clone(), after.clone());
+                }
+                _ => tables_without_columns.push(table.clone()),
+            }
+        }
+
+        let drift = SchemaDrift::between(&compared_previous, &compared_current);
+        SchemaDiff {
+            added_tables: current.table_names.difference(&previous.table_names).cloned().collect(),
+            removed_tables: previous.table_names.difference(&current.table_names).cloned().collect(),
+            changed_tables: drift
+                .changed_tables
+                .into_iter()
+                .map(|table| TableDiff {
+                    table: table.table,
+                    added_columns: table.added_columns,
+                    removed_columns: table.removed_columns,
+                    type_changes: table
+                        .changed_columns
+                        .into_iter()
+                        .map(|change| ColumnTypeDiff {
+                            column: change.column,
+                            previous_type: change.pinned_type,
+                            current_type: change.live_type,
+                        })
+                        .collect(),
+                })
+                .collect(),
+            tables_without_columns,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.added_tables.is_empty() && self.removed_tables.is_empty() && self.changed_tables.is_empty()
+    }
+}
+
+/// Diff of a datasource's stored snapshots; `None` until it has been
+/// inspected at least twice
+pub async fn stored_schema_diff(db: &PgPool, datasource_id: &str) -> Result<Option<SchemaDiff>, sqlx::Error> {
+    let snapshots: Option<(Option<Value>, Option<Value>)> =
+        sqlx::query_as("SELECT schema_info_previous, schema_info FROM data_sources WHERE id = $1")
+            .bind(datasource_id)
+            .fetch_optional(db)
+            .await?;
+
+    Ok(match snapshots {
+        Some((Some(previous), Some(current))) => Some(SchemaDiff::between(
+            &SchemaSnapshot::from_schema_info(&previous),
+            &SchemaSnapshot::from_schema_info(&current),
+        )),
+        _ => None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn reports_table_column_and_type_changes_between_snapshots() {
+        let previous = json!({
+            "table_names": ["customers", "orders", "legacy_events", "audit_log"],
+            "tables": {
+                "customers": { "columns": [
+                    { "column_name": "id", "data_type": "integer" },
+                    { "column_name": "email", "data_type": "text" },
+                    { "column_name": "fax", "data_type": "text" }
+                ] },
+                "orders": { "columns": [
+                    { "name": "id", "type": "integer" },
+                    { "name": "total", "type": "numeric" }
+                ] }
+            }
+        });
+        let current = json!({
+            "table_names": ["customers", "orders", "invoices", "audit_log"],
+            "tables": {
+                "customers": { "columns": [
+                    { "column_name": "id", "data_type": "bigint" },
+                    { "column_name": "email", "data_type": "text" },
+                    { "column_name": "phone", "data_type": "text" }
+                ] },
+                "orders": { "columns": [
+                    { "name": "id", "type": "integer" },
+                    { "name": "total", "type": "numeric" }
+                ] }
+            }
+        });
+
+        let diff = SchemaDiff::between(
+            &SchemaSnapshot::from_schema_info(&previous),
+            &SchemaSnapshot::from_schema_info(&current),
+        );
+
+        assert_eq!(diff.added_tables, vec!["invoices"]);
+        assert_eq!(diff.removed_tables, vec!["legacy_events"]);
+        assert_eq!(
+            diff.changed_tables,
+            vec![TableDiff {
+                table: "customers".to_string(),
+                added_columns: vec!["phone".to_string()],
+                removed_columns: vec!["fax".to_string()],
+                type_changes: vec![ColumnTypeDiff {
+                    column: "id".to_string(),
+                    previous_type: "integer".to_string(),
+                    current_type: "bigint".to_string(),
+                }],
+            }]
+        );
+        assert_eq!(diff.tables_without_columns, vec!["audit_log"]);
+    }
+
+    #[test]
+    fn unchanged_snapshots_have_an_empty_diff() {
+        // A full inspection lists table names only
+        let schema_info = json!({ "table_names": ["orders"], "statistics": { "table_count": 1 } });
+        let snapshot = SchemaSnapshot::from_schema_info(&schema_info);
+        assert!(snapshot.columns.is_empty());
+
+        let diff = SchemaDiff::between(&snapshot, &snapshot);
+        assert!(diff.is_empty());
+        assert_eq!(diff.tables_without_columns, vec!["orders"]);
+    }
+}
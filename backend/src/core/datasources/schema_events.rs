@@ -0,0 +1,44 @@
+//! Schema refresh events
+//!
+//! Every write of a datasource's `schema_info` is followed by
+//! `notify_schema_refreshed`, which publishes a `SchemaRefreshed` event on a
+//! Postgres NOTIFY channel. Inspections also run in the MCP server, a
+//! separate process with no access to the websocket connections, so the event
+//! goes through the database; the backend listens on the channel and forwards
+//! each event to the project's subscribers.
+
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+
+pub const SCHEMA_REFRESH_CHANNEL: &str = "datasource_schema_refreshed";
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SchemaRefreshed {
+    pub project_id: String,
+    pub datasource_id: String,
+    /// Digest of the stored schema; changes whenever the schema does
+    pub schema_version: String,
+}
+
+/// Publish the current schema version of a datasource. A failure is logged
+/// rather than failing the refresh that was already stored.
+pub async fn notify_schema_refreshed(db: &PgPool, datasource_id: &str) {
+    let result = sqlx::query(
+        r#"
+        SELECT pg_notify($1, json_build_object(
+            'project_id', project_id,
+            'datasource_id', id,
+            'schema_version', md5(COALESCE(schema_info::text, ''))
+        )::text)
+        FROM data_sources WHERE id = $2
+        "#,
+    )
+    .bind(SCHEMA_REFRESH_CHANNEL)
+    .bind(datasource_id)
+    .execute(db)
+    .await;
+
+    if let Err(e) = result {
+        tracing::warn!("Failed to publish schema refresh of datasource {}: {}", datasource_id, e);
+    }
+}
@@ -5,7 +5,10 @@ use serde_json::Value;
 use sqlx::{PgPool, Row};
 use uuid::Uuid;
 use crate::core::datasources::cache::{get_datasource_cache, CachedDatasource};
-use crate::utils::datasource::{create_connector, pooling::execute_query_with_pooling};
+use crate::utils::datasource::{create_connector, pooling::execute_read_only_query_with_pooling};
+use crate::utils::datasource::common::connection_config::tag_connection_owner;
+use crate::utils::datasource::common::diagnostics::ConnectionFailure;
+use crate::utils::datasource::common::projection::ColumnRequest;
 
 /// Shared datasource information structure
 #[derive(Debug, Clone)]
@@ -47,9 +50,11 @@ pub async fn get_datasource_with_validation(
     // Cache miss - fetch from database
     let row = sqlx::query(
         r#"
-        SELECT id, name, source_type, connection_config, project_id, created_at
-        FROM data_sources 
-        WHERE id = $1 AND project_id = $2 AND deleted_at IS NULL
+        SELECT ds.id, ds.name, ds.source_type, ds.connection_config, ds.project_id, ds.created_at,
+               p.client_id::text AS client_id
+        FROM data_sources ds
+        LEFT JOIN projects p ON p.id = ds.project_id
+        WHERE ds.id = $1 AND ds.project_id = $2 AND ds.deleted_at IS NULL
         "#
     )
     .bind(datasource_id)
@@ -58,11 +63,16 @@ pub async fn get_datasource_with_validation(
     .await?
     .ok_or("Datasource not found")?;
 
+    // Connectors read the owner from the config to tag their sessions
+    let mut connection_config: Value = row.get("connection_config");
+    let client_id: Option<String> = row.get("client_id");
+    tag_connection_owner(&mut connection_config, client_id.as_deref(), project_id);
+
     let datasource = SharedDatasourceInfo {
         id: row.get("id"),
         name: row.get("name"),
         source_type: row.get("source_type"),
-        connection_config: row.get("connection_config"),
+        connection_config,
         project_id: row.get("project_id"),
         created_at: row.get("created_at"),
     };
@@ -91,26 +101,61 @@ pub async fn execute_query_on_datasource(
     query: &str,
     db_pool: &PgPool,
 ) -> Result<Value, Box<dyn std::error::Error + Send + Sync>> {
+    execute_query_on_datasource_with_limit(datasource_id, project_id, query, 1000000, db_pool).await
+}
+
+/// Like `execute_query_on_datasource`, fetching at most `limit` rows
+pub async fn execute_query_on_datasource_with_limit(
+    datasource_id: &str,
+    project_id: &str,
+    query: &str,
+    limit: i32,
+    db_pool: &PgPool,
+) -> Result<Value, Box<dyn std::error::Error + Send + Sync>> {
     
     // Get datasource info
     let datasource = get_datasource_with_validation(datasource_id, project_id, db_pool).await?;
     
     // Add datasource ID to config if not present (needed by connectors)
     let mut config_with_id = datasource.connection_config.clone();
-    if config_with_id.is_object() {
-        let config_obj = config_with_id.as_object_mut().unwrap();
+    if let Some(config_obj) = config_with_id.as_object_mut() {
         config_obj.insert("id".to_string(), Value::String(datasource_id.to_string()));
     }
     
-    // Execute query using pooling
-    execute_query_with_pooling(
+    // Reads run in a read-only transaction so a write that slips past the
+    // SELECT check still fails at the database
+    execute_read_only_query_with_pooling(
         datasource_id,
         &datasource.source_type,
         &config_with_id,
-        query
+        query,
+        limit
     ).await
 }
 
+/// Like `execute_query_on_datasource_with_limit`, selecting only the columns
+/// `columns` asks for so the rest are never fetched
+pub async fn execute_projected_query_on_datasource(
+    datasource_id: &str,
+    project_id: &str,
+    query: &str,
+    limit: i32,
+    columns: ColumnRequest<'_>,
+    db_pool: &PgPool,
+) -> Result<Value, Box<dyn std::error::Error + Send + Sync>> {
+    let datasource = get_datasource_with_validation(datasource_id, project_id, db_pool).await?;
+
+    let mut config_with_id = datasource.connection_config.clone();
+    if let Some(config_obj) = config_with_id.as_object_mut() {
+        config_obj.insert("id".to_string(), Value::String(datasource_id.to_string()));
+    }
+
+    let connector = create_connector(&datasource.source_type, &config_with_id)
+        .await
+        .map_err(|e| format!("Failed to create connector: {}", e))?;
+    connector.execute_projected_query(query, limit, columns).await
+}
+
 /// List datasources for a project
 pub async fn list_datasources_for_project(
     project_id: &str,
@@ -144,14 +189,17 @@ pub async fn list_datasources_for_project(
 }
 
 /// Test a datasource connection
+///
+/// Failures are `ConnectionFailure`s, so callers can report which config
+/// field is most likely wrong.
 pub async fn test_datasource_connection(
     source_type: &str,
     connection_config: &Value,
 ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
     let mut connector = create_connector(source_type, connection_config).await
-        .map_err(|e| Box::new(std::io::Error::other(e.to_string())) as Box<dyn std::error::Error + Send + Sync>)?;
+        .map_err(|e| Box::new(ConnectionFailure::from_error(source_type, connection_config, &*e)) as Box<dyn std::error::Error + Send + Sync>)?;
     connector.test_connection().await
-        .map_err(|e| Box::new(std::io::Error::other(e.to_string())) as Box<dyn std::error::Error + Send + Sync>)?;
+        .map_err(|e| Box::new(ConnectionFailure::from_error(source_type, connection_config, &*e)) as Box<dyn std::error::Error + Send + Sync>)?;
     Ok(())
 }
 
@@ -0,0 +1,244 @@
+//! Slow query log
+//!
+//! Queries run through the REST query endpoint or the MCP `datasource_query`
+//! tool that take longer than `SLOW_QUERY_THRESHOLD_MS` are written to
+//! `slow_query_log` with their duration and row count. With
+//! `SLOW_QUERY_CAPTURE_PLAN=true` the plan of slow SELECTs is fetched with the
+//! dialect's EXPLAIN and stored alongside. Recording happens in a background
+//! task so it never delays the query response.
+
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use serde_json::Value;
+use sqlx::{PgPool, Row};
+use std::time::Duration;
+use uuid::Uuid;
+
+use crate::utils::datasource::create_connector;
+
+pub use crate::utils::config::SlowQueryConfig;
+
+/// Longest query text kept per entry
+const MAX_LOGGED_QUERY_CHARS: usize = 10_000;
+
+/// A query that crossed the threshold, before it is written
+#[derive(Debug, Clone)]
+pub struct SlowQuery {
+    pub datasource_id: String,
+    pub project_id: String,
+    /// Where the query came from: "rest" or "mcp"
+    pub source: &'static str,
+    pub query: String,
+    pub duration: Duration,
+    pub row_count: Option<i64>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SlowQueryEntry {
+    pub id: Uuid,
+    pub datasource_id: String,
+    pub source: String,
+    pub query: String,
+    pub duration_ms: i64,
+    pub row_count: Option<i64>,
+    pub query_plan: Option<Value>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Row count of a connector result, from `row_count` or the length of `rows`
+pub fn result_row_count(result: &Value) -> Option<i64> {
+    result
+        .get("row_count")
+        .and_then(|v| v.as_i64())
+        .or_else(|| result.get("rows").and_then(|r| r.as_array()).map(|rows| rows.len() as i64))
+}
+
+/// Log `query` in the background if `config` considers it slow
+///
+/// `source_type` and `connection_config` are only used to capture the plan.
+pub fn record_if_slow(
+    db: &PgPool,
+    config: &SlowQueryConfig,
+    source_type: &str,
+    connection_config: &Value,
+    query: SlowQuery,
+) {
+    if !config.is_slow(query.duration) {
+        return;
+    }
+
+    tracing::warn!(
+        "Slow query on datasource {} ({}): {}ms, {} rows",
+        query.datasource_id,
+        query.source,
+        query.duration.as_millis(),
+        query.row_count.map(|c| c.to_string()).unwrap_or_else(|| "?".to_string())
+    );
+
+    let db = db.clone();
+    let capture_plan = config.capture_plan;
+    let source_type = source_type.to_string();
+    let connection_config = connection_config.clone();
+    tokio::spawn(async move {
+        let plan = if capture_plan {
+            capture_query_plan(&source_type, &connection_config, &query).await
+        } else {
+            None
+        };
+        if let Err(e) = insert_slow_query(&db, &query, plan).await {
+            tracing::error!("Failed to record slow query for datasource {}: {}", query.datasource_id, e);
+        }
+    });
+}
+
+async fn insert_slow_query(db: &PgPool, query: &SlowQuery, plan: Option<Value>) -> Result<()> {
+    let text: String = query.query.chars().take(MAX_LOGGED_QUERY_CHARS).collect();
+    sqlx::query(
+        r#"
+        INSERT INTO slow_query_log (id, datasource_id, project_id, source, query, duration_ms, row_count, query_plan, created_at)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, NOW())
+        "#,
+    )
+    .bind(Uuid::new_v4())
+    .bind(&query.datasource_id)
+    .bind(&query.project_id)
+    .bind(query.source)
+    .bind(text)
+    .bind(query.duration.as_millis() as i64)
+    .bind(query.row_count)
+    .bind(plan)
+    .execute(db)
+    .await?;
+    Ok(())
+}
+
+/// EXPLAIN statement for a read query, or None when the dialect or statement isn't supported
+fn explain_statement(source_type: &str, query: &str) -> Option<String> {
+    let query = query.trim().trim_end_matches(';').trim_end();
+    let first_word = query
+        .split(|c: char| c.is_whitespace() || c == '(')
+        .next()
+        .unwrap_or("")
+        .to_ascii_uppercase();
+    if !matches!(first_word.as_str(), "SELECT" | "WITH") {
+        return None;
+    }
+
+    match source_type.to_lowercase().as_str() {
+        "postgresql" | "postgres" => Some(format!("EXPLAIN (FORMAT JSON) {}", query)),
+        "mysql" => Some(format!("EXPLAIN FORMAT=JSON {}", query)),
+        "sqlite" => Some(format!("EXPLAIN QUERY PLAN {}", query)),
+        "clickhouse" | "ch" => Some(format!("EXPLAIN {}", query)),
+        _ => None,
+    }
+}
+
+/// Plan for a slow query; failures are logged and leave the entry without a plan
+async fn capture_query_plan(source_type: &str, connection_config: &Value, query: &SlowQuery) -> Option<Value> {
+    let statement = explain_statement(source_type, &query.query)?;
+
+    let mut config = connection_config.clone();
+    if let Some(obj) = config.as_object_mut() {
+        obj.insert("id".to_string(), Value::String(query.datasource_id.clone()));
+    }
+    let connector = match create_connector(source_type, &config).await.map_err(|e| e.to_string()) {
+        Ok(connector) => connector,
+        Err(e) => {
+            tracing::warn!("Could not capture plan for slow query: {}", e);
+            return None;
+        }
+    };
+
+    match connector.execute_read_only_query(&statement, 1000).await {
+        Ok(result) => Some(result),
+        Err(e) => {
+            tracing::warn!("Could not capture plan for slow query: {}", e);
+            None
+        }
+    }
+}
+
+/// Logged slow queries for a datasource, slowest first by default
+pub async fn list_slow_queries(
+    db: &PgPool,
+    datasource_id: &str,
+    limit: i64,
+    order_by_duration: bool,
+) -> Result<Vec<SlowQueryEntry>> {
+    let order = if order_by_duration { "duration_ms DESC" } else { "created_at DESC" };
+    let rows = sqlx::query(&format!(
+        r#"
+        SELECT id, datasource_id, source, query, duration_ms, row_count, query_plan, created_at
+        FROM slow_query_log
+        WHERE datasource_id = $1
+        ORDER BY {}
+        LIMIT $2
+        "#,
+        order
+    ))
+    .bind(datasource_id)
+    .bind(limit)
+    .fetch_all(db)
+    .await?;
+
+    Ok(rows
+        .iter()
+        .map(|row| SlowQueryEntry {
+            id: row.get("id"),
+            datasource_id: row.get("datasource_id"),
+            source: row.get("source"),
+            query: row.get("query"),
+            duration_ms: row.get("duration_ms"),
+            row_count: row.get("row_count"),
+            query_plan: row.get("query_plan"),
+            created_at: row.get("created_at"),
+        })
+        .collect())
+}
+
+/// Remove every logged slow query for a datasource, returning how many were removed
+pub async fn clear_slow_queries(db: &PgPool, datasource_id: &str) -> Result<u64> {
+    let result = sqlx::query("DELETE FROM slow_query_log WHERE datasource_id = $1")
+        .bind(datasource_id)
+        .execute(db)
+        .await?;
+    Ok(result.rows_affected())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn threshold_zero_disables_the_log() {
+        let config = SlowQueryConfig { threshold: Duration::ZERO, capture_plan: false };
+        assert!(!config.is_slow(Duration::from_secs(60)));
+
+        let config = SlowQueryConfig::default();
+        assert!(config.is_slow(Duration::from_millis(1000)));
+        assert!(!config.is_slow(Duration::from_millis(999)));
+    }
+
+    #[test]
+    fn explain_only_for_read_queries_on_supported_dialects() {
+        assert_eq!(
+            explain_statement("postgresql", "SELECT * FROM orders;").as_deref(),
+            Some("EXPLAIN (FORMAT JSON) SELECT * FROM orders")
+        );
+        assert_eq!(
+            explain_statement("sqlite", "with t AS (SELECT 1) SELECT * FROM t").as_deref(),
+            Some("EXPLAIN QUERY PLAN with t AS (SELECT 1) SELECT * FROM t")
+        );
+        assert_eq!(explain_statement("postgresql", "DELETE FROM orders"), None);
+        assert_eq!(explain_statement("oracle", "SELECT 1 FROM dual"), None);
+    }
+
+    #[test]
+    fn row_count_falls_back_to_rows() {
+        assert_eq!(result_row_count(&json!({ "row_count": 42, "rows": [] })), Some(42));
+        assert_eq!(result_row_count(&json!({ "rows": [[1], [2]] })), Some(2));
+        assert_eq!(result_row_count(&json!({})), None);
+    }
+}
@@ -0,0 +1,171 @@
+//! JSON-RPC batch requests
+//!
+//! A batch is a JSON array of request objects; the reply is an array with one
+//! response per element, in the same order, leaving out notifications (requests
+//! without an id). Runs of consecutive read-only requests (listing tools and
+//! resources, reading a resource, and calling a cacheable tool) execute
+//! concurrently, a few at a time; anything else runs on its own, after
+//! everything before it has finished, so a write is never reordered around
+//! the requests next to it.
+
+use futures_util::stream::{self, StreamExt};
+use serde_json::Value;
+
+use super::dispatch_request;
+use super::handlers::tool_cache::is_cacheable;
+use super::handlers::McpHandlers;
+use super::types::*;
+
+/// Most requests one batch may carry
+pub const MAX_BATCH_SIZE: usize = 50;
+/// Most read-only requests of a batch running at once
+const MAX_CONCURRENT_REQUESTS: usize = 8;
+
+/// The elements of a batch, or `None` when `text` isn't a JSON array
+pub fn batch_items(text: &str) -> Option<Vec<Value>> {
+    if !text.trim_start().starts_with('[') {
+        return None;
+    }
+    match serde_json::from_str::<Value>(text) {
+        Ok(Value::Array(items)) => Some(items),
+        _ => None,
+    }
+}
+
+fn invalid_request(id: Option<Value>, message: String) -> JsonRpcResponse {
+    JsonRpcResponse {
+        jsonrpc: "2.0".to_string(),
+        id,
+        result: None,
+        error: Some(JsonRpcError {
+            code: INVALID_REQUEST,
+            message,
+            data: None,
+        }),
+    }
+}
+
+/// Tool name without the `mcp__<server>__` prefix Claude adds
+fn tool_name(request: &JsonRpcRequest) -> Option<&str> {
+    let name = request.params.as_ref()?.get("name")?.as_str()?;
+    match name.strip_prefix("mcp__").and_then(|rest| rest.split_once("__")) {
+        Some((_, tool)) => Some(tool),
+        None => Some(name),
+    }
+}
+
+/// Whether `request` can run alongside other read-only requests
+fn is_read_only(request: &JsonRpcRequest) -> bool {
+    match request.method.as_str() {
+        "tools/list" | "resources/list" | "resources/read" => true,
+        "tools/call" => tool_name(request).is_some_and(is_cacheable),
+        _ => false,
+    }
+}
+
+/// Run `request`; notifications get no response
+async fn respond(handlers: McpHandlers, request: JsonRpcRequest) -> Option<JsonRpcResponse> {
+    let is_notification = request.id.is_none();
+    let response = dispatch_request(handlers, request).await;
+    (!is_notification).then_some(response)
+}
+
+/// Run read-only requests with bounded concurrency, answering in request order
+async fn respond_concurrently(handlers: &McpHandlers, requests: Vec<JsonRpcRequest>) -> Vec<Option<JsonRpcResponse>> {
+    let mut responses: Vec<(usize, Option<JsonRpcResponse>)> = stream::iter(requests.into_iter().enumerate())
+        .map(|(index, request)| {
+            let handlers = handlers.clone();
+            async move { (index, respond(handlers, request).await) }
+        })
+        .buffer_unordered(MAX_CONCURRENT_REQUESTS)
+        .collect()
+        .await;
+    responses.sort_by_key(|(index, _)| *index);
+    responses.into_iter().map(|(_, response)| response).collect()
+}
+
+/// Responses to a batch, in request order, or `None` when it held only
+/// notifications. An empty or oversized batch is itself an invalid request
+/// and gets a single error object instead of an array.
+pub async fn dispatch_batch(handlers: &McpHandlers, items: Vec<Value>) -> Option<Value> {
+    if items.is_empty() {
+        return Some(
+            serde_json::to_value(invalid_request(None, "Invalid Request: empty batch".to_string()))
+                .unwrap_or(Value::Null),
+        );
+    }
+    if items.len() > MAX_BATCH_SIZE {
+        let message = format!(
+            "Invalid Request: batch of {} requests exceeds the limit of {}",
+            items.len(),
+            MAX_BATCH_SIZE
+        );
+        return Some(serde_json::to_value(invalid_request(None, message)).unwrap_or(Value::Null));
+    }
+
+    // Elements that aren't request objects are answered without running anything
+    let parsed: Vec<Result<JsonRpcRequest, Box<JsonRpcResponse>>> = items
+        .into_iter()
+        .map(|item| {
+            let id = item.get("id").cloned();
+            serde_json::from_value::<JsonRpcRequest>(item)
+                .map_err(|e| Box::new(invalid_request(id, format!("Invalid Request: {}", e))))
+        })
+        .collect();
+
+    let mut responses = Vec::with_capacity(parsed.len());
+    let mut concurrent = Vec::new();
+    for entry in parsed {
+        match entry {
+            Ok(request) if is_read_only(&request) => concurrent.push(request),
+            entry => {
+                responses.extend(respond_concurrently(handlers, std::mem::take(&mut concurrent)).await);
+                match entry {
+                    Ok(request) => responses.push(respond(handlers.clone(), request).await),
+                    Err(response) => responses.push(Some(*response)),
+                }
+            }
+        }
+    }
+    responses.extend(respond_concurrently(handlers, concurrent).await);
+
+    let responses: Vec<JsonRpcResponse> = responses.into_iter().flatten().collect();
+    if responses.is_empty() {
+        return None;
+    }
+    Some(serde_json::to_value(responses).unwrap_or(Value::Null))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn request(method: &str, params: Option<Value>) -> JsonRpcRequest {
+        JsonRpcRequest {
+            jsonrpc: "2.0".to_string(),
+            id: Some(json!(1)),
+            method: method.to_string(),
+            params,
+        }
+    }
+
+    #[test]
+    fn only_listing_and_cacheable_tools_run_concurrently() {
+        assert!(is_read_only(&request("tools/list", None)));
+        assert!(is_read_only(&request("resources/read", Some(json!({ "uri": "datasource://1" })))));
+        assert!(is_read_only(&request("tools/call", Some(json!({ "name": "schema_get" })))));
+        assert!(is_read_only(&request("tools/call", Some(json!({ "name": "mcp__operation__datasource_list" })))));
+        assert!(!is_read_only(&request("tools/call", Some(json!({ "name": "datasource_add" })))));
+        assert!(!is_read_only(&request("tools/call", None)));
+        assert!(!is_read_only(&request("initialize", None)));
+    }
+
+    #[test]
+    fn only_json_arrays_are_batches() {
+        assert_eq!(batch_items(r#" [{"jsonrpc":"2.0","method":"tools/list"}]"#).map(|items| items.len()), Some(1));
+        assert!(batch_items(r#"{"jsonrpc":"2.0","method":"tools/list"}"#).is_none());
+        // Left to the single-request parser, which reports the parse error
+        assert!(batch_items("[{").is_none());
+    }
+}
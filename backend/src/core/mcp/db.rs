@@ -0,0 +1,59 @@
+//! Database pool for MCP servers
+//!
+//! MCP servers often start alongside the backend, before Postgres accepts
+//! connections, so the pool is created with the backend's retry policy
+//! (`DB_CONNECT_*` variables) and jittered delays. `MCP_DB_CONNECT_MAX_ATTEMPTS`
+//! overrides the number of attempts for MCP servers only.
+
+use sqlx::PgPool;
+
+use super::logging::{mcp_log, LogFields, LogLevel};
+use crate::utils::db::RetryPolicy;
+
+pub fn retry_policy() -> RetryPolicy {
+    let mut policy = RetryPolicy::from_env();
+    if let Some(max_attempts) = std::env::var("MCP_DB_CONNECT_MAX_ATTEMPTS")
+        .ok()
+        .and_then(|v| v.parse::<u32>().ok())
+    {
+        policy.max_attempts = max_attempts.max(1);
+    }
+    policy
+}
+
+/// Connect to `database_url`, retrying with backoff until the policy's
+/// attempts are used up
+pub async fn connect_pool(database_url: &str, retry: &RetryPolicy) -> Result<PgPool, sqlx::Error> {
+    let mut attempt = 0;
+    loop {
+        attempt += 1;
+        mcp_log(
+            LogLevel::Info,
+            LogFields::new(format!("Database connection attempt {}/{}", attempt, retry.max_attempts)),
+        );
+
+        match PgPool::connect(database_url).await {
+            Ok(pool) => return Ok(pool),
+            Err(e) if attempt >= retry.max_attempts => {
+                mcp_log(
+                    LogLevel::Error,
+                    LogFields::new(format!("Database connection failed after {} attempts: {}", attempt, e)),
+                );
+                return Err(e);
+            }
+            Err(e) => {
+                let delay = retry.jittered_delay_after(attempt);
+                mcp_log(
+                    LogLevel::Warning,
+                    LogFields::new(format!(
+                        "Database connection attempt {} failed: {}; retrying in {}ms",
+                        attempt,
+                        e,
+                        delay.as_millis()
+                    )),
+                );
+                tokio::time::sleep(delay).await;
+            }
+        }
+    }
+}
@@ -0,0 +1,269 @@
+use super::base::McpHandlers;
+use crate::core::mcp::types::*;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::future::Future;
+use std::time::Duration;
+
+/// Postgres NOTIFY channel the backend forwards choice prompts from; the MCP
+/// server runs in its own process and can't reach the websocket connections
+pub const ASK_USER_CHANNEL: &str = "mcp_ask_user_choice";
+
+const DEFAULT_TIMEOUT_SECS: u64 = 120;
+const MAX_TIMEOUT_SECS: u64 = 600;
+const MAX_OPTIONS: usize = 20;
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// A multi-choice prompt for the user of a conversation
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ChoicePrompt {
+    pub interaction_id: String,
+    pub project_id: String,
+    pub conversation_id: String,
+    pub prompt: String,
+    pub options: Vec<String>,
+}
+
+/// The system message the websocket stores for an `ask_user_response`
+pub fn interaction_response_message(interaction_id: &str, response_json: &str) -> String {
+    format!("{}{}", interaction_response_prefix(interaction_id), response_json)
+}
+
+fn interaction_response_prefix(interaction_id: &str) -> String {
+    format!("User response to interaction {}:\n", interaction_id)
+}
+
+/// The response of a stored `interaction_response_message`
+fn parse_interaction_response(interaction_id: &str, content: &str) -> Option<Value> {
+    let json = content.strip_prefix(&interaction_response_prefix(interaction_id))?;
+    serde_json::from_str(json).ok()
+}
+
+/// Poll until `poll` returns a response or `timeout` passes
+pub async fn wait_for_response<F, Fut>(mut poll: F, timeout: Duration, interval: Duration) -> Option<Value>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Option<Value>>,
+{
+    let deadline = tokio::time::Instant::now() + timeout;
+    loop {
+        if let Some(response) = poll().await {
+            return Some(response);
+        }
+        if tokio::time::Instant::now() + interval > deadline {
+            return None;
+        }
+        tokio::time::sleep(interval).await;
+    }
+}
+
+/// The tool result for what the user answered: the option they picked, or the
+/// default (or nothing, cancelled) when they didn't answer in time
+fn choice_result(interaction_id: &str, options: &[String], default: Option<&str>, response: Option<Value>) -> Value {
+    let Some(response) = response else {
+        return json!({
+            "status": "timed_out",
+            "interaction_id": interaction_id,
+            "selected": default,
+            "cancelled": default.is_none(),
+            "message": match default {
+                Some(default) => format!("The user did not answer in time; using the default '{}'", default),
+                None => "The user did not answer in time; the question was cancelled".to_string(),
+            },
+        });
+    };
+
+    // Buttons answer with a string, checkbox-style prompts with an array
+    let answer = match &response {
+        Value::String(answer) => Some(answer.as_str()),
+        Value::Array(answers) => answers.first().and_then(|a| a.as_str()),
+        _ => None,
+    };
+    let selected = answer.and_then(|answer| {
+        options
+            .iter()
+            .find(|option| option.as_str() == answer)
+            .or_else(|| options.iter().find(|option| option.trim().eq_ignore_ascii_case(answer.trim())))
+    });
+
+    match selected {
+        Some(selected) => json!({
+            "status": "selected",
+            "interaction_id": interaction_id,
+            "selected": selected,
+            "cancelled": false,
+        }),
+        None => json!({
+            "status": "unrecognized",
+            "interaction_id": interaction_id,
+            "selected": null,
+            "cancelled": false,
+            "response": response,
+            "message": "The user's answer did not match any of the options",
+        }),
+    }
+}
+
+impl McpHandlers {
+    /// Show the user a prompt with option buttons and wait for their pick
+    pub async fn handle_ask_user_choice(
+        &self,
+        args: &serde_json::Map<String, Value>,
+    ) -> Result<String, JsonRpcError> {
+        let invalid = |message: &str| JsonRpcError {
+            code: INVALID_PARAMS,
+            message: message.to_string(),
+            data: Some(json!({
+                "correct_format_example": {
+                    "prompt": "Which chart should I build?",
+                    "options": ["Bar chart", "Line chart"],
+                    "default": "Bar chart",
+                    "timeout_seconds": 120
+                }
+            })),
+        };
+
+        let prompt = args
+            .get("prompt")
+            .and_then(|v| v.as_str())
+            .filter(|p| !p.trim().is_empty())
+            .ok_or_else(|| invalid("Missing required parameter: prompt"))?;
+        let options: Vec<String> = args
+            .get("options")
+            .and_then(|v| v.as_array())
+            .ok_or_else(|| invalid("Missing required parameter: options"))?
+            .iter()
+            .map(|o| o.as_str().map(str::to_string))
+            .collect::<Option<_>>()
+            .ok_or_else(|| invalid("Options must be strings"))?;
+        if options.is_empty() || options.len() > MAX_OPTIONS {
+            return Err(invalid(&format!("Provide between 1 and {} options", MAX_OPTIONS)));
+        }
+        if options.iter().any(|o| o.trim().is_empty()) {
+            return Err(invalid("Options cannot contain empty values"));
+        }
+        let default = args.get("default").and_then(|v| v.as_str());
+        if default.is_some_and(|d| !options.iter().any(|o| o == d)) {
+            return Err(invalid("default must be one of the options"));
+        }
+        let timeout = Duration::from_secs(
+            args.get("timeout_seconds")
+                .and_then(|v| v.as_u64())
+                .unwrap_or(DEFAULT_TIMEOUT_SECS)
+                .clamp(1, MAX_TIMEOUT_SECS),
+        );
+        let conversation_id = self
+            .conversation_id
+            .clone()
+            .ok_or_else(|| invalid("ask_user_choice can only be used within a conversation"))?;
+
+        let choice = ChoicePrompt {
+            interaction_id: uuid::Uuid::new_v4().to_string(),
+            project_id: self.project_id.clone(),
+            conversation_id,
+            prompt: prompt.to_string(),
+            options,
+        };
+        let payload = serde_json::to_string(&choice).map_err(|e| JsonRpcError {
+            code: INTERNAL_ERROR,
+            message: format!("Failed to serialize prompt: {}", e),
+            data: None,
+        })?;
+        sqlx::query("SELECT pg_notify($1, $2)")
+            .bind(ASK_USER_CHANNEL)
+            .bind(&payload)
+            .execute(&self.db_pool)
+            .await
+            .map_err(|e| JsonRpcError {
+                code: INTERNAL_ERROR,
+                message: format!("Failed to show the prompt: {}", e),
+                data: None,
+            })?;
+
+        let pattern = format!("{}%", interaction_response_prefix(&choice.interaction_id));
+        let response = wait_for_response(
+            || async {
+                let content: Option<String> = sqlx::query_scalar(
+                    "SELECT content FROM messages WHERE conversation_id = $1 AND role = 'system' \
+                     AND content LIKE $2 ORDER BY created_at DESC LIMIT 1",
+                )
+                .bind(&choice.conversation_id)
+                .bind(&pattern)
+                .fetch_optional(&self.db_pool)
+                .await
+                .unwrap_or_else(|e| {
+                    tracing::warn!("Failed to check for a response to {}: {}", choice.interaction_id, e);
+                    None
+                });
+                content.and_then(|c| parse_interaction_response(&choice.interaction_id, &c))
+            },
+            timeout,
+            POLL_INTERVAL,
+        )
+        .await;
+
+        let result = choice_result(&choice.interaction_id, &choice.options, default, response);
+        serde_json::to_string(&result).map_err(|e| JsonRpcError {
+            code: INTERNAL_ERROR,
+            message: format!("Failed to serialize response: {}", e),
+            data: None,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    fn options() -> Vec<String> {
+        vec!["Bar chart".to_string(), "Line chart".to_string()]
+    }
+
+    #[tokio::test]
+    async fn the_selected_option_is_returned_once_the_user_answers() {
+        let polls = AtomicUsize::new(0);
+        let stored = interaction_response_message("i-1", "\"Line chart\"");
+        let response = wait_for_response(
+            || async {
+                // The answer shows up on the third check
+                (polls.fetch_add(1, Ordering::SeqCst) >= 2)
+                    .then(|| parse_interaction_response("i-1", &stored))
+                    .flatten()
+            },
+            Duration::from_secs(5),
+            Duration::from_millis(1),
+        )
+        .await;
+        assert_eq!(polls.load(Ordering::SeqCst), 3);
+
+        let result = choice_result("i-1", &options(), None, response);
+        assert_eq!(result["status"], json!("selected"));
+        assert_eq!(result["selected"], json!("Line chart"));
+
+        // Another interaction's answer isn't picked up
+        assert_eq!(parse_interaction_response("i-2", &stored), None);
+        let checkbox = choice_result("i-1", &options(), None, Some(json!(["bar chart"])));
+        assert_eq!(checkbox["selected"], json!("Bar chart"));
+    }
+
+    #[tokio::test]
+    async fn an_unanswered_prompt_times_out_with_the_default() {
+        let response = wait_for_response(
+            || async { None },
+            Duration::from_millis(20),
+            Duration::from_millis(5),
+        )
+        .await;
+        assert_eq!(response, None);
+
+        let with_default = choice_result("i-1", &options(), Some("Bar chart"), response.clone());
+        assert_eq!(with_default["status"], json!("timed_out"));
+        assert_eq!(with_default["selected"], json!("Bar chart"));
+        assert_eq!(with_default["cancelled"], json!(false));
+
+        let cancelled = choice_result("i-1", &options(), None, response);
+        assert_eq!(cancelled["selected"], Value::Null);
+        assert_eq!(cancelled["cancelled"], json!(true));
+    }
+}
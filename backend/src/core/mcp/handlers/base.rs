@@ -1,13 +1,19 @@
 use crate::core::datasources::shared_service;
+use crate::core::mcp::logging::{mcp_log, LogFields, LogLevel};
+use crate::core::mcp::handlers::resource_limits::{datasource_resource_text, ResourceLimits};
 use crate::core::mcp::types::*;
 use crate::core::projects::manager::ProjectManager;
 use crate::utils::claude_md_template;
-use chrono::Utc;
+use crate::utils::datasource::common::connection_config::tag_connection_owner;
+use crate::utils::datasource::common::diagnostics::ConnectionFailure;
+use crate::utils::datasource::common::redaction::redact_connection_config;
+use crate::utils::datasource::common::error_handling::ConnectorError;
 use serde_json::{json, Value};
 use sqlx::{PgPool, Row};
 use uuid;
 
 // Import tools functionality
+use super::tool_cache;
 use super::tools;
 
 #[derive(Clone)]
@@ -18,6 +24,16 @@ pub struct McpHandlers {
     #[allow(dead_code)]
     pub server_type: String,
     pub db_pool: PgPool,
+    /// Conversation the calling Claude session belongs to, when known
+    pub conversation_id: Option<String>,
+    /// Identifies the Claude query (model turn) making the call, for per-turn caching
+    pub turn_id: Option<String>,
+}
+
+/// Whether a conversation restricted to `allowlist` may use `datasource_id`;
+/// `None` means no restriction
+pub fn datasource_allowed(allowlist: Option<&[String]>, datasource_id: &str) -> bool {
+    allowlist.is_none_or(|allowed| allowed.iter().any(|id| id == datasource_id))
 }
 
 impl McpHandlers {
@@ -54,6 +70,52 @@ impl McpHandlers {
         Ok(())
     }
 
+    /// Datasource ids the current conversation is restricted to.
+    /// None when there is no conversation context or no restriction set; a
+    /// conversation that isn't in this project is an error rather than
+    /// unrestricted.
+    pub async fn conversation_datasource_allowlist(&self) -> Result<Option<Vec<String>>, JsonRpcError> {
+        let Some(conversation_id) = self.conversation_id.as_deref() else {
+            return Ok(None);
+        };
+
+        let allowed: Option<Value> = sqlx::query_scalar::<_, Option<Value>>(
+            "SELECT allowed_datasource_ids FROM conversations WHERE id = $1 AND project_id = $2",
+        )
+        .bind(conversation_id)
+        .bind(&self.project_id)
+        .fetch_optional(&self.db_pool)
+        .await
+        .map_err(|e| JsonRpcError {
+            code: INTERNAL_ERROR,
+            message: format!("Database error: {}", e),
+            data: None,
+        })?
+        .ok_or_else(|| JsonRpcError {
+            code: INVALID_PARAMS,
+            message: format!("Conversation {} not found in this project", conversation_id),
+            data: None,
+        })?;
+
+        Ok(allowed.and_then(|value| serde_json::from_value::<Vec<String>>(value).ok()))
+    }
+
+    /// Reject access to a datasource outside the current conversation's allowlist
+    pub async fn ensure_datasource_allowed(&self, datasource_id: &str) -> Result<(), JsonRpcError> {
+        let allowlist = self.conversation_datasource_allowlist().await?;
+        if !datasource_allowed(allowlist.as_deref(), datasource_id) {
+            return Err(JsonRpcError {
+                code: INVALID_PARAMS,
+                message: format!(
+                    "Datasource {} is not available in this conversation",
+                    datasource_id
+                ),
+                data: None,
+            });
+        }
+        Ok(())
+    }
+
     /// Refresh CLAUDE.md with current datasource information
     #[allow(dead_code)]
     pub async fn refresh_claude_md(&self) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
@@ -116,21 +178,25 @@ impl McpHandlers {
         match f.await {
             Ok(result) => {
                 let duration = start_time.elapsed();
-                eprintln!(
-                    "[{}] [DEBUG] MCP operation '{}' completed successfully in {}ms",
-                    Utc::now().format("%Y-%m-%d %H:%M:%S UTC"),
-                    operation,
-                    duration.as_millis()
+                mcp_log(
+                    LogLevel::Debug,
+                    LogFields::for_project(&self.project_id).operation(operation).message(format!(
+                        "MCP operation '{}' completed successfully in {}ms",
+                        operation,
+                        duration.as_millis()
+                    )),
                 );
                 Ok(result)
             }
             Err(error) => {
                 let duration = start_time.elapsed();
-                eprintln!(
-                    "[{}] [ERROR] MCP operation '{}' failed after {}ms",
-                    Utc::now().format("%Y-%m-%d %H:%M:%S UTC"),
-                    operation,
-                    duration.as_millis()
+                mcp_log(
+                    LogLevel::Error,
+                    LogFields::for_project(&self.project_id).operation(operation).message(format!(
+                        "MCP operation '{}' failed after {}ms",
+                        operation,
+                        duration.as_millis()
+                    )),
                 );
                 Err(self.handle_mcp_error(operation, error))
             }
@@ -143,11 +209,13 @@ impl McpHandlers {
         operation: &str,
         error: Box<dyn std::error::Error + Send + Sync>,
     ) -> JsonRpcError {
-        eprintln!(
-            "[{}] [ERROR] MCP operation '{}' failed: {}",
-            Utc::now().format("%Y-%m-%d %H:%M:%S UTC"),
-            operation,
-            error
+        mcp_log(
+            LogLevel::Error,
+            LogFields::for_project(&self.project_id).operation(operation).message(format!(
+                "MCP operation '{}' failed: {}",
+                operation,
+                error
+            )),
         );
         JsonRpcError {
             code: INTERNAL_ERROR,
@@ -181,7 +249,8 @@ impl McpHandlers {
             data: None,
         })?;
 
-        let connection_config: Value = source.get("connection_config");
+        let mut connection_config: Value = source.get("connection_config");
+        tag_connection_owner(&mut connection_config, Some(&self.client_id), &self.project_id);
 
         Ok(DataSourceInfo {
             name: source.get("name"),
@@ -196,10 +265,12 @@ impl McpHandlers {
     }
 
     pub async fn handle_initialize(&self, params: Option<Value>) -> Result<Value, JsonRpcError> {
-        eprintln!(
-            "[{}] [INFO] Handling initialize request for project: {}",
-            Utc::now().format("%Y-%m-%d %H:%M:%S UTC"),
-            self.project_id
+        mcp_log(
+            LogLevel::Info,
+            LogFields::for_project(&self.project_id).operation("initialize").message(format!(
+                "Handling initialize request for project: {}",
+                self.project_id
+            )),
         );
         
         // Extract the protocol version from client request and echo it back
@@ -210,15 +281,17 @@ impl McpHandlers {
             .map(|s| s.to_string())
             .unwrap_or_else(|| "2025-06-18".to_string());
             
-        eprintln!(
-            "[{}] [INFO] Client requested protocol version: {}",
-            Utc::now().format("%Y-%m-%d %H:%M:%S UTC"),
-            client_protocol_version
+        mcp_log(
+            LogLevel::Info,
+            LogFields::for_project(&self.project_id).operation("initialize").message(format!(
+                "Client requested protocol version: {}",
+                client_protocol_version
+            )),
         );
         
-        eprintln!(
-            "[{}] [INFO] MCP Server fully initialized and ready for requests",
-            Utc::now().format("%Y-%m-%d %H:%M:%S UTC")
+        mcp_log(
+            LogLevel::Info,
+            LogFields::for_project(&self.project_id).operation("initialize").message("MCP Server fully initialized and ready for requests"),
         );
         
         // Get available tools to include in capabilities
@@ -229,11 +302,13 @@ impl McpHandlers {
             }).collect::<Vec<String>>()
         }).unwrap_or_default();
         
-        eprintln!(
-            "[{}] [DEBUG] Advertising {} tools in capabilities: {:?}",
-            Utc::now().format("%Y-%m-%d %H:%M:%S UTC"),
-            available_tools.len(),
-            available_tools
+        mcp_log(
+            LogLevel::Debug,
+            LogFields::for_project(&self.project_id).operation("initialize").message(format!(
+                "Advertising {} tools in capabilities: {:?}",
+                available_tools.len(),
+                available_tools
+            )),
         );
 
         let result = InitializeResult {
@@ -261,10 +336,12 @@ impl McpHandlers {
     }
 
     pub async fn handle_resources_list(&self, _params: Option<Value>) -> Result<Value, JsonRpcError> {
-        eprintln!(
-            "[{}] [INFO] Handling resources/list request for project: {}",
-            Utc::now().format("%Y-%m-%d %H:%M:%S UTC"),
-            self.project_id
+        mcp_log(
+            LogLevel::Info,
+            LogFields::for_project(&self.project_id).operation("resources/list").message(format!(
+                "Handling resources/list request for project: {}",
+                self.project_id
+            )),
         );
 
         let mut resources = vec![Resource {
@@ -287,8 +364,12 @@ impl McpHandlers {
             data: None,
         })?;
 
+        let allowlist = self.conversation_datasource_allowlist().await?;
         for datasource in datasources {
             let id: String = datasource.get("id");
+            if !datasource_allowed(allowlist.as_deref(), &id) {
+                continue;
+            }
             let name: String = datasource.get("name");
             let source_type: String = datasource.get("source_type");
             
@@ -321,31 +402,42 @@ impl McpHandlers {
                 data: None,
             })?;
 
-        eprintln!(
-            "[{}] [INFO] Handling resources/read request for URI: {}",
-            Utc::now().format("%Y-%m-%d %H:%M:%S UTC"),
-            uri
+        mcp_log(
+            LogLevel::Info,
+            LogFields::for_project(&self.project_id).operation("resources/read").message(format!(
+                "Handling resources/read request for URI: {}",
+                uri
+            )),
         );
 
+        let limits = ResourceLimits::from_env();
+        let timed_out = |_| JsonRpcError {
+            code: INTERNAL_ERROR,
+            message: format!("Reading {} timed out after {}s", uri, limits.timeout.as_secs()),
+            data: None,
+        };
+
         // Check if this is a CLAUDE.md request
         if uri == format!("claude://project/{}/claude.md", self.project_id) {
             // Get CLAUDE.md content for this project
-            let content = sqlx::query_scalar::<_, Option<String>>(
+            let query = sqlx::query_scalar::<_, Option<String>>(
                 "SELECT claude_md FROM projects WHERE id = $1",
             )
             .bind(&self.project_id)
-            .fetch_optional(&self.db_pool)
-            .await
-            .map_err(|e| JsonRpcError {
-                code: INTERNAL_ERROR,
-                message: format!("Database error: {}", e),
-                data: None,
-            })?
-            .flatten()
-            .unwrap_or_else(|| {
-                "# Clay Studio Project\n\nNo CLAUDE.md content available for this project."
-                    .to_string()
-            });
+            .fetch_optional(&self.db_pool);
+            let content = tokio::time::timeout(limits.timeout, query)
+                .await
+                .map_err(timed_out)?
+                .map_err(|e| JsonRpcError {
+                    code: INTERNAL_ERROR,
+                    message: format!("Database error: {}", e),
+                    data: None,
+                })?
+                .flatten()
+                .unwrap_or_else(|| {
+                    "# Clay Studio Project\n\nNo CLAUDE.md content available for this project."
+                        .to_string()
+                });
 
             Ok(json!({
                 "contents": [
@@ -359,50 +451,43 @@ impl McpHandlers {
         } else if uri.starts_with("datasource://") {
             // Handle datasource resource request
             let datasource_id = uri.strip_prefix("datasource://").unwrap_or("");
-            
+            self.ensure_datasource_allowed(datasource_id).await?;
+
             // Get datasource information
-            let datasource = sqlx::query(
-                "SELECT id, name, source_type, config, schema_info FROM data_sources WHERE id = $1 AND project_id = $2 AND deleted_at IS NULL"
+            let query = sqlx::query(
+                "SELECT id, name, source_type, schema_info, table_list FROM data_sources WHERE id = $1 AND project_id = $2 AND deleted_at IS NULL"
             )
             .bind(datasource_id)
             .bind(&self.project_id)
-            .fetch_optional(&self.db_pool)
-            .await
-            .map_err(|e| JsonRpcError {
-                code: INTERNAL_ERROR,
-                message: format!("Database error: {}", e),
-                data: None,
-            })?
-            .ok_or_else(|| JsonRpcError {
-                code: INVALID_PARAMS,
-                message: format!("Datasource not found: {}", datasource_id),
-                data: None,
-            })?;
+            .fetch_optional(&self.db_pool);
+            let datasource = tokio::time::timeout(limits.timeout, query)
+                .await
+                .map_err(timed_out)?
+                .map_err(|e| JsonRpcError {
+                    code: INTERNAL_ERROR,
+                    message: format!("Database error: {}", e),
+                    data: None,
+                })?
+                .ok_or_else(|| JsonRpcError {
+                    code: INVALID_PARAMS,
+                    message: format!("Datasource not found: {}", datasource_id),
+                    data: None,
+                })?;
 
             let name: String = datasource.get("name");
             let source_type: String = datasource.get("source_type");
-            let schema_info: Option<String> = datasource.get("schema_info");
-
-            let mut content = json!({
-                "id": datasource_id,
-                "name": name,
-                "source_type": source_type,
-                "schema_info": schema_info
-            });
+            let schema_info: Option<Value> = datasource.get("schema_info");
+            let table_list: Option<Value> = datasource.get("table_list");
 
-            // Add schema information if available
-            if let Some(schema) = schema_info {
-                if let Ok(schema_json) = serde_json::from_str::<Value>(&schema) {
-                    content["schema"] = schema_json;
-                }
-            }
+            // Large schemas are summarized so they don't flood the model's context
+            let text = datasource_resource_text(datasource_id, &name, &source_type, schema_info, table_list, &limits);
 
             Ok(json!({
                 "contents": [
                     {
                         "uri": uri,
                         "mimeType": "application/json",
-                        "text": serde_json::to_string_pretty(&content).unwrap_or_else(|_| content.to_string())
+                        "text": text
                     }
                 ]
             }))
@@ -464,13 +549,45 @@ impl McpHandlers {
 
         let arguments = params.get("arguments");
 
-        eprintln!(
-            "[{}] [INFO] Handling tools/call request for tool: {} (cleaned: {})",
-            Utc::now().format("%Y-%m-%d %H:%M:%S UTC"),
-            tool_name,
-            clean_tool_name
+        mcp_log(
+            LogLevel::Info,
+            LogFields::for_project(&self.project_id).operation(clean_tool_name).message(format!(
+                "Handling tools/call request for tool: {} (cleaned: {})",
+                tool_name,
+                clean_tool_name
+            )),
         );
 
+        crate::core::mcp::progress::report_progress("started", format!("Running {}", clean_tool_name));
+
+        // Conversation-scoped datasource restrictions apply to every tool that
+        // names a datasource, including each source of a federated query
+        if let Some(args) = arguments {
+            let mut datasource_ids: Vec<&str> = args
+                .get("datasource_id")
+                .and_then(|v| v.as_str())
+                .into_iter()
+                .collect();
+            if let Some(sources) = args.get("sources").and_then(|v| v.as_array()) {
+                datasource_ids.extend(
+                    sources
+                        .iter()
+                        .filter_map(|source| source.get("datasource_id").and_then(|v| v.as_str())),
+                );
+            }
+            for datasource_id in datasource_ids {
+                self.ensure_datasource_allowed(datasource_id).await?;
+            }
+        }
+
+        // Any tool that may change project state, whichever server it belongs
+        // to, clears the read-only results cached for this turn
+        if tool_cache::is_write_tool(clean_tool_name) {
+            if let Some(turn_key) = tool_cache::turn_key(self.conversation_id.as_deref(), self.turn_id.as_deref()) {
+                tool_cache::invalidate(&turn_key);
+            }
+        }
+
         // Route to appropriate tool handler based on tool name
         match clean_tool_name {
             name if tools::analysis::is_analysis_tool(name) => {
@@ -504,9 +621,24 @@ impl McpHandlers {
                 data: None,
             })?;
 
+        // A `tables` filter or `max_tables` cap introspects just that subset live,
+        // which stays fast on databases too large for a full fetch
+        let scope = super::datasource::InspectionScope::from_args(arguments).map_err(|e| JsonRpcError {
+            code: INVALID_PARAMS,
+            message: e,
+            data: None,
+        })?;
+        if scope.is_some() {
+            return self.inspect_datasource(arguments).await;
+        }
+        let force_refresh = arguments
+            .get("force_refresh")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
+
         // Get datasource details
         let row = sqlx::query(
-            "SELECT name, source_type, connection_config, schema_info FROM data_sources 
+            "SELECT name, source_type, connection_config, schema_info, schema_fetched_at FROM data_sources
              WHERE id = $1 AND project_id = $2 AND deleted_at IS NULL"
         )
         .bind(datasource_id)
@@ -523,16 +655,27 @@ impl McpHandlers {
             let name: String = row.get("name");
             let source_type: String = row.get("source_type");
             let existing_schema: Option<Value> = row.get("schema_info");
+            let fetched_at: Option<chrono::DateTime<chrono::Utc>> = row.get("schema_fetched_at");
+
+            // Missing, expired or force-refreshed schemas are inspected live and stored again
+            let now = chrono::Utc::now();
+            let ttl = super::datasource::schema_cache_ttl_from_env();
+            if !super::datasource::schema_cache_is_fresh(existing_schema.as_ref(), fetched_at, now, ttl, force_refresh) {
+                return self.inspect_datasource(arguments).await;
+            }
 
-            // For now, return the existing schema info
-            // In the future, we can implement schema refresh logic
             let result = json!({
                 "status": "success",
                 "datasource_id": datasource_id,
                 "name": name,
                 "source_type": source_type,
                 "schema": existing_schema,
-                "message": "Schema inspection returns cached schema. Live refresh not yet implemented."
+                "cache": {
+                    "schema_fetched_at": fetched_at.map(|t| t.to_rfc3339()),
+                    "age_secs": fetched_at.map(|t| (now - t).num_seconds()),
+                    "ttl_secs": ttl.num_seconds()
+                },
+                "message": "Schema inspection returns cached schema. Pass `force_refresh: true` to inspect the database again, or `tables`/`max_tables` to inspect a subset of tables live."
             });
             Ok(serde_json::to_string(&result).unwrap_or_else(|_| "{}".to_string()))
         } else {
@@ -774,7 +917,7 @@ impl McpHandlers {
             "count": results.len()
         });
         
-        Ok(serde_json::to_string(&result).unwrap())
+        Ok(serde_json::to_string(&result).unwrap_or_else(|_| "{}".to_string()))
     }
 
     pub async fn handle_schema_related(
@@ -810,7 +953,7 @@ impl McpHandlers {
             "message": "Relationship detection requires database-specific implementation"
         });
         
-        Ok(serde_json::to_string(&result).unwrap())
+        Ok(serde_json::to_string(&result).unwrap_or_else(|_| "{}".to_string()))
     }
 
     pub async fn handle_schema_stats(
@@ -884,7 +1027,7 @@ impl McpHandlers {
                 }
             });
             
-            Ok(serde_json::to_string(&result).unwrap())
+            Ok(serde_json::to_string(&result).unwrap_or_else(|_| "{}".to_string()))
         } else {
             Err(JsonRpcError {
                 code: INVALID_PARAMS,
@@ -945,9 +1088,14 @@ impl McpHandlers {
                 data: None,
             })?;
 
+        let allowlist = self.conversation_datasource_allowlist().await?;
+
         let mut datasource_list = Vec::new();
         for row in datasources {
             let id: String = row.get("id");
+            if !datasource_allowed(allowlist.as_deref(), &id) {
+                continue;
+            }
             let name: String = row.get("name");
             let source_type: String = row.get("source_type");
             let is_active: bool = row.get("is_active");
@@ -992,6 +1140,26 @@ impl McpHandlers {
                 data: None,
             })?;
 
+        // allow_writes can only be changed by users, so carry over the stored value
+        let config = match arguments.get("config") {
+            Some(config) => {
+                let existing: Option<Value> = sqlx::query_scalar(
+                    "SELECT connection_config FROM data_sources WHERE id = $1 AND project_id = $2 AND deleted_at IS NULL"
+                )
+                .bind(datasource_id)
+                .bind(&self.project_id)
+                .fetch_optional(&self.db_pool)
+                .await
+                .map_err(|e| JsonRpcError {
+                    code: INTERNAL_ERROR,
+                    message: format!("Database error: {}", e),
+                    data: None,
+                })?;
+                Some(super::datasource::keep_allow_writes(config, &existing.unwrap_or(Value::Null)))
+            }
+            None => None,
+        };
+
         // Build update query dynamically based on provided fields
         let mut update_fields = Vec::new();
         let mut param_count = 3; // Starting at $3 (after id and project_id)
@@ -1044,7 +1212,7 @@ impl McpHandlers {
             query = query.bind(source_type);
         }
 
-        if let Some(config) = arguments.get("config") {
+        if let Some(config) = &config {
             query = query.bind(config);
         }
 
@@ -1104,8 +1272,7 @@ impl McpHandlers {
                 let mut connection_config: Value = row.get("connection_config");
                 
                 // Add datasource ID to config (needed by connectors)
-                if connection_config.is_object() {
-                    let config_obj = connection_config.as_object_mut().unwrap();
+                if let Some(config_obj) = connection_config.as_object_mut() {
                     config_obj.insert("id".to_string(), Value::String(datasource_id.to_string()));
                 }
 
@@ -1118,9 +1285,13 @@ impl McpHandlers {
                         Ok(serde_json::to_string(&result).unwrap_or_else(|_| "{}".to_string()))
                     },
                     Err(e) => {
+                        let failure = ConnectionFailure::from_error(&source_type, &connection_config, &*e);
                         let result = json!({
                             "success": false,
-                            "message": format!("Connection test failed: {}", e)
+                            "message": format!("Connection test failed: {}", failure.diagnosis.hint),
+                            "category": failure.diagnosis.category,
+                            "field": failure.diagnosis.field,
+                            "details": failure.raw
                         });
                         Ok(serde_json::to_string(&result).unwrap_or_else(|_| "{}".to_string()))
                     }
@@ -1160,9 +1331,13 @@ impl McpHandlers {
                     Ok(serde_json::to_string(&result).unwrap_or_else(|_| "{}".to_string()))
                 },
                 Err(e) => {
+                    let failure = ConnectionFailure::from_error(source_type, config, &*e);
                     let result = json!({
                         "success": false,
-                        "message": format!("Connection test failed: {}", e)
+                        "message": format!("Connection test failed: {}", failure.diagnosis.hint),
+                        "category": failure.diagnosis.category,
+                        "field": failure.diagnosis.field,
+                        "details": failure.raw
                     });
                     Ok(serde_json::to_string(&result).unwrap_or_else(|_| "{}".to_string()))
                 }
@@ -1217,7 +1392,7 @@ impl McpHandlers {
                 "is_active": is_active,
                 "created_at": created_at.to_rfc3339(),
                 "updated_at": updated_at.map(|dt| dt.to_rfc3339()),
-                "connection_details": connection_config,
+                "connection_details": redact_connection_config(&connection_config),
                 "schema_info": schema_info
             });
 
@@ -1237,6 +1412,13 @@ impl McpHandlers {
     ) -> Result<String, JsonRpcError> {
         self.query_datasource(arguments).await
     }
+
+    pub async fn handle_data_query_write(
+        &self,
+        arguments: &serde_json::Map<String, serde_json::Value>
+    ) -> Result<String, JsonRpcError> {
+        self.write_datasource(arguments).await
+    }
     
     /// Execute a query using connection pooling
     /// This method provides an easy way for MCP handlers to use the global connection pool
@@ -1255,7 +1437,7 @@ impl McpHandlers {
         ).await
         .map_err(|e| JsonRpcError {
             code: INTERNAL_ERROR,
-            message: format!("Failed to execute query: {}", e),
+            message: format!("Failed to execute query: {}", ConnectorError::from_error(&*e)),
             data: None,
         })
     }
@@ -1395,10 +1577,11 @@ impl McpHandlers {
                 }
             })
         } else {
-            // For binary files, read from filesystem
-            use std::fs;
-            match fs::read_to_string(&file.file_path) {
-                Ok(content) => json!({
+            // For binary files, read from storage
+            let storage = crate::utils::storage::storage();
+            let stored = storage.get(&storage.key_from_location(&file.file_path)).await;
+            match stored.ok().and_then(|data| String::from_utf8(data).ok()) {
+                Some(content) => json!({
                     "status": "success",
                     "message": "File content retrieved from filesystem",
                     "file": {
@@ -1413,7 +1596,7 @@ impl McpHandlers {
                         "created_at": file.created_at
                     }
                 }),
-                Err(_) => json!({
+                None => json!({
                     "status": "error",
                     "message": "File is binary or cannot be read as text",
                     "file": {
@@ -1607,3 +1790,114 @@ impl McpHandlers {
             })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn allowlists_restrict_datasources_only_when_set() {
+        let allowed = vec!["ds-orders".to_string()];
+        assert!(datasource_allowed(Some(allowed.as_slice()), "ds-orders"));
+        assert!(!datasource_allowed(Some(allowed.as_slice()), "ds-payroll"));
+        assert!(!datasource_allowed(Some(&[]), "ds-orders"));
+        assert!(datasource_allowed(None, "ds-payroll"));
+    }
+
+    #[tokio::test]
+    #[ignore = "needs a migrated Clay Studio database in TEST_DATABASE_URL"]
+    async fn resources_are_filtered_by_the_conversation_allowlist() {
+        let url = std::env::var("TEST_DATABASE_URL").expect("Set TEST_DATABASE_URL");
+        let db_pool = PgPool::connect(&url).await.unwrap();
+
+        let suffix = uuid::Uuid::new_v4();
+        let project_id = format!("mcp-allowlist-{}", suffix);
+        let conversation_id = format!("mcp-allowlist-conv-{}", suffix);
+        let (orders, payroll) = (format!("ds-orders-{}", suffix), format!("ds-payroll-{}", suffix));
+        let (client_id, owner_id) = (uuid::Uuid::new_v4(), uuid::Uuid::new_v4());
+        sqlx::query(
+            "INSERT INTO clients (id, name, description, status, install_path, config, created_at, updated_at)
+             VALUES ($1, 'mcp-allowlist-test', NULL, 'active', '', '{}'::jsonb, NOW(), NOW())",
+        )
+        .bind(client_id)
+        .execute(&db_pool)
+        .await
+        .unwrap();
+        sqlx::query(
+            "INSERT INTO users (id, client_id, username, password, role, created_at, updated_at)
+             VALUES ($1, $2, $3, 'unused', 'user', NOW(), NOW())",
+        )
+        .bind(owner_id)
+        .bind(client_id)
+        .bind(format!("mcp-allowlist-{}", owner_id))
+        .execute(&db_pool)
+        .await
+        .unwrap();
+        sqlx::query("INSERT INTO projects (id, name, client_id, user_id) VALUES ($1, 'Allowlist', $2, $3)")
+            .bind(&project_id)
+            .bind(client_id)
+            .bind(owner_id)
+            .execute(&db_pool)
+            .await
+            .unwrap();
+        for (id, name) in [(&orders, "Orders"), (&payroll, "Payroll")] {
+            sqlx::query(
+                "INSERT INTO data_sources (id, project_id, name, source_type, connection_config, created_at, updated_at)
+                 VALUES ($1, $2, $3, 'postgresql', '{}'::jsonb, NOW(), NOW())",
+            )
+            .bind(id)
+            .bind(&project_id)
+            .bind(name)
+            .execute(&db_pool)
+            .await
+            .unwrap();
+        }
+        sqlx::query("INSERT INTO conversations (id, project_id, allowed_datasource_ids) VALUES ($1, $2, $3)")
+            .bind(&conversation_id)
+            .bind(&project_id)
+            .bind(json!([orders]))
+            .execute(&db_pool)
+            .await
+            .unwrap();
+
+        let handlers = |conversation_id: Option<&str>| McpHandlers {
+            project_id: project_id.clone(),
+            client_id: client_id.to_string(),
+            server_type: "operation".to_string(),
+            db_pool: db_pool.clone(),
+            conversation_id: conversation_id.map(str::to_string),
+            turn_id: None,
+        };
+        let restricted = handlers(Some(&conversation_id));
+        let listed = restricted.handle_resources_list(None).await.unwrap();
+        let read_allowed = restricted
+            .handle_resources_read(Some(json!({ "uri": format!("datasource://{}", orders) })))
+            .await;
+        let read_denied = restricted
+            .handle_resources_read(Some(json!({ "uri": format!("datasource://{}", payroll) })))
+            .await;
+        let unknown_conversation = handlers(Some("no-such-conversation")).handle_resources_list(None).await;
+
+        for cleanup in [
+            "DELETE FROM conversations WHERE project_id = $1",
+            "DELETE FROM data_sources WHERE project_id = $1",
+            "DELETE FROM projects WHERE id = $1",
+        ] {
+            sqlx::query(cleanup).bind(&project_id).execute(&db_pool).await.unwrap();
+        }
+        sqlx::query("DELETE FROM users WHERE id = $1").bind(owner_id).execute(&db_pool).await.unwrap();
+        sqlx::query("DELETE FROM clients WHERE id = $1").bind(client_id).execute(&db_pool).await.unwrap();
+
+        let uris: Vec<&str> = listed["resources"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .filter_map(|resource| resource["uri"].as_str())
+            .collect();
+        assert!(uris.contains(&format!("datasource://{}", orders).as_str()));
+        assert!(!uris.contains(&format!("datasource://{}", payroll).as_str()));
+        assert!(read_allowed.is_ok());
+        assert!(read_denied.unwrap_err().message.contains("not available in this conversation"));
+        assert!(unknown_conversation.is_err());
+    }
+}
@@ -0,0 +1,447 @@
+use super::McpHandlers;
+use crate::core::datasources::schema_events::notify_schema_refreshed;
+use crate::core::datasources::shared_service;
+use crate::core::mcp::progress::report_progress;
+use crate::core::mcp::types::*;
+use crate::utils::datasource::create_connector;
+use crate::utils::datasource::core::base::DataSourceConnector;
+use crate::utils::datasource::common::schema_shape::find_table_entry;
+use serde_json::{json, Value};
+
+impl McpHandlers {
+    #[allow(dead_code)]
+    pub async fn inspect_datasource(
+        &self,
+        args: &serde_json::Map<String, Value>,
+    ) -> Result<String, JsonRpcError> {
+        self.execute_db_operation("inspect_datasource", async {
+            let datasource_id = args
+                .get("datasource_id")
+                .and_then(|v| v.as_str())
+                .ok_or_else(|| "Missing required parameter: datasource_id".to_string())?;
+
+            match InspectionScope::from_args(args)? {
+                Some(scope) => self.inspect_datasource_partial(datasource_id, &scope).await,
+                None => self.inspect_datasource_internal(datasource_id).await,
+            }
+        })
+        .await
+    }
+
+    async fn inspection_connector(
+        &self,
+        datasource_id: &str,
+    ) -> Result<
+        (shared_service::SharedDatasourceInfo, Box<dyn DataSourceConnector>),
+        Box<dyn std::error::Error + Send + Sync>,
+    > {
+        // Get datasource info using shared service (with caching)
+        report_progress("connecting", format!("Connecting to datasource {}", datasource_id));
+        let datasource = shared_service::get_datasource_with_validation(
+            datasource_id,
+            &self.project_id,
+            &self.db_pool
+        ).await.map_err(|e| format!("Failed to get datasource: {}", e))?;
+        
+        // Add datasource ID to config for pooling support
+        let mut config_with_id = datasource.connection_config.clone();
+        if let Some(config_obj) = config_with_id.as_object_mut() {
+            config_obj.insert("id".to_string(), Value::String(datasource_id.to_string()));
+        }
+        
+        // Create connector using the same mechanism as datasource_query
+        // This ensures we use pooling where available
+        let connector = create_connector(&datasource.source_type, &config_with_id)
+            .await
+            .map_err(|e| -> Box<dyn std::error::Error + Send + Sync> {
+                Box::new(std::io::Error::other(format!("Failed to create connector: {}", e)))
+            })?;
+
+        Ok((datasource, connector))
+    }
+
+    #[allow(dead_code)]
+    pub async fn inspect_datasource_internal(
+        &self,
+        datasource_id: &str,
+    ) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
+        let (datasource, connector) = self.inspection_connector(datasource_id).await?;
+
+        // Run inspection
+        report_progress("executing", format!("Analyzing schema of {}", datasource.name));
+        let analysis = connector.analyze_database().await.map_err(
+            |e| -> Box<dyn std::error::Error + Send + Sync> {
+                Box::new(std::io::Error::other(format!("Database analysis failed: {}", e)))
+            },
+        )?;
+
+        // Store schema info in database for future reference; schema_fetched_at
+        // starts the cache TTL used by datasource_inspect, and the replaced
+        // snapshot is kept for schema_diff
+        let fetched_at = chrono::Utc::now();
+        sqlx::query("UPDATE data_sources SET schema_info_previous = schema_info, schema_info = $1, schema_fetched_at = $2, updated_at = NOW() WHERE id = $3")
+            .bind(&analysis)
+            .bind(fetched_at)
+            .bind(datasource_id)
+            .execute(&self.db_pool)
+            .await?;
+        notify_schema_refreshed(&self.db_pool, datasource_id).await;
+
+        // Return JSON response instead of formatted text
+        let response_data = json!({
+            "datasource": {
+                "id": datasource_id,
+                "name": datasource.name
+            },
+            "analysis": analysis,
+            "message": "Database inspection completed successfully",
+            "metadata": {
+                "schema_cached": true,
+                "schema_fetched_at": fetched_at.to_rfc3339(),
+                "using_connection_pool": true
+            }
+        });
+        Ok(serde_json::to_string(&response_data)?)
+    }
+
+    /// Introspect only the tables selected by `scope`, merging them into whatever
+    /// schema is already stored so repeated calls extend the picture
+    pub async fn inspect_datasource_partial(
+        &self,
+        datasource_id: &str,
+        scope: &InspectionScope,
+    ) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
+        let (datasource, connector) = self.inspection_connector(datasource_id).await?;
+
+        let all_tables = connector.list_tables().await.map_err(
+            |e| -> Box<dyn std::error::Error + Send + Sync> {
+                Box::new(std::io::Error::other(format!("Failed to list tables: {}", e)))
+            },
+        )?;
+        let selected = scope.select(&all_tables);
+        if selected.is_empty() {
+            return Err(format!(
+                "No tables match {:?}. Use schema_search or datasource_detail to find table names.",
+                scope.patterns
+            )
+            .into());
+        }
+
+        let inspected = connector
+            .get_tables_schema(selected.clone())
+            .await
+            .map_err(|e| -> Box<dyn std::error::Error + Send + Sync> {
+                Box::new(std::io::Error::other(format!("Table inspection failed: {}", e)))
+            })?;
+
+        let existing: Option<Value> =
+            sqlx::query_scalar("SELECT schema_info FROM data_sources WHERE id = $1")
+                .bind(datasource_id)
+                .fetch_optional(&self.db_pool)
+                .await?
+                .flatten();
+        let schema_info = merge_partial_schema(existing, &inspected, &selected, &all_tables);
+
+        sqlx::query("UPDATE data_sources SET schema_info_previous = schema_info, schema_info = $1, updated_at = NOW() WHERE id = $2")
+            .bind(&schema_info)
+            .bind(datasource_id)
+            .execute(&self.db_pool)
+            .await?;
+        notify_schema_refreshed(&self.db_pool, datasource_id).await;
+
+        let inspected_count = schema_info["tables"].as_object().map(|t| t.len()).unwrap_or(0);
+        let response_data = json!({
+            "datasource": {
+                "id": datasource_id,
+                "name": datasource.name
+            },
+            "analysis": {
+                "partial": schema_info["partial"],
+                "tables": inspected,
+                "total_tables": all_tables.len(),
+                "inspected_tables": inspected_count,
+            },
+            "message": format!(
+                "Inspected {} table(s) in this call; {} of {} tables are now in the stored schema. Call datasource_inspect with other `tables` to inspect more.",
+                selected.len(),
+                inspected_count,
+                all_tables.len()
+            ),
+            "metadata": {
+                "schema_cached": true,
+                "using_connection_pool": true
+            }
+        });
+        Ok(serde_json::to_string(&response_data)?)
+    }
+}
+
+const DEFAULT_SCHEMA_CACHE_TTL_SECS: i64 = 3600;
+
+/// How long `datasource_inspect` serves a stored schema before fetching it again,
+/// from `SCHEMA_CACHE_TTL_SECS`
+pub fn schema_cache_ttl_from_env() -> chrono::Duration {
+    let secs = std::env::var("SCHEMA_CACHE_TTL_SECS")
+        .ok()
+        .and_then(|v| v.parse::<i64>().ok())
+        .filter(|v| *v >= 0)
+        .unwrap_or(DEFAULT_SCHEMA_CACHE_TTL_SECS);
+    chrono::Duration::seconds(secs)
+}
+
+/// Whether a stored schema can be served instead of inspecting the database again
+///
+/// Schemas stored before `schema_fetched_at` existed count as stale. Partial
+/// schemas are built up table by table, so only `force_refresh` replaces them
+/// with a full inspection.
+pub fn schema_cache_is_fresh(
+    schema: Option<&Value>,
+    fetched_at: Option<chrono::DateTime<chrono::Utc>>,
+    now: chrono::DateTime<chrono::Utc>,
+    ttl: chrono::Duration,
+    force_refresh: bool,
+) -> bool {
+    if force_refresh {
+        return false;
+    }
+    let Some(schema) = schema.filter(|s| !s.is_null()) else {
+        return false;
+    };
+    if schema.get("partial").and_then(|p| p.as_bool()) == Some(true) {
+        return true;
+    }
+    fetched_at.is_some_and(|fetched_at| now - fetched_at < ttl)
+}
+
+/// Subset of tables a partial inspection covers: names or `*` patterns, capped
+/// at `max_tables`
+#[derive(Debug, Clone, Default)]
+pub struct InspectionScope {
+    pub patterns: Vec<String>,
+    pub max_tables: Option<usize>,
+}
+
+impl InspectionScope {
+    /// `None` when neither `tables` nor `max_tables` is given, i.e. a full inspection
+    pub fn from_args(args: &serde_json::Map<String, Value>) -> Result<Option<Self>, String> {
+        let patterns = match args.get("tables") {
+            None | Some(Value::Null) => Vec::new(),
+            Some(Value::String(s)) => s
+                .split(',')
+                .map(|p| p.trim().to_string())
+                .filter(|p| !p.is_empty())
+                .collect(),
+            Some(Value::Array(items)) => items
+                .iter()
+                .filter_map(|v| v.as_str())
+                .map(|p| p.trim().to_string())
+                .filter(|p| !p.is_empty())
+                .collect(),
+            Some(_) => return Err("tables must be an array of table names or patterns".to_string()),
+        };
+
+        let max_tables = match args.get("max_tables") {
+            None | Some(Value::Null) => None,
+            Some(v) => match v.as_u64() {
+                Some(n) if n > 0 => Some(n as usize),
+                _ => return Err("max_tables must be a positive integer".to_string()),
+            },
+        };
+
+        if patterns.is_empty() && max_tables.is_none() {
+            return Ok(None);
+        }
+        Ok(Some(Self { patterns, max_tables }))
+    }
+
+    pub fn select<'a>(&self, tables: &'a [String]) -> Vec<&'a str> {
+        tables
+            .iter()
+            .filter(|table| {
+                self.patterns.is_empty()
+                    || self.patterns.iter().any(|p| table_matches_pattern(p, table))
+            })
+            .take(self.max_tables.unwrap_or(usize::MAX))
+            .map(|table| table.as_str())
+            .collect()
+    }
+}
+
+/// Case-insensitive match where `*` (or SQL's `%`) stands for any run of characters
+fn table_matches_pattern(pattern: &str, table: &str) -> bool {
+    let pattern = pattern.to_lowercase().replace('%', "*");
+    let table = table.to_lowercase();
+    let parts: Vec<&str> = pattern.split('*').collect();
+    if parts.len() == 1 {
+        return pattern == table;
+    }
+
+    let mut rest = table.as_str();
+    for (i, part) in parts.iter().enumerate() {
+        if i == 0 {
+            match rest.strip_prefix(part) {
+                Some(r) => rest = r,
+                None => return false,
+            }
+        } else if i == parts.len() - 1 {
+            return rest.ends_with(part);
+        } else {
+            match rest.find(part) {
+                Some(pos) => rest = &rest[pos + part.len()..],
+                None => return false,
+            }
+        }
+    }
+    true
+}
+
+/// Fold freshly inspected tables into the stored schema. The result stays marked
+/// `partial` until it covers every table, unless a full analysis was stored already.
+fn merge_partial_schema(
+    existing: Option<Value>,
+    inspected: &Value,
+    selected: &[&str],
+    all_tables: &[String],
+) -> Value {
+    let mut schema = match existing {
+        Some(Value::Object(obj)) => Value::Object(obj),
+        _ => json!({}),
+    };
+    let was_full = schema.as_object().is_some_and(|obj| !obj.is_empty())
+        && schema.get("partial").and_then(|p| p.as_bool()) != Some(true);
+
+    if !schema.get("tables").is_some_and(|t| t.is_object()) {
+        schema["tables"] = json!({});
+    }
+    if let Some(tables) = schema["tables"].as_object_mut() {
+        for name in selected {
+            if let Some(structure) = find_table_entry(inspected, name) {
+                tables.insert(name.to_string(), structure.clone());
+            }
+        }
+    }
+
+    let inspected_count = schema["tables"].as_object().map(|t| t.len()).unwrap_or(0);
+    schema["partial"] = json!(!was_full && inspected_count < all_tables.len());
+    schema["table_names"] = json!(all_tables);
+    schema["total_tables"] = json!(all_tables.len());
+    schema["analyzed_at"] = json!(chrono::Utc::now().to_rfc3339());
+    schema
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(value: Value) -> serde_json::Map<String, Value> {
+        value.as_object().cloned().unwrap()
+    }
+
+    fn names(tables: &[&str]) -> Vec<String> {
+        tables.iter().map(|t| t.to_string()).collect()
+    }
+
+    #[test]
+    fn schema_cache_expires_after_ttl() {
+        let now = chrono::Utc::now();
+        let ttl = chrono::Duration::hours(1);
+        let schema = json!({"tables": {"orders": []}});
+
+        assert!(schema_cache_is_fresh(Some(&schema), Some(now - chrono::Duration::minutes(59)), now, ttl, false));
+        assert!(!schema_cache_is_fresh(Some(&schema), Some(now - chrono::Duration::minutes(61)), now, ttl, false));
+        assert!(!schema_cache_is_fresh(Some(&schema), None, now, ttl, false));
+        assert!(!schema_cache_is_fresh(None, Some(now), now, ttl, false));
+    }
+
+    #[test]
+    fn force_refresh_bypasses_schema_cache() {
+        let now = chrono::Utc::now();
+        let schema = json!({"tables": {"orders": []}});
+        let partial = json!({"partial": true, "tables": {"orders": []}});
+
+        assert!(schema_cache_is_fresh(Some(&schema), Some(now), now, chrono::Duration::hours(1), false));
+        assert!(!schema_cache_is_fresh(Some(&schema), Some(now), now, chrono::Duration::hours(1), true));
+        assert!(schema_cache_is_fresh(Some(&partial), None, now, chrono::Duration::hours(1), false));
+        assert!(!schema_cache_is_fresh(Some(&partial), None, now, chrono::Duration::hours(1), true));
+    }
+
+    #[test]
+    fn scope_is_none_without_filters() {
+        let scope = InspectionScope::from_args(&args(json!({"datasource_id": "ds"}))).unwrap();
+        assert!(scope.is_none());
+    }
+
+    #[test]
+    fn scope_rejects_invalid_max_tables() {
+        assert!(InspectionScope::from_args(&args(json!({"max_tables": 0}))).is_err());
+        assert!(InspectionScope::from_args(&args(json!({"max_tables": "ten"}))).is_err());
+    }
+
+    #[test]
+    fn scope_selects_by_pattern_and_cap() {
+        let tables = names(&["orders", "sales_2023", "sales_2024", "Sales_archive", "users"]);
+
+        let scope = InspectionScope::from_args(&args(json!({"tables": ["orders", "sales_*"]})))
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            scope.select(&tables),
+            vec!["orders", "sales_2023", "sales_2024", "Sales_archive"]
+        );
+
+        let capped = InspectionScope::from_args(&args(json!({"tables": "sales%", "max_tables": 2})))
+            .unwrap()
+            .unwrap();
+        assert_eq!(capped.select(&tables), vec!["sales_2023", "sales_2024"]);
+
+        let count_only = InspectionScope::from_args(&args(json!({"max_tables": 1})))
+            .unwrap()
+            .unwrap();
+        assert_eq!(count_only.select(&tables), vec!["orders"]);
+    }
+
+    #[test]
+    fn table_patterns_match_wildcards_anywhere() {
+        assert!(table_matches_pattern("*_log", "audit_log"));
+        assert!(table_matches_pattern("fact_*_daily", "fact_sales_daily"));
+        assert!(!table_matches_pattern("fact_*_daily", "fact_sales_weekly"));
+        assert!(!table_matches_pattern("orders", "orders_archive"));
+    }
+
+    #[test]
+    fn partial_schema_accumulates_until_complete() {
+        let all = names(&["orders", "users"]);
+
+        let first = merge_partial_schema(
+            None,
+            &json!({"orders": {"columns": []}}),
+            &["orders"],
+            &all,
+        );
+        assert_eq!(first["partial"], true);
+        assert_eq!(first["total_tables"], 2);
+
+        let second = merge_partial_schema(
+            Some(first),
+            &json!([{"name": "users", "columns": []}]),
+            &["users"],
+            &all,
+        );
+        assert_eq!(second["partial"], false);
+        assert!(second["tables"]["orders"].is_object());
+        assert!(second["tables"]["users"].is_object());
+    }
+
+    #[test]
+    fn partial_inspection_keeps_full_analysis_complete() {
+        let full = json!({"statistics": {"table_count": 3}, "table_names": ["a", "b", "c"]});
+        let merged = merge_partial_schema(
+            Some(full),
+            &json!({"a": {"columns": []}}),
+            &["a"],
+            &names(&["a", "b", "c"]),
+        );
+        assert_eq!(merged["partial"], false);
+        assert_eq!(merged["statistics"]["table_count"], 3);
+    }
+}
@@ -1,12 +1,24 @@
 use super::base::McpHandlers;
+use crate::core::datasources::cache::get_datasource_cache;
+use crate::core::datasources::connection_status::{record_connection_test, ConnectionHealth};
 use crate::core::datasources::shared_service;
+use crate::core::mcp::logging::{mcp_log, LogFields, LogLevel};
 use crate::core::mcp::types::*;
 use crate::utils::datasource::create_connector;
-use chrono::Utc;
+use crate::utils::datasource::common::diagnostics::ConnectionFailure;
+use crate::utils::datasource::common::error_handling::ConnectorError;
+use crate::utils::datasource::common::read_replica::validate_replica_settings;
+use crate::utils::datasource::common::redaction::redact_connection_config;
 use serde_json::{json, Value};
 use sqlx::Row;
 use uuid;
 
+mod inspection;
+mod query;
+mod restore;
+
+pub use inspection::{schema_cache_is_fresh, schema_cache_ttl_from_env, InspectionScope};
+
 impl McpHandlers {
     #[allow(dead_code)]
     pub async fn add_datasource(
@@ -26,12 +38,74 @@ impl McpHandlers {
             let config = args.get("config")
                 .ok_or_else(|| "Missing required parameter: config".to_string())?;
 
-            // Parse and validate the connection config
-            let parsed_config = self.parse_connection_config(config, source_type)?;
+            let force = args.get("force").and_then(|v| v.as_bool()).unwrap_or(false);
+
+            // Parse and validate the connection config; writes can only be enabled by users
+            let mut parsed_config = keep_allow_writes(
+                &self.parse_connection_config(config, source_type)?,
+                &Value::Null,
+            );
+
+            // Replica settings may be passed next to a URL string config
+            if matches!(source_type, "postgresql" | "postgres") {
+                if let Some(obj) = parsed_config.as_object_mut() {
+                    for key in ["read_replica_url", "replica_hosts"] {
+                        if let Some(value) = args.get(key) {
+                            obj.insert(key.to_string(), value.clone());
+                        }
+                    }
+                }
+                validate_replica_settings(&parsed_config)?;
+            }
+
+            // A retried call should not create a second copy of the same datasource
+            let same_name = sqlx::query(
+                "SELECT id, source_type, connection_config FROM data_sources
+                 WHERE project_id = $1 AND LOWER(name) = LOWER($2) AND deleted_at IS NULL"
+            )
+            .bind(&self.project_id)
+            .bind(name)
+            .fetch_all(&self.db_pool)
+            .await?;
+
+            let mut name = name.to_string();
+            if !same_name.is_empty() {
+                let existing = same_name.iter().find(|row| {
+                    row.get::<String, _>("source_type") == source_type
+                        && same_connection_config(&row.get::<Value, _>("connection_config"), &parsed_config)
+                });
+
+                if !force {
+                    if let Some(existing) = existing {
+                        let existing_id: String = existing.get("id");
+                        let response_data = json!({
+                            "status": "success",
+                            "datasource": {
+                                "id": existing_id,
+                                "name": name,
+                                "type": source_type
+                            },
+                            "existing": true,
+                            "message": "A datasource with this name and configuration already exists; returning it instead of creating a duplicate. Pass force: true to add another copy."
+                        });
+                        return Ok(serde_json::to_string(&response_data)?);
+                    }
+
+                    let conflicting_id: String = same_name[0].get("id");
+                    return Err(format!(
+                        "A datasource named '{}' already exists in this project (id {}) with a different configuration. Use datasource_update to change it, or pass force: true to add a separate datasource.",
+                        name, conflicting_id
+                    ).into());
+                }
+
+                // Forced duplicates get a distinct name so datasource names stay unique per project
+                name = self.next_available_datasource_name(&name).await?;
+            }
+            let name = name.as_str();
 
             // Test the connection directly before adding (no ID required)
             if let Err(e) = shared_service::test_datasource_connection_direct(source_type, &parsed_config).await {
-                return Err(format!("Connection test failed: {}", e).into());
+                return Err(format!("Connection test failed: {}", ConnectionFailure::from_error(source_type, &parsed_config, &*e)).into());
             }
 
             // Verify that client and project exist before inserting datasource
@@ -63,10 +137,12 @@ impl McpHandlers {
             let refresh_self = self.clone();
             tokio::spawn(async move {
                 if let Err(e) = refresh_self.refresh_claude_md().await {
-                    eprintln!(
-                        "[{}] [WARNING] Failed to refresh CLAUDE.md after adding datasource: {}", 
-                        Utc::now().format("%Y-%m-%d %H:%M:%S UTC"), 
-                        e
+                    mcp_log(
+                        LogLevel::Warning,
+                        LogFields::for_project(&refresh_self.project_id).operation("add_datasource").message(format!(
+                            "Failed to refresh CLAUDE.md after adding datasource: {}",
+                            e
+                        )),
                     );
                 }
             });
@@ -84,6 +160,28 @@ impl McpHandlers {
         }).await
     }
 
+    /// First of `name (2)`, `name (3)`, ... not used by another datasource in the project
+    async fn next_available_datasource_name(
+        &self,
+        name: &str,
+    ) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
+        let taken: Vec<String> = sqlx::query_scalar(
+            "SELECT LOWER(name) FROM data_sources WHERE project_id = $1 AND deleted_at IS NULL"
+        )
+        .bind(&self.project_id)
+        .fetch_all(&self.db_pool)
+        .await?;
+
+        let mut suffix = 2;
+        loop {
+            let candidate = format!("{} ({})", name, suffix);
+            if !taken.contains(&candidate.to_lowercase()) {
+                return Ok(candidate);
+            }
+            suffix += 1;
+        }
+    }
+
     #[allow(dead_code)]
     pub async fn list_datasources(
         &self,
@@ -149,14 +247,19 @@ impl McpHandlers {
             .execute(&self.db_pool)
             .await?;
 
+            // Same invalidation as the REST delete so no cached copy outlives the removal
+            get_datasource_cache().await.invalidate(datasource_id, None).await;
+
             // Refresh CLAUDE.md in the background
             let refresh_self = self.clone();
             tokio::spawn(async move {
                 if let Err(e) = refresh_self.refresh_claude_md().await {
-                    eprintln!(
-                        "[{}] [WARNING] Failed to refresh CLAUDE.md after removing datasource: {}", 
-                        Utc::now().format("%Y-%m-%d %H:%M:%S UTC"), 
-                        e
+                    mcp_log(
+                        LogLevel::Warning,
+                        LogFields::for_project(&refresh_self.project_id).operation("remove_datasource").message(format!(
+                            "Failed to refresh CLAUDE.md after removing datasource: {}",
+                            e
+                        )),
                     );
                 }
             });
@@ -167,7 +270,7 @@ impl McpHandlers {
                     "id": datasource_id,
                     "name": name
                 },
-                "message": "Datasource removed successfully",
+                "message": "Datasource removed successfully. It can be brought back with datasource_restore.",
                 "metadata": {
                     "claude_md_updated": true
                 }
@@ -201,6 +304,22 @@ impl McpHandlers {
 
             // Handle updates
             if let Some(name) = args.get("name").and_then(|v| v.as_str()) {
+                let conflicting: Option<String> = sqlx::query_scalar(
+                    "SELECT id FROM data_sources
+                     WHERE project_id = $1 AND LOWER(name) = LOWER($2) AND id <> $3 AND deleted_at IS NULL
+                     LIMIT 1"
+                )
+                .bind(&self.project_id)
+                .bind(name)
+                .bind(datasource_id)
+                .fetch_optional(&self.db_pool)
+                .await?;
+                if let Some(conflicting_id) = conflicting {
+                    return Err(format!(
+                        "A datasource named '{}' already exists in this project (id {}). Choose a different name.",
+                        name, conflicting_id
+                    ).into());
+                }
                 name_update = Some(name.to_string());
             }
 
@@ -212,10 +331,11 @@ impl McpHandlers {
                 let mut connector = create_connector(&source_type, &parsed_config).await
                     .map_err(|e| format!("Failed to create connector: {}", e))?;
                 if let Err(e) = connector.test_connection().await {
-                    return Err(format!("Connection test failed: {}", e).into());
+                    return Err(format!("Connection test failed: {}", ConnectionFailure::from_error(&source_type, &parsed_config, &*e)).into());
                 }
                 
-                config_update = Some(parsed_config);
+                let existing_config: Value = existing.get("connection_config");
+                config_update = Some(keep_allow_writes(&parsed_config, &existing_config));
             }
 
             if name_update.is_none() && config_update.is_none() {
@@ -260,10 +380,12 @@ impl McpHandlers {
             let refresh_self = self.clone();
             tokio::spawn(async move {
                 if let Err(e) = refresh_self.refresh_claude_md().await {
-                    eprintln!(
-                        "[{}] [WARNING] Failed to refresh CLAUDE.md after updating datasource: {}", 
-                        Utc::now().format("%Y-%m-%d %H:%M:%S UTC"), 
-                        e
+                    mcp_log(
+                        LogLevel::Warning,
+                        LogFields::for_project(&refresh_self.project_id).operation("datasource_update").message(format!(
+                            "Failed to refresh CLAUDE.md after updating datasource: {}",
+                            e
+                        )),
                     );
                 }
             });
@@ -313,12 +435,26 @@ impl McpHandlers {
             }
             
             // Create connector using the same mechanism for consistency
-            let mut connector = create_connector(&datasource.source_type, &config_with_id)
-                .await
-                .map_err(|e| format!("Failed to create connector: {}", e))?;
+            let mut connector = match create_connector(&datasource.source_type, &config_with_id).await {
+                Ok(connector) => connector,
+                Err(e) => {
+                    let error = format!("Failed to create connector: {}", e);
+                    record_connection_test(&self.db_pool, datasource_id, &ConnectionHealth::from_test(Err(error.clone()))).await;
+                    return Err(error.into());
+                }
+            };
 
-            // Test connection
-            match connector.test_connection().await {
+            // Test connection, keeping the outcome for the datasource list
+            let outcome = connector.test_connection().await;
+            let health = ConnectionHealth::from_test(
+                outcome.as_ref().map(|_| ()).map_err(|e| {
+                    let classified = ConnectorError::from_error(&**e);
+                    format!("{}: {}", classified.user_message(), classified.raw)
+                }),
+            );
+            record_connection_test(&self.db_pool, datasource_id, &health).await;
+
+            match outcome {
                 Ok(_) => {
                     let response_data = json!({
                         "status": "success",
@@ -332,6 +468,7 @@ impl McpHandlers {
                     Ok(serde_json::to_string(&response_data)?)
                 },
                 Err(e) => {
+                    let classified = ConnectorError::from_error(&*e);
                     let response_data = json!({
                         "status": "error",
                         "connected": false,
@@ -339,7 +476,9 @@ impl McpHandlers {
                             "id": datasource_id,
                             "name": datasource.name
                         },
-                        "error": e.to_string()
+                        "error": classified.user_message(),
+                        "category": classified.kind.as_str(),
+                        "details": classified.raw
                     });
                     Ok(serde_json::to_string(&response_data)?)
                 },
@@ -378,9 +517,9 @@ impl McpHandlers {
             let updated_at: Option<chrono::DateTime<chrono::Utc>> = source.get("updated_at");
             let schema_info: Option<String> = source.get("schema_info");
 
-            // Parse full connection details including sensitive information
+            // Connection details with passwords and other secrets masked
             let connection_details = match serde_json::from_str::<Value>(&connection_config) {
-                Ok(config) => config,
+                Ok(config) => redact_connection_config(&config),
                 Err(_) => json!("Invalid connection configuration")
             };
 
@@ -413,127 +552,100 @@ impl McpHandlers {
         }).await
     }
 
-    #[allow(dead_code)]
-    pub async fn query_datasource(
-        &self,
-        args: &serde_json::Map<String, Value>,
-    ) -> Result<String, JsonRpcError> {
-        self.execute_db_operation("query_datasource", async {
-            let datasource_id = args
-                .get("datasource_id")
-                .and_then(|v| v.as_str())
-                .ok_or_else(|| "Missing required parameter: datasource_id".to_string())?;
-
-            let query = args
-                .get("query")
-                .and_then(|v| v.as_str())
-                .ok_or_else(|| "Missing required parameter: query".to_string())?;
-
-            // Note: limit parameter is not used when pooling as the pooling mechanism handles limits internally
-
-            // Get datasource info first for the response
-            let datasource = shared_service::get_datasource_with_validation(
-                datasource_id,
-                &self.project_id,
-                &self.db_pool
-            ).await.map_err(|e| format!("Failed to get datasource: {}", e))?;
+}
 
-            // Execute query using shared service with connection pooling
-            let result = shared_service::execute_query_on_datasource(
-                datasource_id,
-                &self.project_id,
-                query,
-                &self.db_pool
-            ).await.map_err(|e| format!("Query execution failed: {}", e))?;
+/// Statements that only read data: these belong in datasource_query and are
+/// the only ones data_query_export and explain_query accept. A WITH query
+/// counts only if none of its CTEs modify data.
+pub(crate) fn is_read_statement(query: &str) -> bool {
+    let first_word = query
+        .trim_start()
+        .split(|c: char| c.is_whitespace() || c == '(')
+        .next()
+        .unwrap_or("")
+        .to_ascii_uppercase();
+    match first_word.as_str() {
+        "SELECT" | "SHOW" | "DESCRIBE" | "DESC" | "EXPLAIN" => true,
+        "WITH" => !has_data_modifying_keyword(query),
+        _ => false,
+    }
+}
 
-            // Return JSON result with metadata
-            let response_data = json!({
-                "datasource": {
-                    "id": datasource_id,
-                    "name": datasource.name
-                },
-                "query": query,
-                "execution_time_ms": result.get("execution_time_ms"),
-                "columns": result.get("columns"),
-                "rows": result.get("rows"),
-                "row_count": result.get("row_count"),
-                "using_connection_pool": true
-            });
-            Ok(serde_json::to_string(&response_data)?)
+/// Whether INSERT, UPDATE, DELETE or MERGE appears as a keyword outside string literals
+fn has_data_modifying_keyword(query: &str) -> bool {
+    query
+        .split('\'')
+        .step_by(2)
+        .flat_map(|code| code.split(|c: char| !(c.is_ascii_alphanumeric() || c == '_')))
+        .any(|word| {
+            ["INSERT", "UPDATE", "DELETE", "MERGE"]
+                .iter()
+                .any(|keyword| word.eq_ignore_ascii_case(keyword))
         })
-        .await
-    }
+}
 
-    #[allow(dead_code)]
-    pub async fn inspect_datasource(
-        &self,
-        args: &serde_json::Map<String, Value>,
-    ) -> Result<String, JsonRpcError> {
-        self.execute_db_operation("inspect_datasource", async {
-            let datasource_id = args
-                .get("datasource_id")
-                .and_then(|v| v.as_str())
-                .ok_or_else(|| "Missing required parameter: datasource_id".to_string())?;
+/// Writes are opt-in per datasource through `allow_writes` in its connection config
+fn writes_allowed(config: &Value) -> bool {
+    match config.get("allow_writes") {
+        Some(Value::Bool(allowed)) => *allowed,
+        Some(Value::String(allowed)) => allowed.eq_ignore_ascii_case("true"),
+        _ => false,
+    }
+}
 
-            self.inspect_datasource_internal(datasource_id).await
-        })
-        .await
+/// `allow_writes` is granted by users, never through MCP: an updated config keeps
+/// whatever the stored config had, and a new one (`existing` null) gets none
+pub(super) fn keep_allow_writes(config: &Value, existing: &Value) -> Value {
+    let mut config = config.clone();
+    if let Some(obj) = config.as_object_mut() {
+        obj.remove("allow_writes");
+        if let Some(allowed) = existing.get("allow_writes") {
+            obj.insert("allow_writes".to_string(), allowed.clone());
+        }
     }
+    config
+}
 
-    #[allow(dead_code)]
-    pub async fn inspect_datasource_internal(
-        &self,
-        datasource_id: &str,
-    ) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
-        // Get datasource info using shared service (with caching)
-        let datasource = shared_service::get_datasource_with_validation(
-            datasource_id,
-            &self.project_id,
-            &self.db_pool
-        ).await.map_err(|e| format!("Failed to get datasource: {}", e))?;
-        
-        // Add datasource ID to config for pooling support
-        let mut config_with_id = datasource.connection_config.clone();
-        if let Some(config_obj) = config_with_id.as_object_mut() {
-            config_obj.insert("id".to_string(), Value::String(datasource_id.to_string()));
+/// Compare two connection configs, ignoring the datasource id stamped into stored configs
+fn same_connection_config(stored: &Value, candidate: &Value) -> bool {
+    let strip_id = |config: &Value| {
+        let mut config = config.clone();
+        if let Some(obj) = config.as_object_mut() {
+            obj.remove("id");
         }
-        
-        // Create connector using the same mechanism as datasource_query
-        // This ensures we use pooling where available
-        let connector = create_connector(&datasource.source_type, &config_with_id)
-            .await
-            .map_err(|e| -> Box<dyn std::error::Error + Send + Sync> {
-                Box::new(std::io::Error::other(format!("Failed to create connector: {}", e)))
-            })?;
-
-        // Run inspection
-        let analysis = connector.analyze_database().await.map_err(
-            |e| -> Box<dyn std::error::Error + Send + Sync> {
-                Box::new(std::io::Error::other(format!("Database analysis failed: {}", e)))
-            },
-        )?;
-
-        // Store schema info in database for future reference
-        let schema_info = serde_json::to_string(&analysis)?;
-        sqlx::query("UPDATE data_sources SET schema_info = $1, updated_at = NOW() WHERE id = $2")
-            .bind(&schema_info)
-            .bind(datasource_id)
-            .execute(&self.db_pool)
-            .await?;
+        config
+    };
+    strip_id(stored) == strip_id(candidate)
+}
 
-        // Return JSON response instead of formatted text
-        let response_data = json!({
-            "datasource": {
-                "id": datasource_id,
-                "name": datasource.name
-            },
-            "analysis": analysis,
-            "message": "Database inspection completed successfully",
-            "metadata": {
-                "schema_cached": true,
-                "using_connection_pool": true
-            }
-        });
-        Ok(serde_json::to_string(&response_data)?)
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn writes_require_explicit_opt_in() {
+        assert!(writes_allowed(&json!({"allow_writes": true})));
+        assert!(writes_allowed(&json!({"allow_writes": "TRUE"})));
+        assert!(!writes_allowed(&json!({"allow_writes": false})));
+        assert!(!writes_allowed(&json!({"allow_writes": "yes"})));
+        assert!(!writes_allowed(&json!({"host": "db"})));
+    }
+
+    #[test]
+    fn allow_writes_is_never_taken_from_mcp_configs() {
+        let requested = json!({"host": "db", "allow_writes": true});
+
+        // New datasources start without write access
+        let added = keep_allow_writes(&requested, &Value::Null);
+        assert_eq!(added, json!({"host": "db"}));
+        assert!(!writes_allowed(&added));
+
+        // Updates keep whatever the stored config granted
+        let updated = keep_allow_writes(&requested, &json!({"host": "old"}));
+        assert!(updated.get("allow_writes").is_none());
+        let updated = keep_allow_writes(&json!({"host": "db"}), &json!({"allow_writes": true}));
+        assert_eq!(updated, json!({"host": "db", "allow_writes": true}));
+        let updated = keep_allow_writes(&requested, &json!({"allow_writes": false}));
+        assert_eq!(updated["allow_writes"], false);
     }
 }
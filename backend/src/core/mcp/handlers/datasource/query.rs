@@ -0,0 +1,229 @@
+use super::McpHandlers;
+use super::{is_read_statement, writes_allowed};
+use crate::core::datasources::query_history::{record_query, ExecutedQuery};
+use crate::core::datasources::shared_service;
+use crate::core::datasources::slow_queries::{record_if_slow, result_row_count, SlowQuery, SlowQueryConfig};
+use crate::core::mcp::logging::{mcp_log, LogFields, LogLevel};
+use crate::core::mcp::progress::{report_progress, report_rows};
+use crate::core::mcp::types::*;
+use crate::utils::config::Config;
+use crate::utils::datasource::create_connector;
+use crate::utils::datasource::common::error_handling::ConnectorError;
+use crate::utils::datasource::common::projection::ColumnRequest;
+use crate::utils::datasource::common::result_budget::ResultBudget;
+use serde_json::{json, Value};
+
+impl McpHandlers {
+    #[allow(dead_code)]
+    pub async fn query_datasource(
+        &self,
+        args: &serde_json::Map<String, Value>,
+    ) -> Result<String, JsonRpcError> {
+        self.execute_db_operation("query_datasource", async {
+            let datasource_id = args
+                .get("datasource_id")
+                .and_then(|v| v.as_str())
+                .ok_or_else(|| "Missing required parameter: datasource_id".to_string())?;
+
+            let query = args
+                .get("query")
+                .and_then(|v| v.as_str())
+                .ok_or_else(|| "Missing required parameter: query".to_string())?;
+
+            let requested_limit = args
+                .get("limit")
+                .and_then(|v| v.as_i64())
+                .map(|limit| limit.clamp(1, i32::MAX as i64) as i32);
+
+            // Get datasource info first for the response
+            report_progress("connecting", format!("Connecting to datasource {}", datasource_id));
+            let datasource = shared_service::get_datasource_with_validation(
+                datasource_id,
+                &self.project_id,
+                &self.db_pool
+            ).await.map_err(|e| format!("Failed to get datasource: {}", e))?;
+
+            // Trim very wide results unless specific columns were requested;
+            // the columns are chosen before the query runs so only they are fetched
+            let requested_columns: Option<Vec<String>> = args
+                .get("columns")
+                .and_then(|v| v.as_array())
+                .map(|cols| cols.iter().filter_map(|c| c.as_str()).map(|s| s.to_string()).collect());
+            let columns = ColumnRequest {
+                requested: requested_columns.as_deref(),
+                max_columns: Config::current()?.max_result_columns,
+            };
+
+            // Execute query using shared service with connection pooling
+            report_progress("executing", format!("Executing query on {}", datasource.name));
+            let budget = ResultBudget::from_env().for_datasource(&datasource.connection_config);
+            let query_start = std::time::Instant::now();
+            let mut result = shared_service::execute_projected_query_on_datasource(
+                datasource_id,
+                &self.project_id,
+                query,
+                budget.fetch_limit(requested_limit),
+                columns,
+                &self.db_pool
+            ).await.map_err(|e| format!("Query execution failed: {}", ConnectorError::from_error(&*e)))?;
+            if let Some(cap) = budget.apply(&mut result) {
+                mcp_log(
+                    LogLevel::Warning,
+                    LogFields::for_project(&self.project_id)
+                        .message(format!("Result of query on {} truncated at {} = {}", datasource_id, cap.name(), cap.limit())),
+                );
+            }
+            if let Some(row_count) = result_row_count(&result) {
+                report_rows(row_count as u64);
+            }
+
+            record_query(&self.db_pool, ExecutedQuery {
+                datasource_id: datasource_id.to_string(),
+                project_id: self.project_id.clone(),
+                source: "mcp",
+                query: query.to_string(),
+                duration: query_start.elapsed(),
+                row_count: result_row_count(&result),
+            });
+            record_if_slow(&self.db_pool, &SlowQueryConfig::from_env(), &datasource.source_type, &datasource.connection_config, SlowQuery {
+                datasource_id: datasource_id.to_string(),
+                project_id: self.project_id.clone(),
+                source: "mcp",
+                query: query.to_string(),
+                duration: query_start.elapsed(),
+                row_count: result_row_count(&result),
+            });
+
+            // Return JSON result with metadata
+            let response_data = json!({
+                "datasource": {
+                    "id": datasource_id,
+                    "name": datasource.name
+                },
+                "query": query,
+                "execution_time_ms": result.get("execution_time_ms"),
+                "columns": result.get("columns"),
+                "rows": result.get("rows"),
+                "row_count": result.get("row_count"),
+                "total_columns": result.get("total_columns"),
+                "columns_truncated": result.get("columns_truncated").cloned().unwrap_or(Value::Bool(false)),
+                "note": result.get("note"),
+                "truncated": result.get("truncated"),
+                "truncated_by": result.get("truncated_by"),
+                "max_result_rows": result.get("max_result_rows"),
+                "max_result_bytes": result.get("max_result_bytes"),
+                "using_connection_pool": true
+            });
+            Ok(serde_json::to_string(&response_data)?)
+        })
+        .await
+    }
+
+    /// Run an INSERT/UPDATE/DELETE (or other non-SELECT statement) on a datasource
+    /// whose connection_config opts in with `allow_writes: true`
+    pub async fn write_datasource(
+        &self,
+        args: &serde_json::Map<String, Value>,
+    ) -> Result<String, JsonRpcError> {
+        let datasource_id = args
+            .get("datasource_id")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| JsonRpcError {
+                code: INVALID_PARAMS,
+                message: "Missing required parameter: datasource_id".to_string(),
+                data: None,
+            })?;
+        let query = args
+            .get("query")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| JsonRpcError {
+                code: INVALID_PARAMS,
+                message: "Missing required parameter: query".to_string(),
+                data: None,
+            })?;
+        let dry_run = args.get("dry_run").and_then(|v| v.as_bool()).unwrap_or(false);
+
+        let datasource = shared_service::get_datasource_with_validation(
+            datasource_id,
+            &self.project_id,
+            &self.db_pool
+        ).await.map_err(|e| JsonRpcError {
+            code: INVALID_PARAMS,
+            message: format!("Failed to get datasource: {}", e),
+            data: None,
+        })?;
+
+        if !writes_allowed(&datasource.connection_config) {
+            return Err(JsonRpcError {
+                code: INVALID_PARAMS,
+                message: format!(
+                    "Writes are disabled for datasource '{}'. Set allow_writes: true in its connection config to enable data_query_write.",
+                    datasource.name
+                ),
+                data: Some(json!({ "datasource_id": datasource_id, "allow_writes": false })),
+            });
+        }
+
+        if is_read_statement(query) {
+            return Err(JsonRpcError {
+                code: INVALID_PARAMS,
+                message: "data_query_write is for statements that modify data; use datasource_query for SELECT queries".to_string(),
+                data: None,
+            });
+        }
+
+        self.execute_db_operation("write_datasource", async {
+            let mut config_with_id = datasource.connection_config.clone();
+            if let Some(config_obj) = config_with_id.as_object_mut() {
+                config_obj.insert("id".to_string(), Value::String(datasource_id.to_string()));
+            }
+
+            let connector = create_connector(&datasource.source_type, &config_with_id)
+                .await
+                .map_err(|e| format!("Failed to create connector: {}", e))?;
+
+            let result = connector.execute_write(query, dry_run).await
+                .map_err(|e| format!("Statement execution failed: {}", ConnectorError::from_error(&*e)))?;
+
+            tracing::info!(
+                "✏️ data_query_write on datasource {} affected {} rows ({})",
+                datasource_id,
+                result.get("rows_affected").and_then(|v| v.as_u64()).unwrap_or(0),
+                if dry_run { "dry run, rolled back" } else { "committed" }
+            );
+
+            let response_data = json!({
+                "datasource": {
+                    "id": datasource_id,
+                    "name": datasource.name
+                },
+                "query": query,
+                "dry_run": dry_run,
+                "rows_affected": result.get("rows_affected"),
+                "committed": result.get("committed"),
+                "execution_time_ms": result.get("execution_time_ms")
+            });
+            Ok(serde_json::to_string(&response_data)?)
+        })
+        .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn read_statements_are_recognized() {
+        assert!(is_read_statement("select * from users"));
+        assert!(is_read_statement("  SELECT(1)"));
+        assert!(is_read_statement("EXPLAIN DELETE FROM users"));
+        assert!(is_read_statement("show tables"));
+        assert!(!is_read_statement("DELETE FROM users"));
+        assert!(!is_read_statement("update users set name = 'select'"));
+        assert!(!is_read_statement("WITH gone AS (DELETE FROM users RETURNING *) SELECT * FROM gone"));
+        assert!(!is_read_statement(""));
+        assert!(is_read_statement("WITH recent AS (SELECT 1) SELECT * FROM recent"));
+        assert!(is_read_statement("WITH notes AS (SELECT 'delete me' AS note) SELECT * FROM notes"));
+    }
+}
@@ -0,0 +1,194 @@
+use super::McpHandlers;
+use crate::core::datasources::cache::get_datasource_cache;
+use crate::core::mcp::logging::{mcp_log, LogFields, LogLevel};
+use crate::core::mcp::types::*;
+use serde_json::{json, Value};
+use sqlx::Row;
+
+impl McpHandlers {
+    /// Undo a `datasource_remove`. Removal only sets deleted_at (as the REST
+    /// delete does), so restoring clears it again unless a live datasource has
+    /// taken the name in the meantime.
+    pub async fn handle_datasource_restore(
+        &self,
+        args: &serde_json::Map<String, Value>,
+    ) -> Result<String, JsonRpcError> {
+        let datasource_id = args
+            .get("datasource_id")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| JsonRpcError {
+                code: INVALID_PARAMS,
+                message: "Missing required parameter: datasource_id".to_string(),
+                data: None,
+            })?;
+
+        self.execute_db_operation("restore_datasource", async {
+            let row = sqlx::query(
+                "SELECT name, source_type, deleted_at FROM data_sources WHERE id = $1 AND project_id = $2"
+            )
+            .bind(datasource_id)
+            .bind(&self.project_id)
+            .fetch_optional(&self.db_pool)
+            .await?
+            .ok_or("Datasource not found in this project")?;
+
+            let name: String = row.get("name");
+            let deleted_at: Option<chrono::DateTime<chrono::Utc>> = row.get("deleted_at");
+            if deleted_at.is_none() {
+                return Err(format!("Datasource '{}' is not removed", name).into());
+            }
+
+            let name_taken: Option<String> = sqlx::query_scalar(
+                "SELECT id FROM data_sources
+                 WHERE project_id = $1 AND LOWER(name) = LOWER($2) AND deleted_at IS NULL AND id <> $3
+                 LIMIT 1"
+            )
+            .bind(&self.project_id)
+            .bind(&name)
+            .bind(datasource_id)
+            .fetch_optional(&self.db_pool)
+            .await?;
+            if let Some(other_id) = name_taken {
+                return Err(format!(
+                    "Cannot restore: datasource {} already uses the name '{}'. Rename it with datasource_update first.",
+                    other_id, name
+                )
+                .into());
+            }
+
+            sqlx::query(
+                "UPDATE data_sources SET deleted_at = NULL, updated_at = NOW() WHERE id = $1 AND project_id = $2"
+            )
+            .bind(datasource_id)
+            .bind(&self.project_id)
+            .execute(&self.db_pool)
+            .await?;
+
+            get_datasource_cache().await.invalidate(datasource_id, None).await;
+
+            let refresh_self = self.clone();
+            tokio::spawn(async move {
+                if let Err(e) = refresh_self.refresh_claude_md().await {
+                    mcp_log(
+                        LogLevel::Warning,
+                        LogFields::for_project(&refresh_self.project_id).operation("restore_datasource").message(format!(
+                            "Failed to refresh CLAUDE.md after restoring datasource: {}",
+                            e
+                        )),
+                    );
+                }
+            });
+
+            let response = json!({
+                "status": "success",
+                "datasource": {
+                    "id": datasource_id,
+                    "name": name,
+                    "source_type": row.get::<String, _>("source_type"),
+                    "removed_at": deleted_at.map(|dt| dt.to_rfc3339()),
+                },
+                "message": "Datasource restored",
+                "metadata": {
+                    "claude_md_updated": true
+                }
+            });
+            Ok(serde_json::to_string(&response)?)
+        })
+        .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sqlx::PgPool;
+
+    #[tokio::test]
+    #[ignore = "needs a migrated Clay Studio database in TEST_DATABASE_URL"]
+    async fn restore_is_refused_while_another_datasource_has_the_name() {
+        let url = std::env::var("TEST_DATABASE_URL").expect("Set TEST_DATABASE_URL");
+        let db_pool = PgPool::connect(&url).await.unwrap();
+
+        let (client_id, owner_id) = (uuid::Uuid::new_v4(), uuid::Uuid::new_v4());
+        let project_id = format!("mcp-restore-{}", client_id);
+        let (removed, replacement) = (format!("ds-removed-{}", client_id), format!("ds-replacement-{}", client_id));
+        sqlx::query(
+            "INSERT INTO clients (id, name, description, status, install_path, config, created_at, updated_at)
+             VALUES ($1, 'mcp-restore-test', NULL, 'active', '', '{}'::jsonb, NOW(), NOW())",
+        )
+        .bind(client_id)
+        .execute(&db_pool)
+        .await
+        .unwrap();
+        sqlx::query(
+            "INSERT INTO users (id, client_id, username, password, role, created_at, updated_at)
+             VALUES ($1, $2, $3, 'unused', 'user', NOW(), NOW())",
+        )
+        .bind(owner_id)
+        .bind(client_id)
+        .bind(format!("mcp-restore-{}", owner_id))
+        .execute(&db_pool)
+        .await
+        .unwrap();
+        sqlx::query("INSERT INTO projects (id, name, client_id, user_id) VALUES ($1, 'Restore', $2, $3)")
+            .bind(&project_id)
+            .bind(client_id)
+            .bind(owner_id)
+            .execute(&db_pool)
+            .await
+            .unwrap();
+        for (id, name, deleted) in [(&removed, "Orders", true), (&replacement, "ORDERS", false)] {
+            sqlx::query(
+                "INSERT INTO data_sources (id, project_id, name, source_type, connection_config, created_at, updated_at, deleted_at)
+                 VALUES ($1, $2, $3, 'postgresql', '{}'::jsonb, NOW(), NOW(), CASE WHEN $4 THEN NOW() END)",
+            )
+            .bind(id)
+            .bind(&project_id)
+            .bind(name)
+            .bind(deleted)
+            .execute(&db_pool)
+            .await
+            .unwrap();
+        }
+
+        let handlers = McpHandlers {
+            project_id: project_id.clone(),
+            client_id: client_id.to_string(),
+            server_type: "operation".to_string(),
+            db_pool: db_pool.clone(),
+            conversation_id: None,
+            turn_id: None,
+        };
+        let mut args = serde_json::Map::new();
+        args.insert("datasource_id".to_string(), json!(removed));
+
+        let blocked = handlers.handle_datasource_restore(&args).await;
+        sqlx::query("UPDATE data_sources SET deleted_at = NOW() WHERE id = $1")
+            .bind(&replacement)
+            .execute(&db_pool)
+            .await
+            .unwrap();
+        let restored = handlers.handle_datasource_restore(&args).await;
+        let live: Option<chrono::DateTime<chrono::Utc>> =
+            sqlx::query_scalar("SELECT deleted_at FROM data_sources WHERE id = $1")
+                .bind(&removed)
+                .fetch_one(&db_pool)
+                .await
+                .unwrap();
+
+        for cleanup in [
+            "DELETE FROM data_sources WHERE project_id = $1",
+            "DELETE FROM projects WHERE id = $1",
+        ] {
+            sqlx::query(cleanup).bind(&project_id).execute(&db_pool).await.unwrap();
+        }
+        sqlx::query("DELETE FROM users WHERE id = $1").bind(owner_id).execute(&db_pool).await.unwrap();
+        sqlx::query("DELETE FROM clients WHERE id = $1").bind(client_id).execute(&db_pool).await.unwrap();
+
+        let message = blocked.unwrap_err().message;
+        assert!(message.contains(&replacement), "{}", message);
+        assert!(message.contains("already uses the name"), "{}", message);
+        assert!(restored.is_ok());
+        assert!(live.is_none());
+    }
+}
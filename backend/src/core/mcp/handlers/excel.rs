@@ -1,10 +1,10 @@
 use super::base::McpHandlers;
+use crate::core::mcp::logging::{mcp_log, LogFields, LogLevel};
 use crate::core::mcp::types::*;
 use chrono::Utc;
 use rust_xlsxwriter::{Color, Format, FormatBorder, Workbook};
+use crate::utils::storage::{excel_export_prefix, storage};
 use serde_json::{json, Value};
-use std::path::PathBuf;
-use tokio::fs;
 use uuid;
 
 impl McpHandlers {
@@ -159,26 +159,15 @@ impl McpHandlers {
         // Create Excel export response
         let export_id = uuid::Uuid::new_v4().to_string();
 
-        // Create directory structure for Excel exports following existing pattern
-        let export_dir = PathBuf::from(".clients")
-            .join(&self.client_id)
-            .join(&self.project_id)
-            .join("excel_exports");
-
-        fs::create_dir_all(&export_dir)
-            .await
-            .map_err(|e| JsonRpcError {
-                code: INTERNAL_ERROR,
-                message: format!("Failed to create export directory: {}", e),
-                data: None,
-            })?;
-
-        // Generate Excel file
-        let file_path = export_dir.join(format!("{}_{}.xlsx", export_id, filename));
-        let relative_path = format!(
-            ".clients/{}/{}/excel_exports/{}_{}.xlsx",
-            self.client_id, self.project_id, export_id, filename
+        // Exports live under the project's excel_exports area of the storage backend
+        let storage = storage();
+        let export_key = format!(
+            "{}{}_{}.xlsx",
+            excel_export_prefix(&self.client_id, &self.project_id),
+            export_id,
+            filename
         );
+        let relative_path = storage.location(&export_key);
 
         // Create workbook
         let mut workbook = Workbook::new();
@@ -500,27 +489,40 @@ impl McpHandlers {
         }
 
         // Save the workbook
-        workbook.save(&file_path).map_err(|e| JsonRpcError {
+        let buffer = workbook.save_to_buffer().map_err(|e| JsonRpcError {
             code: INTERNAL_ERROR,
             message: format!("Failed to save Excel file: {}", e),
             data: None,
         })?;
+        let file_size = buffer.len() as u64;
+
+        storage
+            .put(
+                &export_key,
+                buffer,
+                Some("application/vnd.openxmlformats-officedocument.spreadsheetml.sheet"),
+            )
+            .await
+            .map_err(|e| JsonRpcError {
+                code: INTERNAL_ERROR,
+                message: format!("Failed to store Excel file: {}", e),
+                data: None,
+            })?;
 
         // Cleanup old Excel files in the background
         let cleanup_self = self.clone();
         tokio::spawn(async move {
             if let Err(e) = cleanup_self.cleanup_old_excel_files().await {
-                eprintln!(
-                    "[{}] [WARNING] Failed to cleanup old Excel files: {}",
-                    chrono::Utc::now().format("%Y-%m-%d %H:%M:%S UTC"),
-                    e
+                mcp_log(
+                    LogLevel::Warning,
+                    LogFields::for_project(&cleanup_self.project_id).message(format!(
+                        "Failed to cleanup old Excel files: {}",
+                        e
+                    )),
                 );
             }
         });
 
-        // Get file size
-        let file_size = fs::metadata(&file_path).await.map(|m| m.len()).unwrap_or(0);
-
         // Build success response with download URL
         let download_url = format!(
             "/api/files/excel/{}/{}/{}",
@@ -556,42 +558,38 @@ impl McpHandlers {
     pub async fn cleanup_old_excel_files(
         &self,
     ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
-        let export_dir = PathBuf::from(".clients")
-            .join(&self.client_id)
-            .join(&self.project_id)
-            .join("excel_exports");
-
-        if !export_dir.exists() {
-            return Ok(());
-        }
+        let storage = storage();
+        let exports = storage
+            .list(&excel_export_prefix(&self.client_id, &self.project_id))
+            .await?;
 
         let cutoff_time = Utc::now() - chrono::Duration::hours(24);
 
-        if let Ok(mut entries) = fs::read_dir(&export_dir).await {
-            while let Some(entry) = entries.next_entry().await? {
-                let path = entry.path();
-                if path.is_file() && path.extension().is_some_and(|ext| ext == "xlsx") {
-                    if let Ok(metadata) = fs::metadata(&path).await {
-                        if let Ok(modified) = metadata.modified() {
-                            let modified_time = chrono::DateTime::<Utc>::from(modified);
-                            if modified_time < cutoff_time {
-                                if let Err(e) = fs::remove_file(&path).await {
-                                    eprintln!(
-                                        "[{}] [WARNING] Failed to remove old Excel file {}: {}",
-                                        chrono::Utc::now().format("%Y-%m-%d %H:%M:%S UTC"),
-                                        path.display(),
-                                        e
-                                    );
-                                } else {
-                                    eprintln!(
-                                        "[{}] [INFO] Removed old Excel file: {}",
-                                        chrono::Utc::now().format("%Y-%m-%d %H:%M:%S UTC"),
-                                        path.display()
-                                    );
-                                }
-                            }
-                        }
-                    }
+        for export in exports {
+            if !export.key.ends_with(".xlsx") {
+                continue;
+            }
+            let Some(modified_time) = export.last_modified else {
+                continue;
+            };
+            if modified_time < cutoff_time {
+                if let Err(e) = storage.delete(&export.key).await {
+                    mcp_log(
+                        LogLevel::Warning,
+                        LogFields::for_project(&self.project_id).message(format!(
+                            "Failed to remove old Excel file {}: {}",
+                            export.key,
+                            e
+                        )),
+                    );
+                } else {
+                    mcp_log(
+                        LogLevel::Info,
+                        LogFields::for_project(&self.project_id).message(format!(
+                            "Removed old Excel file: {}",
+                            export.key
+                        )),
+                    );
                 }
             }
         }
@@ -0,0 +1,205 @@
+use super::base::McpHandlers;
+use super::datasource::is_read_statement;
+use super::query_export::result_to_table;
+use crate::core::datasources::shared_service;
+use crate::core::mcp::types::*;
+use crate::utils::datasource::create_connector;
+use serde_json::{json, Value};
+
+/// Datasources whose SQL dialect can describe a query plan
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ExplainDialect {
+    PostgreSQL,
+    MySQL,
+    SQLite,
+    ClickHouse,
+    DuckDB,
+}
+
+impl ExplainDialect {
+    fn from_source_type(source_type: &str) -> Option<Self> {
+        match source_type.to_lowercase().as_str() {
+            "postgresql" | "postgres" => Some(ExplainDialect::PostgreSQL),
+            "mysql" => Some(ExplainDialect::MySQL),
+            "sqlite" => Some(ExplainDialect::SQLite),
+            "clickhouse" | "ch" => Some(ExplainDialect::ClickHouse),
+            "duckdb" => Some(ExplainDialect::DuckDB),
+            _ => None,
+        }
+    }
+}
+
+/// The statement that asks the database for `query`'s plan. With `analyze` the
+/// query is actually run and the plan carries real timings and row counts.
+fn explain_statement(dialect: ExplainDialect, query: &str, analyze: bool) -> Result<String, String> {
+    let query = query.trim().trim_end_matches(';').trim_end();
+    let prefix = match (dialect, analyze) {
+        (ExplainDialect::SQLite, false) => "EXPLAIN QUERY PLAN",
+        (ExplainDialect::SQLite, true) => {
+            return Err("SQLite has no EXPLAIN ANALYZE; call explain_query without analyze for the query plan".to_string())
+        }
+        (ExplainDialect::ClickHouse, true) => {
+            return Err("ClickHouse EXPLAIN does not report actual timings; call explain_query without analyze for the query plan".to_string())
+        }
+        // MySQL needs 8.0.18 or later for EXPLAIN ANALYZE; older servers return a syntax error
+        (_, true) => "EXPLAIN ANALYZE",
+        (_, false) => "EXPLAIN",
+    };
+    Ok(format!("{} {}", prefix, query))
+}
+
+/// Plan rows as text: single-column plans (PostgreSQL, ClickHouse, DuckDB) are
+/// one line per row, tabular plans (MySQL, SQLite) get a header line and
+/// ` | `-separated cells
+fn format_plan(result: &Value) -> String {
+    let (columns, rows) = result_to_table(result);
+    let cell = |value: &Value| match value {
+        Value::Null => String::new(),
+        Value::String(s) => s.clone(),
+        other => other.to_string(),
+    };
+
+    if columns.len() == 1 {
+        return rows
+            .iter()
+            .filter_map(|row| row.first())
+            .map(cell)
+            .collect::<Vec<_>>()
+            .join("\n");
+    }
+
+    std::iter::once(columns.join(" | "))
+        .chain(rows.iter().map(|row| row.iter().map(cell).collect::<Vec<_>>().join(" | ")))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+impl McpHandlers {
+    /// Show how the datasource would execute a SELECT, optionally running it
+    /// (`analyze: true`) to include actual timings
+    pub async fn handle_explain_query(
+        &self,
+        args: &serde_json::Map<String, Value>,
+    ) -> Result<String, JsonRpcError> {
+        let invalid = |message: &str| JsonRpcError {
+            code: INVALID_PARAMS,
+            message: message.to_string(),
+            data: None,
+        };
+
+        let datasource_id = args
+            .get("datasource_id")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| invalid("Missing required parameter: datasource_id"))?;
+        let query = args
+            .get("query")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| invalid("Missing required parameter: query"))?;
+        if !is_read_statement(query) {
+            return Err(invalid("explain_query only explains read-only queries"));
+        }
+        let analyze = args.get("analyze").and_then(|v| v.as_bool()).unwrap_or(false);
+
+        let datasource = shared_service::get_datasource_with_validation(
+            datasource_id,
+            &self.project_id,
+            &self.db_pool,
+        )
+        .await
+        .map_err(|e| invalid(&format!("Failed to get datasource: {}", e)))?;
+
+        let dialect = ExplainDialect::from_source_type(&datasource.source_type).ok_or_else(|| {
+            invalid(&format!(
+                "explain_query is not supported for {} datasources",
+                datasource.source_type
+            ))
+        })?;
+        let statement = explain_statement(dialect, query, analyze).map_err(|e| invalid(&e))?;
+
+        let result: Value = self
+            .execute_db_operation("explain_query", async {
+                let mut config_with_id = datasource.connection_config.clone();
+                if let Some(config_obj) = config_with_id.as_object_mut() {
+                    config_obj.insert("id".to_string(), Value::String(datasource_id.to_string()));
+                }
+
+                let connector = create_connector(&datasource.source_type, &config_with_id)
+                    .await
+                    .map_err(|e| format!("Failed to create connector: {}", e))?;
+
+                // No row limit: it would be appended to the explained query and change its plan
+                let result = connector
+                    .execute_read_only_query(&statement, 0)
+                    .await
+                    .map_err(|e| format!("EXPLAIN failed: {}", e))?;
+                Ok(result)
+            })
+            .await?;
+
+        let response = json!({
+            "datasource": { "id": datasource_id, "name": datasource.name, "type": datasource.source_type },
+            "query": query,
+            "statement": statement,
+            "analyze": analyze,
+            "plan": format_plan(&result),
+            "execution_time_ms": result.get("execution_time_ms").cloned().unwrap_or(Value::Null),
+        });
+
+        serde_json::to_string(&response).map_err(|e| JsonRpcError {
+            code: INTERNAL_ERROR,
+            message: format!("Failed to serialize response: {}", e),
+            data: None,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn explain_prefix_follows_the_dialect_and_analyze_flag() {
+        let query = "SELECT * FROM orders WHERE total > 100;";
+        assert_eq!(
+            explain_statement(ExplainDialect::PostgreSQL, query, false).unwrap(),
+            "EXPLAIN SELECT * FROM orders WHERE total > 100"
+        );
+        assert_eq!(
+            explain_statement(ExplainDialect::PostgreSQL, query, true).unwrap(),
+            "EXPLAIN ANALYZE SELECT * FROM orders WHERE total > 100"
+        );
+        assert!(explain_statement(ExplainDialect::MySQL, query, true).unwrap().starts_with("EXPLAIN ANALYZE "));
+        assert!(explain_statement(ExplainDialect::SQLite, query, false).unwrap().starts_with("EXPLAIN QUERY PLAN "));
+        assert!(explain_statement(ExplainDialect::SQLite, query, true).is_err());
+        assert!(explain_statement(ExplainDialect::ClickHouse, query, true).is_err());
+    }
+
+    #[test]
+    fn only_sql_dialects_with_explain_are_supported() {
+        assert_eq!(ExplainDialect::from_source_type("Postgres"), Some(ExplainDialect::PostgreSQL));
+        assert_eq!(ExplainDialect::from_source_type("duckdb"), Some(ExplainDialect::DuckDB));
+        assert_eq!(ExplainDialect::from_source_type("mongodb"), None);
+        assert_eq!(ExplainDialect::from_source_type("oracle"), None);
+    }
+
+    #[test]
+    fn plans_are_formatted_as_text() {
+        let postgres = json!({
+            "columns": ["QUERY PLAN"],
+            "rows": [
+                ["Seq Scan on orders  (cost=0.00..35.50 rows=850 width=40)"],
+                ["  Filter: (total > 100)"]
+            ]
+        });
+        assert_eq!(
+            format_plan(&postgres),
+            "Seq Scan on orders  (cost=0.00..35.50 rows=850 width=40)\n  Filter: (total > 100)"
+        );
+
+        let sqlite = json!({
+            "columns": ["id", "parent", "notused", "detail"],
+            "rows": [{ "id": 2, "parent": 0, "notused": 0, "detail": "SCAN orders" }]
+        });
+        assert_eq!(format_plan(&sqlite), "id | parent | notused | detail\n2 | 0 | 0 | SCAN orders");
+    }
+}
@@ -0,0 +1,310 @@
+use super::base::McpHandlers;
+use crate::core::analysis::duckdb_manager::DuckDBManager;
+use crate::core::datasources::shared_service;
+use crate::core::mcp::types::*;
+use crate::utils::config::Config;
+use crate::utils::datasource::connectors::common::{apply_row_limit, LimitSyntax};
+use crate::utils::datasource::create_connector;
+use serde_json::{json, Value};
+use std::path::Path;
+
+const DEFAULT_SOURCE_ROW_LIMIT: i32 = 10_000;
+const MAX_SOURCE_ROW_LIMIT: i32 = 100_000;
+const DEFAULT_RESULT_LIMIT: i32 = 100;
+const MAX_RESULT_LIMIT: i32 = 1000;
+const MAX_FEDERATED_SOURCES: usize = 10;
+
+// Runs ahead of the model's SQL once the extracts are loaded: no file, network
+// or extension access, and no statement can switch that back on
+const SANDBOX_SETTINGS: &str = "SET enable_external_access = false; SET lock_configuration = true; ";
+
+/// A table or extract from one datasource, attached to the DuckDB session under `alias`
+struct FederatedSource {
+    datasource_id: String,
+    alias: String,
+    extract_query: String,
+    limit: i32,
+}
+
+impl McpHandlers {
+    /// Run a SQL query across several datasources by loading each referenced
+    /// table/extract into an ephemeral DuckDB database and querying it there
+    pub async fn handle_data_query_federated(
+        &self,
+        args: &serde_json::Map<String, Value>,
+    ) -> Result<String, JsonRpcError> {
+        self.execute_db_operation("data_query_federated", async {
+            let query = args
+                .get("query")
+                .and_then(|v| v.as_str())
+                .ok_or_else(|| "Missing required parameter: query".to_string())?;
+
+            let sources = parse_federated_sources(args)?;
+
+            let limit = args
+                .get("limit")
+                .and_then(|v| v.as_i64())
+                .map(|v| v.clamp(1, MAX_RESULT_LIMIT as i64) as i32)
+                .unwrap_or(DEFAULT_RESULT_LIMIT);
+
+            let start = std::time::Instant::now();
+            let temp_dir = tempfile::tempdir()?;
+            let database_path = temp_dir.path().join("federated.duckdb");
+            let duckdb = DuckDBManager::new(Config::current()?.analysis_data_dir.clone())
+                .await
+                .map_err(|e| format!("Failed to initialize DuckDB: {}", e))?;
+
+            let mut attached = Vec::new();
+            for source in &sources {
+                // Datasources outside this project fail validation, which is the
+                // read permission boundary for MCP tools
+                let datasource = shared_service::get_datasource_with_validation(
+                    &source.datasource_id,
+                    &self.project_id,
+                    &self.db_pool,
+                )
+                .await
+                .map_err(|e| format!("Cannot read datasource {}: {}", source.datasource_id, e))?;
+
+                let mut config_with_id = datasource.connection_config.clone();
+                if let Some(config_obj) = config_with_id.as_object_mut() {
+                    config_obj.insert("id".to_string(), Value::String(source.datasource_id.clone()));
+                }
+
+                let connector = create_connector(&datasource.source_type, &config_with_id)
+                    .await
+                    .map_err(|e| format!("Failed to create connector for {}: {}", datasource.name, e))?;
+
+                let extract = connector
+                    .execute_read_only_query(&source.extract_query, source.limit)
+                    .await
+                    .map_err(|e| format!("Failed to extract '{}' from {}: {}", source.alias, datasource.name, e))?;
+
+                let csv_path = temp_dir.path().join(format!("{}.csv", source.alias));
+                let row_count = write_extract_csv(&extract, &csv_path)
+                    .map_err(|e| format!("Failed to stage '{}': {}", source.alias, e))?;
+
+                duckdb
+                    .import_csv(&database_path, &source.alias, &csv_path)
+                    .await
+                    .map_err(|e| format!("Failed to attach '{}': {}", source.alias, e))?;
+
+                attached.push(json!({
+                    "alias": source.alias,
+                    "datasource_id": source.datasource_id,
+                    "datasource_name": datasource.name,
+                    "source_type": datasource.source_type,
+                    "row_count": row_count,
+                    "truncated": row_count >= source.limit as usize
+                }));
+            }
+
+            let limited_query = apply_row_limit(query, limit, LimitSyntax::Limit);
+
+            // DESCRIBE keeps the result column order, which the JSON records don't
+            let described = duckdb
+                .execute_query_json(&database_path, &sandboxed(&format!("DESCRIBE {}", limited_query)))
+                .await
+                .map_err(|e| format!("Federated query failed: {}", e))?;
+            let columns: Vec<String> = described
+                .as_array()
+                .map(|cols| {
+                    cols.iter()
+                        .filter_map(|c| c.get("column_name").and_then(|n| n.as_str()))
+                        .map(|s| s.to_string())
+                        .collect()
+                })
+                .unwrap_or_default();
+
+            let records = duckdb
+                .execute_query_json(&database_path, &sandboxed(&limited_query))
+                .await
+                .map_err(|e| format!("Federated query failed: {}", e))?;
+            let rows: Vec<Vec<Value>> = records
+                .as_array()
+                .map(|records| {
+                    records
+                        .iter()
+                        .map(|record| {
+                            columns
+                                .iter()
+                                .map(|col| record.get(col).cloned().unwrap_or(Value::Null))
+                                .collect()
+                        })
+                        .collect()
+                })
+                .unwrap_or_default();
+
+            let response_data = json!({
+                "sources": attached,
+                "query": query,
+                "columns": columns,
+                "rows": rows,
+                "row_count": rows.len(),
+                "execution_time_ms": start.elapsed().as_millis() as u64,
+                "engine": "duckdb"
+            });
+            Ok(serde_json::to_string(&response_data)?)
+        })
+        .await
+    }
+}
+
+fn parse_federated_sources(
+    args: &serde_json::Map<String, Value>,
+) -> Result<Vec<FederatedSource>, String> {
+    let sources = args
+        .get("sources")
+        .and_then(|v| v.as_array())
+        .ok_or_else(|| "Missing required parameter: sources".to_string())?;
+
+    if sources.is_empty() {
+        return Err("sources must reference at least one datasource table".to_string());
+    }
+    if sources.len() > MAX_FEDERATED_SOURCES {
+        return Err(format!("At most {} sources can be federated in one query", MAX_FEDERATED_SOURCES));
+    }
+
+    let mut parsed: Vec<FederatedSource> = Vec::new();
+    for source in sources {
+        let datasource_id = source
+            .get("datasource_id")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| "Each source requires a datasource_id".to_string())?;
+
+        let table = source.get("table").and_then(|v| v.as_str());
+        let extract = source.get("query").and_then(|v| v.as_str());
+
+        let extract_query = match (table, extract) {
+            (Some(table), None) => {
+                if !is_valid_table_reference(table) {
+                    return Err(format!("Invalid table name: {}", table));
+                }
+                format!("SELECT * FROM {}", table)
+            }
+            (None, Some(extract)) => extract.to_string(),
+            _ => return Err(format!("Source for datasource {} needs exactly one of 'table' or 'query'", datasource_id)),
+        };
+
+        let alias = match source.get("alias").and_then(|v| v.as_str()) {
+            Some(alias) => alias.to_string(),
+            None => table
+                .and_then(|t| t.rsplit('.').next())
+                .map(|t| t.to_string())
+                .ok_or_else(|| format!("Source for datasource {} using 'query' needs an alias", datasource_id))?,
+        };
+        if !is_valid_alias(&alias) {
+            return Err(format!("Invalid alias '{}': use letters, digits and underscores", alias));
+        }
+        if parsed.iter().any(|p| p.alias.eq_ignore_ascii_case(&alias)) {
+            return Err(format!("Duplicate alias '{}'", alias));
+        }
+
+        let limit = source
+            .get("limit")
+            .and_then(|v| v.as_i64())
+            .map(|v| v.clamp(1, MAX_SOURCE_ROW_LIMIT as i64) as i32)
+            .unwrap_or(DEFAULT_SOURCE_ROW_LIMIT);
+
+        parsed.push(FederatedSource {
+            datasource_id: datasource_id.to_string(),
+            alias,
+            extract_query,
+            limit,
+        });
+    }
+
+    Ok(parsed)
+}
+
+/// `query` behind the settings that cut DuckDB off from the host
+fn sandboxed(query: &str) -> String {
+    format!("{}{}", SANDBOX_SETTINGS, query)
+}
+
+fn is_valid_alias(alias: &str) -> bool {
+    let mut chars = alias.chars();
+    matches!(chars.next(), Some(c) if c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+/// Accept `table` or `schema.table` made of plain identifier characters
+fn is_valid_table_reference(table: &str) -> bool {
+    let parts: Vec<&str> = table.split('.').collect();
+    parts.len() <= 2
+        && parts.iter().all(|part| {
+            let mut chars = part.chars();
+            matches!(chars.next(), Some(c) if c.is_ascii_alphabetic() || c == '_')
+                && chars.all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '$')
+        })
+}
+
+/// Write a connector result to CSV so DuckDB can load it, returning the row count.
+/// NULL cells become empty fields; text, including the text "NULL", is kept as is.
+fn write_extract_csv(
+    result: &Value,
+    path: &Path,
+) -> Result<usize, Box<dyn std::error::Error + Send + Sync>> {
+    let columns: Vec<String> = result
+        .get("columns")
+        .and_then(|c| c.as_array())
+        .map(|cols| cols.iter().filter_map(|c| c.as_str()).map(|s| s.to_string()).collect())
+        .unwrap_or_default();
+
+    if columns.is_empty() {
+        return Err("extract returned no columns".into());
+    }
+
+    let to_field = |value: &Value| -> String {
+        match value {
+            Value::Null => String::new(),
+            Value::String(s) => s.clone(),
+            other => other.to_string(),
+        }
+    };
+
+    let mut writer = csv::Writer::from_path(path)?;
+    writer.write_record(&columns)?;
+
+    let rows = result.get("rows").and_then(|r| r.as_array()).cloned().unwrap_or_default();
+    for row in &rows {
+        let record: Vec<String> = match row {
+            Value::Array(values) => values.iter().map(to_field).collect(),
+            Value::Object(obj) => columns
+                .iter()
+                .map(|col| obj.get(col).map(to_field).unwrap_or_default())
+                .collect(),
+            other => vec![to_field(other)],
+        };
+        writer.write_record(&record)?;
+    }
+    writer.flush()?;
+
+    Ok(rows.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn model_sql_runs_after_the_sandbox_settings() {
+        let query = sandboxed("SELECT * FROM read_text('/etc/passwd')");
+        assert!(query.starts_with("SET enable_external_access = false; SET lock_configuration = true; "));
+        assert!(query.ends_with("SELECT * FROM read_text('/etc/passwd')"));
+    }
+
+    #[test]
+    fn extract_csv_keeps_the_text_null() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("extract.csv");
+        let result = json!({
+            "columns": ["name", "note"],
+            "rows": [["NULL", null], ["Ann", "x"]]
+        });
+
+        assert_eq!(write_extract_csv(&result, &path).unwrap(), 2);
+        let csv = std::fs::read_to_string(&path).unwrap();
+        assert_eq!(csv, "name,note\nNULL,\nAnn,x\n");
+    }
+}
@@ -2,6 +2,7 @@ use crate::core::mcp::handlers::base::McpHandlers;
 use crate::core::mcp::types::*;
 use crate::models::file_upload::FileUpload;
 use crate::utils::content_extractor::ContentExtractor;
+use crate::utils::storage::{storage, upload_key};
 use chrono::Utc;
 use serde_json::json;
 use std::path::Path;
@@ -87,25 +88,11 @@ impl McpHandlers {
         // Download the file with size limits
         let download_result = self.download_file_with_limits(url, &temp_path).await?;
 
-        // Move to permanent storage
-        let upload_dir = format!(".clients/{}/{}/uploads", self.client_id, self.project_id);
-        std::fs::create_dir_all(&upload_dir).map_err(|e| JsonRpcError {
-            code: INTERNAL_ERROR,
-            message: format!("Failed to create upload directory: {}", e),
-            data: None,
-        })?;
-
-        let final_path = format!("{}/{}", upload_dir, stored_filename);
-        std::fs::rename(&temp_path, &final_path).map_err(|e| JsonRpcError {
-            code: INTERNAL_ERROR,
-            message: format!("Failed to move file: {}", e),
-            data: None,
-        })?;
-
-        // Extract content if requested and file is small enough
+        // Extract content if requested and file is small enough (from the local download,
+        // before it moves to the storage backend)
         let (extracted_content, file_content, metadata) = if auto_extract && download_result.size_bytes < 10 * 1024 * 1024 {
             match ContentExtractor::extract_content(
-                Path::new(&final_path),
+                Path::new(&temp_path),
                 &original_name,
                 &download_result.content_type,
             ).await {
@@ -123,6 +110,25 @@ impl McpHandlers {
             (None, None, None)
         };
 
+        // Move to permanent storage
+        let storage = storage();
+        let storage_key = upload_key(&self.client_id, &self.project_id, &stored_filename);
+        let data = std::fs::read(&temp_path).map_err(|e| JsonRpcError {
+            code: INTERNAL_ERROR,
+            message: format!("Failed to read downloaded file: {}", e),
+            data: None,
+        })?;
+        let _ = std::fs::remove_file(&temp_path);
+        storage
+            .put(&storage_key, data, Some(&download_result.content_type))
+            .await
+            .map_err(|e| JsonRpcError {
+                code: INTERNAL_ERROR,
+                message: format!("Failed to store file: {}", e),
+                data: None,
+            })?;
+        let final_path = storage.location(&storage_key);
+
         // Parse client UUID
         let client_uuid = Uuid::parse_str(&self.client_id).map_err(|e| JsonRpcError {
             code: INVALID_PARAMS,
@@ -148,6 +154,9 @@ impl McpHandlers {
             file_content,
             metadata,
             uploaded_by: None, // uploaded_by is Uuid, we'll leave it as None for AI downloads
+            extraction_status: "ready".to_string(),
+            extraction_progress: 100,
+            archive_id: None,
             created_at: Utc::now(),
             updated_at: Utc::now(),
         };
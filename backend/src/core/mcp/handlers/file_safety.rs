@@ -2,8 +2,8 @@ use crate::core::mcp::handlers::base::McpHandlers;
 use crate::core::mcp::types::*;
 use crate::models::file_upload::FileUpload;
 use crate::utils::content_extractor::ContentExtractor;
+use crate::utils::storage::storage;
 use serde_json::json;
-use std::fs;
 
 impl McpHandlers {
     /// Safe file_read implementation with large file protection
@@ -58,7 +58,7 @@ impl McpHandlers {
         let max_size_mb = limits.max_full_parse_size as f64 / (1024.0 * 1024.0);
 
         // Check if file is too large
-        let is_large_file = file.file_size as u64 > limits.max_full_parse_size;
+        let is_large_file = limits.is_large_file(file.file_size as u64);
 
         let response = if is_large_file {
             // File is too large - provide helpful guidance instead of trying to read it
@@ -148,21 +148,23 @@ impl McpHandlers {
                 }
             })
         } else {
-            // Try reading from filesystem only for small files
-            // Double-check file size on disk to prevent accidents
-            let metadata = fs::metadata(&file.file_path).map_err(|_| JsonRpcError {
+            // Try reading from storage only for small files
+            // Double-check the stored size to prevent accidents
+            let storage = storage();
+            let storage_key = storage.key_from_location(&file.file_path);
+            let stored_size = storage.size(&storage_key).await.map_err(|_| JsonRpcError {
                 code: INTERNAL_ERROR,
-                message: "Unable to access file on filesystem".to_string(),
+                message: "Unable to access file in storage".to_string(),
                 data: None,
             })?;
 
-            if metadata.len() > limits.max_full_parse_size {
+            if stored_size > limits.max_full_parse_size {
                 // File on disk is too large - don't attempt to read
                 json!({
                     "status": "partial",
                     "message": format!(
                         "File too large to read directly ({}MB exceeds {}MB limit)",
-                        (metadata.len() as f64 / (1024.0 * 1024.0)).round(),
+                        (stored_size as f64 / (1024.0 * 1024.0)).round(),
                         max_size_mb.round()
                     ),
                     "file": {
@@ -188,7 +190,7 @@ impl McpHandlers {
 
                 if is_image {
                     // For image files, read as binary and encode as base64
-                    match fs::read(&file.file_path) {
+                    match storage.get(&storage_key).await {
                         Ok(binary_data) => {
                             use base64::{Engine as _, engine::general_purpose};
                             let base64_content = general_purpose::STANDARD.encode(&binary_data);
@@ -225,8 +227,8 @@ impl McpHandlers {
                     }
                 } else {
                     // For text files, read as string
-                    match fs::read_to_string(&file.file_path) {
-                        Ok(content) => json!({
+                    match storage.get(&storage_key).await.map(String::from_utf8) {
+                        Ok(Ok(content)) => json!({
                             "status": "success",
                             "message": "File content retrieved from filesystem",
                             "file": {
@@ -241,7 +243,7 @@ impl McpHandlers {
                                 "created_at": file.created_at
                             }
                         }),
-                        Err(_) => json!({
+                        _ => json!({
                             "status": "error",
                             "message": "File is binary or cannot be read as text",
                             "file": {
@@ -0,0 +1,89 @@
+use super::base::McpHandlers;
+use crate::core::datasources::shared_service;
+use crate::core::mcp::types::*;
+use crate::utils::datasource::common::metadata_query::{validate_metadata_query, MetadataDialect};
+use crate::utils::datasource::create_connector;
+use serde_json::{json, Value};
+
+const DEFAULT_METADATA_ROWS: i64 = 100;
+const MAX_METADATA_ROWS: i64 = 1_000;
+
+impl McpHandlers {
+    /// Read-only query against the datasource's system catalogs (row counts, index
+    /// usage, last analyze time ...). Queries that reach user tables are rejected.
+    pub async fn handle_schema_metadata_query(
+        &self,
+        args: &serde_json::Map<String, Value>,
+    ) -> Result<String, JsonRpcError> {
+        let invalid = |message: &str| JsonRpcError {
+            code: INVALID_PARAMS,
+            message: message.to_string(),
+            data: None,
+        };
+
+        let datasource_id = args
+            .get("datasource_id")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| invalid("Missing required parameter: datasource_id"))?;
+        let query = args
+            .get("query")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| invalid("Missing required parameter: query"))?;
+        let limit = args
+            .get("limit")
+            .and_then(|v| v.as_i64())
+            .map(|v| v.clamp(1, MAX_METADATA_ROWS))
+            .unwrap_or(DEFAULT_METADATA_ROWS);
+
+        let datasource = shared_service::get_datasource_with_validation(
+            datasource_id,
+            &self.project_id,
+            &self.db_pool,
+        )
+        .await
+        .map_err(|e| invalid(&format!("Failed to get datasource: {}", e)))?;
+
+        let dialect = MetadataDialect::from_source_type(&datasource.source_type).ok_or_else(|| {
+            invalid(&format!(
+                "schema_metadata_query is not available for {} datasources",
+                datasource.source_type
+            ))
+        })?;
+        validate_metadata_query(query, dialect).map_err(|e| invalid(&e))?;
+
+        let result: Value = self
+            .execute_db_operation("schema_metadata_query", async {
+                let mut config_with_id = datasource.connection_config.clone();
+                if let Some(config_obj) = config_with_id.as_object_mut() {
+                    config_obj.insert("id".to_string(), Value::String(datasource_id.to_string()));
+                }
+
+                let connector = create_connector(&datasource.source_type, &config_with_id)
+                    .await
+                    .map_err(|e| format!("Failed to create connector: {}", e))?;
+
+                let result = connector
+                    .execute_read_only_query(query, limit as i32)
+                    .await
+                    .map_err(|e| format!("Query execution failed: {}", e))?;
+                Ok(result)
+            })
+            .await?;
+
+        let response = json!({
+            "datasource": { "id": datasource_id, "name": datasource.name, "type": datasource.source_type },
+            "query": query,
+            "columns": result.get("columns").cloned().unwrap_or_else(|| json!([])),
+            "rows": result.get("rows").cloned().unwrap_or_else(|| json!([])),
+            "row_count": result.get("row_count").cloned().unwrap_or(Value::Null),
+            "limit": limit,
+            "allowed_catalogs": dialect.catalog_description(),
+        });
+
+        serde_json::to_string(&response).map_err(|e| JsonRpcError {
+            code: INTERNAL_ERROR,
+            message: format!("Failed to serialize response: {}", e),
+            data: None,
+        })
+    }
+}
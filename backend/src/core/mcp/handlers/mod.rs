@@ -1,11 +1,21 @@
+pub mod ask_user_choice;
 pub mod base;
+pub mod connection_urls;
 pub mod datasource;
 pub mod excel;
+pub mod explain;
+pub mod federated;
 pub mod file_download;
 pub mod file_operations;
 pub mod file_safety;
 pub mod interaction;
+pub mod metadata;
+pub mod query_export;
+pub mod resource_limits;
 pub mod schema;
+pub mod schema_columns;
+pub mod schema_diff;
+pub mod tool_cache;
 pub mod tools;
 
 pub use base::McpHandlers;
@@ -0,0 +1,211 @@
+use super::base::McpHandlers;
+use super::datasource::is_read_statement;
+use crate::core::datasources::shared_service;
+use crate::core::mcp::types::*;
+use crate::utils::datasource::create_connector;
+use serde_json::{json, Value};
+
+const DEFAULT_EXPORT_ROWS: i64 = 10_000;
+// Well under Excel's 1,048,576 row sheet limit, and what fits comfortably in memory
+const MAX_EXPORT_ROWS: i64 = 100_000;
+
+impl McpHandlers {
+    /// Run a read-only query and hand its rows to `export_excel`, returning the download URL
+    pub async fn handle_data_query_export(
+        &self,
+        args: &serde_json::Map<String, Value>,
+    ) -> Result<String, JsonRpcError> {
+        let invalid = |message: &str| JsonRpcError {
+            code: INVALID_PARAMS,
+            message: message.to_string(),
+            data: None,
+        };
+
+        let datasource_id = args
+            .get("datasource_id")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| invalid("Missing required parameter: datasource_id"))?;
+        let query = args
+            .get("query")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| invalid("Missing required parameter: query"))?;
+        if !is_read_statement(query) {
+            return Err(invalid("data_query_export only exports read-only queries"));
+        }
+
+        let max_rows = args
+            .get("max_rows")
+            .and_then(|v| v.as_i64())
+            .map(|v| v.clamp(1, MAX_EXPORT_ROWS))
+            .unwrap_or(DEFAULT_EXPORT_ROWS);
+        let filename = export_filename(args.get("filename").and_then(|v| v.as_str()));
+        let sheet_name = args
+            .get("sheet_name")
+            .and_then(|v| v.as_str())
+            .filter(|name| !name.trim().is_empty())
+            .unwrap_or("Query Results");
+
+        let datasource = shared_service::get_datasource_with_validation(
+            datasource_id,
+            &self.project_id,
+            &self.db_pool,
+        )
+        .await
+        .map_err(|e| invalid(&format!("Failed to get datasource: {}", e)))?;
+
+        let result: Value = self
+            .execute_db_operation("data_query_export", async {
+                let mut config_with_id = datasource.connection_config.clone();
+                if let Some(config_obj) = config_with_id.as_object_mut() {
+                    config_obj.insert("id".to_string(), Value::String(datasource_id.to_string()));
+                }
+
+                let connector = create_connector(&datasource.source_type, &config_with_id)
+                    .await
+                    .map_err(|e| format!("Failed to create connector: {}", e))?;
+
+                // One extra row tells us whether max_rows cut the result short
+                let result = connector
+                    .execute_read_only_query(query, (max_rows + 1) as i32)
+                    .await
+                    .map_err(|e| format!("Query execution failed: {}", e))?;
+                Ok(result)
+            })
+            .await?;
+
+        let (columns, mut rows) = result_to_table(&result);
+        let truncated = rows.len() as i64 > max_rows;
+        rows.truncate(max_rows as usize);
+        let row_count = rows.len();
+
+        let mut export_args = serde_json::Map::new();
+        export_args.insert("filename".to_string(), json!(filename));
+        export_args.insert(
+            "sheets".to_string(),
+            json!([{ "name": sheet_name, "headers": columns, "data": rows }]),
+        );
+        export_args.insert(
+            "options".to_string(),
+            json!({ "auto_filter": true, "freeze_panes": { "row": 1, "col": 0 } }),
+        );
+
+        let export = self.handle_export_excel(&export_args).await?;
+        let mut export: Value = serde_json::from_str(&export).map_err(|e| JsonRpcError {
+            code: INTERNAL_ERROR,
+            message: format!("Failed to parse export_excel response: {}", e),
+            data: None,
+        })?;
+
+        if let Some(obj) = export.as_object_mut() {
+            if obj.get("status").and_then(|s| s.as_str()) == Some("success") {
+                obj.insert(
+                    "message".to_string(),
+                    json!(format!("Exported {} rows to {}.xlsx", row_count, filename)),
+                );
+            }
+            obj.insert(
+                "datasource".to_string(),
+                json!({ "id": datasource_id, "name": datasource.name }),
+            );
+            obj.insert("query".to_string(), json!(query));
+            obj.insert("row_count".to_string(), json!(row_count));
+            obj.insert("column_count".to_string(), json!(columns.len()));
+            obj.insert("max_rows".to_string(), json!(max_rows));
+            obj.insert("truncated".to_string(), json!(truncated));
+        }
+
+        serde_json::to_string(&export).map_err(|e| JsonRpcError {
+            code: INTERNAL_ERROR,
+            message: format!("Failed to serialize response: {}", e),
+            data: None,
+        })
+    }
+}
+
+/// Keep the requested name to characters that are safe in a storage key
+fn export_filename(requested: Option<&str>) -> String {
+    let cleaned: String = requested
+        .unwrap_or("")
+        .trim()
+        .trim_end_matches(".xlsx")
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '-' || c == '_' { c } else { '_' })
+        .take(100)
+        .collect();
+    let cleaned = cleaned.trim_matches('_');
+
+    if cleaned.is_empty() {
+        format!("query_export_{}", chrono::Utc::now().format("%Y%m%d_%H%M%S"))
+    } else {
+        cleaned.to_string()
+    }
+}
+
+/// Columns and rows as arrays, whether the connector returned row arrays or objects.
+/// Only JSON null becomes an empty cell; the text "NULL" is real data.
+pub(super) fn result_to_table(result: &Value) -> (Vec<String>, Vec<Vec<Value>>) {
+    let columns: Vec<String> = result
+        .get("columns")
+        .and_then(|c| c.as_array())
+        .map(|cols| cols.iter().filter_map(|c| c.as_str()).map(|s| s.to_string()).collect())
+        .unwrap_or_default();
+
+    let rows = result
+        .get("rows")
+        .and_then(|r| r.as_array())
+        .map(|rows| {
+            rows.iter()
+                .map(|row| match row {
+                    Value::Array(values) => values.clone(),
+                    Value::Object(obj) => columns
+                        .iter()
+                        .map(|col| obj.get(col).cloned().unwrap_or(Value::Null))
+                        .collect(),
+                    other => vec![other.clone()],
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+
+    (columns, rows)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_select_queries_are_exported() {
+        assert!(is_read_statement("SELECT * FROM orders"));
+        assert!(is_read_statement("  with recent AS (SELECT 1) SELECT * FROM recent"));
+        assert!(!is_read_statement("DELETE FROM orders"));
+        assert!(!is_read_statement("UPDATE orders SET total = 0"));
+    }
+
+    #[test]
+    fn filenames_are_safe_storage_keys() {
+        assert_eq!(export_filename(Some("Q3 revenue.xlsx")), "Q3_revenue");
+        assert_eq!(export_filename(Some("../../etc/passwd")), "etc_passwd");
+        assert!(export_filename(None).starts_with("query_export_"));
+        assert!(export_filename(Some("///")).starts_with("query_export_"));
+    }
+
+    #[test]
+    fn rows_become_arrays_in_column_order() {
+        let result = json!({
+            "columns": ["id", "name"],
+            "rows": [
+                [1, "Ada"],
+                { "name": "Grace", "id": 2 },
+                [3, null],
+                [4, "NULL"]
+            ]
+        });
+
+        let (columns, rows) = result_to_table(&result);
+        assert_eq!(columns, vec!["id", "name"]);
+        assert_eq!(rows[1], vec![json!(2), json!("Grace")]);
+        assert_eq!(rows[2], vec![json!(3), Value::Null]);
+        assert_eq!(rows[3], vec![json!(4), json!("NULL")]);
+    }
+}
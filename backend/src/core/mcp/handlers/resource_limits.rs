@@ -0,0 +1,153 @@
+//! Caps on `resources/read` responses
+//!
+//! A datasource resource carries the stored schema, which for large databases
+//! runs to megabytes. When the serialized resource is over `max_bytes` it is
+//! replaced by a summary: table count and the first `summary_tables` tables
+//! with their column counts, marked `truncated: true` and pointing at
+//! `schema_get` for the details. Reads that take longer than `timeout` fail.
+
+use std::time::Duration;
+
+use serde_json::{json, Value};
+
+use crate::core::analysis::schema_pins::normalize_schema;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ResourceLimits {
+    pub max_bytes: usize,
+    pub summary_tables: usize,
+    pub timeout: Duration,
+}
+
+impl Default for ResourceLimits {
+    fn default() -> Self {
+        Self {
+            max_bytes: 256 * 1024,
+            summary_tables: 50,
+            timeout: Duration::from_secs(10),
+        }
+    }
+}
+
+impl ResourceLimits {
+    /// Defaults overridden by `MCP_RESOURCE_MAX_BYTES`,
+    /// `MCP_RESOURCE_SUMMARY_TABLES` and `MCP_RESOURCE_TIMEOUT_SECS`
+    pub fn from_env() -> Self {
+        let defaults = Self::default();
+        let env_u64 = |name: &str| {
+            std::env::var(name)
+                .ok()
+                .and_then(|v| v.parse::<u64>().ok())
+                .filter(|v| *v > 0)
+        };
+
+        Self {
+            max_bytes: env_u64("MCP_RESOURCE_MAX_BYTES").map_or(defaults.max_bytes, |v| v as usize),
+            summary_tables: env_u64("MCP_RESOURCE_SUMMARY_TABLES").map_or(defaults.summary_tables, |v| v as usize),
+            timeout: env_u64("MCP_RESOURCE_TIMEOUT_SECS").map_or(defaults.timeout, Duration::from_secs),
+        }
+    }
+}
+
+/// A datasource resource's JSON text, summarized when the full record is over
+/// the byte cap
+pub fn datasource_resource_text(
+    id: &str,
+    name: &str,
+    source_type: &str,
+    schema_info: Option<Value>,
+    table_list: Option<Value>,
+    limits: &ResourceLimits,
+) -> String {
+    let full = json!({
+        "id": id,
+        "name": name,
+        "source_type": source_type,
+        "truncated": false,
+        "schema_info": schema_info,
+        "table_list": table_list,
+    });
+    let text = serde_json::to_string_pretty(&full).unwrap_or_else(|_| full.to_string());
+    if text.len() <= limits.max_bytes {
+        return text;
+    }
+
+    // Column counts come from the schema; table_list only has names
+    let schema = schema_info.as_ref().map(normalize_schema).unwrap_or_default();
+    let tables: Vec<(String, Option<usize>)> = if schema.is_empty() {
+        table_list
+            .as_ref()
+            .and_then(|list| list.as_array())
+            .map(|list| list.iter().filter_map(|t| t.as_str()).map(|t| (t.to_string(), None)).collect())
+            .unwrap_or_default()
+    } else {
+        schema.iter().map(|(table, columns)| (table.clone(), Some(columns.len()))).collect()
+    };
+
+    let shown: Vec<Value> = tables
+        .iter()
+        .take(limits.summary_tables)
+        .map(|(table, columns)| json!({ "name": table, "column_count": columns }))
+        .collect();
+    let summary = json!({
+        "id": id,
+        "name": name,
+        "source_type": source_type,
+        "truncated": true,
+        "full_size_bytes": text.len(),
+        "max_bytes": limits.max_bytes,
+        "table_count": tables.len(),
+        "tables": shown,
+        "tables_omitted": tables.len().saturating_sub(limits.summary_tables),
+        "note": format!(
+            "The full schema is too large to return here. Use schema_get with datasource_id \"{}\" and a table name for its columns, or schema_search to find tables.",
+            id
+        ),
+    });
+    serde_json::to_string_pretty(&summary).unwrap_or_else(|_| summary.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn large_schema(tables: usize) -> Value {
+        let tables: serde_json::Map<String, Value> = (0..tables)
+            .map(|t| {
+                let columns: Vec<Value> = (0..30)
+                    .map(|c| json!({ "column_name": format!("column_{}", c), "data_type": "character varying" }))
+                    .collect();
+                (format!("table_{:04}", t), Value::Array(columns))
+            })
+            .collect();
+        json!({ "tables": tables })
+    }
+
+    #[test]
+    fn large_schemas_are_summarized() {
+        let limits = ResourceLimits { max_bytes: 64 * 1024, summary_tables: 10, ..Default::default() };
+        let table_list = json!((0..2000).map(|t| format!("table_{:04}", t)).collect::<Vec<_>>());
+        let text = datasource_resource_text("ds-1", "Warehouse", "postgresql", Some(large_schema(2000)), Some(table_list), &limits);
+
+        assert!(text.len() <= limits.max_bytes);
+        let summary: Value = serde_json::from_str(&text).unwrap();
+        assert_eq!(summary["truncated"], json!(true));
+        assert_eq!(summary["table_count"], json!(2000));
+        assert_eq!(summary["tables"].as_array().unwrap().len(), 10);
+        assert_eq!(summary["tables"][0], json!({ "name": "table_0000", "column_count": 30 }));
+        assert_eq!(summary["tables_omitted"], json!(1990));
+        assert!(summary["note"].as_str().unwrap().contains("schema_get"));
+        assert!(summary.get("schema_info").is_none());
+    }
+
+    #[test]
+    fn small_schemas_are_returned_whole() {
+        let limits = ResourceLimits::default();
+        let schema = large_schema(2);
+        let text = datasource_resource_text("ds-1", "Orders", "mysql", Some(schema.clone()), None, &limits);
+
+        let content: Value = serde_json::from_str(&text).unwrap();
+        assert_eq!(content["truncated"], json!(false));
+        assert_eq!(content["schema_info"], schema);
+    }
+}
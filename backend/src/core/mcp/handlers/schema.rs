@@ -1,5 +1,9 @@
 use super::base::McpHandlers;
+use super::connection_urls;
+use crate::core::mcp::logging::{mcp_log, LogFields, LogLevel};
 use crate::core::mcp::types::*;
+use crate::utils::datasource::common::read_replica::validate_replica_settings;
+use crate::utils::datasource::common::redaction::redact_connection_config;
 use crate::utils::datasource::create_connector;
 use serde_json::{json, Value};
 
@@ -417,6 +421,22 @@ impl McpHandlers {
         &self,
         config: &Value,
         source_type: &str,
+    ) -> Result<Value, JsonRpcError> {
+        let parsed = self.parse_connection_config_value(config, source_type)?;
+        if matches!(source_type, "postgresql" | "postgres") {
+            validate_replica_settings(&parsed).map_err(|message| JsonRpcError {
+                code: INVALID_PARAMS,
+                message,
+                data: None,
+            })?;
+        }
+        Ok(parsed)
+    }
+
+    fn parse_connection_config_value(
+        &self,
+        config: &Value,
+        source_type: &str,
     ) -> Result<Value, JsonRpcError> {
         match config {
             Value::String(string_value) => {
@@ -450,36 +470,56 @@ impl McpHandlers {
         url: &str,
         source_type: &str,
     ) -> Result<serde_json::Map<String, Value>, JsonRpcError> {
-        eprintln!("🔗 parse_connection_url called with:");
-        eprintln!("   url: '{}'", url);
-        eprintln!("   source_type: '{}'", source_type);
-        
+        // Connection URLs and parsed configs carry credentials; log them masked
+        let redacted_url = redact_connection_config(&json!(url));
+        self.log_url_parsing(format!("Parsing {} connection URL {}", source_type, redacted_url));
+
+
         let config = match source_type {
             "postgresql" | "postgres" => {
-                eprintln!("   Attempting PostgreSQL URL parsing");
+                self.log_url_parsing("Attempting PostgreSQL URL parsing");
                 self.parse_postgres_url(url)
             },
             "mysql" => {
-                eprintln!("   Attempting MySQL URL parsing");
+                self.log_url_parsing("Attempting MySQL URL parsing");
                 self.parse_mysql_url(url)
             },
             "clickhouse" => {
-                eprintln!("   Attempting ClickHouse URL parsing");
+                self.log_url_parsing("Attempting ClickHouse URL parsing");
                 self.parse_clickhouse_url(url)
             },
+            "oracle" => {
+                self.log_url_parsing("Attempting Oracle URL parsing");
+                connection_urls::parse_oracle_url(url)
+            },
+            "sqlserver" | "mssql" => {
+                self.log_url_parsing("Attempting SQL Server URL parsing");
+                connection_urls::parse_sqlserver_url(url)
+            },
+            "mongodb" | "mongo" => {
+                // Replica set hosts and driver options don't fit host/port fields; keep the URI whole
+                (url.starts_with("mongodb://") || url.starts_with("mongodb+srv://")).then(|| {
+                    let mut config = serde_json::Map::new();
+                    config.insert("url".to_string(), json!(url));
+                    config
+                })
+            },
             _ => {
-                eprintln!("   Attempting generic URL parsing");
+                self.log_url_parsing("Attempting generic URL parsing");
                 self.parse_generic_url(url)
             },
         };
 
         match config {
             Some(c) => {
-                eprintln!("✅ URL parsing succeeded: {:?}", c);
+                self.log_url_parsing(format!(
+                    "URL parsing succeeded: {}",
+                    redact_connection_config(&Value::Object(c.clone()))
+                ));
                 Ok(c)
             },
             None => {
-                eprintln!("❌ URL parsing failed for '{}' ({})", url, source_type);
+                self.log_url_parsing(format!("URL parsing failed for {} ({})", redacted_url, source_type));
                 Err(JsonRpcError {
                     code: INVALID_PARAMS,
                     message: format!("Invalid connection URL format for {}", source_type),
@@ -489,6 +529,13 @@ impl McpHandlers {
         }
     }
 
+    fn log_url_parsing(&self, message: impl Into<String>) {
+        mcp_log(
+            LogLevel::Debug,
+            LogFields::for_project(&self.project_id).operation("parse_connection_url").message(message),
+        );
+    }
+
     pub fn parse_postgres_url(&self, url: &str) -> Option<serde_json::Map<String, Value>> {
         let re = regex::Regex::new(
             r"postgres(?:ql)?://(?:([^:]+)(?::([^@]+))?@)?([^:/]+)(?::(\d+))?/([^?]+)(?:\?(.+))?",
@@ -547,31 +594,7 @@ impl McpHandlers {
     }
 
     pub fn parse_clickhouse_url(&self, url: &str) -> Option<serde_json::Map<String, Value>> {
-        let re = regex::Regex::new(
-            r"clickhouse://(?:([^:]+)(?::([^@]+))?@)?([^:/]+)(?::(\d+))?/([^?]+)(?:\?(.+))?",
-        )
-        .ok()?;
-        let caps = re.captures(url)?;
-
-        let mut config = serde_json::Map::new();
-        config.insert("host".to_string(), json!(caps.get(3)?.as_str()));
-        config.insert("database".to_string(), json!(caps.get(5)?.as_str()));
-
-        if let Some(user) = caps.get(1) {
-            config.insert("user".to_string(), json!(user.as_str()));
-        }
-        if let Some(password) = caps.get(2) {
-            config.insert("password".to_string(), json!(password.as_str()));
-        }
-        if let Some(port) = caps.get(4) {
-            if let Ok(port_num) = port.as_str().parse::<u16>() {
-                config.insert("port".to_string(), json!(port_num));
-            }
-        } else {
-            config.insert("port".to_string(), json!(8123));
-        }
-
-        Some(config)
+        connection_urls::parse_clickhouse_url(url)
     }
 
     pub fn parse_generic_url(&self, url: &str) -> Option<serde_json::Map<String, Value>> {
@@ -606,44 +629,6 @@ impl McpHandlers {
         config: &serde_json::Map<String, Value>,
         source_type: &str,
     ) -> Option<String> {
-        let host = config.get("host")?.as_str()?;
-        let database = config.get("database")?.as_str()?;
-
-        let user = config.get("user").and_then(|v| v.as_str()).unwrap_or("");
-        let password = config
-            .get("password")
-            .and_then(|v| v.as_str())
-            .unwrap_or("");
-        let port =
-            config
-                .get("port")
-                .and_then(|v| v.as_u64())
-                .unwrap_or(match source_type {
-                    "postgresql" | "postgres" => 5432,
-                    "mysql" => 3306,
-                    "clickhouse" => 8123,
-                    _ => 5432,
-                });
-
-        let scheme = match source_type {
-            "postgresql" | "postgres" => "postgresql",
-            "mysql" => "mysql",
-            "clickhouse" => "clickhouse",
-            _ => source_type,
-        };
-
-        if user.is_empty() {
-            Some(format!("{}://{}:{}/{}", scheme, host, port, database))
-        } else if password.is_empty() {
-            Some(format!(
-                "{}://{}@{}:{}/{}",
-                scheme, user, host, port, database
-            ))
-        } else {
-            Some(format!(
-                "{}://{}:{}@{}:{}/{}",
-                scheme, user, password, host, port, database
-            ))
-        }
+        connection_urls::construct_connection_url(config, source_type)
     }
 }
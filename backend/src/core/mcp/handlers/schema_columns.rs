@@ -0,0 +1,80 @@
+use super::base::McpHandlers;
+use crate::core::datasources::shared_service;
+use crate::core::mcp::types::*;
+use crate::utils::datasource::create_connector;
+use serde_json::{json, Value};
+
+impl McpHandlers {
+    /// Columns of one table straight from the datasource catalog: a cheaper
+    /// answer than `schema_get` when only names and types are needed
+    pub async fn handle_schema_columns(
+        &self,
+        args: &serde_json::Map<String, Value>,
+    ) -> Result<String, JsonRpcError> {
+        let invalid = |message: &str| JsonRpcError {
+            code: INVALID_PARAMS,
+            message: message.to_string(),
+            data: None,
+        };
+
+        let datasource_id = args
+            .get("datasource_id")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| invalid("Missing required parameter: datasource_id"))?;
+        let table = args
+            .get("table")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| invalid("Missing required parameter: table"))?;
+
+        let datasource = shared_service::get_datasource_with_validation(
+            datasource_id,
+            &self.project_id,
+            &self.db_pool,
+        )
+        .await
+        .map_err(|e| invalid(&format!("Failed to get datasource: {}", e)))?;
+
+        let columns = self
+            .execute_db_operation("schema_columns", async {
+                let mut config_with_id = datasource.connection_config.clone();
+                if let Some(config_obj) = config_with_id.as_object_mut() {
+                    config_obj.insert("id".to_string(), Value::String(datasource_id.to_string()));
+                }
+
+                let connector = create_connector(&datasource.source_type, &config_with_id)
+                    .await
+                    .map_err(|e| format!("Failed to create connector: {}", e))?;
+
+                let columns = connector
+                    .list_table_columns(table)
+                    .await
+                    .map_err(|e| format!("Failed to list columns: {}", e))?;
+                Ok(columns)
+            })
+            .await?;
+
+        if columns.is_empty() {
+            return Err(invalid(&format!(
+                "Table '{}' not found in datasource {}; use schema_search to find table names",
+                table, datasource_id
+            )));
+        }
+
+        // Compact rows keep the response small; the keys are listed once
+        let response = json!({
+            "datasource_id": datasource_id,
+            "table": table,
+            "fields": ["name", "type", "nullable", "primary_key"],
+            "columns": columns
+                .iter()
+                .map(|c| json!([c.name, c.data_type, c.nullable, c.primary_key]))
+                .collect::<Vec<_>>(),
+        });
+
+        serde_json::to_string(&response).map_err(|e| JsonRpcError {
+            code: INTERNAL_ERROR,
+            message: format!("Failed to serialize response: {}", e),
+            data: None,
+        })
+    }
+}
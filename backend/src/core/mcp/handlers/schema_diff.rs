@@ -0,0 +1,65 @@
+use super::base::McpHandlers;
+use crate::core::datasources::schema_diff::stored_schema_diff;
+use crate::core::datasources::shared_service;
+use crate::core::mcp::types::*;
+use serde_json::{json, Value};
+
+impl McpHandlers {
+    /// Tables and columns added, removed or retyped between the datasource's
+    /// last two inspections, read from the stored snapshots
+    pub async fn handle_schema_diff(
+        &self,
+        args: &serde_json::Map<String, Value>,
+    ) -> Result<String, JsonRpcError> {
+        let datasource_id = args
+            .get("datasource_id")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| JsonRpcError {
+                code: INVALID_PARAMS,
+                message: "Missing required parameter: datasource_id".to_string(),
+                data: None,
+            })?;
+
+        let datasource = shared_service::get_datasource_with_validation(
+            datasource_id,
+            &self.project_id,
+            &self.db_pool,
+        )
+        .await
+        .map_err(|e| JsonRpcError {
+            code: INVALID_PARAMS,
+            message: format!("Failed to get datasource: {}", e),
+            data: None,
+        })?;
+
+        let diff = stored_schema_diff(&self.db_pool, datasource_id)
+            .await
+            .map_err(|e| JsonRpcError {
+                code: INTERNAL_ERROR,
+                message: format!("Failed to load schema snapshots: {}", e),
+                data: None,
+            })?;
+
+        let response = match diff {
+            Some(diff) => json!({
+                "datasource": { "id": datasource_id, "name": datasource.name },
+                "has_previous_snapshot": true,
+                "changed": !diff.is_empty(),
+                "diff": diff,
+            }),
+            None => json!({
+                "datasource": { "id": datasource_id, "name": datasource.name },
+                "has_previous_snapshot": false,
+                "changed": false,
+                "diff": Value::Null,
+                "message": "No earlier snapshot to compare with. Run datasource_inspect to record the current schema, then again after the database changes.",
+            }),
+        };
+
+        serde_json::to_string(&response).map_err(|e| JsonRpcError {
+            code: INTERNAL_ERROR,
+            message: format!("Failed to serialize response: {}", e),
+            data: None,
+        })
+    }
+}
@@ -0,0 +1,190 @@
+use crate::utils::config::Config;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Turns older than this are dropped even if no later call touched them
+const TURN_CACHE_TTL: Duration = Duration::from_secs(300);
+
+/// Read-only tools whose results can be reused for identical arguments within a turn
+const CACHEABLE_TOOLS: &[&str] = &[
+    "datasource_list",
+    "datasource_detail",
+    "schema_get",
+    "schema_columns",
+    "schema_search",
+    "schema_related",
+    "schema_stats",
+    "context_read",
+];
+
+/// Read-only tools whose results aren't cached. Every tool outside this list
+/// and `CACHEABLE_TOOLS` is treated as a write and clears the turn's cache, so
+/// new tools are safe by default.
+const UNCACHED_READ_TOOLS: &[&str] = &[
+    // Operation
+    "datasource_query",
+    "data_query_federated",
+    "data_query_export",
+    "explain_query",
+    "schema_diff",
+    "schema_metadata_query",
+    // Interaction
+    "ask_user",
+    "ask_user_choice",
+    "export_excel",
+    "show_table",
+    "show_chart",
+    "file_list",
+    "file_search",
+    "file_metadata",
+    "file_peek",
+    "file_search_content",
+    "file_range",
+    "file_download_url",
+    "analysis_show",
+    // Analysis
+    "list",
+    "get",
+    "validate",
+    "job_list",
+    "job_get",
+    "job_result",
+];
+
+struct TurnCache {
+    created_at: Instant,
+    results: HashMap<String, Value>,
+}
+
+lazy_static::lazy_static! {
+    static ref TURN_CACHES: Mutex<HashMap<String, TurnCache>> = Mutex::new(HashMap::new());
+}
+
+/// Identifies one model turn: a new Claude query gets a new turn id, so its
+/// cache starts empty. The backend ends the turn when the query finishes.
+pub fn turn_key(conversation_id: Option<&str>, turn_id: Option<&str>) -> Option<String> {
+    if !Config::current().is_ok_and(|config| config.mcp_tool_cache) {
+        return None;
+    }
+    match (conversation_id, turn_id) {
+        (Some(conversation_id), Some(turn_id)) => Some(format!("{}:{}", conversation_id, turn_id)),
+        _ => None,
+    }
+}
+
+pub fn is_cacheable(tool_name: &str) -> bool {
+    CACHEABLE_TOOLS.contains(&tool_name)
+}
+
+/// Anything not known to be read-only may change project state
+pub fn is_write_tool(tool_name: &str) -> bool {
+    !is_cacheable(tool_name) && !UNCACHED_READ_TOOLS.contains(&tool_name)
+}
+
+/// Cache key for a call: tool name plus arguments with sorted keys, ignoring
+/// per-call bookkeeping such as `__mcp_tool_use_id__`
+pub fn call_key(tool_name: &str, arguments: Option<&Value>) -> String {
+    let normalized = arguments.map(normalize).unwrap_or(Value::Null);
+    format!("{}:{}", tool_name, normalized)
+}
+
+pub fn get(turn_key: &str, call_key: &str) -> Option<Value> {
+    let caches = TURN_CACHES.lock().ok()?;
+    let turn = caches.get(turn_key)?;
+    if turn.created_at.elapsed() > TURN_CACHE_TTL {
+        return None;
+    }
+    turn.results.get(call_key).cloned()
+}
+
+pub fn insert(turn_key: &str, call_key: String, result: Value) {
+    let Ok(mut caches) = TURN_CACHES.lock() else {
+        return;
+    };
+    // A conversation runs one turn at a time, so its earlier turns are over
+    let conversation = turn_key.rsplit_once(':').map_or(turn_key, |(conversation, _)| conversation);
+    caches.retain(|key, turn| {
+        turn.created_at.elapsed() <= TURN_CACHE_TTL
+            && (key == turn_key || key.rsplit_once(':').map(|(c, _)| c) != Some(conversation))
+    });
+    caches
+        .entry(turn_key.to_string())
+        .or_insert_with(|| TurnCache {
+            created_at: Instant::now(),
+            results: HashMap::new(),
+        })
+        .results
+        .insert(call_key, result);
+}
+
+pub fn invalidate(turn_key: &str) {
+    if let Ok(mut caches) = TURN_CACHES.lock() {
+        caches.remove(turn_key);
+    }
+}
+
+/// Drop everything cached for a turn once its query has finished
+pub fn end_turn(conversation_id: &str, turn_id: &str) {
+    invalidate(&format!("{}:{}", conversation_id, turn_id));
+}
+
+fn normalize(value: &Value) -> Value {
+    match value {
+        Value::Object(map) => {
+            let mut entries: Vec<(&String, &Value)> =
+                map.iter().filter(|(key, _)| !key.starts_with("__")).collect();
+            entries.sort_by(|a, b| a.0.cmp(b.0));
+            Value::Object(
+                entries
+                    .into_iter()
+                    .map(|(key, value)| (key.clone(), normalize(value)))
+                    .collect(),
+            )
+        }
+        Value::Array(items) => Value::Array(items.iter().map(normalize).collect()),
+        other => other.clone(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unknown_tools_count_as_writes() {
+        assert!(!is_write_tool("schema_get"));
+        assert!(!is_write_tool("datasource_query"));
+        assert!(is_write_tool("data_query_write"));
+        assert!(is_write_tool("connection_test"));
+        assert!(is_write_tool("some_future_tool"));
+    }
+
+    #[test]
+    fn ending_a_turn_drops_its_results() {
+        insert("conversation-a:turn-1", "schema_get:null".to_string(), Value::from(1));
+        assert_eq!(get("conversation-a:turn-1", "schema_get:null"), Some(Value::from(1)));
+
+        end_turn("conversation-a", "turn-1");
+        assert_eq!(get("conversation-a:turn-1", "schema_get:null"), None);
+    }
+
+    #[test]
+    fn a_new_turn_drops_the_conversations_earlier_turns() {
+        insert("conversation-b:turn-1", "schema_get:null".to_string(), Value::from(1));
+        insert("conversation-c:turn-1", "schema_get:null".to_string(), Value::from(2));
+        insert("conversation-b:turn-2", "schema_get:null".to_string(), Value::from(3));
+
+        assert_eq!(get("conversation-b:turn-1", "schema_get:null"), None);
+        assert_eq!(get("conversation-b:turn-2", "schema_get:null"), Some(Value::from(3)));
+        assert_eq!(get("conversation-c:turn-1", "schema_get:null"), Some(Value::from(2)));
+    }
+
+    #[test]
+    fn call_keys_ignore_argument_order_and_bookkeeping() {
+        let a = serde_json::json!({"table": "users", "datasource_id": "d1", "__mcp_tool_use_id__": "x"});
+        let b = serde_json::json!({"datasource_id": "d1", "table": "users", "__mcp_tool_use_id__": "y"});
+        assert_eq!(call_key("schema_get", Some(&a)), call_key("schema_get", Some(&b)));
+    }
+}
@@ -23,7 +23,7 @@ pub fn get_data_analysis_tools() -> Vec<Tool> {
                     },
                     "source_type": {
                         "type": "string",
-                        "enum": ["postgresql", "mysql", "clickhouse", "sqlite", "oracle", "sqlserver"],
+                        "enum": ["postgresql", "mysql", "clickhouse", "sqlite", "oracle", "sqlserver", "mongodb"],
                         "description": "Type of database system"
                     },
                     "config": {
@@ -39,13 +39,24 @@ pub fn get_data_analysis_tools() -> Vec<Tool> {
                                     "port": {"type": "integer"},
                                     "database": {"type": "string"},
                                     "user": {"type": "string"},
-                                    "password": {"type": "string"}
+                                    "password": {"type": "string"},
+                                    "read_replica_url": {"type": "string"},
+                                    "replica_hosts": {"type": "array", "items": {"type": "string"}}
                                 },
                                 "required": ["host", "database"],
                                 "description": "Connection configuration object"
                             }
                         ],
                         "description": "Database connection configuration"
+                    },
+                    "read_replica_url": {
+                        "type": "string",
+                        "description": "PostgreSQL only: connection URL of a read replica. Read queries run there and fall back to the primary if it can't be reached; writes and connection tests always use the primary"
+                    },
+                    "replica_hosts": {
+                        "type": "array",
+                        "items": {"type": "string"},
+                        "description": "PostgreSQL only: read replica hosts as \"host\" or \"host:port\", sharing the primary's database and credentials. Tried in order after read_replica_url"
                     }
                 },
                 "required": ["name", "source_type", "config"]
@@ -68,7 +79,7 @@ pub fn get_data_analysis_tools() -> Vec<Tool> {
         },
         Tool {
             name: "datasource_remove".to_string(),
-            description: "Remove a datasource from the project".to_string(),
+            description: "Remove a datasource from the project. Removed datasources can be brought back with datasource_restore".to_string(),
             input_schema: json!({
                 "type": "object",
                 "properties": {
@@ -80,6 +91,20 @@ pub fn get_data_analysis_tools() -> Vec<Tool> {
                 "required": ["datasource_id"]
             }),
         },
+        Tool {
+            name: "datasource_restore".to_string(),
+            description: "Restore a datasource previously removed with datasource_remove".to_string(),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "datasource_id": {
+                        "type": "string",
+                        "description": "ID of the removed datasource"
+                    }
+                },
+                "required": ["datasource_id"]
+            }),
+        },
         Tool {
             name: "datasource_update".to_string(),
             description: "Update an existing datasource configuration".to_string(),
@@ -140,7 +165,7 @@ pub fn get_data_analysis_tools() -> Vec<Tool> {
         },
         Tool {
             name: "datasource_query".to_string(),
-            description: "Execute a SQL query against a datasource".to_string(),
+            description: "Execute a SQL query against a datasource. MongoDB datasources take a JSON query spec instead of SQL".to_string(),
             input_schema: json!({
                 "type": "object",
                 "properties": {
@@ -150,7 +175,7 @@ pub fn get_data_analysis_tools() -> Vec<Tool> {
                     },
                     "query": {
                         "type": "string",
-                        "description": "SQL query to execute"
+                        "description": "SQL query to execute. For MongoDB pass a JSON string naming one collection with either a find filter or an aggregate pipeline: {\"collection\": \"orders\", \"find\": {\"status\": \"paid\"}, \"projection\": {\"total\": 1}, \"sort\": {\"created_at\": -1}, \"skip\": 0, \"limit\": 50} or {\"collection\": \"orders\", \"aggregate\": [{\"$group\": {\"_id\": \"$status\", \"n\": {\"$sum\": 1}}}]}. Extended JSON such as {\"$oid\": \"...\"} and {\"$date\": \"...\"} is accepted; $out, $merge and JavaScript operators are rejected"
                     },
                     "limit": {
                         "type": "integer",
@@ -165,7 +190,7 @@ pub fn get_data_analysis_tools() -> Vec<Tool> {
         },
         Tool {
             name: "datasource_inspect".to_string(),
-            description: "Analyze a datasource to understand its schema, tables, and structure"
+            description: "Analyze a datasource to understand its schema, tables, and structure. On large databases pass tables or max_tables to inspect a subset; repeated calls add to the stored schema."
                 .to_string(),
             input_schema: json!({
                 "type": "object",
@@ -173,6 +198,21 @@ pub fn get_data_analysis_tools() -> Vec<Tool> {
                     "datasource_id": {
                         "type": "string",
                         "description": "ID of the datasource to inspect"
+                    },
+                    "tables": {
+                        "type": "array",
+                        "items": { "type": "string" },
+                        "description": "Only inspect these tables. Names or patterns where * matches any characters, e.g. [\"orders\", \"sales_*\"]"
+                    },
+                    "max_tables": {
+                        "type": "integer",
+                        "description": "Inspect at most this many tables",
+                        "minimum": 1
+                    },
+                    "force_refresh": {
+                        "type": "boolean",
+                        "description": "Inspect the database again even if the stored schema hasn't expired",
+                        "default": false
                     }
                 },
                 "required": ["datasource_id"]
@@ -607,7 +647,7 @@ pub fn get_data_analysis_tools() -> Vec<Tool> {
 pub fn is_data_analysis_tool(tool_name: &str) -> bool {
     matches!(tool_name,
         // Datasource tools
-        "datasource_add" | "datasource_list" | "datasource_remove" | "datasource_update" |
+        "datasource_add" | "datasource_list" | "datasource_remove" | "datasource_restore" | "datasource_update" |
         "connection_test" | "datasource_detail" | "datasource_query" | "datasource_inspect" |
         "schema_get" | "schema_search" | "schema_related" | "schema_stats" |
         // Analysis management tools
@@ -661,6 +701,17 @@ pub async fn handle_tool_call(
                 data: None,
             })
         },
+        "datasource_restore" => {
+            use crate::core::mcp::handlers::base::McpHandlers as DataSourceHandler;
+            let empty_map = serde_json::Map::new();
+            let args = arguments.and_then(|v| v.as_object()).unwrap_or(&empty_map);
+            let result = DataSourceHandler::handle_datasource_restore(handlers, args).await?;
+            serde_json::from_str(&result).map_err(|e| JsonRpcError {
+                code: INTERNAL_ERROR,
+                message: format!("Invalid JSON response: {}", e),
+                data: None,
+            })
+        },
         "datasource_update" => {
             use crate::core::mcp::handlers::base::McpHandlers as DataSourceHandler;
             let empty_map = serde_json::Map::new();
@@ -25,6 +25,38 @@ pub fn get_interaction_tools() -> Vec<Tool> {
                 "additionalProperties": false
             }),
         },
+        Tool {
+            name: "ask_user_choice".to_string(),
+            description: "Show the user a prompt with one button per option and wait for their pick. Returns the selected option, or the default (cancelled when there is none) if they don't answer within timeout_seconds".to_string(),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "prompt": {
+                        "type": "string",
+                        "description": "The question to show above the options"
+                    },
+                    "options": {
+                        "type": "array",
+                        "items": {"type": "string"},
+                        "minItems": 1,
+                        "maxItems": 20,
+                        "description": "The options to choose from, one button each"
+                    },
+                    "default": {
+                        "type": "string",
+                        "description": "Option to return if the user doesn't answer in time"
+                    },
+                    "timeout_seconds": {
+                        "type": "integer",
+                        "minimum": 1,
+                        "maximum": 600,
+                        "description": "How long to wait for an answer (default 120)"
+                    }
+                },
+                "required": ["prompt", "options"],
+                "additionalProperties": false
+            }),
+        },
         Tool {
             name: "export_excel".to_string(),
             description:
@@ -463,7 +495,7 @@ pub fn get_interaction_tools() -> Vec<Tool> {
 
 /// Check if a tool name is an interaction tool
 pub fn is_interaction_tool(tool_name: &str) -> bool {
-    matches!(tool_name, "ask_user" | "export_excel" | "show_table" | "show_chart" | "file_list" | "file_search" | "file_metadata" | "file_peek" | "file_search_content" | "file_range" | "file_download_url" | "analysis_show")
+    matches!(tool_name, "ask_user" | "ask_user_choice" | "export_excel" | "show_table" | "show_chart" | "file_list" | "file_search" | "file_metadata" | "file_peek" | "file_search_content" | "file_range" | "file_download_url" | "analysis_show")
 }
 
 /// Handle interaction tool calls
@@ -485,6 +517,17 @@ pub async fn handle_tool_call(
             })?;
             Ok(parsed_result)
         },
+        "ask_user_choice" => {
+            let empty_map = serde_json::Map::new();
+            let args = arguments.and_then(|v| v.as_object()).unwrap_or(&empty_map);
+            let result = handlers.handle_ask_user_choice(args).await?;
+            let parsed_result: serde_json::Value = serde_json::from_str(&result).map_err(|e| JsonRpcError {
+                code: INTERNAL_ERROR,
+                message: format!("Failed to parse ask_user_choice response: {}", e),
+                data: None,
+            })?;
+            Ok(parsed_result)
+        },
         "export_excel" => {
             let empty_map = serde_json::Map::new();
             let args = arguments.and_then(|v| v.as_object()).unwrap_or(&empty_map);
@@ -1,5 +1,6 @@
 use crate::core::mcp::types::*;
 use crate::core::mcp::handlers::base::McpHandlers;
+use crate::core::mcp::handlers::tool_cache;
 use crate::core::mcp::response::wrap_mcp_response;
 use serde_json::Value;
 
@@ -13,15 +14,23 @@ pub fn get_operation_tools() -> Vec<&'static str> {
         "datasource_add",
         "datasource_list",
         "datasource_remove",
+        "datasource_restore",
         "datasource_update",
         "datasource_detail",
         "connection_test",
         "datasource_query",
+        "data_query_write",
+        "data_query_federated",
+        "data_query_export",
+        "explain_query",
         "datasource_inspect",
         "schema_get",
+        "schema_columns",
+        "schema_diff",
         "schema_search",
         "schema_related",
         "schema_stats",
+        "schema_metadata_query",
         "context_read",
         "context_update",
         "context_compile",
@@ -46,24 +55,77 @@ pub async fn handle_tool_call(
     tool_name: &str,
     arguments: Option<&Value>
 ) -> Result<Value, JsonRpcError> {
+    // Identical read-only calls within one model turn reuse the first result;
+    // write tools clear the turn's cache before they are routed here
+    let turn_key = tool_cache::turn_key(handlers.conversation_id.as_deref(), handlers.turn_id.as_deref());
+    let call_key = tool_cache::call_key(tool_name, arguments);
+    let cached = match turn_key.as_deref() {
+        Some(turn_key) if tool_cache::is_cacheable(tool_name) => tool_cache::get(turn_key, &call_key),
+        _ => None,
+    };
+
     // Get the result from the specific handler
+    let result = match cached {
+        Some(cached) => {
+            tracing::debug!("♻️ Operation: Reusing cached result for {} within this turn", tool_name);
+            cached
+        }
+        None => {
+            let result = dispatch_operation_tool(handlers, tool_name, arguments).await?;
+            if let Some(turn_key) = turn_key.as_deref().filter(|_| tool_cache::is_cacheable(tool_name)) {
+                tool_cache::insert(turn_key, call_key, result.clone());
+            }
+            result
+        }
+    };
+    
+    // If we have a tool_use_id in arguments, update the database directly
+    if let Some(args) = arguments {
+        if let Some(tool_use_id) = args.get("__mcp_tool_use_id__").and_then(|v| v.as_str()) {
+            tracing::info!("📝 Operation: Received tool_use_id {} for tool {}, updating database", tool_use_id, tool_name);
+            update_tool_usage_in_db(handlers, tool_use_id, &result).await;
+        } else {
+            tracing::warn!("⚠️ Operation: No __mcp_tool_use_id__ found in arguments for tool {}", tool_name);
+            tracing::debug!("Operation: Available args keys: {:?}", args.as_object().map(|o| o.keys().collect::<Vec<_>>()));
+        }
+    } else {
+        tracing::warn!("⚠️ Operation: No arguments provided for tool {}", tool_name);
+    }
+    
+    Ok(result)
+}
+
+/// Route an operation tool call to its handler
+async fn dispatch_operation_tool(
+    handlers: &McpHandlers,
+    tool_name: &str,
+    arguments: Option<&Value>
+) -> Result<Value, JsonRpcError> {
     let result = match tool_name {
         // Datasource management tools
         "datasource_add" => handle_datasource_tool(handlers, tool_name, arguments).await?,
         "datasource_list" => handle_datasource_tool(handlers, tool_name, arguments).await?,
         "datasource_remove" => handle_datasource_tool(handlers, tool_name, arguments).await?,
+        "datasource_restore" => handle_datasource_tool(handlers, tool_name, arguments).await?,
         "datasource_update" => handle_datasource_tool(handlers, tool_name, arguments).await?,
         "datasource_detail" => handle_datasource_tool(handlers, tool_name, arguments).await?,
         "connection_test" => handle_datasource_tool(handlers, tool_name, arguments).await?,
         
         // Schema tools
         "schema_get" => handle_schema_tool(handlers, tool_name, arguments).await?,
+        "schema_columns" => handle_schema_tool(handlers, tool_name, arguments).await?,
+        "schema_diff" => handle_schema_tool(handlers, tool_name, arguments).await?,
         "schema_search" => handle_schema_tool(handlers, tool_name, arguments).await?,
         "schema_related" => handle_schema_tool(handlers, tool_name, arguments).await?,
         "schema_stats" => handle_schema_tool(handlers, tool_name, arguments).await?,
+        "schema_metadata_query" => handle_schema_tool(handlers, tool_name, arguments).await?,
         
         // Query tools
         "datasource_query" => handle_query_tool(handlers, tool_name, arguments).await?,
+        "data_query_write" => handle_query_tool(handlers, tool_name, arguments).await?,
+        "data_query_federated" => handle_query_tool(handlers, tool_name, arguments).await?,
+        "data_query_export" => handle_query_tool(handlers, tool_name, arguments).await?,
+        "explain_query" => handle_query_tool(handlers, tool_name, arguments).await?,
         "datasource_inspect" => handle_query_tool(handlers, tool_name, arguments).await?,
         
         // Context tools
@@ -79,20 +141,6 @@ pub async fn handle_tool_call(
             });
         }
     };
-    
-    // If we have a tool_use_id in arguments, update the database directly
-    if let Some(args) = arguments {
-        if let Some(tool_use_id) = args.get("__mcp_tool_use_id__").and_then(|v| v.as_str()) {
-            tracing::info!("📝 Operation: Received tool_use_id {} for tool {}, updating database", tool_use_id, tool_name);
-            update_tool_usage_in_db(handlers, tool_use_id, &result).await;
-        } else {
-            tracing::warn!("⚠️ Operation: No __mcp_tool_use_id__ found in arguments for tool {}", tool_name);
-            tracing::debug!("Operation: Available args keys: {:?}", args.as_object().map(|o| o.keys().collect::<Vec<_>>()));
-        }
-    } else {
-        tracing::warn!("⚠️ Operation: No arguments provided for tool {}", tool_name);
-    }
-    
     Ok(result)
 }
 
@@ -110,6 +158,7 @@ async fn handle_datasource_tool(
         "datasource_add" => handlers.handle_datasource_add(args).await?,
         "datasource_list" => handlers.handle_datasource_list(args).await?,
         "datasource_remove" => handlers.handle_datasource_remove(args).await?,
+        "datasource_restore" => handlers.handle_datasource_restore(args).await?,
         "datasource_update" => handlers.handle_datasource_update(args).await?,
         "datasource_detail" => handlers.handle_datasource_detail(args).await?,
         "connection_test" => handlers.handle_connection_test(args).await?,
@@ -137,9 +186,12 @@ async fn handle_schema_tool(
     
     let result_str = match tool_name {
         "schema_get" => handlers.handle_schema_get(args).await?,
+        "schema_columns" => handlers.handle_schema_columns(args).await?,
+        "schema_diff" => handlers.handle_schema_diff(args).await?,
         "schema_search" => handlers.handle_schema_search(args).await?,
         "schema_related" => handlers.handle_schema_related(args).await?,
         "schema_stats" => handlers.handle_schema_stats(args).await?,
+        "schema_metadata_query" => handlers.handle_schema_metadata_query(args).await?,
         _ => unreachable!(),
     };
     
@@ -163,6 +215,10 @@ async fn handle_query_tool(
     
     let result_str = match tool_name {
         "datasource_query" => handlers.handle_datasource_query(args).await?,
+        "data_query_write" => handlers.handle_data_query_write(args).await?,
+        "data_query_federated" => handlers.handle_data_query_federated(args).await?,
+        "data_query_export" => handlers.handle_data_query_export(args).await?,
+        "explain_query" => handlers.handle_explain_query(args).await?,
         "datasource_inspect" => handlers.handle_datasource_inspect(args).await?,
         _ => unreachable!(),
     };
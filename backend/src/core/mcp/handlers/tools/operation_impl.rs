@@ -23,7 +23,7 @@ pub fn get_operation_tools() -> Vec<Tool> {
                     },
                     "source_type": {
                         "type": "string",
-                        "enum": ["postgresql", "mysql", "clickhouse", "sqlite", "oracle", "sqlserver"],
+                        "enum": ["postgresql", "mysql", "clickhouse", "sqlite", "oracle", "sqlserver", "mongodb"],
                         "description": "Type of database system"
                     },
                     "config": {
@@ -39,13 +39,29 @@ pub fn get_operation_tools() -> Vec<Tool> {
                                     "port": {"type": "integer"},
                                     "database": {"type": "string"},
                                     "user": {"type": "string"},
-                                    "password": {"type": "string"}
+                                    "password": {"type": "string"},
+                                    "read_replica_url": {"type": "string"},
+                                    "replica_hosts": {"type": "array", "items": {"type": "string"}}
                                 },
                                 "required": ["host", "database"],
                                 "description": "Connection configuration object"
                             }
                         ],
                         "description": "Database connection configuration"
+                    },
+                    "read_replica_url": {
+                        "type": "string",
+                        "description": "PostgreSQL only: connection URL of a read replica. Read queries run there and fall back to the primary if it can't be reached; writes and connection tests always use the primary"
+                    },
+                    "replica_hosts": {
+                        "type": "array",
+                        "items": {"type": "string"},
+                        "description": "PostgreSQL only: read replica hosts as \"host\" or \"host:port\", sharing the primary's database and credentials. Tried in order after read_replica_url"
+                    },
+                    "force": {
+                        "type": "boolean",
+                        "description": "Create a new datasource even if one with the same name already exists (the new one gets a numbered name). Without it, re-adding an identical datasource returns the existing one.",
+                        "default": false
                     }
                 },
                 "required": ["name", "source_type", "config"]
@@ -68,7 +84,7 @@ pub fn get_operation_tools() -> Vec<Tool> {
         },
         Tool {
             name: "datasource_remove".to_string(),
-            description: "Remove a datasource from the project".to_string(),
+            description: "Remove a datasource from the project. Removed datasources can be brought back with datasource_restore".to_string(),
             input_schema: json!({
                 "type": "object",
                 "properties": {
@@ -80,6 +96,20 @@ pub fn get_operation_tools() -> Vec<Tool> {
                 "required": ["datasource_id"]
             }),
         },
+        Tool {
+            name: "datasource_restore".to_string(),
+            description: "Restore a datasource previously removed with datasource_remove".to_string(),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "datasource_id": {
+                        "type": "string",
+                        "description": "ID of the removed datasource"
+                    }
+                },
+                "required": ["datasource_id"]
+            }),
+        },
         Tool {
             name: "datasource_update".to_string(),
             description: "Update an existing datasource configuration".to_string(),
@@ -140,7 +170,7 @@ pub fn get_operation_tools() -> Vec<Tool> {
         },
         Tool {
             name: "datasource_query".to_string(),
-            description: "Execute a SQL query against a datasource".to_string(),
+            description: "Execute a SQL query against a datasource. MongoDB datasources take a JSON query spec instead of SQL".to_string(),
             input_schema: json!({
                 "type": "object",
                 "properties": {
@@ -150,7 +180,7 @@ pub fn get_operation_tools() -> Vec<Tool> {
                     },
                     "query": {
                         "type": "string",
-                        "description": "SQL query to execute"
+                        "description": "SQL query to execute. For MongoDB pass a JSON string naming one collection with either a find filter or an aggregate pipeline: {\"collection\": \"orders\", \"find\": {\"status\": \"paid\"}, \"projection\": {\"total\": 1}, \"sort\": {\"created_at\": -1}, \"skip\": 0, \"limit\": 50} or {\"collection\": \"orders\", \"aggregate\": [{\"$group\": {\"_id\": \"$status\", \"n\": {\"$sum\": 1}}}]}. Extended JSON such as {\"$oid\": \"...\"} and {\"$date\": \"...\"} is accepted; $out, $merge and JavaScript operators are rejected"
                     },
                     "limit": {
                         "type": "integer",
@@ -158,14 +188,154 @@ pub fn get_operation_tools() -> Vec<Tool> {
                         "maximum": 1000,
                         "default": 100,
                         "description": "Maximum number of rows to return"
+                    },
+                    "columns": {
+                        "type": "array",
+                        "items": {"type": "string"},
+                        "description": "Optional: only return these result columns. Very wide results are otherwise cut to the first columns, with total_columns reporting the full count"
+                    }
+                },
+                "required": ["datasource_id", "query"]
+            }),
+        },
+        Tool {
+            name: "data_query_write".to_string(),
+            description: "Execute an INSERT, UPDATE, DELETE or other data-modifying statement inside a transaction and return the affected row count. Only available on datasources whose connection config sets allow_writes: true. Note that MySQL commits DDL implicitly, so dry_run cannot undo it there".to_string(),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "datasource_id": {
+                        "type": "string",
+                        "description": "ID of the datasource to modify"
+                    },
+                    "query": {
+                        "type": "string",
+                        "description": "SQL statement to execute"
+                    },
+                    "dry_run": {
+                        "type": "boolean",
+                        "default": false,
+                        "description": "Roll the transaction back instead of committing, to preview how many rows the statement affects"
+                    }
+                },
+                "required": ["datasource_id", "query"]
+            }),
+        },
+        Tool {
+            name: "data_query_export".to_string(),
+            description: "Run a SELECT query on a datasource and save the rows as a downloadable Excel (.xlsx) file. Returns the download_url and filename".to_string(),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "datasource_id": {
+                        "type": "string",
+                        "description": "ID of the datasource to query"
+                    },
+                    "query": {
+                        "type": "string",
+                        "description": "SELECT query whose rows are exported"
+                    },
+                    "max_rows": {
+                        "type": "integer",
+                        "minimum": 1,
+                        "maximum": 100000,
+                        "default": 10000,
+                        "description": "Maximum number of rows to export; truncated is true when the query returned more"
+                    },
+                    "filename": {
+                        "type": "string",
+                        "description": "Optional: file name without extension (defaults to query_export_<timestamp>)"
+                    },
+                    "sheet_name": {
+                        "type": "string",
+                        "description": "Optional: worksheet name (defaults to \"Query Results\")"
                     }
                 },
                 "required": ["datasource_id", "query"]
             }),
         },
+        Tool {
+            name: "explain_query".to_string(),
+            description: "Show the execution plan of a SELECT query without fetching its rows, to check indexes and cost before running an expensive query. With analyze: true the query is executed and the plan includes actual timings. Supported for PostgreSQL, MySQL, SQLite, ClickHouse and DuckDB datasources".to_string(),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "datasource_id": {
+                        "type": "string",
+                        "description": "ID of the datasource to explain the query on"
+                    },
+                    "query": {
+                        "type": "string",
+                        "description": "SELECT query to explain"
+                    },
+                    "analyze": {
+                        "type": "boolean",
+                        "default": false,
+                        "description": "Run the query (EXPLAIN ANALYZE) to report actual row counts and timings. Not available for SQLite and ClickHouse"
+                    }
+                },
+                "required": ["datasource_id", "query"]
+            }),
+        },
+        Tool {
+            name: "data_query_federated".to_string(),
+            description: "Run a SQL query joining tables from multiple datasources. Each source is loaded into a temporary DuckDB database under its alias, then the query runs there using DuckDB SQL".to_string(),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "sources": {
+                        "type": "array",
+                        "minItems": 1,
+                        "maxItems": 10,
+                        "items": {
+                            "type": "object",
+                            "properties": {
+                                "datasource_id": {
+                                    "type": "string",
+                                    "description": "ID of the datasource to read from"
+                                },
+                                "table": {
+                                    "type": "string",
+                                    "description": "Table to load (table or schema.table). Use either table or query"
+                                },
+                                "query": {
+                                    "type": "string",
+                                    "description": "SQL extract to run on the datasource instead of loading a whole table"
+                                },
+                                "alias": {
+                                    "type": "string",
+                                    "description": "Name to reference this source by in the federated query (defaults to the table name)"
+                                },
+                                "limit": {
+                                    "type": "integer",
+                                    "minimum": 1,
+                                    "maximum": 100000,
+                                    "default": 10000,
+                                    "description": "Maximum number of rows to load from this source"
+                                }
+                            },
+                            "required": ["datasource_id"]
+                        },
+                        "description": "Datasource tables or extracts to make available to the query"
+                    },
+                    "query": {
+                        "type": "string",
+                        "description": "DuckDB SQL query referencing the source aliases"
+                    },
+                    "limit": {
+                        "type": "integer",
+                        "minimum": 1,
+                        "maximum": 1000,
+                        "default": 100,
+                        "description": "Maximum number of rows to return"
+                    }
+                },
+                "required": ["sources", "query"]
+            }),
+        },
         Tool {
             name: "datasource_inspect".to_string(),
-            description: "Analyze a datasource to understand its schema, tables, and structure"
+            description: "Analyze a datasource to understand its schema, tables, and structure. On large databases pass tables or max_tables to inspect a subset; repeated calls add to the stored schema."
                 .to_string(),
             input_schema: json!({
                 "type": "object",
@@ -173,6 +343,21 @@ pub fn get_operation_tools() -> Vec<Tool> {
                     "datasource_id": {
                         "type": "string",
                         "description": "ID of the datasource to inspect"
+                    },
+                    "tables": {
+                        "type": "array",
+                        "items": { "type": "string" },
+                        "description": "Only inspect these tables. Names or patterns where * matches any characters, e.g. [\"orders\", \"sales_*\"]"
+                    },
+                    "max_tables": {
+                        "type": "integer",
+                        "description": "Inspect at most this many tables",
+                        "minimum": 1
+                    },
+                    "force_refresh": {
+                        "type": "boolean",
+                        "description": "Inspect the database again even if the stored schema hasn't expired",
+                        "default": false
                     }
                 },
                 "required": ["datasource_id"]
@@ -268,6 +453,63 @@ pub fn get_operation_tools() -> Vec<Tool> {
                 "required": ["datasource_id"]
             }),
         },
+        Tool {
+            name: "schema_columns".to_string(),
+            description: "List the columns of one table (name, type, nullable, primary key) as a compact list. Cheaper than schema_get when you only need to know what columns a table has".to_string(),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "datasource_id": {
+                        "type": "string",
+                        "description": "ID of the datasource"
+                    },
+                    "table": {
+                        "type": "string",
+                        "description": "Name of the table"
+                    }
+                },
+                "required": ["datasource_id", "table"]
+            }),
+        },
+        Tool {
+            name: "schema_diff".to_string(),
+            description: "Compare the datasource's last two schema inspections: added and removed tables, and added, removed or retyped columns. Column changes cover tables whose columns were inspected in both snapshots; run datasource_inspect to record a new snapshot first".to_string(),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "datasource_id": {
+                        "type": "string",
+                        "description": "ID of the datasource"
+                    }
+                },
+                "required": ["datasource_id"]
+            }),
+        },
+        Tool {
+            name: "schema_metadata_query".to_string(),
+            description: "Run a read-only SELECT against the datasource's system catalogs only (row counts, index usage, last analyze time, constraints). PostgreSQL: information_schema.*, pg_catalog.* (schema-qualified, e.g. pg_catalog.pg_stat_user_tables); MySQL: information_schema, performance_schema, sys; SQLite: sqlite_master, pragma_*; ClickHouse: system, information_schema; SQL Server: INFORMATION_SCHEMA, sys; Oracle: SYS.ALL_*, SYS.USER_*, SYS.DBA_*. Queries touching user tables are rejected".to_string(),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "datasource_id": {
+                        "type": "string",
+                        "description": "ID of the datasource"
+                    },
+                    "query": {
+                        "type": "string",
+                        "description": "SELECT query that reads only system catalog tables or views"
+                    },
+                    "limit": {
+                        "type": "integer",
+                        "minimum": 1,
+                        "maximum": 1000,
+                        "default": 100,
+                        "description": "Maximum number of rows to return"
+                    }
+                },
+                "required": ["datasource_id", "query"]
+            }),
+        },
         // Context management tools
         Tool {
             name: "context_read".to_string(),
@@ -308,10 +550,11 @@ pub fn get_operation_tools() -> Vec<Tool> {
 pub fn is_operation_tool(tool_name: &str) -> bool {
     matches!(tool_name,
         // Datasource tools
-        "datasource_add" | "datasource_list" | "datasource_remove" | "datasource_update" |
+        "datasource_add" | "datasource_list" | "datasource_remove" | "datasource_restore" | "datasource_update" |
         "connection_test" | "datasource_detail" | "datasource_query" | "datasource_inspect" |
+        "data_query_write" | "data_query_federated" | "data_query_export" | "explain_query" |
         // Schema tools
-        "schema_get" | "schema_search" | "schema_related" | "schema_stats" |
+        "schema_get" | "schema_columns" | "schema_diff" | "schema_search" | "schema_related" | "schema_stats" | "schema_metadata_query" |
         // Context tools
         "context_read" | "context_update" | "context_compile"
     )
@@ -357,6 +600,17 @@ pub async fn handle_tool_call(
                 data: None,
             })
         },
+        "datasource_restore" => {
+            use crate::core::mcp::handlers::base::McpHandlers as DataSourceHandler;
+            let empty_map = serde_json::Map::new();
+            let args = arguments.and_then(|v| v.as_object()).unwrap_or(&empty_map);
+            let result = DataSourceHandler::handle_datasource_restore(handlers, args).await?;
+            serde_json::from_str(&result).map_err(|e| JsonRpcError {
+                code: INTERNAL_ERROR,
+                message: format!("Invalid JSON response: {}", e),
+                data: None,
+            })
+        },
         "datasource_update" => {
             use crate::core::mcp::handlers::base::McpHandlers as DataSourceHandler;
             let empty_map = serde_json::Map::new();
@@ -401,6 +655,46 @@ pub async fn handle_tool_call(
                 data: None,
             })
         },
+        "data_query_write" => {
+            let empty_map = serde_json::Map::new();
+            let args = arguments.and_then(|v| v.as_object()).unwrap_or(&empty_map);
+            let result = handlers.handle_data_query_write(args).await?;
+            serde_json::from_str(&result).map_err(|e| JsonRpcError {
+                code: INTERNAL_ERROR,
+                message: format!("Invalid JSON response: {}", e),
+                data: None,
+            })
+        },
+        "data_query_federated" => {
+            let empty_map = serde_json::Map::new();
+            let args = arguments.and_then(|v| v.as_object()).unwrap_or(&empty_map);
+            let result = handlers.handle_data_query_federated(args).await?;
+            serde_json::from_str(&result).map_err(|e| JsonRpcError {
+                code: INTERNAL_ERROR,
+                message: format!("Invalid JSON response: {}", e),
+                data: None,
+            })
+        },
+        "data_query_export" => {
+            let empty_map = serde_json::Map::new();
+            let args = arguments.and_then(|v| v.as_object()).unwrap_or(&empty_map);
+            let result = handlers.handle_data_query_export(args).await?;
+            serde_json::from_str(&result).map_err(|e| JsonRpcError {
+                code: INTERNAL_ERROR,
+                message: format!("Invalid JSON response: {}", e),
+                data: None,
+            })
+        },
+        "explain_query" => {
+            let empty_map = serde_json::Map::new();
+            let args = arguments.and_then(|v| v.as_object()).unwrap_or(&empty_map);
+            let result = handlers.handle_explain_query(args).await?;
+            serde_json::from_str(&result).map_err(|e| JsonRpcError {
+                code: INTERNAL_ERROR,
+                message: format!("Invalid JSON response: {}", e),
+                data: None,
+            })
+        },
         "datasource_inspect" => {
             use crate::core::mcp::handlers::base::McpHandlers as DataSourceHandler;
             let empty_map = serde_json::Map::new();
@@ -456,6 +750,36 @@ pub async fn handle_tool_call(
                 data: None,
             })
         },
+        "schema_columns" => {
+            let empty_map = serde_json::Map::new();
+            let args = arguments.and_then(|v| v.as_object()).unwrap_or(&empty_map);
+            let result = handlers.handle_schema_columns(args).await?;
+            serde_json::from_str(&result).map_err(|e| JsonRpcError {
+                code: INTERNAL_ERROR,
+                message: format!("Invalid JSON response: {}", e),
+                data: None,
+            })
+        },
+        "schema_diff" => {
+            let empty_map = serde_json::Map::new();
+            let args = arguments.and_then(|v| v.as_object()).unwrap_or(&empty_map);
+            let result = handlers.handle_schema_diff(args).await?;
+            serde_json::from_str(&result).map_err(|e| JsonRpcError {
+                code: INTERNAL_ERROR,
+                message: format!("Invalid JSON response: {}", e),
+                data: None,
+            })
+        },
+        "schema_metadata_query" => {
+            let empty_map = serde_json::Map::new();
+            let args = arguments.and_then(|v| v.as_object()).unwrap_or(&empty_map);
+            let result = handlers.handle_schema_metadata_query(args).await?;
+            serde_json::from_str(&result).map_err(|e| JsonRpcError {
+                code: INTERNAL_ERROR,
+                message: format!("Invalid JSON response: {}", e),
+                data: None,
+            })
+        },
         // Context tools
         "context_read" => {
             handle_context_tool(handlers, tool_name, arguments).await
@@ -0,0 +1,186 @@
+//! Diagnostic logging for the MCP server
+//!
+//! stdout carries the JSON-RPC stream, so log lines go to stderr. The default
+//! `text` format keeps the `[timestamp] [LEVEL] message` lines; setting
+//! MCP_LOG_FORMAT=json emits one JSON object per line with `timestamp`,
+//! `level`, `project_id`, `operation` and `message` for log shippers.
+
+use chrono::{DateTime, Utc};
+use serde_json::json;
+use std::sync::LazyLock;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LogFormat {
+    Text,
+    Json,
+}
+
+impl LogFormat {
+    /// Read MCP_LOG_FORMAT; anything other than `json` falls back to text
+    pub fn from_env() -> Self {
+        match std::env::var("MCP_LOG_FORMAT") {
+            Ok(value) if value.trim().eq_ignore_ascii_case("json") => LogFormat::Json,
+            _ => LogFormat::Text,
+        }
+    }
+}
+
+static LOG_FORMAT: LazyLock<LogFormat> = LazyLock::new(LogFormat::from_env);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LogLevel {
+    Debug,
+    Info,
+    Warning,
+    Error,
+    Fatal,
+    Request,
+    Response,
+}
+
+impl LogLevel {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LogLevel::Debug => "DEBUG",
+            LogLevel::Info => "INFO",
+            LogLevel::Warning => "WARNING",
+            LogLevel::Error => "ERROR",
+            LogLevel::Fatal => "FATAL",
+            LogLevel::Request => "REQUEST",
+            LogLevel::Response => "RESPONSE",
+        }
+    }
+}
+
+/// Message plus the context it was logged in
+#[derive(Debug, Clone, Default)]
+pub struct LogFields<'a> {
+    pub project_id: Option<&'a str>,
+    pub operation: Option<&'a str>,
+    pub message: String,
+}
+
+impl<'a> LogFields<'a> {
+    /// A message with no project or operation context
+    pub fn new(message: impl Into<String>) -> Self {
+        Self {
+            message: message.into(),
+            ..Default::default()
+        }
+    }
+
+    pub fn for_project(project_id: &'a str) -> Self {
+        Self {
+            project_id: Some(project_id),
+            ..Default::default()
+        }
+    }
+
+    pub fn operation(mut self, operation: &'a str) -> Self {
+        self.operation = Some(operation);
+        self
+    }
+
+    pub fn message(mut self, message: impl Into<String>) -> Self {
+        self.message = message.into();
+        self
+    }
+}
+
+/// Write one log line to stderr in the configured format
+pub fn mcp_log(level: LogLevel, fields: LogFields<'_>) {
+    eprintln!(
+        "{}",
+        format_log_line(*LOG_FORMAT, level, &fields, Utc::now())
+    );
+}
+
+pub fn format_log_line(
+    format: LogFormat,
+    level: LogLevel,
+    fields: &LogFields<'_>,
+    timestamp: DateTime<Utc>,
+) -> String {
+    match format {
+        LogFormat::Text => format!(
+            "[{}] [{}] {}",
+            timestamp.format("%Y-%m-%d %H:%M:%S UTC"),
+            level.as_str(),
+            fields.message
+        ),
+        LogFormat::Json => json!({
+            "timestamp": timestamp.to_rfc3339(),
+            "level": level.as_str(),
+            "project_id": fields.project_id,
+            "operation": fields.operation,
+            "message": fields.message,
+        })
+        .to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use serde_json::Value;
+
+    fn failed_query_fields() -> LogFields<'static> {
+        LogFields::for_project("project-123")
+            .operation("query_datasource")
+            .message("MCP operation 'query_datasource' failed: relation \"orders\" does not exist")
+    }
+
+    #[test]
+    fn json_line_for_failed_data_query_parses() {
+        let timestamp = Utc.with_ymd_and_hms(2024, 5, 1, 12, 30, 0).unwrap();
+        let line = format_log_line(
+            LogFormat::Json,
+            LogLevel::Error,
+            &failed_query_fields(),
+            timestamp,
+        );
+
+        assert!(!line.contains('\n'));
+        let parsed: Value = serde_json::from_str(&line).expect("log line is valid JSON");
+        assert_eq!(parsed["timestamp"], "2024-05-01T12:30:00+00:00");
+        assert_eq!(parsed["level"], "ERROR");
+        assert_eq!(parsed["project_id"], "project-123");
+        assert_eq!(parsed["operation"], "query_datasource");
+        assert_eq!(
+            parsed["message"],
+            "MCP operation 'query_datasource' failed: relation \"orders\" does not exist"
+        );
+    }
+
+    #[test]
+    fn json_line_keeps_missing_context_as_null() {
+        let timestamp = Utc.with_ymd_and_hms(2024, 5, 1, 12, 30, 0).unwrap();
+        let line = format_log_line(
+            LogFormat::Json,
+            LogLevel::Info,
+            &LogFields::new("MCP Server shutting down"),
+            timestamp,
+        );
+
+        let parsed: Value = serde_json::from_str(&line).unwrap();
+        assert!(parsed["project_id"].is_null());
+        assert!(parsed["operation"].is_null());
+    }
+
+    #[test]
+    fn text_line_matches_previous_format() {
+        let timestamp = Utc.with_ymd_and_hms(2024, 5, 1, 12, 30, 0).unwrap();
+        let line = format_log_line(
+            LogFormat::Text,
+            LogLevel::Error,
+            &failed_query_fields(),
+            timestamp,
+        );
+
+        assert_eq!(
+            line,
+            "[2024-05-01 12:30:00 UTC] [ERROR] MCP operation 'query_datasource' failed: relation \"orders\" does not exist"
+        );
+    }
+}
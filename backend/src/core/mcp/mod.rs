@@ -1,9 +1,17 @@
+pub mod batch;
+pub mod db;
 pub mod handlers;
+pub mod logging;
+pub mod progress;
 pub mod types;
 pub mod response;
 
 use chrono::Utc;
+use crate::utils::config::Config;
+use crate::utils::request_id::request_id;
+use crate::utils::storage::init_storage;
 use handlers::McpHandlers;
+use logging::{mcp_log, LogFields, LogLevel};
 use salvo::prelude::*;
 use serde_json::json;
 use sqlx::PgPool;
@@ -25,27 +33,31 @@ pub struct McpServer {
 impl McpServer {
     #[allow(dead_code)]
     pub fn new(project_id: String, client_id: String) -> Result<Self, Box<dyn std::error::Error>> {
-        let start_time = Utc::now();
-        eprintln!(
-            "[{}] [INFO] MCP Server starting for project: {}, client: {}",
-            start_time.format("%Y-%m-%d %H:%M:%S UTC"),
-            project_id,
-            client_id
+        mcp_log(
+            LogLevel::Info,
+            LogFields::for_project(&project_id).message(format!(
+                "MCP Server starting for project: {}, client: {}",
+                project_id,
+                client_id
+            )),
         );
 
         let runtime = Runtime::new()?;
 
-        // Get database URL from environment
-        let database_url = std::env::var("DATABASE_URL")
-            .map_err(|_| "DATABASE_URL environment variable not set")?;
+        // Handlers and connectors read settings through Config::current
+        let config = Config::from_env()?;
+        Config::install(config.clone());
+        init_storage(&config.storage)?;
 
-        let db_pool = runtime.block_on(async { PgPool::connect(&database_url).await })?;
+        let db_pool = runtime.block_on(db::connect_pool(&config.database_url, &db::retry_policy()))?;
 
         let handlers = McpHandlers {
             project_id: project_id.clone(),
             client_id: client_id.clone(),
             server_type: "operation".to_string(),
             db_pool,
+            conversation_id: None,
+            turn_id: None,
         };
 
         Ok(Self {
@@ -67,17 +79,20 @@ impl McpServer {
 
         let runtime = Runtime::new()?;
 
-        // Get database URL from environment
-        let database_url = std::env::var("DATABASE_URL")
-            .map_err(|_| "DATABASE_URL environment variable not set")?;
+        // Handlers and connectors read settings through Config::current
+        let config = Config::from_env()?;
+        Config::install(config.clone());
+        init_storage(&config.storage)?;
 
-        let db_pool = runtime.block_on(async { PgPool::connect(&database_url).await })?;
+        let db_pool = runtime.block_on(db::connect_pool(&config.database_url, &db::retry_policy()))?;
 
         let handlers = McpHandlers {
             project_id: project_id.clone(),
             client_id: client_id.clone(),
             server_type: server_type.clone(),
             db_pool,
+            conversation_id: None,
+            turn_id: None,
         };
 
         Ok(Self {
@@ -91,9 +106,9 @@ impl McpServer {
 
     #[allow(dead_code)]
     pub fn run(&mut self) {
-        eprintln!(
-            "[{}] [INFO] MCP Server ready, waiting for JSON-RPC requests on stdin...",
-            Utc::now().format("%Y-%m-%d %H:%M:%S UTC")
+        mcp_log(
+            LogLevel::Info,
+            LogFields::for_project(&self.project_id).message("MCP Server ready, waiting for JSON-RPC requests on stdin..."),
         );
 
         let stdin = io::stdin();
@@ -108,71 +123,98 @@ impl McpServer {
                         continue;
                     }
 
-                    let request_time = Utc::now();
-                    eprintln!(
-                        "[{}] [REQUEST] Received: {}",
-                        request_time.format("%Y-%m-%d %H:%M:%S UTC"),
-                        line
+                    mcp_log(
+                        LogLevel::Request,
+                        LogFields::for_project(&self.project_id).message(format!(
+                            "Received: {}",
+                            line
+                        )),
                     );
 
                     // Parse and handle the request
                     let start_processing = std::time::Instant::now();
                     let response = self.handle_request(line);
                     let processing_duration = start_processing.elapsed();
+                    if response.is_empty() {
+                        continue;
+                    }
 
                     // Send response
                     println!("{}", response);
                     if let Err(e) = io::stdout().flush() {
-                        eprintln!(
-                            "[{}] [ERROR] Failed to flush stdout: {}",
-                            Utc::now().format("%Y-%m-%d %H:%M:%S UTC"),
-                            e
+                        mcp_log(
+                            LogLevel::Error,
+                            LogFields::for_project(&self.project_id).message(format!(
+                                "Failed to flush stdout: {}",
+                                e
+                            )),
                         );
                         break;
                     }
 
-                    eprintln!(
-                        "[{}] [RESPONSE] Sent (took {}ms): {}",
-                        Utc::now().format("%Y-%m-%d %H:%M:%S UTC"),
-                        processing_duration.as_millis(),
-                        response
+                    mcp_log(
+                        LogLevel::Response,
+                        LogFields::for_project(&self.project_id).message(format!(
+                            "Sent (took {}ms): {}",
+                            processing_duration.as_millis(),
+                            response
+                        )),
                     );
                 }
                 Err(e) => {
-                    eprintln!(
-                        "[{}] [ERROR] Error reading stdin: {}",
-                        Utc::now().format("%Y-%m-%d %H:%M:%S UTC"),
-                        e
+                    mcp_log(
+                        LogLevel::Error,
+                        LogFields::for_project(&self.project_id).message(format!(
+                            "Error reading stdin: {}",
+                            e
+                        )),
                     );
                     break;
                 }
             }
         }
 
-        eprintln!(
-            "[{}] [INFO] MCP Server shutting down",
-            Utc::now().format("%Y-%m-%d %H:%M:%S UTC")
+        mcp_log(
+            LogLevel::Info,
+            LogFields::for_project(&self.project_id).message("MCP Server shutting down"),
         );
     }
 
     #[allow(dead_code)]
     fn handle_request(&mut self, line: String) -> String {
+        if let Some(items) = batch::batch_items(&line) {
+            mcp_log(
+                LogLevel::Debug,
+                LogFields::for_project(&self.project_id).message(format!("Parsed batch of {} requests", items.len())),
+            );
+            // A batch of notifications gets no reply at all
+            return self
+                .runtime
+                .block_on(batch::dispatch_batch(&self.handlers, items))
+                .map(|responses| responses.to_string())
+                .unwrap_or_default();
+        }
+
         // Parse JSON-RPC request
         let request: JsonRpcRequest = match serde_json::from_str::<JsonRpcRequest>(&line) {
             Ok(req) => {
-                eprintln!(
-                    "[{}] [DEBUG] Parsed request - method: {}, id: {:?}",
-                    Utc::now().format("%Y-%m-%d %H:%M:%S UTC"),
-                    req.method,
-                    req.id
+                mcp_log(
+                    LogLevel::Debug,
+                    LogFields::for_project(&self.project_id).message(format!(
+                        "Parsed request - method: {}, id: {:?}",
+                        req.method,
+                        req.id
+                    )),
                 );
                 req
             }
             Err(e) => {
-                eprintln!(
-                    "[{}] [ERROR] JSON-RPC parse error: {}",
-                    Utc::now().format("%Y-%m-%d %H:%M:%S UTC"),
-                    e
+                mcp_log(
+                    LogLevel::Error,
+                    LogFields::for_project(&self.project_id).message(format!(
+                        "JSON-RPC parse error: {}",
+                        e
+                    )),
                 );
                 return serde_json::to_string(&JsonRpcResponse {
                     jsonrpc: "2.0".to_string(),
@@ -184,10 +226,12 @@ impl McpServer {
                         data: None,
                     }),
                 }).unwrap_or_else(|e| {
-                    eprintln!(
-                        "[{}] [ERROR] Failed to serialize error response: {}", 
-                        Utc::now().format("%Y-%m-%d %H:%M:%S UTC"), 
-                        e
+                    mcp_log(
+                        LogLevel::Error,
+                        LogFields::for_project(&self.project_id).message(format!(
+                            "Failed to serialize error response: {}",
+                            e
+                        )),
                     );
                     r#"{"jsonrpc":"2.0","id":null,"error":{"code":-32700,"message":"Parse error and serialization failed"}}"#.to_string()
                 });
@@ -195,10 +239,12 @@ impl McpServer {
         };
 
         // Route to appropriate handler
-        eprintln!(
-            "[{}] [DEBUG] Routing method: {}",
-            Utc::now().format("%Y-%m-%d %H:%M:%S UTC"),
-            request.method
+        mcp_log(
+            LogLevel::Debug,
+            LogFields::for_project(&self.project_id).operation(&request.method).message(format!(
+                "Routing method: {}",
+                request.method
+            )),
         );
         let method_start = std::time::Instant::now();
 
@@ -212,9 +258,9 @@ impl McpServer {
                 "notifications/initialized" => {
                     // This is a notification from the client that initialization is complete
                     // We just acknowledge it and return an empty result
-                    eprintln!(
-                        "[{}] [INFO] Client initialization complete - MCP server fully ready",
-                        Utc::now().format("%Y-%m-%d %H:%M:%S UTC")
+                    mcp_log(
+                        LogLevel::Info,
+                        LogFields::for_project(&self.project_id).operation(&request.method).message("Client initialization complete - MCP server fully ready"),
                     );
                     Ok(serde_json::json!({}))
                 }
@@ -223,10 +269,12 @@ impl McpServer {
                 "tools/list" => self.handlers.handle_tools_list(request.params).await,
                 "tools/call" => self.handlers.handle_tools_call(request.params).await,
                 _ => {
-                    eprintln!(
-                        "[{}] [ERROR] Method not found: {}",
-                        Utc::now().format("%Y-%m-%d %H:%M:%S UTC"),
-                        request.method
+                    mcp_log(
+                        LogLevel::Error,
+                        LogFields::for_project(&self.project_id).operation(&request.method).message(format!(
+                            "Method not found: {}",
+                            request.method
+                        )),
                     );
                     Err(JsonRpcError {
                         code: METHOD_NOT_FOUND,
@@ -241,11 +289,13 @@ impl McpServer {
         // Build response
         let response = match result {
             Ok(value) => {
-                eprintln!(
-                    "[{}] [DEBUG] Method {} completed successfully in {}ms",
-                    Utc::now().format("%Y-%m-%d %H:%M:%S UTC"),
-                    request.method,
-                    method_duration.as_millis()
+                mcp_log(
+                    LogLevel::Debug,
+                    LogFields::for_project(&self.project_id).operation(&request.method).message(format!(
+                        "Method {} completed successfully in {}ms",
+                        request.method,
+                        method_duration.as_millis()
+                    )),
                 );
                 JsonRpcResponse {
                     jsonrpc: "2.0".to_string(),
@@ -255,13 +305,15 @@ impl McpServer {
                 }
             }
             Err(error) => {
-                eprintln!(
-                    "[{}] [ERROR] Method {} failed in {}ms: {} (code: {})",
-                    Utc::now().format("%Y-%m-%d %H:%M:%S UTC"),
-                    request.method,
-                    method_duration.as_millis(),
-                    error.message,
-                    error.code
+                mcp_log(
+                    LogLevel::Error,
+                    LogFields::for_project(&self.project_id).operation(&request.method).message(format!(
+                        "Method {} failed in {}ms: {} (code: {})",
+                        request.method,
+                        method_duration.as_millis(),
+                        error.message,
+                        error.code
+                    )),
                 );
                 JsonRpcResponse {
                     jsonrpc: "2.0".to_string(),
@@ -273,10 +325,12 @@ impl McpServer {
         };
 
         serde_json::to_string(&response).unwrap_or_else(|e| {
-            eprintln!(
-                "[{}] [ERROR] Failed to serialize response: {}", 
-                Utc::now().format("%Y-%m-%d %H:%M:%S UTC"), 
-                e
+            mcp_log(
+                LogLevel::Error,
+                LogFields::for_project(&self.project_id).operation(&request.method).message(format!(
+                    "Failed to serialize response: {}",
+                    e
+                )),
             );
             format!(
                 r#"{{"jsonrpc":"2.0","id":{},"error":{{"code":-32603,"message":"Failed to serialize response: {}"}}}}"#,
@@ -295,11 +349,7 @@ pub fn run_with_type(project_id: String, client_id: String, server_type: String)
             server.run();
         }
         Err(e) => {
-            eprintln!(
-                "[{}] [FATAL] Failed to start MCP server: {}",
-                Utc::now().format("%Y-%m-%d %H:%M:%S UTC"),
-                e
-            );
+            mcp_log(LogLevel::Fatal, LogFields::new(format!("Failed to start MCP server: {}", e)));
             std::process::exit(1);
         }
     }
@@ -309,21 +359,13 @@ pub fn run_with_type(project_id: String, client_id: String, server_type: String)
 #[allow(dead_code)]
 pub fn run_with_http(project_id: String, client_id: String, server_type: String, port: u16) {
     let runtime = Runtime::new().unwrap_or_else(|e| {
-        eprintln!(
-            "[{}] [FATAL] Failed to create Tokio runtime: {}",
-            Utc::now().format("%Y-%m-%d %H:%M:%S UTC"),
-            e
-        );
+        mcp_log(LogLevel::Fatal, LogFields::new(format!("Failed to create Tokio runtime: {}", e)));
         std::process::exit(1);
     });
 
     runtime.block_on(async {
         if let Err(e) = run_http_server(project_id, client_id, server_type, port).await {
-            eprintln!(
-                "[{}] [FATAL] HTTP MCP server failed: {}",
-                Utc::now().format("%Y-%m-%d %H:%M:%S UTC"),
-                e
-            );
+            mcp_log(LogLevel::Fatal, LogFields::new(format!("HTTP MCP server failed: {}", e)));
             std::process::exit(1);
         }
     });
@@ -335,39 +377,37 @@ async fn run_http_server(
     _server_type: String,
     port: u16,
 ) -> Result<(), Box<dyn std::error::Error>> {
-    // Create database connection pool
-    let database_url = std::env::var("DATABASE_URL")
-        .map_err(|_| "DATABASE_URL environment variable not set")?;
+    // Handlers and connectors read settings through Config::current
+    let config = Config::from_env()?;
+    Config::install(config.clone());
+    init_storage(&config.storage)?;
+
+    mcp_log(LogLevel::Info, LogFields::new("Connecting to database..."));
+    let db_pool = db::connect_pool(&config.database_url, &db::retry_policy()).await?;
     
-    eprintln!(
-        "[{}] [INFO] Connecting to database...",
-        Utc::now().format("%Y-%m-%d %H:%M:%S UTC")
-    );
-    let db_pool = PgPool::connect(&database_url).await?;
+    mcp_log(LogLevel::Info, LogFields::new("Connected to database successfully"));
+
+    let acceptor = TcpListener::new(format!("0.0.0.0:{}", port)).bind().await;
     
-    eprintln!(
-        "[{}] [INFO] Connected to database successfully",
-        Utc::now().format("%Y-%m-%d %H:%M:%S UTC")
-    );
+    mcp_log(LogLevel::Info, LogFields::new(format!("MCP HTTP server listening on port {}", port)));
+    
+    Server::new(acceptor).serve(http_service(db_pool)).await;
+    
+    Ok(())
+}
 
+fn http_service(db_pool: PgPool) -> Service {
     let router = Router::new()
         .push(Router::with_path("/operation/{client_id}/{project_id}").post(handle_mcp_request).get(handle_sse_connection))
         .push(Router::with_path("/analysis/{client_id}/{project_id}").post(handle_mcp_request).get(handle_sse_connection))
         .push(Router::with_path("/interaction/{client_id}/{project_id}").post(handle_mcp_request).get(handle_sse_connection))
+        .push(Router::with_path("/turns/{conversation_id}/{turn_id}").delete(handle_turn_end))
         .hoop(DbMiddleware { db_pool })
-        .hoop(CorsMiddleware);
+        .hoop(request_id());
 
-    let acceptor = TcpListener::new(format!("0.0.0.0:{}", port)).bind().await;
-    
-    eprintln!(
-        "[{}] [INFO] MCP HTTP server listening on port {}",
-        Utc::now().format("%Y-%m-%d %H:%M:%S UTC"),
-        port
-    );
-    
-    Server::new(acceptor).serve(router).await;
-    
-    Ok(())
+    // CORS runs at the service level so preflight requests, which no route
+    // matches, still reach it
+    Service::new(router).hoop(CorsMiddleware)
 }
 
 struct DbMiddleware {
@@ -386,34 +426,38 @@ struct CorsMiddleware;
 
 #[async_trait::async_trait]
 impl Handler for CorsMiddleware {
-    async fn handle(&self, _req: &mut Request, _depot: &mut Depot, res: &mut Response, ctrl: &mut FlowCtrl) {
+    async fn handle(&self, req: &mut Request, depot: &mut Depot, res: &mut Response, ctrl: &mut FlowCtrl) {
         if let Ok(value) = "*".parse() {
             res.headers_mut().insert("Access-Control-Allow-Origin", value);
         }
         if let Ok(value) = "POST, GET, OPTIONS".parse() {
             res.headers_mut().insert("Access-Control-Allow-Methods", value);
         }
-        if let Ok(value) = "Content-Type".parse() {
+        // Allow whatever headers the preflight asks for
+        let allow_headers = req
+            .headers()
+            .get("Access-Control-Request-Headers")
+            .cloned()
+            .or_else(|| "Content-Type, X-Request-Id".parse().ok());
+        if let Some(value) = allow_headers {
             res.headers_mut().insert("Access-Control-Allow-Headers", value);
         }
-        ctrl.call_next(_req, _depot, res).await;
-    }
-}
+        if let Ok(value) = "X-Request-Id".parse() {
+            res.headers_mut().insert("Access-Control-Expose-Headers", value);
+        }
 
-#[handler]
-async fn handle_mcp_request(req: &mut Request, depot: &mut Depot, res: &mut Response) {
-    let db_pool = match depot.get::<PgPool>("db_pool") {
-        Ok(pool) => pool.clone(),
-        Err(_) => {
-            tracing::error!("Database pool not found in depot");
-            res.status_code(StatusCode::INTERNAL_SERVER_ERROR);
-            res.render(Json(json!({
-                "error": "Internal server error: Database pool not available"
-            })));
+        // Answer preflight requests here; they carry no JSON-RPC body
+        if req.method() == salvo::http::Method::OPTIONS {
+            res.status_code(StatusCode::NO_CONTENT);
+            ctrl.skip_rest();
             return;
         }
-    };
-    
+        ctrl.call_next(req, depot, res).await;
+    }
+}
+
+/// Handlers for the client, project and server type named in the request path
+fn handlers_for_request(req: &Request, db_pool: PgPool) -> McpHandlers {
     // Extract client_id and project_id from URL parameters
     let client_id = req.param::<String>("client_id").unwrap_or_else(|| "unknown".to_string());
     let project_id = req.param::<String>("project_id").unwrap_or_else(|| "default".to_string());
@@ -429,20 +473,59 @@ async fn handle_mcp_request(req: &mut Request, depot: &mut Depot, res: &mut Resp
         "operation".to_string()
     };
     
-    // Create handlers for this specific request
-    let handlers = McpHandlers {
-        project_id: project_id.clone(),
-        client_id: client_id.clone(),
-        server_type: server_type.clone(),
+    // Claude sessions append the conversation (and turn) they run in so
+    // per-conversation datasource restrictions and per-turn caching can apply
+    let conversation_id = req.query::<String>("conversation_id");
+    let turn_id = req.query::<String>("turn_id");
+
+    McpHandlers {
+        project_id,
+        client_id,
+        server_type,
         db_pool,
+        conversation_id,
+        turn_id,
+    }
+}
+
+/// The backend reports a finished Claude query here so the tool results
+/// cached for its turn are dropped instead of waiting to expire
+#[handler]
+async fn handle_turn_end(req: &mut Request, res: &mut Response) {
+    if let (Some(conversation_id), Some(turn_id)) =
+        (req.param::<String>("conversation_id"), req.param::<String>("turn_id"))
+    {
+        handlers::tool_cache::end_turn(&conversation_id, &turn_id);
+    }
+    res.status_code(StatusCode::NO_CONTENT);
+}
+
+#[handler]
+async fn handle_mcp_request(req: &mut Request, depot: &mut Depot, res: &mut Response) {
+    let db_pool = match depot.get::<PgPool>("db_pool") {
+        Ok(pool) => pool.clone(),
+        Err(_) => {
+            tracing::error!("Database pool not found in depot");
+            res.status_code(StatusCode::INTERNAL_SERVER_ERROR);
+            res.render(Json(json!({
+                "error": "Internal server error: Database pool not available"
+            })));
+            return;
+        }
     };
     
-    eprintln!(
-        "[{}] [INFO] Processing MCP request for server_type: {}, client_id: {}, project_id: {}",
-        Utc::now().format("%Y-%m-%d %H:%M:%S UTC"),
-        server_type,
-        client_id,
-        project_id
+    // Create handlers for this specific request
+    let handlers = handlers_for_request(req, db_pool);
+    let streaming = progress::wants_event_stream(req.header::<String>("accept").as_deref());
+    
+    mcp_log(
+        LogLevel::Info,
+        LogFields::for_project(&handlers.project_id).message(format!(
+            "Processing MCP request for server_type: {}, client_id: {}, project_id: {}",
+            handlers.server_type,
+            handlers.client_id,
+            handlers.project_id
+        )),
     );
     
     // Parse JSON-RPC request
@@ -465,6 +548,17 @@ async fn handle_mcp_request(req: &mut Request, depot: &mut Depot, res: &mut Resp
     };
 
     let request_text = String::from_utf8_lossy(request_body);
+    if let Some(items) = batch::batch_items(&request_text) {
+        match batch::dispatch_batch(&handlers, items).await {
+            Some(responses) => res.render(Json(responses)),
+            // Only notifications: accepted, nothing to answer
+            None => {
+                res.status_code(StatusCode::ACCEPTED);
+            }
+        }
+        return;
+    }
+
     let json_request: JsonRpcRequest = match serde_json::from_str(&request_text) {
         Ok(req) => req,
         Err(e) => {
@@ -483,13 +577,24 @@ async fn handle_mcp_request(req: &mut Request, depot: &mut Depot, res: &mut Resp
         }
     };
 
+    // Streaming clients follow a tool call's progress; everyone else gets
+    // the one-shot JSON response
+    if streaming && json_request.method == "tools/call" {
+        salvo::sse::stream(res, progress::tool_call_events(dispatch_request(handlers, json_request)));
+        return;
+    }
+
+    res.render(Json(dispatch_request(handlers, json_request).await));
+}
+
+async fn dispatch_request(handlers: McpHandlers, json_request: JsonRpcRequest) -> JsonRpcResponse {
     // Handle the request
     let result = match json_request.method.as_str() {
         "initialize" => handlers.handle_initialize(json_request.params).await,
         "notifications/initialized" => {
-            eprintln!(
-                "[{}] [INFO] Client initialization complete - MCP server fully ready",
-                Utc::now().format("%Y-%m-%d %H:%M:%S UTC")
+            mcp_log(
+                LogLevel::Info,
+                LogFields::for_project(&handlers.project_id).operation(&json_request.method).message("Client initialization complete - MCP server fully ready"),
             );
             Ok(serde_json::json!({}))
         }
@@ -504,7 +609,7 @@ async fn handle_mcp_request(req: &mut Request, depot: &mut Depot, res: &mut Resp
         }),
     };
 
-    let response = match result {
+    match result {
         Ok(value) => JsonRpcResponse {
             jsonrpc: "2.0".to_string(),
             id: json_request.id,
@@ -517,17 +622,28 @@ async fn handle_mcp_request(req: &mut Request, depot: &mut Depot, res: &mut Resp
             result: None,
             error: Some(error),
         },
-    };
-
-    res.render(Json(response));
+    }
 }
 
+/// Server-sent events endpoint. With a JSON-RPC `tools/call` in the
+/// `request` query parameter the call is streamed like a POST with
+/// `Accept: text/event-stream`; otherwise only a `connected` event is sent.
 #[handler]
-async fn handle_sse_connection(req: &mut Request, _depot: &mut Depot, res: &mut Response) {
+async fn handle_sse_connection(req: &mut Request, depot: &mut Depot, res: &mut Response) {
     use salvo::sse::{self as sse, SseEvent};
     use futures_util::stream;
     use std::convert::Infallible;
     
+    let tool_call = req
+        .query::<String>("request")
+        .and_then(|raw| serde_json::from_str::<JsonRpcRequest>(&raw).ok())
+        .filter(|request| request.method == "tools/call");
+    if let (Some(json_request), Ok(db_pool)) = (tool_call, depot.get::<PgPool>("db_pool")) {
+        let handlers = handlers_for_request(req, db_pool.clone());
+        sse::stream(res, progress::tool_call_events(dispatch_request(handlers, json_request)));
+        return;
+    }
+    
     // Extract client_id and project_id from URL parameters for logging
     let client_id = req.param::<String>("client_id").unwrap_or_else(|| "unknown".to_string());
     let project_id = req.param::<String>("project_id").unwrap_or_else(|| "default".to_string());
@@ -543,12 +659,14 @@ async fn handle_sse_connection(req: &mut Request, _depot: &mut Depot, res: &mut
         "operation"
     };
     
-    eprintln!(
-        "[{}] [INFO] SSE connection established for server_type: {}, client_id: {}, project_id: {}",
-        Utc::now().format("%Y-%m-%d %H:%M:%S UTC"),
-        server_type,
-        client_id,
-        project_id
+    mcp_log(
+        LogLevel::Info,
+        LogFields::for_project(&project_id).message(format!(
+            "SSE connection established for server_type: {}, client_id: {}, project_id: {}",
+            server_type,
+            client_id,
+            project_id
+        )),
     );
     
     let event_stream = stream::iter(vec![
@@ -561,3 +679,126 @@ async fn handle_sse_connection(req: &mut Request, _depot: &mut Depot, res: &mut
     
     sse::stream(res, event_stream);
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use salvo::test::{ResponseExt, TestClient};
+
+    #[tokio::test]
+    async fn preflight_requests_get_204_without_reaching_json_rpc() {
+        // Never connects: the preflight must not touch the database
+        let db_pool = PgPool::connect_lazy("postgres://localhost/unused").unwrap();
+        let service = http_service(db_pool);
+
+        let mut res = TestClient::options("http://127.0.0.1:7670/operation/client/project")
+            .add_header("Origin", "http://localhost:7690", true)
+            .add_header("Access-Control-Request-Method", "POST", true)
+            .add_header("Access-Control-Request-Headers", "content-type, mcp-session-id", true)
+            .send(&service)
+            .await;
+
+        assert_eq!(res.status_code, Some(StatusCode::NO_CONTENT));
+        assert_eq!(res.headers().get("Access-Control-Allow-Origin").unwrap(), "*");
+        assert_eq!(
+            res.headers().get("Access-Control-Allow-Headers").unwrap(),
+            "content-type, mcp-session-id"
+        );
+        assert_eq!(res.take_string().await.unwrap(), "");
+    }
+
+    #[tokio::test]
+    async fn ending_a_turn_drops_its_cached_tool_results() {
+        let db_pool = PgPool::connect_lazy("postgres://localhost/unused").unwrap();
+        let service = http_service(db_pool);
+        handlers::tool_cache::insert("conversation:turn", "schema_get:null".to_string(), json!([]));
+
+        let res = TestClient::delete("http://127.0.0.1:7670/turns/conversation/turn")
+            .send(&service)
+            .await;
+
+        assert_eq!(res.status_code, Some(StatusCode::NO_CONTENT));
+        assert_eq!(handlers::tool_cache::get("conversation:turn", "schema_get:null"), None);
+    }
+
+    #[tokio::test]
+    async fn batch_requests_get_responses_in_order() {
+        let db_pool = PgPool::connect_lazy("postgres://localhost/unused").unwrap();
+        let service = http_service(db_pool);
+
+        let mut res = TestClient::post("http://127.0.0.1:7670/operation/client/project")
+            .json(&json!([
+                { "jsonrpc": "2.0", "id": 1, "method": "notifications/initialized" },
+                { "jsonrpc": "2.0", "id": 2, "method": "tools/unknown" },
+                { "jsonrpc": "2.0", "id": 3 }
+            ]))
+            .send(&service)
+            .await;
+
+        let responses: serde_json::Value = res.take_json().await.unwrap();
+        let responses = responses.as_array().unwrap();
+        assert_eq!(responses.len(), 3);
+        assert_eq!(responses[0]["id"], json!(1));
+        assert_eq!(responses[0]["result"], json!({}));
+        assert_eq!(responses[1]["id"], json!(2));
+        assert_eq!(responses[1]["error"]["code"], json!(METHOD_NOT_FOUND));
+        assert_eq!(responses[2]["id"], json!(3));
+        assert_eq!(responses[2]["error"]["code"], json!(INVALID_REQUEST));
+    }
+
+    #[tokio::test]
+    async fn an_empty_batch_is_a_single_invalid_request_error() {
+        let db_pool = PgPool::connect_lazy("postgres://localhost/unused").unwrap();
+        let service = http_service(db_pool);
+
+        let mut res = TestClient::post("http://127.0.0.1:7670/operation/client/project")
+            .raw_json("[]")
+            .send(&service)
+            .await;
+
+        let response: serde_json::Value = res.take_json().await.unwrap();
+        assert_eq!(response["error"]["code"], json!(INVALID_REQUEST));
+        assert!(response["id"].is_null());
+    }
+
+    #[tokio::test]
+    async fn oversized_batches_are_rejected_without_running() {
+        let db_pool = PgPool::connect_lazy("postgres://localhost/unused").unwrap();
+        let service = http_service(db_pool);
+        let batch: Vec<_> = (0..=batch::MAX_BATCH_SIZE)
+            .map(|id| json!({ "jsonrpc": "2.0", "id": id, "method": "tools/unknown" }))
+            .collect();
+
+        let mut res = TestClient::post("http://127.0.0.1:7670/operation/client/project")
+            .json(&batch)
+            .send(&service)
+            .await;
+
+        let response: serde_json::Value = res.take_json().await.unwrap();
+        assert_eq!(response["error"]["code"], json!(INVALID_REQUEST));
+        assert!(response["id"].is_null());
+    }
+
+    #[tokio::test]
+    async fn notifications_in_a_batch_get_no_response() {
+        let db_pool = PgPool::connect_lazy("postgres://localhost/unused").unwrap();
+        let service = http_service(db_pool);
+
+        let mut res = TestClient::post("http://127.0.0.1:7670/operation/client/project")
+            .json(&json!([
+                { "jsonrpc": "2.0", "method": "notifications/initialized" },
+                { "jsonrpc": "2.0", "id": 7, "method": "tools/unknown" }
+            ]))
+            .send(&service)
+            .await;
+        let responses: serde_json::Value = res.take_json().await.unwrap();
+        assert_eq!(responses.as_array().unwrap().len(), 1);
+        assert_eq!(responses[0]["id"], json!(7));
+
+        let res = TestClient::post("http://127.0.0.1:7670/operation/client/project")
+            .json(&json!([{ "jsonrpc": "2.0", "method": "notifications/initialized" }]))
+            .send(&service)
+            .await;
+        assert_eq!(res.status_code, Some(StatusCode::ACCEPTED));
+    }
+}
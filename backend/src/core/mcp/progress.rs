@@ -0,0 +1,136 @@
+//! Progress events for streamed `tools/call` requests
+//!
+//! A client that sends `Accept: text/event-stream` gets the call as a
+//! server-sent event stream: `progress` events while the tool runs and one
+//! `result` event carrying the JSON-RPC response. Tool handlers report
+//! progress with [`report_progress`], which does nothing when the call isn't
+//! being streamed.
+
+use futures_util::Stream;
+use salvo::sse::SseEvent;
+use serde::Serialize;
+use std::convert::Infallible;
+use std::future::Future;
+use tokio::sync::mpsc::{self, UnboundedSender};
+
+use super::types::JsonRpcResponse;
+
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct ProgressUpdate {
+    /// Short machine-readable step, e.g. "connecting", "executing", "rows"
+    pub stage: String,
+    pub message: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub row_count: Option<u64>,
+}
+
+#[derive(Debug)]
+enum StreamEvent {
+    Progress(ProgressUpdate),
+    // Boxed so progress updates don't carry a full response's worth of space
+    Result(Box<JsonRpcResponse>),
+}
+
+tokio::task_local! {
+    static PROGRESS: UnboundedSender<StreamEvent>;
+}
+
+fn send(update: ProgressUpdate) {
+    let _ = PROGRESS.try_with(|tx| tx.send(StreamEvent::Progress(update)));
+}
+
+/// Report a step of the running tool call to a streaming client
+pub fn report_progress(stage: &str, message: impl Into<String>) {
+    send(ProgressUpdate {
+        stage: stage.to_string(),
+        message: message.into(),
+        row_count: None,
+    });
+}
+
+/// Report how many rows the running tool call has produced so far
+pub fn report_rows(row_count: u64) {
+    send(ProgressUpdate {
+        stage: "rows".to_string(),
+        message: format!("{} rows", row_count),
+        row_count: Some(row_count),
+    });
+}
+
+/// Whether the client asked for the call as a server-sent event stream
+pub fn wants_event_stream(accept: Option<&str>) -> bool {
+    accept.is_some_and(|accept| accept.contains("text/event-stream"))
+}
+
+/// Run `call` in the background and stream its progress, ending with a
+/// `result` event
+pub fn tool_call_events<F>(call: F) -> impl Stream<Item = Result<SseEvent, Infallible>>
+where
+    F: Future<Output = JsonRpcResponse> + Send + 'static,
+{
+    let (tx, rx) = mpsc::unbounded_channel();
+    let result_tx = tx.clone();
+    tokio::spawn(async move {
+        let response = PROGRESS.scope(tx, call).await;
+        let _ = result_tx.send(StreamEvent::Result(Box::new(response)));
+    });
+
+    // The stream ends after the result, or early if the call task panicked
+    futures_util::stream::unfold((rx, false), |(mut rx, done)| async move {
+        if done {
+            return None;
+        }
+        let event = rx.recv().await?;
+        let (sse, done) = match event {
+            StreamEvent::Progress(update) => (
+                SseEvent::default()
+                    .name("progress")
+                    .text(serde_json::to_string(&update).unwrap_or_default()),
+                false,
+            ),
+            StreamEvent::Result(response) => (
+                SseEvent::default()
+                    .name("result")
+                    .text(serde_json::to_string(&response).unwrap_or_default()),
+                true,
+            ),
+        };
+        Some((Ok(sse), (rx, done)))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures_util::StreamExt;
+    use serde_json::json;
+
+    #[tokio::test]
+    async fn a_streamed_query_emits_progress_then_the_result() {
+        // Stands in for datasource_query: the steps it reports, then its response
+        let call = async {
+            report_progress("connecting", "Connecting to datasource");
+            report_progress("executing", "Executing query");
+            report_rows(42);
+            JsonRpcResponse {
+                jsonrpc: "2.0".to_string(),
+                id: Some(json!(7)),
+                result: Some(json!({ "row_count": 42 })),
+                error: None,
+            }
+        };
+
+        let events: Vec<String> = tool_call_events(call)
+            .map(|event| event.unwrap().to_string())
+            .collect()
+            .await;
+
+        assert_eq!(events.len(), 4);
+        assert!(events[0].contains("event:progress") && events[0].contains("\"stage\":\"connecting\""));
+        assert!(events[2].contains("\"row_count\":42"));
+        assert!(events[3].contains("event:result") && events[3].contains("\"id\":7"));
+
+        // Outside a streamed call reporting is a no-op
+        report_progress("executing", "ignored");
+    }
+}
@@ -0,0 +1,187 @@
+//! Supervision of the MCP server child process
+//!
+//! The backend runs `mcp_server --http` alongside itself. A supervisor task
+//! owns the child, logs how it exited and starts it again after an
+//! exponential backoff. The backoff resets once a run stays up for
+//! `STABLE_RUN` so an occasional crash doesn't accumulate delay. The current
+//! state is reported by the health endpoint.
+//!
+//! The server listens on `MCP_SERVER_PORT` (default 7670); the same port is
+//! used in the MCP URLs written into each project's Claude settings.
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::path::PathBuf;
+use std::sync::LazyLock;
+use tokio::process::{Child, Command};
+use tokio::sync::RwLock;
+use tokio::time::{sleep, Duration, Instant};
+
+pub use crate::utils::config::{parse_mcp_server_port, DEFAULT_MCP_SERVER_PORT};
+
+const INITIAL_BACKOFF: Duration = Duration::from_secs(1);
+const MAX_BACKOFF: Duration = Duration::from_secs(60);
+const STABLE_RUN: Duration = Duration::from_secs(60);
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum McpProcessState {
+    #[default]
+    NotStarted,
+    Running,
+    /// Exited; waiting out the backoff before the next start
+    Restarting,
+    /// The last start attempt failed to spawn the binary
+    SpawnFailed,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct McpProcessStatus {
+    pub state: McpProcessState,
+    pub pid: Option<u32>,
+    pub started_at: Option<DateTime<Utc>>,
+    pub restarts: u32,
+    pub last_exit: Option<String>,
+    pub last_exit_at: Option<DateTime<Utc>>,
+}
+
+static MCP_STATUS: LazyLock<RwLock<McpProcessStatus>> =
+    LazyLock::new(|| RwLock::new(McpProcessStatus::default()));
+
+pub async fn mcp_process_status() -> McpProcessStatus {
+    MCP_STATUS.read().await.clone()
+}
+
+/// Port the MCP server listens on. `Config::from_env` rejects invalid values
+/// at startup, so the fallback only matters outside the backend process.
+pub fn mcp_server_port() -> u16 {
+    parse_mcp_server_port(std::env::var("MCP_SERVER_PORT").ok().as_deref()).unwrap_or(DEFAULT_MCP_SERVER_PORT)
+}
+
+/// Base URL of the MCP HTTP server, e.g. `http://localhost:7670`
+pub fn mcp_server_base_url() -> String {
+    format!("http://localhost:{}", mcp_server_port())
+}
+
+/// Delay before restart number `consecutive_failures` (counting from 0):
+/// doubles from `INITIAL_BACKOFF` up to `MAX_BACKOFF`
+pub fn restart_backoff(consecutive_failures: u32) -> Duration {
+    INITIAL_BACKOFF
+        .checked_mul(2u32.saturating_pow(consecutive_failures))
+        .map_or(MAX_BACKOFF, |delay| delay.min(MAX_BACKOFF))
+}
+
+/// Look for the MCP server binary: debug (dev), production (same dir),
+/// /app (container), then release (dev fallback)
+pub fn find_mcp_server_binary() -> Option<PathBuf> {
+    let current_dir = std::env::current_dir().unwrap_or_else(|_| PathBuf::from("."));
+    [
+        current_dir.join("target/debug/mcp_server"),
+        current_dir.join("mcp_server"),
+        PathBuf::from("/app/mcp_server"),
+        current_dir.join("target/release/mcp_server"),
+    ]
+    .into_iter()
+    .find(|path| path.exists())
+    .map(|path| path.canonicalize().unwrap_or(path))
+}
+
+fn spawn_mcp_server(path: &PathBuf, port: u16) -> std::io::Result<Child> {
+    Command::new(path)
+        .arg("--http")
+        .arg("--port")
+        .arg(port.to_string())
+        .spawn()
+}
+
+async fn record_started(child: &Child) {
+    let mut status = MCP_STATUS.write().await;
+    status.state = McpProcessState::Running;
+    status.pid = child.id();
+    status.started_at = Some(Utc::now());
+}
+
+/// Start the MCP server and keep it running. Fails if the first start does
+/// not survive its first second, which usually means a configuration
+/// problem rather than a crash worth retrying.
+pub async fn start_mcp_supervisor(path: PathBuf, port: u16) -> Result<(), Box<dyn std::error::Error>> {
+    let mut child = spawn_mcp_server(&path, port)?;
+
+    // Give the server a moment to start
+    sleep(Duration::from_millis(1000)).await;
+    if let Some(status) = child.try_wait()? {
+        return Err(format!("MCP server exited immediately with status: {}", status).into());
+    }
+    record_started(&child).await;
+    tracing::info!("✅ MCP server started successfully on port {}", port);
+
+    tokio::spawn(supervise(path, port, child));
+    Ok(())
+}
+
+async fn supervise(path: PathBuf, port: u16, mut child: Child) {
+    let mut consecutive_failures = 0u32;
+    let mut started = Instant::now();
+
+    loop {
+        let exit = match child.wait().await {
+            Ok(status) => status.to_string(),
+            Err(e) => format!("failed to wait for process: {}", e),
+        };
+        if started.elapsed() >= STABLE_RUN {
+            consecutive_failures = 0;
+        }
+        let delay = restart_backoff(consecutive_failures);
+        tracing::warn!("MCP server exited ({}); restarting in {:?}", exit, delay);
+
+        {
+            let mut status = MCP_STATUS.write().await;
+            status.state = McpProcessState::Restarting;
+            status.pid = None;
+            status.last_exit = Some(exit);
+            status.last_exit_at = Some(Utc::now());
+        }
+
+        // Keep trying to spawn until one succeeds, backing off between attempts
+        child = loop {
+            sleep(restart_backoff(consecutive_failures)).await;
+            consecutive_failures = consecutive_failures.saturating_add(1);
+            MCP_STATUS.write().await.restarts += 1;
+
+            match spawn_mcp_server(&path, port) {
+                Ok(child) => break child,
+                Err(e) => {
+                    tracing::error!("Failed to restart MCP server: {}", e);
+                    MCP_STATUS.write().await.state = McpProcessState::SpawnFailed;
+                }
+            }
+        };
+        started = Instant::now();
+        record_started(&child).await;
+        tracing::info!("MCP server restarted (pid {:?})", child.id());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn backoff_doubles_up_to_the_cap() {
+        assert_eq!(restart_backoff(0), Duration::from_secs(1));
+        assert_eq!(restart_backoff(1), Duration::from_secs(2));
+        assert_eq!(restart_backoff(4), Duration::from_secs(16));
+        assert_eq!(restart_backoff(6), MAX_BACKOFF);
+        assert_eq!(restart_backoff(u32::MAX), MAX_BACKOFF);
+    }
+
+    #[test]
+    fn port_defaults_when_unset_and_rejects_garbage() {
+        assert_eq!(parse_mcp_server_port(None), Ok(DEFAULT_MCP_SERVER_PORT));
+        assert_eq!(parse_mcp_server_port(Some("")), Ok(DEFAULT_MCP_SERVER_PORT));
+        assert_eq!(parse_mcp_server_port(Some("7771")), Ok(7771));
+        assert!(parse_mcp_server_port(Some("0")).is_err());
+        assert!(parse_mcp_server_port(Some("70000")).is_err());
+        assert!(parse_mcp_server_port(Some("mcp")).is_err());
+    }
+}
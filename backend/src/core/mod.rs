@@ -1,7 +1,10 @@
 pub mod analysis;
+pub mod audit;
+pub mod backup;
 pub mod claude;
 pub mod datasources;
 pub mod mcp;
+pub mod mcp_process;
 pub mod projects;
 pub mod sessions;
 pub mod tools;
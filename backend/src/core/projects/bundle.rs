@@ -0,0 +1,346 @@
+//! Project export bundles
+//!
+//! A bundle is a JSON document with a project's metadata, context, CLAUDE.md,
+//! saved queries and datasource definitions. Connection secrets are redacted
+//! unless the export asked for them; a project imported from a redacted
+//! bundle needs its datasource credentials re-entered. Importing creates a
+//! new project with fresh ids, and references to the old project and
+//! datasource ids in the context and CLAUDE.md are rewritten to the new ones.
+
+use std::collections::HashSet;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use super::duplicate::rewrite_ids;
+use super::manager::query_file_stem;
+use crate::utils::datasource::common::redaction::redact_connection_config;
+
+/// Bump when the bundle layout changes incompatibly
+pub const BUNDLE_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProjectBundle {
+    pub version: u32,
+    pub exported_at: DateTime<Utc>,
+    /// Whether datasource configs carry real credentials
+    pub secrets_included: bool,
+    pub project: BundleProject,
+    #[serde(default)]
+    pub claude_md: Option<String>,
+    #[serde(default)]
+    pub saved_queries: Vec<BundleQuery>,
+    #[serde(default)]
+    pub datasources: Vec<BundleDatasource>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BundleProject {
+    pub id: String,
+    pub name: String,
+    #[serde(default)]
+    pub settings: Option<Value>,
+    #[serde(default)]
+    pub organization_settings: Option<Value>,
+    #[serde(default)]
+    pub context: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BundleQuery {
+    pub name: String,
+    pub content: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, sqlx::FromRow)]
+pub struct BundleDatasource {
+    pub id: String,
+    pub name: String,
+    pub source_type: String,
+    pub connection_config: Value,
+    #[serde(default)]
+    pub schema_info: Option<Value>,
+    #[serde(default)]
+    pub table_list: Option<Value>,
+}
+
+impl ProjectBundle {
+    /// A bundle of the given parts; configs are redacted unless `include_secrets`
+    pub fn new(
+        project: BundleProject,
+        claude_md: Option<String>,
+        saved_queries: Vec<BundleQuery>,
+        datasources: Vec<BundleDatasource>,
+        include_secrets: bool,
+    ) -> Self {
+        let datasources = datasources
+            .into_iter()
+            .map(|mut datasource| {
+                if !include_secrets {
+                    datasource.connection_config = redact_connection_config(&datasource.connection_config);
+                }
+                datasource
+            })
+            .collect();
+
+        Self {
+            version: BUNDLE_VERSION,
+            exported_at: Utc::now(),
+            secrets_included: include_secrets,
+            project,
+            claude_md,
+            saved_queries,
+            datasources,
+        }
+    }
+
+    /// Check a bundle before importing it
+    pub fn validate(&self) -> Result<(), String> {
+        if self.version != BUNDLE_VERSION {
+            return Err(format!(
+                "Unsupported bundle version {} (expected {})",
+                self.version, BUNDLE_VERSION
+            ));
+        }
+        if self.project.name.trim().is_empty() {
+            return Err("Bundle project name cannot be empty".to_string());
+        }
+
+        let mut ids = HashSet::new();
+        let mut names = HashSet::new();
+        for datasource in &self.datasources {
+            if datasource.id.is_empty() || datasource.name.trim().is_empty() || datasource.source_type.is_empty() {
+                return Err("Bundle datasources need an id, name and source_type".to_string());
+            }
+            if !datasource.connection_config.is_object() {
+                return Err(format!("Datasource '{}' has no connection_config object", datasource.name));
+            }
+            if !ids.insert(datasource.id.as_str()) {
+                return Err(format!("Duplicate datasource id '{}' in bundle", datasource.id));
+            }
+            // Datasource names are unique per project regardless of case
+            if !names.insert(datasource.name.to_lowercase()) {
+                return Err(format!("Duplicate datasource name '{}' in bundle", datasource.name));
+            }
+        }
+
+        let mut query_names = HashSet::new();
+        for query in &self.saved_queries {
+            if query.name.trim().is_empty() {
+                return Err("Saved query names cannot be empty".to_string());
+            }
+            // Names that map to the same file would overwrite each other on import
+            if !query_names.insert(query_file_stem(&query.name)) {
+                return Err(format!("Duplicate saved query '{}' in bundle", query.name));
+            }
+        }
+        Ok(())
+    }
+
+    /// The bundle as it will be imported: the project and every datasource get
+    /// fresh ids, and the context and CLAUDE.md refer to them
+    pub fn with_new_ids(&self, project_id: &str) -> Self {
+        let replacements: Vec<(String, String)> = std::iter::once((self.project.id.clone(), project_id.to_string()))
+            .chain(
+                self.datasources
+                    .iter()
+                    .map(|datasource| (datasource.id.clone(), Uuid::new_v4().to_string())),
+            )
+            .collect();
+
+        let mut bundle = self.clone();
+        bundle.project.id = project_id.to_string();
+        bundle.project.context = bundle.project.context.map(|context| rewrite_ids(&context, &replacements));
+        bundle.claude_md = bundle.claude_md.map(|content| rewrite_ids(&content, &replacements));
+        for (datasource, (_, new_id)) in bundle.datasources.iter_mut().zip(replacements.iter().skip(1)) {
+            datasource.id = new_id.clone();
+        }
+        bundle
+    }
+}
+
+/// id, name, settings, organization_settings, context
+type ProjectRow = (String, String, Option<Value>, Option<Value>, Option<String>);
+
+/// The database part of a project's bundle: metadata and datasources.
+/// `None` when the project isn't in `client_id` or was deleted.
+pub async fn load_project_rows(
+    db: &PgPool,
+    project_id: &str,
+    client_id: Uuid,
+) -> Result<Option<(BundleProject, Vec<BundleDatasource>)>, sqlx::Error> {
+    let project: Option<ProjectRow> = sqlx::query_as(
+        "SELECT id, name, settings, organization_settings, context FROM projects \
+         WHERE id = $1 AND client_id = $2 AND deleted_at IS NULL",
+    )
+    .bind(project_id)
+    .bind(client_id)
+    .fetch_optional(db)
+    .await?;
+    let Some((id, name, settings, organization_settings, context)) = project else {
+        return Ok(None);
+    };
+
+    let datasources = sqlx::query_as::<_, BundleDatasource>(
+        "SELECT id, name, source_type, connection_config, schema_info, table_list FROM data_sources \
+         WHERE project_id = $1 AND deleted_at IS NULL ORDER BY created_at",
+    )
+    .bind(project_id)
+    .fetch_all(db)
+    .await?;
+
+    Ok(Some((
+        BundleProject { id, name, settings, organization_settings, context },
+        datasources,
+    )))
+}
+
+/// Create the project and datasources of a bundle that already has its new
+/// ids (see `ProjectBundle::with_new_ids`), owned by `user_id`
+pub async fn insert_project_rows(
+    db: &PgPool,
+    bundle: &ProjectBundle,
+    client_id: Uuid,
+    user_id: Uuid,
+) -> Result<(), sqlx::Error> {
+    let mut tx = db.begin().await?;
+
+    sqlx::query(
+        "INSERT INTO projects (id, name, client_id, user_id, settings, organization_settings, context) \
+         VALUES ($1, $2, $3, $4, $5, $6, $7)",
+    )
+    .bind(&bundle.project.id)
+    .bind(&bundle.project.name)
+    .bind(client_id)
+    .bind(user_id)
+    .bind(&bundle.project.settings)
+    .bind(&bundle.project.organization_settings)
+    .bind(&bundle.project.context)
+    .execute(&mut *tx)
+    .await?;
+
+    sqlx::query("INSERT INTO project_members (project_id, user_id, role) VALUES ($1, $2, 'owner')")
+        .bind(&bundle.project.id)
+        .bind(user_id)
+        .execute(&mut *tx)
+        .await?;
+
+    for datasource in &bundle.datasources {
+        sqlx::query(
+            r#"
+            INSERT INTO data_sources (id, project_id, name, source_type, connection_config, schema_info, table_list, created_at, updated_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, NOW(), NOW())
+            "#,
+        )
+        .bind(&datasource.id)
+        .bind(&bundle.project.id)
+        .bind(&datasource.name)
+        .bind(&datasource.source_type)
+        .bind(&datasource.connection_config)
+        .bind(&datasource.schema_info)
+        .bind(&datasource.table_list)
+        .execute(&mut *tx)
+        .await?;
+    }
+
+    tx.commit().await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn exported(include_secrets: bool) -> ProjectBundle {
+        ProjectBundle::new(
+            BundleProject {
+                id: "proj-1".to_string(),
+                name: "Sales".to_string(),
+                settings: Some(json!({ "theme": "dark" })),
+                organization_settings: None,
+                context: Some("Use ds-1 for orders".to_string()),
+            },
+            Some("# Project proj-1\nOrders live in ds-1.\n".to_string()),
+            vec![BundleQuery {
+                name: "top_customers".to_string(),
+                content: "SELECT * FROM customers LIMIT 10".to_string(),
+            }],
+            vec![BundleDatasource {
+                id: "ds-1".to_string(),
+                name: "Orders".to_string(),
+                source_type: "postgresql".to_string(),
+                connection_config: json!({ "host": "db.internal", "password": "hunter2" }),
+                schema_info: Some(json!({ "tables": ["orders"] })),
+                table_list: Some(json!(["orders"])),
+            }],
+            include_secrets,
+        )
+    }
+
+    #[test]
+    fn exported_bundles_import_into_a_fresh_project() {
+        let bundle = exported(false);
+        let json = serde_json::to_string(&bundle).unwrap();
+        assert!(!json.contains("hunter2"));
+
+        let parsed: ProjectBundle = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed, bundle);
+        parsed.validate().unwrap();
+
+        let imported = parsed.with_new_ids("proj-2");
+        let new_datasource_id = imported.datasources[0].id.clone();
+        assert_ne!(new_datasource_id, "ds-1");
+        assert_eq!(imported.project.id, "proj-2");
+        assert_eq!(imported.project.name, "Sales");
+        assert_eq!(imported.project.settings, Some(json!({ "theme": "dark" })));
+        assert_eq!(imported.project.context, Some(format!("Use {} for orders", new_datasource_id)));
+        assert_eq!(
+            imported.claude_md,
+            Some(format!("# Project proj-2\nOrders live in {}.\n", new_datasource_id))
+        );
+        assert_eq!(imported.saved_queries, bundle.saved_queries);
+        assert_eq!(imported.datasources[0].connection_config["password"], json!("****"));
+        assert_eq!(imported.datasources[0].schema_info, bundle.datasources[0].schema_info);
+
+        // Secrets survive when the export asked for them
+        let with_secrets = exported(true);
+        assert!(with_secrets.secrets_included);
+        assert_eq!(with_secrets.datasources[0].connection_config["password"], json!("hunter2"));
+    }
+
+    #[test]
+    fn invalid_bundles_are_rejected() {
+        let mut future = exported(false);
+        future.version = BUNDLE_VERSION + 1;
+        assert!(future.validate().unwrap_err().contains("Unsupported bundle version"));
+
+        let mut duplicate_ids = exported(false);
+        duplicate_ids.datasources.push(duplicate_ids.datasources[0].clone());
+        assert!(duplicate_ids.validate().is_err());
+
+        let mut unnamed = exported(false);
+        unnamed.project.name = " ".to_string();
+        assert!(unnamed.validate().is_err());
+
+        let mut same_name = exported(false);
+        let mut renamed = same_name.datasources[0].clone();
+        renamed.id = "ds-2".to_string();
+        renamed.name = "ORDERS".to_string();
+        same_name.datasources.push(renamed);
+        assert!(same_name.validate().unwrap_err().contains("Duplicate datasource name"));
+
+        // "top customers" and "top_customers" are both saved as top_customers.txt
+        let mut same_file = exported(false);
+        same_file.saved_queries.push(BundleQuery {
+            name: "top customers".to_string(),
+            content: "SELECT 1".to_string(),
+        });
+        assert!(same_file.validate().unwrap_err().contains("Duplicate saved query"));
+
+        // Missing required fields fail to parse at all
+        assert!(serde_json::from_value::<ProjectBundle>(json!({ "version": 1 })).is_err());
+    }
+}
@@ -0,0 +1,333 @@
+//! Duplicating a project's configuration into a new project
+//!
+//! The copy gets the source's settings, context and datasources (with their
+//! connection configs and cached schemas) in the database, plus CLAUDE.md and
+//! the saved queries from the project directory. Conversations, uploads and
+//! query history stay with the source. Every copied row gets a fresh id, and
+//! the source's ids in CLAUDE.md and the context are rewritten to the new
+//! ones. File-backed datasources keep pointing at the source project's files.
+
+use std::fs;
+use std::path::Path;
+
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::utils::AppError;
+
+/// Result of `duplicate_project_rows`: the new project and, for each copied
+/// datasource, its `(source id, copy id)`
+#[derive(Debug, Clone)]
+pub struct DuplicatedProject {
+    pub project_id: String,
+    pub name: String,
+    pub datasource_ids: Vec<(String, String)>,
+}
+
+impl DuplicatedProject {
+    /// `(old, new)` id pairs: the project's and each datasource's
+    pub fn id_replacements(&self, source_project_id: &str) -> Vec<(String, String)> {
+        std::iter::once((source_project_id.to_string(), self.project_id.clone()))
+            .chain(self.datasource_ids.iter().cloned())
+            .collect()
+    }
+}
+
+/// `text` with every old id replaced by its new one
+pub fn rewrite_ids(text: &str, replacements: &[(String, String)]) -> String {
+    replacements
+        .iter()
+        .fold(text.to_string(), |text, (old, new)| text.replace(old, new))
+}
+
+/// Default name for a copy that wasn't given one
+pub fn copy_name(source_name: &str) -> String {
+    format!("{} (copy)", source_name)
+}
+
+/// Create the new project, owned by `user_id` in `client_id`, with copies of
+/// the source's datasources. Returns `None` when the source doesn't exist in
+/// `client_id` or was deleted. Runs in one transaction.
+pub async fn duplicate_project_rows(
+    db: &PgPool,
+    source_project_id: &str,
+    client_id: Uuid,
+    user_id: Uuid,
+    name: Option<String>,
+) -> Result<Option<DuplicatedProject>, sqlx::Error> {
+    let mut tx = db.begin().await?;
+
+    let source: Option<(String, Option<String>)> = sqlx::query_as(
+        "SELECT name, context FROM projects WHERE id = $1 AND client_id = $2 AND deleted_at IS NULL",
+    )
+    .bind(source_project_id)
+    .bind(client_id)
+    .fetch_optional(&mut *tx)
+    .await?;
+    let Some((source_name, source_context)) = source else {
+        return Ok(None);
+    };
+
+    let project_id = Uuid::new_v4().to_string();
+    let name = name.unwrap_or_else(|| copy_name(&source_name));
+
+    let datasource_ids: Vec<(String, String)> = sqlx::query_scalar::<_, String>(
+        "SELECT id FROM data_sources WHERE project_id = $1 AND deleted_at IS NULL ORDER BY created_at",
+    )
+    .bind(source_project_id)
+    .fetch_all(&mut *tx)
+    .await?
+    .into_iter()
+    .map(|id| (id, Uuid::new_v4().to_string()))
+    .collect();
+
+    let duplicated = DuplicatedProject { project_id, name, datasource_ids };
+    let replacements = duplicated.id_replacements(source_project_id);
+
+    // The compiled context is left empty so it is rebuilt with the new ids
+    sqlx::query(
+        r#"
+        INSERT INTO projects (id, name, client_id, user_id, settings, organization_settings, context)
+        SELECT $1, $2, client_id, $3, settings, organization_settings, $4
+        FROM projects WHERE id = $5
+        "#,
+    )
+    .bind(&duplicated.project_id)
+    .bind(&duplicated.name)
+    .bind(user_id)
+    .bind(source_context.map(|context| rewrite_ids(&context, &replacements)))
+    .bind(source_project_id)
+    .execute(&mut *tx)
+    .await?;
+
+    sqlx::query("INSERT INTO project_members (project_id, user_id, role) VALUES ($1, $2, 'owner')")
+        .bind(&duplicated.project_id)
+        .bind(user_id)
+        .execute(&mut *tx)
+        .await?;
+
+    // last_tested_at is not copied: the copy hasn't been tested yet
+    for (source_id, copy_id) in &duplicated.datasource_ids {
+        sqlx::query(
+            r#"
+            INSERT INTO data_sources (
+                id, project_id, name, source_type, connection_config, schema_info, schema_fetched_at,
+                preview_data, table_list, file_path, file_size, file_type, file_metadata, parsing_options,
+                is_active, created_at, updated_at
+            )
+            SELECT $1, $2, name, source_type, connection_config, schema_info, schema_fetched_at,
+                preview_data, table_list, file_path, file_size, file_type, file_metadata, parsing_options,
+                is_active, NOW(), NOW()
+            FROM data_sources WHERE id = $3
+            "#,
+        )
+        .bind(copy_id)
+        .bind(&duplicated.project_id)
+        .bind(source_id)
+        .execute(&mut *tx)
+        .await?;
+    }
+
+    tx.commit().await?;
+    Ok(Some(duplicated))
+}
+
+/// Remove a copy made by `duplicate_project_rows`, for when the rest of the
+/// duplication fails
+pub async fn delete_project_rows(db: &PgPool, project_id: &str) -> Result<(), sqlx::Error> {
+    let mut tx = db.begin().await?;
+    for statement in [
+        "DELETE FROM data_sources WHERE project_id = $1",
+        "DELETE FROM project_members WHERE project_id = $1",
+        "DELETE FROM projects WHERE id = $1",
+    ] {
+        sqlx::query(statement).bind(project_id).execute(&mut *tx).await?;
+    }
+    tx.commit().await
+}
+
+/// Copy CLAUDE.md, with ids rewritten, and the saved queries from one project
+/// directory to another. Everything else in the source directory (uploads,
+/// imported DuckDB files, Claude session state) is left behind.
+pub fn copy_project_files(
+    source_dir: &Path,
+    target_dir: &Path,
+    replacements: &[(String, String)],
+) -> Result<(), AppError> {
+    let copy_error = |e: std::io::Error| AppError::InternalServerError(format!("Failed to copy project files: {}", e));
+    fs::create_dir_all(target_dir).map_err(copy_error)?;
+
+    let claude_md = source_dir.join("CLAUDE.md");
+    if claude_md.is_file() {
+        let content = fs::read_to_string(&claude_md).map_err(copy_error)?;
+        fs::write(target_dir.join("CLAUDE.md"), rewrite_ids(&content, replacements)).map_err(copy_error)?;
+    }
+
+    let queries_dir = source_dir.join("queries");
+    if queries_dir.is_dir() {
+        let target_queries = target_dir.join("queries");
+        fs::create_dir_all(&target_queries).map_err(copy_error)?;
+        for entry in fs::read_dir(&queries_dir).map_err(copy_error)? {
+            let path = entry.map_err(copy_error)?.path();
+            if path.is_file() && path.extension().and_then(|s| s.to_str()) == Some("txt") {
+                if let Some(file_name) = path.file_name() {
+                    fs::copy(&path, target_queries.join(file_name)).map_err(copy_error)?;
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn context_and_saved_queries_are_copied_but_not_conversation_files_or_uploads() {
+        let dir = TempDir::new().unwrap();
+        let source = dir.path().join("source-project");
+        let target = dir.path().join("copy-project");
+        fs::create_dir_all(source.join("queries")).unwrap();
+        fs::create_dir_all(source.join("uploads")).unwrap();
+        fs::create_dir_all(source.join(".claude/conversations")).unwrap();
+        fs::write(
+            source.join("CLAUDE.md"),
+            "# Project source-project\n- Orders (ds-orders)\n- Events (ds-events)\n",
+        )
+        .unwrap();
+        fs::write(source.join("queries/top_customers.txt"), "SELECT * FROM customers").unwrap();
+        fs::write(source.join("uploads/orders.csv"), "id\n1\n").unwrap();
+        fs::write(source.join(".claude/conversations/conv-1.jsonl"), "{}").unwrap();
+
+        let duplicated = DuplicatedProject {
+            project_id: "copy-project".to_string(),
+            name: copy_name("Sales"),
+            datasource_ids: vec![
+                ("ds-orders".to_string(), "ds-orders-copy".to_string()),
+                ("ds-events".to_string(), "ds-events-copy".to_string()),
+            ],
+        };
+        copy_project_files(&source, &target, &duplicated.id_replacements("source-project")).unwrap();
+
+        assert_eq!(duplicated.name, "Sales (copy)");
+        assert_eq!(
+            fs::read_to_string(target.join("CLAUDE.md")).unwrap(),
+            "# Project copy-project\n- Orders (ds-orders-copy)\n- Events (ds-events-copy)\n"
+        );
+        assert_eq!(
+            fs::read_to_string(target.join("queries/top_customers.txt")).unwrap(),
+            "SELECT * FROM customers"
+        );
+        assert!(!target.join("uploads").exists());
+        assert!(!target.join(".claude/conversations").exists());
+    }
+
+    #[tokio::test]
+    #[ignore = "needs TEST_DATABASE_URL"]
+    async fn datasources_and_context_are_copied_but_not_conversations() {
+        let url = std::env::var("TEST_DATABASE_URL").expect("Set TEST_DATABASE_URL");
+        let db_pool = PgPool::connect(&url).await.unwrap();
+
+        let client_id = Uuid::new_v4();
+        let owner_id = Uuid::new_v4();
+        let source_id = format!("duplicate-source-{}", client_id);
+        let datasource_id = format!("duplicate-ds-{}", client_id);
+        let now = chrono::Utc::now();
+        sqlx::query(
+            "INSERT INTO clients (id, name, description, status, install_path, config, created_at, updated_at)
+             VALUES ($1, 'duplicate-test', NULL, 'active', '', '{}'::jsonb, $2, $2)",
+        )
+        .bind(client_id)
+        .bind(now)
+        .execute(&db_pool)
+        .await
+        .unwrap();
+        sqlx::query(
+            "INSERT INTO users (id, client_id, username, password, role, created_at, updated_at)
+             VALUES ($1, $2, $3, 'unused', 'user', $4, $4)",
+        )
+        .bind(owner_id)
+        .bind(client_id)
+        .bind(format!("duplicate-owner-{}", owner_id))
+        .bind(now)
+        .execute(&db_pool)
+        .await
+        .unwrap();
+        sqlx::query("INSERT INTO projects (id, name, client_id, user_id, context) VALUES ($1, 'Sales', $2, $3, $4)")
+            .bind(&source_id)
+            .bind(client_id)
+            .bind(owner_id)
+            .bind(format!("Orders live in {}", datasource_id))
+            .execute(&db_pool)
+            .await
+            .unwrap();
+        sqlx::query(
+            "INSERT INTO data_sources (id, project_id, name, source_type, connection_config, created_at, updated_at)
+             VALUES ($1, $2, 'Orders', 'postgresql', '{\"host\": \"db.internal\"}'::jsonb, NOW(), NOW())",
+        )
+        .bind(&datasource_id)
+        .bind(&source_id)
+        .execute(&db_pool)
+        .await
+        .unwrap();
+        sqlx::query("INSERT INTO conversations (id, project_id, title) VALUES ($1, $2, 'Q3 revenue')")
+            .bind(format!("duplicate-conv-{}", client_id))
+            .bind(&source_id)
+            .execute(&db_pool)
+            .await
+            .unwrap();
+
+        let duplicated = duplicate_project_rows(&db_pool, &source_id, client_id, owner_id, None)
+            .await
+            .unwrap()
+            .unwrap();
+        let copy_id = duplicated.project_id.clone();
+        let (name, context): (String, Option<String>) =
+            sqlx::query_as("SELECT name, context FROM projects WHERE id = $1")
+                .bind(&copy_id)
+                .fetch_one(&db_pool)
+                .await
+                .unwrap();
+        let datasources: Vec<(String, String, serde_json::Value)> = sqlx::query_as(
+            "SELECT id, name, connection_config FROM data_sources WHERE project_id = $1",
+        )
+        .bind(&copy_id)
+        .fetch_all(&db_pool)
+        .await
+        .unwrap();
+        let conversations: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM conversations WHERE project_id = $1")
+            .bind(&copy_id)
+            .fetch_one(&db_pool)
+            .await
+            .unwrap();
+
+        delete_project_rows(&db_pool, &copy_id).await.unwrap();
+        let copy_left: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM projects WHERE id = $1")
+            .bind(&copy_id)
+            .fetch_one(&db_pool)
+            .await
+            .unwrap();
+        for cleanup in [
+            "DELETE FROM conversations WHERE project_id = $1",
+            "DELETE FROM data_sources WHERE project_id = $1",
+            "DELETE FROM projects WHERE id = $1",
+        ] {
+            sqlx::query(cleanup).bind(&source_id).execute(&db_pool).await.unwrap();
+        }
+        sqlx::query("DELETE FROM users WHERE id = $1").bind(owner_id).execute(&db_pool).await.unwrap();
+        sqlx::query("DELETE FROM clients WHERE id = $1").bind(client_id).execute(&db_pool).await.unwrap();
+
+        let copied_datasource_id = &duplicated.datasource_ids[0].1;
+        assert_eq!(name, "Sales (copy)");
+        assert_eq!(context, Some(format!("Orders live in {}", copied_datasource_id)));
+        assert_eq!(datasources.len(), 1);
+        assert_eq!(&datasources[0].0, copied_datasource_id);
+        assert_eq!(datasources[0].1, "Orders");
+        assert_eq!(datasources[0].2["host"], "db.internal");
+        assert_eq!(conversations, 0);
+        assert_eq!(copy_left, 0);
+    }
+}
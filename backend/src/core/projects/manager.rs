@@ -1,3 +1,4 @@
+use crate::models::ProjectMemberRole;
 use crate::utils::AppError;
 use chrono::Utc;
 use serde::{Deserialize, Serialize};
@@ -24,6 +25,8 @@ pub struct ProjectInfoWithStats {
     pub client_id: Uuid,
     pub conversation_count: Option<i32>,
     pub datasource_count: Option<i32>,
+    /// The current user's role; None for root users who aren't members
+    pub role: Option<ProjectMemberRole>,
 }
 
 pub struct ProjectManager {
@@ -85,20 +88,21 @@ impl ProjectManager {
             // Use the actual project_id and client_id parameters passed to the method
             let project_id_str = project_id.to_string();
             let client_id_str = client_id.to_string();
+            let mcp_base_url = crate::core::mcp_process::mcp_server_base_url();
 
             let mcp_config = serde_json::json!({
                 "mcpServers": {
                     "operation": {
                         "type": "http",
-                        "url": format!("http://localhost:7670/operation/{}/{}", client_id_str, project_id_str)
+                        "url": format!("{}/operation/{}/{}", mcp_base_url, client_id_str, project_id_str)
                     },
                     "analysis": {
                         "type": "http",
-                        "url": format!("http://localhost:7670/analysis/{}/{}", client_id_str, project_id_str)
+                        "url": format!("{}/analysis/{}/{}", mcp_base_url, client_id_str, project_id_str)
                     },
                     "interaction": {
                         "type": "http",
-                        "url": format!("http://localhost:7670/interaction/{}/{}", client_id_str, project_id_str)
+                        "url": format!("{}/interaction/{}/{}", mcp_base_url, client_id_str, project_id_str)
                     }
                 }
             });
@@ -215,18 +219,7 @@ Add any project-specific context or instructions here that Claude should be awar
             })?;
         }
 
-        let safe_name = query_name
-            .chars()
-            .map(|c| {
-                if c.is_alphanumeric() || c == '-' || c == '_' {
-                    c
-                } else {
-                    '_'
-                }
-            })
-            .collect::<String>();
-
-        let query_path = queries_dir.join(format!("{}.txt", safe_name));
+        let query_path = queries_dir.join(format!("{}.txt", query_file_stem(query_name)));
 
         fs::write(&query_path, content)
             .map_err(|e| AppError::InternalServerError(format!("Failed to save query: {}", e)))?;
@@ -321,3 +314,18 @@ Add any project-specific context or instructions here that Claude should be awar
         Ok(())
     }
 }
+
+/// The file name (without `.txt`) a saved query is stored under; anything
+/// other than letters, digits, `-` and `_` becomes `_`
+pub fn query_file_stem(query_name: &str) -> String {
+    query_name
+        .chars()
+        .map(|c| {
+            if c.is_alphanumeric() || c == '-' || c == '_' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
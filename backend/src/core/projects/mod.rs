@@ -1,3 +1,5 @@
+pub mod bundle;
+pub mod duplicate;
 pub mod manager;
 
 pub use manager::*;
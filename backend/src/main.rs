@@ -23,7 +23,7 @@ use crate::api::{
 use crate::core::sessions::PostgresSessionStore;
 use crate::utils::middleware::{
     auth::{admin_required, auth_required, root_required},
-    client_scoped, inject_state,
+    client_scoped, inject_state, request_id,
 };
 use crate::utils::{get_app_state, AppState, Config};
 
@@ -65,32 +65,19 @@ async fn kill_process_using_port(port: u16) -> Result<(), Box<dyn std::error::Er
     Ok(())
 }
 
-/// Bind to address with port conflict resolution
-async fn bind_with_retry(address: &str, max_retries: u32) -> TcpAcceptor {
-    // Add a small initial delay to allow any previous process to fully release the port
-    tokio::time::sleep(Duration::from_millis(500)).await;
-
+/// Check that `socket_addr` can be bound, killing a leftover process that
+/// still holds the port
+async fn ensure_port_available(
+    socket_addr: std::net::SocketAddr,
+    max_retries: u32,
+) -> Result<(), String> {
     for attempt in 1..=max_retries {
-        let socket_addr: std::net::SocketAddr = match address.parse() {
-            Ok(addr) => addr,
-            Err(_) => {
-                eprintln!("❌ Invalid address format: {}", address);
-                std::process::exit(1);
-            }
-        };
-
         // Test if the port is available using tokio TcpListener
         match tokio::net::TcpListener::bind(socket_addr).await {
             Ok(test_listener) => {
                 // Port is available, close the test listener
                 drop(test_listener);
-
-                // Give a small grace period for the port to be fully released
-                tokio::time::sleep(Duration::from_millis(200)).await;
-
-                // Now use Salvo's TcpListener
-                eprintln!("🔗 Attempting to bind to {} (attempt {})", address, attempt);
-                return TcpListener::new(address).bind().await;
+                return Ok(());
             }
             Err(e) => {
                 if e.kind() == std::io::ErrorKind::AddrInUse {
@@ -120,17 +107,41 @@ async fn bind_with_retry(address: &str, max_retries: u32) -> TcpAcceptor {
                     }
                 }
 
-                eprintln!("❌ Failed to bind to {}: {}", address, e);
-                std::process::exit(1);
+                return Err(format!("Failed to bind to {}: {}", socket_addr, e));
             }
         }
     }
 
-    eprintln!(
-        "❌ Failed to bind to {} after {} attempts",
-        address, max_retries
-    );
-    std::process::exit(1);
+    Err(format!(
+        "Failed to bind to {} after {} attempts",
+        socket_addr, max_retries
+    ))
+}
+
+/// Bind to address with port conflict resolution
+async fn bind_with_retry(address: &str, max_retries: u32) -> TcpAcceptor {
+    // Add a small initial delay to allow any previous process to fully release the port
+    tokio::time::sleep(Duration::from_millis(500)).await;
+
+    let socket_addr: std::net::SocketAddr = match address.parse() {
+        Ok(addr) => addr,
+        Err(_) => {
+            eprintln!("❌ Invalid address format: {}", address);
+            std::process::exit(1);
+        }
+    };
+
+    if let Err(e) = ensure_port_available(socket_addr, max_retries).await {
+        eprintln!("❌ {}", e);
+        std::process::exit(1);
+    }
+
+    // Give a small grace period for the port to be fully released
+    tokio::time::sleep(Duration::from_millis(200)).await;
+
+    // Now use Salvo's TcpListener
+    eprintln!("🔗 Attempting to bind to {}", address);
+    TcpListener::new(address).bind().await
 }
 
 /// Wait for shutdown signal (SIGTERM, SIGINT, or Ctrl+C)
@@ -167,64 +178,18 @@ async fn shutdown_signal() {
 }
 
 /// Start the MCP server instance
-async fn start_mcp_server() -> Result<(), Box<dyn std::error::Error>> {
-    use std::process::Command;
-    
-    // Get MCP server path - look for the built binary
-    let mcp_server_path = {
-        let current_dir = std::env::current_dir().unwrap_or_else(|_| std::path::PathBuf::from("."));
-        
-        // Development paths (target directory)
-        let release_path = current_dir.join("target/release/mcp_server");
-        let debug_path = current_dir.join("target/debug/mcp_server");
-        
-        // Production path (same directory as main binary or /app)
-        let production_path = current_dir.join("mcp_server");
-        let app_path = std::path::PathBuf::from("/app/mcp_server");
-        
-        // Check paths in order: debug (dev), production (same dir), /app (container), release (dev fallback)
-        if debug_path.exists() {
-            debug_path.canonicalize().unwrap_or(debug_path)
-        } else if production_path.exists() {
-            production_path.canonicalize().unwrap_or(production_path)
-        } else if app_path.exists() {
-            app_path.canonicalize().unwrap_or(app_path)
-        } else if release_path.exists() {
-            release_path.canonicalize().unwrap_or(release_path)
-        } else {
-            return Err("MCP server binary not found. Expected locations: target/debug/mcp_server, ./mcp_server, /app/mcp_server, or target/release/mcp_server".into());
-        }
-    };
-
-    tracing::info!("🔧 Starting MCP server at {:?}", mcp_server_path);
+async fn start_mcp_server(port: u16) -> Result<(), Box<dyn std::error::Error>> {
+    let mcp_server_path = crate::core::mcp_process::find_mcp_server_binary().ok_or(
+        "MCP server binary not found. Expected locations: target/debug/mcp_server, ./mcp_server, /app/mcp_server, or target/release/mcp_server",
+    )?;
 
-    // Start the MCP server as a background process
-    let mut child = Command::new(mcp_server_path)
-        .arg("--http")
-        .arg("--port")
-        .arg("7670")
-        .spawn()?;
+    // The MCP server binds on all interfaces; refuse to start it into a port clash
+    ensure_port_available(std::net::SocketAddr::from(([0, 0, 0, 0], port)), 3).await?;
 
-    // Give the server a moment to start
-    tokio::time::sleep(Duration::from_millis(1000)).await;
+    tracing::info!("🔧 Starting MCP server at {:?} on port {}", mcp_server_path, port);
 
-    // Check if the process is still running
-    match child.try_wait() {
-        Ok(Some(status)) => {
-            return Err(format!("MCP server exited immediately with status: {}", status).into());
-        }
-        Ok(None) => {
-            tracing::info!("✅ MCP server started successfully on port 7670");
-        }
-        Err(e) => {
-            return Err(format!("Failed to check MCP server status: {}", e).into());
-        }
-    }
-
-    // Detach the process so it continues running independently
-    std::mem::forget(child);
-
-    Ok(())
+    // The supervisor owns the child process and restarts it if it exits
+    crate::core::mcp_process::start_mcp_supervisor(mcp_server_path, port).await
 }
 
 /// Ensure global Bun installation is available for all clients
@@ -320,13 +285,34 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     }
 
     let config = Config::from_env()?;
+    Config::install(config.clone());
+    utils::storage::init_storage(&config.storage)?;
+    tracing::info!("📦 Upload storage backend: {}", utils::storage::storage().name());
+    crate::utils::content_extractor::ContentExtractor::configure_limits(config.extraction_limits);
     let state = AppState::new(&config).await?;
 
     // Ensure global Bun installation is available for all clients
     ensure_global_bun_installation().await?;
 
     // Start the MCP server instance
-    start_mcp_server().await?;
+    start_mcp_server(config.mcp_server_port).await?;
+
+    // Detect dead pooled datasource connections before a user query hits them
+    utils::datasource::start_pool_validation(&config.pool_keepalive);
+    // Close pools of datasources nobody has queried in a while
+    utils::datasource::start_pool_reaper(&config.pool_reaper);
+
+    // Forward schema refreshes and MCP prompts to websocket clients
+    api::websocket::notifications::start_notification_listener(state.db_pool.clone());
+
+    // Scheduled backups of the application database
+    if config.backup.enabled {
+        crate::core::backup::start_scheduled_backups(
+            config.backup.clone(),
+            config.database_url.clone(),
+        )
+        .await;
+    }
 
     // Session configuration
     let default_secret = "clay-studio-secret-key-change-in-production-this-is-64-bytes-long";
@@ -375,14 +361,22 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     // Admin routes (accessible to admin and root roles)
     let admin_router = Router::new()
         .hoop(admin_required)
-        .push(Router::with_path("/admin").push(admin::admin_routes()));
+        .push(
+            Router::with_path("/admin")
+                .push(admin::admin_routes())
+                .push(admin::connection_routes())
+                .push(admin::conversation_routes())
+                .push(admin::audit_routes()),
+        );
 
     // Root routes (accessible only to root role)
     let root_router = Router::new()
-        .hoop(root_required);
+        .hoop(root_required)
+        .push(admin::root_routes());
 
     // API routes with state injection and session handling
     let api_router = Router::new()
+        .hoop(request_id())
         .hoop(session_handler)
         .hoop(inject_state(state))
         .push(public_router)
@@ -435,7 +429,8 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
 async fn health_check(res: &mut Response) {
     res.render(Json(serde_json::json!({
         "status": "ok",
-        "service": "clay-studio-backend"
+        "service": "clay-studio-backend",
+        "mcp_server": crate::core::mcp_process::mcp_process_status().await
     })));
 }
 
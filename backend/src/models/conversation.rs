@@ -46,6 +46,9 @@ pub struct Conversation {
     pub created_by_user_id: Option<uuid::Uuid>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub visibility: Option<ConversationVisibility>,
+    /// Claude model picked for this conversation; None uses the default
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -19,6 +19,9 @@ pub struct FileUpload {
     pub file_content: Option<String>, // For text files
     pub metadata: Option<serde_json::Value>,
     pub uploaded_by: Option<Uuid>,
+    pub extraction_status: String, // processing, ready or failed
+    pub extraction_progress: i32,  // Percent complete, 100 once extraction ends
+    pub archive_id: Option<Uuid>,  // Shared by files expanded from one ZIP upload
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
@@ -58,6 +61,10 @@ pub struct FileUploadResponse {
     pub created_at: String,
     pub is_text_file: bool,
     pub preview: Option<String>, // First 500 chars of text files
+    pub extraction_status: String,
+    pub extraction_progress: i32,
+    pub archive_id: Option<String>,
+    pub uploaded_by: Option<String>,
 }
 
 impl FileUpload {
@@ -91,6 +98,10 @@ impl FileUpload {
             created_at: self.created_at.to_rfc3339(),
             is_text_file: is_text,
             preview,
+            extraction_status: self.extraction_status.clone(),
+            extraction_progress: self.extraction_progress,
+            archive_id: self.archive_id.map(|id| id.to_string()),
+            uploaded_by: self.uploaded_by.map(|id| id.to_string()),
         }
     }
 }
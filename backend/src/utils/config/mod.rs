@@ -0,0 +1,218 @@
+use anyhow::{Result, Context};
+use std::env;
+use std::path::PathBuf;
+use std::sync::OnceLock;
+
+use crate::utils::content_extractor::ExtractionLimits;
+use crate::utils::datasource::common::projection::max_result_columns_from_env;
+use crate::utils::datasource::common::result_budget::ResultBudget;
+use crate::utils::datasource::common::timeouts::DatasourceTimeouts;
+use crate::utils::datasource::{PoolKeepaliveConfig, PoolReaperConfig};
+use crate::utils::db::RetryPolicy;
+use crate::utils::query_limit::max_concurrent_queries_from_env;
+use crate::utils::rate_limit::RateLimitConfig;
+use crate::utils::storage::StorageConfig;
+
+mod model;
+mod sections;
+
+pub use model::ModelConfig;
+pub use sections::{
+    cache_enabled_from_env, parse_mcp_server_port, ArchiveLimits, BackupConfig, HeartbeatConfig,
+    SlowQueryConfig, DEFAULT_MCP_SERVER_PORT,
+};
+
+#[derive(Debug, Clone)]
+pub struct Config {
+    pub database_url: String,
+    pub server_address: String,
+    /// Port the supervised MCP server listens on
+    pub mcp_server_port: u16,
+    #[allow(dead_code)]
+    pub jwt_secret: String,
+    pub datasource_pool_warmup: bool,
+    /// Validation interval and TCP keepalive for pooled datasource connections
+    pub pool_keepalive: PoolKeepaliveConfig,
+    /// Idle timeout and cache size limit for pooled datasource connections
+    pub pool_reaper: PoolReaperConfig,
+    /// Default connect and query timeouts for external datasources
+    pub datasource_timeouts: DatasourceTimeouts,
+    /// Rows per page used by the data browser when the client doesn't ask for a size
+    pub default_page_size: i32,
+    /// Largest page the data browser will serve; bigger requests are clamped to this
+    pub max_page_size: i32,
+    /// Distinct values returned for a column filter when the client doesn't ask for a count
+    pub default_distinct_values: i32,
+    /// Column cap for wide tables when the client doesn't pick columns itself
+    pub max_result_columns: usize,
+    /// Default row and byte caps on custom query results
+    pub result_budget: ResultBudget,
+    /// Byte cap for the per-conversation replay buffer of a streaming response
+    pub stream_buffer_max_bytes: usize,
+    /// How long a stream buffer is kept after its conversation loses all subscribers
+    pub stream_buffer_ttl_secs: u64,
+    /// Attempts and backoff for the initial connection to the main database
+    pub db_connect_retry: RetryPolicy,
+    /// Uploads larger than this are extracted in the background with progress events
+    pub upload_async_extraction_bytes: u64,
+    /// Size above which uploads skip content extraction, and caps on extracted content
+    pub extraction_limits: ExtractionLimits,
+    /// pg_dump location, schedule and retention for backups of the main database
+    pub backup: BackupConfig,
+    /// Threshold and plan capture for the slow query log
+    pub slow_query: SlowQueryConfig,
+    /// Ping interval and silence timeout for chat WebSocket connections
+    pub ws_heartbeat: HeartbeatConfig,
+    /// Refill rate and burst of each user's prompt rate limit
+    pub prompt_rate_limit: RateLimitConfig,
+    /// Claude queries a single client may have running at once
+    pub max_concurrent_queries: usize,
+    /// Models conversations may pick and the default when they don't
+    pub claude_models: ModelConfig,
+    /// Working directory of the DuckDB analysis engine
+    pub analysis_data_dir: PathBuf,
+    /// Backend for uploaded files and exports
+    pub storage: StorageConfig,
+    /// Whether the MCP server reuses identical read-only tool results within a turn
+    pub mcp_tool_cache: bool,
+    /// File count and expanded size caps for ZIP uploads
+    pub archive_limits: ArchiveLimits,
+}
+
+/// Configuration for code that has no `AppState` at hand: MCP handlers and
+/// datasource connectors. The backend installs its `Config` at startup; the
+/// MCP server installs one when it starts serving.
+static PROCESS_CONFIG: OnceLock<Config> = OnceLock::new();
+
+impl Config {
+    pub fn from_env() -> Result<Self> {
+        let database_url = env::var("DATABASE_URL")
+            .with_context(|| "DATABASE_URL environment variable must be set")?;
+
+        Self::load(database_url)
+    }
+
+    /// Use `config` wherever `Config::current` is read in this process
+    pub fn install(config: Config) {
+        let _ = PROCESS_CONFIG.set(config);
+    }
+
+    /// The installed configuration. Processes that never install one (unit
+    /// tests, one-off binaries) load the environment on first use; nothing
+    /// that reads it needs DATABASE_URL, so it may be unset there. Fails if
+    /// that environment is invalid, e.g. a malformed MCP_SERVER_PORT.
+    pub fn current() -> Result<&'static Config> {
+        if let Some(config) = PROCESS_CONFIG.get() {
+            return Ok(config);
+        }
+        let config = Self::load(env::var("DATABASE_URL").unwrap_or_default())?;
+        Ok(PROCESS_CONFIG.get_or_init(|| config))
+    }
+
+    fn load(database_url: String) -> Result<Self> {
+        // Check if we're in production mode
+        let is_production = env::var("RUST_ENV")
+            .unwrap_or_else(|_| "development".to_string())
+            .to_lowercase()
+            == "production";
+
+        let server_address = env::var("SERVER_ADDRESS").unwrap_or_else(|_| {
+            if is_production {
+                "0.0.0.0:7680".to_string()
+            } else {
+                "127.0.0.1:7680".to_string()
+            }
+        });
+
+        let mcp_server_port = parse_mcp_server_port(env::var("MCP_SERVER_PORT").ok().as_deref())
+            .map_err(anyhow::Error::msg)?;
+
+        let jwt_secret = env::var("JWT_SECRET")
+            .unwrap_or_else(|_| "development-secret-key".to_string());
+
+        let datasource_pool_warmup = env::var("DATASOURCE_POOL_WARMUP")
+            .unwrap_or_else(|_| "true".to_string())
+            .to_lowercase()
+            == "true";
+
+        let max_page_size = env::var("MAX_PAGE_SIZE")
+            .ok()
+            .and_then(|v| v.parse::<i32>().ok())
+            .filter(|v| *v > 0)
+            .unwrap_or(1000);
+
+        let default_page_size = env::var("DEFAULT_PAGE_SIZE")
+            .ok()
+            .and_then(|v| v.parse::<i32>().ok())
+            .filter(|v| *v > 0)
+            .unwrap_or(50)
+            .min(max_page_size);
+
+        let default_distinct_values = env::var("DEFAULT_DISTINCT_VALUES")
+            .ok()
+            .and_then(|v| v.parse::<i32>().ok())
+            .filter(|v| *v > 0)
+            .unwrap_or(100)
+            .min(max_page_size);
+
+        let stream_buffer_max_bytes = env::var("STREAM_BUFFER_MAX_BYTES")
+            .ok()
+            .and_then(|v| v.parse::<usize>().ok())
+            .filter(|v| *v > 0)
+            .unwrap_or(4 * 1024 * 1024);
+
+        let stream_buffer_ttl_secs = env::var("STREAM_BUFFER_TTL_SECS")
+            .ok()
+            .and_then(|v| v.parse::<u64>().ok())
+            .unwrap_or(1800);
+
+        let upload_async_extraction_bytes = env::var("UPLOAD_ASYNC_EXTRACTION_BYTES")
+            .ok()
+            .and_then(|v| v.parse::<u64>().ok())
+            .unwrap_or(2 * 1024 * 1024);
+
+        let analysis_data_dir = env::var("ANALYSIS_DATA_DIR")
+            .map(PathBuf::from)
+            .unwrap_or_else(|_| PathBuf::from("./analysis_data"));
+
+        Ok(Config {
+            database_url,
+            server_address,
+            mcp_server_port,
+            jwt_secret,
+            datasource_pool_warmup,
+            pool_keepalive: PoolKeepaliveConfig::from_env(),
+            pool_reaper: PoolReaperConfig::from_env(),
+            datasource_timeouts: DatasourceTimeouts::from_env(),
+            default_page_size,
+            max_page_size,
+            default_distinct_values,
+            max_result_columns: max_result_columns_from_env(),
+            result_budget: ResultBudget::from_env(),
+            stream_buffer_max_bytes,
+            stream_buffer_ttl_secs,
+            db_connect_retry: RetryPolicy::from_env(),
+            upload_async_extraction_bytes,
+            extraction_limits: ExtractionLimits::from_env(),
+            backup: BackupConfig::from_env(),
+            slow_query: SlowQueryConfig::from_env(),
+            ws_heartbeat: HeartbeatConfig::from_env(),
+            prompt_rate_limit: RateLimitConfig::from_env(),
+            max_concurrent_queries: max_concurrent_queries_from_env(),
+            claude_models: ModelConfig::from_env(),
+            analysis_data_dir,
+            storage: StorageConfig::from_env()?,
+            mcp_tool_cache: cache_enabled_from_env(),
+            archive_limits: ArchiveLimits::from_env(),
+        })
+    }
+
+    /// Resolve the page size to serve for a request, falling back to `default`
+    /// and clamping to `max_page_size`
+    pub fn effective_page_size(&self, requested: Option<i32>, default: i32) -> i32 {
+        requested
+            .filter(|size| *size > 0)
+            .unwrap_or(default)
+            .min(self.max_page_size)
+    }
+}
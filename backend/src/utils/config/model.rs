@@ -0,0 +1,90 @@
+//! Which Claude models conversations may run on
+
+/// Models a conversation may pick, and the one used when it doesn't pick one
+#[derive(Debug, Clone)]
+pub struct ModelConfig {
+    pub allowed: Vec<String>,
+    /// None leaves the choice to the Claude CLI
+    pub default: Option<String>,
+}
+
+impl Default for ModelConfig {
+    fn default() -> Self {
+        Self {
+            allowed: vec!["sonnet".to_string(), "opus".to_string(), "haiku".to_string()],
+            default: None,
+        }
+    }
+}
+
+impl ModelConfig {
+    /// Read CLAUDE_ALLOWED_MODELS (comma separated) and CLAUDE_DEFAULT_MODEL
+    pub fn from_env() -> Self {
+        let defaults = Self::default();
+        let allowed = std::env::var("CLAUDE_ALLOWED_MODELS")
+            .ok()
+            .map(|v| {
+                v.split(',')
+                    .map(|m| m.trim().to_string())
+                    .filter(|m| !m.is_empty())
+                    .collect::<Vec<_>>()
+            })
+            .filter(|models| !models.is_empty())
+            .unwrap_or(defaults.allowed);
+        let default = std::env::var("CLAUDE_DEFAULT_MODEL")
+            .ok()
+            .map(|v| v.trim().to_string())
+            .filter(|v| !v.is_empty());
+
+        Self { allowed, default }
+    }
+
+    /// The allowed model matching `model` (case-insensitively), None if it isn't allowed
+    pub fn validate(&self, model: &str) -> Option<String> {
+        let model = model.trim();
+        self.allowed
+            .iter()
+            .find(|allowed| allowed.eq_ignore_ascii_case(model))
+            .cloned()
+    }
+
+    /// Model for a query: the requested one if allowed, else the one stored on
+    /// the conversation if still allowed, else the default
+    pub fn resolve(&self, requested: Option<&str>, stored: Option<&str>) -> Option<String> {
+        requested
+            .and_then(|model| self.validate(model))
+            .or_else(|| stored.and_then(|model| self.validate(model)))
+            .or_else(|| self.default.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> ModelConfig {
+        ModelConfig {
+            allowed: vec!["sonnet".to_string(), "haiku".to_string()],
+            default: Some("sonnet".to_string()),
+        }
+    }
+
+    #[test]
+    fn unset_model_falls_back_to_default() {
+        assert_eq!(config().resolve(None, None).as_deref(), Some("sonnet"));
+        assert_eq!(ModelConfig::default().resolve(None, None), None);
+        // A model stored on the conversation is reused by follow-up messages
+        assert_eq!(config().resolve(None, Some("haiku")).as_deref(), Some("haiku"));
+    }
+
+    #[test]
+    fn invalid_model_is_rejected() {
+        let config = config();
+        assert_eq!(config.validate("HAIKU").as_deref(), Some("haiku"));
+        assert_eq!(config.validate("gpt-4"), None);
+        assert_eq!(config.resolve(Some("gpt-4"), None).as_deref(), Some("sonnet"));
+        assert_eq!(config.resolve(Some("gpt-4"), Some("haiku")).as_deref(), Some("haiku"));
+        // A stored model that is no longer allowed is not used either
+        assert_eq!(config.resolve(None, Some("opus")).as_deref(), Some("sonnet"));
+    }
+}
@@ -0,0 +1,191 @@
+//! Settings groups that `Config` reads from the environment
+//!
+//! The modules that use these re-export them, so callers keep importing them
+//! from where the behaviour lives.
+
+use std::path::PathBuf;
+use std::time::Duration;
+
+#[derive(Debug, Clone, Copy)]
+pub struct HeartbeatConfig {
+    /// How often the server pings each client
+    pub interval: Duration,
+    /// Silence after which a connection is considered dead
+    pub timeout: Duration,
+}
+
+impl Default for HeartbeatConfig {
+    fn default() -> Self {
+        Self {
+            interval: Duration::from_secs(30),
+            timeout: Duration::from_secs(90),
+        }
+    }
+}
+
+impl HeartbeatConfig {
+    /// Read WS_HEARTBEAT_INTERVAL_SECS and WS_HEARTBEAT_TIMEOUT_SECS
+    pub fn from_env() -> Self {
+        let defaults = Self::default();
+        let env_secs = |name: &str| {
+            std::env::var(name)
+                .ok()
+                .and_then(|v| v.parse::<u64>().ok())
+                .filter(|v| *v > 0)
+                .map(Duration::from_secs)
+        };
+
+        let interval = env_secs("WS_HEARTBEAT_INTERVAL_SECS").unwrap_or(defaults.interval);
+        // Give clients at least two pings to answer before they are dropped
+        let timeout = env_secs("WS_HEARTBEAT_TIMEOUT_SECS")
+            .unwrap_or(defaults.timeout)
+            .max(interval * 2);
+        Self { interval, timeout }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct BackupConfig {
+    /// Run backups on a schedule; on-demand backups work either way
+    pub enabled: bool,
+    pub pg_dump_path: String,
+    pub directory: PathBuf,
+    pub interval: Duration,
+    /// Number of most recent archives to keep
+    pub retention: usize,
+}
+
+impl Default for BackupConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            pg_dump_path: "pg_dump".to_string(),
+            directory: PathBuf::from("backups"),
+            interval: Duration::from_secs(24 * 60 * 60),
+            retention: 7,
+        }
+    }
+}
+
+impl BackupConfig {
+    /// Read BACKUP_ENABLED, PG_DUMP_PATH, BACKUP_DIR, BACKUP_INTERVAL_SECS and BACKUP_RETENTION
+    pub fn from_env() -> Self {
+        let defaults = Self::default();
+        let env_u64 = |name: &str| std::env::var(name).ok().and_then(|v| v.parse::<u64>().ok());
+
+        Self {
+            enabled: std::env::var("BACKUP_ENABLED")
+                .map(|v| v.eq_ignore_ascii_case("true"))
+                .unwrap_or(defaults.enabled),
+            pg_dump_path: std::env::var("PG_DUMP_PATH").unwrap_or(defaults.pg_dump_path),
+            directory: std::env::var("BACKUP_DIR")
+                .map(PathBuf::from)
+                .unwrap_or(defaults.directory),
+            interval: env_u64("BACKUP_INTERVAL_SECS")
+                .filter(|v| *v >= 60)
+                .map(Duration::from_secs)
+                .unwrap_or(defaults.interval),
+            retention: env_u64("BACKUP_RETENTION")
+                .map(|v| v.max(1) as usize)
+                .unwrap_or(defaults.retention),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct SlowQueryConfig {
+    /// Queries at or above this duration are logged; zero disables the log
+    pub threshold: Duration,
+    /// Run EXPLAIN for slow SELECTs and store the plan
+    pub capture_plan: bool,
+}
+
+impl Default for SlowQueryConfig {
+    fn default() -> Self {
+        Self {
+            threshold: Duration::from_millis(1000),
+            capture_plan: false,
+        }
+    }
+}
+
+impl SlowQueryConfig {
+    /// Read SLOW_QUERY_THRESHOLD_MS and SLOW_QUERY_CAPTURE_PLAN
+    pub fn from_env() -> Self {
+        let defaults = Self::default();
+        Self {
+            threshold: std::env::var("SLOW_QUERY_THRESHOLD_MS")
+                .ok()
+                .and_then(|v| v.parse::<u64>().ok())
+                .map(Duration::from_millis)
+                .unwrap_or(defaults.threshold),
+            capture_plan: std::env::var("SLOW_QUERY_CAPTURE_PLAN")
+                .map(|v| v.eq_ignore_ascii_case("true"))
+                .unwrap_or(defaults.capture_plan),
+        }
+    }
+
+    pub fn is_slow(&self, elapsed: Duration) -> bool {
+        !self.threshold.is_zero() && elapsed >= self.threshold
+    }
+}
+
+pub const DEFAULT_MCP_SERVER_PORT: u16 = 7670;
+
+/// Parse an `MCP_SERVER_PORT` value; unset means the default port
+pub fn parse_mcp_server_port(value: Option<&str>) -> Result<u16, String> {
+    match value.map(str::trim) {
+        None | Some("") => Ok(DEFAULT_MCP_SERVER_PORT),
+        Some(raw) => raw
+            .parse::<u16>()
+            .ok()
+            .filter(|port| *port > 0)
+            .ok_or_else(|| format!("MCP_SERVER_PORT must be a port number between 1 and 65535, got '{}'", raw)),
+    }
+}
+
+/// Whether `MCP_TOOL_CACHE` leaves caching on; any of false/0/off turns it off
+pub fn cache_enabled_from_env() -> bool {
+    std::env::var("MCP_TOOL_CACHE")
+        .map(|v| !matches!(v.to_lowercase().as_str(), "false" | "0" | "off"))
+        .unwrap_or(true)
+}
+
+/// Caps that keep a small archive from expanding into something huge
+#[derive(Debug, Clone, Copy)]
+pub struct ArchiveLimits {
+    pub max_members: usize,
+    /// Sum of the members' uncompressed sizes, in bytes
+    pub max_total_uncompressed: u64,
+}
+
+impl Default for ArchiveLimits {
+    fn default() -> Self {
+        Self {
+            max_members: 100,
+            max_total_uncompressed: 200 * 1024 * 1024,
+        }
+    }
+}
+
+impl ArchiveLimits {
+    /// Read ARCHIVE_MAX_MEMBERS and ARCHIVE_MAX_UNCOMPRESSED_MB
+    pub fn from_env() -> Self {
+        let defaults = Self::default();
+        let env_u64 = |name: &str| {
+            std::env::var(name)
+                .ok()
+                .and_then(|v| v.parse::<u64>().ok())
+                .filter(|v| *v > 0)
+        };
+
+        Self {
+            max_members: env_u64("ARCHIVE_MAX_MEMBERS")
+                .map(|v| v as usize)
+                .unwrap_or(defaults.max_members),
+            max_total_uncompressed: env_u64("ARCHIVE_MAX_UNCOMPRESSED_MB")
+                .map(|mb| mb.saturating_mul(1024 * 1024))
+                .unwrap_or(defaults.max_total_uncompressed),
+        }
+    }
+}
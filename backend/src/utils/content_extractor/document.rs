@@ -0,0 +1,135 @@
+//! Word document (DOCX) text extraction
+
+use std::fs;
+use std::path::Path;
+use serde_json::json;
+use docx_rs::*;
+
+use super::{ContentExtractionError, ContentExtractor, ExtractedContent, ExtractionLimits};
+
+impl ContentExtractor {
+    #[allow(dead_code)]
+    async fn extract_document_content(
+        file_path: &Path,
+        original_name: &str,
+    ) -> Result<ExtractedContent, ContentExtractionError> {
+        Self::extract_document_content_with_limits(file_path, original_name, &Self::get_limits()).await
+    }
+
+    pub(super) async fn extract_document_content_with_limits(
+        file_path: &Path,
+        _original_name: &str,
+        limits: &ExtractionLimits,
+    ) -> Result<ExtractedContent, ContentExtractionError> {
+        // Read and parse the .docx file
+        let file_data = fs::read(file_path)
+            .map_err(|e| ContentExtractionError::IoError(e.to_string()))?;
+        
+        match read_docx(&file_data) {
+            Ok(docx) => {
+                // Extract text content from all paragraphs
+                let mut full_text = String::new();
+                let mut paragraph_count = 0;
+                let mut word_count = 0;
+                
+                for child in &docx.document.children {
+                    if let DocumentChild::Paragraph(para) = child {
+                        paragraph_count += 1;
+                        for run_child in &para.children {
+                            if let ParagraphChild::Run(run) = run_child {
+                                for text_child in &run.children {
+                                    if let RunChild::Text(text) = text_child {
+                                        full_text.push_str(&text.text);
+                                        word_count += text.text.split_whitespace().count();
+                                    }
+                                }
+                            }
+                        }
+                        full_text.push('\n'); // Add newline after each paragraph
+                    }
+                }
+                
+                let char_count = full_text.chars().count();
+                
+                // Create preview (respecting limits)
+                let preview_len = std::cmp::min(char_count, limits.max_preview_length);
+                let preview = if char_count > preview_len {
+                    format!("{}...", &full_text.chars().take(preview_len).collect::<String>())
+                } else {
+                    full_text.clone()
+                };
+                
+                let description = format!(
+                    "Word document with {} paragraphs, {} words, {} characters",
+                    paragraph_count, word_count, char_count
+                );
+
+                Ok(ExtractedContent {
+                    text_content: Some(full_text),
+                    structured_data: Some(json!({
+                        "type": "word_document",
+                        "stats": {
+                            "paragraphs": paragraph_count,
+                            "words": word_count,
+                            "characters": char_count
+                        },
+                        "parsed_successfully": true
+                    })),
+                    description: Some(description),
+                    preview: Some(preview),
+                })
+            }
+            Err(e) => {
+                // Fall back to basic metadata if parsing fails
+                let metadata = fs::metadata(file_path)
+                    .map_err(|e| ContentExtractionError::IoError(e.to_string()))?;
+                
+                let description = format!(
+                    "Word document ({} bytes). Failed to parse: {}",
+                    metadata.len(), e
+                );
+
+                Ok(ExtractedContent {
+                    text_content: None,
+                    structured_data: Some(json!({
+                        "type": "word_document",
+                        "size_bytes": metadata.len(),
+                        "parsing_failed": true,
+                        "error": e.to_string()
+                    })),
+                    description: Some(description),
+                    preview: None,
+                })
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn docx_paragraph_text_is_extracted() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("minutes.docx");
+        let file = fs::File::create(&path).unwrap();
+        Docx::new()
+            .add_paragraph(Paragraph::new().add_run(Run::new().add_text("Meeting minutes")))
+            .add_paragraph(Paragraph::new().add_run(Run::new().add_text("Budget approved for Q3")))
+            .build()
+            .pack(file)
+            .unwrap();
+
+        let extracted = ContentExtractor::extract_content(
+            &path,
+            "minutes.docx",
+            "application/vnd.openxmlformats-officedocument.wordprocessingml.document",
+        )
+        .await
+        .unwrap();
+        let text = extracted.text_content.unwrap();
+        assert!(text.contains("Meeting minutes") && text.contains("Budget approved for Q3"));
+        assert!(extracted.preview.unwrap().starts_with("Meeting minutes"));
+    }
+}
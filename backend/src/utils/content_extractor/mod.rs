@@ -0,0 +1,563 @@
+use std::fs;
+use std::path::Path;
+use std::sync::OnceLock;
+use serde_json::{json, Value};
+
+mod document;
+mod pdf;
+mod spreadsheet;
+
+/// Content extraction service for different file types
+pub struct ContentExtractor;
+
+/// Progress of a long extraction, reported after each sheet or page
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct ExtractionProgress {
+    /// What is being counted: "sheets" or "pages"
+    pub unit: &'static str,
+    pub processed: usize,
+    pub total: usize,
+}
+
+impl ExtractionProgress {
+    pub fn percent(&self) -> u8 {
+        if self.total == 0 {
+            return 100;
+        }
+        (self.processed * 100 / self.total).min(100) as u8
+    }
+}
+
+/// Optional callback invoked with extraction progress
+pub type ProgressCallback<'a> = Option<&'a (dyn Fn(ExtractionProgress) + Send + Sync)>;
+
+/// Configuration for content extraction limits
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ExtractionLimits {
+    /// Maximum file size to attempt full parsing (in bytes)
+    pub max_full_parse_size: u64,
+    /// Maximum text content to store in database (in characters)
+    pub max_text_content: usize,
+    /// Maximum preview length (in characters)
+    pub max_preview_length: usize,
+    /// Maximum rows to extract from Excel sheets
+    pub max_excel_rows: usize,
+    /// Maximum sheets to process in Excel files
+    pub max_excel_sheets: usize,
+}
+
+impl Default for ExtractionLimits {
+    fn default() -> Self {
+        Self {
+            max_full_parse_size: 10 * 1024 * 1024, // 10MB
+            max_text_content: 1_000_000,           // 1M characters
+            max_preview_length: 5000,              // 5K characters
+            max_excel_rows: 10_000,                // 10K rows
+            max_excel_sheets: 20,                  // 20 sheets
+        }
+    }
+}
+
+impl ExtractionLimits {
+    /// Read MAX_FULL_PARSE_SIZE_MB (or MAX_FILE_PARSE_SIZE in bytes),
+    /// MAX_CONTENT_LENGTH (or MAX_TEXT_CONTENT), MAX_PREVIEW_LENGTH,
+    /// MAX_EXCEL_ROWS and MAX_EXCEL_SHEETS
+    pub fn from_env() -> Self {
+        let defaults = Self::default();
+        let env_u64 = |name: &str| {
+            std::env::var(name)
+                .ok()
+                .and_then(|s| s.parse::<u64>().ok())
+                .filter(|v| *v > 0)
+        };
+        let env_usize = |name: &str| env_u64(name).map(|v| v as usize);
+
+        Self {
+            max_full_parse_size: env_u64("MAX_FULL_PARSE_SIZE_MB")
+                .map(|mb| mb.saturating_mul(1024 * 1024))
+                .or_else(|| env_u64("MAX_FILE_PARSE_SIZE"))
+                .unwrap_or(defaults.max_full_parse_size),
+            max_text_content: env_usize("MAX_CONTENT_LENGTH")
+                .or_else(|| env_usize("MAX_TEXT_CONTENT"))
+                .unwrap_or(defaults.max_text_content),
+            max_preview_length: env_usize("MAX_PREVIEW_LENGTH").unwrap_or(defaults.max_preview_length),
+            max_excel_rows: env_usize("MAX_EXCEL_ROWS").unwrap_or(defaults.max_excel_rows),
+            max_excel_sheets: env_usize("MAX_EXCEL_SHEETS").unwrap_or(defaults.max_excel_sheets),
+        }
+    }
+
+    /// Files above the parse limit only get basic metadata
+    pub fn is_large_file(&self, file_size: u64) -> bool {
+        file_size > self.max_full_parse_size
+    }
+}
+
+/// Limits set from `Config` at startup; processes that don't load the
+/// config (the MCP server) read the same environment variables instead
+static CONFIGURED_LIMITS: OnceLock<ExtractionLimits> = OnceLock::new();
+
+impl ContentExtractor {
+    /// Use `limits` for every extraction in this process
+    pub fn configure_limits(limits: ExtractionLimits) {
+        let _ = CONFIGURED_LIMITS.set(limits);
+    }
+
+    /// Get current extraction limits configuration
+    pub fn get_limits() -> ExtractionLimits {
+        *CONFIGURED_LIMITS.get_or_init(ExtractionLimits::from_env)
+    }
+
+    /// Extract meaningful content and metadata from any file type
+    pub async fn extract_content(
+        file_path: &Path,
+        original_name: &str,
+        mime_type: &str,
+    ) -> Result<ExtractedContent, ContentExtractionError> {
+        Self::extract_content_with_limits(file_path, original_name, mime_type, &Self::get_limits()).await
+    }
+
+    /// Extract content, reporting sheets/pages processed for workbooks and PDFs
+    pub async fn extract_content_with_progress(
+        file_path: &Path,
+        original_name: &str,
+        mime_type: &str,
+        progress: ProgressCallback<'_>,
+    ) -> Result<ExtractedContent, ContentExtractionError> {
+        Self::extract_content_inner(file_path, original_name, mime_type, &Self::get_limits(), progress).await
+    }
+
+    /// Extract content with custom limits for large file handling
+    pub async fn extract_content_with_limits(
+        file_path: &Path,
+        original_name: &str,
+        mime_type: &str,
+        limits: &ExtractionLimits,
+    ) -> Result<ExtractedContent, ContentExtractionError> {
+        Self::extract_content_inner(file_path, original_name, mime_type, limits, None).await
+    }
+
+    async fn extract_content_inner(
+        file_path: &Path,
+        original_name: &str,
+        mime_type: &str,
+        limits: &ExtractionLimits,
+        progress: ProgressCallback<'_>,
+    ) -> Result<ExtractedContent, ContentExtractionError> {
+        // Check file size first
+        let metadata = fs::metadata(file_path)
+            .map_err(|e| ContentExtractionError::IoError(e.to_string()))?;
+        
+        let file_size = metadata.len();
+        
+        // If file is too large, return basic metadata only
+        if limits.is_large_file(file_size) {
+            return Self::extract_large_file_metadata(file_path, original_name, mime_type, file_size).await;
+        }
+        let file_extension = Path::new(original_name)
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .unwrap_or("")
+            .to_lowercase();
+
+        let result = match mime_type {
+            // Text files - read directly
+            t if t.starts_with("text/") || Self::is_text_file(&file_extension) => {
+                Self::extract_text_content_with_limits(file_path, original_name, limits).await
+            }
+            
+            // Images - extract metadata and OCR text if possible
+            t if t.starts_with("image/") => {
+                Self::extract_image_content(file_path, original_name).await
+            }
+            
+            // Spreadsheets - extract data as structured text
+            "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet" |
+            "application/vnd.ms-excel" |
+            "text/csv" => {
+                Self::extract_spreadsheet_content_with_limits(file_path, original_name, &file_extension, limits, progress).await
+            }
+            
+            // PDFs - extract text content; browsers sometimes send these as octet-stream
+            "application/pdf" => {
+                Self::extract_pdf_content_with_limits(file_path, original_name, limits, progress).await
+            }
+            _ if file_extension == "pdf" => {
+                Self::extract_pdf_content_with_limits(file_path, original_name, limits, progress).await
+            }
+            
+            // Word documents
+            "application/vnd.openxmlformats-officedocument.wordprocessingml.document" |
+            "application/msword" => {
+                Self::extract_document_content_with_limits(file_path, original_name, limits).await
+            }
+            _ if file_extension == "docx" => {
+                Self::extract_document_content_with_limits(file_path, original_name, limits).await
+            }
+            
+            // JSON files - validate and describe structure
+            "application/json" => {
+                Self::extract_json_content_with_limits(file_path, original_name, limits).await
+            }
+            
+            // Default - basic file info
+            _ => {
+                Self::extract_basic_metadata(file_path, original_name, mime_type).await
+            }
+        };
+
+        // Apply content truncation if needed
+        result.map(|content| Self::apply_content_limits(content, limits))
+    }
+
+    /// Handle large files that exceed processing limits
+    async fn extract_large_file_metadata(
+        _file_path: &Path,
+        _original_name: &str,
+        mime_type: &str,
+        file_size: u64,
+    ) -> Result<ExtractedContent, ContentExtractionError> {
+        let file_size_mb = file_size as f64 / (1024.0 * 1024.0);
+        
+        let description = format!(
+            "Large file ({:.1} MB) - Too large for full content extraction. Basic metadata only.",
+            file_size_mb
+        );
+
+        let file_type = Self::get_file_type_from_mime(mime_type);
+
+        Ok(ExtractedContent {
+            text_content: None,
+            structured_data: Some(json!({
+                "type": file_type,
+                "size_bytes": file_size,
+                "size_mb": file_size_mb,
+                "large_file": true,
+                "content_extraction_skipped": true,
+                "suggested_actions": [
+                    "File is too large for automatic processing",
+                    "Consider splitting into smaller files",
+                    "Manual processing may be required"
+                ]
+            })),
+            description: Some(description),
+            preview: None,
+        })
+    }
+
+    /// Apply content limits to extracted content
+    fn apply_content_limits(mut content: ExtractedContent, limits: &ExtractionLimits) -> ExtractedContent {
+        // Truncate text content if too long
+        if let Some(ref mut text) = content.text_content {
+            if text.chars().count() > limits.max_text_content {
+                let truncated: String = text.chars().take(limits.max_text_content).collect();
+                *text = format!("{}... [Content truncated - original length: {} characters]", 
+                               truncated, text.chars().count());
+                
+                // Update metadata to indicate truncation
+                if let Some(ref mut data) = content.structured_data {
+                    data["content_truncated"] = json!(true);
+                    data["original_length"] = json!(text.chars().count());
+                }
+            }
+        }
+
+        // Truncate preview if too long
+        if let Some(ref mut preview) = content.preview {
+            if preview.chars().count() > limits.max_preview_length {
+                let truncated: String = preview.chars().take(limits.max_preview_length).collect();
+                *preview = format!("{}...", truncated);
+            }
+        }
+
+        content
+    }
+
+    /// Get file type string from MIME type
+    fn get_file_type_from_mime(mime_type: &str) -> &'static str {
+        match mime_type {
+            t if t.starts_with("text/") => "text",
+            t if t.starts_with("image/") => "image",
+            "application/pdf" => "pdf",
+            "application/vnd.openxmlformats-officedocument.wordprocessingml.document" => "word_document",
+            "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet" => "excel",
+            "application/vnd.ms-excel" => "excel",
+            "text/csv" => "csv",
+            "application/json" => "json",
+            _ => "binary_file"
+        }
+    }
+
+    fn is_text_file(extension: &str) -> bool {
+        matches!(extension, 
+            "txt" | "md" | "markdown" | "log" | "conf" | "config" | 
+            "js" | "ts" | "jsx" | "tsx" | "json" | "yaml" | "yml" |
+            "html" | "htm" | "css" | "sql" | "py" | "rs" | "go" |
+            "java" | "cpp" | "c" | "h" | "php" | "rb" | "sh" | "bash"
+        )
+    }
+
+    #[allow(dead_code)]
+    async fn extract_text_content(
+        file_path: &Path,
+        original_name: &str,
+    ) -> Result<ExtractedContent, ContentExtractionError> {
+        Self::extract_text_content_with_limits(file_path, original_name, &Self::get_limits()).await
+    }
+
+    async fn extract_text_content_with_limits(
+        file_path: &Path,
+        _original_name: &str,
+        limits: &ExtractionLimits,
+    ) -> Result<ExtractedContent, ContentExtractionError> {
+        let content = fs::read_to_string(file_path)
+            .map_err(|e| ContentExtractionError::IoError(e.to_string()))?;
+        
+        let line_count = content.lines().count();
+        let word_count = content.split_whitespace().count();
+        let char_count = content.chars().count();
+        
+        // Extract preview (respecting limits)
+        let preview_len = std::cmp::min(char_count, limits.max_preview_length);
+        let preview = if char_count > preview_len {
+            format!("{}...", &content.chars().take(preview_len).collect::<String>())
+        } else {
+            content.clone()
+        };
+
+        Ok(ExtractedContent {
+            text_content: Some(content),
+            structured_data: Some(json!({
+                "type": "text_file",
+                "stats": {
+                    "lines": line_count,
+                    "words": word_count,
+                    "characters": char_count
+                }
+            })),
+            description: Some(format!(
+                "Text file with {} lines, {} words, {} characters",
+                line_count, word_count, char_count
+            )),
+            preview: Some(preview),
+        })
+    }
+
+    async fn extract_image_content(
+        file_path: &Path,
+        _original_name: &str,
+    ) -> Result<ExtractedContent, ContentExtractionError> {
+        // Get basic image metadata
+        let metadata = fs::metadata(file_path)
+            .map_err(|e| ContentExtractionError::IoError(e.to_string()))?;
+        
+        let file_size = metadata.len();
+        
+        // TODO: Add actual image processing here
+        // For now, provide basic info and suggest what could be done
+        let description = format!(
+            "Image file ({} bytes). Content analysis available through Vision AI.", 
+            file_size
+        );
+
+        Ok(ExtractedContent {
+            text_content: None,
+            structured_data: Some(json!({
+                "type": "image",
+                "size_bytes": file_size,
+                "analysis_available": true,
+                "suggested_actions": [
+                    "Use Claude Vision API to analyze image content",
+                    "Extract text using OCR if image contains text",
+                    "Describe visual elements, charts, or diagrams"
+                ]
+            })),
+            description: Some(description),
+            preview: None,
+        })
+    }
+
+    #[allow(dead_code)]
+    async fn extract_json_content(
+        file_path: &Path,
+        original_name: &str,
+    ) -> Result<ExtractedContent, ContentExtractionError> {
+        Self::extract_json_content_with_limits(file_path, original_name, &Self::get_limits()).await
+    }
+
+    async fn extract_json_content_with_limits(
+        file_path: &Path,
+        _original_name: &str,
+        limits: &ExtractionLimits,
+    ) -> Result<ExtractedContent, ContentExtractionError> {
+        let content = fs::read_to_string(file_path)
+            .map_err(|e| ContentExtractionError::IoError(e.to_string()))?;
+        
+        // Parse JSON to validate and analyze structure
+        match serde_json::from_str::<Value>(&content) {
+            Ok(json_value) => {
+                let structure_info = Self::analyze_json_structure(&json_value);
+                let preview_len = std::cmp::min(content.len(), limits.max_preview_length);
+                let preview = if content.len() > preview_len {
+                    format!("{}...", &content[..preview_len])
+                } else {
+                    content.clone()
+                };
+
+                Ok(ExtractedContent {
+                    text_content: Some(content),
+                    structured_data: Some(json!({
+                        "type": "json",
+                        "valid": true,
+                        "structure": structure_info
+                    })),
+                    description: Some(format!("Valid JSON file: {}", structure_info.description)),
+                    preview: Some(preview),
+                })
+            }
+            Err(e) => {
+                Ok(ExtractedContent {
+                    text_content: Some(content.clone()),
+                    structured_data: Some(json!({
+                        "type": "json",
+                        "valid": false,
+                        "error": e.to_string()
+                    })),
+                    description: Some(format!("Invalid JSON file: {}", e)),
+                    preview: Some(content.chars().take(500).collect()),
+                })
+            }
+        }
+    }
+
+    async fn extract_basic_metadata(
+        file_path: &Path,
+        _original_name: &str,
+        mime_type: &str,
+    ) -> Result<ExtractedContent, ContentExtractionError> {
+        let metadata = fs::metadata(file_path)
+            .map_err(|e| ContentExtractionError::IoError(e.to_string()))?;
+        
+        let description = format!(
+            "File of type {} ({} bytes)",
+            mime_type,
+            metadata.len()
+        );
+
+        Ok(ExtractedContent {
+            text_content: None,
+            structured_data: Some(json!({
+                "type": "binary_file",
+                "mime_type": mime_type,
+                "size_bytes": metadata.len()
+            })),
+            description: Some(description),
+            preview: None,
+        })
+    }
+
+    fn analyze_json_structure(value: &Value) -> JsonStructureInfo {
+        match value {
+            Value::Object(obj) => JsonStructureInfo {
+                json_type: "object".to_string(),
+                description: format!("Object with {} keys", obj.len()),
+                details: Some(json!({
+                    "keys": obj.keys().collect::<Vec<_>>(),
+                    "key_count": obj.len()
+                })),
+            },
+            Value::Array(arr) => JsonStructureInfo {
+                json_type: "array".to_string(),
+                description: format!("Array with {} items", arr.len()),
+                details: Some(json!({
+                    "length": arr.len(),
+                    "item_types": arr.iter().map(Self::get_json_type).collect::<Vec<_>>()
+                })),
+            },
+            _ => JsonStructureInfo {
+                json_type: Self::get_json_type(value),
+                description: format!("Single {} value", Self::get_json_type(value)),
+                details: None,
+            },
+        }
+    }
+
+    fn get_json_type(value: &Value) -> String {
+        match value {
+            Value::Null => "null".to_string(),
+            Value::Bool(_) => "boolean".to_string(),
+            Value::Number(_) => "number".to_string(),
+            Value::String(_) => "string".to_string(),
+            Value::Array(_) => "array".to_string(),
+            Value::Object(_) => "object".to_string(),
+        }
+    }
+}
+
+/// Extracted content from a file
+#[derive(Debug, Clone)]
+pub struct ExtractedContent {
+    /// Text content if file is readable as text
+    pub text_content: Option<String>,
+    /// Structured metadata and analysis
+    pub structured_data: Option<Value>,
+    /// Human-readable description
+    pub description: Option<String>,
+    /// Preview of content (first N chars/lines)
+    pub preview: Option<String>,
+}
+
+#[derive(Debug, Clone, serde::Serialize)]
+struct JsonStructureInfo {
+    json_type: String,
+    description: String,
+    details: Option<Value>,
+}
+
+#[derive(Debug)]
+pub enum ContentExtractionError {
+    IoError(String),
+    ParseError(String),
+    UnsupportedFormat(String),
+    DocumentParseError(String),
+}
+
+impl std::fmt::Display for ContentExtractionError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ContentExtractionError::IoError(msg) => write!(f, "IO Error: {}", msg),
+            ContentExtractionError::ParseError(msg) => write!(f, "Parse Error: {}", msg),
+            ContentExtractionError::UnsupportedFormat(msg) => write!(f, "Unsupported Format: {}", msg),
+            ContentExtractionError::DocumentParseError(msg) => write!(f, "Document Parse Error: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for ContentExtractionError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn a_tiny_parse_limit_flags_small_files_as_large() {
+        let limits = ExtractionLimits {
+            max_full_parse_size: 16,
+            ..ExtractionLimits::default()
+        };
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("notes.txt");
+        fs::write(&path, "just over sixteen bytes of text").unwrap();
+
+        assert!(limits.is_large_file(fs::metadata(&path).unwrap().len()));
+        let extracted = ContentExtractor::extract_content_with_limits(&path, "notes.txt", "text/plain", &limits)
+            .await
+            .unwrap();
+        assert!(extracted.text_content.is_none());
+        assert_eq!(extracted.structured_data.unwrap()["content_extraction_skipped"], true);
+
+        let extracted = ContentExtractor::extract_content_with_limits(&path, "notes.txt", "text/plain", &ExtractionLimits::default())
+            .await
+            .unwrap();
+        assert!(extracted.text_content.is_some());
+    }
+}
@@ -0,0 +1,209 @@
+//! PDF text extraction, reporting progress per page
+
+use std::fs;
+use std::path::Path;
+use serde_json::json;
+use pdf_extract::{output_doc_page, Document, OutputError, PlainTextOutput};
+
+use super::{ContentExtractionError, ContentExtractor, ExtractedContent, ExtractionLimits, ExtractionProgress, ProgressCallback};
+
+impl ContentExtractor {
+    #[allow(dead_code)]
+    async fn extract_pdf_content(
+        file_path: &Path,
+        original_name: &str,
+    ) -> Result<ExtractedContent, ContentExtractionError> {
+        Self::extract_pdf_content_with_limits(file_path, original_name, &Self::get_limits(), None).await
+    }
+
+    /// The text of every page and the page count. Only the document structure
+    /// is parsed up front; each page's text is extracted in turn, with progress
+    /// reported after it. A page whose text can't be extracted is skipped.
+    fn extract_pdf_pages(file_path: &Path, progress: ProgressCallback<'_>) -> Result<(String, usize), OutputError> {
+        let mut document = Document::load(file_path)?;
+        if document.is_encrypted() {
+            document.decrypt("")?;
+        }
+
+        let page_numbers: Vec<u32> = document.get_pages().keys().copied().collect();
+        let page_count = page_numbers.len();
+        if let Some(report) = progress {
+            report(ExtractionProgress { unit: "pages", processed: 0, total: page_count });
+        }
+
+        let mut text = String::new();
+        for (page_idx, page_number) in page_numbers.into_iter().enumerate() {
+            let mut page_text = String::new();
+            if output_doc_page(&document, &mut PlainTextOutput::new(&mut page_text), page_number).is_ok() {
+                text.push_str(&page_text);
+            }
+            if let Some(report) = progress {
+                report(ExtractionProgress { unit: "pages", processed: page_idx + 1, total: page_count });
+            }
+        }
+        Ok((text, page_count))
+    }
+
+    pub(super) async fn extract_pdf_content_with_limits(
+        file_path: &Path,
+        _original_name: &str,
+        limits: &ExtractionLimits,
+        progress: ProgressCallback<'_>,
+    ) -> Result<ExtractedContent, ContentExtractionError> {
+        match Self::extract_pdf_pages(file_path, progress) {
+            Ok((text, page_count)) => {
+                let line_count = text.lines().count();
+                let word_count = text.split_whitespace().count();
+                let char_count = text.chars().count();
+
+                // Scanned PDFs have pages but no text layer; say so instead of storing nothing
+                if word_count == 0 {
+                    return Ok(ExtractedContent {
+                        text_content: None,
+                        structured_data: Some(json!({
+                            "type": "pdf",
+                            "stats": { "pages": page_count },
+                            "text_extraction_successful": false,
+                            "needs_ocr": true
+                        })),
+                        description: Some(format!(
+                            "PDF document with {} pages and no text layer (likely scanned); OCR would be needed to read its content",
+                            page_count
+                        )),
+                        preview: None,
+                    });
+                }
+                
+                // Create preview (respecting limits)
+                let preview = if char_count > limits.max_preview_length {
+                    format!("{}...", &text.chars().take(limits.max_preview_length).collect::<String>())
+                } else {
+                    text.clone()
+                };
+                
+                let description = format!(
+                    "PDF document with {} pages, {} lines, {} words, {} characters (text extracted)",
+                    page_count, line_count, word_count, char_count
+                );
+
+                // Check if the text seems meaningful (not just whitespace/gibberish)
+                let meaningful_content = word_count > 0 && char_count > 50;
+
+                Ok(ExtractedContent {
+                    text_content: Some(text),
+                    structured_data: Some(json!({
+                        "type": "pdf",
+                        "stats": {
+                            "pages": page_count,
+                            "lines": line_count,
+                            "words": word_count,
+                            "characters": char_count
+                        },
+                        "text_extraction_successful": true,
+                        "meaningful_content": meaningful_content
+                    })),
+                    description: Some(description),
+                    preview: Some(preview),
+                })
+            }
+            Err(e) => {
+                // Fall back to basic metadata if text extraction fails
+                let metadata = fs::metadata(file_path)
+                    .map_err(|e| ContentExtractionError::IoError(e.to_string()))?;
+                
+                let description = format!(
+                    "PDF document ({} bytes). Text extraction failed: {}",
+                    metadata.len(), e
+                );
+
+                Ok(ExtractedContent {
+                    text_content: None,
+                    structured_data: Some(json!({
+                        "type": "pdf",
+                        "size_bytes": metadata.len(),
+                        "text_extraction_failed": true,
+                        "error": e.to_string(),
+                        "suggested_actions": [
+                            "PDF may be image-based or encrypted",
+                            "Try OCR for image-based PDFs",
+                            "Check if PDF requires password"
+                        ]
+                    })),
+                    description: Some(description),
+                    preview: None,
+                })
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A one-page PDF whose page draws `content`, with a valid xref table
+    fn sample_pdf(content: &str) -> Vec<u8> {
+        let objects = [
+            "<< /Type /Catalog /Pages 2 0 R >>".to_string(),
+            "<< /Type /Pages /Kids [3 0 R] /Count 1 >>".to_string(),
+            "<< /Type /Page /Parent 2 0 R /MediaBox [0 0 612 792] /Contents 4 0 R /Resources << /Font << /F1 5 0 R >> >> >>".to_string(),
+            format!("<< /Length {} >>\nstream\n{}\nendstream", content.len(), content),
+            "<< /Type /Font /Subtype /Type1 /BaseFont /Helvetica >>".to_string(),
+        ];
+        let mut pdf = b"%PDF-1.4\n".to_vec();
+        let mut offsets = Vec::new();
+        for (i, object) in objects.iter().enumerate() {
+            offsets.push(pdf.len());
+            pdf.extend_from_slice(format!("{} 0 obj\n{}\nendobj\n", i + 1, object).as_bytes());
+        }
+        let xref_offset = pdf.len();
+        pdf.extend_from_slice(format!("xref\n0 {}\n0000000000 65535 f \n", objects.len() + 1).as_bytes());
+        for offset in offsets {
+            pdf.extend_from_slice(format!("{:010} 00000 n \n", offset).as_bytes());
+        }
+        pdf.extend_from_slice(
+            format!("trailer\n<< /Size {} /Root 1 0 R >>\nstartxref\n{}\n%%EOF\n", objects.len() + 1, xref_offset).as_bytes(),
+        );
+        pdf
+    }
+
+    #[tokio::test]
+    async fn pdf_text_is_extracted_and_scans_ask_for_ocr() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("report.pdf");
+        fs::write(&path, sample_pdf("BT /F1 24 Tf 72 720 Td (Quarterly revenue report) Tj ET")).unwrap();
+
+        // Uploaded as octet-stream, recognised by the extension
+        let extracted = ContentExtractor::extract_content(&path, "report.pdf", "application/octet-stream")
+            .await
+            .unwrap();
+        assert!(extracted.text_content.unwrap().contains("Quarterly revenue report"));
+        assert!(extracted.preview.unwrap().contains("Quarterly"));
+
+        let scan = dir.path().join("scan.pdf");
+        fs::write(&scan, sample_pdf("q 0 0 0 rg 72 72 100 100 re f Q")).unwrap();
+        let extracted = ContentExtractor::extract_content(&scan, "scan.pdf", "application/pdf")
+            .await
+            .unwrap();
+        assert!(extracted.text_content.is_none());
+        assert!(extracted.description.unwrap().contains("OCR"));
+        assert_eq!(extracted.structured_data.unwrap()["needs_ocr"], true);
+    }
+
+    #[tokio::test]
+    async fn progress_is_reported_as_each_page_is_extracted() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("report.pdf");
+        fs::write(&path, sample_pdf("BT /F1 24 Tf 72 720 Td (Quarterly revenue report) Tj ET")).unwrap();
+
+        let reports = std::sync::Mutex::new(Vec::new());
+        let record = |progress: ExtractionProgress| {
+            reports.lock().unwrap().push((progress.processed, progress.total));
+        };
+        ContentExtractor::extract_content_with_progress(&path, "report.pdf", "application/pdf", Some(&record))
+            .await
+            .unwrap();
+
+        assert_eq!(reports.into_inner().unwrap(), vec![(0, 1), (1, 1)]);
+    }
+}
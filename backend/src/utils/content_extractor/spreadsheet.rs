@@ -0,0 +1,392 @@
+//! CSV and Excel workbook extraction, reporting progress per sheet
+
+use std::fs;
+use std::path::Path;
+use serde_json::json;
+use calamine::{Reader, Xlsx, Xls, open_workbook, Data};
+
+use super::{ContentExtractionError, ContentExtractor, ExtractedContent, ExtractionLimits, ExtractionProgress, ProgressCallback};
+
+impl ContentExtractor {
+    #[allow(dead_code)]
+    async fn extract_spreadsheet_content(
+        file_path: &Path,
+        original_name: &str,
+        extension: &str,
+    ) -> Result<ExtractedContent, ContentExtractionError> {
+        Self::extract_spreadsheet_content_with_limits(file_path, original_name, extension, &Self::get_limits(), None).await
+    }
+
+    pub(super) async fn extract_spreadsheet_content_with_limits(
+        file_path: &Path,
+        original_name: &str,
+        extension: &str,
+        limits: &ExtractionLimits,
+        progress: ProgressCallback<'_>,
+    ) -> Result<ExtractedContent, ContentExtractionError> {
+        match extension {
+            "csv" => Self::extract_csv_content_with_limits(file_path, original_name, limits).await,
+            "xlsx" | "xls" => Self::extract_excel_content_with_limits(file_path, original_name, limits, progress).await,
+            _ => Self::extract_basic_metadata(file_path, original_name, "spreadsheet").await,
+        }
+    }
+
+    #[allow(dead_code)]
+    async fn extract_csv_content(
+        file_path: &Path,
+        original_name: &str,
+    ) -> Result<ExtractedContent, ContentExtractionError> {
+        Self::extract_csv_content_with_limits(file_path, original_name, &Self::get_limits()).await
+    }
+
+    async fn extract_csv_content_with_limits(
+        file_path: &Path,
+        _original_name: &str,
+        limits: &ExtractionLimits,
+    ) -> Result<ExtractedContent, ContentExtractionError> {
+        let content = fs::read_to_string(file_path)
+            .map_err(|e| ContentExtractionError::IoError(e.to_string()))?;
+        
+        let lines: Vec<&str> = content.lines().collect();
+        let row_count = lines.len();
+        
+        if row_count == 0 {
+            return Ok(ExtractedContent {
+                text_content: Some(content),
+                structured_data: Some(json!({"type": "csv", "rows": 0})),
+                description: Some("Empty CSV file".to_string()),
+                preview: None,
+            });
+        }
+
+        // Get headers (first row)
+        let headers = if let Some(first_line) = lines.first() {
+            first_line.split(',').map(|h| h.trim()).collect::<Vec<_>>()
+        } else {
+            vec![]
+        };
+
+        let column_count = headers.len();
+        
+        // Create preview with limited rows
+        let max_preview_rows = std::cmp::min(20, limits.max_excel_rows / 100); // Reasonable preview size
+        let preview_rows = lines.iter().take(max_preview_rows).map(|line| line.to_string()).collect::<Vec<_>>();
+        let preview = preview_rows.join("\n");
+        
+        // Truncate content if too many rows
+        let processed_content = if row_count > limits.max_excel_rows {
+            let truncated_lines: Vec<&str> = lines.iter().take(limits.max_excel_rows).cloned().collect();
+            format!("{}... [CSV truncated - original had {} rows]", 
+                   truncated_lines.join("\n"), row_count)
+        } else {
+            content.clone()
+        };
+        
+        // Create structured description
+        let description = format!(
+            "CSV file with {} rows and {} columns. Headers: {}",
+            row_count,
+            column_count,
+            headers.join(", ")
+        );
+
+        let mut structured_data = json!({
+            "type": "csv",
+            "rows": row_count,
+            "columns": column_count,
+            "headers": headers,
+            "sample_data": preview_rows
+        });
+
+        // Add truncation info if applicable
+        if row_count > limits.max_excel_rows {
+            structured_data["truncated"] = json!(true);
+            structured_data["original_rows"] = json!(row_count);
+            structured_data["processed_rows"] = json!(limits.max_excel_rows);
+        }
+
+        Ok(ExtractedContent {
+            text_content: Some(processed_content),
+            structured_data: Some(structured_data),
+            description: Some(description),
+            preview: Some(preview),
+        })
+    }
+
+    #[allow(dead_code)]
+    async fn extract_excel_content(
+        file_path: &Path,
+        original_name: &str,
+    ) -> Result<ExtractedContent, ContentExtractionError> {
+        Self::extract_excel_content_with_limits(file_path, original_name, &Self::get_limits(), None).await
+    }
+
+    async fn extract_excel_content_with_limits(
+        file_path: &Path,
+        _original_name: &str,
+        limits: &ExtractionLimits,
+        progress: ProgressCallback<'_>,
+    ) -> Result<ExtractedContent, ContentExtractionError> {
+        // Try to extract content based on file extension
+        let extension = file_path.extension()
+            .and_then(|s| s.to_str())
+            .map(|s| s.to_lowercase())
+            .unwrap_or_default();
+
+        let result = if extension == "xlsx" {
+            Self::extract_xlsx_content_with_limits(file_path, limits, progress).await
+        } else if extension == "xls" {
+            Self::extract_xls_content_with_limits(file_path, limits, progress).await
+        } else {
+            Err(ContentExtractionError::UnsupportedFormat(
+                "Unsupported Excel format".to_string()
+            ))
+        };
+
+        result.or_else(|_| {
+            // Fall back to basic metadata if parsing fails
+            let metadata = fs::metadata(file_path)
+                .map_err(|e| ContentExtractionError::IoError(e.to_string()))?;
+            
+            let description = format!(
+                "Excel file ({} bytes). Parsing failed, basic metadata only.",
+                metadata.len()
+            );
+
+            Ok(ExtractedContent {
+                text_content: None,
+                structured_data: Some(json!({
+                    "type": "excel",
+                    "size_bytes": metadata.len(),
+                    "parsing_failed": true
+                })),
+                description: Some(description),
+                preview: None,
+            })
+        })
+    }
+
+    #[allow(dead_code)]
+    async fn extract_xlsx_content(file_path: &Path) -> Result<ExtractedContent, ContentExtractionError> {
+        Self::extract_xlsx_content_with_limits(file_path, &Self::get_limits(), None).await
+    }
+
+    async fn extract_xlsx_content_with_limits(file_path: &Path, limits: &ExtractionLimits, progress: ProgressCallback<'_>) -> Result<ExtractedContent, ContentExtractionError> {
+        let mut workbook: Xlsx<_> = open_workbook(file_path)
+            .map_err(|e| ContentExtractionError::ParseError(format!("XLSX error: {}", e)))?;
+        
+        Self::process_xlsx_workbook_with_limits(&mut workbook, limits, progress).await
+    }
+
+    #[allow(dead_code)]
+    async fn extract_xls_content(file_path: &Path) -> Result<ExtractedContent, ContentExtractionError> {
+        Self::extract_xls_content_with_limits(file_path, &Self::get_limits(), None).await
+    }
+
+    async fn extract_xls_content_with_limits(file_path: &Path, limits: &ExtractionLimits, progress: ProgressCallback<'_>) -> Result<ExtractedContent, ContentExtractionError> {
+        let mut workbook: Xls<_> = open_workbook(file_path)
+            .map_err(|e| ContentExtractionError::ParseError(format!("XLS error: {}", e)))?;
+        
+        Self::process_xls_workbook_with_limits(&mut workbook, limits, progress).await
+    }
+
+    #[allow(dead_code)]
+    async fn process_xlsx_workbook(workbook: &mut Xlsx<std::io::BufReader<std::fs::File>>) -> Result<ExtractedContent, ContentExtractionError> {
+        Self::process_xlsx_workbook_with_limits(workbook, &Self::get_limits(), None).await
+    }
+
+    async fn process_xlsx_workbook_with_limits(workbook: &mut Xlsx<std::io::BufReader<std::fs::File>>, _limits: &ExtractionLimits, progress: ProgressCallback<'_>) -> Result<ExtractedContent, ContentExtractionError> {
+        let sheet_names = workbook.sheet_names().to_owned();
+        let mut full_text = String::new();
+        let mut total_rows = 0;
+        let mut total_cols = 0;
+        let mut sheets_data = Vec::new();
+
+        // Process each sheet
+        for (sheet_idx, sheet_name) in sheet_names.iter().enumerate() {
+            if let Some(report) = progress {
+                report(ExtractionProgress { unit: "sheets", processed: sheet_idx, total: sheet_names.len() });
+            }
+            if let Ok(range) = workbook.worksheet_range(sheet_name) {
+                let (rows, cols) = range.get_size();
+                total_rows += rows;
+                if cols > total_cols {
+                    total_cols = cols;
+                }
+
+                // Extract data from this sheet
+                let mut sheet_text = format!("=== Sheet: {} ===\n", sheet_name);
+                let mut row_data = Vec::new();
+                
+                // Get first 20 rows for preview
+                let preview_rows = std::cmp::min(rows, 20);
+                for row_idx in 0..preview_rows {
+                    let mut row_values = Vec::new();
+                    for col_idx in 0..cols {
+                        if let Some(cell) = range.get_value((row_idx as u32, col_idx as u32)) {
+                            let cell_str = match cell {
+                                Data::Empty => String::new(),
+                                Data::String(s) => s.clone(),
+                                Data::Float(f) => f.to_string(),
+                                Data::Int(i) => i.to_string(),
+                                Data::Bool(b) => b.to_string(),
+                                Data::Error(e) => format!("#ERROR: {:?}", e),
+                                Data::DateTime(dt) => dt.to_string(),
+                                Data::DateTimeIso(dt) => dt.clone(),
+                                Data::DurationIso(d) => d.clone(),
+                            };
+                            row_values.push(cell_str);
+                        } else {
+                            row_values.push(String::new());
+                        }
+                    }
+                    row_data.push(row_values.clone());
+                    sheet_text.push_str(&format!("{}\n", row_values.join("\t")));
+                }
+
+                full_text.push_str(&sheet_text);
+                full_text.push('\n');
+
+                sheets_data.push(json!({
+                    "name": sheet_name,
+                    "rows": rows,
+                    "columns": cols,
+                    "preview_data": row_data
+                }));
+            }
+        }
+
+        if let Some(report) = progress {
+            report(ExtractionProgress { unit: "sheets", processed: sheet_names.len(), total: sheet_names.len() });
+        }
+
+        let char_count = full_text.chars().count();
+        let preview = if char_count > 2000 {
+            format!("{}...", &full_text.chars().take(2000).collect::<String>())
+        } else {
+            full_text.clone()
+        };
+
+        let description = format!(
+            "Excel workbook with {} sheets, {} total rows, {} max columns",
+            sheet_names.len(), total_rows, total_cols
+        );
+
+        Ok(ExtractedContent {
+            text_content: Some(full_text),
+            structured_data: Some(json!({
+                "type": "excel",
+                "stats": {
+                    "sheets": sheet_names.len(),
+                    "total_rows": total_rows,
+                    "max_columns": total_cols,
+                    "sheet_names": sheet_names
+                },
+                "sheets": sheets_data,
+                "parsed_successfully": true
+            })),
+            description: Some(description),
+            preview: Some(preview),
+        })
+    }
+
+    #[allow(dead_code)]
+    async fn process_xls_workbook(workbook: &mut Xls<std::io::BufReader<std::fs::File>>) -> Result<ExtractedContent, ContentExtractionError> {
+        Self::process_xls_workbook_with_limits(workbook, &Self::get_limits(), None).await
+    }
+
+    async fn process_xls_workbook_with_limits(workbook: &mut Xls<std::io::BufReader<std::fs::File>>, _limits: &ExtractionLimits, progress: ProgressCallback<'_>) -> Result<ExtractedContent, ContentExtractionError> {
+        let sheet_names = workbook.sheet_names().to_owned();
+        let mut full_text = String::new();
+        let mut total_rows = 0;
+        let mut total_cols = 0;
+        let mut sheets_data = Vec::new();
+
+        // Process each sheet
+        for (sheet_idx, sheet_name) in sheet_names.iter().enumerate() {
+            if let Some(report) = progress {
+                report(ExtractionProgress { unit: "sheets", processed: sheet_idx, total: sheet_names.len() });
+            }
+            if let Ok(range) = workbook.worksheet_range(sheet_name) {
+                let (rows, cols) = range.get_size();
+                total_rows += rows;
+                if cols > total_cols {
+                    total_cols = cols;
+                }
+
+                // Extract data from this sheet
+                let mut sheet_text = format!("=== Sheet: {} ===\n", sheet_name);
+                let mut row_data = Vec::new();
+                
+                // Get first 20 rows for preview
+                let preview_rows = std::cmp::min(rows, 20);
+                for row_idx in 0..preview_rows {
+                    let mut row_values = Vec::new();
+                    for col_idx in 0..cols {
+                        if let Some(cell) = range.get_value((row_idx as u32, col_idx as u32)) {
+                            let cell_str = match cell {
+                                Data::Empty => String::new(),
+                                Data::String(s) => s.clone(),
+                                Data::Float(f) => f.to_string(),
+                                Data::Int(i) => i.to_string(),
+                                Data::Bool(b) => b.to_string(),
+                                Data::Error(e) => format!("#ERROR: {:?}", e),
+                                Data::DateTime(dt) => dt.to_string(),
+                                Data::DateTimeIso(dt) => dt.clone(),
+                                Data::DurationIso(d) => d.clone(),
+                            };
+                            row_values.push(cell_str);
+                        } else {
+                            row_values.push(String::new());
+                        }
+                    }
+                    row_data.push(row_values.clone());
+                    sheet_text.push_str(&format!("{}\n", row_values.join("\t")));
+                }
+
+                full_text.push_str(&sheet_text);
+                full_text.push('\n');
+
+                sheets_data.push(json!({
+                    "name": sheet_name,
+                    "rows": rows,
+                    "columns": cols,
+                    "preview_data": row_data
+                }));
+            }
+        }
+
+        if let Some(report) = progress {
+            report(ExtractionProgress { unit: "sheets", processed: sheet_names.len(), total: sheet_names.len() });
+        }
+
+        let char_count = full_text.chars().count();
+        let preview = if char_count > 2000 {
+            format!("{}...", &full_text.chars().take(2000).collect::<String>())
+        } else {
+            full_text.clone()
+        };
+
+        let description = format!(
+            "Excel workbook with {} sheets, {} total rows, {} max columns",
+            sheet_names.len(), total_rows, total_cols
+        );
+
+        Ok(ExtractedContent {
+            text_content: Some(full_text),
+            structured_data: Some(json!({
+                "type": "excel",
+                "stats": {
+                    "sheets": sheet_names.len(),
+                    "total_rows": total_rows,
+                    "max_columns": total_cols,
+                    "sheet_names": sheet_names
+                },
+                "sheets": sheets_data,
+                "parsed_successfully": true
+            })),
+            description: Some(description),
+            preview: Some(preview),
+        })
+    }
+}
@@ -109,12 +109,12 @@ impl ContextCompiler {
         
         // Find all ```javascript blocks
         let js_block_regex = Regex::new(r"(?ms)```javascript\n(.*?)\n```")
-            .expect("Invalid regex pattern for JavaScript blocks");
+            .context("Invalid regex pattern for JavaScript blocks")?;
         
         let mut replacements = Vec::new();
         
         for cap in js_block_regex.captures_iter(content) {
-            let full_match = cap.get(0).expect("Regex capture should have full match");
+            let Some(full_match) = cap.get(0) else { continue };
             let js_code = &cap[1];
             
             // Execute the JavaScript code
@@ -145,7 +145,7 @@ impl ContextCompiler {
         
         // Extract SQL query from await ctx.query(`...`)
         let query_regex = Regex::new(r"(?s)await\s+ctx\.query\s*\(\s*`([^`]+)`\s*\)")
-            .expect("Invalid regex pattern for query extraction");
+            .context("Invalid regex pattern for query extraction")?;
         
         if let Some(cap) = query_regex.captures(script) {
             let sql = &cap[1];
@@ -126,6 +126,55 @@ impl ConnectionConfig {
     }
 }
 
+const DEFAULT_APPLICATION_NAME_TEMPLATE: &str = "clay-studio c:{client} p:{project} d:{datasource}";
+/// Postgres truncates application_name to NAMEDATALEN - 1 bytes
+const MAX_APPLICATION_NAME_LEN: usize = 63;
+
+/// Record the owning client and project in a datasource's connection config so
+/// connectors can tag their sessions with them
+pub fn tag_connection_owner(config: &mut Value, client_id: Option<&str>, project_id: &str) {
+    if let Some(obj) = config.as_object_mut() {
+        obj.insert("project_id".to_string(), Value::String(project_id.to_string()));
+        if let Some(client_id) = client_id {
+            obj.insert("client_id".to_string(), Value::String(client_id.to_string()));
+        }
+    }
+}
+
+/// Session name reported to the datasource server (Postgres `application_name`,
+/// SQL Server program name) so DBAs can attribute load to Clay Studio.
+/// The template comes from DATASOURCE_APPLICATION_NAME.
+pub fn connection_application_name(config: &Value) -> String {
+    let template = std::env::var("DATASOURCE_APPLICATION_NAME")
+        .unwrap_or_else(|_| DEFAULT_APPLICATION_NAME_TEMPLATE.to_string());
+    render_application_name(&template, config)
+}
+
+/// Expand `{client_id}`, `{project_id}` and `{datasource_id}` (full ids) and
+/// `{client}`, `{project}`, `{datasource}` (first 8 characters) in `template`
+pub fn render_application_name(template: &str, config: &Value) -> String {
+    let id = |key: &str| config.get(key).and_then(|v| v.as_str()).unwrap_or("-").to_string();
+    let short = |value: &str| value.chars().take(8).collect::<String>();
+
+    let client_id = id("client_id");
+    let project_id = id("project_id");
+    let datasource_id = id("id");
+
+    let name = template
+        .replace("{client_id}", &client_id)
+        .replace("{project_id}", &project_id)
+        .replace("{datasource_id}", &datasource_id)
+        .replace("{client}", &short(&client_id))
+        .replace("{project}", &short(&project_id))
+        .replace("{datasource}", &short(&datasource_id));
+
+    // Servers reject or mangle non-ASCII names
+    name.chars()
+        .filter(|c| c.is_ascii() && !c.is_ascii_control())
+        .take(MAX_APPLICATION_NAME_LEN)
+        .collect()
+}
+
 /// Extract schema name from config with database-specific defaults
 #[allow(dead_code)]
 pub fn extract_schema_name(config: &Value, default_schema: &str) -> String {
@@ -134,4 +183,35 @@ pub fn extract_schema_name(config: &Value, default_schema: &str) -> String {
         .and_then(|v| v.as_str())
         .unwrap_or(default_schema)
         .to_string()
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_application_name_uses_short_ids() {
+        let config = json!({
+            "id": "3f2b1c9e-0000-4000-8000-000000000001",
+            "project_id": "a1b2c3d4-0000-4000-8000-000000000002",
+            "client_id": "99887766-0000-4000-8000-000000000003"
+        });
+        assert_eq!(
+            render_application_name(DEFAULT_APPLICATION_NAME_TEMPLATE, &config),
+            "clay-studio c:99887766 p:a1b2c3d4 d:3f2b1c9e"
+        );
+    }
+
+    #[test]
+    fn test_application_name_is_truncated_and_tolerates_missing_ids() {
+        let name = render_application_name("clay-studio {project_id} {datasource_id} {client_id}", &json!({
+            "id": "3f2b1c9e-0000-4000-8000-000000000001",
+            "project_id": "a1b2c3d4-0000-4000-8000-000000000002"
+        }));
+        assert_eq!(name.len(), MAX_APPLICATION_NAME_LEN);
+        assert!(name.starts_with("clay-studio a1b2c3d4-"));
+
+        assert_eq!(render_application_name("clay-studio {client}", &json!({})), "clay-studio -");
+    }
+}
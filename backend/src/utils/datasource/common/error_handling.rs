@@ -29,6 +29,165 @@ impl fmt::Display for DatabaseError {
 
 impl Error for DatabaseError {}
 
+/// Dialect-independent classification of a connector failure
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConnectorErrorKind {
+    Auth,
+    HostUnreachable,
+    DatabaseNotFound,
+    PermissionDenied,
+    SyntaxError,
+    Timeout,
+    Other,
+}
+
+impl ConnectorErrorKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ConnectorErrorKind::Auth => "auth",
+            ConnectorErrorKind::HostUnreachable => "host_unreachable",
+            ConnectorErrorKind::DatabaseNotFound => "database_not_found",
+            ConnectorErrorKind::PermissionDenied => "permission_denied",
+            ConnectorErrorKind::SyntaxError => "syntax_error",
+            ConnectorErrorKind::Timeout => "timeout",
+            ConnectorErrorKind::Other => "other",
+        }
+    }
+
+    pub fn user_message(&self) -> &'static str {
+        match self {
+            ConnectorErrorKind::Auth => "Authentication failed: check the username and password",
+            ConnectorErrorKind::HostUnreachable => "Could not reach the database server: check the host, port and network access",
+            ConnectorErrorKind::DatabaseNotFound => "The database does not exist on the server: check the database name",
+            ConnectorErrorKind::PermissionDenied => "The database user lacks permission for this operation",
+            ConnectorErrorKind::SyntaxError => "The query has a syntax error for this database dialect",
+            ConnectorErrorKind::Timeout => "The database did not respond in time",
+            ConnectorErrorKind::Other => "The database operation failed",
+        }
+    }
+}
+
+// Patterns are matched against the lowercased driver message. Order matters:
+// MySQL's "access denied for user" is an auth failure, while its other
+// "access denied" variants are privilege errors.
+const CLASSIFICATION_PATTERNS: &[(ConnectorErrorKind, &[&str])] = &[
+    (ConnectorErrorKind::Auth, &[
+        "password authentication failed",
+        "access denied for user",
+        "login failed for user",
+        "ora-01017",
+        "authentication_failed",
+        "code: 516.",
+        "authentication failed",
+        "invalid password",
+        "no password supplied",
+    ]),
+    (ConnectorErrorKind::DatabaseNotFound, &[
+        "unknown database",
+        "cannot open database",
+        "ora-12514",
+        "code: 81.",
+        "unknown_database",
+        "unable to open database file",
+    ]),
+    (ConnectorErrorKind::PermissionDenied, &[
+        "permission denied",
+        "permission was denied",
+        "command denied",
+        "access denied",
+        "insufficient privilege",
+        "ora-01031",
+        "code: 497.",
+        "not enough privileges",
+        "readonly database",
+        "read-only transaction",
+    ]),
+    (ConnectorErrorKind::SyntaxError, &[
+        "syntax error",
+        "error in your sql syntax",
+        "incorrect syntax",
+        "ora-00900",
+        "ora-00933",
+        "ora-00936",
+        "code: 62.",
+        "syntax_error",
+    ]),
+    (ConnectorErrorKind::Timeout, &[
+        "timed out",
+        "timeout",
+        "statement_timeout",
+        "canceling statement due to",
+        "code: 159.",
+    ]),
+    (ConnectorErrorKind::HostUnreachable, &[
+        "connection refused",
+        "no route to host",
+        "failed to lookup address",
+        "name or service not known",
+        "nodename nor servname",
+        "dns error",
+        "network is unreachable",
+        "could not connect to server",
+        "ora-12541",
+        "ora-12154",
+        "ora-12545",
+        "connection reset",
+        "broken pipe",
+    ]),
+];
+
+/// A connector failure with its classification and the raw driver message
+#[derive(Debug, Clone)]
+pub struct ConnectorError {
+    pub kind: ConnectorErrorKind,
+    pub raw: String,
+}
+
+impl ConnectorError {
+    /// Classify a raw driver message from any dialect
+    pub fn classify(raw: impl Into<String>) -> Self {
+        let raw = raw.into();
+        let lower = raw.to_lowercase();
+        // "database \"x\" does not exist" (Postgres, ClickHouse) is split around the name
+        let kind = if lower.contains("database")
+            && (lower.contains("does not exist") || lower.contains("doesn't exist"))
+        {
+            ConnectorErrorKind::DatabaseNotFound
+        } else {
+            CLASSIFICATION_PATTERNS
+                .iter()
+                .find(|(_, patterns)| patterns.iter().any(|p| lower.contains(p)))
+                .map(|(kind, _)| *kind)
+                .unwrap_or(ConnectorErrorKind::Other)
+        };
+        ConnectorError { kind, raw }
+    }
+
+    pub fn from_error(error: &(dyn Error + '_)) -> Self {
+        Self::classify(error.to_string())
+    }
+
+    pub fn user_message(&self) -> &'static str {
+        self.kind.user_message()
+    }
+
+    pub fn to_json(&self) -> Value {
+        json!({
+            "error": self.user_message(),
+            "category": self.kind.as_str(),
+            "details": self.raw
+        })
+    }
+}
+
+impl fmt::Display for ConnectorError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} ({})", self.user_message(), self.raw)
+    }
+}
+
+impl Error for ConnectorError {}
+
 /// Convert various database errors to standardized JSON responses
 #[allow(dead_code)]
 pub trait ErrorMapper {
@@ -137,4 +296,61 @@ pub fn create_success_response(data: Value, execution_time_ms: u64) -> Value {
         "data": data,
         "execution_time_ms": execution_time_ms
     })
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_auth_failures_across_dialects() {
+        for raw in [
+            "error returned from database: password authentication failed for user \"app\"",
+            "error returned from database: 1045 (28000): Access denied for user 'app'@'10.0.0.1' (using password: YES)",
+            "Token error: 'Login failed for user 'sa'.' on server db executing  on line 1 (code: 18456, state: 1, class: 14)",
+            "ORA-01017: invalid username/password; logon denied",
+        ] {
+            assert_eq!(ConnectorError::classify(raw).kind, ConnectorErrorKind::Auth, "{}", raw);
+        }
+    }
+
+    #[test]
+    fn test_database_not_found_and_permission() {
+        assert_eq!(
+            ConnectorError::classify("error returned from database: database \"analytics\" does not exist").kind,
+            ConnectorErrorKind::DatabaseNotFound
+        );
+        assert_eq!(
+            ConnectorError::classify("1049 (42000): Unknown database 'analytics'").kind,
+            ConnectorErrorKind::DatabaseNotFound
+        );
+        assert_eq!(
+            ConnectorError::classify("1142 (42000): SELECT command denied to user 'ro'@'%' for table 'users'").kind,
+            ConnectorErrorKind::PermissionDenied
+        );
+        assert_eq!(
+            ConnectorError::classify("permission denied for table users").kind,
+            ConnectorErrorKind::PermissionDenied
+        );
+    }
+
+    #[test]
+    fn test_syntax_timeout_and_unreachable() {
+        assert_eq!(
+            ConnectorError::classify("error returned from database: syntax error at or near \"FORM\"").kind,
+            ConnectorErrorKind::SyntaxError
+        );
+        assert_eq!(
+            ConnectorError::classify("Incorrect syntax near the keyword 'FROM'.").kind,
+            ConnectorErrorKind::SyntaxError
+        );
+        assert_eq!(
+            ConnectorError::classify("pool timed out while waiting for an open connection").kind,
+            ConnectorErrorKind::Timeout
+        );
+        assert_eq!(
+            ConnectorError::classify("error communicating with database: Connection refused (os error 111)").kind,
+            ConnectorErrorKind::HostUnreachable
+        );
+        assert_eq!(ConnectorError::classify("something odd").kind, ConnectorErrorKind::Other);
+    }
+}
@@ -0,0 +1,151 @@
+//! Validation for table and column names that end up inside SQL text
+//!
+//! The data browser builds queries from names supplied by the client. A name
+//! is accepted when it is a plain identifier, or when it exactly matches a
+//! table or column in the datasource's cached schema (so real tables with
+//! spaces or dashes keep working). Anything else is rejected.
+
+use serde_json::Value;
+
+use super::schema_shape::find_table_entry;
+
+const MAX_IDENTIFIER_LEN: usize = 128;
+
+/// `[A-Za-z_][A-Za-z0-9_]*`
+fn is_plain_identifier(name: &str) -> bool {
+    let mut chars = name.chars();
+    name.len() <= MAX_IDENTIFIER_LEN
+        && chars.next().is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+/// Columns listed for a table entry, whatever shape the connector used
+fn table_columns(entry: &Value) -> Vec<&str> {
+    let columns = match entry {
+        Value::Array(_) => entry,
+        _ => match entry.get("columns") {
+            Some(columns) => columns,
+            None => return Vec::new(),
+        },
+    };
+
+    columns
+        .as_array()
+        .map(|columns| {
+            columns
+                .iter()
+                .filter_map(|c| {
+                    c.as_str()
+                        .or_else(|| c.get("name").and_then(|n| n.as_str()))
+                        .or_else(|| c.get("column_name").and_then(|n| n.as_str()))
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn schema_table<'a>(schema: &'a Value, table_name: &str) -> Option<&'a Value> {
+    schema
+        .get("tables")
+        .and_then(|tables| tables.get(table_name))
+        .or_else(|| find_table_entry(schema, table_name))
+}
+
+/// Columns the cached schema lists for `table_name`, if it knows the table
+pub fn known_columns(schema: &Value, table_name: &str) -> Option<Vec<String>> {
+    let entry = schema_table(schema, table_name)?;
+    let columns: Vec<String> = table_columns(entry).into_iter().map(str::to_string).collect();
+    (!columns.is_empty()).then_some(columns)
+}
+
+/// Primary key columns the cached schema lists for `table_name`, from its
+/// `primary_keys` list or the columns flagged as keys
+pub fn primary_keys(schema: &Value, table_name: &str) -> Option<Vec<String>> {
+    let entry = schema_table(schema, table_name)?;
+    if let Some(keys) = entry.get("primary_keys").and_then(|k| k.as_array()) {
+        return Some(keys.iter().filter_map(|k| k.as_str().map(str::to_string)).collect());
+    }
+    let keys: Vec<String> = entry
+        .get("columns")
+        .and_then(|c| c.as_array())
+        .into_iter()
+        .flatten()
+        .filter(|column| {
+            column.get("primary_key").and_then(|v| v.as_bool()).unwrap_or(false)
+                || column.get("key").and_then(|v| v.as_str()) == Some("PRI")
+        })
+        .filter_map(|column| column.get("name").or_else(|| column.get("column_name")).and_then(|n| n.as_str()))
+        .map(str::to_string)
+        .collect();
+    (!keys.is_empty()).then_some(keys)
+}
+
+/// Accept `table_name` if it is a plain (optionally schema-qualified)
+/// identifier or a table in the cached schema
+pub fn validate_table_name(table_name: &str, schema: Option<&Value>) -> Result<(), String> {
+    let parts: Vec<&str> = table_name.split('.').collect();
+    if parts.len() <= 3 && parts.iter().all(|part| is_plain_identifier(part)) {
+        return Ok(());
+    }
+    if schema.is_some_and(|schema| schema_table(schema, table_name).is_some()) {
+        return Ok(());
+    }
+    Err(format!("Invalid table name '{}'", table_name))
+}
+
+/// Accept `column` if it is a plain identifier or a column of `table_name` in
+/// the cached schema
+pub fn validate_column_name(column: &str, table_name: &str, schema: Option<&Value>) -> Result<(), String> {
+    if is_plain_identifier(column) {
+        return Ok(());
+    }
+    let known = schema
+        .and_then(|schema| schema_table(schema, table_name))
+        .is_some_and(|entry| table_columns(entry).contains(&column));
+    if known {
+        return Ok(());
+    }
+    Err(format!("Invalid column name '{}'", column))
+}
+
+/// Escape a value for use inside a single-quoted SQL string literal
+pub fn escape_string_literal(value: &str) -> String {
+    value.replace('\'', "''")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn injection_attempts_are_rejected() {
+        for name in ["col; DROP TABLE users", "name --", "a) OR (1=1", "x'y", "\"id\"", "1col", ""] {
+            assert!(validate_column_name(name, "users", None).is_err(), "{}", name);
+        }
+        for name in ["users; DROP TABLE users", "users --", "public.users;", "a.b.c.d"] {
+            assert!(validate_table_name(name, None).is_err(), "{}", name);
+        }
+
+        assert!(validate_column_name("created_at", "users", None).is_ok());
+        assert!(validate_table_name("public.users", None).is_ok());
+        assert!(validate_table_name("dbo.Order_Items", None).is_ok());
+    }
+
+    #[test]
+    fn names_from_the_cached_schema_are_allowed() {
+        let schema = json!({
+            "tables": {
+                "Order Details": [
+                    { "column_name": "Unit Price", "data_type": "numeric" }
+                ]
+            }
+        });
+
+        assert!(validate_table_name("Order Details", Some(&schema)).is_ok());
+        assert!(validate_column_name("Unit Price", "Order Details", Some(&schema)).is_ok());
+        // Known tables don't vouch for arbitrary columns
+        assert!(validate_column_name("Unit Price; DROP TABLE x", "Order Details", Some(&schema)).is_err());
+        assert!(validate_table_name("Order Details; DROP TABLE x", Some(&schema)).is_err());
+    }
+}
@@ -0,0 +1,489 @@
+//! Validation for metadata-only SQL
+//!
+//! `schema_metadata_query` lets the model read catalog views (information_schema,
+//! pg_catalog, sys.*, system.*, ALL_* ...) without opening the general query
+//! path. Every relation a query reads must belong to the dialect's system
+//! catalogs; anything else, including user tables reached through joins,
+//! subqueries or comma lists, is rejected before the query is sent.
+
+use std::collections::HashSet;
+
+/// System catalogs a dialect exposes, used for validation and tool help text
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MetadataDialect {
+    PostgreSQL,
+    MySQL,
+    SQLite,
+    ClickHouse,
+    SqlServer,
+    Oracle,
+}
+
+impl MetadataDialect {
+    pub fn from_source_type(source_type: &str) -> Option<Self> {
+        match source_type.to_lowercase().as_str() {
+            "postgresql" | "postgres" => Some(MetadataDialect::PostgreSQL),
+            "mysql" => Some(MetadataDialect::MySQL),
+            "sqlite" => Some(MetadataDialect::SQLite),
+            "clickhouse" | "ch" => Some(MetadataDialect::ClickHouse),
+            "sqlserver" | "mssql" | "sql_server" => Some(MetadataDialect::SqlServer),
+            "oracle" => Some(MetadataDialect::Oracle),
+            _ => None,
+        }
+    }
+
+    /// Human-readable list of what may be queried, returned on rejection
+    pub fn catalog_description(&self) -> &'static str {
+        match self {
+            MetadataDialect::PostgreSQL => "information_schema.* and pg_catalog.* (schema-qualified, e.g. pg_catalog.pg_stat_user_tables)",
+            MetadataDialect::MySQL => "information_schema.*, performance_schema.* and sys.*",
+            MetadataDialect::SQLite => "sqlite_master, sqlite_schema, sqlite_stat* and pragma_* table functions",
+            MetadataDialect::ClickHouse => "system.* and information_schema.*",
+            MetadataDialect::SqlServer => "INFORMATION_SCHEMA.* and sys.*",
+            MetadataDialect::Oracle => "SYS.ALL_*, SYS.USER_* and SYS.DBA_* dictionary views",
+        }
+    }
+
+    /// Whether a relation (already unquoted and lowercased) is a system catalog
+    fn is_catalog_relation(&self, relation: &str) -> bool {
+        let parts: Vec<&str> = relation.split('.').collect();
+        let (schema, name) = match parts.as_slice() {
+            [name] => (None, *name),
+            [schema, name] => (Some(*schema), *name),
+            // database.schema.name (SQL Server) - only the schema matters
+            [_, schema, name] => (Some(*schema), *name),
+            _ => return false,
+        };
+
+        match self {
+            // An unqualified pg_* name resolves through search_path and may be a user table
+            MetadataDialect::PostgreSQL => matches!(schema, Some("information_schema") | Some("pg_catalog")),
+            MetadataDialect::MySQL => matches!(
+                schema,
+                Some("information_schema") | Some("performance_schema") | Some("sys")
+            ),
+            MetadataDialect::SQLite => {
+                schema.is_none_or(|s| s == "main" || s == "temp")
+                    && (name == "sqlite_master"
+                        || name == "sqlite_schema"
+                        || name == "sqlite_temp_master"
+                        || name.starts_with("sqlite_stat")
+                        || name.starts_with("pragma_"))
+            }
+            MetadataDialect::ClickHouse => matches!(schema, Some("system") | Some("information_schema")),
+            MetadataDialect::SqlServer => matches!(schema, Some("information_schema") | Some("sys")),
+            // Likewise a user may own a table called USER_ACCOUNTS; only SYS views count
+            MetadataDialect::Oracle => {
+                (schema == Some("sys") || (schema.is_none() && name == "dual"))
+                    && (name.starts_with("all_")
+                        || name.starts_with("user_")
+                        || name.starts_with("dba_")
+                        || name == "dual")
+            }
+        }
+    }
+}
+
+// Functions that read files, reach other servers or have side effects even
+// inside a read-only transaction
+const DENIED_FUNCTIONS: &[&str] = &[
+    "pg_read_file",
+    "pg_read_binary_file",
+    "pg_ls_dir",
+    "pg_stat_file",
+    "pg_sleep",
+    // pg_sleep_for, pg_sleep_until
+    "pg_sleep_",
+    "pg_terminate_backend",
+    "pg_cancel_backend",
+    "lo_import",
+    "lo_export",
+    "dblink",
+    // dblink_exec, dblink_connect, dblink_send_query ... run SQL over a new
+    // connection, outside the read-only transaction
+    "dblink_",
+    "load_file",
+    "sleep",
+    "benchmark",
+    "openrowset",
+    "opendatasource",
+    "openquery",
+    "xp_cmdshell",
+    "utl_http",
+    "utl_file",
+    "dbms_",
+    // Evaluate a query or dump a table passed as text, bypassing the relation check
+    "ts_stat",
+    "lo_get",
+];
+
+// query_to_xml, table_to_xml, schema_to_xmlschema, database_to_xml_and_xmlschema ...
+const DENIED_FUNCTION_SUFFIXES: &[&str] = &["_to_xml", "_to_xmlschema", "_to_xml_and_xmlschema"];
+
+// Data-modifying statements can hide inside a CTE that starts with WITH
+const WRITE_KEYWORDS: &[&str] = &[
+    "insert", "update", "delete", "merge", "upsert", "drop", "alter", "create", "truncate", "grant",
+    "revoke", "copy", "call", "exec", "execute",
+];
+
+const CLAUSE_KEYWORDS: &[&str] = &[
+    "where", "group", "order", "limit", "having", "union", "except", "intersect", "on", "using",
+    "window", "offset", "fetch", "select", "returning", "settings", "format", "qualify", "connect",
+    "start", "for",
+];
+
+const JOIN_PREFIXES: &[&str] = &["left", "right", "inner", "outer", "full", "cross", "natural", "lateral"];
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Word(String),
+    Open,
+    Close,
+    Comma,
+    Semicolon,
+    Literal,
+}
+
+/// Check that `query` is a single read-only statement touching only system catalogs
+pub fn validate_metadata_query(query: &str, dialect: MetadataDialect) -> Result<(), String> {
+    let tokens = tokenize(query)?;
+
+    let first = tokens.iter().find_map(|t| match t {
+        Token::Word(w) => Some(w.as_str()),
+        _ => None,
+    });
+    if !matches!(first, Some("select") | Some("with")) {
+        return Err("Only SELECT queries against system catalogs are allowed".to_string());
+    }
+
+    // A trailing semicolon is fine; anything after it is a second statement
+    if let Some(pos) = tokens.iter().position(|t| *t == Token::Semicolon) {
+        if pos + 1 < tokens.len() {
+            return Err("Only a single statement is allowed".to_string());
+        }
+    }
+
+    let cte_names = cte_names(&tokens);
+
+    for (i, token) in tokens.iter().enumerate() {
+        let Token::Word(word) = token else { continue };
+        if word == "into" {
+            return Err("SELECT ... INTO is not allowed".to_string());
+        }
+        if WRITE_KEYWORDS.contains(&word.as_str()) {
+            return Err(format!("'{}' is not allowed in metadata queries", word.to_uppercase()));
+        }
+        if tokens.get(i + 1) == Some(&Token::Open) {
+            let function = word.rsplit('.').next().unwrap_or(word);
+            if DENIED_FUNCTIONS
+                .iter()
+                .any(|denied| function == *denied || (denied.ends_with('_') && function.starts_with(denied)))
+                || DENIED_FUNCTION_SUFFIXES.iter().any(|suffix| function.ends_with(suffix))
+            {
+                return Err(format!("Function '{}' is not allowed in metadata queries", word));
+            }
+        }
+    }
+
+    for relation in referenced_relations(&tokens) {
+        if cte_names.contains(&relation) {
+            continue;
+        }
+        if !dialect.is_catalog_relation(&relation) {
+            return Err(format!(
+                "'{}' is not a system catalog. Metadata queries may only read {}",
+                relation,
+                dialect.catalog_description()
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+/// Lowercased words with quoting removed; comments dropped, string literals opaque
+fn tokenize(query: &str) -> Result<Vec<Token>, String> {
+    let chars: Vec<char> = query.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        match c {
+            '-' if chars.get(i + 1) == Some(&'-') => {
+                while i < chars.len() && chars[i] != '\n' {
+                    i += 1;
+                }
+            }
+            '/' if chars.get(i + 1) == Some(&'*') => {
+                i += 2;
+                while i < chars.len() && !(chars[i] == '*' && chars.get(i + 1) == Some(&'/')) {
+                    i += 1;
+                }
+                if i >= chars.len() {
+                    return Err("Unterminated comment".to_string());
+                }
+                i += 2;
+            }
+            '\'' => {
+                i += 1;
+                loop {
+                    match chars.get(i) {
+                        None => return Err("Unterminated string literal".to_string()),
+                        Some('\'') if chars.get(i + 1) == Some(&'\'') => i += 2,
+                        Some('\'') => break,
+                        Some('\\') => i += 2,
+                        Some(_) => i += 1,
+                    }
+                }
+                i += 1;
+                tokens.push(Token::Literal);
+            }
+            '(' => {
+                tokens.push(Token::Open);
+                i += 1;
+            }
+            ')' => {
+                tokens.push(Token::Close);
+                i += 1;
+            }
+            ',' => {
+                tokens.push(Token::Comma);
+                i += 1;
+            }
+            ';' => {
+                tokens.push(Token::Semicolon);
+                i += 1;
+            }
+            c if is_word_char(c) || matches!(c, '"' | '`' | '[') => {
+                // A possibly dotted, possibly quoted identifier: "pg_catalog"."pg_class"
+                let mut word = String::new();
+                loop {
+                    match chars.get(i) {
+                        Some(&q @ ('"' | '`' | '[')) => {
+                            let close = if q == '[' { ']' } else { q };
+                            i += 1;
+                            while let Some(&ch) = chars.get(i) {
+                                i += 1;
+                                if ch == close {
+                                    break;
+                                }
+                                word.push(ch);
+                            }
+                        }
+                        Some(&ch) if is_word_char(ch) => {
+                            word.push(ch);
+                            i += 1;
+                        }
+                        _ => break,
+                    }
+                    if chars.get(i) == Some(&'.') {
+                        word.push('.');
+                        i += 1;
+                    } else if !matches!(chars.get(i), Some(&ch) if is_word_char(ch)) {
+                        break;
+                    }
+                }
+                tokens.push(Token::Word(word.to_lowercase()));
+            }
+            _ => i += 1,
+        }
+    }
+
+    Ok(tokens)
+}
+
+fn is_word_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_' || c == '$'
+}
+
+/// Names defined by `name AS (` in a WITH clause
+fn cte_names(tokens: &[Token]) -> HashSet<String> {
+    tokens
+        .windows(3)
+        .filter_map(|window| match window {
+            [Token::Word(name), Token::Word(as_kw), Token::Open] if as_kw == "as" => Some(name.clone()),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Every relation named after FROM or JOIN, including comma-separated FROM lists,
+/// at any nesting depth
+fn referenced_relations(tokens: &[Token]) -> Vec<String> {
+    let mut relations = Vec::new();
+    // Per parenthesis depth: are we inside a FROM clause?
+    let mut in_from = vec![false];
+    let mut expecting_relation = false;
+
+    for token in tokens {
+        match token {
+            Token::Open => {
+                in_from.push(false);
+                expecting_relation = false;
+            }
+            Token::Close => {
+                if in_from.len() > 1 {
+                    in_from.pop();
+                }
+                expecting_relation = false;
+            }
+            Token::Comma => {
+                if *in_from.last().unwrap_or(&false) {
+                    expecting_relation = true;
+                }
+            }
+            Token::Word(word) => {
+                // The stack always keeps its outermost entry
+                let Some(depth_in_from) = in_from.last_mut() else { continue };
+                if word == "from" || word == "join" {
+                    *depth_in_from = true;
+                    expecting_relation = true;
+                } else if expecting_relation {
+                    if word == "only" || JOIN_PREFIXES.contains(&word.as_str()) {
+                        continue;
+                    }
+                    relations.push(word.clone());
+                    expecting_relation = false;
+                } else if CLAUSE_KEYWORDS.contains(&word.as_str()) {
+                    *depth_in_from = false;
+                }
+            }
+            Token::Semicolon | Token::Literal => expecting_relation = false,
+        }
+    }
+
+    relations
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pg(query: &str) -> Result<(), String> {
+        validate_metadata_query(query, MetadataDialect::PostgreSQL)
+    }
+
+    #[test]
+    fn allows_catalog_queries_per_dialect() {
+        assert!(pg("SELECT relname, n_live_tup, last_analyze FROM pg_catalog.pg_stat_user_tables ORDER BY n_live_tup DESC").is_ok());
+        assert!(pg("SELECT table_name FROM \"information_schema\".\"tables\" t JOIN pg_catalog.pg_class c ON c.relname = t.table_name").is_ok());
+        assert!(pg("WITH sizes AS (SELECT relname FROM pg_catalog.pg_class) SELECT * FROM sizes;").is_ok());
+        assert!(validate_metadata_query(
+            "SELECT table_name, table_rows, data_length FROM information_schema.TABLES WHERE table_schema = DATABASE()",
+            MetadataDialect::MySQL
+        )
+        .is_ok());
+        assert!(validate_metadata_query(
+            "SELECT name, total_rows FROM system.tables WHERE database = currentDatabase()",
+            MetadataDialect::ClickHouse
+        )
+        .is_ok());
+        assert!(validate_metadata_query(
+            "SELECT t.name, p.rows FROM [sys].[tables] t JOIN sys.partitions p ON p.object_id = t.object_id",
+            MetadataDialect::SqlServer
+        )
+        .is_ok());
+        assert!(validate_metadata_query(
+            "SELECT name FROM sqlite_master, pragma_table_info(sqlite_master.name)",
+            MetadataDialect::SQLite
+        )
+        .is_ok());
+        assert!(validate_metadata_query(
+            "SELECT table_name, last_analyzed FROM SYS.ALL_TABLES WHERE owner = 'APP'",
+            MetadataDialect::Oracle
+        )
+        .is_ok());
+    }
+
+    #[test]
+    fn rejects_user_tables_however_they_are_reached() {
+        for query in [
+            "SELECT * FROM users",
+            "SELECT * FROM public.orders",
+            "SELECT * FROM information_schema.tables, users",
+            "SELECT * FROM pg_class JOIN customers ON true",
+            "SELECT * FROM (SELECT * FROM orders) o",
+            "SELECT * FROM (SELECT 1) x, orders",
+            "SELECT (SELECT count(*) FROM orders) FROM pg_class",
+            "SELECT * FROM pg_class WHERE relname IN (SELECT name FROM accounts)",
+            "SELECT * FROM pg_class/**/, orders",
+            "SELECT * FROM pg_class LEFT OUTER JOIN LATERAL orders ON true",
+        ] {
+            assert!(pg(query).is_err(), "accepted: {}", query);
+        }
+    }
+
+    #[test]
+    fn rejects_writes_side_effects_and_multiple_statements() {
+        assert!(pg("DELETE FROM pg_class").is_err());
+        assert!(pg("SELECT * INTO snapshot FROM pg_class").is_err());
+        assert!(pg("WITH gone AS (DELETE FROM pg_class RETURNING *) SELECT * FROM gone").is_err());
+        assert!(pg("SELECT 1 FROM pg_class; DROP TABLE orders").is_err());
+        assert!(pg("SELECT pg_read_file('/etc/passwd')").is_err());
+        assert!(validate_metadata_query("SELECT LOAD_FILE('/etc/passwd')", MetadataDialect::MySQL).is_err());
+        assert!(validate_metadata_query("SELECT * FROM mysql.user", MetadataDialect::MySQL).is_err());
+        assert!(validate_metadata_query(
+            "SELECT * FROM OPENROWSET('SQLNCLI', 'Server=x', 'SELECT 1')",
+            MetadataDialect::SqlServer
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn rejects_every_dblink_and_sleep_variant() {
+        for query in [
+            "SELECT dblink('dbname=app', 'SELECT 1')",
+            "SELECT dblink_exec('dbname=app', 'DELETE FROM orders')",
+            "SELECT dblink_connect('other', 'dbname=app')",
+            "SELECT dblink_send_query('other', 'DELETE FROM orders')",
+            "SELECT dblink_get_result('other')",
+            "SELECT public.dblink_exec('dbname=app', 'DROP TABLE orders')",
+            "SELECT pg_sleep(10)",
+            "SELECT pg_sleep_for('5 minutes')",
+            "SELECT pg_catalog.pg_sleep_until('tomorrow')",
+        ] {
+            assert!(pg(query).is_err(), "accepted: {}", query);
+        }
+    }
+
+    #[test]
+    fn literals_and_comments_do_not_confuse_the_parser() {
+        assert!(pg("SELECT 'FROM users' AS note FROM pg_catalog.pg_class -- FROM users").is_ok());
+        assert!(pg("SELECT * FROM pg_catalog.pg_class WHERE relname = 'it''s'").is_ok());
+        assert!(pg("SELECT * FROM pg_class WHERE relname = 'x").is_err());
+    }
+
+    #[test]
+    fn rejects_functions_that_read_tables_from_text() {
+        for query in [
+            "SELECT query_to_xml('select * from users', true, false, '')",
+            "SELECT pg_catalog.query_to_xml('select * from users', true, false, '')",
+            "SELECT table_to_xml('users', true, false, '')",
+            "SELECT cursor_to_xml('c', 10, true, false, '')",
+            "SELECT schema_to_xml('public', true, false, '')",
+            "SELECT database_to_xml(true, false, '')",
+            "SELECT query_to_xmlschema('select * from users', true, false, '')",
+            "SELECT table_to_xml_and_xmlschema('users', true, false, '')",
+            "SELECT * FROM ts_stat('select body from posts')",
+            "SELECT relname, query_to_xml('select 1', true, false, '') FROM pg_catalog.pg_class",
+        ] {
+            assert!(pg(query).is_err(), "accepted: {}", query);
+        }
+    }
+
+    #[test]
+    fn requires_schema_qualified_catalog_names() {
+        // A user table may be called pg_anything; only the catalog schemas are trusted
+        assert!(pg("SELECT * FROM pg_class").is_err());
+        assert!(pg("SELECT * FROM pg_user_secrets").is_err());
+        assert!(pg("SELECT * FROM public.pg_stat_user_tables").is_err());
+        assert!(pg("SELECT * FROM \"pg_catalog\".\"pg_class\"").is_ok());
+        assert!(pg("SELECT * FROM information_schema.columns").is_ok());
+
+        let oracle = |q: &str| validate_metadata_query(q, MetadataDialect::Oracle);
+        assert!(oracle("SELECT * FROM user_accounts").is_err());
+        assert!(oracle("SELECT * FROM SYS.USER_TABLES").is_ok());
+        assert!(oracle("SELECT 1 FROM DUAL").is_ok());
+    }
+}
@@ -1,6 +1,16 @@
 // Common utilities for database connectors
 pub mod connection_config;
+pub mod diagnostics;
+pub mod metadata_query;
 pub mod pool_manager;
 pub mod error_handling;
+pub mod identifiers;
 pub mod query_builder;
+pub mod read_replica;
+pub mod projection;
+pub mod redaction;
+pub mod result_budget;
+pub mod row_mutations;
+pub mod schema_shape;
+pub mod timeouts;
 
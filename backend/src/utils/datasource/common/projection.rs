@@ -0,0 +1,219 @@
+use serde_json::Value;
+
+use crate::utils::datasource::connectors::common::{quote_identifier, IdentifierQuote};
+
+/// Default number of columns returned for wide results when no projection is requested
+const DEFAULT_MAX_RESULT_COLUMNS: usize = 100;
+
+/// Read the wide-result column cap from `MAX_RESULT_COLUMNS`
+pub fn max_result_columns_from_env() -> usize {
+    std::env::var("MAX_RESULT_COLUMNS")
+        .ok()
+        .and_then(|v| v.parse::<usize>().ok())
+        .filter(|v| *v > 0)
+        .unwrap_or(DEFAULT_MAX_RESULT_COLUMNS)
+}
+
+/// Which columns a caller wants: the `requested` ones, or else at most the
+/// first `max_columns`
+#[derive(Debug, Clone, Copy)]
+pub struct ColumnRequest<'a> {
+    pub requested: Option<&'a [String]>,
+    pub max_columns: usize,
+}
+
+/// The columns chosen for a result out of its `total_columns`
+#[derive(Debug, Clone, PartialEq)]
+pub struct Projection {
+    pub columns: Vec<String>,
+    pub total_columns: usize,
+    /// Wider than the column cap and cut to its first columns
+    pub truncated: bool,
+    /// Every available column, in its original order
+    pub selects_all: bool,
+}
+
+impl ColumnRequest<'_> {
+    /// Choose from `available`. Explicitly requested columns must all exist,
+    /// unless nothing is known about the columns (an empty result may carry no
+    /// column metadata to validate against).
+    pub fn plan(&self, available: &[String]) -> Result<Projection, String> {
+        let total_columns = available.len();
+        let (columns, truncated) = match self.requested.filter(|r| !r.is_empty()) {
+            Some(requested) => {
+                let unknown: Vec<&str> = requested
+                    .iter()
+                    .filter(|name| !available.contains(name))
+                    .map(|name| name.as_str())
+                    .collect();
+                if !unknown.is_empty() && total_columns > 0 {
+                    return Err(format!("Unknown column(s): {}", unknown.join(", ")));
+                }
+                let columns = requested.iter().filter(|name| available.contains(name)).cloned().collect();
+                (columns, false)
+            }
+            None if total_columns > self.max_columns => (available[..self.max_columns].to_vec(), true),
+            None => (available.to_vec(), false),
+        };
+
+        Ok(Projection {
+            selects_all: columns == available,
+            columns,
+            total_columns,
+            truncated,
+        })
+    }
+}
+
+impl Projection {
+    /// `query` as a subquery with only the chosen columns selected from it
+    pub fn subquery(&self, query: &str, quote: IdentifierQuote) -> String {
+        let select_list: Vec<String> = self.columns.iter().map(|c| quote_identifier(c, quote)).collect();
+        // No AS before the alias: Oracle rejects it
+        format!(
+            "SELECT {} FROM ({}) projected_result",
+            select_list.join(", "),
+            query.trim().trim_end_matches(';')
+        )
+    }
+
+    /// Record `total_columns` on a projected result, and a `note` explaining
+    /// how to see the rest when it was truncated
+    pub fn annotate(&self, result: &mut Value) {
+        let Some(obj) = result.as_object_mut() else {
+            return;
+        };
+        if self.truncated {
+            obj.insert("columns_truncated".to_string(), Value::Bool(true));
+            obj.insert(
+                "note".to_string(),
+                Value::String(format!(
+                    "Showing the first {} of {} columns. Request specific columns to see the rest.",
+                    self.columns.len(),
+                    self.total_columns
+                )),
+            );
+        }
+        obj.insert("total_columns".to_string(), Value::from(self.total_columns));
+    }
+}
+
+/// Narrow an already fetched `{columns, rows}` result to a subset of its
+/// columns, for sources that can't select columns in the query itself.
+///
+/// Explicitly `requested` columns must all exist in the result. Without a request,
+/// results wider than `max_columns` keep only the first `max_columns` columns and
+/// get a `note` explaining how to ask for others. `total_columns` is always set.
+pub fn project_result_columns(
+    result: &mut Value,
+    requested: Option<&[String]>,
+    max_columns: usize,
+) -> Result<(), String> {
+    let Some(obj) = result.as_object_mut() else {
+        return Ok(());
+    };
+
+    let columns: Vec<String> = obj
+        .get("columns")
+        .and_then(|c| c.as_array())
+        .map(|cols| cols.iter().filter_map(|c| c.as_str()).map(|s| s.to_string()).collect())
+        .unwrap_or_default();
+    let projection = ColumnRequest { requested, max_columns }.plan(&columns)?;
+
+    if !projection.selects_all {
+        let keep: Vec<usize> = projection
+            .columns
+            .iter()
+            .filter_map(|name| columns.iter().position(|c| c == name))
+            .collect();
+
+        if let Some(rows) = obj.get_mut("rows").and_then(|r| r.as_array_mut()) {
+            for row in rows.iter_mut() {
+                match row {
+                    Value::Array(values) => {
+                        *values = keep
+                            .iter()
+                            .map(|&i| values.get(i).cloned().unwrap_or(Value::Null))
+                            .collect();
+                    }
+                    Value::Object(record) => {
+                        record.retain(|key, _| projection.columns.contains(key));
+                    }
+                    _ => {}
+                }
+            }
+        }
+        obj.insert("columns".to_string(), Value::from(projection.columns.clone()));
+    }
+
+    projection.annotate(result);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_wide_result_is_truncated() {
+        let mut result = json!({
+            "columns": ["a", "b", "c"],
+            "rows": [["1", "2", "3"]]
+        });
+        project_result_columns(&mut result, None, 2).unwrap();
+        assert_eq!(result["columns"], json!(["a", "b"]));
+        assert_eq!(result["rows"], json!([["1", "2"]]));
+        assert_eq!(result["total_columns"], json!(3));
+        assert_eq!(result["columns_truncated"], json!(true));
+    }
+
+    #[test]
+    fn test_requested_columns_are_projected_in_order() {
+        let mut result = json!({
+            "columns": ["a", "b", "c"],
+            "rows": [["1", "2", "3"]]
+        });
+        let requested = vec!["c".to_string(), "a".to_string()];
+        project_result_columns(&mut result, Some(&requested), 2).unwrap();
+        assert_eq!(result["columns"], json!(["c", "a"]));
+        assert_eq!(result["rows"], json!([["3", "1"]]));
+        assert!(result.get("note").is_none());
+    }
+
+    #[test]
+    fn test_unknown_requested_column_is_rejected() {
+        let mut result = json!({
+            "columns": ["a", "b"],
+            "rows": []
+        });
+        let requested = vec!["missing".to_string()];
+        assert!(project_result_columns(&mut result, Some(&requested), 10).is_err());
+    }
+
+    #[test]
+    fn test_plan_picks_columns_to_select_in_sql() {
+        let available: Vec<String> = ["id", "name", "email"].iter().map(|c| c.to_string()).collect();
+
+        let wide = ColumnRequest { requested: None, max_columns: 2 }.plan(&available).unwrap();
+        assert_eq!(wide.columns, vec!["id", "name"]);
+        assert!(wide.truncated && !wide.selects_all);
+        assert_eq!(
+            wide.subquery("SELECT * FROM users;", IdentifierQuote::DoubleQuote),
+            r#"SELECT "id", "name" FROM (SELECT * FROM users) projected_result"#
+        );
+
+        let narrow = ColumnRequest { requested: None, max_columns: 10 }.plan(&available).unwrap();
+        assert!(narrow.selects_all && !narrow.truncated);
+
+        let requested = vec!["email".to_string()];
+        let picked = ColumnRequest { requested: Some(&requested), max_columns: 1 }.plan(&available).unwrap();
+        assert_eq!(picked.columns, vec!["email"]);
+        assert!(!picked.truncated);
+
+        let mut result = json!({ "columns": ["id", "name"], "rows": [] });
+        wide.annotate(&mut result);
+        assert_eq!(result["total_columns"], json!(3));
+        assert_eq!(result["columns_truncated"], json!(true));
+    }
+}
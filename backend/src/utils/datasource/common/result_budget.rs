@@ -0,0 +1,164 @@
+//! Row and byte caps on query results
+//!
+//! Defaults come from `MAX_RESULT_ROWS` and `MAX_RESULT_BYTES`; a datasource
+//! can override either with `max_result_rows` / `max_result_bytes` in its
+//! connection config. The row cap is pushed down as the query's limit (plus
+//! one row, to tell a result that fits from one that was cut), so the
+//! database stops producing rows once it is reached. The byte cap is measured
+//! on the serialized rows. A result that hits either cap keeps the rows that
+//! fit and is marked `truncated: true`, naming the cap in `truncated_by`.
+
+use serde_json::Value;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ResultBudget {
+    pub max_rows: usize,
+    pub max_bytes: usize,
+}
+
+impl Default for ResultBudget {
+    fn default() -> Self {
+        Self {
+            max_rows: 100_000,
+            max_bytes: 64 * 1024 * 1024,
+        }
+    }
+}
+
+/// The cap a result ran into
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BudgetCap {
+    Rows(usize),
+    Bytes(usize),
+}
+
+impl BudgetCap {
+    pub fn name(&self) -> &'static str {
+        match self {
+            BudgetCap::Rows(_) => "max_result_rows",
+            BudgetCap::Bytes(_) => "max_result_bytes",
+        }
+    }
+
+    pub fn limit(&self) -> usize {
+        match self {
+            BudgetCap::Rows(limit) | BudgetCap::Bytes(limit) => *limit,
+        }
+    }
+}
+
+impl ResultBudget {
+    pub fn from_env() -> Self {
+        let defaults = Self::default();
+        let env_usize = |name: &str| {
+            std::env::var(name)
+                .ok()
+                .and_then(|v| v.parse::<usize>().ok())
+                .filter(|v| *v > 0)
+        };
+
+        Self {
+            max_rows: env_usize("MAX_RESULT_ROWS").unwrap_or(defaults.max_rows),
+            max_bytes: env_usize("MAX_RESULT_BYTES").unwrap_or(defaults.max_bytes),
+        }
+    }
+
+    /// This budget with the datasource's own overrides applied
+    pub fn for_datasource(&self, connection_config: &Value) -> Self {
+        let cap = |key: &str| {
+            connection_config
+                .get(key)
+                .and_then(|v| v.as_u64().or_else(|| v.as_str().and_then(|s| s.parse().ok())))
+                .filter(|cap| *cap > 0)
+                .map(|cap| cap as usize)
+        };
+
+        Self {
+            max_rows: cap("max_result_rows").unwrap_or(self.max_rows),
+            max_bytes: cap("max_result_bytes").unwrap_or(self.max_bytes),
+        }
+    }
+
+    /// Row limit to run the query with: the requested limit, or one row past
+    /// the cap when nothing smaller was asked for
+    pub fn fetch_limit(&self, requested: Option<i32>) -> i32 {
+        let cap = i32::try_from(self.max_rows.saturating_add(1)).unwrap_or(i32::MAX);
+        requested.filter(|limit| *limit > 0).map_or(cap, |limit| limit.min(cap))
+    }
+
+    /// Cut a `{rows}` result down to this budget. Sets `truncated`, and when a
+    /// cap was hit `truncated_by` and the cap itself; `row_count` is updated
+    /// to the rows kept.
+    pub fn apply(&self, result: &mut Value) -> Option<BudgetCap> {
+        let obj = result.as_object_mut()?;
+        let rows = obj.get_mut("rows").and_then(|r| r.as_array_mut())?;
+
+        let mut hit = None;
+        let mut bytes = 0usize;
+        let mut keep = rows.len().min(self.max_rows);
+        if rows.len() > self.max_rows {
+            hit = Some(BudgetCap::Rows(self.max_rows));
+        }
+        for (i, row) in rows.iter().take(keep).enumerate() {
+            bytes += serde_json::to_string(row).map(|s| s.len()).unwrap_or(0);
+            if bytes > self.max_bytes {
+                keep = i;
+                hit = Some(BudgetCap::Bytes(self.max_bytes));
+                break;
+            }
+        }
+        rows.truncate(keep);
+
+        obj.insert("truncated".to_string(), Value::Bool(hit.is_some()));
+        if let Some(cap) = hit {
+            obj.insert("row_count".to_string(), Value::from(keep));
+            obj.insert("truncated_by".to_string(), Value::String(cap.name().to_string()));
+            obj.insert(cap.name().to_string(), Value::from(cap.limit()));
+        }
+        hit
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn rows(count: usize) -> Value {
+        let rows: Vec<Value> = (0..count).map(|i| json!([i, format!("row {}", i)])).collect();
+        json!({ "columns": ["id", "label"], "rows": rows, "row_count": count })
+    }
+
+    #[test]
+    fn results_past_the_row_cap_are_truncated_and_flagged() {
+        let budget = ResultBudget::default().for_datasource(&json!({ "max_result_rows": "3" }));
+        assert_eq!(budget.max_rows, 3);
+        // One extra row is fetched so a cut result can be told from one that fits
+        assert_eq!(budget.fetch_limit(None), 4);
+        assert_eq!(budget.fetch_limit(Some(2)), 2);
+
+        let mut result = rows(budget.fetch_limit(Some(1000)) as usize);
+        assert_eq!(budget.apply(&mut result), Some(BudgetCap::Rows(3)));
+        assert_eq!(result["rows"].as_array().unwrap().len(), 3);
+        assert_eq!(result["row_count"], json!(3));
+        assert_eq!(result["truncated"], json!(true));
+        assert_eq!(result["truncated_by"], json!("max_result_rows"));
+        assert_eq!(result["max_result_rows"], json!(3));
+
+        let mut fits = rows(3);
+        assert_eq!(budget.apply(&mut fits), None);
+        assert_eq!(fits["truncated"], json!(false));
+        assert!(fits.get("truncated_by").is_none());
+    }
+
+    #[test]
+    fn results_past_the_byte_cap_keep_the_rows_that_fit() {
+        // Each row serializes to 11 bytes: [0,"row 0"]
+        let budget = ResultBudget { max_rows: 100, max_bytes: 25 };
+        let mut result = rows(5);
+        assert_eq!(budget.apply(&mut result), Some(BudgetCap::Bytes(25)));
+        assert_eq!(result["rows"], json!([[0, "row 0"], [1, "row 1"]]));
+        assert_eq!(result["truncated_by"], json!("max_result_bytes"));
+        assert_eq!(result["max_result_bytes"], json!(25));
+    }
+}
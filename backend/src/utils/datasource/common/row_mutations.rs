@@ -0,0 +1,728 @@
+//! Row edits from the table browser: insert, update and delete
+//!
+//! Each edited row becomes one parameterized statement. By default the
+//! statements run in a single transaction that is rolled back as soon as one
+//! of them fails, so a batch is applied completely or not at all. Best-effort
+//! mode runs them one by one and reports the rows that failed.
+//!
+//! Inserts can skip or update rows whose key already exists instead of
+//! failing on them (`on_conflict`). Those rows are told apart from new ones
+//! by looking the key up just before the insert.
+//!
+//! A dry run executes deletes and updates in a transaction that is always
+//! rolled back, so the affected row count can be shown before confirming.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::error::Error;
+
+use crate::utils::datasource::common::identifiers::escape_string_literal;
+use crate::utils::datasource::connectors::common::{quote_identifier, IdentifierQuote};
+use crate::utils::datasource::pooling::DatabasePool;
+
+const DEFAULT_ID_COLUMN: &str = "id";
+
+/// SQL databases whose row edits run through sqlx
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MutationDialect {
+    Postgres,
+    MySql,
+    Sqlite,
+}
+
+impl MutationDialect {
+    pub fn from_source_type(source_type: &str) -> Option<Self> {
+        match source_type {
+            "postgresql" => Some(Self::Postgres),
+            "mysql" => Some(Self::MySql),
+            "sqlite" => Some(Self::Sqlite),
+            _ => None,
+        }
+    }
+
+    fn quote(self, name: &str) -> String {
+        let quote = match self {
+            Self::MySql => IdentifierQuote::Backtick,
+            Self::Postgres | Self::Sqlite => IdentifierQuote::DoubleQuote,
+        };
+        quote_identifier(name, quote)
+    }
+
+    fn placeholder(self, n: usize) -> String {
+        match self {
+            Self::Postgres => format!("${}", n),
+            Self::MySql | Self::Sqlite => "?".to_string(),
+        }
+    }
+
+    /// SQL for a value: NULL inline, since a typed NULL parameter may not
+    /// match the column's type, anything else as the next placeholder
+    fn value_sql(self, value: &Value, params: &mut Vec<Value>) -> String {
+        if value.is_null() {
+            return "NULL".to_string();
+        }
+        params.push(value.clone());
+        self.placeholder(params.len())
+    }
+
+    /// Row ids arrive as strings, so compare them with the key as text
+    fn id_condition(self, id_column: &str, n: usize) -> String {
+        match self {
+            Self::Postgres => format!("{}::text = {}", self.quote(id_column), self.placeholder(n)),
+            Self::MySql | Self::Sqlite => format!("{} = {}", self.quote(id_column), self.placeholder(n)),
+        }
+    }
+}
+
+/// One row's statement and the values bound to its placeholders
+#[derive(Debug, Clone, PartialEq)]
+pub struct RowStatement {
+    pub sql: String,
+    pub params: Vec<Value>,
+    /// Set for inserts, whose rows are counted as inserted, updated or skipped
+    pub insert: Option<InsertCheck>,
+}
+
+/// How to classify the row of an insert statement
+#[derive(Debug, Clone, PartialEq)]
+pub struct InsertCheck {
+    /// Looks up the existing row the insert would conflict with
+    pub probe: Option<Box<RowStatement>>,
+    /// Whether a conflicting row gets updated rather than left alone
+    pub updates_existing: bool,
+}
+
+/// What an insert does with a row whose key already exists
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OnConflict {
+    /// Fail the row
+    #[default]
+    Error,
+    /// Keep the existing row
+    Ignore,
+    /// Overwrite the existing row's other columns
+    Update,
+}
+
+/// Check the conflict target of an insert. `update` needs one; when the
+/// cached schema knows the table's primary key the target must be exactly
+/// that key, and every row must carry a value for each target column.
+pub fn validate_conflict_target(
+    on_conflict: OnConflict,
+    target: &[String],
+    primary_keys: Option<&[String]>,
+    rows: &[HashMap<String, Value>],
+) -> Result<(), String> {
+    if on_conflict == OnConflict::Update && target.is_empty() {
+        return Err("on_conflict 'update' requires conflict_columns".to_string());
+    }
+    if on_conflict == OnConflict::Error || target.is_empty() {
+        return Ok(());
+    }
+
+    if let Some(primary_keys) = primary_keys.filter(|keys| !keys.is_empty()) {
+        let mut wanted: Vec<&String> = target.iter().collect();
+        let mut keys: Vec<&String> = primary_keys.iter().collect();
+        wanted.sort();
+        wanted.dedup();
+        keys.sort();
+        if wanted != keys {
+            return Err(format!(
+                "conflict_columns must be the table's primary key ({})",
+                primary_keys.join(", ")
+            ));
+        }
+    }
+
+    if let Some(index) = rows.iter().position(|row| target.iter().any(|column| !row.contains_key(column))) {
+        return Err(format!("Row {} is missing a value for the conflict columns", index));
+    }
+    Ok(())
+}
+
+pub fn delete_statements(
+    dialect: MutationDialect,
+    table_name: &str,
+    id_column: Option<&str>,
+    row_ids: &[String],
+) -> Vec<RowStatement> {
+    let id_column = id_column.unwrap_or(DEFAULT_ID_COLUMN);
+    row_ids
+        .iter()
+        .map(|row_id| RowStatement {
+            sql: format!(
+                "DELETE FROM {} WHERE {}",
+                dialect.quote(table_name),
+                dialect.id_condition(id_column, 1)
+            ),
+            params: vec![Value::String(row_id.clone())],
+            insert: None,
+        })
+        .collect()
+}
+
+/// One UPDATE per row id, in row id order; rows without changes are skipped
+pub fn update_statements(
+    dialect: MutationDialect,
+    table_name: &str,
+    id_column: Option<&str>,
+    updates: &HashMap<String, HashMap<String, Value>>,
+) -> Vec<RowStatement> {
+    let id_column = id_column.unwrap_or(DEFAULT_ID_COLUMN);
+    let mut row_ids: Vec<&String> = updates.keys().collect();
+    row_ids.sort();
+
+    row_ids
+        .into_iter()
+        .filter_map(|row_id| {
+            let changes = sorted_entries(&updates[row_id]);
+            if changes.is_empty() {
+                return None;
+            }
+            let mut params = Vec::new();
+            let assignments: Vec<String> = changes
+                .into_iter()
+                .map(|(column, value)| format!("{} = {}", dialect.quote(column), dialect.value_sql(value, &mut params)))
+                .collect();
+            params.push(Value::String(row_id.clone()));
+
+            Some(RowStatement {
+                sql: format!(
+                    "UPDATE {} SET {} WHERE {}",
+                    dialect.quote(table_name),
+                    assignments.join(", "),
+                    dialect.id_condition(id_column, params.len())
+                ),
+                params,
+                insert: None,
+            })
+        })
+        .collect()
+}
+
+/// One INSERT per row, in request order. With `on_conflict` other than
+/// `error`, rows colliding on `conflict_target` (or, without a target, on
+/// any unique key) are skipped or update the existing row.
+pub fn insert_statements(
+    dialect: MutationDialect,
+    table_name: &str,
+    rows: &[HashMap<String, Value>],
+    on_conflict: OnConflict,
+    conflict_target: &[String],
+) -> Vec<RowStatement> {
+    rows.iter()
+        .map(|row| {
+            let values = sorted_entries(row);
+            if values.is_empty() {
+                return RowStatement {
+                    sql: format!("INSERT INTO {} DEFAULT VALUES", dialect.quote(table_name)),
+                    params: Vec::new(),
+                    insert: Some(InsertCheck { probe: None, updates_existing: false }),
+                };
+            }
+            let columns: Vec<&String> = values.iter().map(|(column, _)| *column).collect();
+            let mut params = Vec::new();
+            let placeholders: Vec<String> = values
+                .iter()
+                .map(|(_, value)| dialect.value_sql(value, &mut params))
+                .collect();
+            let mut sql = format!(
+                "INSERT INTO {} ({}) VALUES ({})",
+                dialect.quote(table_name),
+                columns.iter().map(|column| dialect.quote(column)).collect::<Vec<_>>().join(", "),
+                placeholders.join(", ")
+            );
+            if on_conflict != OnConflict::Error {
+                sql.push_str(&conflict_clause(dialect, &columns, on_conflict, conflict_target));
+            }
+
+            let probe = (on_conflict != OnConflict::Error && !conflict_target.is_empty())
+                .then(|| Box::new(existing_row_probe(dialect, table_name, row, conflict_target)));
+            RowStatement {
+                sql,
+                params,
+                insert: Some(InsertCheck {
+                    probe,
+                    updates_existing: on_conflict == OnConflict::Update,
+                }),
+            }
+        })
+        .collect()
+}
+
+/// The upsert clause appended to an INSERT of `columns`
+fn conflict_clause(dialect: MutationDialect, columns: &[&String], on_conflict: OnConflict, target: &[String]) -> String {
+    let updated: Vec<&&String> = match on_conflict {
+        OnConflict::Update => columns.iter().filter(|column| !target.contains(**column)).collect(),
+        _ => Vec::new(),
+    };
+
+    match dialect {
+        // MySQL has no conflict target: any unique key triggers the update.
+        // A no-op assignment stands in for DO NOTHING.
+        MutationDialect::MySql => {
+            let assignments: Vec<String> = if updated.is_empty() {
+                let column = dialect.quote(target.first().unwrap_or(columns[0]));
+                vec![format!("{} = {}", column, column)]
+            } else {
+                updated
+                    .iter()
+                    .map(|column| format!("{} = VALUES({})", dialect.quote(column), dialect.quote(column)))
+                    .collect()
+            };
+            format!(" ON DUPLICATE KEY UPDATE {}", assignments.join(", "))
+        }
+        MutationDialect::Postgres | MutationDialect::Sqlite => {
+            let target_sql = if target.is_empty() {
+                String::new()
+            } else {
+                format!(" ({})", target.iter().map(|column| dialect.quote(column)).collect::<Vec<_>>().join(", "))
+            };
+            if updated.is_empty() {
+                format!(" ON CONFLICT{} DO NOTHING", target_sql)
+            } else {
+                let assignments: Vec<String> = updated
+                    .iter()
+                    .map(|column| format!("{} = excluded.{}", dialect.quote(column), dialect.quote(column)))
+                    .collect();
+                format!(" ON CONFLICT{} DO UPDATE SET {}", target_sql, assignments.join(", "))
+            }
+        }
+    }
+}
+
+/// SELECT finding the row with the same `target` values as `row`
+fn existing_row_probe(
+    dialect: MutationDialect,
+    table_name: &str,
+    row: &HashMap<String, Value>,
+    target: &[String],
+) -> RowStatement {
+    let mut params = Vec::new();
+    let conditions: Vec<String> = target
+        .iter()
+        .map(|column| {
+            let value = row.get(column).unwrap_or(&Value::Null);
+            format!("{} = {}", dialect.quote(column), dialect.value_sql(value, &mut params))
+        })
+        .collect();
+    RowStatement {
+        sql: format!(
+            "SELECT 1 FROM {} WHERE {} LIMIT 1",
+            dialect.quote(table_name),
+            conditions.join(" AND ")
+        ),
+        params,
+        insert: None,
+    }
+}
+
+fn sorted_entries(values: &HashMap<String, Value>) -> Vec<(&String, &Value)> {
+    let mut entries: Vec<(&String, &Value)> = values.iter().collect();
+    entries.sort_by(|a, b| a.0.cmp(b.0));
+    entries
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct RowFailure {
+    /// Position of the failing statement: request order for inserts and
+    /// deletes, row id order for updates
+    pub row_index: usize,
+    pub error: String,
+}
+
+/// What happened to the rows of an insert
+#[derive(Debug, Clone, Default, Serialize, PartialEq)]
+pub struct InsertCounts {
+    pub inserted: u64,
+    pub updated: u64,
+    pub skipped: u64,
+}
+
+impl InsertCounts {
+    fn record(&mut self, check: &InsertCheck, existed: bool, rows_affected: u64) {
+        if rows_affected == 0 || (existed && !check.updates_existing) {
+            self.skipped += 1;
+        } else if existed {
+            self.updated += 1;
+        } else {
+            self.inserted += 1;
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, PartialEq)]
+pub struct MutationOutcome {
+    pub success: bool,
+    pub atomic: bool,
+    /// Whether any changes were kept; false after an atomic rollback or a dry run
+    pub committed: bool,
+    /// Rolled back on purpose; `rows_affected` is what the change would affect
+    pub dry_run: bool,
+    pub rows_affected: u64,
+    pub failures: Vec<RowFailure>,
+    /// Set for inserts
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub counts: Option<InsertCounts>,
+}
+
+macro_rules! bind_params {
+    ($query:expr, $params:expr) => {{
+        let mut query = $query;
+        for param in $params {
+            query = match param {
+                Value::Null => query.bind(None::<String>),
+                Value::Bool(b) => query.bind(*b),
+                Value::Number(n) => match n.as_i64() {
+                    Some(i) => query.bind(i),
+                    None => query.bind(n.as_f64()),
+                },
+                Value::String(s) => query.bind(s.clone()),
+                other => query.bind(other.to_string()),
+            };
+        }
+        query
+    }};
+}
+
+/// Run one statement on `$executor`, probing for an existing row first when
+/// it is an insert that may conflict, and add its effect to `$outcome`
+macro_rules! run_statement {
+    ($executor:expr, $statement:expr, $outcome:expr) => {{
+        let existed = match $statement.insert.as_ref().and_then(|check| check.probe.as_ref()) {
+            Some(probe) => bind_params!(sqlx::query(&probe.sql), &probe.params)
+                .fetch_optional($executor)
+                .await
+                .map(|row| row.is_some()),
+            None => Ok(false),
+        };
+        match existed {
+            Ok(existed) => bind_params!(sqlx::query(&$statement.sql), &$statement.params)
+                .execute($executor)
+                .await
+                .map(|done| {
+                    $outcome.rows_affected += done.rows_affected();
+                    if let Some(check) = &$statement.insert {
+                        $outcome
+                            .counts
+                            .get_or_insert_with(InsertCounts::default)
+                            .record(check, existed, done.rows_affected());
+                    }
+                }),
+            Err(e) => Err(e),
+        }
+    }};
+}
+
+macro_rules! run_statements {
+    ($pool:expr, $statements:expr, $atomic:expr, $dry_run:expr) => {{
+        let mut outcome = MutationOutcome {
+            atomic: $atomic,
+            dry_run: $dry_run,
+            ..Default::default()
+        };
+
+        if $atomic {
+            let mut tx = $pool.begin().await?;
+            for (row_index, statement) in $statements.iter().enumerate() {
+                if let Err(e) = run_statement!(&mut *tx, statement, outcome) {
+                    tx.rollback().await?;
+                    outcome.rows_affected = 0;
+                    outcome.counts = outcome.counts.map(|_| InsertCounts::default());
+                    outcome.failures.push(RowFailure { row_index, error: e.to_string() });
+                    return Ok(outcome);
+                }
+            }
+            if $dry_run {
+                tx.rollback().await?;
+            } else {
+                tx.commit().await?;
+            }
+        } else {
+            for (row_index, statement) in $statements.iter().enumerate() {
+                if let Err(e) = run_statement!(&**$pool, statement, outcome) {
+                    outcome.failures.push(RowFailure { row_index, error: e.to_string() });
+                }
+            }
+        }
+
+        outcome.committed = !$dry_run && (!$atomic || outcome.failures.is_empty());
+        outcome.success = outcome.failures.is_empty();
+        Ok(outcome)
+    }};
+}
+
+/// Run row statements, all-or-nothing when `atomic`
+pub async fn execute_row_statements(
+    pool: &DatabasePool,
+    statements: &[RowStatement],
+    atomic: bool,
+) -> Result<MutationOutcome, Box<dyn Error + Send + Sync>> {
+    match pool {
+        DatabasePool::PostgreSQL(pool) => run_statements!(pool, statements, atomic, false),
+        DatabasePool::MySQL(pool) => run_statements!(pool, statements, atomic, false),
+        DatabasePool::SQLite(pool) => run_statements!(pool, statements, atomic, false),
+    }
+}
+
+/// Run row statements in one transaction and roll it back, reporting what
+/// they would have affected
+pub async fn dry_run_row_statements(
+    pool: &DatabasePool,
+    statements: &[RowStatement],
+) -> Result<MutationOutcome, Box<dyn Error + Send + Sync>> {
+    match pool {
+        DatabasePool::PostgreSQL(pool) => run_statements!(pool, statements, true, true),
+        DatabasePool::MySQL(pool) => run_statements!(pool, statements, true, true),
+        DatabasePool::SQLite(pool) => run_statements!(pool, statements, true, true),
+    }
+}
+
+/// SELECT for up to `limit` of the rows a delete or update of `row_ids`
+/// touches, as they are now. Row ids are inlined as string literals so the
+/// query can run through a connector.
+pub fn affected_rows_sample_query(
+    dialect: MutationDialect,
+    table_name: &str,
+    id_column: Option<&str>,
+    row_ids: &[&String],
+    limit: usize,
+) -> String {
+    let id_column = dialect.quote(id_column.unwrap_or(DEFAULT_ID_COLUMN));
+    let key = match dialect {
+        MutationDialect::Postgres => format!("{}::text", id_column),
+        MutationDialect::MySql | MutationDialect::Sqlite => id_column,
+    };
+    let ids: Vec<String> = row_ids
+        .iter()
+        .take(limit)
+        .map(|id| {
+            let escaped = match dialect {
+                // Backslashes escape quotes in MySQL string literals
+                MutationDialect::MySql => escape_string_literal(&id.replace('\\', "\\\\")),
+                MutationDialect::Postgres | MutationDialect::Sqlite => escape_string_literal(id),
+            };
+            format!("'{}'", escaped)
+        })
+        .collect();
+    format!(
+        "SELECT * FROM {} WHERE {} IN ({}) LIMIT {}",
+        dialect.quote(table_name),
+        key,
+        ids.join(", "),
+        limit
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use sqlx::sqlite::SqlitePoolOptions;
+    use std::sync::Arc;
+
+    #[test]
+    fn statements_are_parameterized_per_dialect() {
+        let updates = HashMap::from([(
+            "7".to_string(),
+            HashMap::from([("name".to_string(), json!("Robert'); DROP TABLE users;--")), ("age".to_string(), json!(40))]),
+        )]);
+
+        let pg = update_statements(MutationDialect::Postgres, "public.users", None, &updates);
+        assert_eq!(pg[0].sql, r#"UPDATE "public"."users" SET "age" = $1, "name" = $2 WHERE "id"::text = $3"#);
+        assert_eq!(pg[0].params, vec![json!(40), json!("Robert'); DROP TABLE users;--"), json!("7")]);
+
+        let mysql = delete_statements(MutationDialect::MySql, "users", Some("user_id"), &["1".to_string()]);
+        assert_eq!(mysql[0].sql, "DELETE FROM `users` WHERE `user_id` = ?");
+    }
+
+    async fn sqlite_pool() -> DatabasePool {
+        // One connection, so every query sees the same in-memory database
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        sqlx::query("CREATE TABLE people (id INTEGER PRIMARY KEY, name TEXT NOT NULL)")
+            .execute(&pool)
+            .await
+            .unwrap();
+        DatabasePool::SQLite(Arc::new(pool))
+    }
+
+    async fn row_count(pool: &DatabasePool) -> i64 {
+        let DatabasePool::SQLite(pool) = pool else { unreachable!() };
+        sqlx::query_scalar("SELECT COUNT(*) FROM people").fetch_one(&**pool).await.unwrap()
+    }
+
+    fn rows_with_failing_middle_row() -> Vec<HashMap<String, Value>> {
+        vec![
+            HashMap::from([("id".to_string(), json!(1)), ("name".to_string(), json!("Ada"))]),
+            HashMap::from([("id".to_string(), json!(2)), ("name".to_string(), Value::Null)]),
+            HashMap::from([("id".to_string(), json!(3)), ("name".to_string(), json!("Grace"))]),
+        ]
+    }
+
+    #[tokio::test]
+    async fn atomic_insert_persists_nothing_when_a_row_fails() {
+        let pool = sqlite_pool().await;
+        let statements = insert_statements(MutationDialect::Sqlite, "people", &rows_with_failing_middle_row(), OnConflict::Error, &[]);
+
+        let outcome = execute_row_statements(&pool, &statements, true).await.unwrap();
+        assert!(!outcome.success);
+        assert!(!outcome.committed);
+        assert_eq!(outcome.rows_affected, 0);
+        assert_eq!(outcome.failures.len(), 1);
+        assert_eq!(outcome.failures[0].row_index, 1);
+        assert_eq!(row_count(&pool).await, 0);
+    }
+
+    #[tokio::test]
+    async fn best_effort_insert_keeps_the_rows_that_succeed() {
+        let pool = sqlite_pool().await;
+        let statements = insert_statements(MutationDialect::Sqlite, "people", &rows_with_failing_middle_row(), OnConflict::Error, &[]);
+
+        let outcome = execute_row_statements(&pool, &statements, false).await.unwrap();
+        assert!(!outcome.success);
+        assert!(outcome.committed);
+        assert_eq!(outcome.rows_affected, 2);
+        assert_eq!(outcome.failures[0].row_index, 1);
+        assert_eq!(row_count(&pool).await, 2);
+    }
+
+    fn people(rows: &[(i64, &str)]) -> Vec<HashMap<String, Value>> {
+        rows.iter()
+            .map(|(id, name)| HashMap::from([("id".to_string(), json!(id)), ("name".to_string(), json!(name))]))
+            .collect()
+    }
+
+    async fn seeded_pool() -> DatabasePool {
+        let pool = sqlite_pool().await;
+        let seed = insert_statements(MutationDialect::Sqlite, "people", &people(&[(1, "Ada")]), OnConflict::Error, &[]);
+        execute_row_statements(&pool, &seed, true).await.unwrap();
+        pool
+    }
+
+    async fn name_of(pool: &DatabasePool, id: i64) -> String {
+        let DatabasePool::SQLite(pool) = pool else { unreachable!() };
+        sqlx::query_scalar("SELECT name FROM people WHERE id = ?").bind(id).fetch_one(&**pool).await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn conflicting_rows_fail_ignore_or_update_by_mode() {
+        let target = vec!["id".to_string()];
+        let rows = people(&[(1, "Ada Lovelace"), (2, "Grace")]);
+
+        // error: the duplicate key fails the batch
+        let pool = seeded_pool().await;
+        let statements = insert_statements(MutationDialect::Sqlite, "people", &rows, OnConflict::Error, &[]);
+        let outcome = execute_row_statements(&pool, &statements, true).await.unwrap();
+        assert!(!outcome.committed);
+        assert_eq!(outcome.failures[0].row_index, 0);
+        assert_eq!(row_count(&pool).await, 1);
+
+        // ignore: the existing row is kept
+        let pool = seeded_pool().await;
+        let statements = insert_statements(MutationDialect::Sqlite, "people", &rows, OnConflict::Ignore, &target);
+        let outcome = execute_row_statements(&pool, &statements, true).await.unwrap();
+        assert!(outcome.success);
+        assert_eq!(outcome.counts, Some(InsertCounts { inserted: 1, updated: 0, skipped: 1 }));
+        assert_eq!(name_of(&pool, 1).await, "Ada");
+
+        // ignore without a target relies on the unique constraint alone
+        let pool = seeded_pool().await;
+        let statements = insert_statements(MutationDialect::Sqlite, "people", &rows, OnConflict::Ignore, &[]);
+        let outcome = execute_row_statements(&pool, &statements, true).await.unwrap();
+        assert_eq!(outcome.counts, Some(InsertCounts { inserted: 1, updated: 0, skipped: 1 }));
+
+        // update: the existing row takes the new values
+        let pool = seeded_pool().await;
+        let statements = insert_statements(MutationDialect::Sqlite, "people", &rows, OnConflict::Update, &target);
+        let outcome = execute_row_statements(&pool, &statements, true).await.unwrap();
+        assert!(outcome.success);
+        assert_eq!(outcome.counts, Some(InsertCounts { inserted: 1, updated: 1, skipped: 0 }));
+        assert_eq!(name_of(&pool, 1).await, "Ada Lovelace");
+        assert_eq!(row_count(&pool).await, 2);
+    }
+
+    #[test]
+    fn upserts_use_each_dialects_syntax() {
+        let rows = people(&[(1, "Ada")]);
+        let target = vec!["id".to_string()];
+
+        let pg = insert_statements(MutationDialect::Postgres, "people", &rows, OnConflict::Update, &target);
+        assert_eq!(
+            pg[0].sql,
+            r#"INSERT INTO "people" ("id", "name") VALUES ($1, $2) ON CONFLICT ("id") DO UPDATE SET "name" = excluded."name""#
+        );
+        let probe = pg[0].insert.as_ref().and_then(|check| check.probe.as_ref()).unwrap();
+        assert_eq!(probe.sql, r#"SELECT 1 FROM "people" WHERE "id" = $1 LIMIT 1"#);
+
+        let mysql = insert_statements(MutationDialect::MySql, "people", &rows, OnConflict::Update, &target);
+        assert_eq!(
+            mysql[0].sql,
+            "INSERT INTO `people` (`id`, `name`) VALUES (?, ?) ON DUPLICATE KEY UPDATE `name` = VALUES(`name`)"
+        );
+        let mysql = insert_statements(MutationDialect::MySql, "people", &rows, OnConflict::Ignore, &target);
+        assert!(mysql[0].sql.ends_with("ON DUPLICATE KEY UPDATE `id` = `id`"));
+    }
+
+    #[test]
+    fn update_mode_needs_the_primary_key_as_target() {
+        let rows = people(&[(1, "Ada")]);
+        let keys = vec!["id".to_string()];
+
+        assert!(validate_conflict_target(OnConflict::Update, &[], Some(&keys), &rows).is_err());
+        assert!(validate_conflict_target(OnConflict::Update, &["name".to_string()], Some(&keys), &rows).is_err());
+        assert!(validate_conflict_target(OnConflict::Update, &keys, Some(&keys), &rows).is_ok());
+        assert!(validate_conflict_target(OnConflict::Ignore, &[], Some(&keys), &rows).is_ok());
+
+        let without_id = vec![HashMap::from([("name".to_string(), json!("Ada"))])];
+        assert!(validate_conflict_target(OnConflict::Update, &keys, None, &without_id).is_err());
+    }
+
+    #[tokio::test]
+    async fn dry_runs_report_the_real_count_without_changing_data() {
+        let pool = sqlite_pool().await;
+        let rows = people(&[(1, "Ada"), (2, "Grace"), (3, "Edsger")]);
+        let seed = insert_statements(MutationDialect::Sqlite, "people", &rows, OnConflict::Error, &[]);
+        execute_row_statements(&pool, &seed, true).await.unwrap();
+
+        // An id that matches nothing doesn't count
+        let ids = vec!["1".to_string(), "3".to_string(), "99".to_string()];
+        let deletes = delete_statements(MutationDialect::Sqlite, "people", None, &ids);
+        let preview = dry_run_row_statements(&pool, &deletes).await.unwrap();
+        assert!(preview.dry_run);
+        assert!(!preview.committed);
+        assert_eq!(preview.rows_affected, 2);
+        assert_eq!(row_count(&pool).await, 3);
+
+        let updates = HashMap::from([("2".to_string(), HashMap::from([("name".to_string(), json!("Grace Hopper"))]))]);
+        let updates = update_statements(MutationDialect::Sqlite, "people", None, &updates);
+        let preview = dry_run_row_statements(&pool, &updates).await.unwrap();
+        assert_eq!(preview.rows_affected, 1);
+        assert_eq!(name_of(&pool, 2).await, "Grace");
+
+        let real = execute_row_statements(&pool, &deletes, true).await.unwrap();
+        assert!(real.committed);
+        assert_eq!(real.rows_affected, 2);
+        assert_eq!(row_count(&pool).await, 1);
+    }
+
+    #[test]
+    fn sample_queries_inline_escaped_ids() {
+        let ids = ["1".to_string(), "it's".to_string()];
+        let ids: Vec<&String> = ids.iter().collect();
+        assert_eq!(
+            affected_rows_sample_query(MutationDialect::Postgres, "people", None, &ids, 20),
+            r#"SELECT * FROM "people" WHERE "id"::text IN ('1', 'it''s') LIMIT 20"#
+        );
+        let backslash = "a\\".to_string();
+        assert_eq!(
+            affected_rows_sample_query(MutationDialect::MySql, "people", Some("key"), &[&backslash], 5),
+            r"SELECT * FROM `people` WHERE `key` IN ('a\\') LIMIT 5"
+        );
+    }
+}
@@ -0,0 +1,26 @@
+//! Helpers for the differently shaped schema JSON connectors return
+
+use serde_json::Value;
+
+/// Find one table's entry in a connector schema. Connectors key tables by name,
+/// nest them under `tables`, or list them as `{name|table, ...}` entries.
+pub fn find_table_entry<'a>(schema: &'a Value, table_name: &str) -> Option<&'a Value> {
+    let matches_name = |entry: &Value| {
+        entry.get("name").and_then(|n| n.as_str()).is_some_and(|n| n == table_name)
+            || entry.get("table").and_then(|n| n.as_str()).is_some_and(|n| n == table_name)
+    };
+
+    match schema {
+        Value::Object(map) => {
+            if let Some(entry) = map.get(table_name).filter(|e| e.get("columns").is_some()) {
+                return Some(entry);
+            }
+            if let Some(tables) = map.get("tables") {
+                return find_table_entry(tables, table_name);
+            }
+            map.values().find(|e| matches_name(e))
+        }
+        Value::Array(entries) => entries.iter().find(|e| matches_name(e)),
+        _ => None,
+    }
+}
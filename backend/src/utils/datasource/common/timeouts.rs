@@ -0,0 +1,166 @@
+//! Connect and query timeouts for external datasources
+//!
+//! Defaults come from `DATASOURCE_CONNECT_TIMEOUT_SECS` and
+//! `DATASOURCE_QUERY_TIMEOUT_SECS`; a datasource can override either with
+//! `connect_timeout_secs` / `query_timeout_secs` in its connection config.
+//! PostgreSQL and MySQL sessions also get a server-side statement timeout, so
+//! a query abandoned by the client timeout doesn't keep running. The process
+//! reads the defaults once, into `Config::datasource_timeouts`.
+
+use serde_json::Value;
+use std::error::Error;
+use std::fmt;
+use std::future::Future;
+use std::time::Duration;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DatasourceTimeouts {
+    /// How long to wait for a pooled connection, including opening a new one
+    pub connect: Duration,
+    /// How long a single query may run
+    pub query: Duration,
+}
+
+impl Default for DatasourceTimeouts {
+    fn default() -> Self {
+        Self {
+            connect: Duration::from_secs(3),
+            query: Duration::from_secs(120),
+        }
+    }
+}
+
+impl DatasourceTimeouts {
+    pub fn from_env() -> Self {
+        let defaults = Self::default();
+        let env_secs = |name: &str| {
+            std::env::var(name)
+                .ok()
+                .and_then(|v| v.parse::<u64>().ok())
+                .filter(|secs| *secs > 0)
+                .map(Duration::from_secs)
+        };
+
+        Self {
+            connect: env_secs("DATASOURCE_CONNECT_TIMEOUT_SECS").unwrap_or(defaults.connect),
+            query: env_secs("DATASOURCE_QUERY_TIMEOUT_SECS").unwrap_or(defaults.query),
+        }
+    }
+
+    /// These timeouts with the datasource's own overrides applied
+    pub fn for_datasource(&self, connection_config: &Value) -> Self {
+        let secs = |key: &str| {
+            connection_config
+                .get(key)
+                .and_then(|v| v.as_u64().or_else(|| v.as_str().and_then(|s| s.parse().ok())))
+                .filter(|secs| *secs > 0)
+                .map(Duration::from_secs)
+        };
+
+        Self {
+            connect: secs("connect_timeout_secs").unwrap_or(self.connect),
+            query: secs("query_timeout_secs").unwrap_or(self.query),
+        }
+    }
+
+    /// Session setting that makes the PostgreSQL server cancel long statements
+    pub fn postgres_session_options(&self) -> Vec<(&'static str, String)> {
+        vec![("statement_timeout", self.query.as_millis().to_string())]
+    }
+
+    /// Session statement that makes a MySQL server cancel long SELECTs, then
+    /// the MariaDB equivalent (in seconds) for servers that reject the first
+    pub fn mysql_session_statements(&self) -> [String; 2] {
+        [
+            format!("SET SESSION max_execution_time = {}", self.query.as_millis()),
+            format!("SET SESSION max_statement_time = {}", self.query.as_secs_f64()),
+        ]
+    }
+}
+
+/// A query ran longer than its datasource's query timeout
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct QueryTimedOut {
+    pub after: Duration,
+}
+
+impl fmt::Display for QueryTimedOut {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Query exceeded the {:?} query timeout", self.after)
+    }
+}
+
+impl Error for QueryTimedOut {}
+
+/// Run `query`, giving up with [`QueryTimedOut`] after `timeout`
+pub async fn with_query_timeout<T>(
+    timeout: Duration,
+    query: impl Future<Output = Result<T, Box<dyn Error + Send + Sync>>>,
+) -> Result<T, Box<dyn Error + Send + Sync>> {
+    match tokio::time::timeout(timeout, query).await {
+        Ok(result) => result,
+        Err(_) => Err(Box::new(QueryTimedOut { after: timeout })),
+    }
+}
+
+/// Whether `error` means the query itself ran too long, as opposed to the
+/// connection timing out: our own timeout or the server cancelling it
+pub fn is_query_timeout(error: &(dyn Error + 'static)) -> bool {
+    if error.downcast_ref::<QueryTimedOut>().is_some() {
+        return true;
+    }
+    let message = error.to_string().to_lowercase();
+    [
+        "canceling statement due to statement timeout",
+        "maximum statement execution time exceeded",
+        "max_statement_time exceeded",
+        "timeout_exceeded",
+    ]
+    .iter()
+    .any(|pattern| message.contains(pattern))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn datasource_overrides_apply_over_the_defaults() {
+        let defaults = DatasourceTimeouts::default();
+        let timeouts = defaults.for_datasource(&json!({ "query_timeout_secs": "2", "connect_timeout_secs": 0 }));
+
+        assert_eq!(timeouts.query, Duration::from_secs(2));
+        assert_eq!(timeouts.connect, defaults.connect);
+        assert_eq!(
+            timeouts.postgres_session_options(),
+            vec![("statement_timeout", "2000".to_string())]
+        );
+        assert_eq!(
+            timeouts.mysql_session_statements(),
+            [
+                "SET SESSION max_execution_time = 2000".to_string(),
+                "SET SESSION max_statement_time = 2".to_string(),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn slow_queries_fail_with_a_query_timeout() {
+        // Stands in for `SELECT pg_sleep(5)` against a short timeout
+        let sleep = async {
+            tokio::time::sleep(Duration::from_secs(5)).await;
+            Ok::<_, Box<dyn Error + Send + Sync>>(())
+        };
+
+        let error = with_query_timeout(Duration::from_millis(20), sleep).await.unwrap_err();
+        assert!(is_query_timeout(&*error));
+        assert_eq!(error.to_string(), "Query exceeded the 20ms query timeout");
+
+        let server_side: Box<dyn Error + Send + Sync> =
+            "error returned from database: canceling statement due to statement timeout".into();
+        assert!(is_query_timeout(&*server_side));
+        let pool: Box<dyn Error + Send + Sync> = "pool timed out while waiting for an open connection".into();
+        assert!(!is_query_timeout(&*pool));
+    }
+}
@@ -0,0 +1,127 @@
+//! JSON values for query result cells
+//!
+//! Connectors decode each cell into a [`Cell`] so numbers, booleans and NULL
+//! keep their JSON types. Clients built against the old all-string rows can
+//! ask for [`stringify_rows`] instead, which renders NULL as `"NULL"`.
+
+use serde_json::{Number, Value};
+
+/// Significant digits an f64 holds exactly; longer decimals stay strings
+const MAX_EXACT_DIGITS: usize = 15;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Cell {
+    Null,
+    Bool(bool),
+    Int(i64),
+    Float(f64),
+    /// Arbitrary precision numeric, as the database printed it
+    Decimal(String),
+    Text(String),
+    /// Structured values such as jsonb documents and arrays
+    Json(Value),
+}
+
+impl Cell {
+    pub fn into_json(self) -> Value {
+        match self {
+            Cell::Null => Value::Null,
+            Cell::Bool(b) => Value::Bool(b),
+            Cell::Int(i) => Value::from(i),
+            Cell::Float(f) => Number::from_f64(f)
+                .map(Value::Number)
+                .unwrap_or_else(|| Value::String(f.to_string())),
+            Cell::Decimal(d) => decimal_number(&d).unwrap_or(Value::String(d)),
+            Cell::Text(s) => Value::String(s),
+            Cell::Json(v) => v,
+        }
+    }
+}
+
+/// `decimal` as a JSON number when an f64 represents it without losing digits
+fn decimal_number(decimal: &str) -> Option<Value> {
+    let significant = decimal
+        .trim_start_matches('-')
+        .chars()
+        .filter(char::is_ascii_digit)
+        .skip_while(|c| *c == '0')
+        .count();
+    if significant > MAX_EXACT_DIGITS {
+        return None;
+    }
+    decimal
+        .parse::<f64>()
+        .ok()
+        .and_then(Number::from_f64)
+        .map(Value::Number)
+}
+
+/// The legacy rendering of a cell: every value a string, NULL as `"NULL"`
+pub fn stringify_value(value: &Value) -> Value {
+    match value {
+        Value::Null => Value::String("NULL".to_string()),
+        Value::String(_) => value.clone(),
+        Value::Bool(b) => Value::String(b.to_string()),
+        Value::Number(n) => Value::String(n.to_string()),
+        other => Value::String(other.to_string()),
+    }
+}
+
+/// Stringify every cell of a result's `rows`, for clients that opt in with `stringify: true`
+pub fn stringify_rows(result: &mut Value) {
+    let Some(rows) = result.get_mut("rows").and_then(Value::as_array_mut) else {
+        return;
+    };
+    for row in rows {
+        match row {
+            Value::Array(cells) => cells.iter_mut().for_each(|cell| *cell = stringify_value(cell)),
+            Value::Object(cells) => cells.values_mut().for_each(|cell| *cell = stringify_value(cell)),
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn cells_keep_their_json_types() {
+        let row: Vec<Value> = vec![
+            Cell::Null,
+            Cell::Int(42),
+            Cell::Float(1.5),
+            Cell::Bool(true),
+            Cell::Decimal("19.99".to_string()),
+            Cell::Decimal("12345678901234567890.5".to_string()),
+            Cell::Text("NULL".to_string()),
+        ]
+        .into_iter()
+        .map(Cell::into_json)
+        .collect();
+
+        assert_eq!(
+            serde_json::to_string(&row).unwrap(),
+            r#"[null,42,1.5,true,19.99,"12345678901234567890.5","NULL"]"#
+        );
+        assert!(row[0].is_null());
+        assert!(row[1].is_i64());
+    }
+
+    #[test]
+    fn structured_cells_stay_structured() {
+        let document = json!({ "theme": "dark", "limits": [1, 2] });
+        assert_eq!(Cell::Json(document.clone()).into_json(), document);
+        assert_eq!(Cell::Json(json!(["a", null])).into_json(), json!(["a", null]));
+        // Legacy clients get the JSON text
+        assert_eq!(stringify_value(&json!([1, 2])), json!("[1,2]"));
+    }
+
+    #[test]
+    fn stringify_restores_the_legacy_rows() {
+        let mut result = json!({ "columns": ["id", "name", "active"], "rows": [[1, null, false]] });
+        stringify_rows(&mut result);
+        assert_eq!(result["rows"], json!([["1", "NULL", "false"]]));
+    }
+}
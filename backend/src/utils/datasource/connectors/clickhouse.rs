@@ -1,4 +1,8 @@
 use super::super::core::base::DataSourceConnector;
+use super::clickhouse_sql::{
+    approximate_count_query, columns_query, exact_count_query, table_page_query, typed_cell,
+};
+use super::common::IdentifierQuote;
 use async_trait::async_trait;
 use clickhouse::Client;
 use serde_json::{json, Value};
@@ -7,6 +11,8 @@ use std::time::Duration;
 
 pub struct ClickHouseConnector {
     client: Client,
+    /// Report table totals from `system.tables` instead of running `count()`
+    approximate_count: bool,
 }
 
 impl ClickHouseConnector {
@@ -49,8 +55,36 @@ impl ClickHouseConnector {
         );
 
         let client = Client::default().with_url(url);
+        let approximate_count = config
+            .get("approximate_count")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(true);
+
+        Ok(Self {
+            client,
+            approximate_count,
+        })
+    }
+
+    /// Total rows of a table, and whether the total is the catalog's estimate
+    async fn table_row_count(&self, table: &str) -> Result<(u64, bool), Box<dyn Error + Send + Sync>> {
+        if self.approximate_count {
+            let estimate: Option<u64> = self
+                .client
+                .query(&approximate_count_query(table))
+                .fetch_optional()
+                .await?;
+            if let Some(total) = estimate {
+                return Ok((total, true));
+            }
+        }
 
-        Ok(Self { client })
+        let total: u64 = self
+            .client
+            .query(&exact_count_query(table))
+            .fetch_one()
+            .await?;
+        Ok((total, false))
     }
 
     fn mask_url(url: &str) -> String {
@@ -79,6 +113,10 @@ impl ClickHouseConnector {
 
 #[async_trait]
 impl DataSourceConnector for ClickHouseConnector {
+    fn identifier_quote(&self) -> IdentifierQuote {
+        IdentifierQuote::Backtick
+    }
+
     async fn test_connection(&mut self) -> Result<bool, Box<dyn Error + Send + Sync>> {
         // Wrap the connection test with a 3-second timeout
         match tokio::time::timeout(
@@ -95,11 +133,7 @@ impl DataSourceConnector for ClickHouseConnector {
 
     async fn execute_query(&self, query: &str, limit: i32) -> Result<Value, Box<dyn Error + Send + Sync>> {
         // Add LIMIT if not present
-        let query_with_limit = if query.to_lowercase().contains("limit") {
-            query.to_string()
-        } else {
-            format!("{} LIMIT {}", query, limit)
-        };
+        let query_with_limit = self.apply_limit(query, limit);
 
         let start = std::time::Instant::now();
 
@@ -126,31 +160,28 @@ impl DataSourceConnector for ClickHouseConnector {
                     let data = json_response.get("data").and_then(|d| d.as_array());
 
                     if let (Some(meta_array), Some(data_array)) = (meta, data) {
-                        // Extract column names from metadata
-                        let columns: Vec<String> = meta_array
+                        // Extract column names and types from metadata
+                        let column_types: Vec<(String, String)> = meta_array
                             .iter()
-                            .filter_map(|col| col.get("name").and_then(|n| n.as_str()))
-                            .map(|s| s.to_string())
+                            .filter_map(|col| {
+                                let name = col.get("name").and_then(|n| n.as_str())?;
+                                let col_type = col.get("type").and_then(|t| t.as_str()).unwrap_or("");
+                                Some((name.to_string(), col_type.to_string()))
+                            })
                             .collect();
+                        let columns: Vec<String> =
+                            column_types.iter().map(|(name, _)| name.clone()).collect();
 
-                        // Convert data rows to the expected format
+                        // Convert data rows to the expected format, typed by column
                         let mut formatted_rows = Vec::new();
                         for row in data_array {
                             if let Some(row_obj) = row.as_object() {
-                                let mut row_data = Vec::new();
-                                for col_name in &columns {
-                                    let value = row_obj.get(col_name).unwrap_or(&Value::Null);
-                                    row_data.push(match value {
-                                        Value::String(s) => s.clone(),
-                                        Value::Number(n) => n.to_string(),
-                                        Value::Bool(b) => b.to_string(),
-                                        Value::Null => "NULL".to_string(),
-                                        Value::Array(a) => serde_json::to_string(a)
-                                            .unwrap_or_else(|_| "[]".to_string()),
-                                        Value::Object(o) => serde_json::to_string(o)
-                                            .unwrap_or_else(|_| "{}".to_string()),
-                                    });
-                                }
+                                let row_data: Vec<Value> = column_types
+                                    .iter()
+                                    .map(|(col_name, col_type)| {
+                                        typed_cell(row_obj.get(col_name).unwrap_or(&Value::Null), col_type)
+                                    })
+                                    .collect();
                                 formatted_rows.push(row_data);
                             }
                         }
@@ -287,23 +318,12 @@ impl DataSourceConnector for ClickHouseConnector {
     }
 
     async fn get_tables_schema(&self, tables: Vec<&str>) -> Result<Value, Box<dyn Error + Send + Sync>> {
-        let table_list = tables
-            .iter()
-            .map(|t| format!("'{}'", t.replace("'", "''")))
-            .collect::<Vec<_>>()
-            .join(", ");
-
-        let query = format!(
-            "SELECT database, table, name, type FROM system.columns WHERE CONCAT(database, '.', table) IN ({}) ORDER BY database, table, name",
-            table_list
-        );
-
-        let columns: Vec<(String, String, String, String)> =
-            self.client.query(&query).fetch_all().await?;
+        let columns: Vec<(String, String, String, String, u8)> =
+            self.client.query(&columns_query(&tables)).fetch_all().await?;
 
         let mut result: std::collections::HashMap<String, Value> = std::collections::HashMap::new();
 
-        for (database, table, name, col_type) in columns {
+        for (database, table, name, col_type, in_primary_key) in columns {
             let full_table_name = format!("{}.{}", database, table);
 
             let table_entry = result.entry(full_table_name.clone()).or_insert_with(|| {
@@ -321,8 +341,9 @@ impl DataSourceConnector for ClickHouseConnector {
             {
                 columns_array.push(json!({
                     "name": name,
+                    "nullable": col_type.contains("Nullable"),
                     "type": col_type,
-                    "nullable": col_type.contains("Nullable")
+                    "primary_key": in_primary_key == 1
                 }));
             }
         }
@@ -441,24 +462,18 @@ impl DataSourceConnector for ClickHouseConnector {
         sort_column: Option<&str>, 
         sort_direction: Option<&str>
     ) -> Result<Value, Box<dyn Error + Send + Sync>> {
-        // TODO: Implement proper pagination with total count for ClickHouse
-        let offset = (page - 1) * limit;
-        let mut query = format!("SELECT * FROM {}", table_name);
-        
-        if let Some(sort_col) = sort_column {
-            let direction = sort_direction.unwrap_or("ASC");
-            query.push_str(&format!(" ORDER BY {} {}", sort_col, direction));
-        }
-        
-        query.push_str(&format!(" LIMIT {} OFFSET {}", limit, offset));
-        
+        let offset = (page.max(1) - 1) * limit;
+        let descending = matches!(sort_direction, Some(d) if d.eq_ignore_ascii_case("desc"));
+        let query = table_page_query(table_name, sort_column, descending, limit, offset);
+
         let mut result = self.execute_query(&query, limit).await?;
-        
-        // Add pagination metadata (temporary - needs proper total count)
-        result["total_rows"] = json!(result["row_count"]);
+        let (total_rows, approximate) = self.table_row_count(table_name).await?;
+
+        result["total_rows"] = json!(total_rows);
+        result["approximate_total"] = json!(approximate);
         result["page"] = json!(page);
         result["page_size"] = json!(limit);
-        
+
         Ok(result)
     }
 }
@@ -0,0 +1,151 @@
+//! SQL generation and value typing for the ClickHouse table browser
+//!
+//! Tables are listed as `database.table`. Counting a large table exactly is a
+//! full scan, so by default the browser reads `system.tables.total_rows`,
+//! which MergeTree engines maintain without scanning; other engines and views
+//! report NULL there and are counted exactly.
+
+use serde_json::Value;
+
+/// Backtick-quoted identifier
+pub fn quote_identifier(name: &str) -> String {
+    format!("`{}`", name.replace('\\', "\\\\").replace('`', "\\`"))
+}
+
+fn string_literal(value: &str) -> String {
+    format!("'{}'", value.replace('\\', "\\\\").replace('\'', "\\'"))
+}
+
+/// `(database, table)`; a bare name lives in the connection's database
+pub fn split_table_name(name: &str) -> (Option<&str>, &str) {
+    match name.split_once('.') {
+        Some((database, table)) => (Some(database), table),
+        None => (None, name),
+    }
+}
+
+pub fn qualified_table(name: &str) -> String {
+    match split_table_name(name) {
+        (Some(database), table) => format!("{}.{}", quote_identifier(database), quote_identifier(table)),
+        (None, table) => quote_identifier(table),
+    }
+}
+
+/// One page of a table, optionally ordered by a single column
+pub fn table_page_query(table: &str, sort_column: Option<&str>, descending: bool, limit: i32, offset: i32) -> String {
+    let mut query = format!("SELECT * FROM {}", qualified_table(table));
+    if let Some(column) = sort_column {
+        query.push_str(&format!(
+            " ORDER BY {} {}",
+            quote_identifier(column),
+            if descending { "DESC" } else { "ASC" }
+        ));
+    }
+    query.push_str(&format!(" LIMIT {} OFFSET {}", limit, offset.max(0)));
+    query
+}
+
+pub fn exact_count_query(table: &str) -> String {
+    format!("SELECT count() FROM {}", qualified_table(table))
+}
+
+/// Row count ClickHouse keeps in its catalog; no row for engines that don't track it
+pub fn approximate_count_query(table: &str) -> String {
+    let (database, name) = split_table_name(table);
+    let database = database.map(string_literal).unwrap_or_else(|| "currentDatabase()".to_string());
+    format!(
+        "SELECT assumeNotNull(total_rows) FROM system.tables WHERE database = {} AND name = {} AND total_rows IS NOT NULL",
+        database,
+        string_literal(name)
+    )
+}
+
+/// Columns of `database.table` names in table order, with primary key membership
+pub fn columns_query(tables: &[&str]) -> String {
+    let names = tables.iter().map(|t| string_literal(t)).collect::<Vec<_>>().join(", ");
+    format!(
+        "SELECT database, table, name, type, is_in_primary_key FROM system.columns \
+         WHERE concat(database, '.', table) IN ({}) ORDER BY database, table, position",
+        names
+    )
+}
+
+/// The type inside `Nullable(...)` and `LowCardinality(...)` wrappers
+pub fn base_type(column_type: &str) -> &str {
+    let mut current = column_type.trim();
+    loop {
+        let inner = ["Nullable(", "LowCardinality("]
+            .iter()
+            .find_map(|wrapper| current.strip_prefix(wrapper).and_then(|rest| rest.strip_suffix(')')));
+        match inner {
+            Some(inner) => current = inner.trim(),
+            None => return current,
+        }
+    }
+}
+
+/// A cell from ClickHouse's JSON output as the JSON value of its column type.
+/// 64-bit integers arrive quoted so clients don't lose precision in doubles;
+/// they become numbers when they fit i64/u64. Dates and DateTimes stay strings.
+pub fn typed_cell(value: &Value, column_type: &str) -> Value {
+    let column_type = base_type(column_type);
+    match value {
+        Value::Null => Value::Null,
+        Value::String(s) if column_type.starts_with("Int") || column_type.starts_with("UInt") => s
+            .parse::<i64>()
+            .map(Value::from)
+            .or_else(|_| s.parse::<u64>().map(Value::from))
+            .unwrap_or_else(|_| value.clone()),
+        Value::String(s) if column_type.starts_with("Float") => s
+            .parse::<f64>()
+            .ok()
+            .and_then(serde_json::Number::from_f64)
+            .map(Value::Number)
+            .unwrap_or_else(|| value.clone()),
+        Value::Number(n) if column_type == "Bool" => Value::Bool(n.as_u64() == Some(1)),
+        _ => value.clone(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn page_and_count_queries_quote_database_and_table() {
+        assert_eq!(
+            table_page_query("analytics.page`views", Some("event time"), true, 50, 100),
+            "SELECT * FROM `analytics`.`page\\`views` ORDER BY `event time` DESC LIMIT 50 OFFSET 100"
+        );
+        assert_eq!(table_page_query("events", None, false, 10, 0), "SELECT * FROM `events` LIMIT 10 OFFSET 0");
+        assert_eq!(exact_count_query("analytics.events"), "SELECT count() FROM `analytics`.`events`");
+        assert_eq!(
+            approximate_count_query("analytics.o'brien"),
+            "SELECT assumeNotNull(total_rows) FROM system.tables WHERE database = 'analytics' AND name = 'o\\'brien' AND total_rows IS NOT NULL"
+        );
+        assert_eq!(
+            approximate_count_query("events"),
+            "SELECT assumeNotNull(total_rows) FROM system.tables WHERE database = currentDatabase() AND name = 'events' AND total_rows IS NOT NULL"
+        );
+        assert!(columns_query(&["analytics.events"]).contains("IN ('analytics.events') ORDER BY database, table, position"));
+    }
+
+    #[test]
+    fn cells_are_typed_by_their_column_type() {
+        assert_eq!(base_type("Nullable(UInt64)"), "UInt64");
+        assert_eq!(base_type("LowCardinality(Nullable(String))"), "String");
+
+        assert_eq!(typed_cell(&json!("18446744073709551615"), "UInt64"), json!(18446744073709551615u64));
+        assert_eq!(typed_cell(&json!("-42"), "Nullable(Int64)"), json!(-42));
+        // Too wide for JSON numbers; kept as text
+        assert_eq!(typed_cell(&json!("340282366920938463463374607431768211455"), "UInt128"), json!("340282366920938463463374607431768211455"));
+        assert_eq!(typed_cell(&json!(7), "UInt8"), json!(7));
+        assert_eq!(typed_cell(&json!(1.5), "Float64"), json!(1.5));
+        assert_eq!(typed_cell(&json!("inf"), "Float64"), json!("inf"));
+        assert_eq!(typed_cell(&json!(1), "Bool"), json!(true));
+        assert_eq!(typed_cell(&json!("2024-03-01 12:00:00"), "DateTime('UTC')"), json!("2024-03-01 12:00:00"));
+        assert_eq!(typed_cell(&json!("2024-03-01"), "Date32"), json!("2024-03-01"));
+        assert_eq!(typed_cell(&Value::Null, "Nullable(Int32)"), Value::Null);
+    }
+}
@@ -1,7 +1,10 @@
 /// Common utilities for database connectors
 /// Reduces code duplication across PostgreSQL, MySQL, SQLite, and SQL Server connectors
 use serde_json::Value;
+use sqlparser::dialect::{Dialect, GenericDialect, MsSqlDialect, MySqlDialect};
+use sqlparser::tokenizer::{Token, Tokenizer};
 use std::error::Error;
+use std::sync::LazyLock;
 
 /// Extract datasource ID from config (optional - only needed for connection pooling)
 pub fn extract_datasource_id(config: &Value) -> String {
@@ -129,6 +132,386 @@ pub fn add_ssl_parameter(
     }
 }
 
+/// Row-limiting syntax understood by a SQL dialect
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LimitSyntax {
+    /// `... LIMIT n` (PostgreSQL, MySQL, SQLite, ClickHouse, DuckDB)
+    Limit,
+    /// `SELECT TOP n ...` (SQL Server)
+    Top,
+    /// `... FETCH FIRST n ROWS ONLY` (Oracle 12c and newer)
+    FetchFirst,
+    /// `SELECT * FROM (...) WHERE ROWNUM <= n` (older Oracle releases)
+    RowNum,
+}
+
+// The pattern is a literal, so building it can't fail
+#[allow(clippy::unwrap_used)]
+static ROW_LIMIT_PATTERN: LazyLock<regex::Regex> = LazyLock::new(|| {
+    regex::Regex::new(
+        r"(?i)\blimit\s+(\d|\$|\?|:)|\btop\s*\(?\s*\d|\bfetch\s+(first|next)\b|\brownum\b"
+    ).unwrap()
+});
+
+/// Check whether a query already restricts its row count in any supported dialect
+pub fn has_row_limit(query: &str) -> bool {
+    ROW_LIMIT_PATTERN.is_match(query)
+}
+
+/// Apply a row limit to a query using the given dialect syntax.
+/// Queries that already carry a row limit, or a non-positive limit, are returned unchanged.
+pub fn apply_row_limit(query: &str, limit: i32, syntax: LimitSyntax) -> String {
+    if limit <= 0 || has_row_limit(query) {
+        return query.to_string();
+    }
+
+    let trimmed = query.trim().trim_end_matches(';').trim_end();
+
+    match syntax {
+        LimitSyntax::Limit => format!("{} LIMIT {}", trimmed, limit),
+        LimitSyntax::FetchFirst => format!("{} FETCH FIRST {} ROWS ONLY", trimmed, limit),
+        LimitSyntax::RowNum => format!("SELECT * FROM ({}) WHERE ROWNUM <= {}", trimmed, limit),
+        LimitSyntax::Top => {
+            // TOP must follow SELECT (and DISTINCT/ALL if present); anything that
+            // isn't a plain SELECT (CTEs, EXEC, DML) is left untouched
+            let lower = trimmed.to_ascii_lowercase();
+            if !lower.starts_with("select") || !lower[6..].starts_with(char::is_whitespace) {
+                return query.to_string();
+            }
+            let rest = trimmed[6..].trim_start();
+            let rest_lower = rest.to_ascii_lowercase();
+            for modifier in ["distinct", "all"] {
+                if rest_lower.starts_with(modifier)
+                    && rest_lower[modifier.len()..].starts_with(char::is_whitespace)
+                {
+                    return format!(
+                        "SELECT {} TOP {} {}",
+                        &rest[..modifier.len()],
+                        limit,
+                        rest[modifier.len()..].trim_start()
+                    );
+                }
+            }
+            format!("SELECT TOP {} {}", limit, rest)
+        }
+    }
+}
+
+/// Words that may directly follow a table reference without being its alias
+const NON_ALIAS_KEYWORDS: &[&str] = &[
+    "where", "join", "inner", "left", "right", "full", "cross", "outer", "natural", "on",
+    "using", "group", "order", "having", "limit", "offset", "fetch", "union", "intersect",
+    "except", "minus", "window", "tablesample", "with", "for", "qualify", "prewhere", "final",
+];
+
+/// Words that close a FROM clause, after which a comma no longer separates tables
+const FROM_LIST_TERMINATORS: &[&str] = &[
+    "select", "where", "group", "having", "order", "limit", "offset", "fetch", "union",
+    "intersect", "except", "minus", "window", "qualify",
+];
+
+/// A token other than whitespace or a comment, with its byte range in the query
+struct SpannedToken {
+    token: Token,
+    start: usize,
+    end: usize,
+}
+
+/// The query nesting level opened by a parenthesis (or the query itself)
+struct Scope {
+    /// Holds a subquery, so FROM and JOIN in it name relations
+    relation: bool,
+    /// Inside a FROM clause, where a comma precedes another table
+    in_from_list: bool,
+}
+
+/// Rewrite a SELECT so every table it reads from is replaced by a row-limited
+/// subquery of at most `sample_rows` rows, returning the rewritten query and
+/// how many table references were sampled. Joins and aggregates then run over
+/// the sample only, so results are indicative rather than exact.
+///
+/// The query is tokenized with sqlparser in the dialect matching `quote`, so
+/// string literals and comments are never rewritten. Only plain (optionally
+/// schema-qualified) table names after FROM, JOIN or a FROM-list comma are
+/// sampled; table functions, derived tables and FROM inside function calls
+/// (e.g. `EXTRACT(YEAR FROM col)`) are left as they are. A query that doesn't
+/// tokenize is returned unchanged.
+pub fn sample_query_sources(
+    query: &str,
+    sample_rows: i32,
+    syntax: LimitSyntax,
+    quote: IdentifierQuote,
+) -> (String, usize) {
+    let trimmed = query.trim().trim_end_matches(';').trim_end();
+    let Some(tokens) = significant_tokens(trimmed, quote) else {
+        return (trimmed.to_string(), 0);
+    };
+
+    let mut output = String::with_capacity(trimmed.len() * 2);
+    let mut last_end = 0;
+    let mut sampled = 0;
+    let mut scopes = vec![Scope { relation: true, in_from_list: false }];
+
+    let mut i = 0;
+    while i < tokens.len() {
+        let (relation, in_from_list) = scopes
+            .last()
+            .map_or((false, false), |scope| (scope.relation, scope.in_from_list));
+        let mut table_follows = false;
+        match &tokens[i].token {
+            Token::LParen => {
+                let subquery = tokens
+                    .get(i + 1)
+                    .and_then(|next| bare_word(&next.token))
+                    .is_some_and(|word| word == "select" || word == "with");
+                scopes.push(Scope { relation: subquery, in_from_list: false });
+            }
+            Token::RParen if scopes.len() > 1 => {
+                scopes.pop();
+            }
+            Token::Comma => table_follows = relation && in_from_list,
+            token if relation => match bare_word(token).as_deref() {
+                Some("from") => {
+                    if let Some(scope) = scopes.last_mut() {
+                        scope.in_from_list = true;
+                    }
+                    table_follows = true;
+                }
+                Some("join") => table_follows = true,
+                Some(word) if FROM_LIST_TERMINATORS.contains(&word) => {
+                    if let Some(scope) = scopes.last_mut() {
+                        scope.in_from_list = false;
+                    }
+                }
+                _ => {}
+            },
+            _ => {}
+        }
+
+        if table_follows {
+            if let Some((parts, has_alias)) = plain_table_reference(&tokens[i + 1..]) {
+                let first = &tokens[i + 1];
+                let last = &tokens[i + parts];
+                let table = &trimmed[first.start..last.end];
+                let sample = apply_row_limit(&format!("SELECT * FROM {}", table), sample_rows, syntax);
+
+                // Keep the original name visible to the rest of the query unless it already has an alias
+                output.push_str(&trimmed[last_end..first.start]);
+                if has_alias {
+                    output.push_str(&format!("({})", sample));
+                } else {
+                    output.push_str(&format!("({}) {}", sample, &trimmed[last.start..last.end]));
+                }
+                last_end = last.end;
+                sampled += 1;
+                i += parts;
+            }
+        }
+        i += 1;
+    }
+
+    output.push_str(&trimmed[last_end..]);
+    (output, sampled)
+}
+
+/// Tokenize `query` in the dialect matching `quote`, keeping the byte range of
+/// every token that isn't whitespace or a comment
+fn significant_tokens(query: &str, quote: IdentifierQuote) -> Option<Vec<SpannedToken>> {
+    let dialect: Box<dyn Dialect> = match quote {
+        IdentifierQuote::DoubleQuote => Box::new(GenericDialect {}),
+        IdentifierQuote::Bracket => Box::new(MsSqlDialect {}),
+        IdentifierQuote::Backtick => Box::new(MySqlDialect {}),
+    };
+    let tokens = Tokenizer::new(dialect.as_ref(), query)
+        .with_unescape(false)
+        .tokenize_with_location()
+        .ok()?;
+
+    // Locations are 1-based lines and character columns; walk the query once
+    // to turn them into byte offsets
+    let mut chars = query.char_indices().peekable();
+    let (mut line, mut column) = (1, 1);
+    let mut starts = Vec::with_capacity(tokens.len());
+    for token in &tokens {
+        while (line, column) < (token.location.line, token.location.column) {
+            match chars.next() {
+                Some((_, '\n')) => {
+                    line += 1;
+                    column = 1;
+                }
+                Some(_) => column += 1,
+                None => break,
+            }
+        }
+        starts.push(chars.peek().map_or(query.len(), |&(offset, _)| offset));
+    }
+
+    Some(
+        tokens
+            .into_iter()
+            .enumerate()
+            .filter(|(_, token)| !matches!(token.token, Token::Whitespace(_)))
+            .map(|(index, token)| SpannedToken {
+                token: token.token,
+                start: starts[index],
+                end: starts.get(index + 1).copied().unwrap_or(query.len()),
+            })
+            .collect(),
+    )
+}
+
+/// The lowercased text of an unquoted word
+fn bare_word(token: &Token) -> Option<String> {
+    match token {
+        Token::Word(word) if word.quote_style.is_none() => Some(word.value.to_ascii_lowercase()),
+        _ => None,
+    }
+}
+
+/// How many tokens a plain `[catalog.][schema.]table` name at the start of
+/// `tokens` spans and whether an alias follows it. `None` when the tokens start
+/// with anything else, such as a derived table or a table function call.
+fn plain_table_reference(tokens: &[SpannedToken]) -> Option<(usize, bool)> {
+    let is_name = |token: &Token| match token {
+        Token::Word(word) if word.quote_style.is_some() => true,
+        Token::Word(word) => {
+            let word = word.value.to_ascii_lowercase();
+            !NON_ALIAS_KEYWORDS.contains(&word.as_str()) && !matches!(word.as_str(), "lateral" | "only" | "select")
+        }
+        _ => false,
+    };
+
+    if !is_name(&tokens.first()?.token) {
+        return None;
+    }
+    let mut parts = 1;
+    while parts < 5
+        && matches!(tokens.get(parts).map(|t| &t.token), Some(Token::Period))
+        && tokens.get(parts + 1).is_some_and(|t| is_name(&t.token))
+    {
+        parts += 2;
+    }
+
+    let has_alias = match tokens.get(parts).map(|t| &t.token) {
+        // A call such as `generate_series(1, 10)` rather than a table
+        Some(Token::LParen) => return None,
+        Some(Token::Word(word)) => {
+            word.quote_style.is_some() || !NON_ALIAS_KEYWORDS.contains(&word.value.to_ascii_lowercase().as_str())
+        }
+        _ => false,
+    };
+    Some((parts, has_alias))
+}
+
+/// Identifier quoting used by a SQL dialect
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IdentifierQuote {
+    /// `"name"` (PostgreSQL, Oracle, SQLite, DuckDB)
+    DoubleQuote,
+    /// `[name]` (SQL Server)
+    Bracket,
+    /// `` `name` `` (MySQL, ClickHouse)
+    Backtick,
+}
+
+/// Quote a possibly schema-qualified identifier (`schema.table`) part by part.
+/// Parts that are already quoted are kept; embedded closing quotes are escaped
+/// so user input such as a sort column cannot break out of the identifier.
+pub fn quote_identifier(name: &str, quote: IdentifierQuote) -> String {
+    let (open, close) = match quote {
+        IdentifierQuote::DoubleQuote => ('"', '"'),
+        IdentifierQuote::Bracket => ('[', ']'),
+        IdentifierQuote::Backtick => ('`', '`'),
+    };
+    let escaped_close = format!("{}{}", close, close);
+    let name = name.trim();
+    let already_quoted = name.len() >= 2
+        && name.starts_with(open)
+        && name.ends_with(close)
+        && !name[1..name.len() - 1].replace(&escaped_close, "").contains(close);
+    if already_quoted {
+        return name.to_string();
+    }
+
+    name.split('.')
+        .map(|part| format!("{}{}{}", open, part.replace(close, &escaped_close), close))
+        .collect::<Vec<_>>()
+        .join(".")
+}
+
+/// `SELECT *` for one page of a table in the given dialect. The table and sort
+/// column are quoted and the direction is normalized to ASC/DESC.
+pub fn table_page_query(
+    table_name: &str,
+    sort_column: Option<&str>,
+    sort_direction: Option<&str>,
+    page: i32,
+    limit: i32,
+    syntax: LimitSyntax,
+    quote: IdentifierQuote,
+) -> String {
+    let offset = (page.max(1) - 1) * limit;
+    let mut query = format!("SELECT * FROM {}", quote_identifier(table_name, quote));
+
+    let order_by = sort_column.map(|column| {
+        let direction = match sort_direction {
+            Some(d) if d.trim().eq_ignore_ascii_case("desc") => "DESC",
+            _ => "ASC",
+        };
+        format!(" ORDER BY {} {}", quote_identifier(column, quote), direction)
+    });
+
+    match syntax {
+        LimitSyntax::Limit => {
+            query.push_str(order_by.as_deref().unwrap_or_default());
+            query.push_str(&format!(" LIMIT {} OFFSET {}", limit, offset));
+            query
+        }
+        LimitSyntax::Top => {
+            // OFFSET .. FETCH needs an ORDER BY
+            query.push_str(order_by.as_deref().unwrap_or(" ORDER BY (SELECT NULL)"));
+            query.push_str(&format!(" OFFSET {} ROWS FETCH NEXT {} ROWS ONLY", offset, limit));
+            query
+        }
+        LimitSyntax::FetchFirst => {
+            query.push_str(order_by.as_deref().unwrap_or_default());
+            query.push_str(&format!(" OFFSET {} ROWS FETCH NEXT {} ROWS ONLY", offset, limit));
+            query
+        }
+        LimitSyntax::RowNum => {
+            query.push_str(order_by.as_deref().unwrap_or_default());
+            format!(
+                "SELECT * FROM (SELECT t.*, ROWNUM rn FROM ({}) t WHERE ROWNUM <= {}) WHERE rn > {}",
+                query,
+                offset + limit,
+                offset
+            )
+        }
+    }
+}
+
+/// Make column names unique so result rows can be keyed by column name.
+/// Repeated names get a numeric suffix, e.g. `id`, `id_2`, `id_3`.
+pub fn dedupe_column_names(columns: Vec<String>) -> Vec<String> {
+    let mut seen = std::collections::HashSet::new();
+    columns
+        .into_iter()
+        .map(|name| {
+            if seen.insert(name.clone()) {
+                return name;
+            }
+            let mut suffix = 2;
+            loop {
+                let candidate = format!("{}_{}", name, suffix);
+                if seen.insert(candidate.clone()) {
+                    return candidate;
+                }
+                suffix += 1;
+            }
+        })
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -190,4 +573,214 @@ mod tests {
         add_ssl_parameter(&mut conn_str, "sslrootcert", "/path/to/cert");
         assert_eq!(conn_str, "postgres://user@localhost/db?sslmode=disable&sslrootcert=/path/to/cert");
     }
+
+    #[test]
+    fn test_apply_row_limit_per_dialect() {
+        let query = "SELECT * FROM users;";
+        assert_eq!(apply_row_limit(query, 10, LimitSyntax::Limit), "SELECT * FROM users LIMIT 10");
+        assert_eq!(apply_row_limit(query, 10, LimitSyntax::Top), "SELECT TOP 10 * FROM users");
+        assert_eq!(
+            apply_row_limit(query, 10, LimitSyntax::FetchFirst),
+            "SELECT * FROM users FETCH FIRST 10 ROWS ONLY"
+        );
+        assert_eq!(
+            apply_row_limit(query, 10, LimitSyntax::RowNum),
+            "SELECT * FROM (SELECT * FROM users) WHERE ROWNUM <= 10"
+        );
+    }
+
+    #[test]
+    fn test_sample_query_sources() {
+        let (query, sampled) = sample_query_sources(
+            "SELECT u.name, COUNT(*) FROM users u JOIN orders ON orders.user_id = u.id GROUP BY u.name;",
+            100,
+            LimitSyntax::Limit,
+            IdentifierQuote::DoubleQuote,
+        );
+        assert_eq!(sampled, 2);
+        assert_eq!(
+            query,
+            "SELECT u.name, COUNT(*) FROM (SELECT * FROM users LIMIT 100) u JOIN (SELECT * FROM orders LIMIT 100) orders ON orders.user_id = u.id GROUP BY u.name"
+        );
+    }
+
+    #[test]
+    fn test_sample_query_sources_skips_function_arguments() {
+        let (query, sampled) = sample_query_sources(
+            "SELECT EXTRACT(YEAR FROM created_at) FROM public.events",
+            10,
+            LimitSyntax::Top,
+            IdentifierQuote::Bracket,
+        );
+        assert_eq!(sampled, 1);
+        assert_eq!(
+            query,
+            "SELECT EXTRACT(YEAR FROM created_at) FROM (SELECT TOP 10 * FROM public.events) events"
+        );
+    }
+
+    #[test]
+    fn test_sample_query_sources_comma_joins() {
+        let (query, sampled) = sample_query_sources(
+            "SELECT * FROM users u, orders o WHERE o.user_id = u.id GROUP BY u.id, o.id",
+            5,
+            LimitSyntax::Limit,
+            IdentifierQuote::DoubleQuote,
+        );
+        assert_eq!(sampled, 2);
+        assert_eq!(
+            query,
+            "SELECT * FROM (SELECT * FROM users LIMIT 5) u, (SELECT * FROM orders LIMIT 5) o WHERE o.user_id = u.id GROUP BY u.id, o.id"
+        );
+    }
+
+    #[test]
+    fn test_sample_query_sources_leaves_literals_and_comments() {
+        let (query, sampled) = sample_query_sources(
+            "SELECT 'sold from stock' AS note -- from archive\nFROM items WHERE label <> 'it''s a join'",
+            5,
+            LimitSyntax::Limit,
+            IdentifierQuote::DoubleQuote,
+        );
+        assert_eq!(sampled, 1);
+        assert_eq!(
+            query,
+            "SELECT 'sold from stock' AS note -- from archive\nFROM (SELECT * FROM items LIMIT 5) items WHERE label <> 'it''s a join'"
+        );
+    }
+
+    #[test]
+    fn test_sample_query_sources_skips_table_functions() {
+        let (query, sampled) = sample_query_sources(
+            "SELECT * FROM generate_series(1, 10) g JOIN events ON events.id = g, (SELECT 1) one",
+            5,
+            LimitSyntax::Limit,
+            IdentifierQuote::DoubleQuote,
+        );
+        assert_eq!(sampled, 1);
+        assert_eq!(
+            query,
+            "SELECT * FROM generate_series(1, 10) g JOIN (SELECT * FROM events LIMIT 5) events ON events.id = g, (SELECT 1) one"
+        );
+    }
+
+    #[test]
+    fn test_sample_query_sources_quoted_names() {
+        let (query, sampled) = sample_query_sources(
+            "SELECT * FROM `shop`.`orders` WHERE note = 'a \\' from b'",
+            5,
+            LimitSyntax::Limit,
+            IdentifierQuote::Backtick,
+        );
+        assert_eq!(sampled, 1);
+        assert_eq!(
+            query,
+            "SELECT * FROM (SELECT * FROM `shop`.`orders` LIMIT 5) `orders` WHERE note = 'a \\' from b'"
+        );
+
+        let (query, sampled) =
+            sample_query_sources("SELECT * FROM [dbo].[Orders] o", 5, LimitSyntax::Top, IdentifierQuote::Bracket);
+        assert_eq!(sampled, 1);
+        assert_eq!(query, "SELECT * FROM (SELECT TOP 5 * FROM [dbo].[Orders]) o");
+    }
+
+    #[test]
+    fn test_sample_query_sources_unterminated_literal_is_unchanged() {
+        let (query, sampled) =
+            sample_query_sources("SELECT * FROM t WHERE a = 'open", 5, LimitSyntax::Limit, IdentifierQuote::DoubleQuote);
+        assert_eq!(sampled, 0);
+        assert_eq!(query, "SELECT * FROM t WHERE a = 'open");
+    }
+
+    #[test]
+    fn test_apply_row_limit_top_with_distinct() {
+        assert_eq!(
+            apply_row_limit("select distinct name from users", 5, LimitSyntax::Top),
+            "SELECT distinct TOP 5 name from users"
+        );
+    }
+
+    #[test]
+    fn test_apply_row_limit_keeps_existing_limit() {
+        assert_eq!(apply_row_limit("SELECT * FROM t LIMIT 5", 10, LimitSyntax::Limit), "SELECT * FROM t LIMIT 5");
+        assert_eq!(apply_row_limit("SELECT TOP 5 * FROM t", 10, LimitSyntax::Top), "SELECT TOP 5 * FROM t");
+        assert_eq!(
+            apply_row_limit("SELECT * FROM t FETCH FIRST 5 ROWS ONLY", 10, LimitSyntax::RowNum),
+            "SELECT * FROM t FETCH FIRST 5 ROWS ONLY"
+        );
+    }
+
+    #[test]
+    fn test_apply_row_limit_ignores_limit_in_identifiers() {
+        assert_eq!(
+            apply_row_limit("SELECT credit_limit FROM accounts", 10, LimitSyntax::Limit),
+            "SELECT credit_limit FROM accounts LIMIT 10"
+        );
+    }
+
+    #[test]
+    fn test_apply_row_limit_top_skips_non_select() {
+        let query = "WITH x AS (SELECT 1 AS a) SELECT a FROM x";
+        assert_eq!(apply_row_limit(query, 10, LimitSyntax::Top), query);
+    }
+
+    #[test]
+    fn test_dedupe_column_names() {
+        let columns = vec!["id".to_string(), "name".to_string(), "id".to_string(), "id".to_string()];
+        assert_eq!(dedupe_column_names(columns), vec!["id", "name", "id_2", "id_3"]);
+    }
+
+    #[test]
+    fn test_dedupe_column_names_avoids_existing_suffix() {
+        let columns = vec!["id".to_string(), "id_2".to_string(), "id".to_string()];
+        assert_eq!(dedupe_column_names(columns), vec!["id", "id_2", "id_3"]);
+    }
+
+    #[test]
+    fn test_table_page_query_per_dialect() {
+        assert_eq!(
+            table_page_query("users", Some("name"), Some("desc"), 3, 10, LimitSyntax::Limit, IdentifierQuote::DoubleQuote),
+            r#"SELECT * FROM "users" ORDER BY "name" DESC LIMIT 10 OFFSET 20"#
+        );
+        assert_eq!(
+            table_page_query("dbo.users", Some("name"), None, 2, 25, LimitSyntax::Top, IdentifierQuote::Bracket),
+            "SELECT * FROM [dbo].[users] ORDER BY [name] ASC OFFSET 25 ROWS FETCH NEXT 25 ROWS ONLY"
+        );
+        assert_eq!(
+            table_page_query("dbo.users", None, None, 1, 25, LimitSyntax::Top, IdentifierQuote::Bracket),
+            "SELECT * FROM [dbo].[users] ORDER BY (SELECT NULL) OFFSET 0 ROWS FETCH NEXT 25 ROWS ONLY"
+        );
+        assert_eq!(
+            table_page_query("HR.EMPLOYEES", Some("SALARY"), Some("DESC"), 2, 50, LimitSyntax::FetchFirst, IdentifierQuote::DoubleQuote),
+            r#"SELECT * FROM "HR"."EMPLOYEES" ORDER BY "SALARY" DESC OFFSET 50 ROWS FETCH NEXT 50 ROWS ONLY"#
+        );
+        assert_eq!(
+            table_page_query("EMPLOYEES", None, None, 2, 50, LimitSyntax::RowNum, IdentifierQuote::DoubleQuote),
+            r#"SELECT * FROM (SELECT t.*, ROWNUM rn FROM (SELECT * FROM "EMPLOYEES") t WHERE ROWNUM <= 100) WHERE rn > 50"#
+        );
+    }
+
+    #[test]
+    fn test_sort_column_cannot_inject_sql() {
+        let query = table_page_query(
+            "users",
+            Some("name]; DROP TABLE users; --"),
+            Some("ASC; DROP TABLE users"),
+            1,
+            10,
+            LimitSyntax::Top,
+            IdentifierQuote::Bracket,
+        );
+        assert_eq!(
+            query,
+            "SELECT * FROM [users] ORDER BY [name]]; DROP TABLE users; --] ASC OFFSET 0 ROWS FETCH NEXT 10 ROWS ONLY"
+        );
+        assert_eq!(quote_identifier(r#"a"b"#, IdentifierQuote::DoubleQuote), r#""a""b""#);
+        assert_eq!(quote_identifier("[Order Details]", IdentifierQuote::Bracket), "[Order Details]");
+        assert_eq!(
+            quote_identifier("[a]; DROP TABLE users; --[b]", IdentifierQuote::Bracket),
+            "[[a]]; DROP TABLE users; --[b]]]"
+        );
+        assert_eq!(quote_identifier("x`y", IdentifierQuote::Backtick), "`x``y`");
+    }
 }
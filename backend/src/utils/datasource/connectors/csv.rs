@@ -4,10 +4,10 @@ use async_trait::async_trait;
 use serde_json::{json, Value};
 use std::error::Error;
 use std::fs::File;
-use std::io::{BufReader, BufRead, Read};
+use std::io::{BufReader, BufRead};
 use std::path::Path;
 use std::time::Instant;
-use tracing::{debug, error, info};
+use tracing::{debug, error, info, warn};
 use csv::ReaderBuilder;
 
 pub struct CsvConnector {
@@ -98,6 +98,8 @@ impl CsvConnector {
                 column_count: 0,
                 estimated_row_count: None,
             },
+            duckdb_wrapper: None,
+            use_duckdb: false,
         };
 
         // Load file metadata
@@ -181,7 +183,7 @@ impl CsvConnector {
 
         // Skip rows if configured
         for _ in 0..self.skip_rows {
-            if !reader.records().next().is_some() {
+            if reader.records().next().is_none() {
                 break;
             }
         }
@@ -198,7 +200,6 @@ impl CsvConnector {
         let mut float_count = 0;
         let mut bool_count = 0;
         let mut date_count = 0;
-        let null_count = 0;
         let total_non_null = values.iter().filter(|v| !v.is_empty() && *v != "NULL" && *v != "null").count();
 
         for value in values {
@@ -381,7 +382,7 @@ impl DataSourceConnector for CsvConnector {
     async fn execute_query(&self, query: &str, limit: i32) -> Result<Value, Box<dyn Error + Send + Sync>> {
         // Use DuckDB for full SQL support if available
         if self.use_duckdb {
-            if let Some(ref mut wrapper) = self.duckdb_wrapper {
+            if let Some(ref wrapper) = self.duckdb_wrapper {
                 debug!("Executing CSV query with DuckDB: {}", query);
                 match wrapper.execute_query(query, limit).await {
                     Ok(result) => {
@@ -511,7 +512,6 @@ impl DataSourceConnector for CsvConnector {
                 match wrapper.fetch_schema().await {
                     Ok(schema) => {
                         info!("DuckDB CSV schema fetched successfully");
-                        self.schema_cache = Some(schema.clone());
                         return Ok(schema);
                     },
                     Err(e) => {
@@ -587,8 +587,6 @@ impl DataSourceConnector for CsvConnector {
             .and_then(|s| s.to_str())
             .unwrap_or("data");
 
-        let schema = self.fetch_schema().await?;
-
         Ok(json!({
             "database_schema": "file",
             "statistics": {
@@ -635,7 +633,7 @@ impl DataSourceConnector for CsvConnector {
                         "primary_keys": [],
                         "foreign_keys": [],
                         "row_count": self.file_metadata.estimated_row_count.unwrap_or(0),
-                        "sample_data": sample_rows.iter().enumerate().map(|(i, row)| {
+                        "sample_data": sample_rows.iter().map(|row| {
                             let mut row_obj = json!({});
                             for (j, val) in row.iter().enumerate() {
                                 if let Some(col_name) = headers.get(j) {
@@ -0,0 +1,438 @@
+use super::super::core::base::{format_bytes, ColumnSummary, DataSourceConnector};
+use super::common::dedupe_column_names;
+use super::table_filters::TableFilters;
+use super::table_keyset::{cursor_literal, keyset_operator};
+use super::table_sort::SortKey;
+use async_trait::async_trait;
+use duckdb::{AccessMode, Config, Connection};
+use serde_json::{json, Value};
+use std::error::Error;
+use std::path::Path;
+use tracing::{debug, info};
+
+/// Connector for a DuckDB database file, e.g. one created by a CSV import.
+/// DuckDB connections are blocking and not `Sync`, so each call opens the file
+/// read-only on a blocking thread.
+pub struct DuckDbConnector {
+    file_path: String,
+}
+
+type QueryRows = (Vec<String>, Vec<Vec<Value>>);
+
+impl DuckDbConnector {
+    pub fn new(config: &Value) -> Result<Self, Box<dyn Error + Send + Sync>> {
+        let file_path = config
+            .get("file_path")
+            .and_then(|v| v.as_str())
+            .ok_or("Missing file_path in configuration")?;
+
+        if !Path::new(file_path).exists() {
+            return Err(format!("File not found: {}", file_path).into());
+        }
+
+        info!("DuckDB connector configured for file: {}", file_path);
+        Ok(Self {
+            file_path: file_path.to_string(),
+        })
+    }
+
+    async fn fetch(&self, sql: String) -> Result<QueryRows, Box<dyn Error + Send + Sync>> {
+        let file_path = self.file_path.clone();
+        tokio::task::spawn_blocking(move || fetch_rows(&file_path, &sql))
+            .await
+            .map_err(|e| format!("DuckDB query task failed: {}", e))?
+    }
+
+    async fn table_columns(&self, table: &str) -> Result<Vec<Value>, Box<dyn Error + Send + Sync>> {
+        let (_, rows) = self
+            .fetch(format!(
+                "SELECT column_name, data_type, is_nullable FROM information_schema.columns \
+                 WHERE table_name = '{}' ORDER BY ordinal_position",
+                table.replace('\'', "''")
+            ))
+            .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| {
+                json!({
+                    "column_name": row[0],
+                    "data_type": row[1],
+                    "is_nullable": row[2] == json!("YES"),
+                })
+            })
+            .collect())
+    }
+
+    async fn row_count(&self, table: &str) -> i64 {
+        self.fetch(format!("SELECT COUNT(*) FROM {}", quote_identifier(table)))
+            .await
+            .ok()
+            .and_then(|(_, rows)| rows.first().and_then(|row| row[0].as_i64()))
+            .unwrap_or(0)
+    }
+}
+
+fn quote_identifier(name: &str) -> String {
+    format!("\"{}\"", name.replace('"', "\"\""))
+}
+
+fn open_read_only(file_path: &str) -> Result<Connection, Box<dyn Error + Send + Sync>> {
+    let config = Config::default().access_mode(AccessMode::ReadOnly)?;
+    Ok(Connection::open_with_flags(file_path, config)?)
+}
+
+fn fetch_rows(file_path: &str, sql: &str) -> Result<QueryRows, Box<dyn Error + Send + Sync>> {
+    let connection = open_read_only(file_path)?;
+    let mut stmt = connection.prepare(sql)?;
+
+    let mut result_rows = Vec::new();
+    {
+        let mut rows = stmt.query([])?;
+        while let Some(row) = rows.next()? {
+            let column_count = row.as_ref().column_count();
+            let mut row_data = Vec::with_capacity(column_count);
+            for i in 0..column_count {
+                row_data.push(duckdb_value_to_json(row.get::<_, duckdb::types::Value>(i)?));
+            }
+            result_rows.push(row_data);
+        }
+    }
+
+    Ok((dedupe_column_names(stmt.column_names()), result_rows))
+}
+
+pub(super) fn duckdb_value_to_json(value: duckdb::types::Value) -> Value {
+    use duckdb::types::Value as DuckValue;
+    match value {
+        DuckValue::Null => Value::Null,
+        DuckValue::Boolean(b) => json!(b),
+        DuckValue::TinyInt(i) => json!(i),
+        DuckValue::SmallInt(i) => json!(i),
+        DuckValue::Int(i) => json!(i),
+        DuckValue::BigInt(i) => json!(i),
+        DuckValue::UTinyInt(i) => json!(i),
+        DuckValue::USmallInt(i) => json!(i),
+        DuckValue::UInt(i) => json!(i),
+        DuckValue::UBigInt(i) => json!(i.to_string()),
+        DuckValue::Float(f) => json!(f),
+        DuckValue::Double(d) => json!(d),
+        DuckValue::Text(s) => json!(s),
+        DuckValue::Blob(_) => json!("[BLOB]"),
+        other => json!(format!("{:?}", other)),
+    }
+}
+
+#[async_trait]
+impl DataSourceConnector for DuckDbConnector {
+    async fn test_connection(&mut self) -> Result<bool, Box<dyn Error + Send + Sync>> {
+        self.fetch("SELECT 1".to_string()).await?;
+        Ok(true)
+    }
+
+    async fn execute_query(&self, query: &str, limit: i32) -> Result<Value, Box<dyn Error + Send + Sync>> {
+        debug!("Executing DuckDB query: {}", query);
+        let start = std::time::Instant::now();
+        let (columns, rows) = self.fetch(self.apply_limit(query, limit)).await?;
+
+        Ok(json!({
+            "columns": columns,
+            "row_count": rows.len(),
+            "rows": rows,
+            "execution_time_ms": start.elapsed().as_millis() as i64
+        }))
+    }
+
+    async fn describe_query_columns(&self, query: &str) -> Result<Vec<String>, Box<dyn Error + Send + Sync>> {
+        let (columns, _) = self
+            .fetch(format!("SELECT * FROM ({}) AS q LIMIT 0", query.trim().trim_end_matches(';')))
+            .await?;
+        Ok(columns)
+    }
+
+    async fn get_table_data_with_pagination(
+        &self,
+        table_name: &str,
+        page: i32,
+        limit: i32,
+        sort_column: Option<&str>,
+        sort_direction: Option<&str>,
+    ) -> Result<Value, Box<dyn Error + Send + Sync>> {
+        let offset = (page.max(1) - 1) * limit;
+        let mut query = format!("SELECT * FROM {}", quote_identifier(table_name));
+
+        if let Some(sort_col) = sort_column {
+            let direction = match sort_direction {
+                Some(d) if d.eq_ignore_ascii_case("desc") => "DESC",
+                _ => "ASC",
+            };
+            query.push_str(&format!(" ORDER BY {} {}", quote_identifier(sort_col), direction));
+        }
+        query.push_str(&format!(" LIMIT {} OFFSET {}", limit, offset));
+
+        let mut result = self.execute_query(&query, limit).await?;
+        result["total_rows"] = json!(self.row_count(table_name).await);
+        result["page"] = json!(page);
+        result["page_size"] = json!(limit);
+        Ok(result)
+    }
+
+    fn supports_cursor_pagination(&self) -> bool {
+        true
+    }
+
+    async fn get_table_data_after_cursor(
+        &self,
+        table_name: &str,
+        limit: i32,
+        key: &SortKey,
+        cursor: &Value,
+        filters: &TableFilters,
+        columns: Option<&[String]>,
+    ) -> Result<Value, Box<dyn Error + Send + Sync>> {
+        let column = quote_identifier(&key.column);
+        let select_list = match columns {
+            Some(columns) if !columns.is_empty() => columns.iter().map(|c| quote_identifier(c)).collect::<Vec<_>>().join(", "),
+            _ => "*".to_string(),
+        };
+        let query = format!(
+            "SELECT {} FROM {} WHERE {} {} {} ORDER BY {} {} LIMIT {}",
+            select_list,
+            quote_identifier(table_name),
+            column,
+            keyset_operator(key),
+            cursor_literal(cursor)?,
+            column,
+            if key.descending() { "DESC" } else { "ASC" },
+            limit
+        );
+
+        let mut result = self.execute_query(&query, limit).await?;
+        result["total_rows"] = json!(self.row_count(table_name).await);
+        result["page_size"] = json!(limit);
+        if !filters.is_empty() {
+            result["note"] = json!("Filtering is not supported for this datasource type; showing unfiltered rows");
+        }
+        Ok(result)
+    }
+
+    async fn fetch_schema(&self) -> Result<Value, Box<dyn Error + Send + Sync>> {
+        let mut schema = json!({
+            "tables": {},
+            "refreshed_at": chrono::Utc::now().to_rfc3339()
+        });
+
+        for table in self.list_tables().await? {
+            schema["tables"][&table] = json!(self.table_columns(&table).await?);
+        }
+
+        Ok(schema)
+    }
+
+    async fn list_tables(&self) -> Result<Vec<String>, Box<dyn Error + Send + Sync>> {
+        let (_, rows) = self
+            .fetch("SELECT table_name FROM information_schema.tables ORDER BY table_name".to_string())
+            .await?;
+        Ok(rows
+            .into_iter()
+            .filter_map(|row| row[0].as_str().map(str::to_string))
+            .collect())
+    }
+
+    async fn analyze_database(&self) -> Result<Value, Box<dyn Error + Send + Sync>> {
+        let tables = self.list_tables().await?;
+        let mut table_info = Vec::new();
+        for table in &tables {
+            table_info.push(json!({
+                "name": table,
+                "row_count": self.row_count(table).await,
+                "column_count": self.table_columns(table).await?.len(),
+            }));
+        }
+
+        Ok(json!({
+            "database_type": "duckdb",
+            "table_count": tables.len(),
+            "tables": table_info,
+        }))
+    }
+
+    async fn get_tables_schema(&self, tables: Vec<&str>) -> Result<Value, Box<dyn Error + Send + Sync>> {
+        let mut result = json!({});
+        for table in tables {
+            let columns = self.table_columns(table).await?;
+            if columns.is_empty() {
+                continue;
+            }
+            let sample = self
+                .execute_query(&format!("SELECT * FROM {}", quote_identifier(table)), 5)
+                .await?;
+            result[table] = json!({
+                "columns": columns,
+                "row_count": self.row_count(table).await,
+                "sample_data": sample["rows"],
+            });
+        }
+        Ok(result)
+    }
+
+    async fn search_tables(&self, pattern: &str) -> Result<Value, Box<dyn Error + Send + Sync>> {
+        let pattern = pattern.replace('%', "").to_lowercase();
+        let mut results = Vec::new();
+        for table in self.list_tables().await? {
+            if table.to_lowercase().contains(&pattern) {
+                results.push(json!({
+                    "name": table,
+                    "description": null,
+                    "column_count": self.table_columns(&table).await?.len(),
+                }));
+            }
+        }
+
+        Ok(json!({
+            "matches": results,
+            "total_matches": results.len(),
+        }))
+    }
+
+    async fn list_table_columns(&self, table: &str) -> Result<Vec<ColumnSummary>, Box<dyn Error + Send + Sync>> {
+        let (_, rows) = self
+            .fetch(format!(
+                "SELECT c.column_name, c.data_type, c.is_nullable = 'YES', \
+                 EXISTS (SELECT 1 FROM duckdb_constraints() k \
+                         WHERE k.table_name = c.table_name AND k.constraint_type = 'PRIMARY KEY' \
+                           AND list_contains(k.constraint_column_names, c.column_name)) \
+                 FROM information_schema.columns c WHERE c.table_name = '{}' ORDER BY c.ordinal_position",
+                table.replace('\'', "''")
+            ))
+            .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| {
+                let primary_key = row[3].as_bool().unwrap_or(false);
+                ColumnSummary {
+                    name: row[0].as_str().unwrap_or_default().to_string(),
+                    data_type: row[1].as_str().unwrap_or_default().to_string(),
+                    nullable: !primary_key && row[2].as_bool().unwrap_or(true),
+                    primary_key,
+                }
+            })
+            .collect())
+    }
+
+    async fn get_related_tables(&self, table: &str) -> Result<Value, Box<dyn Error + Send + Sync>> {
+        // Imported files have no foreign keys
+        let main_schema = self.get_tables_schema(vec![table]).await?;
+        if main_schema.get(table).is_none() {
+            return Err("Table not found".into());
+        }
+
+        Ok(json!({
+            "main_table": main_schema[table],
+            "related_tables": {},
+            "relationship_count": 0,
+        }))
+    }
+
+    async fn get_database_stats(&self) -> Result<Value, Box<dyn Error + Send + Sync>> {
+        let size_bytes = std::fs::metadata(&self.file_path).map(|m| m.len()).unwrap_or(0);
+        let mut table_stats = Vec::new();
+        for table in self.list_tables().await? {
+            table_stats.push(json!({
+                "name": table,
+                "row_count": self.row_count(&table).await,
+            }));
+        }
+
+        Ok(json!({
+            "database_type": "duckdb",
+            "size_bytes": size_bytes,
+            "size": format_bytes(size_bytes),
+            "table_count": table_stats.len(),
+            "tables": table_stats,
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::table_keyset::next_cursor;
+    use super::*;
+
+    #[tokio::test]
+    async fn lists_the_columns_of_a_known_table() {
+        let dir = tempfile::tempdir().unwrap();
+        let db_path = dir.path().join("shop.duckdb");
+        Connection::open(&db_path)
+            .unwrap()
+            .execute_batch("CREATE TABLE orders (id INTEGER PRIMARY KEY, customer VARCHAR NOT NULL, note VARCHAR)")
+            .unwrap();
+
+        let connector = DuckDbConnector::new(&json!({ "file_path": db_path.to_str().unwrap() })).unwrap();
+        let columns = connector.list_table_columns("orders").await.unwrap();
+
+        let summary = |name: &str, data_type: &str, nullable: bool, primary_key: bool| ColumnSummary {
+            name: name.to_string(),
+            data_type: data_type.to_string(),
+            nullable,
+            primary_key,
+        };
+        assert_eq!(
+            columns,
+            vec![
+                summary("id", "INTEGER", false, true),
+                summary("customer", "VARCHAR", false, false),
+                summary("note", "VARCHAR", true, false),
+            ]
+        );
+        assert!(connector.list_table_columns("missing").await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn keyset_pages_match_offset_pages() {
+        let dir = tempfile::tempdir().unwrap();
+        let db_path = dir.path().join("events.duckdb");
+        Connection::open(&db_path)
+            .unwrap()
+            .execute_batch(
+                "CREATE TABLE events (id INTEGER PRIMARY KEY, name VARCHAR);
+                 INSERT INTO events SELECT i, 'event ' || i FROM range(1, 26) t(i);",
+            )
+            .unwrap();
+        let connector = DuckDbConnector::new(&json!({ "file_path": db_path.to_str().unwrap() })).unwrap();
+
+        let mut by_offset = Vec::new();
+        for page in 1..=3 {
+            let result = connector
+                .get_table_data_with_pagination("events", page, 10, Some("id"), Some("asc"))
+                .await
+                .unwrap();
+            by_offset.extend(result["rows"].as_array().unwrap().clone());
+        }
+
+        let key = SortKey {
+            column: "id".to_string(),
+            direction: None,
+            nulls: None,
+        };
+        let filters = TableFilters::default();
+        let first = connector
+            .get_table_data_with_pagination("events", 1, 10, Some("id"), Some("asc"))
+            .await
+            .unwrap();
+        let mut by_cursor = first["rows"].as_array().unwrap().clone();
+        let mut cursor = next_cursor(&first, "id", 10);
+        while !cursor.is_null() {
+            let result = connector
+                .get_table_data_after_cursor("events", 10, &key, &cursor, &filters, None)
+                .await
+                .unwrap();
+            by_cursor.extend(result["rows"].as_array().unwrap().clone());
+            cursor = next_cursor(&result, "id", 10);
+        }
+
+        assert_eq!(by_offset.len(), 25);
+        assert_eq!(by_cursor, by_offset);
+    }
+}
@@ -6,7 +6,13 @@ use std::error::Error;
 use std::path::Path;
 use std::time::Instant;
 use tracing::{debug, error, info, warn};
-use calamine::{open_workbook, Reader, DataType, Xlsx};
+use calamine::{open_workbook, Data, Reader, Xlsx};
+
+/// Sheet names, headers and rows
+type SheetPreview = (Vec<String>, Vec<String>, Vec<Vec<String>>);
+
+/// Headers and rows of one sheet
+type SheetRows = (Vec<String>, Vec<Vec<String>>);
 
 pub struct ExcelConnector {
     file_path: String,
@@ -129,7 +135,7 @@ impl ExcelConnector {
         self.file_metadata.last_modified = metadata.modified()?.into();
 
         // Get sheet names
-        let mut excel: Xlsx<_> = open_workbook(&self.file_path)?;
+        let excel: Xlsx<_> = open_workbook(&self.file_path)?;
         self.file_metadata.sheet_names = excel.sheet_names().to_vec();
         self.file_metadata.total_sheets = self.file_metadata.sheet_names.len();
 
@@ -143,15 +149,13 @@ impl ExcelConnector {
     fn get_sheet_info(&self, sheet_name: &str) -> Result<SheetInfo, Box<dyn Error + Send + Sync>> {
         let mut excel: Xlsx<_> = open_workbook(&self.file_path)?;
 
-        if let Some(range) = excel.worksheet_range(sheet_name) {
-            let range = range?;
-            Ok(SheetInfo {
+        match excel.worksheet_range(sheet_name) {
+            Ok(range) => Ok(SheetInfo {
                 name: sheet_name.to_string(),
                 row_count: range.height(),
                 column_count: range.width(),
-            })
-        } else {
-            Err(format!("Sheet '{}' not found", sheet_name).into())
+            }),
+            Err(_) => Err(format!("Sheet '{}' not found", sheet_name).into()),
         }
     }
 
@@ -159,12 +163,10 @@ impl ExcelConnector {
         Ok(self.file_metadata.sheet_names.clone())
     }
 
-    fn read_sheet_data(&self, sheet_name: &str, limit: Option<usize>) -> Result<(Vec<String>, Vec<Vec<String>>), Box<dyn Error + Send + Sync>> {
+    fn read_sheet_data(&self, sheet_name: &str, limit: Option<usize>) -> Result<SheetRows, Box<dyn Error + Send + Sync>> {
         let mut excel: Xlsx<_> = open_workbook(&self.file_path)?;
 
-        if let Some(range) = excel.worksheet_range(sheet_name) {
-            let range = range?;
-
+        if let Ok(range) = excel.worksheet_range(sheet_name) {
             let mut headers = Vec::new();
             let mut rows = Vec::new();
             let mut row_count = 0;
@@ -190,12 +192,15 @@ impl ExcelConnector {
 
                 let row_data: Vec<String> = row.iter()
                     .map(|cell| match cell {
-                        DataType::Empty => String::new(),
-                        DataType::String(s) => s.clone(),
-                        DataType::Float(f) => f.to_string(),
-                        DataType::Int(i) => i.to_string(),
-                        DataType::Bool(b) => b.to_string(),
-                        DataType::Error(e) => format!("ERROR: {:?}", e),
+                        Data::Empty => String::new(),
+                        Data::String(s) => s.clone(),
+                        Data::Float(f) => f.to_string(),
+                        Data::Int(i) => i.to_string(),
+                        Data::Bool(b) => b.to_string(),
+                        Data::Error(e) => format!("ERROR: {:?}", e),
+                        Data::DateTime(dt) => dt.to_string(),
+                        Data::DateTimeIso(dt) => dt.clone(),
+                        Data::DurationIso(d) => d.clone(),
                     })
                     .collect();
 
@@ -230,12 +235,10 @@ impl ExcelConnector {
         let mut float_count = 0;
         let mut bool_count = 0;
         let mut date_count = 0;
-        let mut null_count = 0;
-        let total_non_null = values.iter().filter(|v| !v.is_empty() && v != "NULL" && v != "null").count();
+        let total_non_null = values.iter().filter(|v| !v.is_empty() && *v != "NULL" && *v != "null").count();
 
         for value in values {
             if value.is_empty() || value == "NULL" || value == "null" {
-                null_count += 1;
                 continue;
             }
 
@@ -305,6 +308,17 @@ impl ExcelConnector {
             Err("No sheets found in Excel file".into())
         }
     }
+
+    /// Sheet names, headers and the first `limit` rows of the configured (or
+    /// first) sheet, for upload previews
+    pub fn preview_rows(&self, limit: usize) -> Result<SheetPreview, Box<dyn Error + Send + Sync>> {
+        let sheet_name = match &self.sheet_name {
+            Some(sheet) => sheet.clone(),
+            None => self.get_active_sheet_name()?,
+        };
+        let (headers, rows) = self.read_sheet_data(&sheet_name, Some(limit))?;
+        Ok((self.get_all_sheet_names()?, headers, rows))
+    }
 }
 
 #[async_trait]
@@ -351,7 +365,6 @@ impl DataSourceConnector for ExcelConnector {
         }
 
         // Fallback to basic Excel operations for simple SELECT * queries
-        let default_sheet_name = self.get_active_sheet_name()?;
         let sheet_names = self.get_all_sheet_names()?;
 
         // For Excel, we only support basic SELECT * FROM [table] queries
@@ -413,8 +426,8 @@ impl DataSourceConnector for ExcelConnector {
             if let Some(col_index) = headers.iter().position(|h| h == sort_col) {
                 let direction = sort_direction.unwrap_or("ASC");
                 rows.sort_by(|a, b| {
-                    let a_val = a.get(col_index).unwrap_or(&"".to_string());
-                    let b_val = b.get(col_index).unwrap_or(&"".to_string());
+                    let a_val = a.get(col_index).map(String::as_str).unwrap_or("");
+                    let b_val = b.get(col_index).map(String::as_str).unwrap_or("");
 
                     if direction == "DESC" {
                         b_val.cmp(a_val)
@@ -517,7 +530,7 @@ impl DataSourceConnector for ExcelConnector {
                         }
 
                         let data_type = Self::infer_data_type(&values);
-                        let nullable = values.iter().filter(|v| v.is_empty() || v == "NULL" || v == "null").count() > 0;
+                        let nullable = values.iter().filter(|v| v.is_empty() || *v == "NULL" || *v == "null").count() > 0;
 
                         columns.push(json!({
                             "column_name": col_name,
@@ -662,7 +675,7 @@ impl DataSourceConnector for ExcelConnector {
                             "total_columns": sheet_info.column_count,
                             "is_configured_sheet": self.sheet_name.as_ref().map_or(false, |s| s == table_name)
                         },
-                        "sample_data": sample_rows.iter().enumerate().map(|(_i, row)| {
+                        "sample_data": sample_rows.iter().map(|row| {
                             let mut row_obj = json!({});
                             for (j, val) in row.iter().enumerate() {
                                 if let Some(col_name) = headers.get(j) {
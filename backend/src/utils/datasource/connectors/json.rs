@@ -9,6 +9,9 @@ use std::path::Path;
 use std::time::Instant;
 use tracing::{debug, error, info, warn};
 
+/// Column names and rows
+type PreviewRows = (Vec<String>, Vec<Vec<String>>);
+
 pub struct JsonConnector {
     file_path: String,
     root_path: Option<String>,
@@ -222,7 +225,7 @@ impl JsonConnector {
             Value::Array(arr) => Ok(arr.clone()),
             Value::Object(obj) => {
                 // If it's an object, wrap it in an array
-                Ok(vec![obj.clone()])
+                Ok(vec![Value::Object(obj.clone())])
             },
             _ => {
                 // If it's a primitive value, create objects with a "value" field
@@ -304,7 +307,7 @@ impl JsonConnector {
         let mut float_count = 0;
         let mut bool_count = 0;
         let mut date_count = 0;
-        let mut total_non_null = values.iter().filter(|v| !v.is_empty() && *v != "NULL").count();
+        let total_non_null = values.iter().filter(|v| !v.is_empty() && *v != "NULL").count();
 
         for value in values {
             if value.is_empty() || value == "NULL" {
@@ -367,6 +370,40 @@ impl JsonConnector {
             .unwrap_or("json_data")
             .to_string()
     }
+
+    /// Flattened keys and values of the first `limit` objects, for upload
+    /// previews. Missing keys and nulls come back as empty strings.
+    pub fn preview_rows(&self, limit: usize) -> Result<PreviewRows, Box<dyn Error + Send + Sync>> {
+        let objects: Vec<Vec<(String, String)>> = self
+            .get_data_array()?
+            .iter()
+            .take(limit)
+            .map(|obj| self.flatten_json_object(obj, ""))
+            .collect();
+
+        let mut headers: Vec<String> = Vec::new();
+        for (key, _) in objects.iter().flatten() {
+            if !headers.contains(key) {
+                headers.push(key.clone());
+            }
+        }
+
+        let rows = objects
+            .into_iter()
+            .map(|flattened| {
+                let values: std::collections::HashMap<String, String> = flattened.into_iter().collect();
+                headers
+                    .iter()
+                    .map(|key| match values.get(key).map(String::as_str) {
+                        None | Some("NULL") => String::new(),
+                        Some(value) => value.to_string(),
+                    })
+                    .collect()
+            })
+            .collect();
+
+        Ok((headers, rows))
+    }
 }
 
 #[async_trait]
@@ -594,7 +631,7 @@ impl DataSourceConnector for JsonConnector {
 
             for (key, value) in &flattened {
                 all_keys.insert(key.clone());
-                column_values.entry(key.clone()).or_insert_with(Vec::new).push(value.clone());
+                column_values.entry(key.clone()).or_default().push(value.clone());
             }
         }
 
@@ -603,7 +640,7 @@ impl DataSourceConnector for JsonConnector {
 
         let mut columns = Vec::new();
         for header in &headers {
-            let values = column_values.get(header).unwrap_or(&Vec::new());
+            let values = column_values.get(header).map(Vec::as_slice).unwrap_or(&[]);
             let data_type = Self::infer_data_type(values);
             let nullable = values.iter().filter(|v| v.is_empty() || *v == "NULL").count() > 0;
 
@@ -1,14 +1,23 @@
+pub mod cell_value;
 pub mod common;
 pub mod csv;
+pub mod duckdb;
 pub mod duckdb_wrapper;
 pub mod excel;
 pub mod json;
+pub mod mongodb;
 pub mod clickhouse;
+pub mod clickhouse_sql;
 pub mod mysql;
 pub mod oracle;
 pub mod postgres;
 pub mod sqlite;
+pub mod sql_transactions;
 pub mod sqlserver;
+pub mod table_distinct;
+pub mod table_filters;
+pub mod table_keyset;
+pub mod table_sort;
 
 // Removed unused imports - uncomment when needed
 // pub use clickhouse::*;
@@ -1,428 +1,16 @@
-use super::super::core::base::{DataSourceConnector, format_bytes};
-use async_trait::async_trait;
+//! Schema introspection for MySQL: tables, columns, relations and sizes in
+//! the connected database
+
 use serde_json::{json, Value};
 use sqlx::types::Decimal;
-use sqlx::{
-    mysql::{MySqlPool, MySqlPoolOptions},
-    Column, Row as SqlxRow,
-};
+use sqlx::Row as SqlxRow;
 use std::error::Error;
-use std::time::Duration;
-use tracing::{debug, error, info, warn};
-use super::super::pooling::{get_pool_manager, DatabasePool};
-
-pub struct MySQLConnector {
-    connection_string: String,
-    original_connection_string: String,
-    ssl_mode_used: Option<String>,
-    datasource_id: String,
-    config: Value,
-}
-
-impl MySQLConnector {
-    async fn get_pool(&self) -> Result<MySqlPool, Box<dyn Error + Send + Sync>> {
-        let pool_manager = get_pool_manager().await;
-        let db_pool = pool_manager.get_pool(&self.datasource_id, "mysql", &self.config).await?;
-        match db_pool {
-            DatabasePool::MySQL(pool) => Ok((*pool).clone()),
-            _ => Err("Wrong pool type returned from global pool manager".into()),
-        }
-    }
-
-    /// Create a new connection pool (public method for pool manager)
-    pub async fn create_pool(&self) -> Result<MySqlPool, Box<dyn Error + Send + Sync>> {
-        info!("Creating new MySQL connection pool");
-        let pool_creation_start = std::time::Instant::now();
-        let pool = MySqlPoolOptions::new()
-            .max_connections(5)
-            .min_connections(1)
-            .acquire_timeout(Duration::from_secs(3))
-            .idle_timeout(Some(Duration::from_secs(30)))
-            .connect(&self.connection_string)
-            .await?;
-        let creation_time = pool_creation_start.elapsed().as_millis();
-        info!("MySQL pool creation took {}ms", creation_time);
-        Ok(pool)
-    }
-    
-    /// Create a new connection pool from config (static method for pool manager)
-    #[allow(dead_code)]
-    pub async fn create_pool_from_config(config: &Value) -> Result<MySqlPool, Box<dyn Error + Send + Sync>> {
-        let connector = Self::new(config)?;
-        connector.create_pool().await
-    }
-
-    pub fn new(config: &Value) -> Result<Self, Box<dyn Error + Send + Sync>> {
-        // Get datasource ID from config (optional - only needed for connection pooling)
-        let datasource_id = config
-            .get("id")
-            .and_then(|v| v.as_str())
-            .unwrap_or("temp-connection-test")
-            .to_string();
-        
-        // Check for SSL/TLS settings
-        let disable_ssl = config
-            .get("disable_ssl")
-            .and_then(|v| v.as_bool())
-            .unwrap_or(false);
-        let ssl_mode = config.get("ssl_mode").and_then(|v| v.as_str());
-
-        // Prefer URL if provided, otherwise construct from individual components
-        let mut connection_string = if let Some(url) = config.get("url").and_then(|v| v.as_str()) {
-            debug!("🔗 Using provided MySQL URL directly");
-            url.to_string()
-        } else {
-            // Fallback to individual components for backward compatibility
-            let host = config
-                .get("host")
-                .and_then(|v| v.as_str())
-                .unwrap_or("localhost");
-            let port = config.get("port").and_then(|v| v.as_u64()).unwrap_or(3306);
-            let database = config
-                .get("database")
-                .and_then(|v| v.as_str())
-                .ok_or("Missing database name")?;
-            let username = config
-                .get("username")
-                .and_then(|v| v.as_str())
-                .unwrap_or("root");
-            let password = config
-                .get("password")
-                .and_then(|v| v.as_str())
-                .unwrap_or("");
-
-            info!(
-                "🔧 Building MySQL URL from components: host={}, port={}, database={}, username={}",
-                host, port, database, username
-            );
-
-            // URL encode username and password to handle special characters
-            let encoded_username = urlencoding::encode(username);
-            let encoded_password = if password.is_empty() {
-                String::new()
-            } else {
-                urlencoding::encode(password).to_string()
-            };
-
-            if encoded_password.is_empty() {
-                format!(
-                    "mysql://{}@{}:{}/{}",
-                    encoded_username, host, port, database
-                )
-            } else {
-                format!(
-                    "mysql://{}:{}@{}:{}/{}",
-                    encoded_username, encoded_password, host, port, database
-                )
-            }
-        };
-
-        // Handle SSL/TLS configuration
-        if disable_ssl || ssl_mode == Some("disabled") {
-            // Add SSL disabled parameter if not already present
-            if !connection_string.contains("sslmode=") && !connection_string.contains("ssl-mode=") {
-                let separator = if connection_string.contains('?') {
-                    "&"
-                } else {
-                    "?"
-                };
-                connection_string.push_str(&format!("{}sslmode=disabled", separator));
-                debug!("🔒 SSL disabled for MySQL connection");
-            }
-        } else if let Some(mode) = ssl_mode {
-            // Add specific SSL mode if provided
-            if !connection_string.contains("sslmode=") && !connection_string.contains("ssl-mode=") {
-                let separator = if connection_string.contains('?') {
-                    "&"
-                } else {
-                    "?"
-                };
-                connection_string.push_str(&format!("{}sslmode={}", separator, mode));
-                info!("🔒 SSL mode set to: {}", mode);
-            }
-        };
-
-        // Log connection string (masking password for security)
-        let masked_conn_str = if connection_string.contains('@') {
-            let parts: Vec<&str> = connection_string.splitn(2, '@').collect();
-            if parts.len() == 2 {
-                // Extract the protocol and auth part
-                if let Some(protocol_end) = parts[0].find("://") {
-                    let protocol = &parts[0][..protocol_end + 3];
-                    let auth = &parts[0][protocol_end + 3..];
-
-                    // Check if there's a password
-                    if auth.contains(':') {
-                        let user_parts: Vec<&str> = auth.splitn(2, ':').collect();
-                        format!("{}{}:****@{}", protocol, user_parts[0], parts[1])
-                    } else {
-                        connection_string.clone()
-                    }
-                } else {
-                    "mysql://****".to_string()
-                }
-            } else {
-                connection_string.clone()
-            }
-        } else {
-            connection_string.clone()
-        };
-        info!("🔗 MySQL connection string (masked): {}", masked_conn_str);
-
-        Ok(Self {
-            original_connection_string: connection_string.clone(),
-            connection_string,
-            ssl_mode_used: ssl_mode.map(|s| s.to_string()).or(if disable_ssl {
-                Some("disabled".to_string())
-            } else {
-                None
-            }),
-            datasource_id,
-            config: config.clone(),
-        })
-    }
-}
 
-#[async_trait]
-impl DataSourceConnector for MySQLConnector {
-    async fn test_connection(&mut self) -> Result<bool, Box<dyn Error + Send + Sync>> {
-        // Try connection with current settings first
-        let mut connection_strings_to_try = vec![self.connection_string.clone()];
-
-        // If SSL mode is not explicitly set, prepare fallback options
-        if self.ssl_mode_used.is_none() {
-            // Try with SSL disabled as fallback
-            let with_ssl_disabled = if self.original_connection_string.contains("sslmode=") {
-                self.original_connection_string.clone()
-            } else {
-                let separator = if self.original_connection_string.contains('?') {
-                    "&"
-                } else {
-                    "?"
-                };
-                format!(
-                    "{}{}sslmode=disabled",
-                    self.original_connection_string, separator
-                )
-            };
-
-            if with_ssl_disabled != self.connection_string {
-                connection_strings_to_try.push(with_ssl_disabled);
-            }
-        }
+use super::super::super::core::base::{format_bytes, ColumnSummary};
+use super::MySQLConnector;
 
-        let mut last_error: Option<String> = None;
-        let mut attempt = 0;
-
-        for conn_str in connection_strings_to_try {
-            attempt += 1;
-            let ssl_status = if conn_str.contains("sslmode=disabled") {
-                "with SSL disabled"
-            } else {
-                "with SSL enabled (default)"
-            };
-
-            info!("🔄 MySQL connection attempt {} {}", attempt, ssl_status);
-
-            // Create a temporary connector with this connection string
-            let temp_self = Self {
-                connection_string: conn_str.clone(),
-                original_connection_string: self.original_connection_string.clone(),
-                ssl_mode_used: self.ssl_mode_used.clone(),
-                datasource_id: self.datasource_id.clone(),
-                config: self.config.clone(),
-            };
-
-            // Try to connect
-            match temp_self.get_pool().await {
-                Ok(pool) => {
-                    // Try a simple query
-                    match sqlx::query("SELECT 1 as test").fetch_one(&pool).await {
-                        Ok(_) => {
-                            info!("✅ MySQL connection successful {}", ssl_status);
-
-                            // Save the working connection string
-                            self.connection_string = conn_str.clone();
-                            if conn_str.contains("sslmode=disabled") {
-                                self.ssl_mode_used = Some("disabled".to_string());
-                                info!("💾 Saved working configuration with SSL disabled");
-                            } else {
-                                info!("💾 Saved working configuration with SSL enabled");
-                            }
-
-                            return Ok(true);
-                        }
-                        Err(e) => {
-                            last_error = Some(e.to_string());
-                        }
-                    }
-                }
-                Err(e) => {
-                    last_error = Some(e.to_string());
-                }
-            }
-        }
-
-        // All attempts failed, show detailed error diagnostics
-        if let Some(e) = last_error {
-            error!("💥 ========== MySQL Connection Failed After All Attempts ==========");
-            error!("❌ Failed to create MySQL connection pool");
-            error!("📄 Error message: {}", e);
-
-            // Log connection parameters (without password) for debugging
-            let masked_conn_str = if self.connection_string.contains('@') {
-                let parts: Vec<&str> = self.connection_string.splitn(2, '@').collect();
-                if parts.len() == 2 {
-                    if let Some(protocol_end) = parts[0].find("://") {
-                        let protocol = &parts[0][..protocol_end + 3];
-                        let auth = &parts[0][protocol_end + 3..];
-                        if auth.contains(':') {
-                            let user_parts: Vec<&str> = auth.splitn(2, ':').collect();
-                            format!("{}{}:****@{}", protocol, user_parts[0], parts[1])
-                        } else {
-                            self.connection_string.clone()
-                        }
-                    } else {
-                        "mysql://****".to_string()
-                    }
-                } else {
-                    self.connection_string.clone()
-                }
-            } else {
-                self.connection_string.clone()
-            };
-            debug!("🔗 Connection string (masked): {}", masked_conn_str);
-
-            // Extract host and port for better diagnostics
-            if let Some(at_pos) = self.connection_string.find('@') {
-                if let Some(host_start) = self.connection_string.get(at_pos + 1..) {
-                    let host_part = host_start.split('/').next().unwrap_or("");
-                    debug!("🎯 Target host/port: {}", host_part);
-                }
-            }
-
-            // Check if it's a specific type of error
-            let error_string = e.to_string();
-            debug!("🔍 Analyzing error type...");
-
-            if error_string.contains("Access denied") {
-                error!("🚨 Authentication failed - check username and password");
-                warn!("💡 Common causes:");
-                warn!("  - Incorrect password");
-                warn!("  - User doesn't exist");
-                warn!("  - User exists but lacks privileges for the database");
-            } else if error_string.contains("Unknown database") {
-                error!("🚨 Database does not exist");
-                warn!("💡 Create the database first with: CREATE DATABASE <dbname>");
-            } else if error_string.contains("Can't connect")
-                || error_string.contains("Connection refused")
-            {
-                error!("🚨 Cannot reach MySQL server - check host and port");
-                warn!("💡 Common causes:");
-                warn!("  - MySQL server is not running");
-                warn!("  - Incorrect host or port");
-                warn!("  - Firewall blocking the connection");
-                warn!("  - MySQL not configured to accept network connections");
-            } else if error_string.contains("timeout") {
-                error!("🚨 Connection timeout - server may be unreachable or slow");
-                warn!("💡 Common causes:");
-                warn!("  - Network issues");
-                warn!("  - Server is overloaded");
-                warn!("  - Incorrect host/port causing connection to hang");
-            } else if error_string.contains("SSL")
-                || error_string.contains("TLS")
-                || error_string.contains("2026")
-            {
-                error!("🚨 SSL/TLS connection issue");
-                warn!("💡 The server requires SSL but may not support it properly");
-                info!("📝 Already tried connecting with and without SSL");
-            } else if error_string.contains("Host") && error_string.contains("is not allowed") {
-                error!("🚨 Host not allowed to connect");
-                warn!("💡 Grant access from your host: GRANT ALL ON *.* TO 'user'@'your-host'");
-            } else {
-                error!("🚨 Unrecognized error type");
-                warn!("💡 Check the full error message above for more details");
-            }
-
-            error!("💥 ========== MySQL Connection Test Failed ==========");
-            Err(e.into())
-        } else {
-            Err("No connection attempts were made".into())
-        }
-    }
-
-    async fn execute_query(&self, query: &str, limit: i32) -> Result<Value, Box<dyn Error + Send + Sync>> {
-        let pool = self
-            .get_pool()
-            .await?;
-
-        let query_with_limit = if query.to_lowercase().contains("limit") {
-            query.to_string()
-        } else {
-            format!("{} LIMIT {}", query, limit)
-        };
-
-        let start = std::time::Instant::now();
-        let rows = sqlx::query(&query_with_limit).fetch_all(&pool).await?;
-        let execution_time_ms = start.elapsed().as_millis() as i64;
-
-        if rows.is_empty() {
-            return Ok(json!({
-                "columns": [],
-                "rows": [],
-                "row_count": 0,
-                "execution_time_ms": execution_time_ms
-            }));
-        }
-
-        // Get column names from the first row
-        let first_row = &rows[0];
-        let columns: Vec<String> = first_row
-            .columns()
-            .iter()
-            .map(|c| c.name().to_string())
-            .collect();
-
-        // Convert rows to JSON
-        let mut result_rows = Vec::new();
-        for row in rows.iter() {
-            let mut row_data = Vec::new();
-            for (i, _col) in columns.iter().enumerate() {
-                // Try to get value as different types
-                if let Ok(val) = row.try_get::<String, _>(i) {
-                    row_data.push(val);
-                } else if let Ok(val) = row.try_get::<i32, _>(i) {
-                    row_data.push(val.to_string());
-                } else if let Ok(val) = row.try_get::<i64, _>(i) {
-                    row_data.push(val.to_string());
-                } else if let Ok(val) = row.try_get::<f64, _>(i) {
-                    row_data.push(val.to_string());
-                } else if let Ok(val) = row.try_get::<f32, _>(i) {
-                    row_data.push(val.to_string());
-                } else if let Ok(val) = row.try_get::<bool, _>(i) {
-                    row_data.push(if val { "1" } else { "0" }.to_string());
-                } else if let Ok(val) = row.try_get::<chrono::NaiveDateTime, _>(i) {
-                    row_data.push(val.to_string());
-                } else if let Ok(val) = row.try_get::<chrono::NaiveDate, _>(i) {
-                    row_data.push(val.to_string());
-                } else {
-                    row_data.push("NULL".to_string());
-                }
-            }
-            result_rows.push(row_data);
-        }
-
-        let result = json!({
-            "columns": columns,
-            "rows": result_rows,
-            "row_count": result_rows.len(),
-            "execution_time_ms": execution_time_ms
-        });
-
-        Ok(result)
-    }
-
-    async fn fetch_schema(&self) -> Result<Value, Box<dyn Error + Send + Sync>> {
+impl MySQLConnector {
+    pub(super) async fn load_schema(&self) -> Result<Value, Box<dyn Error + Send + Sync>> {
         let pool = self
             .get_pool()
             .await?;
@@ -471,7 +59,7 @@ impl DataSourceConnector for MySQLConnector {
         Ok(schema)
     }
 
-    async fn list_tables(&self) -> Result<Vec<String>, Box<dyn Error + Send + Sync>> {
+    pub(super) async fn table_names(&self) -> Result<Vec<String>, Box<dyn Error + Send + Sync>> {
         let pool = self
             .get_pool()
             .await?;
@@ -486,7 +74,7 @@ impl DataSourceConnector for MySQLConnector {
         Ok(table_names)
     }
 
-    async fn analyze_database(&self) -> Result<Value, Box<dyn Error + Send + Sync>> {
+    pub(super) async fn database_analysis(&self) -> Result<Value, Box<dyn Error + Send + Sync>> {
         let pool = self
             .get_pool()
             .await?;
@@ -612,7 +200,7 @@ impl DataSourceConnector for MySQLConnector {
         Ok(result)
     }
 
-    async fn get_tables_schema(&self, tables: Vec<&str>) -> Result<Value, Box<dyn Error + Send + Sync>> {
+    pub(super) async fn tables_schema(&self, tables: Vec<&str>) -> Result<Value, Box<dyn Error + Send + Sync>> {
         let pool = self
             .get_pool()
             .await?;
@@ -773,7 +361,7 @@ impl DataSourceConnector for MySQLConnector {
         Ok(result)
     }
 
-    async fn search_tables(&self, pattern: &str) -> Result<Value, Box<dyn Error + Send + Sync>> {
+    pub(super) async fn tables_matching(&self, pattern: &str) -> Result<Value, Box<dyn Error + Send + Sync>> {
         let pool = self
             .get_pool()
             .await?;
@@ -815,13 +403,40 @@ impl DataSourceConnector for MySQLConnector {
         Ok(result)
     }
 
-    async fn get_related_tables(&self, table: &str) -> Result<Value, Box<dyn Error + Send + Sync>> {
+    pub(super) async fn column_summaries(&self, table: &str) -> Result<Vec<ColumnSummary>, Box<dyn Error + Send + Sync>> {
+        let pool = self.get_pool().await?;
+        let rows = sqlx::query(
+            "SELECT
+                CAST(COLUMN_NAME AS CHAR) as column_name,
+                CAST(COLUMN_TYPE AS CHAR) as data_type,
+                IS_NULLABLE = 'YES' as nullable,
+                COLUMN_KEY = 'PRI' as primary_key
+             FROM INFORMATION_SCHEMA.COLUMNS
+             WHERE TABLE_SCHEMA = DATABASE() AND TABLE_NAME = ?
+             ORDER BY ORDINAL_POSITION",
+        )
+        .bind(table)
+        .fetch_all(&pool)
+        .await?;
+
+        Ok(rows
+            .iter()
+            .map(|row| ColumnSummary {
+                name: row.get("column_name"),
+                data_type: row.get("data_type"),
+                nullable: row.get::<i64, _>("nullable") != 0,
+                primary_key: row.get::<i64, _>("primary_key") != 0,
+            })
+            .collect())
+    }
+
+    pub(super) async fn related_tables(&self, table: &str) -> Result<Value, Box<dyn Error + Send + Sync>> {
         let pool = self
             .get_pool()
             .await?;
 
         // Get the main table schema
-        let main_schema = self.get_tables_schema(vec![table]).await?;
+        let main_schema = self.tables_schema(vec![table]).await?;
 
         if main_schema.get(table).is_none() {
             return Err("Table not found".into());
@@ -870,7 +485,7 @@ impl DataSourceConnector for MySQLConnector {
         // Get schemas for related tables
         let related_schemas = if !related_tables.is_empty() {
             let refs: Vec<&str> = related_tables.iter().map(|s| s.as_str()).collect();
-            self.get_tables_schema(refs).await?
+            self.tables_schema(refs).await?
         } else {
             json!({})
         };
@@ -884,7 +499,7 @@ impl DataSourceConnector for MySQLConnector {
         Ok(result)
     }
 
-    async fn get_database_stats(&self) -> Result<Value, Box<dyn Error + Send + Sync>> {
+    pub(super) async fn database_stats(&self) -> Result<Value, Box<dyn Error + Send + Sync>> {
         let pool = self
             .get_pool()
             .await?;
@@ -975,33 +590,4 @@ impl DataSourceConnector for MySQLConnector {
 
         Ok(result)
     }
-
-    async fn get_table_data_with_pagination(
-        &self, 
-        table_name: &str, 
-        page: i32, 
-        limit: i32, 
-        sort_column: Option<&str>, 
-        sort_direction: Option<&str>
-    ) -> Result<Value, Box<dyn Error + Send + Sync>> {
-        // TODO: Implement proper pagination with total count for MySQL
-        let offset = (page - 1) * limit;
-        let mut query = format!("SELECT * FROM {}", table_name);
-        
-        if let Some(sort_col) = sort_column {
-            let direction = sort_direction.unwrap_or("ASC");
-            query.push_str(&format!(" ORDER BY {} {}", sort_col, direction));
-        }
-        
-        query.push_str(&format!(" LIMIT {} OFFSET {}", limit, offset));
-        
-        let mut result = self.execute_query(&query, limit).await?;
-        
-        // Add pagination metadata (temporary - needs proper total count)
-        result["total_rows"] = json!(result["row_count"]);
-        result["page"] = json!(page);
-        result["page_size"] = json!(limit);
-        
-        Ok(result)
-    }
 }
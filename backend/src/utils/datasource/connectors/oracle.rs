@@ -1,4 +1,5 @@
 use super::super::core::base::{format_bytes, DataSourceConnector};
+use super::common::{dedupe_column_names, table_page_query, IdentifierQuote, LimitSyntax};
 use async_trait::async_trait;
 use oracle::Connection;
 use serde_json::{json, Value};
@@ -152,6 +153,7 @@ pub struct OracleConnector {
     password: String,
     schema: String,
     pool: Arc<ConnectionPool>,
+    limit_syntax: LimitSyntax,
 }
 
 impl OracleConnector {
@@ -201,6 +203,12 @@ impl OracleConnector {
             .unwrap_or("")
             .to_string();
 
+        // FETCH FIRST is only available from Oracle 12c; fall back to ROWNUM otherwise
+        let limit_syntax = match config.get("version").and_then(|v| v.as_u64()) {
+            Some(major) if major >= 12 => LimitSyntax::FetchFirst,
+            _ => LimitSyntax::RowNum,
+        };
+
         // Create pool configuration from config
         let mut pool_config = PoolConfig::default();
 
@@ -253,6 +261,7 @@ impl OracleConnector {
             password,
             schema,
             pool,
+            limit_syntax,
         })
     }
 
@@ -274,6 +283,105 @@ impl OracleConnector {
         result
     }
 
+    /// Run a query and convert its rows to JSON, optionally inside a read-only transaction
+    async fn run_query(&self, query: &str, limit: i32, read_only: bool) -> Result<Value, Box<dyn Error + Send + Sync>> {
+        // Add row limit if not already present and limit is specified
+        let modified_query = self.apply_limit(query, limit);
+        let schema = self.schema.clone();
+        let pool = Arc::clone(&self.pool);
+
+        tokio::task::spawn_blocking(move || -> Result<Value, Box<dyn Error + Send + Sync>> {
+            // Use the connection pool
+            let runtime = tokio::runtime::Handle::current();
+            let pooled_conn = runtime.block_on(pool.get_connection())?;
+
+            // Set the current schema for this session
+            let set_schema_sql = format!("ALTER SESSION SET CURRENT_SCHEMA = {}", schema);
+            pooled_conn
+                .connection
+                .execute(&set_schema_sql, &[])
+                .map_err(|e| Box::new(e) as Box<dyn Error + Send + Sync>)?;
+
+            // Any write the query attempts fails at the database, not just our SELECT check.
+            // SET TRANSACTION must be the first statement of the transaction.
+            if read_only {
+                pooled_conn
+                    .connection
+                    .execute("SET TRANSACTION READ ONLY", &[])
+                    .map_err(|e| Box::new(e) as Box<dyn Error + Send + Sync>)?;
+            }
+
+            let start = std::time::Instant::now();
+            let rows = pooled_conn
+                .connection
+                .query(&modified_query, &[])
+                .map_err(|e| Box::new(e) as Box<dyn Error + Send + Sync>)?;
+            let execution_time_ms = start.elapsed().as_millis() as i64;
+
+            let mut results = Vec::new();
+            // Column info comes from the statement, so it is populated even for empty results
+            let columns: Vec<String> = dedupe_column_names(
+                rows.column_info()
+                    .iter()
+                    .map(|col| col.name().to_string())
+                    .collect(),
+            );
+
+            for row_result in rows {
+                let row = row_result.map_err(|e| Box::new(e) as Box<dyn Error + Send + Sync>)?;
+                let mut record = serde_json::Map::new();
+
+                for (i, col_name) in columns.iter().enumerate() {
+                    // Get value as a string representation for JSON
+                    let json_value = if let Ok(v) = row.get::<_, Option<String>>(i) {
+                        match v {
+                            Some(s) => json!(s),
+                            None => Value::Null,
+                        }
+                    } else if let Ok(v) = row.get::<_, Option<i64>>(i) {
+                        match v {
+                            Some(n) => json!(n),
+                            None => Value::Null,
+                        }
+                    } else if let Ok(v) = row.get::<_, Option<f64>>(i) {
+                        match v {
+                            Some(n) => json!(n),
+                            None => Value::Null,
+                        }
+                    } else {
+                        Value::Null
+                    };
+
+                    record.insert(col_name.clone(), json_value);
+                }
+
+                results.push(Value::Object(record));
+            }
+
+            // End the read-only transaction before the connection goes back to the pool
+            if read_only {
+                pooled_conn
+                    .connection
+                    .rollback()
+                    .map_err(|e| Box::new(e) as Box<dyn Error + Send + Sync>)?;
+            }
+
+            // Return connection to pool
+            let result = json!({
+                "columns": columns,
+                "rows": results,
+                "row_count": results.len(),
+                "execution_time_ms": execution_time_ms
+            });
+
+            runtime.block_on(pool.return_connection(pooled_conn));
+            Ok(result)
+        })
+        .await
+        .map_err(|e| Box::new(e) as Box<dyn Error + Send + Sync>)?
+        .map_err(|e| e as Box<dyn Error + Send + Sync>)
+    }
+
     // Background task to cleanup expired connections
     #[allow(dead_code)]
     pub async fn start_maintenance_task(&self) {
@@ -291,6 +399,10 @@ impl OracleConnector {
 
 #[async_trait]
 impl DataSourceConnector for OracleConnector {
+    fn limit_syntax(&self) -> LimitSyntax {
+        self.limit_syntax
+    }
+
     async fn test_connection(&mut self) -> Result<bool, Box<dyn Error + Send + Sync>> {
         // Run in blocking context since oracle crate is synchronous
         let pool = Arc::clone(&self.pool);
@@ -345,91 +457,45 @@ impl DataSourceConnector for OracleConnector {
     }
 
     async fn execute_query(&self, query: &str, limit: i32) -> Result<Value, Box<dyn Error + Send + Sync>> {
-        let query = query.to_string();
+        self.run_query(query, limit, false).await
+    }
+
+    async fn execute_read_only_query(&self, query: &str, limit: i32) -> Result<Value, Box<dyn Error + Send + Sync>> {
+        self.run_query(query, limit, true).await
+    }
+
+    async fn describe_query_columns(&self, query: &str) -> Result<Vec<String>, Box<dyn Error + Send + Sync>> {
+        // Oracle has no LIMIT 0 and rejects AS on subquery aliases, so use a false predicate
+        let sql = format!("SELECT * FROM ({}) WHERE 1 = 0", query.trim().trim_end_matches(';'));
         let schema = self.schema.clone();
         let pool = Arc::clone(&self.pool);
 
-        tokio::task::spawn_blocking(move || -> Result<Value, Box<dyn Error + Send + Sync>> {
-            // Use the connection pool
+        tokio::task::spawn_blocking(move || -> Result<Vec<String>, Box<dyn Error + Send + Sync>> {
             let runtime = tokio::runtime::Handle::current();
             let pooled_conn = runtime.block_on(pool.get_connection())?;
 
-            // Set the current schema for this session
             let set_schema_sql = format!("ALTER SESSION SET CURRENT_SCHEMA = {}", schema);
             pooled_conn
                 .connection
                 .execute(&set_schema_sql, &[])
                 .map_err(|e| Box::new(e) as Box<dyn Error + Send + Sync>)?;
 
-            // Add ROWNUM limit if not already present and limit is specified
-            let modified_query = if limit > 0
-                && !query.to_uppercase().contains("ROWNUM")
-                && !query.to_uppercase().contains("FETCH")
-            {
-                format!("SELECT * FROM ({}) WHERE ROWNUM <= {}", query, limit)
-            } else {
-                query
+            let columns = {
+                let rows = pooled_conn
+                    .connection
+                    .query(&sql, &[])
+                    .map_err(|e| Box::new(e) as Box<dyn Error + Send + Sync>)?;
+                rows.column_info()
+                    .iter()
+                    .map(|col| col.name().to_string())
+                    .collect()
             };
 
-            let start = std::time::Instant::now();
-            let rows = pooled_conn
-                .connection
-                .query(&modified_query, &[])
-                .map_err(|e| Box::new(e) as Box<dyn Error + Send + Sync>)?;
-            let execution_time_ms = start.elapsed().as_millis() as i64;
-
-            let mut results = Vec::new();
-            let columns: Vec<String> = rows
-                .column_info()
-                .iter()
-                .map(|col| col.name().to_string())
-                .collect();
-
-            for row_result in rows {
-                let row = row_result.map_err(|e| Box::new(e) as Box<dyn Error + Send + Sync>)?;
-                let mut record = serde_json::Map::new();
-
-                for (i, col_name) in columns.iter().enumerate() {
-                    // Get value as a string representation for JSON
-                    let json_value = if let Ok(v) = row.get::<_, Option<String>>(i) {
-                        match v {
-                            Some(s) => json!(s),
-                            None => Value::Null,
-                        }
-                    } else if let Ok(v) = row.get::<_, Option<i64>>(i) {
-                        match v {
-                            Some(n) => json!(n),
-                            None => Value::Null,
-                        }
-                    } else if let Ok(v) = row.get::<_, Option<f64>>(i) {
-                        match v {
-                            Some(n) => json!(n),
-                            None => Value::Null,
-                        }
-                    } else {
-                        Value::Null
-                    };
-
-                    record.insert(col_name.clone(), json_value);
-                }
-
-                results.push(Value::Object(record));
-            }
-
-            // Return connection to pool
-            let result = json!({
-                "columns": columns,
-                "rows": results,
-                "row_count": results.len(),
-                "execution_time_ms": execution_time_ms
-            });
-
             runtime.block_on(pool.return_connection(pooled_conn));
-            Ok(result)
+            Ok(dedupe_column_names(columns))
         })
         .await
         .map_err(|e| Box::new(e) as Box<dyn Error + Send + Sync>)?
-        .map_err(|e| e as Box<dyn Error + Send + Sync>)
     }
 
     async fn fetch_schema(&self) -> Result<Value, Box<dyn Error + Send + Sync>> {
@@ -838,20 +904,16 @@ impl DataSourceConnector for OracleConnector {
         sort_direction: Option<&str>
     ) -> Result<Value, Box<dyn Error + Send + Sync>> {
         // TODO: Implement proper pagination with total count for Oracle
-        let offset = (page - 1) * limit;
-        let mut query = format!("SELECT * FROM {}", table_name);
-        
-        if let Some(sort_col) = sort_column {
-            let direction = sort_direction.unwrap_or("ASC");
-            query.push_str(&format!(" ORDER BY {} {}", sort_col, direction));
-        }
-        
-        // Oracle uses ROWNUM instead of LIMIT/OFFSET
-        query = format!(
-            "SELECT * FROM (SELECT ROWNUM rn, t.* FROM ({}) t WHERE ROWNUM <= {}) WHERE rn > {}",
-            query, limit + offset, offset
+        let query = table_page_query(
+            table_name,
+            sort_column,
+            sort_direction,
+            page,
+            limit,
+            self.limit_syntax,
+            IdentifierQuote::DoubleQuote,
         );
-        
+
         let mut result = self.execute_query(&query, limit).await?;
         
         // Add pagination metadata (temporary - needs proper total count)
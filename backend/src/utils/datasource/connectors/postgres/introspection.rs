@@ -0,0 +1,609 @@
+//! Schema introspection for PostgreSQL: tables, columns, relations and sizes
+//! within the connector's schema
+
+use serde_json::{json, Value};
+use sqlx::Row as SqlxRow;
+use std::error::Error;
+use tracing::warn;
+
+use super::super::super::core::base::{format_bytes, ColumnSummary};
+use super::{quote_ident, PostgreSQLConnector};
+
+impl PostgreSQLConnector {
+    pub(super) async fn load_schema(&self) -> Result<Value, Box<dyn Error + Send + Sync>> {
+        let pool = self.get_pool().await?;
+
+        // Fetch table and column information
+        // Use json_agg instead of array_agg to return JSONB type
+        let tables = sqlx::query(
+            "SELECT 
+                t.table_name,
+                json_agg(
+                    json_build_object(
+                        'column_name', c.column_name,
+                        'data_type', c.data_type,
+                        'is_nullable', c.is_nullable
+                    ) ORDER BY c.ordinal_position
+                ) as columns
+             FROM information_schema.tables t
+             JOIN information_schema.columns c ON t.table_name = c.table_name AND t.table_schema = c.table_schema
+             WHERE t.table_schema = $1 
+             AND t.table_type = 'BASE TABLE'
+             GROUP BY t.table_name
+             ORDER BY t.table_name"
+        )
+        .bind(&self.schema)
+        .fetch_all(&pool)
+        .await?;
+
+        let mut schema = json!({
+            "database_schema": &self.schema,
+            "tables": {},
+            "refreshed_at": chrono::Utc::now().to_rfc3339()
+        });
+
+        for row in tables {
+            let table_name: String = row
+                .try_get("table_name")
+                .map_err(|e| format!("Failed to get table_name: {}", e))?;
+            let columns: Value = row.try_get("columns")
+                .map_err(|e| format!("Failed to get columns for table '{}': {}. This may be due to a PostgreSQL type compatibility issue with JSON/JSONB columns.", table_name, e))?;
+            schema["tables"][table_name] = columns;
+        }
+
+        Ok(schema)
+    }
+
+    pub(super) async fn table_names(&self) -> Result<Vec<String>, Box<dyn Error + Send + Sync>> {
+        let pool = self.get_pool().await?;
+
+        let tables = sqlx::query(
+            "SELECT table_name 
+             FROM information_schema.tables 
+             WHERE table_schema = $1 
+             AND table_type = 'BASE TABLE'
+             ORDER BY table_name",
+        )
+        .bind(&self.schema)
+        .fetch_all(&pool)
+        .await?;
+
+        let mut table_names = Vec::new();
+        for row in &tables {
+            let table_name: String = row
+                .try_get("table_name")
+                .map_err(|e| format!("Failed to get table_name: {}", e))?;
+            table_names.push(table_name);
+        }
+        Ok(table_names)
+    }
+
+    pub(super) async fn database_analysis(&self) -> Result<Value, Box<dyn Error + Send + Sync>> {
+        let pool = self.get_pool().await?;
+
+        // Get basic statistics
+        let stats = sqlx::query(
+            "SELECT 
+                COUNT(DISTINCT table_name) as table_count,
+                SUM(pg_total_relation_size(quote_ident(table_schema)||'.'||quote_ident(table_name)::text)) as total_size
+             FROM information_schema.tables
+             WHERE table_schema = $1 AND table_type = 'BASE TABLE'"
+        )
+        .bind(&self.schema)
+        .fetch_one(&pool)
+        .await?;
+
+        let table_count: i64 = stats
+            .try_get("table_count")
+            .map_err(|e| format!("Failed to get table_count: {}", e))?;
+        let total_size: Option<i64> = match stats.try_get("total_size") {
+            Ok(size) => size,
+            Err(e) => {
+                warn!("Failed to decode total_size column: {}", e);
+                // Return user-friendly error instead of panicking
+                return Err(format!("Database type compatibility issue: Unable to read table size statistics. This is likely due to a PostgreSQL version or configuration difference. Error: {}", e).into());
+            }
+        };
+
+        // Get detailed table information with safer query
+        let tables = sqlx::query(
+            "SELECT 
+                t.table_name,
+                pg_total_relation_size(quote_ident(t.table_schema)||'.'||quote_ident(t.table_name)::text) as size_bytes,
+                obj_description((quote_ident(t.table_schema)||'.'||quote_ident(t.table_name))::regclass) as description
+             FROM information_schema.tables t
+             WHERE t.table_schema = $1 AND t.table_type = 'BASE TABLE'
+             ORDER BY pg_total_relation_size(quote_ident(t.table_schema)||'.'||quote_ident(t.table_name)::text) DESC NULLS LAST"
+        )
+        .bind(&self.schema)
+        .fetch_all(&pool)
+        .await?;
+
+        // Get table relationships (foreign keys)
+        let relationships = sqlx::query(
+            "SELECT 
+                tc.table_name,
+                kcu.column_name,
+                ccu.table_name AS foreign_table_name,
+                ccu.column_name AS foreign_column_name
+             FROM information_schema.table_constraints AS tc
+             JOIN information_schema.key_column_usage AS kcu
+                 ON tc.constraint_name = kcu.constraint_name AND tc.table_schema = kcu.table_schema
+             JOIN information_schema.constraint_column_usage AS ccu
+                 ON ccu.constraint_name = tc.constraint_name AND ccu.table_schema = tc.table_schema
+             WHERE tc.constraint_type = 'FOREIGN KEY' AND tc.table_schema = $1",
+        )
+        .bind(&self.schema)
+        .fetch_all(&pool)
+        .await?;
+
+        // Build the analysis result
+        let mut key_tables = Vec::new();
+        let mut largest_tables = Vec::new();
+        let mut table_names = Vec::new();
+
+        for (idx, table) in tables.iter().enumerate() {
+            let table_name: String = table
+                .try_get("table_name")
+                .map_err(|e| format!("Failed to get table_name: {}", e))?;
+            let size_bytes: Option<i64> = table.try_get("size_bytes").ok();
+
+            table_names.push(table_name.clone());
+
+            // Track largest tables
+            if idx < 10 {
+                if let Some(size) = size_bytes {
+                    largest_tables.push(json!({
+                        "name": table_name,
+                        "size_bytes": size,
+                        "size_human": format_bytes(size as u64),
+                    }));
+                }
+            }
+
+            // Count relationships for this table
+            let connections = relationships
+                .iter()
+                .filter_map(|r| {
+                    if let (Ok(t), Ok(ft)) = (
+                        r.try_get::<String, _>("table_name"),
+                        r.try_get::<String, _>("foreign_table_name"),
+                    ) {
+                        Some((t, ft))
+                    } else {
+                        None
+                    }
+                })
+                .filter(|(t, ft)| t == &table_name || ft == &table_name)
+                .count();
+
+            // Add to key tables if it has many connections or is large
+            if connections > 2 || idx < 5 {
+                key_tables.push(json!({
+                    "name": table_name,
+                    "size_bytes": size_bytes.unwrap_or(0),
+                    "connections": connections,
+                }));
+            }
+        }
+
+        // Sort key tables by importance (connections + size)
+        key_tables.sort_by_key(|t| {
+            let connections = t["connections"].as_u64().unwrap_or(0);
+            let size = t["size_bytes"].as_i64().unwrap_or(0);
+            -(connections as i64 * 1000 + size / 1000000)
+        });
+
+        Ok(json!({
+            "database_schema": &self.schema,
+            "statistics": {
+                "table_count": table_count,
+                "total_size": total_size.unwrap_or(0),
+                "total_size_human": format_bytes(total_size.unwrap_or(0) as u64),
+                "total_rows": 0, // Would need to query each table
+            },
+            "table_names": table_names,
+            "key_tables": key_tables.into_iter().take(10).collect::<Vec<_>>(),
+            "largest_tables": largest_tables,
+            "analyzed_at": chrono::Utc::now().to_rfc3339(),
+        }))
+    }
+
+    pub(super) async fn tables_schema(&self, tables: Vec<&str>) -> Result<Value, Box<dyn Error + Send + Sync>> {
+        let pool = self.get_pool().await?;
+        let mut result = json!({});
+
+        for table_name in tables {
+            // Get columns
+            let columns = sqlx::query(
+                "SELECT 
+                    column_name,
+                    data_type,
+                    is_nullable,
+                    column_default,
+                    character_maximum_length
+                 FROM information_schema.columns
+                 WHERE table_schema = $1 AND table_name = $2
+                 ORDER BY ordinal_position",
+            )
+            .bind(&self.schema)
+            .bind(table_name)
+            .fetch_all(&pool)
+            .await?;
+
+            if columns.is_empty() {
+                continue; // Table doesn't exist, skip it
+            }
+
+            // Get primary keys
+            let primary_keys = sqlx::query(
+                "SELECT kcu.column_name
+                 FROM information_schema.table_constraints tc
+                 JOIN information_schema.key_column_usage kcu
+                     ON tc.constraint_name = kcu.constraint_name AND tc.table_schema = kcu.table_schema
+                 WHERE tc.table_schema = $1 
+                     AND tc.table_name = $2
+                     AND tc.constraint_type = 'PRIMARY KEY'"
+            )
+            .bind(&self.schema)
+            .bind(table_name)
+            .fetch_all(&pool)
+            .await?;
+
+            // Get foreign keys
+            let foreign_keys = sqlx::query(
+                "SELECT 
+                    kcu.column_name,
+                    ccu.table_name AS foreign_table_name,
+                    ccu.column_name AS foreign_column_name
+                 FROM information_schema.table_constraints AS tc
+                 JOIN information_schema.key_column_usage AS kcu
+                     ON tc.constraint_name = kcu.constraint_name AND tc.table_schema = kcu.table_schema
+                 JOIN information_schema.constraint_column_usage AS ccu
+                     ON ccu.constraint_name = tc.constraint_name AND ccu.table_schema = tc.table_schema
+                 WHERE tc.constraint_type = 'FOREIGN KEY' AND tc.table_schema = $1 AND tc.table_name = $2"
+            )
+            .bind(&self.schema)
+            .bind(table_name)
+            .fetch_all(&pool)
+            .await?;
+
+            // Get row count (safely with proper quoting)
+            let count_query = format!(
+                "SELECT COUNT(*) as count FROM {}.{}",
+                quote_ident(&self.schema),
+                quote_ident(table_name)
+            );
+            let row_count: i64 = sqlx::query(&count_query)
+                .fetch_one(&pool)
+                .await
+                .map(|r| r.try_get("count").unwrap_or(0))
+                .unwrap_or(0);
+
+            // Get sample data (safely with proper quoting)
+            let sample_query = format!(
+                "SELECT * FROM {}.{} LIMIT 5",
+                quote_ident(&self.schema),
+                quote_ident(table_name)
+            );
+            let sample_rows = sqlx::query(&sample_query)
+                .fetch_all(&pool)
+                .await
+                .unwrap_or_default();
+
+            let mut sample_data = Vec::new();
+            for row in sample_rows {
+                let mut row_data = json!({});
+                for col in columns.iter() {
+                    let col_name: String = col
+                        .try_get("column_name")
+                        .map_err(|e| format!("Failed to get column_name: {}", e))?;
+                    // Try to get value as string (simplified)
+                    if let Ok(val) = row.try_get::<Option<String>, _>(col_name.as_str()) {
+                        row_data[&col_name] = json!(val);
+                    } else if let Ok(val) = row.try_get::<Option<i32>, _>(col_name.as_str()) {
+                        row_data[&col_name] = json!(val);
+                    } else if let Ok(val) = row.try_get::<Option<i64>, _>(col_name.as_str()) {
+                        row_data[&col_name] = json!(val);
+                    } else if let Ok(val) = row.try_get::<Option<bool>, _>(col_name.as_str()) {
+                        row_data[&col_name] = json!(val);
+                    } else {
+                        row_data[&col_name] = json!(null);
+                    }
+                }
+                sample_data.push(row_data);
+            }
+
+            let column_details: Result<Vec<_>, Box<dyn Error + Send + Sync>> = columns.iter().map(|c| {
+                Ok(json!({
+                    "name": c.try_get::<String, _>("column_name")
+                        .map_err(|e| format!("Failed to get column_name: {}", e))?,
+                    "type": c.try_get::<String, _>("data_type")
+                        .map_err(|e| format!("Failed to get data_type: {}", e))?,
+                    "nullable": c.try_get::<String, _>("is_nullable")
+                        .map_err(|e| format!("Failed to get is_nullable: {}", e))? == "YES",
+                    "default": c.try_get::<Option<String>, _>("column_default").ok().flatten(),
+                    "max_length": c.try_get::<Option<i32>, _>("character_maximum_length").ok().flatten(),
+                }))
+            }).collect();
+
+            let pk_list: Result<Vec<_>, Box<dyn Error + Send + Sync>> = primary_keys
+                .iter()
+                .map(|pk| {
+                    pk.try_get::<String, _>("column_name")
+                        .map_err(|e| Box::new(e) as Box<dyn Error + Send + Sync>)
+                })
+                .collect();
+
+            let fk_list: Result<Vec<_>, Box<dyn Error + Send + Sync>> = foreign_keys
+                .iter()
+                .map(|fk| {
+                    Ok(json!({
+                        "column": fk.try_get::<String, _>("column_name")
+                            .map_err(|e| format!("Failed to get column_name: {}", e))?,
+                        "references_table": fk.try_get::<String, _>("foreign_table_name")
+                            .map_err(|e| format!("Failed to get foreign_table_name: {}", e))?,
+                        "references_column": fk.try_get::<String, _>("foreign_column_name")
+                            .map_err(|e| format!("Failed to get foreign_column_name: {}", e))?,
+                    }))
+                })
+                .collect();
+
+            result[table_name] = json!({
+                "columns": column_details?,
+                "primary_keys": pk_list?,
+                "foreign_keys": fk_list?,
+                "row_count": row_count,
+                "sample_data": sample_data,
+            });
+        }
+
+        Ok(result)
+    }
+
+    pub(super) async fn tables_matching(&self, pattern: &str) -> Result<Value, Box<dyn Error + Send + Sync>> {
+        let pool = self.get_pool().await?;
+
+        let tables = sqlx::query(
+            "SELECT 
+                table_name,
+                obj_description((quote_ident(table_schema)||'.'||quote_ident(table_name))::regclass) as description
+             FROM information_schema.tables
+             WHERE table_schema = $1 
+                 AND table_type = 'BASE TABLE'
+                 AND table_name LIKE $2
+             ORDER BY table_name"
+        )
+        .bind(&self.schema)
+        .bind(pattern)
+        .fetch_all(&pool)
+        .await?;
+
+        let mut results = Vec::new();
+        for table in tables {
+            let table_name: String = table
+                .try_get("table_name")
+                .map_err(|e| format!("Failed to get table_name: {}", e))?;
+            let description: Option<String> = table.try_get("description").ok();
+
+            // Get column count
+            let col_count: i64 = sqlx::query(
+                "SELECT COUNT(*) as count FROM information_schema.columns 
+                 WHERE table_schema = $1 AND table_name = $2",
+            )
+            .bind(&self.schema)
+            .bind(&table_name)
+            .fetch_one(&pool)
+            .await
+            .map(|r| r.try_get("count").unwrap_or(0))
+            .unwrap_or(0);
+
+            results.push(json!({
+                "name": table_name,
+                "description": description,
+                "column_count": col_count,
+            }));
+        }
+
+        Ok(json!({
+            "matches": results,
+            "total_matches": results.len(),
+        }))
+    }
+
+    pub(super) async fn column_summaries(&self, table: &str) -> Result<Vec<ColumnSummary>, Box<dyn Error + Send + Sync>> {
+        let pool = self.get_pool().await?;
+        let rows = sqlx::query(
+            "SELECT
+                c.column_name::text AS column_name,
+                c.data_type::text AS data_type,
+                c.is_nullable = 'YES' AS nullable,
+                EXISTS (
+                    SELECT 1
+                    FROM information_schema.table_constraints tc
+                    JOIN information_schema.key_column_usage kcu
+                        ON tc.constraint_name = kcu.constraint_name AND tc.table_schema = kcu.table_schema
+                    WHERE tc.constraint_type = 'PRIMARY KEY'
+                        AND tc.table_schema = c.table_schema
+                        AND tc.table_name = c.table_name
+                        AND kcu.column_name = c.column_name
+                ) AS primary_key
+             FROM information_schema.columns c
+             WHERE c.table_schema = $1 AND c.table_name = $2
+             ORDER BY c.ordinal_position",
+        )
+        .bind(&self.schema)
+        .bind(table)
+        .fetch_all(&pool)
+        .await?;
+
+        Ok(rows
+            .iter()
+            .map(|row| ColumnSummary {
+                name: row.get("column_name"),
+                data_type: row.get("data_type"),
+                nullable: row.get("nullable"),
+                primary_key: row.get("primary_key"),
+            })
+            .collect())
+    }
+
+    pub(super) async fn related_tables(&self, table: &str) -> Result<Value, Box<dyn Error + Send + Sync>> {
+        // Get the main table schema
+        let main_schema = self.tables_schema(vec![table]).await?;
+
+        if main_schema.get(table).is_none() {
+            return Err("Table not found".into());
+        }
+
+        let pool = self.get_pool().await?;
+
+        // Get tables that this table references (outgoing foreign keys)
+        let references = sqlx::query(
+            "SELECT DISTINCT ccu.table_name AS foreign_table_name
+             FROM information_schema.table_constraints AS tc
+             JOIN information_schema.key_column_usage AS kcu
+                 ON tc.constraint_name = kcu.constraint_name AND tc.table_schema = kcu.table_schema
+             JOIN information_schema.constraint_column_usage AS ccu
+                 ON ccu.constraint_name = tc.constraint_name AND ccu.table_schema = tc.table_schema
+             WHERE tc.constraint_type = 'FOREIGN KEY' 
+                 AND tc.table_name = $2
+                 AND tc.table_schema = $1",
+        )
+        .bind(&self.schema)
+        .bind(table)
+        .fetch_all(&pool)
+        .await?;
+
+        // Get tables that reference this table (incoming foreign keys)
+        let referenced_by = sqlx::query(
+            "SELECT DISTINCT tc.table_name
+             FROM information_schema.table_constraints AS tc
+             JOIN information_schema.key_column_usage AS kcu
+                 ON tc.constraint_name = kcu.constraint_name AND tc.table_schema = kcu.table_schema
+             JOIN information_schema.constraint_column_usage AS ccu
+                 ON ccu.constraint_name = tc.constraint_name AND ccu.table_schema = tc.table_schema
+             WHERE tc.constraint_type = 'FOREIGN KEY' 
+                 AND ccu.table_name = $2
+                 AND tc.table_schema = $1",
+        )
+        .bind(&self.schema)
+        .bind(table)
+        .fetch_all(&pool)
+        .await?;
+
+        // Collect all related table names
+        let mut related_tables = Vec::new();
+        for row in references {
+            if let Ok(table_name) = row.try_get::<String, _>("foreign_table_name") {
+                related_tables.push(table_name);
+            }
+        }
+        for row in referenced_by {
+            if let Ok(table_name) = row.try_get::<String, _>("table_name") {
+                related_tables.push(table_name);
+            }
+        }
+
+        // Get schemas for related tables
+        let related_schemas = if !related_tables.is_empty() {
+            let refs: Vec<&str> = related_tables.iter().map(|s| s.as_str()).collect();
+            self.tables_schema(refs).await?
+        } else {
+            json!({})
+        };
+
+        Ok(json!({
+            "main_table": main_schema[table],
+            "related_tables": related_schemas,
+            "relationship_count": related_tables.len(),
+        }))
+    }
+
+    pub(super) async fn database_stats(&self) -> Result<Value, Box<dyn Error + Send + Sync>> {
+        let pool = self.get_pool().await?;
+
+        // Get overall statistics
+        let stats = sqlx::query(
+            "SELECT 
+                COUNT(DISTINCT table_name) as table_count,
+                COALESCE(SUM(pg_total_relation_size(quote_ident(table_schema)||'.'||quote_ident(table_name)::text))::bigint, 0) as total_size
+             FROM information_schema.tables
+             WHERE table_schema = $1 AND table_type = 'BASE TABLE'"
+        )
+        .bind(&self.schema)
+        .fetch_one(&pool)
+        .await?;
+
+        // Get largest tables
+        let largest_tables = sqlx::query(
+            "SELECT 
+                table_name,
+                COALESCE(pg_total_relation_size(quote_ident(table_schema)||'.'||quote_ident(table_name)::text)::bigint, 0) as size_bytes,
+                pg_size_pretty(pg_total_relation_size(quote_ident(table_schema)||'.'||quote_ident(table_name)::text)) as size_human
+             FROM information_schema.tables
+             WHERE table_schema = $1 AND table_type = 'BASE TABLE'
+             ORDER BY pg_total_relation_size(quote_ident(table_schema)||'.'||quote_ident(table_name)::text) DESC NULLS LAST
+             LIMIT 10"
+        )
+        .bind(&self.schema)
+        .fetch_all(&pool)
+        .await?;
+
+        // Get most connected tables
+        let connections = sqlx::query(
+            "WITH foreign_keys AS (
+                SELECT 
+                    tc.table_name,
+                    COUNT(*) as outgoing_fks
+                FROM information_schema.table_constraints AS tc
+                WHERE tc.constraint_type = 'FOREIGN KEY' AND tc.table_schema = $1
+                GROUP BY tc.table_name
+            ),
+            referenced AS (
+                SELECT 
+                    ccu.table_name,
+                    COUNT(*) as incoming_fks
+                FROM information_schema.table_constraints AS tc
+                JOIN information_schema.constraint_column_usage AS ccu
+                    ON ccu.constraint_name = tc.constraint_name AND ccu.table_schema = tc.table_schema
+                WHERE tc.constraint_type = 'FOREIGN KEY' AND tc.table_schema = $1
+                GROUP BY ccu.table_name
+            )
+            SELECT 
+                t.table_name,
+                COALESCE(f.outgoing_fks, 0) as references_count,
+                COALESCE(r.incoming_fks, 0) as referenced_by_count,
+                COALESCE(f.outgoing_fks, 0) + COALESCE(r.incoming_fks, 0) as total_connections
+            FROM information_schema.tables t
+            LEFT JOIN foreign_keys f ON t.table_name = f.table_name
+            LEFT JOIN referenced r ON t.table_name = r.table_name
+            WHERE t.table_schema = $1 AND t.table_type = 'BASE TABLE'
+                AND (f.outgoing_fks > 0 OR r.incoming_fks > 0)
+            ORDER BY total_connections DESC
+            LIMIT 10"
+        )
+        .bind(&self.schema)
+        .fetch_all(&pool)
+        .await?;
+
+        Ok(json!({
+            "summary": {
+                "total_tables": stats.get::<i64, _>("table_count"),
+                "total_size_bytes": stats.get::<i64, _>("total_size"),
+                "total_size_human": format_bytes(stats.get::<i64, _>("total_size") as u64),
+            },
+            "largest_tables": largest_tables.iter().map(|t| json!({
+                "name": t.get::<String, _>("table_name"),
+                "size_bytes": t.get::<i64, _>("size_bytes"),
+                "size_human": t.get::<String, _>("size_human"),
+            })).collect::<Vec<_>>(),
+            "most_connected_tables": connections.iter().map(|c| json!({
+                "name": c.get::<String, _>("table_name"),
+                "references_count": c.get::<i64, _>("references_count"),
+                "referenced_by_count": c.get::<i64, _>("referenced_by_count"),
+                "total_connections": c.get::<i64, _>("total_connections"),
+            })).collect::<Vec<_>>(),
+        }))
+    }
+}
@@ -0,0 +1,142 @@
+//! Running read queries, on a read replica when one is configured
+//!
+//! Reads go to the first replica that answers; a replica that can't be reached
+//! is skipped, and when none answers the primary runs the query.
+
+use serde_json::{json, Value};
+use sqlx::{postgres::PgPool, Column, Row as SqlxRow};
+use std::error::Error;
+use tracing::{debug, info, warn};
+
+use super::super::super::core::base::DataSourceConnector;
+use super::super::super::common::read_replica::{route_query, QueryRoute};
+use super::super::super::pooling::{get_pool_manager, DatabasePool};
+use super::super::common::dedupe_column_names;
+use super::{pg_cell, PostgreSQLConnector};
+
+impl PostgreSQLConnector {
+    /// Run a query and convert its rows to JSON, optionally inside a read-only transaction
+    pub(super) async fn run_query(&self, query: &str, limit: i32, read_only: bool) -> Result<Value, Box<dyn Error + Send + Sync>> {
+        // Log the schema being used
+        info!("Executing query with schema: {}", self.schema);
+        info!("Original query: {}", query);
+
+        // No need to set search_path here anymore - it's set in the connection string
+        // Add LIMIT if not present
+        let query_with_limit = self.apply_limit(query, limit);
+        
+        info!("Final query to execute: {}", query_with_limit);
+
+        let start = std::time::Instant::now();
+        let replica_rows = match route_query(!self.replicas.is_empty(), query) {
+            QueryRoute::Replica => self.fetch_rows_on_replica(&query_with_limit, read_only).await,
+            QueryRoute::Primary => None,
+        };
+        let rows = match replica_rows {
+            Some(rows) => rows?,
+            None => {
+                let pool = self.get_pool().await?;
+                self.fetch_rows(&pool, &query_with_limit, read_only).await?
+            }
+        };
+        let execution_time_ms = start.elapsed().as_millis() as i64;
+        
+        debug!("Query returned {} rows", rows.len());
+
+        if rows.is_empty() {
+            // Still report the result shape for queries that matched nothing
+            let columns = self.describe_query_columns(query).await.unwrap_or_else(|e| {
+                debug!("Could not describe columns for empty result: {}", e);
+                Vec::new()
+            });
+            return Ok(json!({
+                "columns": columns,
+                "rows": [],
+                "row_count": 0,
+                "execution_time_ms": execution_time_ms
+            }));
+        }
+
+        // Get column names from the first row
+        let first_row = &rows[0];
+        let columns: Vec<String> = dedupe_column_names(
+            first_row
+                .columns()
+                .iter()
+                .map(|c| c.name().to_string())
+                .collect(),
+        );
+
+        // Convert rows to JSON
+        let mut result_rows = Vec::new();
+        for row in rows.iter() {
+            let row_data: Vec<Value> = (0..columns.len())
+                .map(|i| pg_cell(row, i, &columns[i]).into_json())
+                .collect();
+            result_rows.push(row_data);
+        }
+
+        Ok(json!({
+            "columns": columns,
+            "rows": result_rows,
+            "row_count": result_rows.len(),
+            "execution_time_ms": execution_time_ms,
+            "query": query
+        }))
+    }
+
+    async fn fetch_rows(
+        &self,
+        pool: &PgPool,
+        sql: &str,
+        read_only: bool,
+    ) -> Result<Vec<sqlx::postgres::PgRow>, Box<dyn Error + Send + Sync>> {
+        if !read_only {
+            return Ok(sqlx::query(sql).fetch_all(pool).await?);
+        }
+
+        // Any write the query attempts fails at the database, not just our SELECT check
+        let mut tx = pool.begin().await?;
+        sqlx::query("SET TRANSACTION READ ONLY").execute(&mut *tx).await?;
+        let rows = sqlx::query(sql).fetch_all(&mut *tx).await;
+        tx.rollback().await?;
+        Ok(rows?)
+    }
+
+    /// Run a read on the first replica that answers. None means no replica
+    /// could be reached and the caller should use the primary.
+    async fn fetch_rows_on_replica(
+        &self,
+        sql: &str,
+        read_only: bool,
+    ) -> Option<Result<Vec<sqlx::postgres::PgRow>, Box<dyn Error + Send + Sync>>> {
+        let pool_manager = get_pool_manager().await;
+        for (index, replica) in self.replicas.iter().enumerate() {
+            let pool_id = format!("{}:replica-{}", self.datasource_id, index);
+            let pool = match pool_manager.get_pool(&pool_id, "postgresql", replica).await {
+                Ok(DatabasePool::PostgreSQL(pool)) => pool,
+                Ok(_) => continue,
+                Err(e) => {
+                    warn!("Read replica {} of datasource {} unavailable: {}", index, self.datasource_id, e);
+                    continue;
+                }
+            };
+            match self.fetch_rows(&pool, sql, read_only).await {
+                Err(e) if is_connection_error(&*e) => {
+                    warn!("Read replica {} of datasource {} lost its connection: {}", index, self.datasource_id, e);
+                }
+                result => return Some(result),
+            }
+        }
+        warn!("No read replica reachable for datasource {}, falling back to the primary", self.datasource_id);
+        None
+    }
+}
+
+/// Errors that mean the server could not be reached, as opposed to a failing statement
+fn is_connection_error(error: &(dyn Error + Send + Sync + 'static)) -> bool {
+    matches!(
+        error.downcast_ref::<sqlx::Error>(),
+        Some(sqlx::Error::Io(_) | sqlx::Error::Tls(_) | sqlx::Error::PoolTimedOut | sqlx::Error::PoolClosed | sqlx::Error::WorkerCrashed)
+    )
+}
@@ -0,0 +1,262 @@
+//! Table browser pages for PostgreSQL
+//!
+//! Column filters, sort keys and the keyset cursor all become parameterized
+//! SQL, so a page is narrowed and ordered by the server rather than after the
+//! fetch.
+
+use serde_json::{json, Value};
+use sqlx::{Column, Row as SqlxRow};
+use std::error::Error;
+use std::time::Instant;
+
+use super::super::common::IdentifierQuote;
+use super::super::table_filters::{postgres_filter_clause, FilterParam, TableFilters};
+use super::super::table_keyset::{cursor_text, keyset_key, keyset_operator};
+use super::super::table_sort::{check_sort_columns, order_by_clause, SortKey};
+use super::{pg_cell, quote_ident, PostgreSQLConnector};
+
+impl PostgreSQLConnector {
+    /// A page of table rows: by OFFSET for `page`, or after the keyset cursor
+    /// when `after` is given, selecting only `columns` when provided
+    #[allow(clippy::too_many_arguments)]
+    pub(super) async fn filtered_table_page(
+        &self,
+        table_name: &str,
+        page: i32,
+        limit: i32,
+        sort: &[SortKey],
+        filters: &TableFilters,
+        after: Option<&Value>,
+        columns: Option<&[String]>,
+    ) -> Result<Value, Box<dyn Error + Send + Sync>> {
+        let pool_start = Instant::now();
+        let pool = self.get_pool().await?;
+        let pool_time = pool_start.elapsed().as_millis() as u64;
+        
+        let start = Instant::now();
+
+        // The same parameterized WHERE narrows the count and the page, so totals match
+        let columns = columns.filter(|columns| !columns.is_empty());
+        let (where_clause, filter_params, order_clause) = if filters.is_empty() && sort.is_empty() && columns.is_none() {
+            (String::new(), Vec::new(), String::new())
+        } else {
+            let table_columns: Vec<String> = sqlx::query_scalar(
+                "SELECT column_name::text FROM information_schema.columns WHERE table_schema = $1 AND table_name = $2 ORDER BY ordinal_position",
+            )
+            .bind(&self.schema)
+            .bind(table_name)
+            .fetch_all(&pool)
+            .await?;
+            check_sort_columns(sort, &table_columns)?;
+            let unknown: Vec<&str> = columns
+                .unwrap_or_default()
+                .iter()
+                .filter(|column| !table_columns.contains(column))
+                .map(String::as_str)
+                .collect();
+            if !unknown.is_empty() {
+                return Err(format!("Unknown column(s): {}", unknown.join(", ")).into());
+            }
+            let (where_clause, filter_params) = postgres_filter_clause(&table_columns, filters)?;
+            (where_clause, filter_params, order_by_clause(sort, IdentifierQuote::DoubleQuote, true))
+        };
+
+        // Keyset mode adds `col > cursor` to the page query only; the total
+        // still counts every matching row. The cursor is bound as text and cast
+        // to the column's type so it compares by value, not as a string.
+        let mut page_where = where_clause.clone();
+        let mut page_params = filter_params.clone();
+        if let Some(cursor) = after {
+            let key = keyset_key(sort)?;
+            let column_type: String = sqlx::query_scalar(
+                "SELECT udt_name::text FROM information_schema.columns WHERE table_schema = $1 AND table_name = $2 AND column_name = $3",
+            )
+            .bind(&self.schema)
+            .bind(table_name)
+            .bind(&key.column)
+            .fetch_one(&pool)
+            .await?;
+            page_params.push(FilterParam::Text(cursor_text(cursor)?));
+            let condition = format!(
+                "{} {} ${}::{}",
+                quote_ident(&key.column),
+                keyset_operator(key),
+                page_params.len(),
+                quote_ident(&column_type)
+            );
+            page_where = if page_where.is_empty() {
+                format!(" WHERE {}", condition)
+            } else {
+                format!("{} AND {}", page_where, condition)
+            };
+        }
+        
+        // First, get the total count
+        let count_start = Instant::now();
+        let count_query = format!("SELECT COUNT(*) as total FROM {}.{}{}", quote_ident(&self.schema), table_name, where_clause);
+        let count_row = bind_filter_params(sqlx::query(&count_query), &filter_params)
+            .fetch_one(&pool)
+            .await?;
+        let total_rows: i64 = count_row.try_get("total")?;
+        let count_time = count_start.elapsed().as_millis() as u64;
+        
+        // Build the data query, selecting only the projected columns
+        let select_list = match columns {
+            Some(columns) => columns.iter().map(|c| quote_ident(c)).collect::<Vec<_>>().join(", "),
+            None => "*".to_string(),
+        };
+        let mut query = format!(
+            "SELECT {} FROM {}.{}{}{}",
+            select_list,
+            quote_ident(&self.schema),
+            table_name,
+            page_where,
+            order_clause
+        );
+        
+        // Add pagination
+        if after.is_some() {
+            query.push_str(&format!(" LIMIT {}", limit));
+        } else {
+            let offset = (page - 1) * limit;
+            query.push_str(&format!(" LIMIT {} OFFSET {}", limit, offset));
+        }
+        
+        // Execute the data query
+        let data_start = Instant::now();
+        let rows = bind_filter_params(sqlx::query(&query), &page_params)
+            .fetch_all(&pool)
+            .await?;
+        let data_time = data_start.elapsed().as_millis() as u64;
+
+        let execution_time_ms = start.elapsed().as_millis() as u64;
+
+        if rows.is_empty() {
+            return Ok(json!({
+                "columns": [],
+                "rows": [],
+                "row_count": rows.len(),
+                "total_rows": total_rows,
+                "execution_time_ms": execution_time_ms,
+                "timing_breakdown": {
+                    "pool_access_ms": pool_time,
+                    "count_query_ms": count_time,
+                    "data_query_ms": data_time,
+                    "total_db_ms": execution_time_ms
+                },
+                "page": page,
+                "page_size": limit
+            }));
+        }
+
+        // Get column names from the first row
+        let first_row = &rows[0];
+        let columns: Vec<String> = first_row
+            .columns()
+            .iter()
+            .map(|c| c.name().to_string())
+            .collect();
+
+        // Convert rows to JSON
+        let mut result_rows = Vec::new();
+        for row in rows.iter() {
+            let row_data: Vec<Value> = (0..columns.len())
+                .map(|i| pg_cell(row, i, &columns[i]).into_json())
+                .collect();
+            result_rows.push(row_data);
+        }
+
+        Ok(json!({
+            "columns": columns,
+            "rows": result_rows,
+            "row_count": result_rows.len(),
+            "total_rows": total_rows,
+            "execution_time_ms": execution_time_ms,
+            "timing_breakdown": {
+                "pool_access_ms": pool_time,
+                "count_query_ms": count_time,
+                "data_query_ms": data_time,
+                "total_db_ms": execution_time_ms
+            },
+            "page": page,
+            "page_size": limit
+        }))
+    }
+}
+
+type PgQuery<'q> = sqlx::query::Query<'q, sqlx::Postgres, sqlx::postgres::PgArguments>;
+
+// Bind table browser filter values to their placeholders, in order
+fn bind_filter_params<'q>(mut query: PgQuery<'q>, params: &'q [FilterParam]) -> PgQuery<'q> {
+    for param in params {
+        query = match param {
+            FilterParam::Text(text) => query.bind(text),
+            FilterParam::TextList(values) => query.bind(values),
+        };
+    }
+    query
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::super::super::super::core::base::DataSourceConnector;
+    use super::super::super::table_keyset::next_cursor;
+    use sqlx::postgres::PgPool;
+    use uuid::Uuid;
+
+    #[tokio::test]
+    #[ignore = "needs a PostgreSQL server in TEST_DATABASE_URL"]
+    async fn keyset_pages_match_offset_pages() {
+        let url = std::env::var("TEST_DATABASE_URL").expect("Set TEST_DATABASE_URL");
+        let table = format!("keyset_pages_{}", Uuid::new_v4().simple());
+        let pool = PgPool::connect(&url).await.unwrap();
+        sqlx::query(&format!(
+            "CREATE TABLE public.{} AS SELECT i * 3 AS id, 'row ' || i AS name FROM generate_series(1, 47) i",
+            table
+        ))
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        let connector = PostgreSQLConnector::new(&json!({ "url": url })).unwrap();
+        let filters = TableFilters::from_value(Some(&json!({ "name": "row" })));
+        for direction in ["asc", "desc"] {
+            let key = SortKey {
+                column: "id".to_string(),
+                direction: Some(direction.to_string()),
+                nulls: None,
+            };
+
+            let mut by_offset = Vec::new();
+            for page in 1..=5 {
+                let result = connector
+                    .get_filtered_table_data(&table, page, 10, std::slice::from_ref(&key), &filters, None)
+                    .await
+                    .unwrap();
+                by_offset.extend(result["rows"].as_array().unwrap().clone());
+            }
+
+            let first = connector
+                .get_filtered_table_data(&table, 1, 10, std::slice::from_ref(&key), &filters, None)
+                .await
+                .unwrap();
+            let mut by_cursor = first["rows"].as_array().unwrap().clone();
+            let mut cursor = next_cursor(&first, "id", 10);
+            while !cursor.is_null() {
+                let result = connector
+                    .get_table_data_after_cursor(&table, 10, &key, &cursor, &filters, None)
+                    .await
+                    .unwrap();
+                assert_eq!(result["total_rows"], json!(47));
+                by_cursor.extend(result["rows"].as_array().unwrap().clone());
+                cursor = next_cursor(&result, "id", 10);
+            }
+
+            assert_eq!(by_offset.len(), 47, "{}", direction);
+            assert_eq!(by_cursor, by_offset, "{}", direction);
+        }
+
+        sqlx::query(&format!("DROP TABLE public.{}", table)).execute(&pool).await.unwrap();
+    }
+}
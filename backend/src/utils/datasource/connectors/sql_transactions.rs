@@ -0,0 +1,117 @@
+//! Transaction-scoped statement execution shared by the sqlx connectors
+//!
+//! PostgreSQL, MySQL and SQLite all run `data_query_write` statements the same
+//! way: inside a transaction that is committed, or rolled back for a dry run.
+//! sqlx has no database-generic rows-affected accessor, so `RowsAffected`
+//! bridges the three result types.
+
+use serde_json::{json, Value};
+use sqlx::{Database, Executor, IntoArguments, Pool};
+use std::error::Error;
+use std::time::Instant;
+
+/// Rows changed by a statement, for the sqlx result types of each dialect
+pub trait RowsAffected {
+    fn rows_affected(&self) -> u64;
+}
+
+impl RowsAffected for sqlx::postgres::PgQueryResult {
+    fn rows_affected(&self) -> u64 {
+        sqlx::postgres::PgQueryResult::rows_affected(self)
+    }
+}
+
+impl RowsAffected for sqlx::mysql::MySqlQueryResult {
+    fn rows_affected(&self) -> u64 {
+        sqlx::mysql::MySqlQueryResult::rows_affected(self)
+    }
+}
+
+impl RowsAffected for sqlx::sqlite::SqliteQueryResult {
+    fn rows_affected(&self) -> u64 {
+        sqlx::sqlite::SqliteQueryResult::rows_affected(self)
+    }
+}
+
+/// Run a data-modifying statement in a transaction and report the rows it
+/// affected. With `dry_run` the transaction is rolled back instead of committed.
+pub async fn execute_write_statement<DB>(
+    pool: &Pool<DB>,
+    statement: &str,
+    dry_run: bool,
+) -> Result<Value, Box<dyn Error + Send + Sync>>
+where
+    DB: Database,
+    DB::QueryResult: RowsAffected,
+    for<'c> &'c mut DB::Connection: Executor<'c, Database = DB>,
+    for<'q> DB::Arguments<'q>: IntoArguments<'q, DB>,
+{
+    let start = Instant::now();
+    let mut tx = pool.begin().await?;
+    let rows_affected = sqlx::query::<DB>(statement).execute(&mut *tx).await?.rows_affected();
+    if dry_run {
+        tx.rollback().await?;
+    } else {
+        tx.commit().await?;
+    }
+
+    Ok(json!({
+        "rows_affected": rows_affected,
+        "committed": !dry_run,
+        "execution_time_ms": start.elapsed().as_millis() as i64
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sqlx::sqlite::SqlitePoolOptions;
+
+    async fn pool_with_rows() -> Pool<sqlx::Sqlite> {
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        sqlx::query("CREATE TABLE items (id INTEGER PRIMARY KEY, name TEXT)")
+            .execute(&pool)
+            .await
+            .unwrap();
+        sqlx::query("INSERT INTO items (name) VALUES ('a'), ('b'), ('c')")
+            .execute(&pool)
+            .await
+            .unwrap();
+        pool
+    }
+
+    async fn count(pool: &Pool<sqlx::Sqlite>) -> i64 {
+        sqlx::query_scalar("SELECT COUNT(*) FROM items")
+            .fetch_one(pool)
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn write_statement_commits() {
+        let pool = pool_with_rows().await;
+        let result = execute_write_statement(&pool, "DELETE FROM items WHERE name <> 'a'", false)
+            .await
+            .unwrap();
+
+        assert_eq!(result["rows_affected"], 2);
+        assert_eq!(result["committed"], true);
+        assert_eq!(count(&pool).await, 1);
+    }
+
+    #[tokio::test]
+    async fn dry_run_rolls_back() {
+        let pool = pool_with_rows().await;
+        let result = execute_write_statement(&pool, "DELETE FROM items", true)
+            .await
+            .unwrap();
+
+        assert_eq!(result["rows_affected"], 3);
+        assert_eq!(result["committed"], false);
+        assert_eq!(count(&pool).await, 3);
+    }
+}
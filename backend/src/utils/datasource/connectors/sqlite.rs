@@ -1,10 +1,13 @@
-use super::super::core::base::{format_bytes, DataSourceConnector};
+use super::super::core::base::{format_bytes, ColumnSummary, DataSourceConnector};
 use async_trait::async_trait;
 use serde_json::{json, Value};
-use sqlx::{sqlite::SqlitePool, Column, Row as SqlxRow};
+use sqlx::{sqlite::{SqlitePool, SqlitePoolOptions}, Column, Executor, Row as SqlxRow, Statement};
 use std::error::Error;
-use tracing::info;
+use tracing::{debug, info};
 use super::super::pooling::{get_pool_manager, DatabasePool};
+use super::sql_transactions::execute_write_statement;
+use super::common::{dedupe_column_names, quote_identifier, IdentifierQuote};
+use super::table_distinct::{bound_distinct_values_query, search_pattern, BoundDialect};
 
 pub struct SQLiteConnector {
     connection_string: String,
@@ -54,7 +57,7 @@ impl SQLiteConnector {
     pub async fn create_pool(&self) -> Result<SqlitePool, Box<dyn Error + Send + Sync>> {
         info!("Creating new SQLite connection pool");
         let pool_creation_start = std::time::Instant::now();
-        let pool = SqlitePool::connect(&self.connection_string).await?;
+        let pool = pool_options().connect(&self.connection_string).await?;
         let creation_time = pool_creation_start.elapsed().as_millis();
         info!("SQLite pool creation took {}ms", creation_time);
         Ok(pool)
@@ -100,6 +103,104 @@ impl SQLiteConnector {
             }
         }
     }
+
+    /// Run a query and convert its rows to JSON, optionally inside a read-only transaction
+    async fn run_query(&self, query: &str, limit: i32, read_only: bool) -> Result<Value, Box<dyn Error + Send + Sync>> {
+        let pool = self
+            .get_pool()
+            .await
+?;
+
+        let query_with_limit = self.apply_limit(query, limit);
+
+        let start = std::time::Instant::now();
+        let rows = self.fetch_rows(&pool, &query_with_limit, read_only).await?;
+        let execution_time_ms = start.elapsed().as_millis() as i64;
+
+        if rows.is_empty() {
+            // Still report the result shape for queries that matched nothing
+            let columns = self.describe_query_columns(query).await.unwrap_or_else(|e| {
+                debug!("Could not describe columns for empty result: {}", e);
+                Vec::new()
+            });
+            return Ok(json!({
+                "columns": columns,
+                "rows": [],
+                "row_count": 0,
+                "execution_time_ms": execution_time_ms
+            }));
+        }
+
+        // Get column names from the first row
+        let first_row = &rows[0];
+        let columns: Vec<String> = dedupe_column_names(
+            first_row
+                .columns()
+                .iter()
+                .map(|c| c.name().to_string())
+                .collect(),
+        );
+
+        // Convert rows to JSON
+        let mut result_rows = Vec::new();
+        for row in rows.iter() {
+            let mut row_data = Vec::new();
+            for (i, _col) in columns.iter().enumerate() {
+                // Try to get value as different types
+                if let Ok(val) = row.try_get::<String, _>(i) {
+                    row_data.push(val);
+                } else if let Ok(val) = row.try_get::<i32, _>(i) {
+                    row_data.push(val.to_string());
+                } else if let Ok(val) = row.try_get::<i64, _>(i) {
+                    row_data.push(val.to_string());
+                } else if let Ok(val) = row.try_get::<f64, _>(i) {
+                    row_data.push(val.to_string());
+                } else if let Ok(val) = row.try_get::<f32, _>(i) {
+                    row_data.push(val.to_string());
+                } else if let Ok(val) = row.try_get::<bool, _>(i) {
+                    row_data.push(if val { "true" } else { "false" }.to_string());
+                } else {
+                    row_data.push("NULL".to_string());
+                }
+            }
+            result_rows.push(row_data);
+        }
+
+        Ok(json!({
+            "columns": columns,
+            "rows": result_rows,
+            "row_count": result_rows.len(),
+            "execution_time_ms": execution_time_ms
+        }))
+    }
+
+    async fn fetch_rows(
+        &self,
+        pool: &SqlitePool,
+        sql: &str,
+        read_only: bool,
+    ) -> Result<Vec<sqlx::sqlite::SqliteRow>, Box<dyn Error + Send + Sync>> {
+        if !read_only {
+            return Ok(sqlx::query(sql).fetch_all(pool).await?);
+        }
+
+        // SQLite has no read-only transactions; query_only rejects writes on this
+        // connection until the pool's after_release hook turns it off again
+        let mut conn = pool.acquire().await?;
+        conn.execute("PRAGMA query_only = ON").await?;
+        Ok(sqlx::query(sql).fetch_all(&mut *conn).await?)
+    }
+}
+
+// Read-only queries switch query_only on. Turning it off on release also covers
+// connections whose query future was dropped mid-read, e.g. by a timeout.
+fn pool_options() -> SqlitePoolOptions {
+    SqlitePoolOptions::new().after_release(|conn, _meta| {
+        Box::pin(async move {
+            conn.execute("PRAGMA query_only = OFF").await?;
+            Ok(true)
+        })
+    })
 }
 
 #[async_trait]
@@ -177,69 +278,34 @@ impl DataSourceConnector for SQLiteConnector {
     }
 
     async fn execute_query(&self, query: &str, limit: i32) -> Result<Value, Box<dyn Error + Send + Sync>> {
-        let pool = self
-            .get_pool()
-            .await
-?;
-
-        let query_with_limit = if query.to_lowercase().contains("limit") {
-            query.to_string()
-        } else {
-            format!("{} LIMIT {}", query, limit)
-        };
-
-        let start = std::time::Instant::now();
-        let rows = sqlx::query(&query_with_limit).fetch_all(&pool).await?;
-        let execution_time_ms = start.elapsed().as_millis() as i64;
+        self.run_query(query, limit, false).await
+    }
 
-        if rows.is_empty() {
-            return Ok(json!({
-                "columns": [],
-                "rows": [],
-                "row_count": 0,
-                "execution_time_ms": execution_time_ms
-            }));
-        }
+    async fn execute_read_only_query(&self, query: &str, limit: i32) -> Result<Value, Box<dyn Error + Send + Sync>> {
+        self.run_query(query, limit, true).await
+    }
 
-        // Get column names from the first row
-        let first_row = &rows[0];
-        let columns: Vec<String> = first_row
-            .columns()
-            .iter()
-            .map(|c| c.name().to_string())
-            .collect();
+    async fn execute_write(&self, statement: &str, dry_run: bool) -> Result<Value, Box<dyn Error + Send + Sync>> {
+        let pool = self.get_pool().await?;
+        execute_write_statement(&pool, statement, dry_run).await
+    }
 
-        // Convert rows to JSON
-        let mut result_rows = Vec::new();
-        for row in rows.iter() {
-            let mut row_data = Vec::new();
-            for (i, _col) in columns.iter().enumerate() {
-                // Try to get value as different types
-                if let Ok(val) = row.try_get::<String, _>(i) {
-                    row_data.push(val);
-                } else if let Ok(val) = row.try_get::<i32, _>(i) {
-                    row_data.push(val.to_string());
-                } else if let Ok(val) = row.try_get::<i64, _>(i) {
-                    row_data.push(val.to_string());
-                } else if let Ok(val) = row.try_get::<f64, _>(i) {
-                    row_data.push(val.to_string());
-                } else if let Ok(val) = row.try_get::<f32, _>(i) {
-                    row_data.push(val.to_string());
-                } else if let Ok(val) = row.try_get::<bool, _>(i) {
-                    row_data.push(if val { "true" } else { "false" }.to_string());
-                } else {
-                    row_data.push("NULL".to_string());
-                }
-            }
-            result_rows.push(row_data);
+    async fn get_distinct_values(
+        &self,
+        table_name: &str,
+        column: &str,
+        search: Option<&str>,
+        limit: i32,
+    ) -> Result<Vec<String>, Box<dyn Error + Send + Sync>> {
+        let pool = self.get_pool().await?;
+        let search = search.filter(|term| !term.is_empty());
+        let table = quote_identifier(table_name, IdentifierQuote::DoubleQuote);
+        let query = bound_distinct_values_query(BoundDialect::Sqlite, &table, column, search.is_some(), limit);
+        let mut values = sqlx::query_scalar::<_, Option<String>>(&query);
+        if let Some(term) = search {
+            values = values.bind(search_pattern(term));
         }
-
-        Ok(json!({
-            "columns": columns,
-            "rows": result_rows,
-            "row_count": result_rows.len(),
-            "execution_time_ms": execution_time_ms
-        }))
+        Ok(values.fetch_all(&pool).await?.into_iter().flatten().collect())
     }
 
     async fn fetch_schema(&self) -> Result<Value, Box<dyn Error + Send + Sync>> {
@@ -573,6 +639,24 @@ impl DataSourceConnector for SQLiteConnector {
         }))
     }
 
+    async fn list_table_columns(&self, table: &str) -> Result<Vec<ColumnSummary>, Box<dyn Error + Send + Sync>> {
+        let pool = self.get_pool().await?;
+        let rows = sqlx::query("SELECT name, type, \"notnull\", pk FROM pragma_table_info(?) ORDER BY cid")
+            .bind(table)
+            .fetch_all(&pool)
+            .await?;
+
+        Ok(rows
+            .iter()
+            .map(|row| ColumnSummary {
+                name: row.get("name"),
+                data_type: row.get("type"),
+                nullable: row.get::<i64, _>("notnull") == 0,
+                primary_key: row.get::<i64, _>("pk") > 0,
+            })
+            .collect())
+    }
+
     async fn get_related_tables(&self, table: &str) -> Result<Value, Box<dyn Error + Send + Sync>> {
         let pool = self
             .get_pool()
@@ -735,6 +819,15 @@ impl DataSourceConnector for SQLiteConnector {
         }))
     }
 
+    async fn describe_query_columns(&self, query: &str) -> Result<Vec<String>, Box<dyn Error + Send + Sync>> {
+        // Preparing the statement resolves its result columns without executing it
+        let pool = self.get_pool().await?;
+        let statement = pool.prepare(query).await?;
+        Ok(dedupe_column_names(
+            statement.columns().iter().map(|c| c.name().to_string()).collect(),
+        ))
+    }
+
     async fn get_table_data_with_pagination(
         &self, 
         table_name: &str, 
@@ -764,3 +857,44 @@ impl DataSourceConnector for SQLiteConnector {
         Ok(result)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn single_connection_pool() -> SqlitePool {
+        let pool = pool_options()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        sqlx::query("CREATE TABLE items (name TEXT)").execute(&pool).await.unwrap();
+        pool
+    }
+
+    #[tokio::test]
+    async fn read_only_reads_reject_writes() {
+        let pool = single_connection_pool().await;
+        let connector = SQLiteConnector::new(&json!({ "url": "sqlite::memory:" })).unwrap();
+
+        let write = connector
+            .fetch_rows(&pool, "INSERT INTO items VALUES ('a') RETURNING name", true)
+            .await;
+        assert!(write.is_err());
+        assert!(connector.fetch_rows(&pool, "SELECT name FROM items", true).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn released_connections_accept_writes_again() {
+        let pool = single_connection_pool().await;
+        let connector = SQLiteConnector::new(&json!({ "url": "sqlite::memory:" })).unwrap();
+        connector.fetch_rows(&pool, "SELECT name FROM items", true).await.unwrap();
+
+        // A read dropped mid-flight never reaches a reset of its own
+        let mut conn = pool.acquire().await.unwrap();
+        sqlx::raw_sql("PRAGMA query_only = ON").execute(&mut *conn).await.unwrap();
+        drop(conn);
+
+        sqlx::query("INSERT INTO items VALUES ('a')").execute(&pool).await.unwrap();
+    }
+}
@@ -1,4 +1,6 @@
 use super::super::core::base::{format_bytes, DataSourceConnector};
+use super::common::{dedupe_column_names, table_page_query, IdentifierQuote, LimitSyntax};
+use super::super::common::connection_config::connection_application_name;
 use async_trait::async_trait;
 use serde_json::{json, Value};
 use std::error::Error;
@@ -100,6 +102,9 @@ impl SqlServerConnector {
             tiberius_config.database(db);
         }
 
+        // Reported as program_name in sys.dm_exec_sessions
+        tiberius_config.application_name(connection_application_name(config));
+
         // Handle SSL/TLS configuration
         let disable_ssl = config
             .get("disable_ssl")
@@ -268,10 +273,39 @@ impl SqlServerConnector {
 
         Ok(client)
     }
+
+    /// Resolve result columns with sp_describe_first_result_set, which analyses
+    /// the statement without executing it
+    async fn describe_first_result_set(
+        client: &mut Client<Compat<TcpStream>>,
+        query: &str,
+    ) -> Result<Vec<String>, Box<dyn Error + Send + Sync>> {
+        let rows = client
+            .query("EXEC sp_describe_first_result_set @tsql = @P1", &[&query])
+            .await?
+            .into_first_result()
+            .await?;
+
+        let columns = rows
+            .iter()
+            .filter(|row| row.get::<bool, _>("is_hidden") != Some(true))
+            .map(|row| row.get::<&str, _>("name").unwrap_or("").to_string())
+            .collect();
+
+        Ok(dedupe_column_names(columns))
+    }
 }
 
 #[async_trait]
 impl DataSourceConnector for SqlServerConnector {
+    fn limit_syntax(&self) -> LimitSyntax {
+        LimitSyntax::Top
+    }
+
+    fn identifier_quote(&self) -> IdentifierQuote {
+        IdentifierQuote::Bracket
+    }
+
     async fn test_connection(&mut self) -> Result<bool, Box<dyn Error + Send + Sync>> {
         debug!("SQL Server Connection Test Started");
         debug!("Attempting SQL Server connection to: {}", self.server);
@@ -441,6 +475,11 @@ impl DataSourceConnector for SqlServerConnector {
         }
     }
 
+    async fn describe_query_columns(&self, query: &str) -> Result<Vec<String>, Box<dyn Error + Send + Sync>> {
+        let mut client = self.get_connection().await?;
+        Self::describe_first_result_set(&mut client, query).await
+    }
+
     async fn execute_query(&self, query: &str, limit: i32) -> Result<Value, Box<dyn Error + Send + Sync>> {
         let mut client = self.get_connection().await?;
 
@@ -465,18 +504,8 @@ impl DataSourceConnector for SqlServerConnector {
             }
         }
 
-        // Add TOP clause if not present (SQL Server specific)
-        let query_with_limit =
-            if query.to_lowercase().contains("top ") || query.to_lowercase().contains("limit ") {
-                query.to_string()
-            } else {
-                // SQL Server uses TOP instead of LIMIT
-                if query.to_lowercase().starts_with("select ") {
-                    format!("SELECT TOP {} {}", limit, &query[7..])
-                } else {
-                    query.to_string()
-                }
-            };
+        // Add TOP clause if not present (SQL Server uses TOP instead of LIMIT)
+        let query_with_limit = self.apply_limit(query, limit);
 
         let start = std::time::Instant::now();
         let stream = client.query(&query_with_limit, &[]).await?;
@@ -484,8 +513,15 @@ impl DataSourceConnector for SqlServerConnector {
         let execution_time_ms = start.elapsed().as_millis() as i64;
 
         if results.is_empty() || results[0].is_empty() {
+            // Still report the result shape for queries that matched nothing
+            let columns = Self::describe_first_result_set(&mut client, query)
+                .await
+                .unwrap_or_else(|e| {
+                    debug!("Could not describe columns for empty result: {}", e);
+                    Vec::new()
+                });
             return Ok(json!({
-                "columns": [],
+                "columns": columns,
                 "rows": [],
                 "row_count": 0,
                 "execution_time_ms": execution_time_ms
@@ -495,11 +531,13 @@ impl DataSourceConnector for SqlServerConnector {
         let rows = &results[0];
 
         // Get column names
-        let columns: Vec<String> = rows[0]
-            .columns()
-            .iter()
-            .map(|c| c.name().to_string())
-            .collect();
+        let columns: Vec<String> = dedupe_column_names(
+            rows[0]
+                .columns()
+                .iter()
+                .map(|c| c.name().to_string())
+                .collect(),
+        );
 
         // Convert rows to JSON
         let mut result_rows = Vec::new();
@@ -1225,19 +1263,16 @@ impl DataSourceConnector for SqlServerConnector {
         sort_direction: Option<&str>
     ) -> Result<Value, Box<dyn Error + Send + Sync>> {
         // TODO: Implement proper pagination with total count for SQL Server
-        let offset = (page - 1) * limit;
-        let mut query = format!("SELECT * FROM {}", table_name);
-        
-        if let Some(sort_col) = sort_column {
-            let direction = sort_direction.unwrap_or("ASC");
-            query.push_str(&format!(" ORDER BY {} {}", sort_col, direction));
-        } else {
-            // SQL Server requires ORDER BY for OFFSET
-            query.push_str(" ORDER BY (SELECT NULL)");
-        }
-        
-        query.push_str(&format!(" OFFSET {} ROWS FETCH NEXT {} ROWS ONLY", offset, limit));
-        
+        let query = table_page_query(
+            table_name,
+            sort_column,
+            sort_direction,
+            page,
+            limit,
+            LimitSyntax::Top,
+            IdentifierQuote::Bracket,
+        );
+
         let mut result = self.execute_query(&query, limit).await?;
         
         // Add pagination metadata (temporary - needs proper total count)
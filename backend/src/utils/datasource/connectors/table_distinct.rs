@@ -0,0 +1,172 @@
+//! Distinct column values for the table browser's filter dropdowns
+//!
+//! Connectors that bind parameters (PostgreSQL, MySQL, SQLite) bind the search
+//! term. The rest get it inlined as a LIKE pattern whose quote and backslash
+//! characters are `_` wildcards, so it can't leave the string literal under
+//! any dialect's escaping rules; the wildcard still matches the original
+//! character.
+
+use serde_json::Value;
+
+use super::common::{quote_identifier, IdentifierQuote};
+use super::table_filters::contains_pattern;
+
+/// Dialects whose connectors bind the search pattern
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BoundDialect {
+    Postgres,
+    MySql,
+    Sqlite,
+}
+
+/// `SELECT DISTINCT` of `column` as text from `table` (already quoted), with a
+/// placeholder for the search pattern when `searching`. Bind
+/// `search_pattern(term)` to it.
+pub fn bound_distinct_values_query(
+    dialect: BoundDialect,
+    table: &str,
+    column: &str,
+    searching: bool,
+    limit: i32,
+) -> String {
+    let (quote, value, condition) = match dialect {
+        BoundDialect::Postgres => (IdentifierQuote::DoubleQuote, "{}::text", "{}::text ILIKE $1"),
+        BoundDialect::MySql => (IdentifierQuote::Backtick, "CAST({} AS CHAR)", "CAST({} AS CHAR) LIKE ?"),
+        // SQLite's LIKE has no escape character unless one is named
+        BoundDialect::Sqlite => (IdentifierQuote::DoubleQuote, "CAST({} AS TEXT)", "CAST({} AS TEXT) LIKE ? ESCAPE '\\'"),
+    };
+    let column = quote_identifier(column, quote);
+
+    let mut query = format!("SELECT DISTINCT {} AS value FROM {}", value.replace("{}", &column), table);
+    if searching {
+        query.push_str(&format!(" WHERE {}", condition.replace("{}", &column)));
+    }
+    query.push_str(&format!(" LIMIT {}", limit));
+    query
+}
+
+/// The value bound to the search placeholder: `%term%` with LIKE wildcards escaped
+pub fn search_pattern(term: &str) -> String {
+    contains_pattern(term)
+}
+
+/// `SELECT DISTINCT` of `column` from `table` with the search inlined; the
+/// caller applies the row limit in its dialect
+pub fn inline_distinct_values_query(
+    table: &str,
+    column: &str,
+    search: Option<&str>,
+    quote: IdentifierQuote,
+) -> String {
+    let column = quote_identifier(column, quote);
+    let mut query = format!("SELECT DISTINCT {} FROM {}", column, quote_identifier(table, quote));
+    if let Some(term) = search.filter(|term| !term.is_empty()) {
+        query.push_str(&format!(" WHERE {} LIKE '{}'", column, inline_like_pattern(term)));
+    }
+    query
+}
+
+fn inline_like_pattern(term: &str) -> String {
+    let term: String = term
+        .chars()
+        .map(|c| if matches!(c, '\'' | '\\') { '_' } else { c })
+        .collect();
+    format!("%{}%", term)
+}
+
+/// The first cell of each result row as text, NULLs left out
+pub fn first_column_values(result: &Value) -> Vec<String> {
+    let rows = result
+        .get("rows")
+        .or_else(|| result.get("data"))
+        .and_then(|rows| rows.as_array())
+        .cloned()
+        .unwrap_or_default();
+
+    rows.iter()
+        .filter_map(|row| match row {
+            Value::Array(cells) => cells.first().cloned(),
+            Value::Object(obj) => obj.values().next().cloned(),
+            _ => None,
+        })
+        .filter_map(|value| match value {
+            Value::Null => None,
+            Value::String(s) => Some(s),
+            other => Some(other.to_string()),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn bound_queries_keep_the_term_out_of_the_sql() {
+        assert_eq!(
+            bound_distinct_values_query(BoundDialect::Postgres, r#""public"."Order Details""#, "Ship City", true, 50),
+            r#"SELECT DISTINCT "Ship City"::text AS value FROM "public"."Order Details" WHERE "Ship City"::text ILIKE $1 LIMIT 50"#
+        );
+        assert_eq!(
+            bound_distinct_values_query(BoundDialect::MySql, "`orders`", "city", true, 10),
+            "SELECT DISTINCT CAST(`city` AS CHAR) AS value FROM `orders` WHERE CAST(`city` AS CHAR) LIKE ? LIMIT 10"
+        );
+        assert_eq!(
+            bound_distinct_values_query(BoundDialect::Sqlite, "\"orders\"", "city", false, 10),
+            "SELECT DISTINCT CAST(\"city\" AS TEXT) AS value FROM \"orders\" LIMIT 10"
+        );
+        assert_eq!(search_pattern("50%_off"), "%50\\%\\_off%");
+    }
+
+    #[tokio::test]
+    async fn bound_search_matches_quotes_and_wildcards_literally() {
+        let pool = sqlx::sqlite::SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        sqlx::query("CREATE TABLE t (c TEXT)").execute(&pool).await.unwrap();
+        sqlx::query("INSERT INTO t VALUES ('it''s'), ('its'), ('50%'), ('500')")
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        let query = bound_distinct_values_query(BoundDialect::Sqlite, "\"t\"", "c", true, 10);
+        for (term, expected) in [("'", vec!["it's"]), ("0%", vec!["50%"])] {
+            let values: Vec<Option<String>> = sqlx::query_scalar(&query)
+                .bind(search_pattern(term))
+                .fetch_all(&pool)
+                .await
+                .unwrap();
+            assert_eq!(values.into_iter().flatten().collect::<Vec<_>>(), expected);
+        }
+    }
+
+    #[test]
+    fn inlined_search_cannot_leave_the_literal() {
+        // A MySQL-style backslash escape would otherwise end the literal
+        let query = inline_distinct_values_query(
+            "Order Details",
+            "city",
+            Some("\\' OR 1=1 -- "),
+            IdentifierQuote::Backtick,
+        );
+        assert_eq!(
+            query,
+            "SELECT DISTINCT `city` FROM `Order Details` WHERE `city` LIKE '%__ OR 1=1 -- %'"
+        );
+        assert_eq!(
+            inline_distinct_values_query("t", "c", Some(""), IdentifierQuote::Bracket),
+            "SELECT DISTINCT [c] FROM [t]"
+        );
+    }
+
+    #[test]
+    fn values_come_from_the_first_column() {
+        let result = json!({ "columns": ["value"], "rows": [["a"], [null], [3]] });
+        assert_eq!(first_column_values(&result), vec!["a", "3"]);
+        let legacy = json!({ "data": [{ "city": "Oslo" }] });
+        assert_eq!(first_column_values(&legacy), vec!["Oslo"]);
+    }
+}
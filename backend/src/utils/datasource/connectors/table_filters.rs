@@ -0,0 +1,218 @@
+//! Filters sent by the table browser
+//!
+//! The browser posts `filters` as an object keyed by column name, plus an
+//! optional `global` key holding the search box text. Values never reach the
+//! SQL text: the clause builders return placeholders and the parameters to
+//! bind to them. Filters on columns the table doesn't have are rejected.
+
+use serde_json::Value;
+use std::error::Error;
+use std::fmt;
+
+/// Filter key holding the browser's global search text
+const GLOBAL_FILTER_KEY: &str = "global";
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TableFilters {
+    /// Text searched for in every column
+    pub global: Option<String>,
+    /// Per-column filters: a scalar matches as a substring, an array as one of its values
+    pub columns: Vec<(String, Value)>,
+}
+
+impl TableFilters {
+    pub fn from_value(filters: Option<&Value>) -> Self {
+        let Some(filters) = filters.and_then(|f| f.as_object()) else {
+            return Self::default();
+        };
+
+        let mut result = Self::default();
+        for (key, value) in filters {
+            if key == GLOBAL_FILTER_KEY {
+                result.global = value_text(value).filter(|term| !term.trim().is_empty());
+            } else if !is_blank(value) {
+                result.columns.push((key.clone(), value.clone()));
+            }
+        }
+        // Keep the generated SQL stable whatever order the keys arrived in
+        result.columns.sort_by(|a, b| a.0.cmp(&b.0));
+        result
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.global.is_none() && self.columns.is_empty()
+    }
+
+    /// Reject column filters naming columns the table doesn't have
+    pub fn check_columns(&self, columns: &[String]) -> Result<(), UnknownFilterColumns> {
+        let unknown: Vec<String> = self
+            .columns
+            .iter()
+            .filter(|(column, _)| !columns.contains(column))
+            .map(|(column, _)| column.clone())
+            .collect();
+        if unknown.is_empty() {
+            Ok(())
+        } else {
+            Err(UnknownFilterColumns(unknown))
+        }
+    }
+}
+
+/// Column filters on columns the table doesn't have; the API reports it as a
+/// bad request rather than a query failure
+#[derive(Debug, Clone, PartialEq)]
+pub struct UnknownFilterColumns(pub Vec<String>);
+
+impl fmt::Display for UnknownFilterColumns {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Unknown filter column(s): {}", self.0.join(", "))
+    }
+}
+
+impl Error for UnknownFilterColumns {}
+
+fn is_blank(value: &Value) -> bool {
+    match value {
+        Value::Null => true,
+        Value::String(s) => s.trim().is_empty(),
+        Value::Array(items) => items.is_empty(),
+        _ => false,
+    }
+}
+
+fn value_text(value: &Value) -> Option<String> {
+    match value {
+        Value::String(s) => Some(s.clone()),
+        Value::Number(n) => Some(n.to_string()),
+        Value::Bool(b) => Some(b.to_string()),
+        _ => None,
+    }
+}
+
+/// A value bound to a filter placeholder
+#[derive(Debug, Clone, PartialEq)]
+pub enum FilterParam {
+    Text(String),
+    TextList(Vec<String>),
+}
+
+/// `%term%` with LIKE wildcards in `term` escaped (backslash is the default escape)
+pub fn contains_pattern(term: &str) -> String {
+    let escaped = term
+        .replace('\\', "\\\\")
+        .replace('%', "\\%")
+        .replace('_', "\\_");
+    format!("%{}%", escaped)
+}
+
+/// PostgreSQL `WHERE` clause (with a leading space, or empty) for `filters`
+/// over a table with `columns`. Column filters are ANDed together and with the
+/// global search, which ORs a case-insensitive match across every column cast
+/// to text. Filters on columns the table doesn't have are an error.
+pub fn postgres_filter_clause(
+    columns: &[String],
+    filters: &TableFilters,
+) -> Result<(String, Vec<FilterParam>), UnknownFilterColumns> {
+    filters.check_columns(columns)?;
+    let quote = |name: &str| format!("\"{}\"", name.replace('"', "\"\""));
+    let mut conditions = Vec::new();
+    let mut params = Vec::new();
+
+    for (column, value) in &filters.columns {
+        match value {
+            Value::Array(items) => {
+                params.push(FilterParam::TextList(items.iter().filter_map(value_text).collect()));
+                conditions.push(format!("{}::text = ANY(${})", quote(column), params.len()));
+            }
+            value => {
+                let Some(text) = value_text(value) else { continue };
+                params.push(FilterParam::Text(contains_pattern(&text)));
+                conditions.push(format!("{}::text ILIKE ${}", quote(column), params.len()));
+            }
+        }
+    }
+
+    if let Some(term) = &filters.global {
+        if !columns.is_empty() {
+            params.push(FilterParam::Text(contains_pattern(term.trim())));
+            let placeholder = params.len();
+            let matches: Vec<String> = columns
+                .iter()
+                .map(|column| format!("{}::text ILIKE ${}", quote(column), placeholder))
+                .collect();
+            conditions.push(format!("({})", matches.join(" OR ")));
+        }
+    }
+
+    if conditions.is_empty() {
+        Ok((String::new(), params))
+    } else {
+        Ok((format!(" WHERE {}", conditions.join(" AND ")), params))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn columns() -> Vec<String> {
+        ["id", "name", "email"].iter().map(|c| c.to_string()).collect()
+    }
+
+    #[test]
+    fn global_search_matches_across_all_columns() {
+        let filters = TableFilters::from_value(Some(&json!({ "global": " ann_50% " })));
+        let (clause, params) = postgres_filter_clause(&columns(), &filters).unwrap();
+
+        assert_eq!(
+            clause,
+            r#" WHERE ("id"::text ILIKE $1 OR "name"::text ILIKE $1 OR "email"::text ILIKE $1)"#
+        );
+        // The term is bound, with LIKE wildcards escaped
+        assert_eq!(params, vec![FilterParam::Text("%ann\\_50\\%%".to_string())]);
+    }
+
+    #[test]
+    fn column_filters_combine_with_global_search() {
+        let filters = TableFilters::from_value(Some(&json!({
+            "name": "O'Brien",
+            "id": [1, 2],
+            "email": "",
+            "global": "example.com"
+        })));
+        let (clause, params) = postgres_filter_clause(&columns(), &filters).unwrap();
+
+        assert_eq!(
+            clause,
+            r#" WHERE "id"::text = ANY($1) AND "name"::text ILIKE $2 AND ("id"::text ILIKE $3 OR "name"::text ILIKE $3 OR "email"::text ILIKE $3)"#
+        );
+        assert_eq!(
+            params,
+            vec![
+                FilterParam::TextList(vec!["1".to_string(), "2".to_string()]),
+                FilterParam::Text("%O'Brien%".to_string()),
+                FilterParam::Text("%example.com%".to_string()),
+            ]
+        );
+
+        assert!(TableFilters::from_value(Some(&json!({ "global": "  " }))).is_empty());
+    }
+
+    #[test]
+    fn filters_on_unknown_columns_are_rejected() {
+        let filters = TableFilters::from_value(Some(&json!({
+            "name": "ann",
+            "missing": "x",
+            "nope": [1],
+            "absent": "",
+            "global": "ann"
+        })));
+
+        let err = postgres_filter_clause(&columns(), &filters).unwrap_err();
+        // Blank filters are dropped before the check, so "absent" isn't reported
+        assert_eq!(err, UnknownFilterColumns(vec!["missing".to_string(), "nope".to_string()]));
+        assert_eq!(err.to_string(), "Unknown filter column(s): missing, nope");
+    }
+}
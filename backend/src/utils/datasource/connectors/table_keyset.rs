@@ -0,0 +1,110 @@
+//! Keyset (cursor) pagination for the table browser
+//!
+//! Deep OFFSET pages make the database read and throw away every row before
+//! the page. With a `cursor`, the last-seen value of the single sort column,
+//! the next page is `WHERE col > cursor ORDER BY col LIMIT n` instead, which an
+//! index on the column answers directly. The column should be unique (a
+//! primary key is ideal): rows sharing the cursor value are skipped at a page
+//! boundary, and rows whose sort value is NULL can't be reached by cursor.
+
+use serde_json::Value;
+
+use super::table_sort::SortKey;
+
+/// The sort key a cursor pages by; keyset mode needs exactly one
+pub fn keyset_key(sort: &[SortKey]) -> Result<&SortKey, String> {
+    match sort {
+        [key] => Ok(key),
+        [] => Err("Cursor pagination needs a sort column".to_string()),
+        _ => Err("Cursor pagination supports a single sort column".to_string()),
+    }
+}
+
+/// `>` for ascending keys, `<` for descending
+pub fn keyset_operator(key: &SortKey) -> &'static str {
+    if key.descending() {
+        "<"
+    } else {
+        ">"
+    }
+}
+
+/// The cursor as text, for binding to a placeholder cast to the column's type
+pub fn cursor_text(cursor: &Value) -> Result<String, String> {
+    match cursor {
+        Value::String(s) => Ok(s.clone()),
+        Value::Number(n) => Ok(n.to_string()),
+        Value::Bool(b) => Ok(b.to_string()),
+        _ => Err("cursor must be a string, number or boolean".to_string()),
+    }
+}
+
+/// The cursor as a SQL literal, for dialects that can't bind parameters here
+pub fn cursor_literal(cursor: &Value) -> Result<String, String> {
+    match cursor {
+        Value::String(s) => Ok(format!("'{}'", s.replace('\'', "''"))),
+        Value::Number(n) => Ok(n.to_string()),
+        Value::Bool(b) => Ok(if *b { "TRUE" } else { "FALSE" }.to_string()),
+        _ => Err("cursor must be a string, number or boolean".to_string()),
+    }
+}
+
+/// Cursor for the page after `result`: the sort column's value in its last
+/// row, or null when the page came back short and there is nothing after it
+pub fn next_cursor(result: &Value, column: &str, limit: i32) -> Value {
+    let rows = match result.get("rows").and_then(|r| r.as_array()) {
+        Some(rows) if !rows.is_empty() && rows.len() as i64 >= limit as i64 => rows,
+        _ => return Value::Null,
+    };
+    let Some(last) = rows.last() else {
+        return Value::Null;
+    };
+
+    match last {
+        Value::Object(row) => row.get(column).cloned().unwrap_or(Value::Null),
+        Value::Array(cells) => result
+            .get("columns")
+            .and_then(|c| c.as_array())
+            .and_then(|columns| columns.iter().position(|c| c.as_str() == Some(column)))
+            .and_then(|index| cells.get(index))
+            .cloned()
+            .unwrap_or(Value::Null),
+        _ => Value::Null,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn keyset_mode_needs_exactly_one_sort_column() {
+        let key = |column: &str, direction: Option<&str>| SortKey {
+            column: column.to_string(),
+            direction: direction.map(str::to_string),
+            nulls: None,
+        };
+
+        assert!(keyset_key(&[]).is_err());
+        assert!(keyset_key(&[key("id", None), key("name", None)]).is_err());
+        assert_eq!(keyset_operator(keyset_key(&[key("id", None)]).unwrap()), ">");
+        assert_eq!(keyset_operator(&key("id", Some("DESC"))), "<");
+
+        assert_eq!(cursor_literal(&json!("O'Brien")).unwrap(), "'O''Brien'");
+        assert_eq!(cursor_literal(&json!(42)).unwrap(), "42");
+        assert!(cursor_literal(&json!({ "id": 1 })).is_err());
+    }
+
+    #[test]
+    fn next_cursor_is_the_last_rows_sort_value() {
+        let page = json!({ "columns": ["id", "name"], "rows": [[1, "a"], [2, "b"]] });
+        assert_eq!(next_cursor(&page, "id", 2), json!(2));
+        assert_eq!(next_cursor(&page, "name", 2), json!("b"));
+        // A short page is the last one
+        assert_eq!(next_cursor(&page, "id", 3), Value::Null);
+
+        let objects = json!({ "rows": [{ "id": 7 }] });
+        assert_eq!(next_cursor(&objects, "id", 1), json!(7));
+    }
+}
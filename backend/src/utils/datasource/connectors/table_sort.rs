@@ -0,0 +1,147 @@
+//! Sort order sent by the table browser
+//!
+//! Requests either carry a `sort` list of `{column, direction, nulls}` keys or
+//! the older single `sort_column`/`sort_direction` pair. Sort columns must
+//! exist in the table; the clause builders quote them and spell NULL
+//! placement in each dialect's syntax.
+
+use serde::{Deserialize, Serialize};
+
+use super::common::{quote_identifier, IdentifierQuote};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum NullsOrder {
+    First,
+    Last,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SortKey {
+    pub column: String,
+    /// "asc" (default) or "desc"
+    #[serde(default)]
+    pub direction: Option<String>,
+    /// Where NULLs go; the database default when omitted
+    #[serde(default)]
+    pub nulls: Option<NullsOrder>,
+}
+
+impl SortKey {
+    pub fn descending(&self) -> bool {
+        self.direction
+            .as_deref()
+            .is_some_and(|d| d.eq_ignore_ascii_case("desc"))
+    }
+}
+
+/// The request's sort keys: the `sort` list when given, otherwise the legacy
+/// single column with `nulls` applied to it
+pub fn sort_keys(
+    sort: Option<&[SortKey]>,
+    sort_column: Option<&str>,
+    sort_direction: Option<&str>,
+    nulls: Option<NullsOrder>,
+) -> Vec<SortKey> {
+    match sort {
+        Some(keys) if !keys.is_empty() => keys.to_vec(),
+        _ => sort_column
+            .map(|column| SortKey {
+                column: column.to_string(),
+                direction: sort_direction.map(str::to_string),
+                nulls,
+            })
+            .into_iter()
+            .collect(),
+    }
+}
+
+/// Reject sort keys naming columns the table doesn't have
+pub fn check_sort_columns(keys: &[SortKey], columns: &[String]) -> Result<(), String> {
+    match keys.iter().find(|key| !columns.contains(&key.column)) {
+        Some(key) => Err(format!("Unknown sort column '{}'", key.column)),
+        None => Ok(()),
+    }
+}
+
+/// ` ORDER BY ...` (with a leading space, or empty) for `keys`. Dialects
+/// without `NULLS FIRST/LAST` (MySQL, SQL Server) get a leading IS NULL key.
+pub fn order_by_clause(keys: &[SortKey], quote: IdentifierQuote, native_nulls: bool) -> String {
+    let terms: Vec<String> = keys
+        .iter()
+        .map(|key| {
+            let column = quote_identifier(&key.column, quote);
+            let direction = if key.descending() { "DESC" } else { "ASC" };
+            match (key.nulls, native_nulls) {
+                (None, _) => format!("{} {}", column, direction),
+                (Some(nulls), true) => format!(
+                    "{} {} NULLS {}",
+                    column,
+                    direction,
+                    if nulls == NullsOrder::First { "FIRST" } else { "LAST" }
+                ),
+                (Some(nulls), false) => format!(
+                    "CASE WHEN {} IS NULL THEN {} ELSE {} END, {} {}",
+                    column,
+                    if nulls == NullsOrder::First { 0 } else { 1 },
+                    if nulls == NullsOrder::First { 1 } else { 0 },
+                    column,
+                    direction
+                ),
+            }
+        })
+        .collect();
+
+    if terms.is_empty() {
+        String::new()
+    } else {
+        format!(" ORDER BY {}", terms.join(", "))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn keys() -> Vec<SortKey> {
+        serde_json::from_value(json!([
+            { "column": "last_name", "direction": "asc", "nulls": "last" },
+            { "column": "created_at", "direction": "DESC", "nulls": "first" },
+            { "column": "id" }
+        ]))
+        .unwrap()
+    }
+
+    #[test]
+    fn order_by_places_nulls_per_dialect() {
+        assert_eq!(
+            order_by_clause(&keys(), IdentifierQuote::DoubleQuote, true),
+            r#" ORDER BY "last_name" ASC NULLS LAST, "created_at" DESC NULLS FIRST, "id" ASC"#
+        );
+        assert_eq!(
+            order_by_clause(&keys()[1..2], IdentifierQuote::Bracket, false),
+            " ORDER BY CASE WHEN [created_at] IS NULL THEN 0 ELSE 1 END, [created_at] DESC"
+        );
+
+        // The legacy single column still works and picks up `nulls`
+        let legacy = sort_keys(None, Some("name"), Some("desc"), Some(NullsOrder::Last));
+        assert_eq!(
+            order_by_clause(&legacy, IdentifierQuote::Backtick, false),
+            " ORDER BY CASE WHEN `name` IS NULL THEN 1 ELSE 0 END, `name` DESC"
+        );
+        assert_eq!(order_by_clause(&[], IdentifierQuote::DoubleQuote, true), "");
+    }
+
+    #[test]
+    fn unknown_sort_columns_are_rejected() {
+        let columns: Vec<String> = ["id", "last_name", "created_at"].iter().map(|c| c.to_string()).collect();
+        assert!(check_sort_columns(&keys(), &columns).is_ok());
+
+        let bad = sort_keys(None, Some("id; DROP TABLE users"), None, None);
+        assert_eq!(
+            check_sort_columns(&bad, &columns),
+            Err("Unknown sort column 'id; DROP TABLE users'".to_string())
+        );
+    }
+}
@@ -1,7 +1,15 @@
 use async_trait::async_trait;
+use serde::Serialize;
 use serde_json::Value;
 use std::error::Error;
 
+use super::super::common::projection::{project_result_columns, ColumnRequest};
+use super::super::connectors::common::{apply_row_limit, IdentifierQuote, LimitSyntax};
+use super::super::connectors::table_distinct::{first_column_values, inline_distinct_values_query};
+use super::super::connectors::table_filters::TableFilters;
+use super::super::connectors::table_sort::SortKey;
+use super::row_stream::{batches_from_result, RowBatchStream};
+
 #[async_trait]
 #[allow(dead_code)]
 pub trait DataSourceConnector: Send + Sync {
@@ -9,6 +17,81 @@ pub trait DataSourceConnector: Send + Sync {
     #[allow(dead_code)]
     async fn test_connection(&mut self) -> Result<bool, Box<dyn Error + Send + Sync>>;
     async fn execute_query(&self, query: &str, limit: i32) -> Result<Value, Box<dyn Error + Send + Sync>>;
+
+    /// Execute a query that must not modify data. Dialects with read-only
+    /// transactions enforce this in the database; the rest fall back to a plain query.
+    async fn execute_read_only_query(&self, query: &str, limit: i32) -> Result<Value, Box<dyn Error + Send + Sync>> {
+        self.execute_query(query, limit).await
+    }
+
+    /// Run a data-modifying statement inside a transaction and report the rows it
+    /// affected. With `dry_run` the transaction is rolled back instead of committed.
+    async fn execute_write(&self, _statement: &str, _dry_run: bool) -> Result<Value, Box<dyn Error + Send + Sync>> {
+        Err("Write statements are not supported for this datasource type".into())
+    }
+
+    // Dialect-specific row limiting
+    fn limit_syntax(&self) -> LimitSyntax {
+        LimitSyntax::Limit
+    }
+
+    /// How the dialect quotes table and column names
+    fn identifier_quote(&self) -> IdentifierQuote {
+        IdentifierQuote::DoubleQuote
+    }
+
+    /// Apply a row limit to a user-supplied query unless it already has one
+    fn apply_limit(&self, query: &str, limit: i32) -> String {
+        apply_row_limit(query, limit, self.limit_syntax())
+    }
+
+    /// Rows of `query` in batches of at most `batch_size`, read as the stream is
+    /// consumed. `limit` caps the rows when given. The default runs the query
+    /// in full and splits the result; see `row_stream`.
+    async fn execute_query_stream(
+        &self,
+        query: &str,
+        limit: Option<i32>,
+        batch_size: usize,
+    ) -> Result<RowBatchStream, Box<dyn Error + Send + Sync>> {
+        let result = self.execute_query(query, limit.unwrap_or(i32::MAX)).await?;
+        Ok(batches_from_result(result, batch_size))
+    }
+
+    /// Resolve the column names a query would return without fetching any rows.
+    /// Used when a query yields an empty result so callers still get its shape.
+    async fn describe_query_columns(&self, _query: &str) -> Result<Vec<String>, Box<dyn Error + Send + Sync>> {
+        Ok(Vec::new())
+    }
+
+    /// A read-only query narrowed to the columns `columns` asks for. They are
+    /// chosen from `describe_query_columns` and selected from the query as a
+    /// subquery, so the rest are never fetched. Dialects that can't describe a
+    /// query are narrowed after fetching instead; a query that describes but
+    /// fails as a subquery reports that error rather than running again.
+    async fn execute_projected_query(
+        &self,
+        query: &str,
+        limit: i32,
+        columns: ColumnRequest<'_>,
+    ) -> Result<Value, Box<dyn Error + Send + Sync>> {
+        let available = self.describe_query_columns(query).await.unwrap_or_default();
+        if !available.is_empty() {
+            let projection = columns.plan(&available)?;
+            let mut result = if projection.selects_all {
+                self.execute_read_only_query(query, limit).await?
+            } else {
+                self.execute_read_only_query(&projection.subquery(query, self.identifier_quote()), limit)
+                    .await?
+            };
+            projection.annotate(&mut result);
+            return Ok(result);
+        }
+
+        let mut result = self.execute_read_only_query(query, limit).await?;
+        project_result_columns(&mut result, columns.requested, columns.max_columns)?;
+        Ok(result)
+    }
     
     // Table data methods
     #[allow(dead_code)]
@@ -21,6 +104,82 @@ pub trait DataSourceConnector: Send + Sync {
         sort_direction: Option<&str>
     ) -> Result<Value, Box<dyn Error + Send + Sync>>;
 
+    /// A page of table rows narrowed by the browser's column filters and global
+    /// search and ordered by `sort`, with only `columns` selected when given.
+    /// Dialects without filter support return the unfiltered page, sorted by
+    /// the first key only, with a note; they narrow the columns after fetching.
+    async fn get_filtered_table_data(
+        &self,
+        table_name: &str,
+        page: i32,
+        limit: i32,
+        sort: &[SortKey],
+        filters: &TableFilters,
+        columns: Option<&[String]>,
+    ) -> Result<Value, Box<dyn Error + Send + Sync>> {
+        let first_key = sort.first();
+        let mut result = self
+            .get_table_data_with_pagination(
+                table_name,
+                page,
+                limit,
+                first_key.map(|key| key.column.as_str()),
+                first_key.map(|key| if key.descending() { "desc" } else { "asc" }),
+            )
+            .await?;
+
+        let mut notes = Vec::new();
+        if !filters.is_empty() {
+            notes.push("Filtering is not supported for this datasource type; showing unfiltered rows");
+        }
+        if sort.len() > 1 || sort.iter().any(|key| key.nulls.is_some()) {
+            notes.push("Only the first sort column is applied for this datasource type");
+        }
+        if !notes.is_empty() {
+            result["note"] = Value::String(notes.join(". "));
+        }
+        if let Some(columns) = columns {
+            project_result_columns(&mut result, Some(columns), usize::MAX)?;
+        }
+        Ok(result)
+    }
+
+    /// Up to `limit` distinct non-NULL values of `column`, narrowed to those
+    /// containing `search`. The default inlines the search; see `table_distinct`.
+    async fn get_distinct_values(
+        &self,
+        table_name: &str,
+        column: &str,
+        search: Option<&str>,
+        limit: i32,
+    ) -> Result<Vec<String>, Box<dyn Error + Send + Sync>> {
+        let query = self.apply_limit(
+            &inline_distinct_values_query(table_name, column, search, self.identifier_quote()),
+            limit,
+        );
+        let result = self.execute_read_only_query(&query, limit).await?;
+        Ok(first_column_values(&result))
+    }
+
+    /// Whether `get_table_data_after_cursor` is implemented
+    fn supports_cursor_pagination(&self) -> bool {
+        false
+    }
+
+    /// The page of rows after `cursor`, the last-seen value of `key`'s column
+    /// (keyset pagination), narrowed by `filters`. See `table_keyset`.
+    async fn get_table_data_after_cursor(
+        &self,
+        _table_name: &str,
+        _limit: i32,
+        _key: &SortKey,
+        _cursor: &Value,
+        _filters: &TableFilters,
+        _columns: Option<&[String]>,
+    ) -> Result<Value, Box<dyn Error + Send + Sync>> {
+        Err("Cursor pagination is not supported for this datasource type".into())
+    }
+
     // Schema inspection methods
     #[allow(dead_code)]
     async fn fetch_schema(&self) -> Result<Value, Box<dyn Error + Send + Sync>>;
@@ -30,6 +189,14 @@ pub trait DataSourceConnector: Send + Sync {
     #[allow(dead_code)]
     async fn analyze_database(&self) -> Result<Value, Box<dyn Error + Send + Sync>>;
     async fn get_tables_schema(&self, tables: Vec<&str>) -> Result<Value, Box<dyn Error + Send + Sync>>;
+
+    /// Name, type, nullability and primary key flag of each column of one table,
+    /// empty if the table doesn't exist. The default reads them out of
+    /// `get_tables_schema`; SQL connectors override it with a single catalog query.
+    async fn list_table_columns(&self, table: &str) -> Result<Vec<ColumnSummary>, Box<dyn Error + Send + Sync>> {
+        let schema = self.get_tables_schema(vec![table]).await?;
+        Ok(schema.get(table).map(column_summaries).unwrap_or_default())
+    }
     #[allow(dead_code)]
     async fn search_tables(&self, pattern: &str) -> Result<Value, Box<dyn Error + Send + Sync>>;
     #[allow(dead_code)]
@@ -38,6 +205,55 @@ pub trait DataSourceConnector: Send + Sync {
     async fn get_database_stats(&self) -> Result<Value, Box<dyn Error + Send + Sync>>;
 }
 
+/// One column as reported by `list_table_columns`
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ColumnSummary {
+    pub name: String,
+    pub data_type: String,
+    pub nullable: bool,
+    pub primary_key: bool,
+}
+
+/// Column summaries from a `get_tables_schema` table entry, whichever of the
+/// connectors' key spellings it uses
+pub fn column_summaries(entry: &Value) -> Vec<ColumnSummary> {
+    let text = |column: &Value, keys: &[&str]| {
+        keys.iter()
+            .find_map(|key| column.get(*key).and_then(|v| v.as_str()))
+            .map(str::to_string)
+    };
+    let primary_keys: Vec<&str> = entry
+        .get("primary_keys")
+        .and_then(|v| v.as_array())
+        .map(|keys| keys.iter().filter_map(|k| k.as_str()).collect())
+        .unwrap_or_default();
+
+    entry
+        .get("columns")
+        .and_then(|v| v.as_array())
+        .map(|columns| {
+            columns
+                .iter()
+                .filter_map(|column| {
+                    let name = text(column, &["name", "column_name"])?;
+                    let nullable = ["nullable", "is_nullable"]
+                        .iter()
+                        .find_map(|key| column.get(*key))
+                        .map(|v| v.as_bool().unwrap_or_else(|| v.as_str().is_some_and(|s| s.eq_ignore_ascii_case("yes"))))
+                        .unwrap_or(true);
+                    Some(ColumnSummary {
+                        primary_key: primary_keys.contains(&name.as_str())
+                            || column.get("primary_key").and_then(|v| v.as_bool()).unwrap_or(false),
+                        data_type: text(column, &["type", "data_type"]).unwrap_or_default(),
+                        nullable,
+                        name,
+                    })
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
 // Helper function to format bytes
 pub fn format_bytes(bytes: u64) -> String {
     const UNITS: &[&str] = &["B", "KB", "MB", "GB", "TB"];
@@ -1,13 +1,16 @@
 use super::base::DataSourceConnector;
 use super::super::connectors::clickhouse::ClickHouseConnector;
 use super::super::connectors::csv::CsvConnector;
+use super::super::connectors::duckdb::DuckDbConnector;
 use super::super::connectors::excel::ExcelConnector;
 use super::super::connectors::json::JsonConnector;
+use super::super::connectors::mongodb::MongoDBConnector;
 use super::super::connectors::mysql::MySQLConnector;
 use super::super::connectors::oracle::OracleConnector;
 use super::super::connectors::postgres::PostgreSQLConnector;
 use super::super::connectors::sqlite::SQLiteConnector;
 use super::super::connectors::sqlserver::SqlServerConnector;
+use super::super::common::diagnostics::ConnectionFailure;
 use serde_json::Value;
 use std::error::Error;
 
@@ -26,9 +29,11 @@ pub enum DataSourceType {
     ClickHouse,
     SqlServer,
     Oracle,
+    MongoDB,
     Csv,
     Excel,
     Json,
+    DuckDB,
 }
 
 impl From<&str> for DataSourceType {
@@ -40,17 +45,31 @@ impl From<&str> for DataSourceType {
             "clickhouse" | "ch" => DataSourceType::ClickHouse,
             "sqlserver" | "mssql" | "sql_server" => DataSourceType::SqlServer,
             "oracle" => DataSourceType::Oracle,
+            "mongodb" | "mongo" => DataSourceType::MongoDB,
             "csv" | "tsv" => DataSourceType::Csv,
             "excel" | "xlsx" | "xls" | "xlsm" => DataSourceType::Excel,
             "json" | "jsonl" => DataSourceType::Json,
+            "duckdb" => DataSourceType::DuckDB,
             _ => DataSourceType::PostgreSQL, // default
         }
     }
 }
 
+/// Create a connector for the datasource type
+///
+/// Failures come back as a `ConnectionFailure` naming the config field that is
+/// most likely wrong.
 pub async fn create_connector(
     source_type: &str,
     config: &Value,
+) -> Result<Box<dyn DataSourceConnector>, Box<dyn Error>> {
+    build_connector(source_type, config)
+        .map_err(|e| Box::new(ConnectionFailure::from_error(source_type, config, &*e)) as Box<dyn Error>)
+}
+
+fn build_connector(
+    source_type: &str,
+    config: &Value,
 ) -> Result<Box<dyn DataSourceConnector>, Box<dyn Error>> {
     match DataSourceType::from(source_type) {
         DataSourceType::PostgreSQL => {
@@ -77,6 +96,10 @@ pub async fn create_connector(
             let connector = OracleConnector::new(config).map_err(convert_error)?;
             Ok(Box::new(connector))
         },
+        DataSourceType::MongoDB => {
+            let connector = MongoDBConnector::new(config).map_err(convert_error)?;
+            Ok(Box::new(connector))
+        },
         DataSourceType::Csv => {
             let connector = CsvConnector::new(config).map_err(convert_error)?;
             Ok(Box::new(connector))
@@ -88,6 +111,10 @@ pub async fn create_connector(
         DataSourceType::Json => {
             let connector = JsonConnector::new(config).map_err(convert_error)?;
             Ok(Box::new(connector))
+        },
+        DataSourceType::DuckDB => {
+            let connector = DuckDbConnector::new(config).map_err(convert_error)?;
+            Ok(Box::new(connector))
         }
     }
 }
@@ -143,6 +170,10 @@ pub async fn create_connector_with_pooling(
             let connector = OracleConnector::new(config).map_err(convert_error)?;
             Ok(Box::new(connector))
         },
+        DataSourceType::MongoDB => {
+            let connector = MongoDBConnector::new(config).map_err(convert_error)?;
+            Ok(Box::new(connector))
+        },
         DataSourceType::Csv => {
             let connector = CsvConnector::new(config).map_err(convert_error)?;
             Ok(Box::new(connector))
@@ -154,6 +185,10 @@ pub async fn create_connector_with_pooling(
         DataSourceType::Json => {
             let connector = JsonConnector::new(config).map_err(convert_error)?;
             Ok(Box::new(connector))
+        },
+        DataSourceType::DuckDB => {
+            let connector = DuckDbConnector::new(config).map_err(convert_error)?;
+            Ok(Box::new(connector))
         }
     }
 }
@@ -1,5 +1,6 @@
 pub mod base;
 pub mod factory;
+pub mod row_stream;
 
 // Removed unused import - uncomment when needed
 // pub use base::*;
@@ -0,0 +1,152 @@
+//! Streaming query results in row batches
+//!
+//! `execute_query` holds every row in memory before returning. A row stream
+//! hands rows over in batches instead. Connectors that can read rows
+//! incrementally produce them in a background task that feeds a small
+//! bounded channel: once `CHANNEL_CAPACITY` batches are waiting, the producer
+//! stops until the consumer catches up, so a slow client slows the query
+//! down rather than filling memory.
+
+use futures::stream::{self, BoxStream, StreamExt};
+use serde::Serialize;
+use serde_json::{Map, Value};
+use std::error::Error;
+use tokio::sync::mpsc;
+
+pub const DEFAULT_STREAM_BATCH_SIZE: usize = 1_000;
+pub const MAX_STREAM_BATCH_SIZE: usize = 10_000;
+
+/// Batches buffered between producer and consumer
+const CHANNEL_CAPACITY: usize = 2;
+
+/// Consecutive rows of a result, with the result's column names
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct RowBatch {
+    pub columns: Vec<String>,
+    pub rows: Vec<Vec<Value>>,
+}
+
+pub type RowBatchResult = Result<RowBatch, Box<dyn Error + Send + Sync>>;
+pub type RowBatchStream = BoxStream<'static, RowBatchResult>;
+
+/// Requested batch size within 1..=MAX_STREAM_BATCH_SIZE
+pub fn batch_size(requested: Option<usize>) -> usize {
+    requested
+        .unwrap_or(DEFAULT_STREAM_BATCH_SIZE)
+        .clamp(1, MAX_STREAM_BATCH_SIZE)
+}
+
+/// A bounded channel for a producer task and the stream that drains it. A
+/// failed `send` means the consumer is gone and the producer should stop.
+pub fn batch_channel() -> (mpsc::Sender<RowBatchResult>, RowBatchStream) {
+    let (tx, rx) = mpsc::channel(CHANNEL_CAPACITY);
+    let stream = stream::unfold(rx, |mut rx| async move { rx.recv().await.map(|batch| (batch, rx)) });
+    (tx, stream.boxed())
+}
+
+/// Split an `execute_query` result (`columns` and array or object `rows`)
+/// into batches; the fallback for connectors that can't stream
+pub fn batches_from_result(result: Value, batch_size: usize) -> RowBatchStream {
+    let columns: Vec<String> = result
+        .get("columns")
+        .and_then(|c| c.as_array())
+        .map(|columns| columns.iter().filter_map(|c| c.as_str().map(str::to_string)).collect())
+        .unwrap_or_default();
+
+    let rows: Vec<Vec<Value>> = match result.get("rows") {
+        Some(Value::Array(rows)) => rows
+            .iter()
+            .map(|row| match row {
+                Value::Array(cells) => cells.clone(),
+                Value::Object(cells) => columns
+                    .iter()
+                    .map(|column| cells.get(column).cloned().unwrap_or(Value::Null))
+                    .collect(),
+                other => vec![other.clone()],
+            })
+            .collect(),
+        _ => Vec::new(),
+    };
+
+    let batches: Vec<RowBatchResult> = rows
+        .chunks(batch_size.max(1))
+        .map(|chunk| {
+            Ok(RowBatch {
+                columns: columns.clone(),
+                rows: chunk.to_vec(),
+            })
+        })
+        .collect();
+    stream::iter(batches).boxed()
+}
+
+/// A batch as newline-delimited JSON: one object per row, keyed by column
+pub fn ndjson_lines(batch: &RowBatch) -> String {
+    let mut out = String::new();
+    for row in &batch.rows {
+        let object: Map<String, Value> = batch
+            .columns
+            .iter()
+            .cloned()
+            .zip(row.iter().cloned())
+            .collect();
+        out.push_str(&Value::Object(object).to_string());
+        out.push('\n');
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[tokio::test]
+    async fn streamed_batches_carry_every_row_of_a_large_result() {
+        let total = 25_003;
+        let (tx, mut batches) = batch_channel();
+        tokio::spawn(async move {
+            let size = batch_size(Some(1_000_000));
+            let mut rows = Vec::with_capacity(size);
+            for i in 0..total {
+                rows.push(vec![json!(i), json!(format!("row {}", i))]);
+                if rows.len() == size {
+                    let batch = RowBatch { columns: vec!["id".into(), "label".into()], rows: std::mem::take(&mut rows) };
+                    if tx.send(Ok(batch)).await.is_err() {
+                        return;
+                    }
+                }
+            }
+            let _ = tx.send(Ok(RowBatch { columns: vec!["id".into(), "label".into()], rows })).await;
+        });
+
+        let mut count = 0;
+        let mut batch_count = 0;
+        while let Some(batch) = batches.next().await {
+            let batch = batch.unwrap();
+            assert!(batch.rows.len() <= MAX_STREAM_BATCH_SIZE);
+            count += batch.rows.len();
+            batch_count += 1;
+        }
+        assert_eq!(count, total);
+        assert_eq!(batch_count, 3);
+    }
+
+    #[tokio::test]
+    async fn materialized_results_are_split_into_batches() {
+        let result = json!({
+            "columns": ["id", "name"],
+            "rows": [[1, "a"], [2, "b"], [3, "c"]]
+        });
+        let batches: Vec<RowBatch> = batches_from_result(result, 2).map(|b| b.unwrap()).collect().await;
+        assert_eq!(batches.len(), 2);
+        assert_eq!(batches[1].rows, vec![vec![json!(3), json!("c")]]);
+        assert_eq!(ndjson_lines(&batches[1]), "{\"id\":3,\"name\":\"c\"}\n");
+
+        let objects = json!({ "columns": ["id"], "rows": [{ "id": 7 }] });
+        let batches: Vec<RowBatch> = batches_from_result(objects, 10).map(|b| b.unwrap()).collect().await;
+        assert_eq!(batches[0].rows, vec![vec![json!(7)]]);
+        assert_eq!(batch_size(None), DEFAULT_STREAM_BATCH_SIZE);
+        assert_eq!(batch_size(Some(0)), 1);
+    }
+}
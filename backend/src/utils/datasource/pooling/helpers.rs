@@ -13,6 +13,7 @@ use std::error::Error;
 /// - Connection pooling (where applicable)
 /// - Proper type conversion
 /// - Consistent result formatting
+#[allow(dead_code)]
 pub async fn execute_query_with_pooling(
     datasource_id: &str,
     source_type: &str,
@@ -39,6 +40,31 @@ pub async fn execute_query_with_pooling(
     Ok(result)
 }
 
+/// Like `execute_query_with_pooling`, but runs the query through the connector's
+/// read-only path so writes are rejected by the database where it supports that
+pub async fn execute_read_only_query_with_pooling(
+    datasource_id: &str,
+    source_type: &str,
+    config: &Value,
+    query: &str,
+    limit: i32,
+) -> Result<Value, Box<dyn Error + Send + Sync>> {
+    tracing::info!("Executing read-only query for {} datasource {} using connector", source_type, datasource_id);
+
+    let mut config_with_id = config.clone();
+    if let Some(config_obj) = config_with_id.as_object_mut() {
+        config_obj.insert("id".to_string(), Value::String(datasource_id.to_string()));
+    }
+
+    let connector = create_connector(source_type, &config_with_id).await
+        .map_err(|e| Box::new(std::io::Error::other(e.to_string())) as Box<dyn Error + Send + Sync>)?;
+
+    let result = connector.execute_read_only_query(query, limit).await
+        .map_err(|e| Box::new(std::io::Error::other(e.to_string())) as Box<dyn Error + Send + Sync>)?;
+
+    Ok(result)
+}
+
 /// Get a connection pool for direct use
 /// This is useful when you need to perform multiple operations on the same connection
 #[allow(dead_code)]
@@ -0,0 +1,114 @@
+//! Keeping pooled datasource connections alive
+//!
+//! Firewalls and NAT gateways silently drop connections that sit idle for a
+//! few minutes, and the next query on such a connection fails with a reset.
+//! Two things guard against that: PostgreSQL sessions ask the server for TCP
+//! keepalives, and a background task runs a validation query on every cached
+//! pool each `DATASOURCE_POOL_VALIDATION_INTERVAL_SECS`, dropping pools that
+//! fail so the next request builds a fresh one.
+
+use std::time::Duration;
+use tokio::time::{interval, MissedTickBehavior};
+use tracing::{debug, info, warn};
+
+use super::sql_pools::get_pool_manager;
+
+/// Probes sent after the first keepalive goes unanswered before the server gives up
+const TCP_KEEPALIVE_PROBE_INTERVAL_SECS: u64 = 10;
+const TCP_KEEPALIVE_PROBE_COUNT: u32 = 3;
+
+#[derive(Debug, Clone)]
+pub struct PoolKeepaliveConfig {
+    /// How often idle pools are validated; zero disables the background check
+    pub validation_interval: Duration,
+    /// Idle time before the first TCP keepalive probe; None leaves the OS default
+    pub tcp_keepalive_idle: Option<Duration>,
+}
+
+impl Default for PoolKeepaliveConfig {
+    fn default() -> Self {
+        Self {
+            validation_interval: Duration::from_secs(60),
+            tcp_keepalive_idle: Some(Duration::from_secs(60)),
+        }
+    }
+}
+
+impl PoolKeepaliveConfig {
+    /// Read DATASOURCE_POOL_VALIDATION_INTERVAL_SECS and DATASOURCE_TCP_KEEPALIVE_SECS (0 disables either)
+    pub fn from_env() -> Self {
+        let defaults = Self::default();
+        let env_secs = |name: &str| std::env::var(name).ok().and_then(|v| v.parse::<u64>().ok());
+
+        Self {
+            validation_interval: env_secs("DATASOURCE_POOL_VALIDATION_INTERVAL_SECS")
+                .map(Duration::from_secs)
+                .unwrap_or(defaults.validation_interval),
+            tcp_keepalive_idle: match env_secs("DATASOURCE_TCP_KEEPALIVE_SECS") {
+                Some(0) => None,
+                Some(secs) => Some(Duration::from_secs(secs)),
+                None => defaults.tcp_keepalive_idle,
+            },
+        }
+    }
+
+    /// Session settings that make the PostgreSQL server send TCP keepalives
+    pub fn postgres_session_options(&self) -> Vec<(&'static str, String)> {
+        match self.tcp_keepalive_idle {
+            Some(idle) => vec![
+                ("tcp_keepalives_idle", idle.as_secs().max(1).to_string()),
+                ("tcp_keepalives_interval", TCP_KEEPALIVE_PROBE_INTERVAL_SECS.to_string()),
+                ("tcp_keepalives_count", TCP_KEEPALIVE_PROBE_COUNT.to_string()),
+            ],
+            None => Vec::new(),
+        }
+    }
+}
+
+/// Validate cached pools every `validation_interval` in the background
+pub fn start_pool_validation(config: &PoolKeepaliveConfig) {
+    if config.validation_interval.is_zero() {
+        info!("Datasource pool validation disabled");
+        return;
+    }
+
+    let period = config.validation_interval;
+    info!("Validating idle datasource pools every {}s", period.as_secs());
+    tokio::spawn(async move {
+        let mut timer = interval(period);
+        timer.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        // The first tick fires immediately; there is nothing to validate yet
+        timer.tick().await;
+
+        loop {
+            timer.tick().await;
+            let (validated, dropped) = get_pool_manager().await.validate_idle_pools(period).await;
+            if dropped > 0 {
+                warn!("Pool validation dropped {} dead pool(s) of {} checked", dropped, validated);
+            } else {
+                debug!("Pool validation checked {} pool(s)", validated);
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn postgres_options_follow_keepalive_idle() {
+        let config = PoolKeepaliveConfig::default();
+        assert_eq!(
+            config.postgres_session_options(),
+            vec![
+                ("tcp_keepalives_idle", "60".to_string()),
+                ("tcp_keepalives_interval", "10".to_string()),
+                ("tcp_keepalives_count", "3".to_string()),
+            ]
+        );
+
+        let disabled = PoolKeepaliveConfig { tcp_keepalive_idle: None, ..config };
+        assert!(disabled.postgres_session_options().is_empty());
+    }
+}
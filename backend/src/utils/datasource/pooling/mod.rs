@@ -1,8 +1,12 @@
 pub mod clickhouse_client_pool;
 pub mod helpers;
+pub mod keepalive;
+pub mod reaper;
 pub mod sql_pools;
 
 // Removed unused import - uncomment when needed
 // pub use clickhouse_client_pool::*;
 pub use helpers::*;
+pub use keepalive::*;
+pub use reaper::*;
 pub use sql_pools::*;
\ No newline at end of file
@@ -0,0 +1,81 @@
+//! Evicting unused datasource pools
+//!
+//! Every datasource gets its own cached pool, and without eviction a server
+//! that has touched many datasources keeps all of their connections open.
+//! A background task closes pools that have not been used for `idle_timeout`
+//! and, when more than `max_pools` remain, the least recently used ones.
+
+use std::time::{Duration, Instant};
+use tokio::time::{interval, MissedTickBehavior};
+use tracing::{debug, info};
+
+use super::sql_pools::get_pool_manager;
+
+#[derive(Debug, Clone)]
+pub struct PoolReaperConfig {
+    /// How often cached pools are checked; zero disables the reaper
+    pub interval: Duration,
+    /// Pools unused for this long are closed
+    pub idle_timeout: Duration,
+    /// Most pools kept cached at once; zero leaves the count unbounded
+    pub max_pools: usize,
+}
+
+impl Default for PoolReaperConfig {
+    fn default() -> Self {
+        Self {
+            interval: Duration::from_secs(60),
+            idle_timeout: Duration::from_secs(15 * 60),
+            max_pools: 100,
+        }
+    }
+}
+
+impl PoolReaperConfig {
+    /// Read DATASOURCE_POOL_REAP_INTERVAL_SECS, DATASOURCE_POOL_IDLE_TIMEOUT_SECS
+    /// and DATASOURCE_POOL_MAX_CACHED (0 disables the reaper or the count limit)
+    pub fn from_env() -> Self {
+        let defaults = Self::default();
+        let env_u64 = |name: &str| std::env::var(name).ok().and_then(|v| v.parse::<u64>().ok());
+
+        Self {
+            interval: env_u64("DATASOURCE_POOL_REAP_INTERVAL_SECS")
+                .map(Duration::from_secs)
+                .unwrap_or(defaults.interval),
+            idle_timeout: env_u64("DATASOURCE_POOL_IDLE_TIMEOUT_SECS")
+                .filter(|secs| *secs > 0)
+                .map(Duration::from_secs)
+                .unwrap_or(defaults.idle_timeout),
+            max_pools: env_u64("DATASOURCE_POOL_MAX_CACHED")
+                .map(|count| count as usize)
+                .unwrap_or(defaults.max_pools),
+        }
+    }
+}
+
+/// Reap idle and surplus pools every `interval` in the background
+pub fn start_pool_reaper(config: &PoolReaperConfig) {
+    if config.interval.is_zero() {
+        info!("Datasource pool reaping disabled");
+        return;
+    }
+
+    let config = config.clone();
+    info!(
+        "Reaping datasource pools idle for {}s every {}s (at most {} cached)",
+        config.idle_timeout.as_secs(),
+        config.interval.as_secs(),
+        config.max_pools
+    );
+    tokio::spawn(async move {
+        let mut timer = interval(config.interval);
+        timer.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        timer.tick().await;
+
+        loop {
+            timer.tick().await;
+            let reaped = get_pool_manager().await.reap_pools(&config, Instant::now()).await;
+            debug!("Pool reaper closed {} pool(s)", reaped);
+        }
+    });
+}
@@ -5,6 +5,7 @@ use serde_json::Value;
 use tracing::{info, warn, debug};
 use sqlx::{Pool, postgres::Postgres, mysql::MySql, sqlite::Sqlite};
 use sqlx::Row;
+use super::reaper::PoolReaperConfig;
 use super::super::connectors::postgres::PostgreSQLConnector;
 use super::super::connectors::mysql::MySQLConnector;
 use super::super::connectors::sqlite::SQLiteConnector;
@@ -17,6 +18,35 @@ pub enum DatabasePool {
     SQLite(Arc<Pool<Sqlite>>),
 }
 
+impl DatabasePool {
+    /// Close the pool: new acquires fail, and open connections close as they
+    /// are returned
+    pub async fn close(&self) {
+        match self {
+            DatabasePool::PostgreSQL(pool) => pool.close().await,
+            DatabasePool::MySQL(pool) => pool.close().await,
+            DatabasePool::SQLite(pool) => pool.close().await,
+        }
+    }
+
+    #[allow(dead_code)]
+    pub fn is_closed(&self) -> bool {
+        match self {
+            DatabasePool::PostgreSQL(pool) => pool.is_closed(),
+            DatabasePool::MySQL(pool) => pool.is_closed(),
+            DatabasePool::SQLite(pool) => pool.is_closed(),
+        }
+    }
+}
+
+/// Whether `cache_key` holds a pool of `datasource_id`: its own
+/// (`<id>_<hash>`) or one of its read replicas' (`<id>:replica-<n>_<hash>`)
+fn is_datasource_key(cache_key: &str, datasource_id: &str) -> bool {
+    cache_key
+        .strip_prefix(datasource_id)
+        .is_some_and(|rest| rest.starts_with('_') || rest.starts_with(":replica-"))
+}
+
 /// Global connection pool manager that caches SQLx connection pools
 /// to avoid recreating them on every request
 pub struct ConnectionPoolManager {
@@ -183,6 +213,41 @@ impl ConnectionPoolManager {
         }
     }
     
+    /// Run the validation query on pools that have been neither used nor validated
+    /// for `idle_for`, dropping the ones that fail so the next request recreates
+    /// them. Returns how many pools were checked and how many were dropped.
+    pub async fn validate_idle_pools(&self, idle_for: std::time::Duration) -> (usize, usize) {
+        let candidates: Vec<(String, DatabasePool)> = {
+            let pools = self.pools.read().await;
+            let stats = self.pool_stats.read().await;
+            pools
+                .iter()
+                .filter(|(key, _)| {
+                    stats.get(*key).is_none_or(|stat| {
+                        stat.last_used.elapsed() >= idle_for
+                            && stat.last_validated.is_none_or(|at| at.elapsed() >= idle_for)
+                    })
+                })
+                .map(|(key, pool)| (key.clone(), pool.clone()))
+                .collect()
+        };
+
+        let mut dropped = 0;
+        for (cache_key, pool) in &candidates {
+            if self.validate_pool(pool).await {
+                self.record_validation_success(cache_key).await;
+            } else {
+                // In-flight users keep their Arc; the pool closes once they finish
+                self.pools.write().await.remove(cache_key);
+                self.pool_stats.write().await.remove(cache_key);
+                dropped += 1;
+                warn!("Idle pool {} failed validation and was dropped", cache_key);
+            }
+        }
+
+        (candidates.len(), dropped)
+    }
+
     /// Check if we should retry pool validation or recreate
     /// Implements exponential backoff: validate less frequently for stable pools
     async fn should_retry_pool(&self, cache_key: &str) -> bool {
@@ -268,7 +333,6 @@ impl ConnectionPoolManager {
     }
     
     /// Remove a pool from cache (useful when datasource config changes)
-    #[allow(dead_code)]
     pub async fn remove_pool(&self, datasource_id: &str, config: &Value) {
         let cache_key = self.generate_cache_key(datasource_id, config);
         let mut pools = self.pools.write().await;
@@ -280,6 +344,38 @@ impl ConnectionPoolManager {
         }
     }
     
+    /// Drop every cached pool of a datasource, whichever config it was built
+    /// from, and close them in the background so connections made with old
+    /// credentials don't linger. The next query creates a fresh pool.
+    /// Returns how many pools were dropped.
+    pub async fn remove_datasource_pools(&self, datasource_id: &str) -> usize {
+        let removed: Vec<DatabasePool> = {
+            let mut pools = self.pools.write().await;
+            let mut stats = self.pool_stats.write().await;
+            let keys: Vec<String> = pools
+                .keys()
+                .filter(|key| is_datasource_key(key, datasource_id))
+                .cloned()
+                .collect();
+            keys.iter()
+                .filter_map(|key| {
+                    stats.remove(key);
+                    pools.remove(key)
+                })
+                .collect()
+        };
+
+        // Closing waits for in-flight queries to hand their connections back
+        for pool in &removed {
+            let pool = pool.clone();
+            tokio::spawn(async move { pool.close().await });
+        }
+        if !removed.is_empty() {
+            info!("Closed {} cached pool(s) for datasource {}", removed.len(), datasource_id);
+        }
+        removed.len()
+    }
+
     /// Clear all cached pools (useful for testing or config changes)
     #[allow(dead_code)]
     pub async fn clear_all(&self) {
@@ -292,31 +388,48 @@ impl ConnectionPoolManager {
         info!("Cleared all {} cached connection pools", count);
     }
     
-    /// Cleanup stale pools that haven't been used for a while
-    #[allow(dead_code)]
-    pub async fn cleanup_stale_pools(&self, max_idle_time: std::time::Duration) -> usize {
-        let mut pools = self.pools.write().await;
-        let mut stats = self.pool_stats.write().await;
-        let now = std::time::Instant::now();
-        let mut removed_count = 0;
-        
-        let stale_keys: Vec<String> = stats.iter()
-            .filter(|(_, stat)| now.duration_since(stat.last_used) > max_idle_time)
-            .map(|(key, _)| key.clone())
-            .collect();
-        
-        for key in stale_keys {
-            pools.remove(&key);
-            stats.remove(&key);
-            removed_count += 1;
-            debug!("Removed stale pool for key: {}", key);
+    /// Close pools unused for `config.idle_timeout` as of `now`, then the least
+    /// recently used ones while more than `config.max_pools` remain cached.
+    /// Returns how many pools were closed.
+    pub async fn reap_pools(&self, config: &PoolReaperConfig, now: std::time::Instant) -> usize {
+        let reaped: Vec<(String, DatabasePool)> = {
+            let mut pools = self.pools.write().await;
+            let mut stats = self.pool_stats.write().await;
+
+            // Pools without stats sort first and count as idle
+            let mut by_last_use: Vec<(String, Option<std::time::Instant>)> = pools
+                .keys()
+                .map(|key| (key.clone(), stats.get(key).map(|stat| stat.last_used)))
+                .collect();
+            by_last_use.sort_by_key(|(_, last_used)| *last_used);
+
+            let surplus = match config.max_pools {
+                0 => 0,
+                max => by_last_use.len().saturating_sub(max),
+            };
+            by_last_use
+                .into_iter()
+                .enumerate()
+                .filter(|(rank, (_, last_used))| {
+                    *rank < surplus
+                        || last_used.is_none_or(|at| now.saturating_duration_since(at) >= config.idle_timeout)
+                })
+                .filter_map(|(_, (key, _))| {
+                    stats.remove(&key);
+                    pools.remove(&key).map(|pool| (key, pool))
+                })
+                .collect()
+        };
+
+        // Closing waits for in-flight queries to hand their connections back
+        for (key, pool) in &reaped {
+            pool.close().await;
+            debug!("Closed unused pool {}", key);
         }
-        
-        if removed_count > 0 {
-            info!("Cleaned up {} stale connection pools", removed_count);
+        if !reaped.is_empty() {
+            info!("Reaped {} unused connection pool(s)", reaped.len());
         }
-        
-        removed_count
+        reaped.len()
     }
       /// Warm up connection pools for all active datasources
     /// This should be called on application startup to avoid slow first requests
@@ -531,4 +644,86 @@ pub async fn get_pool_manager() -> &'static ConnectionPoolManager {
 pub async fn warm_up_project_pools(app_db_pool: &sqlx::PgPool, project_id: &str) -> Result<usize, String> {
     let pool_manager = get_pool_manager().await;
     pool_manager.warm_up_project_pools(app_db_pool, project_id).await
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn same_pool(a: &DatabasePool, b: &DatabasePool) -> bool {
+        match (a, b) {
+            (DatabasePool::SQLite(a), DatabasePool::SQLite(b)) => Arc::ptr_eq(a, b),
+            _ => false,
+        }
+    }
+
+    #[test]
+    fn replica_pools_belong_to_their_datasource() {
+        assert!(is_datasource_key("ds-1_9f2c", "ds-1"));
+        assert!(is_datasource_key("ds-1:replica-0_9f2c", "ds-1"));
+        assert!(!is_datasource_key("ds-10_9f2c", "ds-1"));
+        assert!(!is_datasource_key("ds-2_9f2c", "ds-1"));
+    }
+
+    #[tokio::test]
+    async fn a_new_pool_is_created_after_the_config_changes() {
+        let manager = ConnectionPoolManager::new();
+        let old_config = json!({ "url": "sqlite::memory:", "username": "old" });
+        let new_config = json!({ "url": "sqlite::memory:", "username": "rotated" });
+
+        let old_pool = manager.get_pool("ds-1", "sqlite", &old_config).await.unwrap();
+        let other = manager.get_pool("ds-2", "sqlite", &old_config).await.unwrap();
+        let cached = manager.get_pool("ds-1", "sqlite", &old_config).await.unwrap();
+        assert!(same_pool(&old_pool, &cached));
+
+        // What update_datasource does once the config is saved
+        assert_eq!(manager.remove_datasource_pools("ds-1").await, 1);
+
+        let new_pool = manager.get_pool("ds-1", "sqlite", &new_config).await.unwrap();
+        assert!(!same_pool(&old_pool, &new_pool));
+        old_pool.close().await;
+        assert!(old_pool.is_closed());
+        assert!(!new_pool.is_closed());
+
+        // Other datasources keep their pools
+        let other_again = manager.get_pool("ds-2", "sqlite", &old_config).await.unwrap();
+        assert!(same_pool(&other, &other_again));
+    }
+
+    #[tokio::test]
+    async fn idle_and_least_recently_used_pools_are_reaped() {
+        let manager = ConnectionPoolManager::new();
+        let config = json!({ "url": "sqlite::memory:" });
+        let policy = PoolReaperConfig {
+            idle_timeout: std::time::Duration::from_secs(600),
+            max_pools: 2,
+            ..Default::default()
+        };
+
+        let start = std::time::Instant::now();
+        let mut pools = Vec::new();
+        for (offset, id) in ["ds-idle", "ds-old", "ds-recent", "ds-newest"].iter().enumerate() {
+            let pool = manager.get_pool(id, "sqlite", &config).await.unwrap();
+            let key = manager.generate_cache_key(id, &config);
+            // Used a minute apart, ds-idle furthest in the past
+            manager.pool_stats.write().await.get_mut(&key).unwrap().last_used =
+                start + std::time::Duration::from_secs(60 * offset as u64);
+            pools.push(pool);
+        }
+
+        // Nothing has been idle for ten minutes yet, but only two pools may stay
+        assert_eq!(manager.reap_pools(&policy, start + std::time::Duration::from_secs(300)).await, 2);
+        assert!(pools[0].is_closed() && pools[1].is_closed());
+        assert!(!pools[2].is_closed() && !pools[3].is_closed());
+
+        // Twelve minutes in, ds-recent (used at 2m) has idled past the timeout
+        assert_eq!(manager.reap_pools(&policy, start + std::time::Duration::from_secs(720)).await, 1);
+        assert!(pools[2].is_closed());
+        assert!(!pools[3].is_closed());
+
+        // A reaped pool is rebuilt on the next request
+        let rebuilt = manager.get_pool("ds-idle", "sqlite", &config).await.unwrap();
+        assert!(!same_pool(&pools[0], &rebuilt));
+        assert!(!rebuilt.is_closed());
+    }
+}
@@ -3,11 +3,84 @@ use sea_orm_migration::prelude::*;
 use std::time::Duration;
 use tracing::{error, info, warn};
 
-pub async fn connect(database_url: &str) -> Result<DatabaseConnection, sea_orm::DbErr> {
+/// How often and how patiently to retry the initial database connection
+#[derive(Debug, Clone)]
+pub struct RetryPolicy {
+    pub max_attempts: u32,
+    pub initial_delay: Duration,
+    /// Double the delay after each failed attempt, up to `max_delay`
+    pub exponential: bool,
+    pub max_delay: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            initial_delay: Duration::from_secs(2),
+            exponential: false,
+            max_delay: Duration::from_secs(60),
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Read the policy from DB_CONNECT_MAX_ATTEMPTS, DB_CONNECT_RETRY_DELAY_MS,
+    /// DB_CONNECT_BACKOFF ("fixed" or "exponential") and DB_CONNECT_MAX_DELAY_MS
+    pub fn from_env() -> Self {
+        let defaults = Self::default();
+        let env_u64 = |name: &str| std::env::var(name).ok().and_then(|v| v.parse::<u64>().ok());
+
+        Self {
+            max_attempts: env_u64("DB_CONNECT_MAX_ATTEMPTS")
+                .map(|v| v.max(1) as u32)
+                .unwrap_or(defaults.max_attempts),
+            initial_delay: env_u64("DB_CONNECT_RETRY_DELAY_MS")
+                .map(Duration::from_millis)
+                .unwrap_or(defaults.initial_delay),
+            exponential: std::env::var("DB_CONNECT_BACKOFF")
+                .map(|v| v.eq_ignore_ascii_case("exponential"))
+                .unwrap_or(defaults.exponential),
+            max_delay: env_u64("DB_CONNECT_MAX_DELAY_MS")
+                .map(Duration::from_millis)
+                .unwrap_or(defaults.max_delay),
+        }
+    }
+
+    /// Delay to wait after the given (1-based) failed attempt
+    pub fn delay_after(&self, attempt: u32) -> Duration {
+        if !self.exponential {
+            return self.initial_delay;
+        }
+        let factor = 2u32.saturating_pow(attempt.saturating_sub(1));
+        self.initial_delay.saturating_mul(factor).min(self.max_delay)
+    }
+
+    /// `delay_after` with random jitter, so processes restarting together
+    /// (backend and MCP servers) don't retry in lockstep
+    pub fn jittered_delay_after(&self, attempt: u32) -> Duration {
+        with_jitter(self.delay_after(attempt), rand::random::<f64>())
+    }
+}
+
+/// Somewhere between half of `delay` and all of it, picked by `unit` in [0, 1)
+fn with_jitter(delay: Duration, unit: f64) -> Duration {
+    let half = delay / 2;
+    half + (delay - half).mul_f64(unit.clamp(0.0, 1.0))
+}
+
+pub async fn connect(database_url: &str, retry: &RetryPolicy) -> Result<DatabaseConnection, sea_orm::DbErr> {
     info!(
         "🔌 Initiating database connection to: {}",
         mask_database_url(database_url)
     );
+    info!(
+        "🔁 Connection retry policy: {} attempts, {} backoff starting at {}ms (max {}ms)",
+        retry.max_attempts,
+        if retry.exponential { "exponential" } else { "fixed" },
+        retry.initial_delay.as_millis(),
+        retry.max_delay.as_millis()
+    );
 
     let mut opt = ConnectOptions::new(database_url.to_owned());
 
@@ -28,13 +101,12 @@ pub async fn connect(database_url: &str) -> Result<DatabaseConnection, sea_orm::
 
     // Attempt connection with retry logic
     let mut attempts = 0;
-    const MAX_ATTEMPTS: u8 = 3;
 
     let db = loop {
         attempts += 1;
         info!(
             "🔄 Database connection attempt {}/{}",
-            attempts, MAX_ATTEMPTS
+            attempts, retry.max_attempts
         );
 
         match Database::connect(opt.clone()).await {
@@ -64,13 +136,14 @@ pub async fn connect(database_url: &str) -> Result<DatabaseConnection, sea_orm::
                     error!("💡 Close unused connections or increase max_connections");
                 }
 
-                if attempts >= MAX_ATTEMPTS {
+                if attempts >= retry.max_attempts {
                     error!("💥 All database connection attempts exhausted");
                     return Err(e);
                 }
 
-                warn!("⏳ Retrying database connection in 2 seconds...");
-                tokio::time::sleep(Duration::from_secs(2)).await;
+                let delay = retry.jittered_delay_after(attempts);
+                warn!("⏳ Retrying database connection in {}ms...", delay.as_millis());
+                tokio::time::sleep(delay).await;
             }
         }
     };
@@ -106,3 +179,34 @@ fn mask_database_url(url: &str) -> String {
     }
     url.to_string()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn jittered_backoff_stays_within_half_and_full_delay() {
+        let retry = RetryPolicy {
+            max_attempts: 5,
+            initial_delay: Duration::from_millis(500),
+            exponential: true,
+            max_delay: Duration::from_secs(3),
+        };
+        assert_eq!(retry.delay_after(1), Duration::from_millis(500));
+        assert_eq!(retry.delay_after(3), Duration::from_secs(2));
+        assert_eq!(retry.delay_after(10), Duration::from_secs(3));
+
+        assert_eq!(with_jitter(Duration::from_secs(2), 0.0), Duration::from_secs(1));
+        assert_eq!(with_jitter(Duration::from_secs(2), 0.5), Duration::from_millis(1500));
+        assert_eq!(with_jitter(Duration::from_secs(2), 1.0), Duration::from_secs(2));
+        assert_eq!(with_jitter(Duration::ZERO, 0.7), Duration::ZERO);
+
+        for attempt in 1..=6 {
+            let delay = retry.delay_after(attempt);
+            for _ in 0..50 {
+                let jittered = retry.jittered_delay_after(attempt);
+                assert!(jittered >= delay / 2 && jittered <= delay, "{:?} outside {:?}", jittered, delay);
+            }
+        }
+    }
+}
@@ -25,6 +25,32 @@ pub fn extract_domain_from_request(req: &Request) -> Option<String> {
         })
 }
 
+/// Lowercase host with a trailing dot and the default HTTP(S) port removed,
+/// so `App.Example.com:443` and `app.example.com` compare equal
+fn normalize_host(host: &str) -> String {
+    let host = host.trim().to_ascii_lowercase();
+    let host = host
+        .strip_suffix(":80")
+        .or_else(|| host.strip_suffix(":443"))
+        .unwrap_or(&host);
+    host.trim_end_matches('.').to_string()
+}
+
+/// Whether `host` matches a client domain entry: either exactly or, for a
+/// `*.example.com` entry, as any subdomain of `example.com`. The wildcard
+/// doesn't cover `example.com` itself; that needs its own entry.
+pub fn domain_matches(pattern: &str, host: &str) -> bool {
+    let pattern = normalize_host(pattern);
+    let host = normalize_host(host);
+    match pattern.strip_prefix("*.") {
+        Some(parent) => host
+            .strip_suffix(parent)
+            .and_then(|subdomain| subdomain.strip_suffix('.'))
+            .is_some_and(|subdomain| !subdomain.is_empty()),
+        None => pattern == host,
+    }
+}
+
 /// Check if a client is allowed to serve a specific domain
 pub async fn is_client_allowed_for_domain(
     pool: &PgPool,
@@ -49,7 +75,7 @@ pub async fn is_client_allowed_for_domain(
         Some(domains) if domains.is_empty() => Ok(true),
         Some(domains) => {
             // Check if the request domain matches any of the client's domains
-            Ok(domains.iter().any(|d| d == request_domain))
+            Ok(domains.iter().any(|d| domain_matches(d, request_domain)))
         }
     }
 }
@@ -76,3 +102,30 @@ pub async fn validate_client_domain(
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn exact_domains_match_ignoring_case_and_default_ports() {
+        assert!(domain_matches("app.example.com", "app.example.com"));
+        assert!(domain_matches("App.Example.com", "app.EXAMPLE.com:443"));
+        assert!(domain_matches("app.example.com:80", "app.example.com"));
+        assert!(domain_matches("localhost:7690", "localhost:7690"));
+        // Other ports are part of the domain
+        assert!(!domain_matches("app.example.com", "app.example.com:8443"));
+        assert!(!domain_matches("app.example.com", "other.example.com"));
+    }
+
+    #[test]
+    fn wildcards_match_subdomains_only() {
+        assert!(domain_matches("*.example.com", "app.example.com"));
+        assert!(domain_matches("*.example.com", "eu.app.example.com"));
+        assert!(domain_matches("*.Example.com", "APP.example.com:443"));
+        assert!(!domain_matches("*.example.com", "example.com"));
+        assert!(!domain_matches("*.example.com", "badexample.com"));
+        assert!(!domain_matches("*.example.com", "app.example.com.evil.net"));
+        assert!(!domain_matches("*.example.com", "app.example.com:8080"));
+    }
+}
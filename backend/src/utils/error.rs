@@ -1,6 +1,8 @@
 use salvo::prelude::*;
 use thiserror::Error;
 
+use crate::utils::request_id::current_request_id;
+
 #[derive(Debug, Error)]
 pub enum AppError {
     #[error("Database error: {0}")]
@@ -21,9 +23,15 @@ pub enum AppError {
     #[error("Forbidden: {0}")]
     Forbidden(String),
 
+    #[error("Conflict: {0}")]
+    Conflict(String),
+
     #[error("Service unavailable: {0}")]
     ServiceUnavailable(String),
 
+    #[error("Gateway timeout: {0}")]
+    GatewayTimeout(String),
+
     #[error("Parse error: {0}")]
     ParseError(#[from] salvo::http::ParseError),
 
@@ -47,20 +55,81 @@ impl AppError {
             AppError::BadRequest(_) => StatusCode::BAD_REQUEST,
             AppError::Unauthorized(_) => StatusCode::UNAUTHORIZED,
             AppError::Forbidden(_) => StatusCode::FORBIDDEN,
+            AppError::Conflict(_) => StatusCode::CONFLICT,
             AppError::ServiceUnavailable(_) => StatusCode::SERVICE_UNAVAILABLE,
+            AppError::GatewayTimeout(_) => StatusCode::GATEWAY_TIMEOUT,
             AppError::InternalServerError(_) => StatusCode::INTERNAL_SERVER_ERROR,
             AppError::StatusError(status_error) => status_error.code,
             _ => StatusCode::INTERNAL_SERVER_ERROR,
         }
     }
+
+    /// Machine-readable category sent with every error response, so clients
+    /// can branch on it instead of parsing the message
+    pub fn code(&self) -> &'static str {
+        match self {
+            AppError::NotFound(_) => "NOT_FOUND",
+            AppError::BadRequest(_) | AppError::ParseError(_) => "VALIDATION",
+            AppError::Unauthorized(_) => "UNAUTHORIZED",
+            AppError::Forbidden(_) => "FORBIDDEN",
+            AppError::Conflict(_) => "CONFLICT",
+            AppError::ServiceUnavailable(_) => "SERVICE_UNAVAILABLE",
+            AppError::GatewayTimeout(_) => "TIMEOUT",
+            AppError::Database(_) | AppError::SqlxError(_) => "DB_ERROR",
+            AppError::StatusError(status_error) => match status_error.code.as_u16() {
+                400 | 422 => "VALIDATION",
+                401 => "UNAUTHORIZED",
+                403 => "FORBIDDEN",
+                404 => "NOT_FOUND",
+                409 => "CONFLICT",
+                429 => "RATE_LIMITED",
+                _ => "INTERNAL",
+            },
+            AppError::InternalServerError(_) | AppError::IoError(_) | AppError::JsonError(_) => "INTERNAL",
+        }
+    }
+
+    /// JSON error body; `error` repeats the message for older clients
+    pub fn body(&self) -> serde_json::Value {
+        let message = self.to_string();
+        serde_json::json!({
+            "code": self.code(),
+            "message": message,
+            "error": message,
+        })
+    }
 }
 
 #[async_trait]
 impl Writer for AppError {
-    async fn write(mut self, _req: &mut Request, _depot: &mut Depot, res: &mut Response) {
+    async fn write(mut self, _req: &mut Request, depot: &mut Depot, res: &mut Response) {
+        let mut body = self.body();
+        if let Some(request_id) = current_request_id(depot) {
+            body["request_id"] = serde_json::Value::String(request_id.to_string());
+        }
         res.status_code(self.status_code());
-        res.render(Json(serde_json::json!({
-            "error": self.to_string()
-        })));
+        res.render(Json(body));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn errors_render_a_code_next_to_the_message() {
+        let error = AppError::NotFound("Datasource abc".to_string());
+        assert_eq!(error.status_code(), StatusCode::NOT_FOUND);
+        assert_eq!(
+            error.body(),
+            serde_json::json!({
+                "code": "NOT_FOUND",
+                "message": "Not found: Datasource abc",
+                "error": "Not found: Datasource abc",
+            })
+        );
+
+        assert_eq!(AppError::BadRequest("bad".to_string()).code(), "VALIDATION");
+        assert_eq!(AppError::SqlxError(sqlx::Error::RowNotFound).code(), "DB_ERROR");
     }
 }
@@ -66,6 +66,16 @@ pub fn get_mcp_tools() -> HashMap<String, McpTool> {
         },
     );
 
+    tools.insert(
+        "mcp__operation__datasource_restore".to_string(),
+        McpTool {
+            name: "datasource_restore",
+            display_name: "Restore Data Source",
+            description: "Restores a removed data source",
+            result_indicators: vec!["Datasource restored", "Cannot restore"],
+        },
+    );
+
     tools.insert(
         "mcp__operation__datasource_update".to_string(),
         McpTool {
@@ -119,6 +129,16 @@ pub fn get_mcp_tools() -> HashMap<String, McpTool> {
         },
     );
 
+    tools.insert(
+        "mcp__operation__data_query_write".to_string(),
+        McpTool {
+            name: "data_query_write",
+            display_name: "Modify Data",
+            description: "Executes a data-modifying statement",
+            result_indicators: vec!["rows_affected", "Writes are disabled"],
+        },
+    );
+
     tools.insert(
         "mcp__operation__schema_stats".to_string(),
         McpTool {
@@ -148,6 +168,16 @@ pub fn get_mcp_tools() -> HashMap<String, McpTool> {
         },
     );
 
+    tools.insert(
+        "mcp__operation__schema_columns".to_string(),
+        McpTool {
+            name: "schema_columns",
+            display_name: "List Columns",
+            description: "Lists the columns of one table",
+            result_indicators: vec!["primary_key", "nullable"],
+        },
+    );
+
     tools.insert(
         "mcp__operation__schema_get".to_string(),
         McpTool {
@@ -173,6 +203,16 @@ pub fn get_mcp_tools() -> HashMap<String, McpTool> {
         },
     );
 
+    tools.insert(
+        "mcp__interaction__ask_user_choice".to_string(),
+        McpTool {
+            name: "ask_user_choice",
+            display_name: "Ask User",
+            description: "Asks the user to pick one of several options and waits for the answer",
+            result_indicators: vec!["\"selected\"", "timed_out"],
+        },
+    );
+
     tools.insert(
         "mcp__interaction__show_table".to_string(),
         McpTool {
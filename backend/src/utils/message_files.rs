@@ -86,6 +86,9 @@ pub async fn get_messages_files(
             file_content: row.get("file_content"),
             metadata: row.get("metadata"),
             uploaded_by: row.get("uploaded_by"),
+            extraction_status: row.get("extraction_status"),
+            extraction_progress: row.get("extraction_progress"),
+            archive_id: row.get("archive_id"),
             created_at: row.get("created_at"),
             updated_at: row.get("updated_at"),
         };
@@ -13,7 +13,11 @@ pub mod log_organizer;
 pub mod mcp_tools;
 pub mod message_files;
 pub mod middleware;
+pub mod query_limit;
+pub mod rate_limit;
+pub mod request_id;
 pub mod state;
+pub mod storage;
 
 pub use config::*;
 pub use error::*;
@@ -0,0 +1,108 @@
+//! Per-client cap on concurrent Claude queries
+//!
+//! Each prompt runs a Claude query in a background task. A client gets a
+//! semaphore with `CLAUDE_MAX_CONCURRENT_QUERIES` permits (default 3); a
+//! prompt that arrives while all of them are held is rejected rather than
+//! queued, so a burst of messages can't pile up unbounded tasks.
+
+use salvo::prelude::*;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+
+use crate::utils::middleware::get_current_client_id;
+use crate::utils::{get_app_state, AppError};
+
+pub const DEFAULT_MAX_CONCURRENT_QUERIES: usize = 3;
+
+/// Read CLAUDE_MAX_CONCURRENT_QUERIES (at least 1)
+pub fn max_concurrent_queries_from_env() -> usize {
+    std::env::var("CLAUDE_MAX_CONCURRENT_QUERIES")
+        .ok()
+        .and_then(|v| v.parse::<usize>().ok())
+        .filter(|v| *v > 0)
+        .unwrap_or(DEFAULT_MAX_CONCURRENT_QUERIES)
+}
+
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+pub struct QueryUsage {
+    pub limit: usize,
+    pub active: usize,
+}
+
+/// Semaphores keyed by client id
+pub struct QueryLimiter {
+    max_per_client: usize,
+    clients: Mutex<HashMap<String, Arc<Semaphore>>>,
+}
+
+impl QueryLimiter {
+    pub fn new(max_per_client: usize) -> Self {
+        Self {
+            max_per_client: max_per_client.max(1),
+            clients: Mutex::new(HashMap::new()),
+        }
+    }
+
+    fn semaphore(&self, client_id: &str) -> Arc<Semaphore> {
+        let mut clients = self.clients.lock().unwrap_or_else(|e| e.into_inner());
+        clients
+            .entry(client_id.to_string())
+            .or_insert_with(|| Arc::new(Semaphore::new(self.max_per_client)))
+            .clone()
+    }
+
+    /// Claim a query slot for this client. The slot is released when the
+    /// permit is dropped; `None` means every slot is taken.
+    pub fn try_acquire(&self, client_id: &str) -> Option<OwnedSemaphorePermit> {
+        self.semaphore(client_id).try_acquire_owned().ok()
+    }
+
+    pub fn usage(&self, client_id: &str) -> QueryUsage {
+        let available = self.semaphore(client_id).available_permits();
+        QueryUsage {
+            limit: self.max_per_client,
+            active: self.max_per_client.saturating_sub(available),
+        }
+    }
+}
+
+/// Message shown when a prompt is rejected for exceeding the limit
+pub fn query_limit_message(limit: usize) -> String {
+    format!(
+        "Too many concurrent queries: at most {} can run at once. Wait for one to finish, then send your message again.",
+        limit
+    )
+}
+
+/// GET /chat/query-limit: the current client's limit and running queries
+#[handler]
+pub async fn get_query_limit(depot: &mut Depot, res: &mut Response) -> Result<(), AppError> {
+    let state = get_app_state(depot)?;
+    let client_id = get_current_client_id(depot)?;
+    res.render(Json(state.claude_query_limiter.usage(&client_id.to_string())));
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn messages_beyond_the_limit_are_rejected_until_a_query_finishes() {
+        let limiter = QueryLimiter::new(3);
+
+        let permits: Vec<_> = (0..5).map(|_| limiter.try_acquire("client-a")).collect();
+        assert_eq!(permits.iter().filter(|p| p.is_some()).count(), 3);
+        assert!(permits[3].is_none() && permits[4].is_none());
+        assert_eq!(limiter.usage("client-a"), QueryUsage { limit: 3, active: 3 });
+
+        // Other clients have their own slots
+        assert!(limiter.try_acquire("client-b").is_some());
+
+        drop(permits);
+        assert_eq!(limiter.usage("client-a").active, 0);
+        assert!(limiter.try_acquire("client-a").is_some());
+    }
+}
@@ -0,0 +1,212 @@
+//! Per-user rate limiting for prompts
+//!
+//! Every prompt starts a Claude query, so each user (within a client) gets a
+//! token bucket: `burst` prompts can be sent back to back, after which tokens
+//! come back at `per_minute`. Buckets live in memory on `AppState`.
+
+use salvo::http::HeaderValue;
+use salvo::prelude::*;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use crate::utils::middleware::{get_current_client_id, get_current_user_id};
+use crate::utils::get_app_state;
+
+/// Buckets kept before full (idle) ones are pruned
+const MAX_IDLE_BUCKETS: usize = 1024;
+
+#[derive(Debug, Clone, Copy)]
+pub struct RateLimitConfig {
+    /// Prompts regained per minute; zero disables the limit
+    pub per_minute: u32,
+    /// Prompts that can be sent back to back
+    pub burst: u32,
+}
+
+impl Default for RateLimitConfig {
+    fn default() -> Self {
+        Self {
+            per_minute: 20,
+            burst: 5,
+        }
+    }
+}
+
+impl RateLimitConfig {
+    /// Read PROMPT_RATE_LIMIT_PER_MINUTE (0 disables) and PROMPT_RATE_LIMIT_BURST
+    pub fn from_env() -> Self {
+        let defaults = Self::default();
+        let env_u32 = |name: &str| std::env::var(name).ok().and_then(|v| v.parse::<u32>().ok());
+
+        Self {
+            per_minute: env_u32("PROMPT_RATE_LIMIT_PER_MINUTE").unwrap_or(defaults.per_minute),
+            burst: env_u32("PROMPT_RATE_LIMIT_BURST")
+                .filter(|v| *v > 0)
+                .unwrap_or(defaults.burst),
+        }
+    }
+
+    fn is_enabled(&self) -> bool {
+        self.per_minute > 0
+    }
+
+    fn tokens_per_sec(&self) -> f64 {
+        self.per_minute as f64 / 60.0
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct TokenBucket {
+    tokens: f64,
+    updated_at: Instant,
+}
+
+impl TokenBucket {
+    fn full(config: &RateLimitConfig, now: Instant) -> Self {
+        Self {
+            tokens: config.burst as f64,
+            updated_at: now,
+        }
+    }
+
+    fn refill(&mut self, config: &RateLimitConfig, now: Instant) {
+        let elapsed = now.saturating_duration_since(self.updated_at).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * config.tokens_per_sec()).min(config.burst as f64);
+        self.updated_at = now;
+    }
+
+    /// Take one token, or return how long until one is available
+    fn try_take(&mut self, config: &RateLimitConfig, now: Instant) -> Result<(), Duration> {
+        self.refill(config, now);
+        if self.tokens >= 1.0 {
+            self.tokens -= 1.0;
+            Ok(())
+        } else {
+            Err(Duration::from_secs_f64((1.0 - self.tokens) / config.tokens_per_sec()))
+        }
+    }
+}
+
+/// Token buckets keyed by client and user
+pub struct RateLimiter {
+    config: RateLimitConfig,
+    buckets: Mutex<HashMap<String, TokenBucket>>,
+}
+
+impl RateLimiter {
+    pub fn new(config: RateLimitConfig) -> Self {
+        Self {
+            config,
+            buckets: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Spend one prompt for this user, or return how long they have to wait
+    pub fn check(&self, client_id: Option<&str>, user_id: &str) -> Result<(), Duration> {
+        self.check_at(&bucket_key(client_id, user_id), Instant::now())
+    }
+
+    fn check_at(&self, key: &str, now: Instant) -> Result<(), Duration> {
+        if !self.config.is_enabled() {
+            return Ok(());
+        }
+        let Ok(mut buckets) = self.buckets.lock() else {
+            return Ok(());
+        };
+
+        if buckets.len() > MAX_IDLE_BUCKETS {
+            let config = self.config;
+            buckets.retain(|_, bucket| {
+                bucket.refill(&config, now);
+                bucket.tokens < config.burst as f64
+            });
+        }
+
+        buckets
+            .entry(key.to_string())
+            .or_insert_with(|| TokenBucket::full(&self.config, now))
+            .try_take(&self.config, now)
+    }
+}
+
+fn bucket_key(client_id: Option<&str>, user_id: &str) -> String {
+    format!("{}:{}", client_id.unwrap_or_default(), user_id)
+}
+
+/// Message shown when a prompt is rejected
+pub fn rate_limit_message(retry_after: Duration) -> String {
+    format!(
+        "Too many messages. Please wait {}s before sending another.",
+        retry_secs(retry_after)
+    )
+}
+
+fn retry_secs(retry_after: Duration) -> u64 {
+    retry_after.as_secs_f64().ceil().max(1.0) as u64
+}
+
+/// Middleware for routes that start a prompt: answers 429 with Retry-After
+/// once the current user has used up their bucket
+#[handler]
+pub async fn prompt_rate_limit(
+    depot: &mut Depot,
+    res: &mut Response,
+    ctrl: &mut FlowCtrl,
+) {
+    // auth_required runs first and rejects requests without a user
+    let (Ok(state), Ok(user_id)) = (get_app_state(depot), get_current_user_id(depot)) else {
+        return;
+    };
+    let client_id = get_current_client_id(depot).ok().map(|id| id.to_string());
+
+    if let Err(retry_after) = state.prompt_rate_limiter.check(client_id.as_deref(), &user_id.to_string()) {
+        res.status_code(StatusCode::TOO_MANY_REQUESTS);
+        res.headers_mut().insert(
+            "Retry-After",
+            HeaderValue::from(retry_secs(retry_after)),
+        );
+        res.render(Json(serde_json::json!({
+            "error": rate_limit_message(retry_after),
+            "code": 429
+        })));
+        ctrl.skip_rest();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bucket_allows_burst_then_refills() {
+        let limiter = RateLimiter::new(RateLimitConfig { per_minute: 60, burst: 3 });
+        let start = Instant::now();
+
+        for _ in 0..3 {
+            assert!(limiter.check_at("c:u", start).is_ok());
+        }
+        let wait = limiter.check_at("c:u", start).unwrap_err();
+        assert_eq!(retry_secs(wait), 1);
+
+        // One token per second comes back, capped at the burst size
+        assert!(limiter.check_at("c:u", start + Duration::from_millis(1000)).is_ok());
+        assert!(limiter.check_at("c:u", start + Duration::from_millis(1500)).is_err());
+        for _ in 0..3 {
+            assert!(limiter.check_at("c:u", start + Duration::from_secs(60)).is_ok());
+        }
+        assert!(limiter.check_at("c:u", start + Duration::from_secs(60)).is_err());
+
+        // Other users have their own bucket
+        assert!(limiter.check_at("c:other", start).is_ok());
+    }
+
+    #[test]
+    fn zero_rate_disables_the_limit() {
+        let limiter = RateLimiter::new(RateLimitConfig { per_minute: 0, burst: 1 });
+        let now = Instant::now();
+        for _ in 0..10 {
+            assert!(limiter.check_at("c:u", now).is_ok());
+        }
+    }
+}
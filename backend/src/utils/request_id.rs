@@ -0,0 +1,99 @@
+//! Request ids for tracing a request across logs and services
+//!
+//! Every request gets an id: the caller's `X-Request-Id` when it sends a
+//! usable one, otherwise a fresh UUID. The id is stored in the depot, echoed
+//! in the response header, attached to the request's tracing span and
+//! included in error bodies.
+
+use salvo::http::HeaderValue;
+use salvo::prelude::*;
+use tracing::Instrument;
+use uuid::Uuid;
+
+pub const REQUEST_ID_HEADER: &str = "x-request-id";
+const REQUEST_ID_DEPOT_KEY: &str = "request_id";
+const MAX_REQUEST_ID_LEN: usize = 128;
+
+/// Incoming ids end up in logs and headers, so only plain tokens are kept
+fn is_usable_request_id(id: &str) -> bool {
+    !id.is_empty()
+        && id.len() <= MAX_REQUEST_ID_LEN
+        && id.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.' | ':'))
+}
+
+pub struct RequestIdHandler;
+
+#[async_trait]
+impl Handler for RequestIdHandler {
+    async fn handle(&self, req: &mut Request, depot: &mut Depot, res: &mut Response, ctrl: &mut FlowCtrl) {
+        let request_id = req
+            .header::<String>(REQUEST_ID_HEADER)
+            .filter(|id| is_usable_request_id(id))
+            .unwrap_or_else(|| Uuid::new_v4().to_string());
+
+        depot.insert(REQUEST_ID_DEPOT_KEY, request_id.clone());
+        if let Ok(value) = HeaderValue::from_str(&request_id) {
+            res.headers_mut().insert(REQUEST_ID_HEADER, value);
+        }
+
+        let span = tracing::info_span!(
+            "request",
+            request_id = %request_id,
+            method = %req.method(),
+            path = %req.uri().path()
+        );
+        ctrl.call_next(req, depot, res).instrument(span).await;
+    }
+}
+
+pub fn request_id() -> RequestIdHandler {
+    RequestIdHandler
+}
+
+/// Id of the current request, if the request id middleware ran
+pub fn current_request_id(depot: &Depot) -> Option<&str> {
+    depot.get::<String>(REQUEST_ID_DEPOT_KEY).ok().map(String::as_str)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::AppError;
+    use salvo::test::{ResponseExt, TestClient};
+    use serde_json::Value;
+
+    #[handler]
+    async fn missing() -> Result<(), AppError> {
+        Err(AppError::NotFound("Datasource abc".to_string()))
+    }
+
+    fn service() -> Service {
+        Service::new(Router::new().hoop(request_id()).get(missing))
+    }
+
+    #[tokio::test]
+    async fn incoming_request_id_round_trips() {
+        let mut res = TestClient::get("http://127.0.0.1:5800/")
+            .add_header(REQUEST_ID_HEADER, "req-123", true)
+            .send(&service())
+            .await;
+
+        assert_eq!(res.headers().get(REQUEST_ID_HEADER).unwrap(), "req-123");
+        let body: Value = res.take_json().await.unwrap();
+        assert_eq!(body["code"], "NOT_FOUND");
+        assert_eq!(body["request_id"], "req-123");
+    }
+
+    #[tokio::test]
+    async fn a_request_id_is_generated_when_none_is_sent() {
+        let mut res = TestClient::get("http://127.0.0.1:5800/").send(&service()).await;
+
+        let generated = res.headers().get(REQUEST_ID_HEADER).unwrap().to_str().unwrap().to_string();
+        assert!(Uuid::parse_str(&generated).is_ok());
+        let body: Value = res.take_json().await.unwrap();
+        assert_eq!(body["request_id"], generated.as_str());
+
+        assert!(!is_usable_request_id("two words"));
+        assert!(!is_usable_request_id(&"x".repeat(MAX_REQUEST_ID_LEN + 1)));
+    }
+}
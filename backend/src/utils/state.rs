@@ -1,7 +1,10 @@
+use crate::api::websocket::resume::ResumeStore;
 use crate::core::analysis::AnalysisService;
 use crate::core::sessions::PostgresSessionStore;
 use crate::models::{client::Client, tool_usage::ToolUsage, Message};
 use crate::utils::db;
+use crate::utils::query_limit::QueryLimiter;
+use crate::utils::rate_limit::RateLimiter;
 use crate::utils::Config;
 use chrono::{DateTime, Utc};
 use sea_orm::DatabaseConnection;
@@ -9,6 +12,7 @@ use sqlx::{postgres::PgPoolOptions, PgPool, Row};
 use std::path::PathBuf;
 use std::collections::HashMap;
 use std::collections::HashSet;
+use std::collections::VecDeque;
 use std::sync::Arc;
 use std::time::Duration;
 use tokio::sync::RwLock;
@@ -47,10 +51,117 @@ pub struct StreamingState {
 
     /// Complete history of all events (progress, tool_use, tool_complete)
     /// stored in order to replay them exactly when WebSocket reconnects
-    pub progress_events: Vec<serde_json::Value>,
+    pub progress_events: VecDeque<serde_json::Value>,
+
+    /// Serialized size of each entry in `progress_events` (0 for the truncation marker)
+    event_sizes: VecDeque<usize>,
 
     /// Completed tool usages during streaming (not yet saved to database)
     pub completed_tool_usages: Vec<ToolUsage>,
+
+    /// Approximate serialized size of `progress_events`, used to enforce the buffer cap
+    pub buffered_bytes: usize,
+
+    /// When the conversation last lost its final subscriber; None while someone is listening
+    pub orphaned_since: Option<DateTime<Utc>>,
+}
+
+impl StreamingState {
+    pub fn new(message_id: String) -> Self {
+        Self {
+            message_id,
+            partial_content: String::new(),
+            active_tools: Vec::new(),
+            progress_events: VecDeque::new(),
+            event_sizes: VecDeque::new(),
+            completed_tool_usages: Vec::new(),
+            buffered_bytes: 0,
+            orphaned_since: None,
+        }
+    }
+
+    /// Buffer an event for replay, dropping the oldest events once the buffer
+    /// exceeds `max_bytes`. Dropped events are summarised by a leading
+    /// `buffer_truncated` marker so a reconnecting client knows history is missing.
+    pub fn push_event(&mut self, event: serde_json::Value, max_bytes: usize) {
+        let size = event.to_string().len();
+        self.buffered_bytes += size;
+        self.progress_events.push_back(event);
+        self.event_sizes.push_back(size);
+
+        let mut dropped = 0u64;
+        let has_marker = Self::is_truncation_marker(self.progress_events.front());
+        let first_droppable = if has_marker { 1 } else { 0 };
+
+        // Always keep the newest event, even if it alone is over the cap
+        while self.buffered_bytes > max_bytes && self.progress_events.len() > first_droppable + 1 {
+            self.progress_events.remove(first_droppable);
+            let removed_size = self.event_sizes.remove(first_droppable).unwrap_or(0);
+            self.buffered_bytes = self.buffered_bytes.saturating_sub(removed_size);
+            dropped += 1;
+        }
+
+        if dropped > 0 {
+            if has_marker {
+                if let Some(count) = self.progress_events[0].get_mut("dropped_events") {
+                    *count = serde_json::Value::from(count.as_u64().unwrap_or(0) + dropped);
+                }
+            } else {
+                self.progress_events.push_front(serde_json::json!({
+                    "type": "buffer_truncated",
+                    "dropped_events": dropped
+                }));
+                self.event_sizes.push_front(0);
+            }
+            warn!(
+                "Stream buffer for message {} exceeded {} bytes, dropped {} oldest events",
+                self.message_id, max_bytes, dropped
+            );
+        }
+    }
+
+    /// Release buffered replay data while keeping the stream marked as active
+    pub fn evict_buffer(&mut self) {
+        self.progress_events.clear();
+        self.event_sizes.clear();
+        self.partial_content.clear();
+        self.buffered_bytes = 0;
+    }
+
+    fn is_truncation_marker(event: Option<&serde_json::Value>) -> bool {
+        event
+            .and_then(|e| e.get("type"))
+            .and_then(|t| t.as_str())
+            == Some("buffer_truncated")
+    }
+}
+
+/// Free the replay buffers of streams whose conversation has had no
+/// subscriber for longer than `ttl`
+fn reap_orphaned_stream_buffers(
+    streams: &mut HashMap<String, StreamingState>,
+    subscribed: &HashSet<String>,
+    now: DateTime<Utc>,
+    ttl: chrono::Duration,
+) {
+    for (conversation_id, stream) in streams.iter_mut() {
+        if subscribed.contains(conversation_id) {
+            stream.orphaned_since = None;
+            continue;
+        }
+
+        // Streams that started without any subscriber begin their window now
+        let orphaned_since = *stream.orphaned_since.get_or_insert(now);
+        if now - orphaned_since > ttl && !stream.progress_events.is_empty() {
+            info!(
+                "🧹 Evicting stream buffer for conversation {} ({} events, no reconnect for {}s)",
+                conversation_id,
+                stream.progress_events.len(),
+                ttl.num_seconds()
+            );
+            stream.evict_buffer();
+        }
+    }
 }
 
 #[derive(Clone, Debug)]
@@ -65,6 +176,42 @@ pub struct ConversationCache {
     pub last_accessed: DateTime<Utc>,
 }
 
+/// Held for the duration of one Claude generation in a conversation; the
+/// conversation is released when the lock is dropped
+pub struct ConversationLock {
+    generations: Arc<std::sync::Mutex<HashMap<String, Uuid>>>,
+    conversation_id: String,
+    generation_id: Uuid,
+}
+
+impl ConversationLock {
+    /// Claim `conversation_id` in `generations`, or None while it is held
+    fn acquire(generations: &Arc<std::sync::Mutex<HashMap<String, Uuid>>>, conversation_id: &str) -> Option<Self> {
+        let mut held = generations.lock().ok()?;
+        if held.contains_key(conversation_id) {
+            return None;
+        }
+        let generation_id = Uuid::new_v4();
+        held.insert(conversation_id.to_string(), generation_id);
+        Some(Self {
+            generations: Arc::clone(generations),
+            conversation_id: conversation_id.to_string(),
+            generation_id,
+        })
+    }
+}
+
+impl Drop for ConversationLock {
+    fn drop(&mut self) {
+        if let Ok(mut generations) = self.generations.lock() {
+            // Only ever release our own claim
+            if generations.get(&self.conversation_id) == Some(&self.generation_id) {
+                generations.remove(&self.conversation_id);
+            }
+        }
+    }
+}
+
 #[derive(Clone)]
 pub struct AppState {
     #[allow(dead_code)]
@@ -76,6 +223,14 @@ pub struct AppState {
     pub clients: Arc<RwLock<HashMap<Uuid, Client>>>,
     pub active_claude_streams: Arc<RwLock<HashMap<String, StreamingState>>>,
     pub conversation_cache: Arc<RwLock<HashMap<String, ConversationCache>>>,
+    /// Conversations with a Claude generation in flight, mapped to that generation's id
+    pub active_generations: Arc<std::sync::Mutex<HashMap<String, Uuid>>>,
+    /// Per-user token buckets limiting how often prompts can be sent
+    pub prompt_rate_limiter: Arc<RateLimiter>,
+    /// Per-client semaphores capping concurrent Claude queries
+    pub claude_query_limiter: Arc<QueryLimiter>,
+    /// Subscriptions that reconnecting WebSocket clients can resume
+    pub ws_resume: Arc<ResumeStore>,
     pub session_store: PostgresSessionStore,
     pub analysis_service: AnalysisService,
 }
@@ -83,7 +238,7 @@ pub struct AppState {
 impl AppState {
     pub async fn new(config: &Config) -> Result<Self, Box<dyn std::error::Error>> {
         // Connect to database with SeaORM (handles its own connection pool)
-        let db = db::connect(&config.database_url).await?;
+        let db = db::connect(&config.database_url, &config.db_connect_retry).await?;
 
         // Create SQLx connection pool with comprehensive logging
         info!("🔌 Creating SQLx PostgreSQL connection pool...");
@@ -93,8 +248,7 @@ impl AppState {
         let session_store = PostgresSessionStore::new(db_arc.clone());
 
         // Initialize analysis service
-        let data_dir = PathBuf::from("./analysis_data");
-        tokio::fs::create_dir_all(&data_dir).await?;
+        tokio::fs::create_dir_all(&config.analysis_data_dir).await?;
         let clients_dir = PathBuf::from("./.clients");
         tokio::fs::create_dir_all(&clients_dir).await?;
         let analysis_service = AnalysisService::new(db_pool.clone(), clients_dir)?;
@@ -106,6 +260,10 @@ impl AppState {
             clients: Arc::new(RwLock::new(HashMap::new())),
             active_claude_streams: Arc::new(RwLock::new(HashMap::new())),
             conversation_cache: Arc::new(RwLock::new(HashMap::new())),
+            active_generations: Arc::new(std::sync::Mutex::new(HashMap::new())),
+            prompt_rate_limiter: Arc::new(RateLimiter::new(config.prompt_rate_limit)),
+            claude_query_limiter: Arc::new(QueryLimiter::new(config.max_concurrent_queries)),
+            ws_resume: Arc::new(ResumeStore::default()),
             session_store,
             analysis_service,
         };
@@ -113,6 +271,9 @@ impl AppState {
         // Start pool health monitor
         state.start_pool_health_monitor();
 
+        // Evict replay buffers of streams nobody has reconnected to
+        state.start_stream_buffer_reaper();
+
         Ok(state)
     }
 
@@ -395,6 +556,12 @@ impl AppState {
         self.load_conversation_cache(conversation_id).await
     }
 
+    /// Claim a conversation for a new generation. Returns None while another
+    /// generation is still running in it.
+    pub fn try_lock_conversation(&self, conversation_id: &str) -> Option<ConversationLock> {
+        ConversationLock::acquire(&self.active_generations, conversation_id)
+    }
+
     /// Add a subscriber to a conversation
     pub async fn add_conversation_subscriber(&self, conversation_id: &str, client_id: &str) {
         let mut cache = self.conversation_cache.write().await;
@@ -426,6 +593,12 @@ impl AppState {
                 conversation_id, client_id
             );
         }
+        drop(cache);
+
+        // Someone is listening again, so the stream buffer must be kept
+        if let Some(stream) = self.active_claude_streams.write().await.get_mut(conversation_id) {
+            stream.orphaned_since = None;
+        }
     }
 
     /// Remove a subscriber from a conversation
@@ -448,6 +621,12 @@ impl AppState {
                     "🗑️ Removed cache for conversation {} (no subscribers)",
                     conversation_id
                 );
+                drop(cache);
+
+                // Start the reconnect window for any in-flight stream buffer
+                if let Some(stream) = self.active_claude_streams.write().await.get_mut(conversation_id) {
+                    stream.orphaned_since.get_or_insert_with(Utc::now);
+                }
             }
         }
     }
@@ -482,6 +661,33 @@ impl AppState {
         self.load_conversation_cache(conversation_id).await
     }
 
+    /// Start background task that frees replay buffers of streams whose
+    /// conversation has had no subscriber for longer than the configured TTL
+    fn start_stream_buffer_reaper(&self) {
+        let streams = self.active_claude_streams.clone();
+        let conversation_cache = self.conversation_cache.clone();
+        let ttl = chrono::Duration::seconds(self.config.stream_buffer_ttl_secs as i64);
+
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(Duration::from_secs(60));
+
+            loop {
+                interval.tick().await;
+
+                let subscribed: HashSet<String> = conversation_cache
+                    .read()
+                    .await
+                    .iter()
+                    .filter(|(_, cached)| !cached.subscribers.is_empty())
+                    .map(|(id, _)| id.clone())
+                    .collect();
+
+                let mut streams = streams.write().await;
+                reap_orphaned_stream_buffers(&mut streams, &subscribed, Utc::now(), ttl);
+            }
+        });
+    }
+
     /// Start background task to monitor pool health and attempt recovery
     fn start_pool_health_monitor(&self) {
         let pool = self.db_pool.clone();
@@ -564,3 +770,93 @@ pub fn get_app_state(depot: &Depot) -> Result<&AppState, salvo::http::StatusErro
         .obtain::<AppState>()
         .map_err(|_| salvo::http::StatusError::internal_server_error())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn progress(n: usize) -> serde_json::Value {
+        json!({ "type": "progress", "content": format!("chunk {:04}", n) })
+    }
+
+    #[test]
+    fn buffer_stays_under_the_cap_and_keeps_the_newest_events() {
+        let event_size = progress(0).to_string().len();
+        let max_bytes = event_size * 10;
+        let mut stream = StreamingState::new("msg-1".to_string());
+        for n in 0..100 {
+            stream.push_event(progress(n), max_bytes);
+        }
+
+        assert!(stream.buffered_bytes <= max_bytes);
+        assert_eq!(stream.buffered_bytes, event_size * (stream.progress_events.len() - 1));
+        assert_eq!(stream.progress_events.back(), Some(&progress(99)));
+
+        // A single event over the cap is still kept
+        let mut tiny = StreamingState::new("msg-2".to_string());
+        tiny.push_event(progress(0), 1);
+        assert_eq!(tiny.progress_events.len(), 1);
+    }
+
+    #[test]
+    fn dropped_events_are_counted_by_one_leading_marker() {
+        let max_bytes = progress(0).to_string().len() * 10;
+        let mut stream = StreamingState::new("msg-1".to_string());
+        for n in 0..100 {
+            stream.push_event(progress(n), max_bytes);
+        }
+
+        let kept = stream.progress_events.len() - 1;
+        assert_eq!(
+            stream.progress_events.front(),
+            Some(&json!({ "type": "buffer_truncated", "dropped_events": 100 - kept }))
+        );
+        let markers = stream
+            .progress_events
+            .iter()
+            .filter(|event| event["type"] == "buffer_truncated")
+            .count();
+        assert_eq!(markers, 1);
+        assert_eq!(stream.progress_events[1], progress(100 - kept));
+    }
+
+    #[test]
+    fn a_conversation_is_locked_until_its_generation_ends() {
+        let generations = Arc::new(std::sync::Mutex::new(HashMap::new()));
+
+        let lock = ConversationLock::acquire(&generations, "conv-1").unwrap();
+        assert!(ConversationLock::acquire(&generations, "conv-1").is_none());
+        // Other conversations are unaffected
+        let other = ConversationLock::acquire(&generations, "conv-2").unwrap();
+
+        drop(lock);
+        assert!(ConversationLock::acquire(&generations, "conv-1").is_some());
+        drop(other);
+        assert!(generations.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn reaper_frees_buffers_only_after_the_ttl_without_subscribers() {
+        let ttl = chrono::Duration::seconds(300);
+        let start = Utc::now();
+        let mut streams = HashMap::new();
+        for conversation_id in ["watched", "orphaned"] {
+            let mut stream = StreamingState::new(format!("msg-{}", conversation_id));
+            stream.push_event(progress(0), usize::MAX);
+            streams.insert(conversation_id.to_string(), stream);
+        }
+        let subscribed: HashSet<String> = ["watched".to_string()].into_iter().collect();
+
+        reap_orphaned_stream_buffers(&mut streams, &subscribed, start, ttl);
+        assert_eq!(streams["orphaned"].orphaned_since, Some(start));
+        assert_eq!(streams["orphaned"].progress_events.len(), 1);
+
+        let later = start + ttl + chrono::Duration::seconds(1);
+        reap_orphaned_stream_buffers(&mut streams, &subscribed, later, ttl);
+        assert!(streams["orphaned"].progress_events.is_empty());
+        assert_eq!(streams["orphaned"].buffered_bytes, 0);
+        assert_eq!(streams["watched"].orphaned_since, None);
+        assert_eq!(streams["watched"].progress_events.len(), 1);
+    }
+}
@@ -0,0 +1,564 @@
+//! Storage for uploaded files and generated exports
+//!
+//! Files are addressed by keys of the form `{client_id}/{project_id}/{area}/{file}`.
+//! The local backend keeps a key at `.clients/{key}` (the historical layout); the
+//! S3 backend stores it as an object under an optional prefix. STORAGE_BACKEND
+//! selects the backend for both the API server and the MCP server; it is read
+//! into `Config::storage`, so a misconfigured backend fails startup.
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use s3::{creds::Credentials, Bucket, Region};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, OnceLock};
+
+use crate::utils::config::Config;
+
+const LOCAL_ROOT: &str = ".clients";
+
+#[derive(Debug, thiserror::Error)]
+pub enum StorageError {
+    #[error("Object not found: {0}")]
+    NotFound(String),
+    #[error("Storage I/O error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Storage backend error: {0}")]
+    Backend(String),
+}
+
+/// An object returned by `StorageBackend::list`
+#[derive(Debug, Clone)]
+pub struct StoredObject {
+    pub key: String,
+    pub size: u64,
+    pub last_modified: Option<DateTime<Utc>>,
+}
+
+#[async_trait]
+pub trait StorageBackend: Send + Sync {
+    fn name(&self) -> &'static str;
+
+    async fn put(&self, key: &str, data: Vec<u8>, content_type: Option<&str>) -> Result<(), StorageError>;
+    /// Store the file at `path` under `key` without reading it all into memory
+    async fn put_file(&self, key: &str, path: &Path, content_type: Option<&str>) -> Result<(), StorageError>;
+    async fn get(&self, key: &str) -> Result<Vec<u8>, StorageError>;
+    /// Bytes `start..=end` of an object, for HTTP range requests. `end` must be
+    /// within the object.
+    async fn get_range(&self, key: &str, start: u64, end: u64) -> Result<Vec<u8>, StorageError> {
+        let data = self.get(key).await?;
+        let start = (start as usize).min(data.len());
+        let end = (end as usize).saturating_add(1).min(data.len()).max(start);
+        Ok(data[start..end].to_vec())
+    }
+    async fn size(&self, key: &str) -> Result<u64, StorageError>;
+    /// Deleting a missing key is not an error
+    async fn delete(&self, key: &str) -> Result<(), StorageError>;
+    /// Objects whose key starts with `prefix`, within the prefix's directory
+    async fn list(&self, prefix: &str) -> Result<Vec<StoredObject>, StorageError>;
+
+    /// Where the key lives, as recorded in `file_uploads.file_path`
+    fn location(&self, key: &str) -> String;
+    /// Inverse of `location`; also accepts bare keys
+    fn key_from_location(&self, location: &str) -> String;
+
+    /// Path on local disk when the backend keeps files there, so they can be
+    /// served or parsed without a copy
+    fn local_path(&self, _key: &str) -> Option<PathBuf> {
+        None
+    }
+}
+
+pub fn upload_key(client_id: &str, project_id: &str, file_name: &str) -> String {
+    format!("{}/{}/uploads/{}", client_id, project_id, file_name)
+}
+
+pub fn excel_export_prefix(client_id: &str, project_id: &str) -> String {
+    format!("{}/{}/excel_exports/", client_id, project_id)
+}
+
+/// Filesystem layout under `.clients`, used when STORAGE_BACKEND is unset or "local"
+pub struct LocalStorage {
+    root: PathBuf,
+}
+
+impl LocalStorage {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+
+    fn path_for(&self, key: &str) -> PathBuf {
+        self.root.join(key)
+    }
+}
+
+impl Default for LocalStorage {
+    fn default() -> Self {
+        Self::new(LOCAL_ROOT)
+    }
+}
+
+#[async_trait]
+impl StorageBackend for LocalStorage {
+    fn name(&self) -> &'static str {
+        "local"
+    }
+
+    async fn put(&self, key: &str, data: Vec<u8>, _content_type: Option<&str>) -> Result<(), StorageError> {
+        let path = self.path_for(key);
+        if let Some(parent) = path.parent() {
+            tokio::fs::create_dir_all(parent).await?;
+        }
+        tokio::fs::write(&path, data).await?;
+        Ok(())
+    }
+
+    async fn put_file(&self, key: &str, path: &Path, _content_type: Option<&str>) -> Result<(), StorageError> {
+        let target = self.path_for(key);
+        if let Some(parent) = target.parent() {
+            tokio::fs::create_dir_all(parent).await?;
+        }
+        tokio::fs::copy(path, &target).await?;
+        Ok(())
+    }
+
+    async fn get(&self, key: &str) -> Result<Vec<u8>, StorageError> {
+        tokio::fs::read(self.path_for(key)).await.map_err(|e| match e.kind() {
+            std::io::ErrorKind::NotFound => StorageError::NotFound(key.to_string()),
+            _ => StorageError::Io(e),
+        })
+    }
+
+    async fn get_range(&self, key: &str, start: u64, end: u64) -> Result<Vec<u8>, StorageError> {
+        use tokio::io::{AsyncReadExt, AsyncSeekExt};
+
+        let mut file = tokio::fs::File::open(self.path_for(key)).await.map_err(|e| match e.kind() {
+            std::io::ErrorKind::NotFound => StorageError::NotFound(key.to_string()),
+            _ => StorageError::Io(e),
+        })?;
+        file.seek(std::io::SeekFrom::Start(start)).await?;
+        let mut data = Vec::new();
+        file.take(end.saturating_sub(start) + 1).read_to_end(&mut data).await?;
+        Ok(data)
+    }
+
+    async fn size(&self, key: &str) -> Result<u64, StorageError> {
+        tokio::fs::metadata(self.path_for(key))
+            .await
+            .map(|m| m.len())
+            .map_err(|e| match e.kind() {
+                std::io::ErrorKind::NotFound => StorageError::NotFound(key.to_string()),
+                _ => StorageError::Io(e),
+            })
+    }
+
+    async fn delete(&self, key: &str) -> Result<(), StorageError> {
+        match tokio::fs::remove_file(self.path_for(key)).await {
+            Ok(()) => Ok(()),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+            Err(e) => Err(StorageError::Io(e)),
+        }
+    }
+
+    async fn list(&self, prefix: &str) -> Result<Vec<StoredObject>, StorageError> {
+        let (dir, name_prefix) = match prefix.rfind('/') {
+            Some(pos) => (&prefix[..pos], &prefix[pos + 1..]),
+            None => ("", prefix),
+        };
+
+        let mut entries = match tokio::fs::read_dir(self.root.join(dir)).await {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(StorageError::Io(e)),
+        };
+
+        let mut objects = Vec::new();
+        while let Some(entry) = entries.next_entry().await? {
+            let file_name = entry.file_name().to_string_lossy().to_string();
+            if !file_name.starts_with(name_prefix) {
+                continue;
+            }
+            let metadata = entry.metadata().await?;
+            if !metadata.is_file() {
+                continue;
+            }
+            objects.push(StoredObject {
+                key: if dir.is_empty() { file_name } else { format!("{}/{}", dir, file_name) },
+                size: metadata.len(),
+                last_modified: metadata.modified().ok().map(DateTime::<Utc>::from),
+            });
+        }
+        Ok(objects)
+    }
+
+    fn location(&self, key: &str) -> String {
+        self.path_for(key).to_string_lossy().to_string()
+    }
+
+    fn key_from_location(&self, location: &str) -> String {
+        let root = format!("{}/", self.root.to_string_lossy());
+        let location = location.trim_start_matches("./");
+        location.strip_prefix(&root).unwrap_or(location).to_string()
+    }
+
+    fn local_path(&self, key: &str) -> Option<PathBuf> {
+        Some(self.path_for(key))
+    }
+}
+
+/// Which backend stores files, as selected by STORAGE_BACKEND
+#[derive(Debug, Clone, Default)]
+pub enum StorageConfig {
+    #[default]
+    Local,
+    S3(S3Config),
+}
+
+impl StorageConfig {
+    /// An unknown STORAGE_BACKEND or an s3 backend without S3_BUCKET is an error
+    pub fn from_env() -> Result<Self, StorageError> {
+        match std::env::var("STORAGE_BACKEND").unwrap_or_default().to_lowercase().as_str() {
+            "" | "local" => Ok(StorageConfig::Local),
+            "s3" => Ok(StorageConfig::S3(S3Config::from_env()?)),
+            other => Err(StorageError::Backend(format!("Unknown STORAGE_BACKEND: {}", other))),
+        }
+    }
+}
+
+/// Connection settings of the S3 backend
+#[derive(Clone)]
+pub struct S3Config {
+    pub bucket: String,
+    pub region: String,
+    pub endpoint: Option<String>,
+    pub access_key_id: Option<String>,
+    pub secret_access_key: Option<String>,
+    pub path_style: bool,
+    pub prefix: String,
+}
+
+impl std::fmt::Debug for S3Config {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("S3Config")
+            .field("bucket", &self.bucket)
+            .field("region", &self.region)
+            .field("endpoint", &self.endpoint)
+            .field("access_key_id", &self.access_key_id)
+            .field("secret_access_key", &self.secret_access_key.as_ref().map(|_| "****"))
+            .field("path_style", &self.path_style)
+            .field("prefix", &self.prefix)
+            .finish()
+    }
+}
+
+impl S3Config {
+    /// Read S3_BUCKET, S3_REGION, S3_ENDPOINT, S3_ACCESS_KEY_ID, S3_SECRET_ACCESS_KEY,
+    /// S3_PATH_STYLE and S3_PREFIX
+    pub fn from_env() -> Result<Self, StorageError> {
+        let bucket = std::env::var("S3_BUCKET")
+            .ok()
+            .filter(|b| !b.is_empty())
+            .ok_or_else(|| StorageError::Backend("S3_BUCKET must be set for the s3 storage backend".to_string()))?;
+
+        Ok(Self {
+            bucket,
+            region: std::env::var("S3_REGION").unwrap_or_else(|_| "us-east-1".to_string()),
+            endpoint: std::env::var("S3_ENDPOINT").ok().filter(|e| !e.is_empty()),
+            access_key_id: std::env::var("S3_ACCESS_KEY_ID").ok(),
+            secret_access_key: std::env::var("S3_SECRET_ACCESS_KEY").ok(),
+            path_style: std::env::var("S3_PATH_STYLE").map(|v| v == "true" || v == "1").unwrap_or(false),
+            prefix: std::env::var("S3_PREFIX")
+                .map(|p| p.trim_matches('/').to_string())
+                .unwrap_or_default(),
+        })
+    }
+}
+
+/// Any S3-compatible object store (AWS S3, MinIO, R2, ...)
+pub struct S3Storage {
+    bucket: Box<Bucket>,
+    prefix: String,
+}
+
+impl S3Storage {
+    /// Without explicit keys the usual AWS credential chain (environment,
+    /// profile, instance metadata) is used
+    pub fn new(config: &S3Config) -> Result<Self, StorageError> {
+        let region = match &config.endpoint {
+            Some(endpoint) => Region::Custom { region: config.region.clone(), endpoint: endpoint.clone() },
+            None => config
+                .region
+                .parse::<Region>()
+                .map_err(|e| StorageError::Backend(format!("Invalid S3_REGION: {}", e)))?,
+        };
+
+        let credentials = match (&config.access_key_id, &config.secret_access_key) {
+            (Some(access_key), Some(secret_key)) => {
+                Credentials::new(Some(access_key), Some(secret_key), None, None, None)
+            }
+            _ => Credentials::default(),
+        }
+        .map_err(|e| StorageError::Backend(format!("Invalid S3 credentials: {}", e)))?;
+
+        let mut bucket = Bucket::new(&config.bucket, region, credentials)
+            .map_err(|e| StorageError::Backend(format!("Invalid S3 bucket configuration: {}", e)))?;
+        if config.path_style {
+            bucket = bucket.with_path_style();
+        }
+
+        Ok(Self { bucket, prefix: config.prefix.clone() })
+    }
+
+    fn object_key(&self, key: &str) -> String {
+        if self.prefix.is_empty() {
+            key.to_string()
+        } else {
+            format!("{}/{}", self.prefix, key)
+        }
+    }
+
+    fn strip_prefix<'a>(&self, object_key: &'a str) -> &'a str {
+        if self.prefix.is_empty() {
+            return object_key;
+        }
+        object_key
+            .strip_prefix(&self.prefix)
+            .map(|rest| rest.trim_start_matches('/'))
+            .unwrap_or(object_key)
+    }
+}
+
+fn check_status(status: u16, key: &str) -> Result<(), StorageError> {
+    match status {
+        200..=299 => Ok(()),
+        404 => Err(StorageError::NotFound(key.to_string())),
+        code => Err(StorageError::Backend(format!("S3 returned status {} for {}", code, key))),
+    }
+}
+
+#[async_trait]
+impl StorageBackend for S3Storage {
+    fn name(&self) -> &'static str {
+        "s3"
+    }
+
+    async fn put(&self, key: &str, data: Vec<u8>, content_type: Option<&str>) -> Result<(), StorageError> {
+        let response = self
+            .bucket
+            .put_object_with_content_type(
+                self.object_key(key),
+                &data,
+                content_type.unwrap_or("application/octet-stream"),
+            )
+            .await
+            .map_err(|e| StorageError::Backend(e.to_string()))?;
+        check_status(response.status_code(), key)
+    }
+
+    async fn put_file(&self, key: &str, path: &Path, content_type: Option<&str>) -> Result<(), StorageError> {
+        let mut file = tokio::fs::File::open(path).await?;
+        let response = self
+            .bucket
+            .put_object_stream_with_content_type(
+                &mut file,
+                self.object_key(key),
+                content_type.unwrap_or("application/octet-stream"),
+            )
+            .await
+            .map_err(|e| StorageError::Backend(e.to_string()))?;
+        check_status(response.status_code(), key)
+    }
+
+    async fn get(&self, key: &str) -> Result<Vec<u8>, StorageError> {
+        let response = self
+            .bucket
+            .get_object(self.object_key(key))
+            .await
+            .map_err(|e| StorageError::Backend(e.to_string()))?;
+        check_status(response.status_code(), key)?;
+        Ok(response.bytes().to_vec())
+    }
+
+    async fn get_range(&self, key: &str, start: u64, end: u64) -> Result<Vec<u8>, StorageError> {
+        // rust-s3 wants start < end, so a one-byte range asks for two and trims
+        let response = self
+            .bucket
+            .get_object_range(self.object_key(key), start, Some(end.max(start + 1)))
+            .await
+            .map_err(|e| StorageError::Backend(e.to_string()))?;
+        check_status(response.status_code(), key)?;
+        let mut data = response.bytes().to_vec();
+        data.truncate((end.saturating_sub(start) + 1) as usize);
+        Ok(data)
+    }
+
+    async fn size(&self, key: &str) -> Result<u64, StorageError> {
+        let (head, status) = self
+            .bucket
+            .head_object(self.object_key(key))
+            .await
+            .map_err(|e| StorageError::Backend(e.to_string()))?;
+        check_status(status, key)?;
+        Ok(head.content_length.unwrap_or(0).max(0) as u64)
+    }
+
+    async fn delete(&self, key: &str) -> Result<(), StorageError> {
+        let response = self
+            .bucket
+            .delete_object(self.object_key(key))
+            .await
+            .map_err(|e| StorageError::Backend(e.to_string()))?;
+        match check_status(response.status_code(), key) {
+            Err(StorageError::NotFound(_)) => Ok(()),
+            other => other,
+        }
+    }
+
+    async fn list(&self, prefix: &str) -> Result<Vec<StoredObject>, StorageError> {
+        let pages = self
+            .bucket
+            .list(self.object_key(prefix), Some("/".to_string()))
+            .await
+            .map_err(|e| StorageError::Backend(e.to_string()))?;
+
+        Ok(pages
+            .into_iter()
+            .flat_map(|page| page.contents)
+            .map(|object| StoredObject {
+                key: self.strip_prefix(&object.key).to_string(),
+                size: object.size,
+                last_modified: DateTime::parse_from_rfc3339(&object.last_modified)
+                    .ok()
+                    .map(|dt| dt.with_timezone(&Utc)),
+            })
+            .collect())
+    }
+
+    fn location(&self, key: &str) -> String {
+        format!("s3://{}/{}", self.bucket.name(), self.object_key(key))
+    }
+
+    fn key_from_location(&self, location: &str) -> String {
+        let bucket_root = format!("s3://{}/", self.bucket.name());
+        match location.strip_prefix(&bucket_root) {
+            Some(object_key) => self.strip_prefix(object_key).to_string(),
+            // Rows written while the local backend was active
+            None => LocalStorage::default().key_from_location(location),
+        }
+    }
+}
+
+fn backend_for(config: &StorageConfig) -> Result<Arc<dyn StorageBackend>, StorageError> {
+    match config {
+        StorageConfig::Local => Ok(Arc::new(LocalStorage::default())),
+        StorageConfig::S3(s3) => Ok(Arc::new(S3Storage::new(s3)?)),
+    }
+}
+
+static STORAGE: OnceLock<Arc<dyn StorageBackend>> = OnceLock::new();
+
+/// Build the backend `config` selects; called at startup so a misconfigured
+/// backend stops the process instead of writing somewhere unexpected
+pub fn init_storage(config: &StorageConfig) -> Result<(), StorageError> {
+    let backend = backend_for(config)?;
+    let _ = STORAGE.set(backend);
+    Ok(())
+}
+
+/// The configured storage backend. Processes that skip `init_storage` (unit
+/// tests) build it from `Config::current` on first use, falling back to local
+/// storage if that configuration is invalid.
+pub fn storage() -> Arc<dyn StorageBackend> {
+    STORAGE
+        .get_or_init(|| {
+            let backend = Config::current()
+                .map_err(|e| e.to_string())
+                .and_then(|config| backend_for(&config.storage).map_err(|e| e.to_string()));
+            backend.unwrap_or_else(|e| {
+                tracing::error!("Storage backend unavailable, using local storage: {}", e);
+                Arc::new(LocalStorage::default())
+            })
+        })
+        .clone()
+}
+
+/// A stored file available on local disk, for code that needs a path
+/// (content extraction). Temp copies are removed on drop.
+pub enum LocalFile {
+    Stored(PathBuf),
+    Temp(tempfile::TempPath),
+}
+
+impl LocalFile {
+    pub fn path(&self) -> &Path {
+        match self {
+            LocalFile::Stored(path) => path,
+            LocalFile::Temp(path) => path,
+        }
+    }
+
+    /// Copy a file into a temp file that keeps its extension
+    pub fn temp_copy(source: &Path) -> std::io::Result<Self> {
+        let temp = temp_file_like(source)?;
+        std::fs::copy(source, temp.path())?;
+        Ok(LocalFile::Temp(temp.into_temp_path()))
+    }
+
+    /// Write `data` to a temp file with the same extension as `name`
+    pub fn temp_with_data(name: &Path, data: &[u8]) -> std::io::Result<Self> {
+        let temp = temp_file_like(name)?;
+        std::fs::write(temp.path(), data)?;
+        Ok(LocalFile::Temp(temp.into_temp_path()))
+    }
+}
+
+fn temp_file_like(path: &Path) -> std::io::Result<tempfile::NamedTempFile> {
+    let suffix = path
+        .extension()
+        .map(|ext| format!(".{}", ext.to_string_lossy()))
+        .unwrap_or_default();
+    tempfile::Builder::new().suffix(&suffix).tempfile()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn list_returns_matching_files_in_the_prefix_directory() {
+        let root = tempfile::tempdir().unwrap();
+        let storage = LocalStorage::new(root.path());
+        storage.put("c/p/excel_exports/report-1.xlsx", b"one".to_vec(), None).await.unwrap();
+        storage.put("c/p/excel_exports/report-2.xlsx", b"two!".to_vec(), None).await.unwrap();
+        storage.put("c/p/excel_exports/other.xlsx", b"x".to_vec(), None).await.unwrap();
+        storage.put("c/p/excel_exports/nested/report-3.xlsx", b"x".to_vec(), None).await.unwrap();
+
+        let mut objects = storage.list("c/p/excel_exports/report-").await.unwrap();
+        objects.sort_by(|a, b| a.key.cmp(&b.key));
+        let listed: Vec<(&str, u64)> = objects.iter().map(|o| (o.key.as_str(), o.size)).collect();
+        assert_eq!(
+            listed,
+            vec![("c/p/excel_exports/report-1.xlsx", 3), ("c/p/excel_exports/report-2.xlsx", 4)]
+        );
+        assert!(storage.list("c/missing/").await.unwrap().is_empty());
+    }
+
+    #[test]
+    fn key_from_location_inverts_location() {
+        let storage = LocalStorage::default();
+        let key = "c/p/uploads/file.csv";
+        assert_eq!(storage.key_from_location(&storage.location(key)), key);
+        assert_eq!(storage.key_from_location("./.clients/c/p/uploads/file.csv"), key);
+        assert_eq!(storage.key_from_location(key), key);
+    }
+
+    #[tokio::test]
+    async fn put_file_copies_the_file_under_its_key() {
+        let root = tempfile::tempdir().unwrap();
+        let storage = LocalStorage::new(root.path().join("store"));
+        let source = root.path().join("upload.tmp");
+        std::fs::write(&source, b"a,b\n1,2\n").unwrap();
+
+        storage.put_file("c/p/uploads/data.csv", &source, Some("text/csv")).await.unwrap();
+        assert_eq!(storage.get("c/p/uploads/data.csv").await.unwrap(), b"a,b\n1,2\n");
+    }
+}
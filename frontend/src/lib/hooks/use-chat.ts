@@ -2,7 +2,8 @@ import { useCallback, useEffect } from "react";
 import { useNavigate } from "react-router-dom";
 import { useSnapshot } from "valtio";
 import { wsService } from "../services/ws-service";
-import { chatStore } from "../store/chat/chat-store";
+import { chatStore, setConversationError } from "../store/chat/chat-store";
+import { chatInputActions } from "../store/chat-input-store";
 import { sidebarActions } from "../store/chat/sidebar-store";
 import type { CONVERSATION_ID, Message, PROJECT_ID } from "../types/chat";
 import type { ServerMessage } from "../types/ws";
@@ -95,6 +96,20 @@ export const useChat = () => {
       // Could add error state to store if needed
     };
 
+    // Another generation is running in this conversation, so the server dropped our message
+    const handleConversationBusy = (
+      message: ServerMessage & { type: "conversation_busy" }
+    ) => {
+      const messages = chatStore.map[message.conversation_id]?.messages;
+      const lastMessage = messages?.[messages.length - 1];
+      if (messages && lastMessage?.role === "user") {
+        // Undo the optimistic message and give its text back to the input
+        messages.pop();
+        chatInputActions.setInput(lastMessage.content);
+      }
+      setConversationError(message.conversation_id, message.message);
+    };
+
     // Handle new conversation management responses
     const handleConversationList = (
       message: ServerMessage & { type: "conversation_list" }
@@ -269,6 +284,7 @@ export const useChat = () => {
     wsService.on("content", stream.content);
     wsService.on("complete", stream.complete);
     wsService.on("error", handleError);
+    wsService.on("conversation_busy", handleConversationBusy);
     wsService.on("subscribed", (msg: { conversation_id: string }) => {
       chatStore.conversation_id = msg.conversation_id;
     });
@@ -448,6 +464,7 @@ export const useChat = () => {
       wsService.off("content", stream.content);
       wsService.off("complete", stream.complete);
       wsService.off("error", handleError);
+      wsService.off("conversation_busy", handleConversationBusy);
 
       // Tool event cleanup
       wsService.off("tool_started", handleToolStarted);
@@ -57,6 +57,8 @@ class WebSocketService extends EventEmitter {
   private currentConversationId = "";
   private activeStreams = new Map<string, StreamingState>();
   private messageQueue: ClientMessage[] = [];
+  // Token from the last "connected" message, used to restore the subscription after a reconnect
+  private resumeToken: string | null = null;
 
   // Singleton pattern
   private static instance: WebSocketService;
@@ -105,6 +107,7 @@ class WebSocketService extends EventEmitter {
 
     this.activeStreams.clear();
     this.messageQueue = [];
+    this.resumeToken = null;
     this.emit("disconnected");
   }
 
@@ -288,12 +291,15 @@ class WebSocketService extends EventEmitter {
         }
       }
 
-      // Auto-subscribe if we have project/conversation
+      // Auto-subscribe if we have project/conversation, otherwise pick up
+      // where the previous connection left off
       if (this.currentProjectId) {
         this.subscribe(
           this.currentProjectId,
           this.currentConversationId || undefined
         );
+      } else if (this.resumeToken) {
+        this.sendMessage({ type: "resume", resume_token: this.resumeToken });
       }
 
       this.startPingInterval();
@@ -314,8 +320,8 @@ class WebSocketService extends EventEmitter {
       this.clearTimers();
       this.emit("disconnected");
 
-      // Auto-reconnect unless it was a clean close
-      if (event.code !== 1000) {
+      // Auto-reconnect unless it was a clean close or an admin disconnect (1008)
+      if (event.code !== 1000 && event.code !== 1008) {
         this.handleReconnect();
       }
     };
@@ -341,6 +347,7 @@ class WebSocketService extends EventEmitter {
           console.warn("WebSocket not authenticated");
           this.emit("authentication_required");
         }
+        this.resumeToken = message.resume_token || null;
         break;
 
       case "conversation_redirect":
@@ -8,6 +8,7 @@ interface Project {
   client_id: string
   conversation_count?: number
   datasource_count?: number
+  role?: 'owner' | 'member' | null
 }
 
 export const projectsStore = proxy({
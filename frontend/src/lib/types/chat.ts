@@ -48,6 +48,7 @@ export type Conversation = {
   is_title_manually_set?: boolean;
   created_by_user_id?: string;
   visibility?: "private" | "public";
+  model?: string;
   messages: Message[];
 };
 
@@ -8,6 +8,7 @@ export type ServerMessage =
       authenticated: boolean;
       client_id?: string;
       role?: string;
+      resume_token?: string;
     }
   | { type: "authentication_required" }
   | { type: "subscribed"; project_id: string; conversation_id?: string }
@@ -17,6 +18,8 @@ export type ServerMessage =
       new_conversation_id: string;
     }
   | { type: "pong" }
+  | { type: "resume_failed"; reason: string }
+  | { type: "disconnected"; reason: string }
   | { type: "start"; id: string; conversation_id: string }
   | { 
       type: "progress"; 
@@ -80,6 +83,7 @@ export type ServerMessage =
       tool_usages?: ToolUsage[];
     }
   | { type: "error"; error: string; conversation_id: string }
+  | { type: "conversation_busy"; conversation_id: string; message: string }
   | {
       type: "conversation_activity";
       conversation_id: string;
@@ -94,6 +98,7 @@ export type ServerMessage =
   | { type: "conversation_details"; conversation: import('./chat').Conversation }
   | { type: "conversation_updated"; conversation: import('./chat').Conversation }
   | { type: "conversation_deleted"; conversation_id: string }
+  | { type: "conversation_restored"; conversation: import('./chat').Conversation }
   | {
       type: "conversations_bulk_deleted";
       conversation_ids: string[];
@@ -103,12 +108,15 @@ export type ServerMessage =
       type: "conversation_messages";
       conversation_id: string;
       messages: Message[];
+      has_more?: boolean;
+      next_cursor?: string | null;
     };
 
 // Client message types (sent to backend)
 export type ClientMessage =
   | { type: "subscribe"; project_id: string; conversation_id?: string }
   | { type: "unsubscribe" }
+  | { type: "resume"; resume_token: string }
   | { type: "ping" }
   | {
       type: "ask_user_response";
@@ -123,8 +131,9 @@ export type ClientMessage =
       conversation_id: string;
       content: string;
       file_ids?: string[];
+      model?: string;
     }
-  | { type: "create_conversation"; project_id: string; title?: string; first_message?: string; file_ids?: string[] }
+  | { type: "create_conversation"; project_id: string; title?: string; first_message?: string; file_ids?: string[]; model?: string }
   | { type: "list_conversations"; project_id: string }
   | { type: "get_conversation"; conversation_id: string }
   | {
@@ -133,8 +142,14 @@ export type ClientMessage =
       title?: string;
     }
   | { type: "delete_conversation"; conversation_id: string }
+  | { type: "restore_conversation"; conversation_id: string }
   | { type: "bulk_delete_conversations"; conversation_ids: string[] }
-  | { type: "get_conversation_messages"; conversation_id: string }
+  | {
+      type: "get_conversation_messages";
+      conversation_id: string;
+      before?: string;
+      limit?: number;
+    }
   | { type: "retry_last_message"; project_id: string; conversation_id: string };
 
 export interface StreamingState {
@@ -16,11 +16,16 @@ export function getFriendlyToolName(toolName: string): string {
         // operation__schema__* tools
         "operation__schema__search": "Search Schema",
         "operation__schema__get": "Get Schema",
+        "operation__schema_columns": "List Columns",
+        "operation__schema_diff": "Compare Schema Snapshots",
+        "operation__schema_metadata_query": "Query System Catalogs",
         // operation__datasource__* tools
         "operation__datasource__query": "Query Datasource",
         "operation__datasource__list": "List Datasources",
         "operation__datasource__get": "Get Datasource",
         "operation__datasource__create": "Create Datasource",
+        "operation__data_query_export": "Export Query to Excel",
+        "operation__explain_query": "Explain Query",
         // interaction__* tools
         "interaction__export_excel": "Export to Excel",
         "interaction__show_chart": "Show Chart",
@@ -47,11 +47,14 @@ const cleanupPort = (port: number) => {
   }
 };
 
-// Initial cleanup - kill processes on ports 7680, 7690, and 7670
+// The backend starts the MCP server on MCP_SERVER_PORT (default 7670)
+const mcpPort = Number(process.env.MCP_SERVER_PORT || 7670);
+
+// Initial cleanup - kill processes on the backend, frontend and MCP ports
 console.log("🧹 Cleaning up existing processes...");
 cleanupPort(7680); // backend
 cleanupPort(7690); // frontend
-cleanupPort(7670); // MCP server
+cleanupPort(mcpPort); // MCP server
 
 // Build MCP server and analysis executor debug binaries in parallel
 console.log("🔧 Building MCP server and analysis executor debug binaries...");
@@ -113,7 +116,7 @@ const restartBackend = async () => {
   
   // Clean up ports
   cleanupPort(7680); // backend
-  cleanupPort(7670); // MCP server (started by backend)
+  cleanupPort(mcpPort); // MCP server (started by backend)
   
   // Wait a bit for cleanup
   await new Promise(resolve => setTimeout(resolve, 1000));
@@ -189,7 +192,7 @@ const monitorBackend = async () => {
             backendRunning = false;
             // Clean up ports immediately when recompilation starts
             cleanupPort(7680); // backend
-            cleanupPort(7670); // MCP server
+            cleanupPort(mcpPort); // MCP server
           } else {
             console.log("🔧 Compiling backend...");
           }
@@ -261,7 +264,7 @@ const monitorBackend = async () => {
             
             // Clean up ports immediately when we detect a crash
             cleanupPort(7680); // backend
-            cleanupPort(7670); // MCP server
+            cleanupPort(mcpPort); // MCP server
             
             // Restart backend if we haven't exceeded max attempts
             if (restartAttempts < MAX_RESTART_ATTEMPTS) {